@@ -0,0 +1,32 @@
+//! Generates the C header `virolai.h` into `OUT_DIR` when the `capi`
+//! feature is enabled; does nothing otherwise. The checked-in copy in
+//! `include/` is only rewritten on request, with `VIROLAI_WRITE_HEADER=1`.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "capi")]
+    capi_header();
+}
+
+#[cfg(feature = "capi")]
+fn capi_header() {
+    use std::path::PathBuf;
+
+    println!("cargo:rerun-if-changed=src/capi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=VIROLAI_WRITE_HEADER");
+    let crate_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("cbindgen.toml is valid");
+    let bindings = cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(crate_dir.join("src/capi.rs"))
+        .generate()
+        .expect("the C API can be expressed in C");
+    bindings.write_to_file(out_dir.join("virolai.h"));
+    if std::env::var_os("VIROLAI_WRITE_HEADER").is_some_and(|v| v == "1") {
+        // Leaves the header untouched when nothing changed.
+        bindings.write_to_file(crate_dir.join("include/virolai.h"));
+    }
+}
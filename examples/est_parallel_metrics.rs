@@ -0,0 +1,101 @@
+//! Benchmark of EST's parallel metric recomputation.
+//!
+//! Schedules a synthetic night of several thousand short observations, each
+//! with a few visibility windows, once with the serial metric update and
+//! once with the rayon one, and checks both produce the same schedule.
+//!
+//! Run with `cargo run --release --features parallel --example est_parallel_metrics [tasks]`.
+
+use std::time::Instant;
+
+use qtty::{Quantity, Second};
+use virolai::algorithms::{ESTScheduler, SchedulingAlgorithm};
+use virolai::constraints::IntervalConstraint;
+use virolai::scheduling_block::{SchedulingBlock, Task};
+use virolai::solution_space::{Horizon, Interval, SolutionSpace};
+use virolai::synthetic::SplitMix64;
+
+#[derive(Debug, Clone)]
+struct Observation {
+    name: String,
+    duration: Quantity<Second>,
+    priority: i32,
+}
+
+impl Task<Second> for Observation {
+    type SizeUnit = Second;
+    type ConstraintLeaf = IntervalConstraint<Second>;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn size(&self) -> Quantity<Second> {
+        self.duration
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+}
+
+fn main() {
+    let tasks: usize = std::env::args()
+        .nth(1)
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(5_000);
+    let night = 12.0 * 3600.0;
+    let horizon = Horizon::from_bounds(Quantity::<Second>::new(0.0), Quantity::new(night))
+        .expect("a night is non-empty");
+
+    let mut rng = SplitMix64::new(42);
+    let mut block = SchedulingBlock::<Observation, Second>::new();
+    let mut space = SolutionSpace::new();
+    for i in 0..tasks {
+        let id = format!("obs-{i:05}");
+        let duration = 30.0 + 270.0 * rng.unit();
+        for _ in 0..3 {
+            let start = (night - 1800.0) * rng.unit();
+            let length = duration + 3600.0 * rng.unit();
+            space.add_interval(
+                &id,
+                Interval::new(Quantity::new(start), Quantity::new(start + length)),
+            );
+        }
+        let task = Observation {
+            name: id.clone(),
+            duration: Quantity::new(duration),
+            priority: (10.0 * rng.unit()) as i32,
+        };
+        block.add_task_with_id(task, Some(id)).unwrap();
+    }
+    let blocks = [block];
+    let scheduler = ESTScheduler::new(Quantity::new(3.0));
+
+    println!("{tasks} tasks on {} threads", rayon::current_num_threads());
+
+    let started = Instant::now();
+    let serial = scheduler.schedule(&blocks, &space, horizon);
+    let serial_time = started.elapsed();
+    println!(
+        "serial:   {serial_time:>10.2?}  ({} scheduled)",
+        serial.len()
+    );
+
+    let started = Instant::now();
+    let parallel = scheduler.schedule_par(&blocks, &space, horizon);
+    let parallel_time = started.elapsed();
+    println!(
+        "parallel: {parallel_time:>10.2?}  ({} scheduled)",
+        parallel.len()
+    );
+
+    assert!(
+        serial.iter().eq(parallel.iter()),
+        "parallel schedule differs from the serial one"
+    );
+    println!(
+        "speed-up: {:.2}x, schedules identical",
+        serial_time.as_secs_f64() / parallel_time.as_secs_f64()
+    );
+}
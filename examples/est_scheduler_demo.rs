@@ -4,7 +4,7 @@ use qtty::{Quantity, Second};
 use virolai::algorithms::{ESTScheduler, SchedulingAlgorithm};
 use virolai::constraints::IntervalConstraint;
 use virolai::scheduling_block::{SchedulingBlock, Task};
-use virolai::solution_space::{Interval, SolutionSpace};
+use virolai::solution_space::{Horizon, SolutionSpace};
 
 #[derive(Debug, Clone)]
 struct SimpleTask {
@@ -56,16 +56,17 @@ fn main() {
     block.add_task(task4);
 
     // Define the scheduling horizon (e.g., 24 hours = 86400 seconds)
-    let horizon = Interval::new(
+    let horizon = Horizon::from_bounds(
         Quantity::<Second>::new(0.0),
         Quantity::<Second>::new(1000.0),
-    );
+    )
+    .expect("non-empty horizon");
 
     // Create solution space (all tasks can be scheduled anywhere in the horizon)
     let solution_space = SolutionSpace::populate(&[block.clone()], horizon);
 
     // Create EST scheduler with endangered threshold of 100 seconds
-    let scheduler = ESTScheduler::new(100);
+    let scheduler = ESTScheduler::new(Quantity::new(100.0));
 
     // Run the scheduler
     let schedule = scheduler.schedule(&[block], &solution_space, horizon);
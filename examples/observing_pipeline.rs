@@ -0,0 +1,164 @@
+//! A night of observations, from a task list to a published plan.
+//!
+//! Loads a JSON task list, derives each task's windows, schedules with EST,
+//! checks the result, exports the windows as iCalendar free/busy and an SVG
+//! band chart, and reports the run's KPIs. The integration tests in
+//! `tests/examples.rs` run the same pipeline over the fixtures in
+//! `examples/data`.
+//!
+//! ```text
+//! cargo run --example observing_pipeline --features serde -- examples/data/night_48.json
+//! ```
+
+use std::collections::HashSet;
+
+use qtty::{Quantity, Second};
+use virolai::algorithms::ESTScheduler;
+use virolai::display::DisplayOptions;
+use virolai::io::export::{windows_to_ics, windows_to_svg};
+use virolai::io::import::{from_json, FieldMapping, ImportedTask};
+use virolai::runs::{RunKpis, RunRegistry, RunSpec};
+use virolai::schedule::{lint, Lint, Schedule};
+use virolai::scheduling_block::SchedulingBlock;
+use virolai::solution_space::{Horizon, Interval, SolutionSpace};
+
+type Block = SchedulingBlock<ImportedTask<Second>, Second>;
+
+/// Everything the pipeline produced.
+pub struct PipelineReport {
+    pub schedule: Schedule<Second>,
+    pub kpis: RunKpis,
+    /// Problems found by [`validate`]; empty for a valid schedule.
+    pub violations: Vec<String>,
+    pub lints: Vec<Lint<Second>>,
+    pub ics: String,
+    pub svg: String,
+}
+
+/// Runs the pipeline over a JSON task list, with times in seconds from
+/// `origin` (a Unix time) and `horizon` the span to schedule.
+pub fn run_pipeline(json: &str, horizon: Horizon<Second>, origin: f64) -> PipelineReport {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let block: Block = from_json(&mut deserializer, &FieldMapping::default())
+        .expect("fixture is a valid task list");
+    let blocks = [block];
+
+    let space = SolutionSpace::populate(&blocks, horizon);
+
+    let mut runs = RunRegistry::new();
+    let run = runs
+        .run(
+            RunSpec::new("pipeline", "est"),
+            &ESTScheduler::new(Quantity::new(1.0)),
+            &blocks,
+            &space,
+            horizon,
+        )
+        .expect("in-memory registry");
+    let (schedule, kpis) = (run.schedule.clone(), run.kpis.clone());
+
+    let violations = validate(&schedule, &blocks, &space);
+    let lints = lint(&schedule, &blocks, &space);
+
+    let mut ids: Vec<&str> = blocks[0].tasks().map(|(id, _)| id).collect();
+    ids.sort_unstable();
+    let mut ics = Vec::new();
+    windows_to_ics(
+        &space,
+        &ids,
+        horizon.interval(),
+        Quantity::new(origin),
+        &mut ics,
+    )
+    .expect("writing to memory");
+    let svg = windows_to_svg(
+        &space,
+        &ids,
+        horizon.interval(),
+        DisplayOptions::humanized(),
+    );
+
+    PipelineReport {
+        schedule,
+        kpis,
+        violations,
+        lints,
+        ics: String::from_utf8(ics).expect("iCalendar is UTF-8"),
+        svg,
+    }
+}
+
+/// Checks what every schedule must satisfy: known tasks inside
+/// its windows, without overlaps, after its scheduled predecessors.
+pub fn validate(
+    schedule: &Schedule<Second>,
+    blocks: &[Block],
+    space: &SolutionSpace<Second>,
+) -> Vec<String> {
+    let mut violations = Vec::new();
+    let known: HashSet<&str> = blocks
+        .iter()
+        .flat_map(|b| b.tasks().map(|(id, _)| id))
+        .collect();
+    for (id, _) in schedule.iter() {
+        if !known.contains(id.as_str()) {
+            violations.push(format!("{id} is not a task"));
+        }
+    }
+    for id in schedule.infeasible_entries(space) {
+        violations.push(format!("{id} is outside its windows"));
+    }
+    let entries: Vec<_> = schedule.iter().collect();
+    for pair in entries.windows(2) {
+        if pair[0].1.overlaps(&pair[1].1) {
+            violations.push(format!("{} overlaps {}", pair[0].0, pair[1].0));
+        }
+    }
+    for block in blocks {
+        for (id, _) in block.tasks() {
+            let (Some(node), Some(slot)) = (block.node_of(id), schedule.get_interval(id)) else {
+                continue;
+            };
+            for pred in block.predecessors(node) {
+                let pred = block.id_of(pred).expect("predecessor is in the block");
+                // Like repair, a dependency only orders tasks that both run.
+                if let Some(before) = schedule.get_interval(pred) {
+                    if before.end().value() > slot.start().value() {
+                        violations.push(format!("{id} starts before {pred} ends"));
+                    }
+                }
+            }
+        }
+    }
+    violations
+}
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "examples/data/night_48.json".to_string());
+    let json = std::fs::read_to_string(&path).expect("cannot read the task list");
+    // A 12-hour night starting at 2026-03-01T20:00:00Z.
+    let horizon = Horizon::new(Interval::from_f64(0.0, 43_200.0)).expect("a twelve-hour night");
+    let report = run_pipeline(&json, horizon, 1_772_395_200.0);
+
+    let k = &report.kpis;
+    println!(
+        "{path}: {} scheduled, {} left out",
+        k.scheduled, k.unscheduled
+    );
+    println!(
+        "utilization {:.1}%, priority scheduled {}",
+        k.utilization * 100.0,
+        k.priority_scheduled
+    );
+    for violation in &report.violations {
+        println!("error: {violation}");
+    }
+    for warning in &report.lints {
+        println!("warning: {warning}");
+    }
+    std::fs::write("windows.ics", &report.ics).expect("cannot write windows.ics");
+    std::fs::write("windows.svg", &report.svg).expect("cannot write windows.svg");
+    println!("wrote windows.ics and windows.svg");
+}
@@ -0,0 +1,99 @@
+//! Benchmark of the flat and day-bucketed schedule indexes.
+//!
+//! Fills a year-long schedule with a few hundred thousand short entries,
+//! once with the flat start index and once with day buckets, then times
+//! insertion, per-day range queries, conflict checks and a full pass, and
+//! checks both layouts answer identically.
+//!
+//! Run with `cargo run --release --example schedule_time_buckets [entries]`.
+
+use std::time::{Duration, Instant};
+
+use qtty::{Quantity, Second};
+use virolai::schedule::Schedule;
+use virolai::solution_space::Interval;
+use virolai::synthetic::SplitMix64;
+
+const DAY: f64 = 86_400.0;
+const YEAR: f64 = 365.0 * DAY;
+
+struct Timings {
+    insert: Duration,
+    days: Duration,
+    conflicts: Duration,
+    pass: Duration,
+    checksum: (usize, usize, f64),
+}
+
+fn run(mut schedule: Schedule<Second>, entries: usize, queries: &[f64]) -> Timings {
+    let slot = YEAR / entries as f64;
+    let started = Instant::now();
+    for i in 0..entries {
+        let start = i as f64 * slot;
+        let interval = Interval::new(Quantity::new(start), Quantity::new(start + 0.6 * slot));
+        schedule.add(format!("obs-{i:07}"), interval).unwrap();
+    }
+    let insert = started.elapsed();
+
+    let started = Instant::now();
+    let mut in_days = 0;
+    for &t in queries {
+        let day = (t / DAY).floor() * DAY;
+        in_days += schedule
+            .starting_in(Interval::from_f64(day, day + DAY))
+            .count();
+    }
+    let days = started.elapsed();
+
+    let started = Instant::now();
+    let mut conflicts = 0;
+    for &t in queries {
+        conflicts += schedule
+            .conflicts_count(Interval::from_f64(t, t + 600.0), usize::MAX)
+            .unwrap();
+    }
+    let conflicts_time = started.elapsed();
+
+    let started = Instant::now();
+    let busy = schedule.total_duration().value();
+    let pass = started.elapsed();
+
+    Timings {
+        insert,
+        days,
+        conflicts: conflicts_time,
+        pass,
+        checksum: (in_days, conflicts, busy),
+    }
+}
+
+fn main() {
+    let entries: usize = std::env::args()
+        .nth(1)
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(300_000);
+    let mut rng = SplitMix64::new(7);
+    let queries: Vec<f64> = (0..20_000).map(|_| YEAR * rng.unit()).collect();
+
+    println!("{entries} entries over a year, {} queries", queries.len());
+    let flat = run(Schedule::new(), entries, &queries);
+    let bucketed = run(
+        Schedule::new().with_time_buckets(Quantity::new(DAY)),
+        entries,
+        &queries,
+    );
+    assert_eq!(
+        flat.checksum, bucketed.checksum,
+        "the layouts answered differently"
+    );
+
+    println!("{:<12}{:>14}{:>14}", "", "flat", "day buckets");
+    for (name, a, b) in [
+        ("insert", flat.insert, bucketed.insert),
+        ("day ranges", flat.days, bucketed.days),
+        ("conflicts", flat.conflicts, bucketed.conflicts),
+        ("full pass", flat.pass, bucketed.pass),
+    ] {
+        println!("{name:<12}{:>14.2?}{:>14.2?}", a, b);
+    }
+}
@@ -6,7 +6,7 @@
 use qtty::{Quantity, Second};
 use virolai::constraints::{ConstraintExpr, IntervalConstraint};
 use virolai::scheduling_block::{SchedulingBlock, Task};
-use virolai::solution_space::{Interval, SolutionSpace};
+use virolai::solution_space::{Horizon, Interval, SolutionSpace};
 
 #[derive(Debug)]
 struct MyTask {
@@ -69,10 +69,11 @@ fn main() {
     let task_c_id = block2.add_task(task_c);
 
     // Populate solution space from multiple blocks
-    let range = Interval::new(
+    let range = Horizon::from_bounds(
         Quantity::<Second>::new(0.0),
         Quantity::<Second>::new(1000.0),
-    );
+    )
+    .expect("non-empty range");
 
     let blocks = vec![block1, block2];
     let solution_space = SolutionSpace::populate(&blocks, range);
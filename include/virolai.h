@@ -0,0 +1,238 @@
+/* virolai C API. Generated from src/capi.rs by cbindgen; do not edit. */
+
+#ifndef VIROLAI_H
+#define VIROLAI_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Outcome of a C API call.
+ */
+typedef enum VirolaiStatus {
+  VIROLAI_STATUS_OK = 0,
+  /**
+   * A handle, string or output pointer was null.
+   */
+  VIROLAI_STATUS_NULL_POINTER = 1,
+  /**
+   * A string was not valid UTF-8.
+   */
+  VIROLAI_STATUS_INVALID_UTF8 = 2,
+  /**
+   * A number was NaN, negative where it must not be, or an interval
+   * ended before it started.
+   */
+  VIROLAI_STATUS_INVALID_ARGUMENT = 3,
+  /**
+   * A task with this ID already exists in the block.
+   */
+  VIROLAI_STATUS_DUPLICATE_ID = 4,
+  /**
+   * No task or entry with this ID or index.
+   */
+  VIROLAI_STATUS_NOT_FOUND = 5,
+  /**
+   * The dependency would make the block cyclic.
+   */
+  VIROLAI_STATUS_CYCLE = 6,
+  /**
+   * The library panicked. Handles passed to the call may be left
+   * partially updated.
+   */
+  VIROLAI_STATUS_PANIC = 7,
+} VirolaiStatus;
+
+/**
+ * A scheduling block of tasks and their dependencies.
+ */
+typedef struct VirolaiBlock VirolaiBlock;
+
+/**
+ * A schedule produced by [`virolai_est_schedule`]: the primary entries in
+ * start order, then the background ones.
+ */
+typedef struct VirolaiSchedule VirolaiSchedule;
+
+/**
+ * Allowed placement windows per task.
+ */
+typedef struct VirolaiSpace VirolaiSpace;
+
+/**
+ * A scheduled task, borrowed from its [`VirolaiSchedule`].
+ */
+typedef struct VirolaiEntry {
+  /**
+   * The task ID, valid until the schedule is freed.
+   */
+  const char *task_id;
+  double start;
+  double end;
+  /**
+   * Whether the task is in the background lane, where it may overlap
+   * other entries.
+   */
+  bool background;
+} VirolaiEntry;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the message of the last failed call on this thread, or an empty
+ * string. The pointer is valid until the next failing call on the thread.
+ */
+const char *virolai_last_error(void);
+
+/**
+ * Returns the library version, a static string.
+ */
+const char *virolai_version(void);
+
+/**
+ * Creates an empty block. Free it with [`virolai_block_free`].
+ */
+struct VirolaiBlock *virolai_block_new(void);
+
+/**
+ * Frees a block. Null is ignored.
+ *
+ * # Safety
+ *
+ * `block` must be null or a live handle from [`virolai_block_new`].
+ */
+void virolai_block_free(struct VirolaiBlock *block);
+
+/**
+ * Adds a task of `size` seconds, named after its ID.
+ *
+ * # Safety
+ *
+ * `block` must be a live handle and `id` a NUL-terminated string.
+ */
+enum VirolaiStatus virolai_block_add_task(struct VirolaiBlock *block,
+                                          const char *id,
+                                          double size,
+                                          int32_t priority);
+
+/**
+ * Makes task `to` depend on task `from`.
+ *
+ * # Safety
+ *
+ * `block` must be a live handle and `from` and `to` NUL-terminated
+ * strings.
+ */
+enum VirolaiStatus virolai_block_add_dependency(struct VirolaiBlock *block,
+                                                const char *from,
+                                                const char *to);
+
+/**
+ * Returns the number of tasks in the block, 0 for null.
+ *
+ * # Safety
+ *
+ * `block` must be null or a live handle.
+ */
+size_t virolai_block_len(const struct VirolaiBlock *block);
+
+/**
+ * Creates an empty solution space. Free it with [`virolai_space_free`].
+ */
+struct VirolaiSpace *virolai_space_new(void);
+
+/**
+ * Frees a solution space. Null is ignored.
+ *
+ * # Safety
+ *
+ * `space` must be null or a live handle from [`virolai_space_new`].
+ */
+void virolai_space_free(struct VirolaiSpace *space);
+
+/**
+ * Allows task `id` within `[start, end)`, merged with its other windows.
+ *
+ * # Safety
+ *
+ * `space` must be a live handle and `id` a NUL-terminated string.
+ */
+enum VirolaiStatus virolai_space_add_interval(struct VirolaiSpace *space,
+                                              const char *id,
+                                              double start,
+                                              double end);
+
+/**
+ * Schedules `block` with EST within `[horizon_start, horizon_end)`, tasks
+ * with less than `endangered_threshold` seconds of flexibility counting as
+ * endangered.
+ *
+ * Returns a schedule to free with [`virolai_schedule_free`], or null on
+ * invalid arguments, including an empty or infinite horizon (see
+ * [`virolai_last_error`]). Tasks EST cannot place are left out of the
+ * schedule.
+ *
+ * # Safety
+ *
+ * `block` and `space` must be live handles.
+ */
+struct VirolaiSchedule *virolai_est_schedule(const struct VirolaiBlock *block,
+                                             const struct VirolaiSpace *space,
+                                             double horizon_start,
+                                             double horizon_end,
+                                             uint32_t endangered_threshold);
+
+/**
+ * Frees a schedule. Null is ignored.
+ *
+ * # Safety
+ *
+ * `schedule` must be null or a live handle from [`virolai_est_schedule`].
+ */
+void virolai_schedule_free(struct VirolaiSchedule *schedule);
+
+/**
+ * Returns the number of scheduled tasks, 0 for null.
+ *
+ * # Safety
+ *
+ * `schedule` must be null or a live handle.
+ */
+size_t virolai_schedule_len(const struct VirolaiSchedule *schedule);
+
+/**
+ * Writes the `index`-th scheduled task to `out`, in the order described on
+ * [`VirolaiSchedule`].
+ *
+ * # Safety
+ *
+ * `schedule` must be a live handle and `out` point to writable memory for
+ * one [`VirolaiEntry`].
+ */
+enum VirolaiStatus virolai_schedule_entry(const struct VirolaiSchedule *schedule,
+                                          size_t index,
+                                          struct VirolaiEntry *out);
+
+/**
+ * Writes the interval of task `id` to `start` and `end`.
+ *
+ * # Safety
+ *
+ * `schedule` must be a live handle, `id` a NUL-terminated string and
+ * `start` and `end` point to writable doubles.
+ */
+enum VirolaiStatus virolai_schedule_interval_of(const struct VirolaiSchedule *schedule,
+                                                const char *id,
+                                                double *start,
+                                                double *end);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* VIROLAI_H */
@@ -0,0 +1,307 @@
+//! Anytime scheduling: algorithms that produce improving incumbent schedules.
+//!
+//! Heavier algorithms can take a long time to converge. An [`AnytimeAlgorithm`]
+//! reports every improved schedule it finds to an [`AnytimeContext`], and checks
+//! the context for a deadline or cancellation between steps. Whatever happens,
+//! the best schedule found so far stays retrievable from the context, so a
+//! deadline-bound planning run always ends with a usable plan if one was found.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::sync::mpsc;
+//! use std::time::Duration;
+//! use virolai::algorithms::anytime::{AnytimeAlgorithm, AnytimeContext};
+//! use virolai::algorithms::BeamSearchScheduler;
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let space = instance.solution_space;
+//!
+//! let (tx, rx) = mpsc::channel();
+//! let mut ctx = AnytimeContext::new()
+//!     .with_time_limit(Duration::from_secs(2))
+//!     .on_incumbent(move |inc| {
+//!         let _ = tx.send(inc.schedule.clone());
+//!     });
+//!
+//! BeamSearchScheduler::new(8, 3).schedule_anytime(&blocks, &space, horizon, &mut ctx);
+//! let best = ctx.into_schedule().unwrap_or_default();
+//! ```
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, SolutionSpace};
+use qtty::Unit;
+
+/// A schedule accepted as the best found so far.
+#[derive(Debug, Clone)]
+pub struct Incumbent<U: Unit> {
+    /// The schedule.
+    pub schedule: Schedule<U>,
+    /// How many incumbents were accepted before this one.
+    pub improvements: usize,
+    /// Time since the context was created.
+    pub elapsed: Duration,
+}
+
+/// Callback invoked with each newly accepted incumbent.
+type IncumbentCallback<'a, U> = Box<dyn FnMut(&Incumbent<U>) + 'a>;
+
+/// Stop conditions, incumbent callback and best-so-far storage for an anytime run.
+///
+/// A schedule replaces the incumbent if it places more tasks, or the same
+/// number of tasks with a larger total duration.
+pub struct AnytimeContext<'a, U: Unit> {
+    started: Instant,
+    deadline: Option<Instant>,
+    cancel: Option<&'a AtomicBool>,
+    on_incumbent: Option<IncumbentCallback<'a, U>>,
+    incumbent: Option<Incumbent<U>>,
+}
+
+impl<'a, U: Unit> AnytimeContext<'a, U> {
+    /// Creates a context with no deadline, no cancellation flag and no callback.
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            deadline: None,
+            cancel: None,
+            on_incumbent: None,
+            incumbent: None,
+        }
+    }
+
+    /// Stops the run once `limit` has elapsed since the context was created.
+    pub fn with_time_limit(self, limit: Duration) -> Self {
+        let deadline = self.started + limit;
+        self.with_deadline(deadline)
+    }
+
+    /// Stops the run once `deadline` has passed.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stops the run once `flag` is set, e.g. from another thread.
+    pub fn with_cancel_flag(mut self, flag: &'a AtomicBool) -> Self {
+        self.cancel = Some(flag);
+        self
+    }
+
+    /// Calls `callback` every time a new incumbent is accepted.
+    pub fn on_incumbent(mut self, callback: impl FnMut(&Incumbent<U>) + 'a) -> Self {
+        self.on_incumbent = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns true if the deadline has passed or cancellation was requested.
+    ///
+    /// Algorithms should call this between steps and return promptly when it
+    /// is true.
+    pub fn should_stop(&self) -> bool {
+        self.cancel.is_some_and(|flag| flag.load(Ordering::Relaxed))
+            || self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+
+    /// Offers a complete schedule. Returns true if it became the new incumbent.
+    pub fn offer(&mut self, schedule: Schedule<U>) -> bool {
+        let improves = self
+            .incumbent
+            .as_ref()
+            .is_none_or(|current| is_improvement(&schedule, &current.schedule));
+        if !improves {
+            return false;
+        }
+
+        let improvements = self.incumbent.as_ref().map_or(0, |i| i.improvements + 1);
+        let incumbent = Incumbent {
+            schedule,
+            improvements,
+            elapsed: self.started.elapsed(),
+        };
+        if let Some(callback) = self.on_incumbent.as_mut() {
+            callback(&incumbent);
+        }
+        self.incumbent = Some(incumbent);
+        true
+    }
+
+    /// Returns the best schedule found so far.
+    pub fn incumbent(&self) -> Option<&Incumbent<U>> {
+        self.incumbent.as_ref()
+    }
+
+    /// Consumes the context, returning the best incumbent.
+    pub fn into_incumbent(self) -> Option<Incumbent<U>> {
+        self.incumbent
+    }
+
+    /// Consumes the context, returning the best schedule.
+    pub fn into_schedule(self) -> Option<Schedule<U>> {
+        self.incumbent.map(|i| i.schedule)
+    }
+}
+
+impl<U: Unit> Default for AnytimeContext<'_, U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<U: Unit> fmt::Debug for AnytimeContext<'_, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnytimeContext")
+            .field("deadline", &self.deadline)
+            .field("cancel", &self.cancel)
+            .field("has_callback", &self.on_incumbent.is_some())
+            .field("incumbent", &self.incumbent)
+            .finish()
+    }
+}
+
+/// More tasks placed wins; ties go to the larger total duration.
+fn is_improvement<U: Unit>(candidate: &Schedule<U>, current: &Schedule<U>) -> bool {
+    match candidate.len().cmp(&current.len()) {
+        std::cmp::Ordering::Greater => true,
+        std::cmp::Ordering::Less => false,
+        std::cmp::Ordering::Equal => {
+            candidate.total_duration().value() > current.total_duration().value()
+        }
+    }
+}
+
+/// Scheduling algorithm that can be interrupted and reports improving solutions.
+pub trait AnytimeAlgorithm<T, U, D, E>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    /// Searches for schedules, offering each improvement to `ctx`.
+    ///
+    /// Returns when the search is exhausted or [`AnytimeContext::should_stop`]
+    /// becomes true. The best schedule is then available from `ctx`.
+    fn schedule_anytime(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+        ctx: &mut AnytimeContext<'_, U>,
+    );
+
+    /// Runs the search for at most `limit` and returns the best schedule found.
+    fn schedule_within(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+        limit: Duration,
+    ) -> Option<Schedule<U>> {
+        let mut ctx = AnytimeContext::new().with_time_limit(limit);
+        self.schedule_anytime(blocks, solution_space, horizon, &mut ctx);
+        ctx.into_schedule()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::BeamSearchScheduler;
+    use crate::test_utils::{block_and_space, hz, iv, TestTask};
+    use qtty::Second;
+
+    fn schedule_of(intervals: &[(&str, f64, f64)]) -> Schedule<Second> {
+        let mut schedule = Schedule::new();
+        for &(id, start, end) in intervals {
+            schedule.add(id, iv(start, end)).unwrap();
+        }
+        schedule
+    }
+
+    fn trap() -> (SchedulingBlock<TestTask, Second>, SolutionSpace<Second>) {
+        block_and_space(&[
+            ("wide", 10.0, 0, iv(0.0, 100.0)),
+            ("narrow", 10.0, 0, iv(5.0, 15.0)),
+        ])
+    }
+
+    // ── AnytimeContext ────────────────────────────────────────────────
+
+    #[test]
+    fn offer_keeps_only_improvements() {
+        let mut ctx = AnytimeContext::<Second>::new();
+        assert!(ctx.offer(schedule_of(&[("a", 0.0, 10.0)])));
+        assert!(!ctx.offer(schedule_of(&[("b", 0.0, 5.0)])));
+        assert!(ctx.offer(schedule_of(&[("c", 0.0, 20.0)])));
+        assert!(ctx.offer(schedule_of(&[("a", 0.0, 1.0), ("b", 1.0, 2.0)])));
+
+        let incumbent = ctx.into_incumbent().unwrap();
+        assert_eq!(incumbent.schedule.len(), 2);
+        assert_eq!(incumbent.improvements, 2);
+    }
+
+    #[test]
+    fn callback_sees_each_incumbent() {
+        let mut seen = Vec::new();
+        {
+            let mut ctx = AnytimeContext::<Second>::new().on_incumbent(|inc| {
+                seen.push(inc.schedule.len());
+            });
+            ctx.offer(schedule_of(&[("a", 0.0, 10.0)]));
+            ctx.offer(schedule_of(&[("a", 0.0, 10.0)]));
+            ctx.offer(schedule_of(&[("a", 0.0, 10.0), ("b", 10.0, 20.0)]));
+        }
+        assert_eq!(seen, vec![1, 2]);
+    }
+
+    #[test]
+    fn should_stop_on_cancel_and_deadline() {
+        let flag = AtomicBool::new(false);
+        let ctx = AnytimeContext::<Second>::new().with_cancel_flag(&flag);
+        assert!(!ctx.should_stop());
+        flag.store(true, Ordering::Relaxed);
+        assert!(ctx.should_stop());
+
+        let expired = AnytimeContext::<Second>::new().with_time_limit(Duration::ZERO);
+        assert!(expired.should_stop());
+    }
+
+    // ── BeamSearchScheduler ───────────────────────────────────────────
+
+    #[test]
+    fn beam_reports_est_then_improvement() {
+        let (block, ss) = trap();
+        let mut sizes = Vec::new();
+        let mut ctx = AnytimeContext::new().on_incumbent(|inc| sizes.push(inc.schedule.len()));
+        BeamSearchScheduler::new(2, 1).schedule_anytime(&[block], &ss, hz(0.0, 100.0), &mut ctx);
+        assert_eq!(ctx.into_schedule().unwrap().len(), 2);
+        assert_eq!(sizes, vec![1, 2]);
+    }
+
+    #[test]
+    fn cancelled_run_yields_no_incumbent() {
+        let (block, ss) = trap();
+        let flag = AtomicBool::new(true);
+        let mut ctx = AnytimeContext::new().with_cancel_flag(&flag);
+        BeamSearchScheduler::default().schedule_anytime(&[block], &ss, hz(0.0, 100.0), &mut ctx);
+        assert!(ctx.incumbent().is_none());
+    }
+
+    #[test]
+    fn schedule_within_returns_best() {
+        let (block, ss) = trap();
+        let schedule = BeamSearchScheduler::new(2, 1)
+            .schedule_within(&[block], &ss, hz(0.0, 100.0), Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(schedule.len(), 2);
+    }
+}
@@ -0,0 +1,197 @@
+//! Choosing among resources that could all host a task.
+//!
+//! When [`CoalitionScheduler`](super::CoalitionScheduler) has found the
+//! earliest start of a task, several resources are often free at that
+//! start. An [`ArbitrationPolicy`] ranks them, and the scheduler books the
+//! best ones the task needs. The start itself is not up for arbitration:
+//! policies only decide *where* a task runs, never *when*.
+//!
+//! [`Arbitration`] holds the built-in policies as data, so the choice can
+//! live in a run configuration. With the `serde` feature it is tagged by a
+//! `policy` field:
+//!
+//! ```text
+//! {"policy": "resource_order"}
+//! {"policy": "preference", "order": ["lst-1", "magic-1"]}
+//! {"policy": "load_balance"}
+//! {"policy": "minimize_slew"}
+//! {"policy": "random", "seed": 7}
+//! ```
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::algorithms::{Arbitration, CoalitionScheduler};
+//! # let types = [("lst-1", "LST"), ("lst-2", "LST")];
+//! # fn distance(_: Option<&str>, _: &str) -> f64 { 0.0 }
+//!
+//! let scheduler = CoalitionScheduler::new(types)
+//!     .with_arbitration(Arbitration::MinimizeSlew)
+//!     .with_slew_cost(|_resource, from, to| distance(from, to));
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+
+use qtty::{Quantity, Unit};
+
+use crate::hash::fnv1a;
+use crate::synthetic::SplitMix64;
+use crate::Id;
+
+/// A resource free to host a task at the chosen start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbitrationCandidate<'a, U: Unit> {
+    pub resource: &'a str,
+    /// Type the scheduler registered for the resource, empty if none.
+    pub resource_type: &'a str,
+    /// Time already booked on the resource.
+    pub load: Quantity<U>,
+    /// Task booked on the resource last before the start, if any.
+    pub previous: Option<Id>,
+    /// Cost of moving from `previous` to the task, 0 without a slew cost.
+    pub slew: f64,
+}
+
+/// Ranks the resources free to host a task.
+pub trait ArbitrationPolicy<U: Unit> {
+    /// Sorts `candidates`, which arrive in resource-ID order, from most to
+    /// least preferred for `task` starting at `start`.
+    fn rank(&self, task: &str, start: Quantity<U>, candidates: &mut [ArbitrationCandidate<'_, U>]);
+}
+
+/// The built-in arbitration policies.
+///
+/// Ties always go to the resource with the smaller ID, so every policy is
+/// deterministic.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "policy", rename_all = "snake_case")
+)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Arbitration {
+    /// Resource-ID order.
+    #[default]
+    ResourceOrder,
+    /// The listed resources in list order, then the others in ID order.
+    Preference { order: Vec<Id> },
+    /// Least booked time first.
+    LoadBalance,
+    /// Cheapest move from the resource's previous task first, see
+    /// [`CoalitionScheduler::with_slew_cost`](super::CoalitionScheduler::with_slew_cost).
+    MinimizeSlew,
+    /// A random order, the same for every run with the same seed.
+    Random { seed: u64 },
+}
+
+impl<U: Unit> ArbitrationPolicy<U> for Arbitration {
+    fn rank(
+        &self,
+        task: &str,
+        _start: Quantity<U>,
+        candidates: &mut [ArbitrationCandidate<'_, U>],
+    ) {
+        // Stable sorts keep the resource-ID order among ties.
+        match self {
+            Arbitration::ResourceOrder => {}
+            Arbitration::Preference { order } => candidates.sort_by_key(|c| {
+                order
+                    .iter()
+                    .position(|r| r == c.resource)
+                    .unwrap_or(order.len())
+            }),
+            Arbitration::LoadBalance => {
+                candidates.sort_by(|a, b| a.load.value().total_cmp(&b.load.value()))
+            }
+            Arbitration::MinimizeSlew => candidates.sort_by(|a, b| a.slew.total_cmp(&b.slew)),
+            Arbitration::Random { seed } => candidates.sort_by_cached_key(|c| {
+                SplitMix64::new(seed ^ fnv1a(task) ^ fnv1a(c.resource).rotate_left(32)).next_u64()
+            }),
+        }
+    }
+}
+
+type SlewFn = dyn Fn(&str, Option<&str>, &str) -> f64 + Send + Sync;
+
+/// Cost of moving a resource from its previous task to the next one.
+#[derive(Clone)]
+pub(crate) struct SlewCost(Arc<SlewFn>);
+
+impl SlewCost {
+    pub(crate) fn new(
+        cost: impl Fn(&str, Option<&str>, &str) -> f64 + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(cost))
+    }
+
+    pub(crate) fn cost(&self, resource: &str, previous: Option<&str>, task: &str) -> f64 {
+        (self.0)(resource, previous, task)
+    }
+}
+
+impl fmt::Debug for SlewCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SlewCost")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::q;
+    use qtty::Second;
+
+    fn candidates() -> Vec<ArbitrationCandidate<'static, Second>> {
+        [("a", 30.0, 2.0), ("b", 10.0, 0.5), ("c", 10.0, 1.0)]
+            .into_iter()
+            .map(|(resource, load, slew)| ArbitrationCandidate {
+                resource,
+                resource_type: "",
+                load: q(load),
+                previous: None,
+                slew,
+            })
+            .collect()
+    }
+
+    fn ranked(policy: &Arbitration, task: &str) -> Vec<&'static str> {
+        let mut candidates = candidates();
+        policy.rank(task, q(0.0), &mut candidates);
+        candidates.iter().map(|c| c.resource).collect()
+    }
+
+    #[test]
+    fn policies_rank_candidates() {
+        assert_eq!(ranked(&Arbitration::ResourceOrder, "t"), ["a", "b", "c"]);
+        let preference = Arbitration::Preference {
+            order: vec!["c".into(), "x".into()],
+        };
+        assert_eq!(ranked(&preference, "t"), ["c", "a", "b"]);
+        assert_eq!(ranked(&Arbitration::LoadBalance, "t"), ["b", "c", "a"]);
+        assert_eq!(ranked(&Arbitration::MinimizeSlew, "t"), ["b", "c", "a"]);
+
+        let random = Arbitration::Random { seed: 7 };
+        assert_eq!(ranked(&random, "t"), ranked(&random, "t"));
+        let orders: std::collections::HashSet<_> =
+            (0..20).map(|i| ranked(&random, &format!("t{i}"))).collect();
+        assert!(orders.len() > 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_round_trips() {
+        let policy: Arbitration =
+            serde_json::from_str(r#"{"policy": "preference", "order": ["lst-1"]}"#).unwrap();
+        assert_eq!(
+            policy,
+            Arbitration::Preference {
+                order: vec!["lst-1".into()]
+            }
+        );
+        let random = Arbitration::Random { seed: 7 };
+        let json = serde_json::to_string(&random).unwrap();
+        assert_eq!(json, r#"{"policy":"random","seed":7}"#);
+        assert_eq!(serde_json::from_str::<Arbitration>(&json).unwrap(), random);
+    }
+}
@@ -0,0 +1,514 @@
+//! Decision logs for any scheduling algorithm.
+//!
+//! [`Audited`] wraps a [`SchedulingAlgorithm`] and records, next to the
+//! schedule it returns, a [`DecisionLog`] explaining it: the conflict and
+//! window check behind every placement, and for every task left out, each
+//! window it could have used and why that window failed. The wrapped
+//! algorithm is not modified: the log is derived by replaying its output
+//! against the inputs, so it works for any algorithm, including ones that
+//! keep no trace of their own.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::algorithms::{Audited, GreedyScheduler};
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let space = instance.solution_space;
+//!
+//! let audited = Audited::new(GreedyScheduler::new());
+//! let (schedule, log) = audited.audit(&blocks, &space, horizon);
+//! for (task_id, cause) in log.rejections() {
+//!     println!("{task_id}: {cause}");
+//! }
+//! ```
+
+use std::fmt;
+use std::sync::Mutex;
+
+use petgraph::EdgeType;
+use qtty::Unit;
+
+use super::greedy::free_windows;
+use super::SchedulingAlgorithm;
+use crate::repair::dependency_bounds;
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+use crate::Id;
+
+/// How one window of an unplaced task fared.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    /// The window had a free slot long enough for the task.
+    Fits,
+    /// The window lies outside the horizon or the range allowed by the
+    /// task's scheduled predecessors and successors.
+    OutOfBounds,
+    /// The usable part of the window is shorter than the task.
+    TooShort,
+    /// The window was long enough, but the listed tasks occupied it.
+    Occupied { by: Vec<Id> },
+}
+
+/// Why a task was left out of the schedule.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RejectionCause {
+    /// The task has no entry in the solution space.
+    NotInSolutionSpace,
+    /// No window is long enough within the horizon and dependency bounds.
+    NoFittingWindow,
+    /// Windows were long enough, but other tasks occupied them.
+    Blocked,
+    /// A window had room, but the algorithm did not use it.
+    NotSelected,
+}
+
+impl fmt::Display for RejectionCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RejectionCause::NotInSolutionSpace => write!(f, "not in solution space"),
+            RejectionCause::NoFittingWindow => write!(f, "no fitting window"),
+            RejectionCause::Blocked => write!(f, "blocked"),
+            RejectionCause::NotSelected => write!(f, "not selected"),
+        }
+    }
+}
+
+/// One entry of a [`DecisionLog`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "kind", rename_all = "snake_case", bound = "")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision<U: Unit> {
+    /// A placement was checked against every other scheduled task.
+    ConflictCheck {
+        task_id: Id,
+        interval: Interval<U>,
+        /// Tasks overlapping `interval`; empty if the placement is valid.
+        conflicts: Vec<Id>,
+    },
+    /// The task was placed.
+    Placed {
+        task_id: Id,
+        interval: Interval<U>,
+        /// Whether `interval` lies inside one of the task's windows.
+        in_window: bool,
+    },
+    /// A window of an unplaced task was tried.
+    Attempt {
+        task_id: Id,
+        window: Interval<U>,
+        outcome: AttemptOutcome,
+    },
+    /// The task was left out.
+    Rejected { task_id: Id, cause: RejectionCause },
+}
+
+impl<U: Unit> Decision<U> {
+    /// The task the decision is about.
+    pub fn task_id(&self) -> &str {
+        match self {
+            Decision::ConflictCheck { task_id, .. }
+            | Decision::Placed { task_id, .. }
+            | Decision::Attempt { task_id, .. }
+            | Decision::Rejected { task_id, .. } => task_id,
+        }
+    }
+}
+
+/// Every decision behind a schedule, placements first in start order (the
+/// background lane after the primary one), then unplaced tasks by ID.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionLog<U: Unit> {
+    decisions: Vec<Decision<U>>,
+}
+
+impl<U: Unit> DecisionLog<U> {
+    /// Explains `schedule` as a result for `blocks`, `solution_space` and
+    /// `horizon`.
+    ///
+    /// Placements are checked against the rest of the schedule and the
+    /// task's windows. Each window of an unplaced task is clipped to the
+    /// horizon and to its scheduled predecessors and successors, then
+    /// checked for a free slot.
+    pub fn from_schedule<T, D, E>(
+        schedule: &Schedule<U>,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+    ) -> Self
+    where
+        T: Task<U>,
+        E: EdgeType,
+    {
+        let mut decisions = Vec::new();
+        for (task_id, interval, background) in schedule.iter_all() {
+            // Background tasks may overlap anything.
+            let conflicts = if background {
+                Vec::new()
+            } else {
+                overlapping(schedule, interval)
+                    .into_iter()
+                    .filter(|other| *other != task_id)
+                    .collect()
+            };
+            let in_window = solution_space.get_intervals(&task_id).is_some_and(|ws| {
+                ws.iter()
+                    .any(|w| w.start() <= interval.start() && interval.end() <= w.end())
+            });
+            decisions.push(Decision::ConflictCheck {
+                task_id: task_id.clone(),
+                interval,
+                conflicts,
+            });
+            decisions.push(Decision::Placed {
+                task_id,
+                interval,
+                in_window,
+            });
+        }
+
+        let mut unplaced: Vec<(&str, f64)> = blocks
+            .iter()
+            .flat_map(|block| block.tasks())
+            .filter(|(id, _)| !schedule.contains_task(id))
+            .map(|(id, task)| (id, task.size_on_axis().value()))
+            .collect();
+        unplaced.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+        for (task_id, size) in unplaced {
+            let Some(windows) = solution_space.get_intervals(task_id) else {
+                decisions.push(Decision::Rejected {
+                    task_id: task_id.to_string(),
+                    cause: RejectionCause::NotInSolutionSpace,
+                });
+                continue;
+            };
+            let (lo, hi) = dependency_bounds(task_id, blocks, schedule);
+            let lo = lo.max(horizon.start().value());
+            let hi = hi.min(horizon.end().value());
+
+            let mut cause = RejectionCause::NoFittingWindow;
+            for &window in windows {
+                let (start, end) = (window.start().value().max(lo), window.end().value().min(hi));
+                let outcome = if start >= end {
+                    AttemptOutcome::OutOfBounds
+                } else if end - start < size {
+                    AttemptOutcome::TooShort
+                } else if free_windows(&[(start, end)], lo, hi, schedule)
+                    .iter()
+                    .any(|free| free.duration().value() >= size)
+                {
+                    cause = RejectionCause::NotSelected;
+                    AttemptOutcome::Fits
+                } else {
+                    if cause == RejectionCause::NoFittingWindow {
+                        cause = RejectionCause::Blocked;
+                    }
+                    let usable = Interval::from_f64(start, end);
+                    AttemptOutcome::Occupied {
+                        by: overlapping(schedule, usable),
+                    }
+                };
+                decisions.push(Decision::Attempt {
+                    task_id: task_id.to_string(),
+                    window,
+                    outcome,
+                });
+            }
+            decisions.push(Decision::Rejected {
+                task_id: task_id.to_string(),
+                cause,
+            });
+        }
+
+        Self { decisions }
+    }
+
+    /// All decisions, in log order.
+    pub fn decisions(&self) -> &[Decision<U>] {
+        &self.decisions
+    }
+
+    /// Decisions about `task_id`, in log order.
+    pub fn for_task<'a>(&'a self, task_id: &'a str) -> impl Iterator<Item = &'a Decision<U>> {
+        self.decisions
+            .iter()
+            .filter(move |d| d.task_id() == task_id)
+    }
+
+    /// Unplaced tasks with the reason, by ID.
+    pub fn rejections(&self) -> impl Iterator<Item = (&str, RejectionCause)> {
+        self.decisions.iter().filter_map(|d| match d {
+            Decision::Rejected { task_id, cause } => Some((task_id.as_str(), *cause)),
+            _ => None,
+        })
+    }
+
+    /// Placements that overlap another task or leave the task's windows.
+    pub fn violations(&self) -> impl Iterator<Item = &Decision<U>> {
+        self.decisions.iter().filter(|d| match d {
+            Decision::ConflictCheck { conflicts, .. } => !conflicts.is_empty(),
+            Decision::Placed { in_window, .. } => !in_window,
+            _ => false,
+        })
+    }
+}
+
+/// IDs of the scheduled tasks overlapping `interval`, in start order.
+fn overlapping<U: Unit>(schedule: &Schedule<U>, interval: Interval<U>) -> Vec<Id> {
+    schedule
+        .conflicts_ref(interval)
+        .map(|conflicts| conflicts.map(|(id, _)| id.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// A scheduling algorithm that keeps a [`DecisionLog`] of its last run.
+///
+/// Scheduling through [`SchedulingAlgorithm::schedule`] returns the wrapped
+/// algorithm's schedule unchanged and stores the log, available from
+/// [`last_log`](Self::last_log). [`audit`](Self::audit) returns both
+/// directly.
+#[derive(Debug)]
+pub struct Audited<A, U: Unit> {
+    inner: A,
+    last_log: Mutex<Option<DecisionLog<U>>>,
+}
+
+impl<A, U: Unit> Audited<A, U> {
+    /// Wraps `inner`.
+    pub fn new(inner: A) -> Self {
+        Self {
+            inner,
+            last_log: Mutex::new(None),
+        }
+    }
+
+    /// The wrapped algorithm.
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Unwraps the algorithm.
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+
+    /// The log of the last [`schedule`](SchedulingAlgorithm::schedule)
+    /// call, if any.
+    pub fn last_log(&self) -> Option<DecisionLog<U>> {
+        self.lock().clone()
+    }
+
+    /// Takes the log of the last call, leaving none.
+    pub fn take_log(&self) -> Option<DecisionLog<U>> {
+        self.lock().take()
+    }
+
+    /// Runs the wrapped algorithm and explains its schedule.
+    pub fn audit<T, D, E>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+    ) -> (Schedule<U>, DecisionLog<U>)
+    where
+        A: SchedulingAlgorithm<T, U, D, E>,
+        T: Task<U>,
+        E: EdgeType,
+    {
+        let schedule = self.inner.schedule(blocks, solution_space, horizon);
+        let log = DecisionLog::from_schedule(&schedule, blocks, solution_space, horizon);
+        (schedule, log)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<DecisionLog<U>>> {
+        // The log is replaced whole, so a poisoned lock still holds a
+        // consistent value.
+        self.last_log.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<A, T, U, D, E> SchedulingAlgorithm<T, U, D, E> for Audited<A, U>
+where
+    A: SchedulingAlgorithm<T, U, D, E>,
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    fn schedule(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+    ) -> Schedule<U> {
+        let (schedule, log) = self.audit(blocks, solution_space, horizon);
+        *self.lock() = Some(log);
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::GreedyScheduler;
+    use crate::test_utils::{block_and_space, hz, iv, TestTask};
+    use qtty::Second;
+
+    /// Places only task "a", at 0, whatever its windows say.
+    struct OnlyA;
+
+    impl SchedulingAlgorithm<TestTask, Second, (), petgraph::Directed> for OnlyA {
+        fn schedule(
+            &self,
+            _: &[SchedulingBlock<TestTask, Second>],
+            _: &SolutionSpace<Second>,
+            _: Horizon<Second>,
+        ) -> Schedule<Second> {
+            let mut schedule = Schedule::new();
+            schedule.add("a", iv(0.0, 10.0)).unwrap();
+            schedule
+        }
+    }
+
+    fn problem() -> (
+        Vec<SchedulingBlock<TestTask, Second>>,
+        SolutionSpace<Second>,
+    ) {
+        // b only fits where a is, c has room later, d's window is too short.
+        let (mut block, mut space) = block_and_space(&[
+            ("a", 10.0, 0, iv(5.0, 50.0)),
+            ("b", 10.0, 0, iv(0.0, 12.0)),
+            ("c", 10.0, 0, iv(0.0, 12.0)),
+            ("d", 30.0, 0, iv(20.0, 40.0)),
+        ]);
+        space.add_interval("c", iv(40.0, 60.0));
+        // e has no window at all.
+        block
+            .add_task_with_id(TestTask::new("e", 5.0), Some("e".to_string()))
+            .unwrap();
+        (vec![block], space)
+    }
+
+    #[test]
+    fn log_explains_placements_and_rejections() {
+        let (blocks, space) = problem();
+        let audited = Audited::new(OnlyA);
+        let (schedule, log) = audited.audit(&blocks, &space, hz(0.0, 100.0));
+        assert_eq!(schedule.len(), 1);
+
+        // a was placed at 0, outside its window [5, 50).
+        assert_eq!(log.violations().count(), 1);
+        assert!(matches!(
+            log.violations().next(),
+            Some(Decision::Placed {
+                in_window: false,
+                ..
+            })
+        ));
+
+        let rejections: Vec<_> = log.rejections().collect();
+        assert_eq!(
+            rejections,
+            [
+                ("b", RejectionCause::Blocked),
+                ("c", RejectionCause::NotSelected),
+                ("d", RejectionCause::NoFittingWindow),
+                ("e", RejectionCause::NotInSolutionSpace),
+            ]
+        );
+        let b_attempt = log.for_task("b").next().unwrap();
+        assert_eq!(
+            b_attempt,
+            &Decision::Attempt {
+                task_id: "b".into(),
+                window: iv(0.0, 12.0),
+                outcome: AttemptOutcome::Occupied {
+                    by: vec!["a".into()]
+                },
+            }
+        );
+        assert_eq!(log.for_task("c").count(), 3);
+        assert_eq!(RejectionCause::NotSelected.to_string(), "not selected");
+    }
+
+    #[test]
+    fn log_explains_background_placements() {
+        let (blocks, space) = problem();
+        let mut schedule = Schedule::new();
+        schedule.add("a", iv(5.0, 15.0)).unwrap();
+        schedule.add_background("c", iv(0.0, 10.0)).unwrap();
+        let log = DecisionLog::from_schedule(&schedule, &blocks, &space, hz(0.0, 100.0));
+
+        assert_eq!(
+            log.for_task("c").collect::<Vec<_>>(),
+            [
+                &Decision::ConflictCheck {
+                    task_id: "c".into(),
+                    interval: iv(0.0, 10.0),
+                    conflicts: vec![],
+                },
+                &Decision::Placed {
+                    task_id: "c".into(),
+                    interval: iv(0.0, 10.0),
+                    in_window: true,
+                },
+            ]
+        );
+        assert!(log.for_task("a").any(|d| matches!(
+            d,
+            Decision::ConflictCheck { conflicts, .. } if conflicts.is_empty()
+        )));
+        assert_eq!(log.violations().count(), 0);
+    }
+
+    #[test]
+    fn wrapper_returns_the_inner_schedule_and_keeps_the_log() {
+        let (blocks, space) = problem();
+        let horizon = hz(0.0, 100.0);
+        let audited = Audited::new(GreedyScheduler::new());
+        assert!(audited.last_log().is_none());
+
+        let schedule = audited.schedule(&blocks, &space, horizon);
+        let plain = GreedyScheduler::new().schedule(&blocks, &space, horizon);
+        assert_eq!(
+            schedule.iter().collect::<Vec<_>>(),
+            plain.iter().collect::<Vec<_>>()
+        );
+
+        let log = audited.take_log().unwrap();
+        assert_eq!(log.violations().count(), 0);
+        assert_eq!(
+            log.decisions()
+                .iter()
+                .filter(|d| matches!(d, Decision::Placed { .. }))
+                .count(),
+            schedule.len()
+        );
+        assert!(audited.last_log().is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn log_round_trips_through_json() {
+        let (blocks, space) = problem();
+        let (_, log) = Audited::new(OnlyA).audit(&blocks, &space, hz(0.0, 100.0));
+        let json = serde_json::to_string(&log).unwrap();
+        assert!(json.contains(r#""kind":"rejected""#));
+        let back: DecisionLog<Second> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, log);
+    }
+}
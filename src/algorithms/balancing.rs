@@ -0,0 +1,357 @@
+//! Cheap multi-resource strategies that assign each task to one resource.
+//!
+//! Unlike [`IndependentScheduler`](super::IndependentScheduler), which lets
+//! every resource schedule every task it is eligible for, these adapters
+//! first give each task to a single resource and then run the wrapped
+//! single-resource algorithm on each resource's share:
+//!
+//! - [`RoundRobinScheduler`] deals tasks out cyclically over the resources
+//! - [`LeastLoadedScheduler`] gives each task to the resource with the most
+//!   time left
+//!
+//! A resource is eligible for a task when its solution space holds a window
+//! within the horizon long enough for the task. Tasks are assigned by
+//! descending priority, ties broken by task ID, and resources are visited in
+//! ID order, so assignments are deterministic. Tasks no resource is eligible
+//! for are left out.
+//!
+//! Assignment ignores time: two tasks given to one resource may compete for
+//! the same window, and the wrapped algorithm then drops one of them even if
+//! another resource was free. [`CoalitionScheduler`](super::CoalitionScheduler)
+//! books across resources with full knowledge of each one's timeline.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::algorithms::{ESTScheduler, LeastLoadedScheduler, MultiResourceAlgorithm};
+//! # use qtty::Quantity;
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let space = instance.solution_space;
+//! # use std::collections::HashMap;
+//! # use virolai::solution_space::SolutionSpace;
+//! # let resource_spaces = HashMap::from([
+//! #     ("lst-1".to_string(), space),
+//! #     ("lst-2".to_string(), SolutionSpace::new()),
+//! #     ("magic-1".to_string(), SolutionSpace::new()),
+//! # ]);
+//!
+//! let multi = LeastLoadedScheduler::new(ESTScheduler::new(Quantity::new(1.0)));
+//! let schedules = multi.schedule_multi(&blocks, &resource_spaces, horizon);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::algorithms::{MultiResourceAlgorithm, SchedulingAlgorithm};
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, IntervalSet, SolutionSpace};
+use crate::Id;
+use qtty::Unit;
+
+/// Assigns tasks to eligible resources in turn, then schedules each
+/// resource with the wrapped algorithm.
+///
+/// Resources are taken cyclically in ID order; a task skips the resources it
+/// is not eligible for, and the next task starts after the one it took.
+pub struct RoundRobinScheduler<A> {
+    inner: A,
+}
+
+impl<A> RoundRobinScheduler<A> {
+    /// Wraps a single-resource algorithm.
+    pub fn new(algorithm: A) -> Self {
+        Self { inner: algorithm }
+    }
+
+    /// Returns the resource each task is assigned to.
+    pub fn assign<T, U, D, E>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        resource_spaces: &HashMap<Id, SolutionSpace<U>>,
+        horizon: Horizon<U>,
+    ) -> HashMap<Id, Id>
+    where
+        T: Task<U>,
+        U: Unit,
+        E: petgraph::EdgeType,
+    {
+        let resources = sorted_resources(resource_spaces);
+        let mut next = 0;
+        let mut assignment = HashMap::new();
+        for (id, task) in tasks_by_priority(blocks) {
+            let picked = (0..resources.len())
+                .map(|offset| (next + offset) % resources.len())
+                .find(|&i| is_eligible(task, id, &resource_spaces[resources[i]], horizon));
+            if let Some(i) = picked {
+                assignment.insert(id.to_string(), resources[i].to_string());
+                next = i + 1;
+            }
+        }
+        assignment
+    }
+}
+
+/// Assigns each task to the eligible resource with the most remaining
+/// capacity, then schedules each resource with the wrapped algorithm.
+///
+/// A resource's capacity is the time its solution space covers within the
+/// horizon, counting overlapping windows once; each assigned task uses up
+/// its size. Ties go to the resource with the smallest ID.
+pub struct LeastLoadedScheduler<A> {
+    inner: A,
+}
+
+impl<A> LeastLoadedScheduler<A> {
+    /// Wraps a single-resource algorithm.
+    pub fn new(algorithm: A) -> Self {
+        Self { inner: algorithm }
+    }
+
+    /// Returns the resource each task is assigned to.
+    pub fn assign<T, U, D, E>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        resource_spaces: &HashMap<Id, SolutionSpace<U>>,
+        horizon: Horizon<U>,
+    ) -> HashMap<Id, Id>
+    where
+        T: Task<U>,
+        U: Unit,
+        E: petgraph::EdgeType,
+    {
+        let resources = sorted_resources(resource_spaces);
+        let mut remaining: Vec<f64> = resources
+            .iter()
+            .map(|r| capacity(&resource_spaces[*r], horizon))
+            .collect();
+        let mut assignment = HashMap::new();
+        for (id, task) in tasks_by_priority(blocks) {
+            let picked = (0..resources.len())
+                .filter(|&i| is_eligible(task, id, &resource_spaces[resources[i]], horizon))
+                .fold(None, |best: Option<usize>, i| match best {
+                    Some(b) if remaining[b] >= remaining[i] => Some(b),
+                    _ => Some(i),
+                });
+            if let Some(i) = picked {
+                remaining[i] -= task.size_on_axis().value();
+                assignment.insert(id.to_string(), resources[i].to_string());
+            }
+        }
+        assignment
+    }
+}
+
+impl<A, T, U, D, E> MultiResourceAlgorithm<T, U, D, E> for RoundRobinScheduler<A>
+where
+    A: SchedulingAlgorithm<T, U, D, E>,
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    fn schedule_multi(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        resource_spaces: &HashMap<Id, SolutionSpace<U>>,
+        horizon: Horizon<U>,
+    ) -> HashMap<Id, Schedule<U>> {
+        let assignment = self.assign(blocks, resource_spaces, horizon);
+        schedule_assigned(&self.inner, blocks, resource_spaces, horizon, &assignment)
+    }
+}
+
+impl<A, T, U, D, E> MultiResourceAlgorithm<T, U, D, E> for LeastLoadedScheduler<A>
+where
+    A: SchedulingAlgorithm<T, U, D, E>,
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    fn schedule_multi(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        resource_spaces: &HashMap<Id, SolutionSpace<U>>,
+        horizon: Horizon<U>,
+    ) -> HashMap<Id, Schedule<U>> {
+        let assignment = self.assign(blocks, resource_spaces, horizon);
+        schedule_assigned(&self.inner, blocks, resource_spaces, horizon, &assignment)
+    }
+}
+
+/// Resource IDs, sorted.
+fn sorted_resources<U: Unit>(resource_spaces: &HashMap<Id, SolutionSpace<U>>) -> Vec<&str> {
+    let mut resources: Vec<&str> = resource_spaces.keys().map(String::as_str).collect();
+    resources.sort_unstable();
+    resources
+}
+
+/// Every task of `blocks`, by descending priority then ID.
+fn tasks_by_priority<T, U, D, E>(blocks: &[SchedulingBlock<T, U, D, E>]) -> Vec<(&str, &T)>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    let mut tasks: Vec<(&str, &T)> = blocks.iter().flat_map(|b| b.tasks()).collect();
+    tasks.sort_by(|(a_id, a), (b_id, b)| {
+        b.priority().cmp(&a.priority()).then_with(|| a_id.cmp(b_id))
+    });
+    tasks
+}
+
+/// Whether `space` holds a window within `horizon` long enough for `task`.
+fn is_eligible<T, U>(task: &T, id: &str, space: &SolutionSpace<U>, horizon: Horizon<U>) -> bool
+where
+    T: Task<U>,
+    U: Unit,
+{
+    let size = task.size_on_axis().value();
+    space.get_intervals(id).is_some_and(|windows| {
+        windows
+            .iter()
+            .filter_map(|window| window.intersection(&horizon.interval()))
+            .any(|window| window.duration().value() >= size)
+    })
+}
+
+/// Time covered by the windows of `space` within `horizon`.
+fn capacity<U: Unit>(space: &SolutionSpace<U>, horizon: Horizon<U>) -> f64 {
+    let mut covered = IntervalSet::new();
+    for (_, windows) in space.iter() {
+        covered.extend_from_slice(windows.as_slice());
+    }
+    horizon
+        .clip(&covered)
+        .iter()
+        .map(|window| window.duration().value())
+        .sum()
+}
+
+/// Runs `algorithm` on each resource over the tasks assigned to it.
+fn schedule_assigned<A, T, U, D, E>(
+    algorithm: &A,
+    blocks: &[SchedulingBlock<T, U, D, E>],
+    resource_spaces: &HashMap<Id, SolutionSpace<U>>,
+    horizon: Horizon<U>,
+    assignment: &HashMap<Id, Id>,
+) -> HashMap<Id, Schedule<U>>
+where
+    A: SchedulingAlgorithm<T, U, D, E>,
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    resource_spaces
+        .iter()
+        .map(|(resource_id, space)| {
+            let mut share = SolutionSpace::new();
+            for (id, windows) in space.iter() {
+                if assignment.get(id) == Some(resource_id) {
+                    share.set_intervals(id, windows.to_vec());
+                }
+            }
+            let schedule = algorithm.schedule(blocks, &share, horizon);
+            (resource_id.clone(), schedule)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::ESTScheduler;
+    use crate::solution_space::Interval;
+    use crate::test_utils::{hz, iv, q, TestTask};
+    use qtty::Second;
+
+    fn block(tasks: &[(&str, f64, i32)]) -> SchedulingBlock<TestTask, Second> {
+        let mut block = SchedulingBlock::new();
+        for &(id, size, priority) in tasks {
+            let task = TestTask::new(id, size).with_priority(priority);
+            block.add_task_with_id(task, Some(id.to_string())).unwrap();
+        }
+        block
+    }
+
+    fn spaces(windows: &[(&str, &str, Interval<Second>)]) -> HashMap<Id, SolutionSpace<Second>> {
+        let mut out: HashMap<Id, SolutionSpace<Second>> = HashMap::new();
+        for &(resource, task, window) in windows {
+            out.entry(resource.to_string())
+                .or_default()
+                .add_interval(task, window);
+        }
+        out
+    }
+
+    #[test]
+    fn round_robin_cycles_over_eligible_resources() {
+        let blocks = [block(&[
+            ("a", 10.0, 5),
+            ("b", 10.0, 4),
+            ("c", 10.0, 3),
+            ("d", 10.0, 2),
+            ("e", 10.0, 1),
+        ])];
+        let mut windows = vec![];
+        for r in ["r1", "r2", "r3"] {
+            for t in ["a", "b", "d", "e"] {
+                windows.push((r, t, iv(0.0, 100.0)));
+            }
+        }
+        // `c` only fits on r1; its window on r2 is too short.
+        windows.push(("r1", "c", iv(0.0, 100.0)));
+        windows.push(("r2", "c", iv(0.0, 5.0)));
+        let ss = spaces(&windows);
+        let horizon = hz(0.0, 100.0);
+
+        let scheduler = RoundRobinScheduler::new(ESTScheduler::new(q(1.0)));
+        let assignment = scheduler.assign(&blocks, &ss, horizon);
+        let of = |t: &str| assignment[t].as_str();
+        assert_eq!(
+            ["a", "b", "c", "d", "e"].map(of),
+            ["r1", "r2", "r1", "r2", "r3"]
+        );
+
+        let schedules = scheduler.schedule_multi(&blocks, &ss, horizon);
+        assert_eq!(schedules.len(), 3);
+        assert_eq!(schedules["r1"].len(), 2);
+        assert!(schedules["r1"].contains_task("c"));
+        assert_eq!(schedules.values().map(Schedule::len).sum::<usize>(), 5);
+    }
+
+    #[test]
+    fn least_loaded_fills_the_roomiest_resource() {
+        let blocks = [block(&[
+            ("a", 40.0, 3),
+            ("b", 30.0, 2),
+            ("c", 20.0, 1),
+            ("lost", 20.0, 0),
+        ])];
+        let mut windows = vec![];
+        for t in ["a", "b", "c"] {
+            // r1 has 100 s of time, r2 only 60 s.
+            windows.push(("r1", t, iv(0.0, 100.0)));
+            windows.push(("r2", t, iv(0.0, 60.0)));
+        }
+        windows.push(("r2", "lost", iv(200.0, 300.0)));
+        let ss = spaces(&windows);
+        let horizon = hz(0.0, 100.0);
+
+        let scheduler = LeastLoadedScheduler::new(ESTScheduler::new(q(1.0)));
+        let assignment = scheduler.assign(&blocks, &ss, horizon);
+        // a takes r1 (100 s against 60 s), b ties at 60 s and takes r1 by
+        // ID, c takes r2 (60 s against 30 s).
+        assert_eq!(assignment["a"], "r1");
+        assert_eq!(assignment["b"], "r1");
+        assert_eq!(assignment["c"], "r2");
+        assert!(!assignment.contains_key("lost"));
+
+        let schedules = scheduler.schedule_multi(&blocks, &ss, horizon);
+        assert_eq!(schedules["r1"].len(), 2);
+        assert_eq!(schedules["r2"].len(), 1);
+    }
+}
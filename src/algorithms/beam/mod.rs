@@ -0,0 +1,576 @@
+//! Lookahead beam-search scheduling algorithm.
+//!
+//! A middle ground between greedy [`ESTScheduler`](crate::algorithms::ESTScheduler)
+//! and an exhaustive search. Like EST, tasks are placed one at a time at their
+//! earliest start time behind a moving cursor. Before committing each placement,
+//! the scheduler explores alternatives:
+//!
+//! 1. Candidates are ranked by a [`TaskScorer`] on `[cursor, horizon.end]`,
+//!    ties broken with EST's ordering, and the top `beam_width` become
+//!    branches. The default [`EstOrder`] scorer ties every candidate.
+//! 2. From each branch, a beam of width `beam_width` is expanded `depth - 1`
+//!    further steps, always keeping the best `beam_width` partial schedules.
+//! 3. The branch whose beam reaches the best state is committed.
+//!
+//! States are scored with EST's metrics: first by the number of tasks that are
+//! placed or still feasible (have an EST on the remaining horizon), then by the
+//! total priority of placed tasks, then by the earliest cursor.
+//!
+//! Each placement honors the task's placement preference inside its EST
+//! window, as in EST. With `beam_width = 1` and the default scorer the result
+//! is identical to EST.
+//!
+//! As an [`AnytimeAlgorithm`], the scheduler reruns with widths 1, 2, 4, … up
+//! to `beam_width`, reporting each improved schedule.
+
+use crate::algorithms::anytime::{AnytimeAlgorithm, AnytimeContext};
+use crate::algorithms::est::engine::{anchor_in_est_window, priority_key};
+use crate::algorithms::est::metrics::compute_metrics;
+use crate::algorithms::scoring::{EstOrder, ScoringContext, TaskScorer};
+use crate::schedule::errors::ScheduleError;
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+use crate::Id;
+use qtty::{Quantity, Second, Unit};
+use std::cmp::Ordering;
+
+/// Beam-search scheduler with configurable width and lookahead depth.
+///
+/// # Example
+///
+/// ```no_run
+/// use virolai::algorithms::{BeamSearchScheduler, SchedulingAlgorithm};
+/// # use virolai::algorithms::scoring::FlexibilityScore;
+/// # use qtty::Second;
+/// # use virolai::solution_space::{Horizon, Interval};
+/// # use virolai::synthetic::{generate, SyntheticConfig};
+/// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+/// # let instance = generate(&SyntheticConfig::new(20, horizon));
+/// # let blocks = vec![instance.block];
+/// # let space = instance.solution_space;
+///
+/// // Explore the best 4 orderings, 3 placements ahead
+/// let scheduler = BeamSearchScheduler::new(4, 3);
+/// let schedule = scheduler.schedule(&blocks, &space, horizon);
+///
+/// // Rank candidates by priority per unit of flexibility first
+/// let scheduler = BeamSearchScheduler::new(4, 3).with_scorer(FlexibilityScore);
+/// # let schedule = scheduler.schedule(&blocks, &space, horizon);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BeamSearchScheduler<S = EstOrder, U: Unit = Second> {
+    beam_width: usize,
+    depth: usize,
+    endangered_threshold: Quantity<U>,
+    scorer: S,
+}
+
+impl<U: Unit> BeamSearchScheduler<EstOrder, U> {
+    /// Creates a beam-search scheduler.
+    ///
+    /// # Arguments
+    ///
+    /// * `beam_width` - Number of orderings kept at each step (B), at least 1
+    /// * `depth` - Number of placements explored before committing (k), at least 1
+    pub fn new(beam_width: usize, depth: usize) -> Self {
+        Self {
+            beam_width: beam_width.max(1),
+            depth: depth.max(1),
+            endangered_threshold: Quantity::new(1.0),
+            scorer: EstOrder,
+        }
+    }
+}
+
+impl<S, U: Unit> BeamSearchScheduler<S, U> {
+    /// Ranks candidates by `scorer` (highest first) before EST's ordering.
+    pub fn with_scorer<S2>(self, scorer: S2) -> BeamSearchScheduler<S2, U> {
+        BeamSearchScheduler {
+            beam_width: self.beam_width,
+            depth: self.depth,
+            endangered_threshold: self.endangered_threshold,
+            scorer,
+        }
+    }
+
+    /// Sets the flexibility threshold used by EST's candidate ordering.
+    pub fn with_endangered_threshold(mut self, endangered_threshold: Quantity<U>) -> Self {
+        self.endangered_threshold = endangered_threshold;
+        self
+    }
+
+    /// Returns the beam width (B).
+    pub fn beam_width(&self) -> usize {
+        self.beam_width
+    }
+
+    /// Returns the lookahead depth (k).
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the endangered threshold.
+    pub fn endangered_threshold(&self) -> Quantity<U> {
+        self.endangered_threshold
+    }
+
+    /// Returns the candidate scorer.
+    pub fn scorer(&self) -> &S {
+        &self.scorer
+    }
+}
+
+impl<U: Unit> Default for BeamSearchScheduler<EstOrder, U> {
+    /// Creates a beam-search scheduler with width 3, depth 2 and threshold 1.
+    fn default() -> Self {
+        Self::new(3, 2)
+    }
+}
+
+/// A partial schedule explored by the beam.
+#[derive(Debug, Clone)]
+struct BeamState<U: Unit> {
+    /// Indices of placed tasks with their intervals, in placement order.
+    placed: Vec<(usize, Interval<U>)>,
+    /// Indices of tasks not yet placed.
+    remaining: Vec<usize>,
+    cursor: Quantity<U>,
+    placed_priority: i64,
+}
+
+/// Score of a beam state; larger is better.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StateScore {
+    viable: usize,
+    placed_priority: i64,
+    cursor: f64,
+}
+
+impl StateScore {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.viable
+            .cmp(&other.viable)
+            .then(self.placed_priority.cmp(&other.placed_priority))
+            // Earlier cursor leaves more room for the rest
+            .then(other.cursor.total_cmp(&self.cursor))
+    }
+}
+
+/// Shared, read-only search inputs.
+struct Search<'a, T, U: Unit, S> {
+    tasks: Vec<(&'a str, &'a T)>,
+    /// Milestones and background tasks, placed outside the search.
+    detached: Vec<(&'a str, &'a T)>,
+    solution_space: &'a SolutionSpace<U>,
+    horizon: Horizon<U>,
+    beam_width: usize,
+    endangered_threshold: Quantity<U>,
+    scorer: &'a S,
+}
+
+impl<T, U, S> Search<'_, T, U, S>
+where
+    T: Task<U>,
+    U: Unit,
+    S: TaskScorer<T, U>,
+{
+    /// Returns up to `beam_width` successors of `state`, best candidate first.
+    fn expand(&self, state: &BeamState<U>) -> Vec<BeamState<U>> {
+        let Some(range) = self
+            .horizon
+            .remaining_after(state.cursor)
+            .map(|h| h.interval())
+        else {
+            return Vec::new();
+        };
+
+        let mut ranked: Vec<_> = state
+            .remaining
+            .iter()
+            .map(|&idx| {
+                let (id, task) = self.tasks[idx];
+                let task_metrics = compute_metrics(task, id, self.solution_space, range);
+                let metrics = task_metrics.metrics;
+                let context = ScoringContext::new(task, id, self.solution_space, range);
+                let score = self.scorer.score(id, &task_metrics, &context);
+                let score = if score.is_nan() {
+                    f64::NEG_INFINITY
+                } else {
+                    score
+                };
+                let key = priority_key(
+                    id,
+                    task.priority() as f64,
+                    metrics.est,
+                    metrics.flexibility,
+                    self.endangered_threshold,
+                );
+                (score, key, idx, metrics.est)
+            })
+            .filter(|(_, _, _, est)| est.is_some())
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+        ranked
+            .into_iter()
+            .take(self.beam_width)
+            .filter_map(|(_, _, idx, est)| {
+                let (id, task) = self.tasks[idx];
+                let start = est?;
+                let interval = anchor_in_est_window(task, id, start, self.solution_space, range)
+                    .unwrap_or_else(|| Interval::new(start, start + task.duration_at(start)));
+                let mut child = state.clone();
+                child.placed.push((idx, interval));
+                child.remaining.retain(|&r| r != idx);
+                child.cursor = interval.end() + task.gap_after();
+                child.placed_priority += task.priority() as i64;
+                Some(child)
+            })
+            .collect()
+    }
+
+    fn score(&self, state: &BeamState<U>) -> StateScore {
+        let feasible = self
+            .horizon
+            .remaining_after(state.cursor)
+            .map_or(0, |rest| {
+                let range = rest.interval();
+                state
+                    .remaining
+                    .iter()
+                    .filter(|&&idx| {
+                        let (id, task) = self.tasks[idx];
+                        !compute_metrics(task, id, self.solution_space, range).is_impossible()
+                    })
+                    .count()
+            });
+        StateScore {
+            viable: state.placed.len() + feasible,
+            placed_priority: state.placed_priority,
+            cursor: state.cursor.value(),
+        }
+    }
+
+    /// Best score reachable from `root` within `steps` further placements.
+    fn lookahead(&self, root: BeamState<U>, steps: usize) -> StateScore {
+        let mut frontier = vec![(self.score(&root), root)];
+        for _ in 0..steps {
+            let mut next = Vec::new();
+            for (score, state) in frontier {
+                let children = self.expand(&state);
+                if children.is_empty() {
+                    // Terminal states stay in the beam
+                    next.push((score, state));
+                } else {
+                    next.extend(children.into_iter().map(|c| (self.score(&c), c)));
+                }
+            }
+            // Stable sort keeps EST order among equally scored states
+            next.sort_by(|a, b| b.0.cmp(&a.0));
+            next.truncate(self.beam_width);
+            frontier = next;
+        }
+        frontier[0].0
+    }
+
+    /// Runs the search, committing one placement per iteration.
+    ///
+    /// Returns `None` if `stop` requests an early exit before the run finishes.
+    fn run(&self, depth: usize, mut stop: impl FnMut() -> bool) -> Option<Schedule<U>> {
+        let mut current = BeamState {
+            placed: Vec::new(),
+            remaining: (0..self.tasks.len()).collect(),
+            cursor: self.horizon.start(),
+            placed_priority: 0,
+        };
+
+        loop {
+            if stop() {
+                return None;
+            }
+
+            let branches = self.expand(&current);
+            if branches.is_empty() {
+                break;
+            }
+
+            // First branch wins ties, which keeps EST's ordering as the default
+            let mut best: Option<(StateScore, BeamState<U>)> = None;
+            for branch in branches {
+                let score = self.lookahead(branch.clone(), depth - 1);
+                if best
+                    .as_ref()
+                    .is_none_or(|(b, _)| score.cmp(b) == Ordering::Greater)
+                {
+                    best = Some((score, branch));
+                }
+            }
+            current = best.expect("at least one branch").1;
+        }
+
+        let mut schedule = self.place_detached();
+        for (idx, interval) in current.placed {
+            let id: Id = self.tasks[idx].0.to_string();
+            // Placements follow the cursor and never overlap; an ID repeated
+            // across blocks keeps its first entry, as in EST.
+            if let Err(err) = schedule.add(id, interval) {
+                debug_assert!(matches!(err, ScheduleError::DuplicateTaskId(_)), "{err}");
+            }
+        }
+        Some(schedule)
+    }
+
+    /// Places milestones and background tasks as EST does: each at its
+    /// earliest start in the whole horizon, without moving the cursor, and
+    /// background tasks in the schedule's background lane.
+    fn place_detached(&self) -> Schedule<U> {
+        let mut schedule = Schedule::new();
+        let range = self.horizon.interval();
+        for &(id, task) in &self.detached {
+            let Some(start) = compute_metrics(task, id, self.solution_space, range)
+                .metrics
+                .est
+            else {
+                continue;
+            };
+            let interval = anchor_in_est_window(task, id, start, self.solution_space, range)
+                .unwrap_or_else(|| Interval::new(start, start + task.duration_at(start)));
+            let placed = if task.is_background() {
+                schedule.add_background(id, interval)
+            } else {
+                schedule.add(id, interval)
+            };
+            // Neither lane checks milestones or background entries for
+            // overlaps; a repeated ID is dropped, as in EST.
+            if let Err(err) = placed {
+                debug_assert!(matches!(err, ScheduleError::DuplicateTaskId(_)), "{err}");
+            }
+        }
+        schedule
+    }
+}
+
+impl<S, U: Unit> BeamSearchScheduler<S, U> {
+    fn search<'a, T, D, E>(
+        &'a self,
+        blocks: &'a [SchedulingBlock<T, U, D, E>],
+        solution_space: &'a SolutionSpace<U>,
+        horizon: Horizon<U>,
+        beam_width: usize,
+    ) -> Search<'a, T, U, S>
+    where
+        T: Task<U>,
+        E: petgraph::EdgeType,
+    {
+        let (detached, tasks) = blocks
+            .iter()
+            .flat_map(|block| block.tasks())
+            .partition(|(_, task)| task.is_milestone() || task.is_background());
+        Search {
+            tasks,
+            detached,
+            solution_space,
+            horizon,
+            beam_width,
+            endangered_threshold: self.endangered_threshold,
+            scorer: &self.scorer,
+        }
+    }
+}
+
+impl<S, T, U, D, E> crate::algorithms::SchedulingAlgorithm<T, U, D, E> for BeamSearchScheduler<S, U>
+where
+    S: TaskScorer<T, U>,
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    fn schedule(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+    ) -> Schedule<U> {
+        self.search(blocks, solution_space, horizon, self.beam_width)
+            .run(self.depth, || false)
+            .unwrap_or_default()
+    }
+}
+
+impl<S, T, U, D, E> AnytimeAlgorithm<T, U, D, E> for BeamSearchScheduler<S, U>
+where
+    S: TaskScorer<T, U>,
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    /// Runs the search with beam widths 1, 2, 4, … up to the configured
+    /// width, offering each completed schedule as an incumbent.
+    ///
+    /// The width-1 run is EST, so a usable plan is available quickly.
+    fn schedule_anytime(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+        ctx: &mut AnytimeContext<'_, U>,
+    ) {
+        let mut width = 1;
+        loop {
+            let search = self.search(blocks, solution_space, horizon, width);
+            let depth = if width == 1 { 1 } else { self.depth };
+            match search.run(depth, || ctx.should_stop()) {
+                Some(schedule) => {
+                    ctx.offer(schedule);
+                }
+                None => return,
+            }
+            if width >= self.beam_width {
+                return;
+            }
+            width = (width * 2).min(self.beam_width);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{ESTScheduler, SchedulingAlgorithm};
+    use crate::synthetic::{generate, SyntheticConfig};
+    use crate::test_utils::{block_and_space, hz, iv, q, TestTask};
+    use qtty::Second;
+
+    /// EST places `wide` first at 0, which blocks the only window of `narrow`.
+    fn trap() -> (SchedulingBlock<TestTask, Second>, SolutionSpace<Second>) {
+        block_and_space(&[
+            ("wide", 10.0, 0, iv(0.0, 100.0)),
+            ("narrow", 10.0, 0, iv(5.0, 15.0)),
+        ])
+    }
+
+    // ── configuration ─────────────────────────────────────────────────
+
+    #[test]
+    fn new_clamps_to_at_least_one() {
+        let scheduler: BeamSearchScheduler = BeamSearchScheduler::new(0, 0);
+        assert_eq!(scheduler.beam_width(), 1);
+        assert_eq!(scheduler.depth(), 1);
+    }
+
+    #[test]
+    fn default_configuration() {
+        let scheduler: BeamSearchScheduler = BeamSearchScheduler::default();
+        assert_eq!(scheduler.beam_width(), 3);
+        assert_eq!(scheduler.depth(), 2);
+        assert_eq!(scheduler.endangered_threshold(), q(1.0));
+        assert_eq!(
+            scheduler
+                .with_endangered_threshold(q(4.0))
+                .endangered_threshold(),
+            q(4.0)
+        );
+    }
+
+    // ── scheduling ────────────────────────────────────────────────────
+
+    #[test]
+    fn width_one_matches_est() {
+        let (block, ss) = trap();
+        let blocks = [block];
+        let horizon = hz(0.0, 100.0);
+
+        let beam = BeamSearchScheduler::new(1, 3).schedule(&blocks, &ss, horizon);
+        let est = ESTScheduler::default().schedule(&blocks, &ss, horizon);
+        assert_eq!(
+            beam.iter().collect::<Vec<_>>(),
+            est.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn width_one_matches_est_on_random_instances() {
+        for seed in 0..50 {
+            let config = SyntheticConfig::new(20, hz(0.0, 500.0))
+                .with_size_range(q(5.0), q(60.0))
+                .with_window_density(0.15)
+                .with_dependency_density(0.1)
+                .with_seed(seed);
+            let instance = generate(&config);
+            let blocks = [instance.block];
+            let (ss, horizon) = (&instance.solution_space, instance.horizon);
+
+            let beam = BeamSearchScheduler::new(1, 3).schedule(&blocks, ss, horizon);
+            let est = ESTScheduler::default().schedule(&blocks, ss, horizon);
+            let sorted = |schedule: &Schedule<Second>| {
+                let mut entries: Vec<_> = schedule.iter_all().collect();
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                entries
+            };
+            assert_eq!(sorted(&beam), sorted(&est), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn background_tasks_go_in_the_background_lane() {
+        let (mut block, mut ss) = block_and_space(&[("a", 50.0, 0, iv(0.0, 100.0))]);
+        let bg = TestTask::new("bg", 100.0).with_priority(10).in_background();
+        block.add_task_with_id(bg, Some("bg".into())).unwrap();
+        ss.add_interval("bg", iv(0.0, 100.0));
+        let blocks = [block];
+        let horizon = hz(0.0, 100.0);
+
+        let beam = BeamSearchScheduler::new(1, 1).schedule(&blocks, &ss, horizon);
+        let est = ESTScheduler::default().schedule(&blocks, &ss, horizon);
+        for schedule in [&beam, &est] {
+            assert!(schedule.is_background("bg"));
+            assert_eq!(schedule.get_interval("bg"), Some(iv(0.0, 100.0)));
+            assert_eq!(schedule.get_interval("a"), Some(iv(0.0, 50.0)));
+        }
+    }
+
+    #[test]
+    fn lookahead_avoids_blocking_placement() {
+        let (block, ss) = trap();
+        let blocks = [block];
+        let horizon = hz(0.0, 100.0);
+
+        let est = ESTScheduler::default().schedule(&blocks, &ss, horizon);
+        assert_eq!(est.len(), 1);
+
+        let beam = BeamSearchScheduler::new(2, 1).schedule(&blocks, &ss, horizon);
+        assert_eq!(beam.len(), 2);
+        assert_eq!(beam.get_interval("narrow"), Some(iv(5.0, 15.0)));
+        assert_eq!(beam.get_interval("wide"), Some(iv(15.0, 25.0)));
+    }
+
+    #[test]
+    fn deeper_lookahead_places_all_feasible_tasks() {
+        let (block, ss) = block_and_space(&[
+            ("a", 10.0, 0, iv(0.0, 100.0)),
+            ("b", 10.0, 0, iv(0.0, 100.0)),
+            ("c", 10.0, 0, iv(5.0, 15.0)),
+            ("d", 10.0, 0, iv(20.0, 30.0)),
+        ]);
+        let schedule = BeamSearchScheduler::new(3, 3).schedule(&[block], &ss, hz(0.0, 100.0));
+        assert_eq!(schedule.len(), 4);
+
+        let mut intervals: Vec<_> = schedule.intervals().collect();
+        intervals.sort_by(|a, b| a.start().value().total_cmp(&b.start().value()));
+        for pair in intervals.windows(2) {
+            assert!(pair[0].end().value() <= pair[1].start().value());
+        }
+    }
+
+    #[test]
+    fn empty_input_produces_empty_schedule() {
+        let block = SchedulingBlock::<TestTask, Second>::new();
+        let schedule = BeamSearchScheduler::default().schedule(
+            &[block],
+            &SolutionSpace::new(),
+            hz(0.0, 100.0),
+        );
+        assert!(schedule.is_empty());
+    }
+}
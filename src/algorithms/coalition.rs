@@ -0,0 +1,643 @@
+//! Coalition-aware multi-resource scheduling.
+//!
+//! A task with a [`Task::coalition`] requirement needs `k` resources of each
+//! listed type *simultaneously*. [`CoalitionScheduler`] places such tasks at
+//! the earliest start where enough resources of every required type are free
+//! for the whole task, and books them on all members at once through a
+//! [`Timeline`]. Tasks without a coalition take any single eligible resource.
+//!
+//! # Ordering
+//!
+//! Tasks are placed greedily by descending priority, ties broken by task ID.
+//! Among the resources free at the chosen start, members are picked in the
+//! order of the scheduler's [`ArbitrationPolicy`], resource-ID order by
+//! default, so results are deterministic.
+//!
+//! # Exclusion groups
+//!
+//! Resources registered with
+//! [`with_exclusion_group`](CoalitionScheduler::with_exclusion_group) are
+//! never busy at the same time: a resource is only free where every partner
+//! in its groups is free too, and a coalition never picks two members of one
+//! group. Members are still picked greedily, so a coalition may be missed
+//! when only a different choice of members would avoid a group.
+//!
+//! # Concurrency limits
+//!
+//! [`with_concurrency_limit`](CoalitionScheduler::with_concurrency_limit)
+//! caps how many tasks with a given tag run at once across all resources,
+//! such as a downlink shared by every spacecraft. A capped task is only
+//! placed where fewer than the limit of its class are already running;
+//! tags are read from the task's [`SchedulingBlock`].
+
+use std::collections::HashMap;
+
+use crate::algorithms::arbitration::{
+    Arbitration, ArbitrationCandidate, ArbitrationPolicy, SlewCost,
+};
+use crate::algorithms::greedy::free_windows;
+use crate::algorithms::MultiResourceAlgorithm;
+use crate::resource::Resource;
+use crate::schedule::{ConcurrencyLimit, Schedule, Timeline};
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+use crate::Id;
+use qtty::{Quantity, Unit};
+
+/// Greedy multi-resource scheduler honoring coalition requirements.
+///
+/// Resource types are matched against the keys of each task's
+/// [`CoalitionConstraint`](crate::constraints::CoalitionConstraint).
+///
+/// # Example
+///
+/// ```no_run
+/// use virolai::algorithms::{CoalitionScheduler, MultiResourceAlgorithm};
+/// # use qtty::Second;
+/// # use virolai::solution_space::{Horizon, Interval};
+/// # use virolai::synthetic::{generate, SyntheticConfig};
+/// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+/// # let instance = generate(&SyntheticConfig::new(20, horizon));
+/// # let blocks = vec![instance.block];
+/// # let space = instance.solution_space;
+/// # use std::collections::HashMap;
+/// # use virolai::solution_space::SolutionSpace;
+/// # let resource_spaces = HashMap::from([
+/// #     ("lst-1".to_string(), space),
+/// #     ("lst-2".to_string(), SolutionSpace::new()),
+/// #     ("magic-1".to_string(), SolutionSpace::new()),
+/// # ]);
+///
+/// let scheduler = CoalitionScheduler::new([("lst-1", "LST"), ("lst-2", "LST"), ("magic-1", "MAGIC")]);
+/// let timeline = scheduler.schedule_timeline(&blocks, &resource_spaces, horizon);
+/// println!("{:?}", timeline.resources_of("joint-obs"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CoalitionScheduler<P = Arbitration> {
+    resource_types: HashMap<Id, String>,
+    exclusion_groups: Vec<Vec<Id>>,
+    concurrency_limits: Vec<ConcurrencyLimit>,
+    arbitration: P,
+    slew: Option<SlewCost>,
+}
+
+impl CoalitionScheduler {
+    /// Creates a scheduler from `(resource ID, resource type)` pairs.
+    pub fn new(
+        resource_types: impl IntoIterator<Item = (impl Into<Id>, impl Into<String>)>,
+    ) -> Self {
+        Self {
+            resource_types: resource_types
+                .into_iter()
+                .map(|(id, ty)| (id.into(), ty.into()))
+                .collect(),
+            exclusion_groups: Vec::new(),
+            concurrency_limits: Vec::new(),
+            arbitration: Arbitration::default(),
+            slew: None,
+        }
+    }
+
+    /// Creates a scheduler using each resource's
+    /// [`resource_id`](Resource::resource_id) and
+    /// [`resource_type`](Resource::resource_type).
+    pub fn from_resources<'a, A, R>(resources: impl IntoIterator<Item = &'a R>) -> Self
+    where
+        A: Unit,
+        R: Resource<A>,
+    {
+        Self::new(
+            resources
+                .into_iter()
+                .map(|r| (r.resource_id().to_string(), r.resource_type().to_string())),
+        )
+    }
+}
+
+impl<P> CoalitionScheduler<P> {
+    /// Ranks the resources free at a task's start with `policy` instead of
+    /// taking them in ID order, see [`arbitration`](crate::algorithms::arbitration).
+    pub fn with_arbitration<Q>(self, policy: Q) -> CoalitionScheduler<Q> {
+        CoalitionScheduler {
+            resource_types: self.resource_types,
+            exclusion_groups: self.exclusion_groups,
+            concurrency_limits: self.concurrency_limits,
+            arbitration: policy,
+            slew: self.slew,
+        }
+    }
+
+    /// Sets the cost of moving a resource from its previous task (`None`
+    /// if it has none yet) to the next one, as
+    /// `cost(resource, previous, task)`. Policies see it as
+    /// [`ArbitrationCandidate::slew`].
+    pub fn with_slew_cost(
+        mut self,
+        cost: impl Fn(&str, Option<&str>, &str) -> f64 + Send + Sync + 'static,
+    ) -> Self {
+        self.slew = Some(SlewCost::new(cost));
+        self
+    }
+
+    /// Forbids the given resources from being busy simultaneously, e.g. two
+    /// instruments sharing one focal station.
+    pub fn with_exclusion_group(
+        mut self,
+        resources: impl IntoIterator<Item = impl Into<Id>>,
+    ) -> Self {
+        self.exclusion_groups
+            .push(resources.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Allows at most `max` tasks tagged `key = value` to run at once, over
+    /// all resources together.
+    pub fn with_concurrency_limit(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+        max: usize,
+    ) -> Self {
+        self.concurrency_limits.push(ConcurrencyLimit {
+            key: key.into(),
+            value: value.into(),
+            max,
+        });
+        self
+    }
+
+    /// Type of a resource; resources not registered have the empty type.
+    pub fn resource_type(&self, resource: &str) -> &str {
+        self.resource_types
+            .get(resource)
+            .map(String::as_str)
+            .unwrap_or("")
+    }
+
+    /// Schedules all tasks, returning a timeline with one schedule per
+    /// resource in `resource_spaces`.
+    ///
+    /// A coalition task appears with the same interval in the schedule of
+    /// every resource it was booked on.
+    pub fn schedule_timeline<T, U, D, E>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        resource_spaces: &HashMap<Id, SolutionSpace<U>>,
+        horizon: Horizon<U>,
+    ) -> Timeline<U>
+    where
+        T: Task<U>,
+        U: Unit,
+        E: petgraph::EdgeType,
+        P: ArbitrationPolicy<U>,
+    {
+        let mut timeline = Timeline::with_resources(resource_spaces.keys().cloned());
+        for group in &self.exclusion_groups {
+            timeline.add_exclusion_group(group.iter().cloned());
+        }
+        for limit in &self.concurrency_limits {
+            timeline.add_concurrency_limit(limit.key.clone(), limit.value.clone(), limit.max);
+        }
+
+        let mut resources: Vec<&str> = resource_spaces.keys().map(String::as_str).collect();
+        resources.sort_unstable();
+
+        let mut tasks: Vec<_> = blocks
+            .iter()
+            .flat_map(|b| b.tasks().map(move |(id, task)| (id, task, b)))
+            .collect();
+        tasks.sort_by(|(a_id, a, _), (b_id, b, _)| {
+            b.priority().cmp(&a.priority()).then_with(|| a_id.cmp(b_id))
+        });
+
+        for (id, task, block) in tasks {
+            for (key, value) in block.tags_of(id) {
+                timeline.tag(id, key.clone(), value.clone());
+            }
+            let eligible: Vec<&str> = resources
+                .iter()
+                .copied()
+                .filter(|r| resource_spaces[*r].get_intervals(id).is_some())
+                .collect();
+            let groups = self.requirement_groups(task, &eligible);
+            if groups.iter().any(|(pool, k)| pool.len() < *k) {
+                continue;
+            }
+
+            let size = task.size_on_axis().value();
+            let saturated = timeline.saturated_for(id);
+            let free: HashMap<&str, Vec<Interval<U>>> = groups
+                .iter()
+                .flat_map(|(pool, _)| pool.iter().copied())
+                .map(|r| {
+                    let windows: Vec<(f64, f64)> = resource_spaces[r]
+                        .get_intervals(id)
+                        .expect("eligible resources contain the task")
+                        .iter()
+                        .map(|w| (w.start().value(), w.end().value()))
+                        .collect();
+                    let schedule = timeline.schedule(r).expect("timeline has every resource");
+                    let mut free = free_windows(
+                        &windows,
+                        horizon.start().value(),
+                        horizon.end().value(),
+                        schedule,
+                    );
+                    for partner in timeline.exclusive_partners(r) {
+                        if let Some(busy) = timeline.schedule(partner) {
+                            let windows: Vec<(f64, f64)> = free
+                                .iter()
+                                .map(|w| (w.start().value(), w.end().value()))
+                                .collect();
+                            free = free_windows(
+                                &windows,
+                                horizon.start().value(),
+                                horizon.end().value(),
+                                busy,
+                            );
+                        }
+                    }
+                    (r, without(free, &saturated))
+                })
+                .collect();
+
+            let exclusive = |a: &str, b: &str| timeline.are_exclusive(a, b);
+            let rank = |start: f64, pool: &mut Vec<&str>| {
+                let mut candidates: Vec<ArbitrationCandidate<'_, U>> = pool
+                    .iter()
+                    .map(|&resource| {
+                        let schedule = timeline
+                            .schedule(resource)
+                            .expect("timeline has every resource");
+                        let previous = schedule
+                            .iter()
+                            .take_while(|(_, iv)| iv.start().value() < start)
+                            .last()
+                            .map(|(id, _)| id);
+                        let slew = self
+                            .slew
+                            .as_ref()
+                            .map_or(0.0, |cost| cost.cost(resource, previous.as_deref(), id));
+                        ArbitrationCandidate {
+                            resource,
+                            resource_type: self.resource_type(resource),
+                            load: schedule.total_duration(),
+                            previous,
+                            slew,
+                        }
+                    })
+                    .collect();
+                self.arbitration
+                    .rank(id, Quantity::new(start), &mut candidates);
+                let ranked: Vec<&str> = candidates
+                    .iter()
+                    .filter_map(|c| pool.iter().copied().find(|r| *r == c.resource))
+                    .collect();
+                *pool = ranked;
+            };
+            if let Some((start, members)) =
+                earliest_coalition(&groups, &free, size, exclusive, rank)
+            {
+                let interval = Interval::new(Quantity::new(start), Quantity::new(start + size));
+                timeline
+                    .book(id, interval, &members)
+                    .expect("members are free over the interval");
+            }
+        }
+
+        timeline
+    }
+
+    /// Splits eligible resources into `(pool, count)` groups, one per required
+    /// type. Tasks without a coalition need one of any eligible resource.
+    fn requirement_groups<'r, T, U>(
+        &self,
+        task: &T,
+        eligible: &[&'r str],
+    ) -> Vec<(Vec<&'r str>, usize)>
+    where
+        T: Task<U>,
+        U: Unit,
+    {
+        let mut required: Vec<(&str, u32)> = task
+            .coalition()
+            .map(|c| {
+                c.requirements()
+                    .iter()
+                    .filter(|(_, &k)| k > 0)
+                    .map(|(ty, &k)| (ty.as_str(), k))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if required.is_empty() {
+            return vec![(eligible.to_vec(), 1)];
+        }
+        required.sort_unstable();
+        required
+            .into_iter()
+            .map(|(ty, k)| {
+                let pool = eligible
+                    .iter()
+                    .copied()
+                    .filter(|r| self.resource_type(r) == ty)
+                    .collect();
+                (pool, k as usize)
+            })
+            .collect()
+    }
+}
+
+/// Removes the `busy` intervals from the sorted, disjoint `free` windows.
+fn without<U: Unit>(free: Vec<Interval<U>>, busy: &[Interval<U>]) -> Vec<Interval<U>> {
+    if busy.is_empty() {
+        return free;
+    }
+    let mut out = Vec::with_capacity(free.len());
+    for window in free {
+        let mut start = window.start().value();
+        let end = window.end().value();
+        for b in busy.iter().filter(|b| b.overlaps(&window)) {
+            if b.start().value() > start {
+                out.push(Interval::new(Quantity::new(start), b.start()));
+            }
+            start = start.max(b.end().value());
+        }
+        if start < end {
+            out.push(Interval::new(Quantity::new(start), Quantity::new(end)));
+        }
+    }
+    out
+}
+
+/// Finds the earliest start at which every group has `k` members free for
+/// `size`, none of them mutually `exclusive`, returning the start and the
+/// chosen members. Members are taken in the order `rank` puts the free
+/// resources of each group in.
+///
+/// The earliest feasible start is always the start of some free window, so
+/// only those are tried.
+fn earliest_coalition<'r, U: Unit>(
+    groups: &[(Vec<&'r str>, usize)],
+    free: &HashMap<&'r str, Vec<Interval<U>>>,
+    size: f64,
+    exclusive: impl Fn(&str, &str) -> bool,
+    rank: impl Fn(f64, &mut Vec<&'r str>),
+) -> Option<(f64, Vec<&'r str>)> {
+    let mut starts: Vec<f64> = free
+        .values()
+        .flatten()
+        .filter(|w| w.duration().value() >= size)
+        .map(|w| w.start().value())
+        .collect();
+    starts.sort_by(f64::total_cmp);
+    starts.dedup();
+
+    let fits = |r: &str, start: f64| {
+        free[r]
+            .iter()
+            .any(|w| w.start().value() <= start && start + size <= w.end().value())
+    };
+
+    starts.into_iter().find_map(|start| {
+        let mut members: Vec<&str> = Vec::new();
+        for (pool, k) in groups {
+            let mut pool: Vec<&str> = pool.iter().copied().filter(|r| fits(r, start)).collect();
+            if pool.len() < *k {
+                return None;
+            }
+            rank(start, &mut pool);
+            let mut chosen = 0;
+            for r in pool {
+                if chosen == *k {
+                    break;
+                }
+                if !members.iter().any(|m| exclusive(m, r)) {
+                    members.push(r);
+                    chosen += 1;
+                }
+            }
+            if chosen < *k {
+                return None;
+            }
+        }
+        Some((start, members))
+    })
+}
+
+impl<T, U, D, E, P> MultiResourceAlgorithm<T, U, D, E> for CoalitionScheduler<P>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+    P: ArbitrationPolicy<U>,
+{
+    fn schedule_multi(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        resource_spaces: &HashMap<Id, SolutionSpace<U>>,
+        horizon: Horizon<U>,
+    ) -> HashMap<Id, Schedule<U>> {
+        self.schedule_timeline(blocks, resource_spaces, horizon)
+            .into_schedules()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::Arbitration;
+    use crate::constraints::CoalitionConstraint;
+    use crate::test_utils::{hz, iv, TestTask};
+    use qtty::Second;
+
+    fn scheduler() -> CoalitionScheduler {
+        CoalitionScheduler::new([
+            ("lst-1", "LST"),
+            ("lst-2", "LST"),
+            ("lst-3", "LST"),
+            ("magic-1", "MAGIC"),
+        ])
+    }
+
+    fn spaces(windows: &[(&str, &str, Interval<Second>)]) -> HashMap<Id, SolutionSpace<Second>> {
+        let mut out: HashMap<Id, SolutionSpace<Second>> = HashMap::new();
+        for r in ["lst-1", "lst-2", "lst-3", "magic-1"] {
+            out.insert(r.to_string(), SolutionSpace::new());
+        }
+        for &(resource, task, window) in windows {
+            out.get_mut(resource).unwrap().add_interval(task, window);
+        }
+        out
+    }
+
+    fn block(tasks: Vec<TestTask>) -> SchedulingBlock<TestTask, Second> {
+        let mut block = SchedulingBlock::new();
+        for task in tasks {
+            let name = task.name.clone();
+            block.add_task_with_id(task, Some(name)).unwrap();
+        }
+        block
+    }
+
+    #[test]
+    fn coalition_booked_simultaneously() {
+        let joint = TestTask::new("joint", 10.0)
+            .with_coalition(CoalitionConstraint::new([("LST", 2), ("MAGIC", 1)]));
+        let ss = spaces(&[
+            ("lst-1", "joint", iv(0.0, 100.0)),
+            ("lst-2", "joint", iv(20.0, 100.0)),
+            ("lst-3", "joint", iv(50.0, 100.0)),
+            ("magic-1", "joint", iv(15.0, 100.0)),
+        ]);
+
+        let t = scheduler().schedule_timeline(&[block(vec![joint])], &ss, hz(0.0, 100.0));
+
+        assert_eq!(t.interval_of("joint"), Some(iv(20.0, 30.0)));
+        assert_eq!(t.resources_of("joint"), vec!["lst-1", "lst-2", "magic-1"]);
+        assert!(t.schedule("lst-3").unwrap().is_empty());
+    }
+
+    #[test]
+    fn coalition_waits_for_busy_members() {
+        let solo = TestTask::new("solo", 30.0).with_priority(10);
+        let joint =
+            TestTask::new("joint", 10.0).with_coalition(CoalitionConstraint::single_type("LST", 3));
+        let ss = spaces(&[
+            ("lst-1", "solo", iv(0.0, 30.0)),
+            ("lst-1", "joint", iv(0.0, 100.0)),
+            ("lst-2", "joint", iv(0.0, 100.0)),
+            ("lst-3", "joint", iv(0.0, 100.0)),
+        ]);
+
+        let t = scheduler().schedule_timeline(&[block(vec![solo, joint])], &ss, hz(0.0, 100.0));
+
+        assert_eq!(t.resources_of("solo"), vec!["lst-1"]);
+        assert_eq!(t.interval_of("joint"), Some(iv(30.0, 40.0)));
+        assert_eq!(t.resources_of("joint"), vec!["lst-1", "lst-2", "lst-3"]);
+    }
+
+    #[test]
+    fn unsatisfiable_coalition_is_skipped() {
+        let joint = TestTask::new("joint", 10.0)
+            .with_coalition(CoalitionConstraint::single_type("MAGIC", 2));
+        let ss = spaces(&[("magic-1", "joint", iv(0.0, 100.0))]);
+
+        let t = scheduler().schedule_timeline(&[block(vec![joint])], &ss, hz(0.0, 100.0));
+
+        assert_eq!(t.task_count(), 0);
+    }
+
+    #[test]
+    fn plain_tasks_take_one_resource() {
+        let a = TestTask::new("a", 10.0);
+        let b = TestTask::new("b", 10.0);
+        let ss = spaces(&[
+            ("lst-1", "a", iv(0.0, 10.0)),
+            ("lst-1", "b", iv(0.0, 10.0)),
+            ("magic-1", "b", iv(0.0, 10.0)),
+        ]);
+
+        let schedules = scheduler().schedule_multi(&[block(vec![a, b])], &ss, hz(0.0, 100.0));
+
+        assert!(schedules["lst-1"].contains_task("a"));
+        assert!(schedules["magic-1"].contains_task("b"));
+        assert_eq!(schedules.len(), 4);
+    }
+
+    #[test]
+    fn exclusion_group_members_never_overlap() {
+        // lst-2 and magic-1 share a focal station.
+        let scheduler = scheduler().with_exclusion_group(["lst-2", "magic-1"]);
+        let solo = TestTask::new("solo", 20.0).with_priority(10);
+        let joint = TestTask::new("joint", 10.0)
+            .with_coalition(CoalitionConstraint::new([("LST", 1), ("MAGIC", 1)]));
+        let ss = spaces(&[
+            ("lst-2", "solo", iv(0.0, 20.0)),
+            ("lst-2", "joint", iv(0.0, 100.0)),
+            ("magic-1", "joint", iv(0.0, 100.0)),
+        ]);
+
+        let t = scheduler.schedule_timeline(&[block(vec![solo, joint])], &ss, hz(0.0, 100.0));
+
+        // magic-1 waits for lst-2, and joint cannot use both at once.
+        assert_eq!(t.resources_of("solo"), vec!["lst-2"]);
+        assert_eq!(t.task_count(), 1);
+
+        let ss = spaces(&[
+            ("lst-1", "joint", iv(0.0, 100.0)),
+            ("lst-2", "solo", iv(0.0, 20.0)),
+            ("magic-1", "joint", iv(0.0, 100.0)),
+        ]);
+        let solo = TestTask::new("solo", 20.0).with_priority(10);
+        let joint = TestTask::new("joint", 10.0)
+            .with_coalition(CoalitionConstraint::new([("LST", 1), ("MAGIC", 1)]));
+        let t = scheduler.schedule_timeline(&[block(vec![solo, joint])], &ss, hz(0.0, 100.0));
+        assert_eq!(t.interval_of("joint"), Some(iv(20.0, 30.0)));
+        assert_eq!(t.resources_of("joint"), vec!["lst-1", "magic-1"]);
+        assert!(t.exclusion_conflicts().is_empty());
+    }
+
+    #[test]
+    fn arbitration_picks_among_free_resources() {
+        let tasks = || {
+            block(vec![
+                TestTask::new("a", 10.0).with_priority(1),
+                TestTask::new("b", 10.0),
+            ])
+        };
+        let ss = spaces(&[
+            ("lst-1", "a", iv(0.0, 100.0)),
+            ("lst-2", "a", iv(0.0, 100.0)),
+            ("lst-1", "b", iv(50.0, 100.0)),
+            ("lst-2", "b", iv(50.0, 100.0)),
+        ]);
+        let placed = |scheduler: &CoalitionScheduler| {
+            let t = scheduler.schedule_timeline(&[tasks()], &ss, hz(0.0, 100.0));
+            assert_eq!(t.interval_of("b"), Some(iv(50.0, 60.0)));
+            [t.resources_of("a"), t.resources_of("b")].map(|r| r.join(","))
+        };
+
+        assert_eq!(placed(&scheduler()), ["lst-1", "lst-1"]);
+        let balanced = scheduler().with_arbitration(Arbitration::LoadBalance);
+        assert_eq!(placed(&balanced), ["lst-1", "lst-2"]);
+        let preferred = scheduler().with_arbitration(Arbitration::Preference {
+            order: vec!["lst-2".into()],
+        });
+        assert_eq!(placed(&preferred), ["lst-2", "lst-2"]);
+        // Slewing away from a costs more than starting afresh.
+        let slew = scheduler()
+            .with_arbitration(Arbitration::MinimizeSlew)
+            .with_slew_cost(|_, previous, _| if previous.is_some() { 5.0 } else { 1.0 });
+        assert_eq!(placed(&slew), ["lst-1", "lst-2"]);
+    }
+
+    #[test]
+    fn concurrency_limit_spans_resources() {
+        // Three spacecraft share a downlink carrying two passes at a time.
+        let scheduler = scheduler().with_concurrency_limit("link", "downlink", 2);
+        let mut b = block(vec![
+            TestTask::new("d1", 10.0).with_priority(3),
+            TestTask::new("d2", 10.0).with_priority(2),
+            TestTask::new("d3", 10.0).with_priority(1),
+            TestTask::new("science", 10.0),
+        ]);
+        for id in ["d1", "d2", "d3"] {
+            b.tag(id, "link", "downlink").unwrap();
+        }
+        let ss = spaces(&[
+            ("lst-1", "d1", iv(0.0, 100.0)),
+            ("lst-2", "d2", iv(5.0, 100.0)),
+            ("lst-3", "d3", iv(0.0, 100.0)),
+            ("magic-1", "science", iv(0.0, 100.0)),
+        ]);
+
+        let t = scheduler.schedule_timeline(&[b], &ss, hz(0.0, 100.0));
+
+        assert_eq!(t.interval_of("d1"), Some(iv(0.0, 10.0)));
+        assert_eq!(t.interval_of("d2"), Some(iv(5.0, 15.0)));
+        // d3 waits for d1 to finish; untagged work is not limited.
+        assert_eq!(t.interval_of("d3"), Some(iv(10.0, 20.0)));
+        assert_eq!(t.interval_of("science"), Some(iv(0.0, 10.0)));
+        assert!(t.concurrency_violations().is_empty());
+    }
+}
@@ -0,0 +1,436 @@
+//! Multi-horizon planning with commitment levels.
+//!
+//! Queue-based operations replan repeatedly over a long horizon that is split
+//! into three consecutive zones:
+//!
+//! | Zone        | Rule on replanning                                                |
+//! |-------------|-------------------------------------------------------------------|
+//! | `Committed` | Placements from the previous plan are never moved                 |
+//! | `Tentative` | Previous placements are kept while still feasible, else replanned |
+//! | `Forecast`  | Placements are recomputed freely                                  |
+//!
+//! [`ZonedPlanner`] wraps any [`SchedulingAlgorithm`] and applies these rules;
+//! the resulting [`ZonedPlan`] labels every task with the zone it starts in.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::algorithms::SchedulingAlgorithm;
+use crate::schedule::errors::ScheduleError;
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, IntervalSet, SolutionSpace};
+use crate::Id;
+use qtty::{Quantity, Unit};
+
+/// Stability level of a zone of the planning horizon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CommitmentLevel {
+    /// Frozen: placements are never moved.
+    Committed,
+    /// Placements are kept if still feasible and replanned freely otherwise.
+    Tentative,
+    /// Placements are recomputed freely.
+    Forecast,
+}
+
+impl fmt::Display for CommitmentLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Committed => write!(f, "Committed"),
+            Self::Tentative => write!(f, "Tentative"),
+            Self::Forecast => write!(f, "Forecast"),
+        }
+    }
+}
+
+/// A horizon divided into committed, tentative and forecast zones.
+///
+/// The zones are `[start, committed_end)`, `[committed_end, tentative_end)`
+/// and `[tentative_end, end)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanningZones<U: Unit> {
+    horizon: Horizon<U>,
+    committed_end: Quantity<U>,
+    tentative_end: Quantity<U>,
+}
+
+impl<U: Unit> PlanningZones<U> {
+    /// Creates planning zones over `horizon`.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `horizon.start() <= committed_end <= tentative_end <= horizon.end()`.
+    pub fn new(
+        horizon: Horizon<U>,
+        committed_end: Quantity<U>,
+        tentative_end: Quantity<U>,
+    ) -> Self {
+        assert!(
+            horizon.start().value() <= committed_end.value()
+                && committed_end.value() <= tentative_end.value()
+                && tentative_end.value() <= horizon.end().value(),
+            "Zone boundaries must be ordered within the horizon"
+        );
+        Self {
+            horizon,
+            committed_end,
+            tentative_end,
+        }
+    }
+
+    /// Returns the full planning horizon.
+    pub fn horizon(&self) -> Horizon<U> {
+        self.horizon
+    }
+
+    /// Returns the committed zone.
+    pub fn committed(&self) -> Interval<U> {
+        Interval::new(self.horizon.start(), self.committed_end)
+    }
+
+    /// Returns the tentative zone.
+    pub fn tentative(&self) -> Interval<U> {
+        Interval::new(self.committed_end, self.tentative_end)
+    }
+
+    /// Returns the forecast zone.
+    pub fn forecast(&self) -> Interval<U> {
+        Interval::new(self.tentative_end, self.horizon.end())
+    }
+
+    /// Returns the commitment level at `position`.
+    ///
+    /// Positions before the horizon are committed; positions after it are forecast.
+    pub fn level_at(&self, position: Quantity<U>) -> CommitmentLevel {
+        if position.value() < self.committed_end.value() {
+            CommitmentLevel::Committed
+        } else if position.value() < self.tentative_end.value() {
+            CommitmentLevel::Tentative
+        } else {
+            CommitmentLevel::Forecast
+        }
+    }
+}
+
+/// Result of a zoned replanning run.
+#[derive(Debug, Clone)]
+pub struct ZonedPlan<U: Unit> {
+    /// The new schedule.
+    pub schedule: Schedule<U>,
+    /// Zone of every scheduled task, by its start time.
+    pub zones: HashMap<Id, CommitmentLevel>,
+    /// Previously tentative tasks that were moved or dropped.
+    pub moved: Vec<Id>,
+    /// Placements of the wrapped algorithm that could not be added to the
+    /// new schedule, with the reason, such as a task it placed although it
+    /// was already kept from the previous plan.
+    pub rejected: Vec<(Id, ScheduleError)>,
+}
+
+impl<U: Unit> ZonedPlan<U> {
+    /// Returns the zone a task was placed in, if scheduled.
+    pub fn level_of(&self, id: &str) -> Option<CommitmentLevel> {
+        self.zones.get(id).copied()
+    }
+
+    /// Returns the IDs of tasks placed in the given zone, sorted.
+    pub fn tasks_in(&self, level: CommitmentLevel) -> Vec<&str> {
+        let mut ids: Vec<&str> = self
+            .zones
+            .iter()
+            .filter(|(_, l)| **l == level)
+            .map(|(id, _)| id.as_str())
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+/// Replans with zone-dependent stability rules around a wrapped algorithm.
+///
+/// # Example
+///
+/// ```no_run
+/// use virolai::algorithms::commitment::{PlanningZones, ZonedPlanner};
+/// use virolai::algorithms::ESTScheduler;
+/// # use qtty::Quantity;
+/// # use virolai::schedule::Schedule;
+/// # use qtty::Second;
+/// # use virolai::solution_space::{Horizon, Interval};
+/// # use virolai::synthetic::{generate, SyntheticConfig};
+/// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+/// # let instance = generate(&SyntheticConfig::new(20, horizon));
+/// # let blocks = vec![instance.block];
+/// # let space = instance.solution_space;
+/// # let previous_schedule = Schedule::new();
+/// # let tonight_end = Quantity::new(43_200.0);
+/// # let week_end = Quantity::new(86_400.0);
+///
+/// let zones = PlanningZones::new(horizon, tonight_end, week_end);
+/// let planner = ZonedPlanner::new(ESTScheduler::default(), zones);
+/// let plan = planner.replan(&blocks, &space, &previous_schedule);
+/// ```
+pub struct ZonedPlanner<A, U: Unit> {
+    inner: A,
+    zones: PlanningZones<U>,
+}
+
+impl<A, U: Unit> ZonedPlanner<A, U> {
+    /// Wraps `algorithm`, which is used to place everything not kept from the
+    /// previous plan.
+    pub fn new(algorithm: A, zones: PlanningZones<U>) -> Self {
+        Self {
+            inner: algorithm,
+            zones,
+        }
+    }
+
+    /// Returns the planning zones.
+    pub fn zones(&self) -> &PlanningZones<U> {
+        &self.zones
+    }
+
+    /// Produces a new plan from `previous`.
+    ///
+    /// 1. Previous placements starting in the committed zone are kept as-is.
+    /// 2. Previous placements starting in the tentative zone are kept if the
+    ///    task still exists, still fits its solution space and does not
+    ///    collide with kept placements.
+    /// 3. All other tasks are scheduled by the wrapped algorithm on
+    ///    `[committed_end, horizon.end)`, using only time not already taken.
+    ///    Its placements that cannot be added are reported in
+    ///    [`ZonedPlan::rejected`].
+    pub fn replan<T, D, E>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        previous: &Schedule<U>,
+    ) -> ZonedPlan<U>
+    where
+        A: SchedulingAlgorithm<T, U, D, E>,
+        T: Task<U>,
+        E: petgraph::EdgeType,
+    {
+        let sizes: HashMap<&str, Quantity<U>> = blocks
+            .iter()
+            .flat_map(|block| block.tasks())
+            .map(|(id, task)| (id, task.size_on_axis()))
+            .collect();
+
+        let mut schedule = Schedule::new();
+        let mut moved = Vec::new();
+        let mut rejected = Vec::new();
+
+        for (id, interval, background) in previous.iter_all() {
+            match self.zones.level_at(interval.start()) {
+                CommitmentLevel::Committed => {
+                    // Entries of one schedule never collide with each other.
+                    schedule
+                        .add_to_lane(id, interval, background)
+                        .expect("committed placements come from a valid schedule");
+                }
+                CommitmentLevel::Tentative => {
+                    let still_fits = sizes
+                        .get(id.as_str())
+                        .is_some_and(|size| solution_space.can_place(&id, interval.start(), *size));
+                    if !(still_fits
+                        && schedule
+                            .add_to_lane(id.clone(), interval, background)
+                            .is_ok())
+                    {
+                        moved.push(id);
+                    }
+                }
+                CommitmentLevel::Forecast => {}
+            }
+        }
+
+        // Everything else is placed on the free time after the committed zone.
+        if let Some(open) = self.zones.horizon.remaining_after(self.zones.committed_end) {
+            let taken: IntervalSet<U> = schedule.intervals().collect();
+            let free = taken.complement(open.interval());
+            let mut remaining = SolutionSpace::new();
+            for id in sizes.keys().filter(|id| !schedule.contains_task(id)) {
+                if let Some(set) = solution_space.get_intervals(id) {
+                    remaining.set_intervals(id.to_string(), set.intersection(&free).into_inner());
+                }
+            }
+
+            let placed = self.inner.schedule(blocks, &remaining, open);
+            for (id, interval, background) in placed.iter_all() {
+                if let Err(err) = schedule.add_to_lane(id.clone(), interval, background) {
+                    rejected.push((id, err));
+                }
+            }
+        }
+
+        moved.retain(|id| schedule.get_interval(id) != previous.get_interval(id));
+        moved.sort();
+
+        let zones = schedule
+            .iter_all()
+            .map(|(id, interval, _)| (id, self.zones.level_at(interval.start())))
+            .collect();
+
+        ZonedPlan {
+            schedule,
+            zones,
+            moved,
+            rejected,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::ESTScheduler;
+    use crate::test_utils::{block_and_space, hz, iv, q, TestTask};
+    use qtty::Second;
+
+    fn zones() -> PlanningZones<Second> {
+        PlanningZones::new(hz(0.0, 300.0), q(100.0), q(200.0))
+    }
+
+    // ── PlanningZones ─────────────────────────────────────────────────
+
+    #[test]
+    fn zones_partition_horizon() {
+        let z = zones();
+        assert_eq!(z.committed(), iv(0.0, 100.0));
+        assert_eq!(z.tentative(), iv(100.0, 200.0));
+        assert_eq!(z.forecast(), iv(200.0, 300.0));
+        assert_eq!(z.level_at(q(99.0)), CommitmentLevel::Committed);
+        assert_eq!(z.level_at(q(100.0)), CommitmentLevel::Tentative);
+        assert_eq!(z.level_at(q(250.0)), CommitmentLevel::Forecast);
+    }
+
+    #[test]
+    #[should_panic(expected = "Zone boundaries must be ordered")]
+    fn zones_reject_unordered_boundaries() {
+        PlanningZones::new(hz(0.0, 300.0), q(200.0), q(100.0));
+    }
+
+    // ── ZonedPlanner ──────────────────────────────────────────────────
+
+    #[test]
+    fn committed_placements_never_move() {
+        // "a" now prefers a later window, but it is committed at [50, 60)
+        let (block, ss) = block_and_space(&[("a", 10.0, 0, iv(150.0, 300.0))]);
+        let mut previous = Schedule::new();
+        previous.add("a", iv(50.0, 60.0)).unwrap();
+
+        let planner = ZonedPlanner::new(ESTScheduler::default(), zones());
+        let plan = planner.replan(&[block], &ss, &previous);
+
+        assert_eq!(plan.schedule.get_interval("a"), Some(iv(50.0, 60.0)));
+        assert_eq!(plan.level_of("a"), Some(CommitmentLevel::Committed));
+        assert!(plan.moved.is_empty());
+    }
+
+    #[test]
+    fn tentative_kept_when_feasible_and_moved_otherwise() {
+        let (block, ss) = block_and_space(&[
+            ("keep", 10.0, 0, iv(100.0, 300.0)),
+            ("shift", 10.0, 0, iv(160.0, 300.0)),
+        ]);
+        let mut previous = Schedule::new();
+        previous.add("keep", iv(150.0, 160.0)).unwrap();
+        // Window shrank: [120, 130) is no longer feasible
+        previous.add("shift", iv(120.0, 130.0)).unwrap();
+
+        let planner = ZonedPlanner::new(ESTScheduler::default(), zones());
+        let plan = planner.replan(&[block], &ss, &previous);
+
+        assert_eq!(plan.schedule.get_interval("keep"), Some(iv(150.0, 160.0)));
+        assert_eq!(plan.schedule.get_interval("shift"), Some(iv(160.0, 170.0)));
+        assert_eq!(plan.moved, vec!["shift"]);
+    }
+
+    #[test]
+    fn new_tasks_avoid_committed_zone_and_kept_tasks() {
+        let (block, ss) = block_and_space(&[
+            ("kept", 10.0, 0, iv(100.0, 300.0)),
+            ("new", 10.0, 0, iv(0.0, 300.0)),
+        ]);
+        let mut previous = Schedule::new();
+        previous.add("kept", iv(100.0, 110.0)).unwrap();
+
+        let planner = ZonedPlanner::new(ESTScheduler::default(), zones());
+        let plan = planner.replan(&[block], &ss, &previous);
+
+        assert_eq!(plan.schedule.get_interval("new"), Some(iv(110.0, 120.0)));
+        assert_eq!(
+            plan.tasks_in(CommitmentLevel::Tentative),
+            vec!["kept", "new"]
+        );
+    }
+
+    #[test]
+    fn background_tasks_keep_their_lane() {
+        let (mut block, mut ss) = block_and_space(&[("a", 10.0, 0, iv(0.0, 300.0))]);
+        for id in ["monitor", "calib"] {
+            let task = TestTask::new(id, 50.0).in_background();
+            block.add_task_with_id(task, Some(id.into())).unwrap();
+            ss.add_interval(id, iv(0.0, 300.0));
+        }
+        let mut previous = Schedule::new();
+        previous.add("a", iv(50.0, 60.0)).unwrap();
+        previous.add_background("monitor", iv(20.0, 70.0)).unwrap();
+
+        let planner = ZonedPlanner::new(ESTScheduler::default(), zones());
+        let plan = planner.replan(&[block], &ss, &previous);
+
+        assert_eq!(plan.schedule.get_interval("a"), Some(iv(50.0, 60.0)));
+        assert!(plan.schedule.is_background("monitor"));
+        assert_eq!(plan.schedule.get_interval("monitor"), Some(iv(20.0, 70.0)));
+        assert!(plan.schedule.is_background("calib"));
+        assert_eq!(plan.level_of("monitor"), Some(CommitmentLevel::Committed));
+        assert_eq!(plan.level_of("calib"), Some(CommitmentLevel::Tentative));
+    }
+
+    /// Places task "a" at 150, ignoring that it may already be scheduled.
+    struct AlwaysA;
+
+    impl SchedulingAlgorithm<TestTask, Second, (), petgraph::Directed> for AlwaysA {
+        fn schedule(
+            &self,
+            _: &[SchedulingBlock<TestTask, Second>],
+            _: &SolutionSpace<Second>,
+            _: Horizon<Second>,
+        ) -> Schedule<Second> {
+            let mut schedule = Schedule::new();
+            schedule.add("a", iv(150.0, 160.0)).unwrap();
+            schedule
+        }
+    }
+
+    #[test]
+    fn rejected_inner_placements_are_reported() {
+        let (block, ss) = block_and_space(&[("a", 10.0, 0, iv(0.0, 300.0))]);
+        let mut previous = Schedule::new();
+        previous.add("a", iv(50.0, 60.0)).unwrap();
+
+        let plan = ZonedPlanner::new(AlwaysA, zones()).replan(&[block], &ss, &previous);
+
+        assert_eq!(plan.schedule.get_interval("a"), Some(iv(50.0, 60.0)));
+        assert_eq!(
+            plan.rejected,
+            vec![("a".to_string(), ScheduleError::DuplicateTaskId("a".into()))]
+        );
+    }
+
+    #[test]
+    fn forecast_placements_are_recomputed() {
+        let (block, ss) = block_and_space(&[("f", 10.0, 0, iv(100.0, 300.0))]);
+        let mut previous = Schedule::new();
+        previous.add("f", iv(250.0, 260.0)).unwrap();
+
+        let planner = ZonedPlanner::new(ESTScheduler::default(), zones());
+        let plan = planner.replan(&[block], &ss, &previous);
+
+        assert_eq!(plan.schedule.get_interval("f"), Some(iv(100.0, 110.0)));
+        assert_eq!(plan.level_of("f"), Some(CommitmentLevel::Tentative));
+    }
+}
@@ -0,0 +1,366 @@
+//! Algorithm selection as data.
+//!
+//! An [`AlgorithmConfig`] names an algorithm and holds its parameters, so a
+//! run configuration can live in a file and be turned into a scheduler with
+//! [`AlgorithmConfig::build`]. With the `serde` feature the enum is tagged
+//! by an `algorithm` field and missing parameters take their defaults:
+//!
+//! ```text
+//! {"algorithm": "est", "endangered_threshold": 2}
+//! {"algorithm": "greedy", "objective": "ExpectedValue", "weights": {"priority": 2.0, "urgency": 1.0}}
+//! {"algorithm": "beam", "beam_width": 4, "depth": 3, "scorer": "flexibility"}
+//! {"algorithm": "distilled", "scorer": {"w1": [], "b1": [], "w2": [1, 0, 0, 0, 0, 0], "b2": 0}}
+//! ```
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #[cfg(feature = "serde")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use virolai::algorithms::AlgorithmConfig;
+//! # use petgraph::Directed;
+//! # use virolai::synthetic::SyntheticTask;
+//! # type MyTask = SyntheticTask<Second>;
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let space = instance.solution_space;
+//!
+//! let config: AlgorithmConfig = serde_json::from_str(r#"{"algorithm": "beam", "depth": 3}"#)?;
+//! let scheduler = config.build::<MyTask, Second, (), Directed>()?;
+//! let schedule = scheduler.schedule(&blocks, &space, horizon);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "serde"))]
+//! # fn main() {}
+//! ```
+
+use thiserror::Error;
+
+use crate::algorithms::greedy::WeightedScore;
+use crate::algorithms::rl::distilled::{DistilledScheduler, DistilledScorer};
+use crate::algorithms::scoring::{EstOrder, FlexibilityScore};
+use crate::algorithms::{
+    BeamSearchScheduler, ESTScheduler, GreedyScheduler, Objective, SchedulingAlgorithm,
+};
+use crate::scheduling_block::Task;
+use qtty::{Quantity, Second};
+
+/// A reason an [`AlgorithmConfig`] cannot be built.
+#[derive(Debug, Error)]
+pub enum AlgorithmConfigError {
+    #[error("Score weight {name} must be finite, got {value}")]
+    InvalidWeight { name: &'static str, value: f64 },
+
+    #[cfg(feature = "rl-nn")]
+    #[error(transparent)]
+    InvalidRLConfig(#[from] crate::algorithms::rl::config::RLConfigError),
+
+    #[cfg(feature = "rl-nn")]
+    #[error("Cannot load the RL checkpoint: {0}")]
+    Checkpoint(#[from] tch::TchError),
+}
+
+/// A scheduling algorithm and its parameters.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "algorithm", rename_all = "snake_case")
+)]
+#[derive(Debug, Clone)]
+pub enum AlgorithmConfig {
+    /// [`ESTScheduler`].
+    Est(EstConfig),
+    /// [`GreedyScheduler`] with a [`WeightedScore`].
+    Greedy(GreedyConfig),
+    /// [`BeamSearchScheduler`].
+    Beam(BeamConfig),
+    /// [`DistilledScheduler`].
+    Distilled(DistilledConfig),
+    /// [`RLScheduler`](crate::algorithms::RLScheduler), on the CPU.
+    #[cfg(feature = "rl-nn")]
+    Rl(RLSchedulerConfig),
+}
+
+/// Parameters of [`ESTScheduler`].
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EstConfig {
+    /// Read in the unit of the scheduling axis the scheduler is built for.
+    pub endangered_threshold: Quantity<Second>,
+    pub objective: Objective,
+}
+
+impl Default for EstConfig {
+    fn default() -> Self {
+        Self {
+            endangered_threshold: ESTScheduler::default().endangered_threshold(),
+            objective: Objective::Nominal,
+        }
+    }
+}
+
+/// Parameters of [`GreedyScheduler`].
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GreedyConfig {
+    pub objective: Objective,
+    pub weights: WeightedScore,
+}
+
+/// Candidate scorer of [`BeamSearchScheduler`].
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BeamScorer {
+    /// [`EstOrder`].
+    #[default]
+    Est,
+    /// [`FlexibilityScore`].
+    Flexibility,
+    /// [`WeightedScore`].
+    Weighted(WeightedScore),
+}
+
+/// Parameters of [`BeamSearchScheduler`].
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeamConfig {
+    pub beam_width: usize,
+    pub depth: usize,
+    /// Read in the unit of the scheduling axis the scheduler is built for.
+    pub endangered_threshold: Quantity<Second>,
+    pub scorer: BeamScorer,
+}
+
+impl Default for BeamConfig {
+    fn default() -> Self {
+        let beam: BeamSearchScheduler = BeamSearchScheduler::default();
+        Self {
+            beam_width: beam.beam_width(),
+            depth: beam.depth(),
+            endangered_threshold: beam.endangered_threshold(),
+            scorer: BeamScorer::Est,
+        }
+    }
+}
+
+/// Parameters of [`DistilledScheduler`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistilledConfig {
+    pub scorer: DistilledScorer,
+}
+
+/// Parameters of [`RLScheduler`](crate::algorithms::RLScheduler).
+#[cfg(feature = "rl-nn")]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub struct RLSchedulerConfig {
+    /// Path to the saved `actor.safetensors` file.
+    pub checkpoint: std::path::PathBuf,
+    /// Configuration the actor was trained with. Its step duration is read
+    /// in the unit of the scheduling axis the scheduler is built for.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub config: crate::algorithms::rl::config::RLConfig,
+}
+
+impl AlgorithmConfig {
+    /// The name of the algorithm, as in the serialized `algorithm` tag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Est(_) => "est",
+            Self::Greedy(_) => "greedy",
+            Self::Beam(_) => "beam",
+            Self::Distilled(_) => "distilled",
+            #[cfg(feature = "rl-nn")]
+            Self::Rl(_) => "rl",
+        }
+    }
+
+    /// Builds the configured scheduler.
+    ///
+    /// # Errors
+    ///
+    /// - [`AlgorithmConfigError::InvalidWeight`] if a score weight is NaN or
+    ///   infinite
+    /// - with `rl-nn`, [`AlgorithmConfigError::InvalidRLConfig`] and
+    ///   [`AlgorithmConfigError::Checkpoint`] if the RL configuration is
+    ///   invalid or the checkpoint cannot be loaded
+    pub fn build<T, U, D, E>(
+        &self,
+    ) -> Result<Box<dyn SchedulingAlgorithm<T, U, D, E>>, AlgorithmConfigError>
+    where
+        T: Task<U> + Clone,
+        U: qtty::Unit,
+        E: petgraph::EdgeType,
+    {
+        Ok(match self {
+            Self::Est(c) => {
+                let threshold = Quantity::<U>::new(c.endangered_threshold.value());
+                Box::new(ESTScheduler::new(threshold).with_objective(c.objective))
+            }
+            Self::Greedy(c) => Box::new(
+                GreedyScheduler::with_score(checked(c.weights)?).with_objective(c.objective),
+            ),
+            Self::Beam(c) => {
+                let beam = BeamSearchScheduler::new(c.beam_width, c.depth)
+                    .with_endangered_threshold(Quantity::new(c.endangered_threshold.value()));
+                match c.scorer {
+                    BeamScorer::Est => Box::new(beam.with_scorer(EstOrder)),
+                    BeamScorer::Flexibility => Box::new(beam.with_scorer(FlexibilityScore)),
+                    BeamScorer::Weighted(weights) => Box::new(beam.with_scorer(checked(weights)?)),
+                }
+            }
+            Self::Distilled(c) => Box::new(DistilledScheduler::new(c.scorer.clone())),
+            #[cfg(feature = "rl-nn")]
+            Self::Rl(c) => {
+                c.config.validate()?;
+                let step = qtty::Quantity::<U>::new(c.config.step_duration.value());
+                Box::new(crate::algorithms::RLScheduler::from_checkpoint_with_config(
+                    &c.checkpoint,
+                    c.config.clone().with_step_duration(step),
+                    tch::Device::Cpu,
+                )?)
+            }
+        })
+    }
+}
+
+impl Default for AlgorithmConfig {
+    /// EST with its default threshold.
+    fn default() -> Self {
+        Self::Est(EstConfig::default())
+    }
+}
+
+/// `weights`, if both are finite.
+fn checked(weights: WeightedScore) -> Result<WeightedScore, AlgorithmConfigError> {
+    for (name, value) in [("priority", weights.priority), ("urgency", weights.urgency)] {
+        if !value.is_finite() {
+            return Err(AlgorithmConfigError::InvalidWeight { name, value });
+        }
+    }
+    Ok(weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling_block::SchedulingBlock;
+    use crate::solution_space::SolutionSpace;
+    use crate::test_utils::{block_and_space, hz, iv, TestTask};
+    use qtty::Second;
+
+    fn problem() -> (SchedulingBlock<TestTask, Second>, SolutionSpace<Second>) {
+        block_and_space(&[
+            ("a", 10.0, 1, iv(0.0, 40.0)),
+            ("b", 10.0, 5, iv(0.0, 20.0)),
+            ("c", 10.0, 3, iv(10.0, 30.0)),
+        ])
+    }
+
+    #[test]
+    fn built_schedulers_match_direct_construction() {
+        let (block, space) = problem();
+        let blocks = [block];
+        let horizon = hz(0.0, 40.0);
+
+        let config = AlgorithmConfig::default();
+        assert_eq!(config.name(), "est");
+        let built = config
+            .build::<TestTask, Second, (), petgraph::Directed>()
+            .unwrap();
+        let direct = ESTScheduler::default().schedule(&blocks, &space, horizon);
+        let schedule = built.schedule(&blocks, &space, horizon);
+        assert_eq!(
+            schedule.iter().collect::<Vec<_>>(),
+            direct.iter().collect::<Vec<_>>()
+        );
+
+        let config = AlgorithmConfig::Greedy(GreedyConfig::default());
+        let built = config
+            .build::<TestTask, Second, (), petgraph::Directed>()
+            .unwrap();
+        let direct = GreedyScheduler::new().schedule(&blocks, &space, horizon);
+        let schedule = built.schedule(&blocks, &space, horizon);
+        assert_eq!(
+            schedule.iter().collect::<Vec<_>>(),
+            direct.iter().collect::<Vec<_>>()
+        );
+
+        let config = AlgorithmConfig::Beam(BeamConfig {
+            scorer: BeamScorer::Weighted(WeightedScore {
+                priority: f64::NAN,
+                urgency: 1.0,
+            }),
+            ..BeamConfig::default()
+        });
+        assert!(matches!(
+            config.build::<TestTask, Second, (), petgraph::Directed>(),
+            Err(AlgorithmConfigError::InvalidWeight {
+                name: "priority",
+                ..
+            })
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn configs_round_trip_through_json() {
+        let config: AlgorithmConfig =
+            serde_json::from_str(r#"{"algorithm": "beam", "depth": 3, "scorer": "flexibility"}"#)
+                .unwrap();
+        let AlgorithmConfig::Beam(beam) = &config else {
+            panic!("expected beam, got {config:?}");
+        };
+        assert_eq!(
+            *beam,
+            BeamConfig {
+                depth: 3,
+                scorer: BeamScorer::Flexibility,
+                ..BeamConfig::default()
+            }
+        );
+
+        for config in [
+            AlgorithmConfig::Est(EstConfig {
+                endangered_threshold: Quantity::new(4.0),
+                objective: Objective::ExpectedValue,
+            }),
+            AlgorithmConfig::Greedy(GreedyConfig {
+                objective: Objective::Nominal,
+                weights: WeightedScore {
+                    priority: 2.0,
+                    urgency: 0.5,
+                },
+            }),
+            AlgorithmConfig::Distilled(DistilledConfig {
+                scorer: DistilledScorer::linear([1.0, 0.0, 0.0, 0.0, 0.0, 0.0], 0.0),
+            }),
+        ] {
+            let json = serde_json::to_string(&config).unwrap();
+            assert!(json.contains(&format!(r#""algorithm":"{}""#, config.name())));
+            let back: AlgorithmConfig = serde_json::from_str(&json).unwrap();
+            assert_eq!(format!("{back:?}"), format!("{config:?}"));
+        }
+    }
+}
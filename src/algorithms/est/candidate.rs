@@ -1,5 +1,6 @@
 //! Task candidate with computed scheduling metrics.
 
+use super::metrics::{CandidateMetrics, TaskMetrics};
 use crate::scheduling_block::Task;
 use crate::solution_space::Interval;
 use crate::Id;
@@ -14,9 +15,7 @@ where
 {
     pub(crate) task: T,
     pub(crate) task_id: Id,
-    pub(crate) est: Option<Quantity<A>>,
-    pub(crate) deadline: Option<Quantity<A>>,
-    pub(crate) flexibility: Quantity<A>,
+    pub(crate) metrics: CandidateMetrics<A>,
 }
 
 impl<T, A> Candidate<T, A>
@@ -29,33 +28,30 @@ where
         Self {
             task,
             task_id: task_id.into(),
-            est: None,
-            deadline: None,
-            flexibility: Quantity::new(0.0),
+            metrics: CandidateMetrics::impossible(),
         }
     }
 
     /// Returns true if the task cannot be scheduled (no EST found).
     pub fn is_impossible(&self) -> bool {
-        self.est.is_none()
+        self.metrics.is_impossible()
     }
 
     /// Returns true if the task is endangered (low flexibility).
-    /// Uses strict less-than to match C++ core implementation.
-    pub fn is_endangered(&self, threshold: u32) -> bool {
-        !self.is_impossible() && self.flexibility.value() < threshold as f64
+    pub fn is_endangered(&self, threshold: Quantity<A>) -> bool {
+        self.metrics.is_endangered(threshold)
     }
 
     /// Returns true if the task is flexible (high flexibility).
-    /// Uses greater-than-or-equal to match C++ core implementation.
-    pub fn is_flexible(&self, threshold: u32) -> bool {
-        !self.is_impossible() && self.flexibility.value() >= threshold as f64
+    pub fn is_flexible(&self, threshold: Quantity<A>) -> bool {
+        self.metrics.is_flexible(threshold)
     }
 
     /// Get the scheduled interval for this candidate (in axis units).
     pub fn get_interval(&self) -> Option<Interval<A>> {
-        self.est
-            .map(|start| Interval::new(start, start + self.task.size_on_axis()))
+        self.metrics
+            .est
+            .map(|start| Interval::new(start, start + self.task.duration_at(start)))
     }
 
     /// Get task reference.
@@ -70,18 +66,26 @@ where
 
     /// Get earliest start time.
     pub fn est(&self) -> Option<Quantity<A>> {
-        self.est
+        self.metrics.est
     }
 
     /// Get deadline.
     #[allow(dead_code)]
     pub fn deadline(&self) -> Option<Quantity<A>> {
-        self.deadline
+        self.metrics.deadline
     }
 
     /// Get flexibility.
     pub fn flexibility(&self) -> Quantity<A> {
-        self.flexibility
+        self.metrics.flexibility
+    }
+
+    /// Snapshot of the current metrics, labelled with the task ID.
+    pub fn metrics(&self) -> TaskMetrics<A> {
+        TaskMetrics {
+            task_id: self.task_id.clone(),
+            metrics: self.metrics,
+        }
     }
 }
 
@@ -110,40 +114,40 @@ mod tests {
     #[test]
     fn not_impossible_with_est() {
         let mut c = Candidate::<TestTask, Second>::new(TestTask::new("t", 10.0), "t");
-        c.est = Some(Quantity::new(0.0));
+        c.metrics.est = Some(Quantity::new(0.0));
         assert!(!c.is_impossible());
     }
 
     #[test]
     fn is_endangered_low_flexibility() {
         let mut c = Candidate::<TestTask, Second>::new(TestTask::new("t", 10.0), "t");
-        c.est = Some(Quantity::new(0.0));
-        c.flexibility = Quantity::new(3.0);
-        assert!(c.is_endangered(5)); // 3 < 5
-        assert!(!c.is_flexible(5));
+        c.metrics.est = Some(Quantity::new(0.0));
+        c.metrics.flexibility = Quantity::new(3.0);
+        assert!(c.is_endangered(Quantity::new(5.0))); // 3 < 5
+        assert!(!c.is_flexible(Quantity::new(5.0)));
     }
 
     #[test]
     fn is_flexible_high_flexibility() {
         let mut c = Candidate::<TestTask, Second>::new(TestTask::new("t", 10.0), "t");
-        c.est = Some(Quantity::new(0.0));
-        c.flexibility = Quantity::new(10.0);
-        assert!(c.is_flexible(5)); // 10 >= 5
-        assert!(!c.is_endangered(5));
+        c.metrics.est = Some(Quantity::new(0.0));
+        c.metrics.flexibility = Quantity::new(10.0);
+        assert!(c.is_flexible(Quantity::new(5.0))); // 10 >= 5
+        assert!(!c.is_endangered(Quantity::new(5.0)));
     }
 
     #[test]
     fn impossible_is_neither_endangered_nor_flexible() {
         let c = Candidate::<TestTask, Second>::new(TestTask::new("t", 10.0), "t");
         // est is None → impossible
-        assert!(!c.is_endangered(5));
-        assert!(!c.is_flexible(5));
+        assert!(!c.is_endangered(Quantity::new(5.0)));
+        assert!(!c.is_flexible(Quantity::new(5.0)));
     }
 
     #[test]
     fn get_interval_returns_correct_interval() {
         let mut c = Candidate::<TestTask, Second>::new(TestTask::new("t", 10.0), "t");
-        c.est = Some(Quantity::new(5.0));
+        c.metrics.est = Some(Quantity::new(5.0));
         let interval = c.get_interval().unwrap();
         assert_eq!(interval.start().value(), 5.0);
         assert_eq!(interval.end().value(), 15.0); // 5 + 10
@@ -158,8 +162,8 @@ mod tests {
     #[test]
     fn est_and_deadline_accessors() {
         let mut c = Candidate::<TestTask, Second>::new(TestTask::new("t", 10.0), "t");
-        c.est = Some(Quantity::new(5.0));
-        c.deadline = Some(Quantity::new(90.0));
+        c.metrics.est = Some(Quantity::new(5.0));
+        c.metrics.deadline = Some(Quantity::new(90.0));
         assert_eq!(c.est().unwrap().value(), 5.0);
         assert_eq!(c.deadline().unwrap().value(), 90.0);
     }
@@ -167,10 +171,10 @@ mod tests {
     #[test]
     fn flexibility_at_threshold_boundary() {
         let mut c = Candidate::<TestTask, Second>::new(TestTask::new("t", 10.0), "t");
-        c.est = Some(Quantity::new(0.0));
-        c.flexibility = Quantity::new(5.0);
+        c.metrics.est = Some(Quantity::new(0.0));
+        c.metrics.flexibility = Quantity::new(5.0);
         // Exactly at threshold: >= 5 is flexible, < 5 is endangered
-        assert!(c.is_flexible(5));
-        assert!(!c.is_endangered(5));
+        assert!(c.is_flexible(Quantity::new(5.0)));
+        assert!(!c.is_endangered(Quantity::new(5.0)));
     }
 }
@@ -0,0 +1,215 @@
+//! Demand against capacity, before scheduling.
+//!
+//! [`ESTScheduler::capacity_report`] looks at an instance the way EST would
+//! and reports, without running the scheduling loop, which tasks cannot fit
+//! at all and where in the horizon the tasks that can fit ask for more time
+//! than there is.
+//!
+//! Each feasible task spreads its size evenly over the windows it fits in
+//! (within the horizon), so a bucket's demand is the time the tasks would
+//! take there if each were equally likely to run anywhere it fits. A bucket
+//! with more demand than length is oversubscribed: some of the tasks
+//! counting on it will have to run elsewhere or not at all. Tasks with a
+//! single tight window weigh fully on it, so a cluster of them shows up even
+//! when the horizon as a whole has room.
+
+use qtty::{Quantity, Unit};
+
+use super::metrics::compute_candidate_metrics;
+use super::ESTScheduler;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+use crate::Id;
+
+/// Demand and capacity over one stretch of the horizon.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CapacityBucket<U: Unit> {
+    pub interval: Interval<U>,
+    /// Expected time the feasible tasks take in the bucket.
+    pub demand: Quantity<U>,
+    /// Number of feasible tasks with a fitting window in the bucket.
+    pub tasks: usize,
+}
+
+impl<U: Unit> CapacityBucket<U> {
+    /// Demand over the bucket's length; above 1 when oversubscribed.
+    pub fn load(&self) -> f64 {
+        let length = self.interval.duration().value();
+        if length > 0.0 {
+            self.demand.value() / length
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns true if the demand exceeds the bucket's length.
+    pub fn is_oversubscribed(&self) -> bool {
+        self.demand.value() > self.interval.duration().value()
+    }
+}
+
+/// Result of [`ESTScheduler::capacity_report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapacityReport<U: Unit> {
+    /// Equal buckets covering the horizon, in time order.
+    pub buckets: Vec<CapacityBucket<U>>,
+    /// Tasks that fit in no window within the horizon, sorted. No scheduler
+    /// can place them.
+    pub infeasible: Vec<Id>,
+    /// Feasible tasks whose flexibility is below the scheduler's threshold.
+    pub endangered: usize,
+    /// Total size of the feasible tasks.
+    pub demand: Quantity<U>,
+    /// Length of the horizon.
+    pub capacity: Quantity<U>,
+}
+
+impl<U: Unit> CapacityReport<U> {
+    /// Returns true if the feasible tasks together need more time than the
+    /// horizon has, so some are bound to be left out.
+    pub fn is_oversubscribed(&self) -> bool {
+        self.demand.value() > self.capacity.value()
+    }
+
+    /// The oversubscribed buckets, consecutive ones merged.
+    pub fn oversubscribed_regions(&self) -> Vec<Interval<U>> {
+        let mut regions: Vec<Interval<U>> = Vec::new();
+        for bucket in self.buckets.iter().filter(|b| b.is_oversubscribed()) {
+            match regions.last_mut() {
+                Some(last) if last.end() == bucket.interval.start() => {
+                    *last = Interval::new(last.start(), bucket.interval.end());
+                }
+                _ => regions.push(bucket.interval),
+            }
+        }
+        regions
+    }
+}
+
+impl<U: Unit> ESTScheduler<U> {
+    /// Compares demand with capacity over `buckets` equal stretches of
+    /// `horizon`, without scheduling.
+    ///
+    /// A task is infeasible if EST finds no start for it within the horizon;
+    /// the others count towards the demand of the buckets their fitting
+    /// windows cross, see the [module documentation](crate::algorithms::est::capacity).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buckets` is zero.
+    pub fn capacity_report<T, D, E>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+        buckets: usize,
+    ) -> CapacityReport<U>
+    where
+        T: Task<U>,
+        E: petgraph::EdgeType,
+    {
+        assert!(buckets > 0, "a capacity report needs at least one bucket");
+        let mut report = CapacityReport {
+            buckets: Vec::new(),
+            infeasible: Vec::new(),
+            endangered: 0,
+            demand: Quantity::new(0.0),
+            capacity: horizon.duration(),
+        };
+        let (start, length) = (horizon.start().value(), horizon.duration().value());
+        report.buckets = (0..buckets)
+            .map(|i| {
+                let from = start + length * i as f64 / buckets as f64;
+                let to = if i + 1 == buckets {
+                    horizon.end().value()
+                } else {
+                    start + length * (i + 1) as f64 / buckets as f64
+                };
+                CapacityBucket {
+                    interval: Interval::new(Quantity::new(from), Quantity::new(to)),
+                    demand: Quantity::new(0.0),
+                    tasks: 0,
+                }
+            })
+            .collect();
+
+        for block in blocks {
+            for (id, task) in block.tasks() {
+                let metrics =
+                    compute_candidate_metrics(task, id, solution_space, horizon.interval());
+                if metrics.is_impossible() {
+                    report.infeasible.push(id.to_string());
+                    continue;
+                }
+                if metrics.flexibility.value() < self.endangered_threshold.value() {
+                    report.endangered += 1;
+                }
+                let size = task.size_on_axis().value();
+                report.demand = Quantity::new(report.demand.value() + size);
+
+                let fitting: Vec<Interval<U>> = solution_space
+                    .get_intervals(id)
+                    .map(|windows| {
+                        windows
+                            .iter()
+                            .filter_map(|w| w.intersection(&horizon.interval()))
+                            .filter(|w| w.duration().value() >= size)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let room: f64 = fitting.iter().map(|w| w.duration().value()).sum();
+                if room <= 0.0 {
+                    continue;
+                }
+                for bucket in &mut report.buckets {
+                    let shared: f64 = fitting
+                        .iter()
+                        .filter_map(|w| w.intersection(&bucket.interval))
+                        .map(|w| w.duration().value())
+                        .sum();
+                    if shared > 0.0 {
+                        bucket.demand = Quantity::new(bucket.demand.value() + size * shared / room);
+                        bucket.tasks += 1;
+                    }
+                }
+            }
+        }
+        report.infeasible.sort();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{block_and_space, hz, iv, q, TestTask};
+
+    #[test]
+    fn flags_tight_regions_and_hopeless_tasks() {
+        let (mut block, space) = block_and_space(&[
+            // Three tasks of 20 s squeezed into [0, 25).
+            ("a", 20.0, 0, iv(0.0, 25.0)),
+            ("b", 20.0, 0, iv(0.0, 25.0)),
+            ("c", 20.0, 0, iv(0.0, 25.0)),
+            // Free to run anywhere in the second half.
+            ("d", 10.0, 0, iv(50.0, 100.0)),
+            // Windows too short or outside the horizon.
+            ("short", 30.0, 0, iv(60.0, 80.0)),
+            ("late", 5.0, 0, iv(150.0, 200.0)),
+        ]);
+        block
+            .add_task_with_id(TestTask::new("nowhere", 1.0), Some("nowhere".into()))
+            .unwrap();
+
+        let report = ESTScheduler::new(q(2.0)).capacity_report(&[block], &space, hz(0.0, 100.0), 4);
+        assert_eq!(report.infeasible, ["late", "nowhere", "short"]);
+        assert_eq!(report.endangered, 3);
+        assert_eq!(report.demand.value(), 70.0);
+        assert!(!report.is_oversubscribed());
+
+        let loads: Vec<f64> = report.buckets.iter().map(CapacityBucket::load).collect();
+        assert_eq!(loads, [60.0 / 25.0, 0.0, 5.0 / 25.0, 5.0 / 25.0]);
+        assert_eq!(report.buckets[0].tasks, 3);
+        assert_eq!(report.oversubscribed_regions(), [iv(0.0, 25.0)]);
+    }
+}
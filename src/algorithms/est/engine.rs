@@ -1,69 +1,167 @@
 //! Core scheduling engine with candidate update and scheduling loop.
 
+use std::collections::{HashMap, HashSet};
+
+use crate::algorithms::Objective;
+use crate::constraints::{DynamicConstraint, DynamicConstraintIndex, SchedulingContext};
+use crate::schedule::errors::ScheduleError;
 use crate::schedule::Schedule;
 use crate::scheduling_block::Task;
-use crate::solution_space::{Interval, SolutionSpace};
-use qtty::Unit;
+use crate::solution_space::{Feasibility, Interval, SolutionSpace};
+use crate::Id;
+use qtty::{Quantity, Unit};
 
 use super::candidate::Candidate;
-use super::metrics::{compute_deadline, compute_est, compute_flexibility};
+use super::metrics::compute_candidate_metrics;
+use super::rejection::{Rejection, RejectionHook, RejectionReason};
 
 /// Updates candidate metrics and sorts them.
-pub fn update_candidates<T, U>(
+pub fn update_candidates<T, U, F>(
     candidates: &mut [Candidate<T, U>],
-    solution_space: &SolutionSpace<U>,
+    solution_space: &F,
     horizon: Interval<U>,
-    endangered_threshold: u32,
+    endangered_threshold: Quantity<U>,
+    objective: Objective,
 ) where
     T: Task<U>,
     U: Unit,
+    F: Feasibility<U> + ?Sized,
 {
-    // Update metrics for all candidates
-    for candidate in candidates.iter_mut() {
-        candidate.flexibility =
-            compute_flexibility(&candidate.task, &candidate.task_id, solution_space, horizon);
-        candidate.est = compute_est(&candidate.task, &candidate.task_id, solution_space, horizon);
-        candidate.deadline =
-            compute_deadline(&candidate.task, &candidate.task_id, solution_space, horizon);
-    }
+    alloc_phase!(Candidates, {
+        // Update metrics for all candidates
+        for candidate in candidates.iter_mut() {
+            refresh(candidate, solution_space, horizon);
+        }
+        sort_candidates(candidates, endangered_threshold, objective);
+    });
+}
+
+/// Like [`update_candidates`], recomputing metrics on the rayon thread pool.
+///
+/// Each candidate's metrics depend only on the candidate, and the sort key is
+/// total, so the result is identical to the serial path.
+#[cfg(feature = "parallel")]
+pub(crate) fn update_candidates_par<T, U, F>(
+    candidates: &mut [Candidate<T, U>],
+    solution_space: &F,
+    horizon: Interval<U>,
+    endangered_threshold: Quantity<U>,
+    objective: Objective,
+) where
+    T: Task<U>,
+    U: Unit + Send + Sync,
+    F: Feasibility<U> + Sync + ?Sized,
+{
+    use rayon::prelude::*;
+
+    // Metric computation is cheap per candidate; small chunks only add
+    // scheduling overhead.
+    const MIN_CHUNK: usize = 64;
+
+    alloc_phase!(Candidates, {
+        candidates
+            .par_iter_mut()
+            .with_min_len(MIN_CHUNK)
+            .for_each(|candidate| refresh(candidate, solution_space, horizon));
+        sort_candidates(candidates, endangered_threshold, objective);
+    });
+}
 
-    // Sort candidates
+/// Recomputes the metrics of `candidate` on `horizon`.
+fn refresh<T, U, F>(candidate: &mut Candidate<T, U>, solution_space: &F, horizon: Interval<U>)
+where
+    T: Task<U>,
+    U: Unit,
+    F: Feasibility<U> + ?Sized,
+{
+    candidate.metrics =
+        compute_candidate_metrics(&candidate.task, &candidate.task_id, solution_space, horizon);
+}
+
+/// Sorts candidates by [`priority_key`].
+fn sort_candidates<T, U>(
+    candidates: &mut [Candidate<T, U>],
+    endangered_threshold: Quantity<U>,
+    objective: Objective,
+) where
+    T: Task<U>,
+    U: Unit,
+{
     // Sort candidates using a total, deterministic key to avoid panics from
     // comparator inconsistencies when floating-point values (NaN) are present.
+    candidates.sort_by_key(|c| {
+        let value = c
+            .est()
+            .map_or(f64::NEG_INFINITY, |est| objective.task_value(c.task(), est));
+        priority_key(
+            c.task_id(),
+            value,
+            c.est(),
+            c.flexibility(),
+            endangered_threshold,
+        )
+    });
+}
+
+/// Total, deterministic candidate sort key; smaller sorts first.
+///
+/// Components: `(impossible, kind, est, -value, flexibility, task_id)`, where
+/// kind is endangered (0), flexible (1) or other (2), and value is the task's
+/// priority (or expected value, see [`Objective`]).
+pub(crate) type PriorityKey = (u8, u8, i128, i128, i128, Id);
+
+/// Builds the [`PriorityKey`] used to order candidates.
+///
+/// Floating-point values are mapped to a total order so that NaN metrics can
+/// never make the sort inconsistent.
+pub(crate) fn priority_key<U: Unit>(
+    task_id: &str,
+    value: f64,
+    est: Option<Quantity<U>>,
+    flexibility: Quantity<U>,
+    endangered_threshold: Quantity<U>,
+) -> PriorityKey {
     fn f64_to_ordered_i128(x: f64) -> i128 {
-        let u = x.to_bits() as i128;
-        // Map IEEE-754 bit pattern to lexicographically ordered integer
-        if (u as u128) >> 127 & 1 == 1 {
-            !u
+        let bits = x.to_bits();
+        // Map IEEE-754 bit pattern to lexicographically ordered integer:
+        // negatives have all bits flipped, positives get the sign bit set.
+        let ordered = if bits >> 63 == 1 {
+            !bits
         } else {
-            u ^ (1i128 << 63)
-        }
+            bits | (1u64 << 63)
+        };
+        ordered as i128
     }
 
-    candidates.sort_by_key(|c| {
-        // impossible last
-        let impossible_flag: u8 = if c.is_impossible() { 1 } else { 0 };
-        // kind: endangered (0), flexible (1), other (2)
-        let kind: u8 = if c.is_endangered(endangered_threshold) {
-            0
-        } else if c.is_flexible(endangered_threshold) {
-            1
-        } else {
-            2
-        };
-        // EST key (total order). Missing EST → large value to push later.
-        let est_key: i128 = c
-            .est()
-            .map(|q| f64_to_ordered_i128(q.value()))
-            .unwrap_or(i128::MAX / 4);
-        // priority: higher first → negate to sort ascending
-        let prio_key: i32 = -c.task().priority();
-        // flexibility key (total order)
-        let flex_key: i128 = f64_to_ordered_i128(c.flexibility().value());
-        // final tie-breaker: task id
-        let tid = c.task_id().to_string();
-        (impossible_flag, kind, est_key, prio_key, flex_key, tid)
-    });
+    // impossible last
+    let impossible = est.is_none();
+    let impossible_flag: u8 = if impossible { 1 } else { 0 };
+    // kind: endangered (0), flexible (1), other (2)
+    let threshold = endangered_threshold.value();
+    let kind: u8 = if !impossible && flexibility.value() < threshold {
+        0
+    } else if !impossible && flexibility.value() >= threshold {
+        1
+    } else {
+        2
+    };
+    // EST key (total order). Missing EST → large value to push later.
+    let est_key: i128 = est
+        .map(|q| f64_to_ordered_i128(q.value()))
+        .unwrap_or(i128::MAX / 4);
+    // value: higher first → negate to sort ascending
+    let value_key: i128 = f64_to_ordered_i128(-value);
+    // flexibility key (total order)
+    let flex_key: i128 = f64_to_ordered_i128(flexibility.value());
+    // final tie-breaker: task id
+    (
+        impossible_flag,
+        kind,
+        est_key,
+        value_key,
+        flex_key,
+        task_id.to_string(),
+    )
 }
 
 /// Checks if scheduling is done (no more schedulable tasks or cursor past horizon).
@@ -88,7 +186,7 @@ where
 #[cfg(test)]
 pub(crate) fn find_next_endangered_index<T, U>(
     candidates: &[Candidate<T, U>],
-    endangered_threshold: u32,
+    endangered_threshold: Quantity<U>,
 ) -> usize
 where
     T: Task<U>,
@@ -100,6 +198,93 @@ where
         .unwrap_or(candidates.len())
 }
 
+/// Computes where a candidate is placed, honoring its placement preference.
+///
+/// Falls back to the EST if the window cannot be resolved.
+fn placement_interval<T, U, F>(
+    candidate: &Candidate<T, U>,
+    solution_space: &F,
+    remaining_horizon: Interval<U>,
+) -> Option<Interval<U>>
+where
+    T: Task<U>,
+    U: Unit,
+    F: Feasibility<U> + ?Sized,
+{
+    let est = candidate.est()?;
+    anchor_in_est_window(
+        candidate.task(),
+        candidate.task_id(),
+        est,
+        solution_space,
+        remaining_horizon,
+    )
+    .or_else(|| candidate.get_interval())
+}
+
+/// Anchors a task inside the window that contains its EST.
+///
+/// Keeping the task in its EST window leaves the scheduling order unaffected;
+/// [`Task::placement_preference`] only picks the start within that window.
+/// Returns `None` if no window in `remaining_horizon` contains `est`.
+pub(crate) fn anchor_in_est_window<T, U, F>(
+    task: &T,
+    task_id: &str,
+    est: Quantity<U>,
+    solution_space: &F,
+    remaining_horizon: Interval<U>,
+) -> Option<Interval<U>>
+where
+    T: Task<U>,
+    U: Unit,
+    F: Feasibility<U> + ?Sized,
+{
+    let size = task.duration_at(est);
+    let start = solution_space.anchor(
+        task_id,
+        est,
+        size,
+        &task.placement_preference(),
+        remaining_horizon,
+    )?;
+    // The preference chose `start` for the duration at `est`; keep it only if
+    // the task still fits with its own duration there.
+    let duration = task.duration_at(start);
+    let fits = duration.value() == size.value()
+        || ((start + duration).value() <= remaining_horizon.end().value()
+            && solution_space.allows(task_id, start, duration));
+    Some(if fits {
+        Interval::new(start, start + duration)
+    } else {
+        Interval::new(est, est + size)
+    })
+}
+
+/// [`schedule_segment_with_hook`] without a rejection hook.
+#[cfg(test)]
+pub fn schedule_segment<T, U, F>(
+    schedule: &mut Schedule<U>,
+    candidates: Vec<Candidate<T, U>>,
+    solution_space: &F,
+    horizon: Interval<U>,
+    endangered_threshold: Quantity<U>,
+    objective: Objective,
+) where
+    T: Task<U>,
+    U: Unit,
+    F: Feasibility<U> + ?Sized,
+{
+    schedule_segment_with_hook(
+        schedule,
+        candidates,
+        solution_space,
+        horizon,
+        endangered_threshold,
+        objective,
+        &mut |_: &Rejection<U>| {},
+    );
+}
+
 /// Schedules a segment of the horizon.
 ///
 /// This is the main scheduling loop that repeatedly:
@@ -111,16 +296,114 @@ where
 /// Candidate metrics are recomputed on `[cursor, horizon.end]` at each iteration.
 /// This keeps EST/deadline/flexibility aligned with the already scheduled prefix,
 /// so candidates are not dropped due to stale EST values that overlap.
-pub fn schedule_segment<T, U>(
+///
+/// [Milestones](Task::is_milestone) take no time and are placed first, each
+/// at its earliest point in the whole horizon; they neither wait for nor
+/// advance the cursor. [Background tasks](Task::is_background) are placed
+/// the same way, at their earliest start, in the schedule's background lane.
+///
+/// Every candidate that leaves the loop unscheduled is reported to `hook`.
+/// Impossible candidates are reported (and removed) as soon as a metric
+/// update classifies them; since the remaining horizon only shrinks, they
+/// could never become schedulable again.
+pub fn schedule_segment_with_hook<T, U, F, H>(
     schedule: &mut Schedule<U>,
-    mut candidates: Vec<Candidate<T, U>>,
-    solution_space: &SolutionSpace<U>,
+    candidates: Vec<Candidate<T, U>>,
+    solution_space: &F,
     horizon: Interval<U>,
-    endangered_threshold: u32,
+    endangered_threshold: Quantity<U>,
+    objective: Objective,
+    hook: &mut H,
 ) where
     T: Task<U>,
     U: Unit,
+    F: Feasibility<U> + ?Sized,
+    H: RejectionHook<U> + ?Sized,
 {
+    run_segment(
+        schedule,
+        candidates,
+        solution_space,
+        horizon,
+        hook,
+        |candidates, remaining_horizon| {
+            update_candidates(
+                candidates,
+                solution_space,
+                remaining_horizon,
+                endangered_threshold,
+                objective,
+            )
+        },
+    );
+}
+
+/// Like [`schedule_segment_with_hook`], recomputing candidate metrics in
+/// parallel at each iteration (see [`update_candidates_par`]).
+#[cfg(feature = "parallel")]
+pub(crate) fn schedule_segment_par_with_hook<T, U, F, H>(
+    schedule: &mut Schedule<U>,
+    candidates: Vec<Candidate<T, U>>,
+    solution_space: &F,
+    horizon: Interval<U>,
+    endangered_threshold: Quantity<U>,
+    objective: Objective,
+    hook: &mut H,
+) where
+    T: Task<U>,
+    U: Unit + Send + Sync,
+    F: Feasibility<U> + Sync + ?Sized,
+    H: RejectionHook<U> + ?Sized,
+{
+    run_segment(
+        schedule,
+        candidates,
+        solution_space,
+        horizon,
+        hook,
+        |candidates, remaining_horizon| {
+            update_candidates_par(
+                candidates,
+                solution_space,
+                remaining_horizon,
+                endangered_threshold,
+                objective,
+            )
+        },
+    );
+}
+
+/// The scheduling loop, with `update` recomputing and sorting the remaining
+/// candidates on the remaining horizon.
+fn run_segment<T, U, F, H>(
+    schedule: &mut Schedule<U>,
+    candidates: Vec<Candidate<T, U>>,
+    solution_space: &F,
+    horizon: Interval<U>,
+    hook: &mut H,
+    mut update: impl FnMut(&mut [Candidate<T, U>], Interval<U>),
+) where
+    T: Task<U>,
+    U: Unit,
+    F: Feasibility<U> + ?Sized,
+    H: RejectionHook<U> + ?Sized,
+{
+    let (detached, mut candidates): (Vec<_>, Vec<_>) = candidates
+        .into_iter()
+        .partition(|c| c.task().is_milestone() || c.task().is_background());
+    for mut candidate in detached {
+        update(std::slice::from_mut(&mut candidate), horizon);
+        if candidate.is_impossible() {
+            reject(hook, &candidate, RejectionReason::Impossible);
+            continue;
+        }
+        match placement_interval(&candidate, solution_space, horizon) {
+            Some(interval)
+                if alloc_phase!(Build, place(schedule, &candidate, interval)).is_ok() => {}
+            _ => reject(hook, &candidate, RejectionReason::Dropped),
+        }
+    }
+
     // Initialize cursor at horizon start
     let mut cursor = horizon.start();
 
@@ -128,35 +411,207 @@ pub fn schedule_segment<T, U>(
         let remaining_horizon = Interval::new(cursor, horizon.end());
 
         // Recompute all remaining candidates against the current frontier.
-        update_candidates(
-            &mut candidates,
-            solution_space,
-            remaining_horizon,
-            endangered_threshold,
-        );
+        update(&mut candidates, remaining_horizon);
+
+        // Impossible candidates sort last.
+        let possible = candidates.partition_point(|c| !c.is_impossible());
+        for candidate in candidates.drain(possible..) {
+            reject(hook, &candidate, RejectionReason::Impossible);
+        }
 
         if is_done(&candidates, cursor, horizon) {
+            // The cursor reached the end of the horizon: nothing left fits.
+            for candidate in candidates.drain(..) {
+                reject(hook, &candidate, RejectionReason::Impossible);
+            }
             break;
         }
 
         let candidate = candidates.remove(0);
 
         // Schedule the task
-        if let Some(interval) = candidate.get_interval() {
-            if schedule.add(candidate.task_id(), interval).is_ok() {
-                // Advance cursor to the end of the scheduled task plus any
-                // required gap. Because intervals are half-open [start, end),
-                // the next task may begin exactly at `interval.end()` without
-                // overlapping — no epsilon offset is needed.
-                cursor = interval.end() + candidate.task().gap_after();
+        let placed = match placement_interval(&candidate, solution_space, remaining_horizon) {
+            Some(interval)
+                if alloc_phase!(Build, schedule.add(candidate.task_id(), interval)).is_ok() =>
+            {
+                Some(interval)
+            }
+            _ => None,
+        };
+        match placed {
+            // Advance cursor to the end of the scheduled task plus any
+            // required gap. Because intervals are half-open [start, end),
+            // the next task may begin exactly at `interval.end()` without
+            // overlapping — no epsilon offset is needed.
+            Some(interval) => cursor = interval.end() + candidate.task().gap_after(),
+            None => reject(hook, &candidate, RejectionReason::Dropped),
+        }
+    }
+}
+
+/// Like [`schedule_segment_with_hook`], honoring the dynamic constraints in
+/// `index` between tasks.
+///
+/// - A candidate waits until every task it references is placed or
+///   rejected. Its windows are then the static ones narrowed by its dynamic
+///   constraints, re-evaluated against the schedule at every iteration.
+/// - After a placement the cursor advances by the task's gap, but never past
+///   the shortest [`max_wait`](DynamicConstraint::max_wait) of the tasks
+///   referencing it, so a no-wait successor can still start right after it.
+/// - Milestones compete with the other candidates on the remaining horizon
+///   and do not advance the cursor.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn schedule_segment_dynamic<T, U, D, H>(
+    schedule: &mut Schedule<U>,
+    candidates: Vec<Candidate<T, U>>,
+    solution_space: &SolutionSpace<U>,
+    index: &DynamicConstraintIndex<'_, D>,
+    horizon: Interval<U>,
+    endangered_threshold: Quantity<U>,
+    objective: Objective,
+    hook: &mut H,
+) where
+    T: Task<U>,
+    U: Unit,
+    D: DynamicConstraint<U>,
+    H: RejectionHook<U> + ?Sized,
+{
+    let mut max_wait: HashMap<&str, Quantity<U>> = HashMap::new();
+    for (_, source, constraint) in index.iter() {
+        if let Some(wait) = constraint.max_wait() {
+            let shortest = max_wait.entry(source).or_insert(wait);
+            if wait < *shortest {
+                *shortest = wait;
             }
         }
     }
+    let mut effective = SolutionSpace::from_hashmap(
+        solution_space
+            .iter()
+            .map(|(id, windows)| (id.to_string(), windows.to_vec()))
+            .collect(),
+    );
+
+    let mut waiting = candidates;
+    let mut active: Vec<Candidate<T, U>> = Vec::new();
+    let mut cursor = horizon.start();
+    loop {
+        let pending: HashSet<Id> = waiting
+            .iter()
+            .chain(&active)
+            .map(|c| c.task_id().to_string())
+            .collect();
+        let (ready, blocked): (Vec<_>, Vec<_>) = waiting.into_iter().partition(|c| {
+            index
+                .get_edges(c.task_id())
+                .unwrap_or_default()
+                .iter()
+                .all(|(source, _)| !pending.contains(source))
+        });
+        waiting = blocked;
+        active.extend(ready);
+        if active.is_empty() {
+            // Only references in a cycle across blocks can leave tasks blocked.
+            for candidate in waiting.drain(..) {
+                reject(hook, &candidate, RejectionReason::Impossible);
+            }
+            break;
+        }
+
+        for candidate in &active {
+            let id = candidate.task_id();
+            if !index.has_constraints(id) {
+                continue;
+            }
+            let ctx = SchedulingContext::new(schedule, solution_space)
+                .with_task_size(candidate.task().size_on_axis());
+            let windows = solution_space
+                .get_intervals(id)
+                .cloned()
+                .unwrap_or_default();
+            let narrowed = index.compute_effective_intervals(id, &windows, horizon, &ctx);
+            effective.set_intervals(id, narrowed.into_inner());
+        }
+
+        let remaining_horizon = Interval::new(cursor, horizon.end());
+        update_candidates(
+            &mut active,
+            &effective,
+            remaining_horizon,
+            endangered_threshold,
+            objective,
+        );
+        let possible = active.partition_point(|c| !c.is_impossible());
+        for candidate in active.drain(possible..) {
+            reject(hook, &candidate, RejectionReason::Impossible);
+        }
+        if active.is_empty() {
+            // Rejections may have released waiting candidates.
+            continue;
+        }
+        if is_done(&active, cursor, horizon) {
+            for candidate in active.drain(..).chain(waiting.drain(..)) {
+                reject(hook, &candidate, RejectionReason::Impossible);
+            }
+            break;
+        }
+
+        let candidate = active.remove(0);
+        let placed = match placement_interval(&candidate, &effective, remaining_horizon) {
+            Some(interval)
+                if alloc_phase!(Build, place(schedule, &candidate, interval)).is_ok() =>
+            {
+                Some(interval)
+            }
+            _ => None,
+        };
+        match placed {
+            Some(_) if candidate.task().is_milestone() || candidate.task().is_background() => {}
+            Some(interval) => {
+                let gap = candidate.task().gap_after();
+                let wait = max_wait.get(candidate.task_id()).copied();
+                cursor = interval.end() + wait.filter(|w| *w < gap).unwrap_or(gap);
+            }
+            None => reject(hook, &candidate, RejectionReason::Dropped),
+        }
+    }
+}
+
+/// Adds `candidate` at `interval`, in the background lane if it is a
+/// [background task](Task::is_background).
+fn place<T, U>(
+    schedule: &mut Schedule<U>,
+    candidate: &Candidate<T, U>,
+    interval: Interval<U>,
+) -> Result<(), ScheduleError>
+where
+    T: Task<U>,
+    U: Unit,
+{
+    if candidate.task().is_background() {
+        schedule.add_background(candidate.task_id(), interval)
+    } else {
+        schedule.add(candidate.task_id(), interval)
+    }
+}
+
+fn reject<T, U, H>(hook: &mut H, candidate: &Candidate<T, U>, reason: RejectionReason)
+where
+    T: Task<U>,
+    U: Unit,
+    H: RejectionHook<U> + ?Sized,
+{
+    hook.on_rejection(&Rejection {
+        reason,
+        metrics: candidate.metrics(),
+    });
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scheduling_block::PlacementPreference;
+    use crate::solution_space::{CandidateStarts, SolutionSpace};
     use crate::test_utils::{iv, q, TestTask};
     use qtty::Second;
 
@@ -180,7 +635,7 @@ mod tests {
         let ss = make_space_for(&[("a", vec![iv(0.0, 100.0)]), ("b", vec![iv(50.0, 100.0)])]);
         let horizon = iv(0.0, 100.0);
 
-        update_candidates(&mut candidates, &ss, horizon, 5);
+        update_candidates(&mut candidates, &ss, horizon, q(5.0), Objective::Nominal);
 
         // After update, candidates should have EST set and be sorted
         assert!(candidates[0].est().is_some());
@@ -191,21 +646,76 @@ mod tests {
     fn update_candidates_sorts_by_priority() {
         let mut candidates = vec![make_candidate("low", 10.0), make_candidate("high", 10.0)];
         // Manually set different metrics to test sorting
-        candidates[0].est = Some(q(0.0));
-        candidates[0].flexibility = q(10.0);
-        candidates[1].est = Some(q(0.0));
-        candidates[1].flexibility = q(10.0);
+        candidates[0].metrics.est = Some(q(0.0));
+        candidates[0].metrics.flexibility = q(10.0);
+        candidates[1].metrics.est = Some(q(0.0));
+        candidates[1].metrics.flexibility = q(10.0);
 
         let ss = make_space_for(&[
             ("low", vec![iv(0.0, 100.0)]),
             ("high", vec![iv(0.0, 100.0)]),
         ]);
 
-        update_candidates(&mut candidates, &ss, iv(0.0, 100.0), 5);
+        update_candidates(
+            &mut candidates,
+            &ss,
+            iv(0.0, 100.0),
+            q(5.0),
+            Objective::Nominal,
+        );
         // Both have same EST/priority/flexibility, sorted by ID
         assert!(candidates[0].task_id() < candidates[1].task_id());
     }
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_segment_matches_serial() {
+        let tasks: Vec<(String, f64, i32)> = (0..500_usize)
+            .map(|i| (format!("t{i:03}"), 1.0 + (i % 7) as f64, (i % 5) as i32))
+            .collect();
+        let mut ss = SolutionSpace::new();
+        for (i, (id, _, _)) in tasks.iter().enumerate() {
+            let start = (i * 37 % 900) as f64;
+            ss.add_interval(id, iv(start, start + 40.0 + (i % 11) as f64));
+        }
+        let candidates = || -> Vec<Candidate<TestTask, Second>> {
+            tasks
+                .iter()
+                .map(|(id, size, p)| Candidate::new(TestTask::new(id, *size).with_priority(*p), id))
+                .collect()
+        };
+
+        let mut serial = Schedule::new();
+        let mut serial_rejected = Vec::new();
+        schedule_segment_with_hook(
+            &mut serial,
+            candidates(),
+            &ss,
+            iv(0.0, 1000.0),
+            q(2.0),
+            Objective::Nominal,
+            &mut |r: &Rejection<Second>| serial_rejected.push(r.metrics.task_id.clone()),
+        );
+        let mut parallel = Schedule::new();
+        let mut parallel_rejected = Vec::new();
+        schedule_segment_par_with_hook(
+            &mut parallel,
+            candidates(),
+            &ss,
+            iv(0.0, 1000.0),
+            q(2.0),
+            Objective::Nominal,
+            &mut |r: &Rejection<Second>| parallel_rejected.push(r.metrics.task_id.clone()),
+        );
+
+        assert!(serial.len() > 10);
+        assert_eq!(
+            parallel.iter().collect::<Vec<_>>(),
+            serial.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(parallel_rejected, serial_rejected);
+    }
+
     // ── is_done ───────────────────────────────────────────────────────
 
     #[test]
@@ -224,15 +734,15 @@ mod tests {
     #[test]
     fn is_done_cursor_past_horizon() {
         let mut candidates = vec![make_candidate("a", 10.0)];
-        candidates[0].est = Some(q(0.0));
+        candidates[0].metrics.est = Some(q(0.0));
         assert!(is_done(&candidates, q(100.0), iv(0.0, 100.0)));
     }
 
     #[test]
     fn is_done_not_done_yet() {
         let mut candidates = vec![make_candidate("a", 10.0)];
-        candidates[0].est = Some(q(0.0));
-        candidates[0].flexibility = q(5.0);
+        candidates[0].metrics.est = Some(q(0.0));
+        candidates[0].metrics.flexibility = q(5.0);
         assert!(!is_done(&candidates, q(0.0), iv(0.0, 100.0)));
     }
 
@@ -241,22 +751,22 @@ mod tests {
     #[test]
     fn find_next_endangered_all_flexible() {
         let mut candidates = vec![make_candidate("a", 10.0), make_candidate("b", 10.0)];
-        candidates[0].est = Some(q(0.0));
-        candidates[0].flexibility = q(10.0);
-        candidates[1].est = Some(q(5.0));
-        candidates[1].flexibility = q(10.0);
+        candidates[0].metrics.est = Some(q(0.0));
+        candidates[0].metrics.flexibility = q(10.0);
+        candidates[1].metrics.est = Some(q(5.0));
+        candidates[1].metrics.flexibility = q(10.0);
 
-        let index = find_next_endangered_index(&candidates, 5);
+        let index = find_next_endangered_index(&candidates, q(5.0));
         assert_eq!(index, 2); // None found → returns len()
     }
 
     #[test]
     fn find_next_endangered_first_is_endangered() {
         let mut candidates = vec![make_candidate("a", 10.0)];
-        candidates[0].est = Some(q(0.0));
-        candidates[0].flexibility = q(2.0); // < 5
+        candidates[0].metrics.est = Some(q(0.0));
+        candidates[0].metrics.flexibility = q(2.0); // < 5
 
-        let index = find_next_endangered_index(&candidates, 5);
+        let index = find_next_endangered_index(&candidates, q(5.0));
         assert_eq!(index, 0);
     }
 
@@ -268,7 +778,14 @@ mod tests {
         let candidates = vec![make_candidate("a", 10.0)];
         let ss = make_space_for(&[("a", vec![iv(0.0, 100.0)])]);
 
-        schedule_segment(&mut schedule, candidates, &ss, iv(0.0, 100.0), 5);
+        schedule_segment(
+            &mut schedule,
+            candidates,
+            &ss,
+            iv(0.0, 100.0),
+            q(5.0),
+            Objective::Nominal,
+        );
 
         assert_eq!(schedule.len(), 1);
         assert!(schedule.contains_task("a"));
@@ -283,7 +800,14 @@ mod tests {
         let candidates = vec![make_candidate("a", 10.0), make_candidate("b", 10.0)];
         let ss = make_space_for(&[("a", vec![iv(0.0, 100.0)]), ("b", vec![iv(0.0, 100.0)])]);
 
-        schedule_segment(&mut schedule, candidates, &ss, iv(0.0, 100.0), 5);
+        schedule_segment(
+            &mut schedule,
+            candidates,
+            &ss,
+            iv(0.0, 100.0),
+            q(5.0),
+            Objective::Nominal,
+        );
 
         assert_eq!(schedule.len(), 2);
         assert!(schedule.contains_task("a"));
@@ -296,8 +820,183 @@ mod tests {
         let candidates = vec![make_candidate("impossible", 200.0)]; // too big for any window
         let ss = make_space_for(&[("impossible", vec![iv(0.0, 50.0)])]);
 
-        schedule_segment(&mut schedule, candidates, &ss, iv(0.0, 50.0), 5);
+        schedule_segment(
+            &mut schedule,
+            candidates,
+            &ss,
+            iv(0.0, 50.0),
+            q(5.0),
+            Objective::Nominal,
+        );
 
         assert_eq!(schedule.len(), 0);
     }
+
+    // ── placement preferences ─────────────────────────────────────────
+
+    #[test]
+    fn schedule_segment_latest_stays_in_est_window() {
+        let mut schedule = Schedule::new();
+        let task = TestTask::new("a", 10.0).with_placement(PlacementPreference::Latest);
+        let candidates = vec![Candidate::new(task, "a")];
+        let ss = make_space_for(&[("a", vec![iv(20.0, 50.0), iv(60.0, 100.0)])]);
+
+        schedule_segment(
+            &mut schedule,
+            candidates,
+            &ss,
+            iv(0.0, 100.0),
+            q(5.0),
+            Objective::Nominal,
+        );
+
+        assert_eq!(schedule.get_interval("a"), Some(iv(40.0, 50.0)));
+    }
+
+    #[test]
+    fn schedule_segment_centered_task_advances_cursor() {
+        let mut schedule = Schedule::new();
+        let centered = TestTask::new("a", 10.0)
+            .with_priority(1)
+            .with_placement(PlacementPreference::CenteredOn(q(30.0)));
+        let candidates = vec![Candidate::new(centered, "a"), make_candidate("b", 10.0)];
+        let ss = make_space_for(&[("a", vec![iv(0.0, 100.0)]), ("b", vec![iv(0.0, 100.0)])]);
+
+        schedule_segment(
+            &mut schedule,
+            candidates,
+            &ss,
+            iv(0.0, 100.0),
+            q(5.0),
+            Objective::Nominal,
+        );
+
+        assert_eq!(schedule.get_interval("a"), Some(iv(25.0, 35.0)));
+        assert_eq!(schedule.get_interval("b"), Some(iv(35.0, 45.0)));
+    }
+
+    // ── objectives ────────────────────────────────────────────────────
+
+    #[test]
+    fn schedule_segment_expected_value_prefers_likely_success() {
+        let run = |objective| {
+            let mut schedule = Schedule::new();
+            let candidates = vec![
+                Candidate::new(
+                    TestTask::new("risky", 10.0)
+                        .with_priority(5)
+                        .with_success_probability(0.2),
+                    "risky",
+                ),
+                Candidate::new(TestTask::new("safe", 10.0).with_priority(2), "safe"),
+            ];
+            let ss = make_space_for(&[
+                ("risky", vec![iv(0.0, 100.0)]),
+                ("safe", vec![iv(0.0, 100.0)]),
+            ]);
+            schedule_segment(
+                &mut schedule,
+                candidates,
+                &ss,
+                iv(0.0, 100.0),
+                q(1.0),
+                objective,
+            );
+            schedule
+        };
+
+        let nominal = run(Objective::Nominal);
+        assert_eq!(nominal.get_interval("risky"), Some(iv(0.0, 10.0)));
+
+        let expected = run(Objective::ExpectedValue);
+        assert_eq!(expected.get_interval("safe"), Some(iv(0.0, 10.0)));
+    }
+
+    // ── candidate starts ──────────────────────────────────────────────
+
+    #[test]
+    fn schedule_segment_uses_discrete_starts() {
+        let mut schedule = Schedule::new();
+        let candidates = vec![
+            Candidate::new(
+                TestTask::new("a", 10.0).with_placement(PlacementPreference::Latest),
+                "a",
+            ),
+            make_candidate("b", 10.0),
+        ];
+        let mut starts = CandidateStarts::new();
+        starts.set_starts("a", vec![q(0.0), q(5.0), q(30.0)]);
+        starts.set_starts("b", vec![q(5.0), q(12.0)]);
+
+        // "b" is endangered (2 starts < 3) and goes first at 5; the cursor then
+        // passes 0 and 5, leaving 30 as the only start for "a".
+        schedule_segment(
+            &mut schedule,
+            candidates,
+            &starts,
+            iv(0.0, 100.0),
+            q(3.0),
+            Objective::Nominal,
+        );
+
+        assert_eq!(schedule.get_interval("b"), Some(iv(5.0, 15.0)));
+        assert_eq!(schedule.get_interval("a"), Some(iv(30.0, 40.0)));
+        assert!(schedule.infeasible_entries(&starts).is_empty());
+    }
+
+    #[test]
+    fn schedule_segment_dynamic_honors_max_wait() {
+        use crate::constraints::MaxWaitConstraint;
+        use crate::scheduling_block::SchedulingBlock;
+
+        let mut block: SchedulingBlock<TestTask, Second, MaxWaitConstraint<Second>> =
+            SchedulingBlock::new();
+        let mut add = |id: &str, task: TestTask| {
+            let node = block.add_task_with_id(task, Some(id.into())).unwrap();
+            block.node_of(&node).unwrap()
+        };
+        let acquire = add("acquire", TestTask::new("acquire", 10.0).with_delay(20.0));
+        let exposure = add("exposure", TestTask::new("exposure", 10.0));
+        add("filler", TestTask::new("filler", 10.0));
+        let never = add("never", TestTask::new("never", 10.0));
+        let orphan = add("orphan", TestTask::new("orphan", 10.0));
+        block
+            .add_dependency(acquire, exposure, MaxWaitConstraint::no_wait())
+            .unwrap();
+        block
+            .add_dependency(never, orphan, MaxWaitConstraint::new(q(50.0)))
+            .unwrap();
+        let blocks = [block];
+
+        let ss = make_space_for(&[
+            ("acquire", vec![iv(0.0, 100.0)]),
+            ("exposure", vec![iv(0.0, 100.0)]),
+            ("filler", vec![iv(0.0, 100.0)]),
+            ("orphan", vec![iv(0.0, 100.0)]),
+        ]);
+        let candidates = blocks[0]
+            .tasks()
+            .map(|(id, task)| Candidate::new(task.clone(), id))
+            .collect();
+        let mut schedule = Schedule::new();
+        let mut rejected = Vec::new();
+        schedule_segment_dynamic(
+            &mut schedule,
+            candidates,
+            &ss,
+            &DynamicConstraintIndex::from_blocks(&blocks),
+            iv(0.0, 100.0),
+            q(2.0),
+            Objective::Nominal,
+            &mut |r: &Rejection<Second>| rejected.push(r.metrics.task_id.clone()),
+        );
+
+        // The acquisition's 20 s gap would push the cursor past the exposure's
+        // only start; the no-wait edge caps it at the acquisition's end.
+        assert_eq!(schedule.get_interval("acquire"), Some(iv(0.0, 10.0)));
+        assert_eq!(schedule.get_interval("exposure"), Some(iv(10.0, 20.0)));
+        assert_eq!(schedule.get_interval("filler"), Some(iv(20.0, 30.0)));
+        rejected.sort();
+        assert_eq!(rejected, ["never", "orphan"]);
+    }
 }
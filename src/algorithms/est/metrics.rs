@@ -8,10 +8,82 @@
 //! During the scheduling loop, the horizon parameter remains unchanged even as tasks
 //! are scheduled. A separate cursor variable tracks scheduling progress, but metrics
 //! are always computed relative to the original full horizon.
-
+//!
+//! The functions are public so that reporting tools can obtain the same metrics
+//! the scheduler uses without running it. [`CandidateMetrics`] is what EST
+//! ranks a candidate by; [`TaskMetrics`] labels it with the task ID for
+//! reports and rejection hooks. [`compute_all`] computes all three
+//! metrics for a batch of tasks, looking up and clipping each task's windows once.
+//! [`cost_of_delay`] explains, for each task a schedule left out, what would
+//! have to change for it to fit. [`MetricsCache`] keeps metrics across
+//! solution-space edits, recomputing only the tasks whose entries changed.
+
+use crate::algorithms::greedy::find_earliest_non_overlapping;
+use crate::schedule::Schedule;
 use crate::scheduling_block::Task;
-use crate::solution_space::{Interval, SolutionSpace};
+use crate::solution_space::{Feasibility, Interval, SolutionSpace};
+use crate::Id;
 use qtty::{Quantity, Unit};
+use std::collections::HashMap;
+
+/// The metrics EST ranks a candidate by, on the remaining horizon.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CandidateMetrics<A: Unit> {
+    /// Earliest start time, or `None` if the task cannot fit.
+    pub est: Option<Quantity<A>>,
+    /// Latest possible start time, or `None` if the task cannot fit.
+    pub deadline: Option<Quantity<A>>,
+    /// Sum of `window_duration / task_duration` over the fitting windows.
+    pub flexibility: Quantity<A>,
+    /// Time the task could occupy: `flexibility × size`, with the size at the
+    /// EST. For a fixed size, the total length of the fitting windows.
+    pub remaining_capacity: Quantity<A>,
+}
+
+impl<A: Unit> CandidateMetrics<A> {
+    /// Metrics of a task that cannot fit, as before the first computation.
+    pub fn impossible() -> Self {
+        Self {
+            est: None,
+            deadline: None,
+            flexibility: Quantity::new(0.0),
+            remaining_capacity: Quantity::new(0.0),
+        }
+    }
+
+    /// Returns true if the task cannot be scheduled (no EST found).
+    pub fn is_impossible(&self) -> bool {
+        self.est.is_none()
+    }
+
+    /// Returns true if the task can fit but its flexibility is below
+    /// `threshold` (strict, as in the C++ core).
+    pub fn is_endangered(&self, threshold: Quantity<A>) -> bool {
+        !self.is_impossible() && self.flexibility.value() < threshold.value()
+    }
+
+    /// Returns true if the task can fit with flexibility of at least
+    /// `threshold`.
+    pub fn is_flexible(&self, threshold: Quantity<A>) -> bool {
+        !self.is_impossible() && self.flexibility.value() >= threshold.value()
+    }
+}
+
+/// [`CandidateMetrics`] of a single task, labelled with its ID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskMetrics<A: Unit> {
+    /// ID of the task the metrics belong to.
+    pub task_id: Id,
+    /// The task's metrics.
+    pub metrics: CandidateMetrics<A>,
+}
+
+impl<A: Unit> TaskMetrics<A> {
+    /// Returns true if the task cannot be scheduled (no EST found).
+    pub fn is_impossible(&self) -> bool {
+        self.metrics.is_impossible()
+    }
+}
 
 /// Finds the earliest start time for a task in the solution space.
 ///
@@ -29,7 +101,7 @@ use qtty::{Quantity, Unit};
 ///
 /// The earliest possible start time, or None if the task cannot fit.
 ///
-/// Note: Uses `task.size_on_axis()` to get the duration in axis units.
+/// Note: Uses [`Task::duration_at`] to get the duration in axis units.
 pub fn compute_est<T, A>(
     task: &T,
     task_id: &str,
@@ -40,30 +112,7 @@ where
     T: Task<A>,
     A: Unit,
 {
-    let intervals = solution_space.get_intervals(task_id)?;
-    let task_size = task.size_on_axis();
-
-    for interval in intervals {
-        // Skip windows that end before horizon begins
-        if interval.end().value() <= horizon.start().value() {
-            continue;
-        }
-
-        // Skip windows that start after horizon ends (intervals are sorted)
-        if interval.start().value() >= horizon.end().value() {
-            break;
-        }
-
-        // Compute intersection: window ∩ horizon
-        if let Some(intersection) = interval.intersection(&horizon) {
-            // Check if task fits within the effective window
-            if intersection.duration().value() >= task_size.value() {
-                return Some(intersection.start());
-            }
-        }
-    }
-
-    None
+    compute_candidate_metrics(task, task_id, solution_space, horizon).est
 }
 
 /// Finds the latest possible start time (deadline) for a task.
@@ -82,7 +131,7 @@ where
 ///
 /// The latest possible start time, or None if the task cannot fit.
 ///
-/// Note: Uses `task.size_on_axis()` to get the duration in axis units.
+/// Note: Uses [`Task::duration_at`] to get the duration in axis units.
 pub fn compute_deadline<T, A>(
     task: &T,
     task_id: &str,
@@ -93,31 +142,7 @@ where
     T: Task<A>,
     A: Unit,
 {
-    let intervals = solution_space.get_intervals(task_id)?;
-    let task_size = task.size_on_axis();
-
-    // Search backwards from the end
-    for interval in intervals.iter().rev() {
-        // Skip windows that begin after horizon ends
-        if interval.start().value() >= horizon.end().value() {
-            continue;
-        }
-
-        // Skip windows that end before horizon begins
-        if interval.end().value() <= horizon.start().value() {
-            break;
-        }
-
-        // Compute intersection: window ∩ horizon
-        if let Some(intersection) = interval.intersection(&horizon) {
-            // Check if task fits within the effective window
-            if intersection.duration().value() >= task_size.value() {
-                return Some(intersection.end() - task_size);
-            }
-        }
-    }
-
-    None
+    compute_candidate_metrics(task, task_id, solution_space, horizon).deadline
 }
 
 /// Computes task flexibility as the ratio of available time to task duration.
@@ -140,7 +165,7 @@ where
 ///
 /// The flexibility value (dimensionless ratio).
 ///
-/// Note: Uses `task.size_on_axis()` to get the duration in axis units.
+/// Note: Uses [`Task::duration_at`] to get the duration in axis units.
 pub fn compute_flexibility<T, A>(
     task: &T,
     task_id: &str,
@@ -151,46 +176,373 @@ where
     T: Task<A>,
     A: Unit,
 {
-    let intervals = solution_space.get_intervals(task_id);
-    if intervals.is_none() {
-        return Quantity::new(0.0);
+    compute_candidate_metrics(task, task_id, solution_space, horizon).flexibility
+}
+
+/// Computes EST, deadline and flexibility for a single task in one pass.
+///
+/// Equivalent to calling [`compute_est`], [`compute_deadline`] and
+/// [`compute_flexibility`] separately, but the task's windows are looked up
+/// and clipped to the horizon only once.
+///
+/// Accepts any [`Feasibility`]; with [`CandidateStarts`](crate::solution_space::CandidateStarts)
+/// the deadline is the last allowed start that fits and flexibility is the
+/// number of allowed starts.
+pub fn compute_metrics<T, A, F>(
+    task: &T,
+    task_id: &str,
+    feasibility: &F,
+    horizon: Interval<A>,
+) -> TaskMetrics<A>
+where
+    T: Task<A>,
+    A: Unit,
+    F: Feasibility<A> + ?Sized,
+{
+    TaskMetrics {
+        task_id: task_id.to_string(),
+        metrics: compute_candidate_metrics(task, task_id, feasibility, horizon),
     }
+}
 
-    let intervals = intervals.unwrap();
-    let task_size = task.size_on_axis();
-    let mut flexibility = 0.0;
+/// Like [`compute_metrics`], without labelling the result.
+pub fn compute_candidate_metrics<T, A, F>(
+    task: &T,
+    task_id: &str,
+    feasibility: &F,
+    horizon: Interval<A>,
+) -> CandidateMetrics<A>
+where
+    T: Task<A>,
+    A: Unit,
+    F: Feasibility<A> + ?Sized,
+{
+    let range = feasibility.start_range_with(task_id, &|start| task.duration_at(start), horizon);
+    let size = range
+        .earliest
+        .map_or_else(|| task.size_on_axis(), |est| task.duration_at(est));
+    CandidateMetrics {
+        est: range.earliest,
+        deadline: range.latest,
+        flexibility: Quantity::new(range.flexibility),
+        remaining_capacity: Quantity::new(range.flexibility * size.value()),
+    }
+}
 
-    for interval in intervals {
-        // Skip windows that end before horizon begins
-        if interval.end().value() <= horizon.start().value() {
-            continue;
-        }
+/// Computes [`TaskMetrics`] for every `(task_id, task)` pair.
+///
+/// Accepts anything yielding `(&str, &T)`, such as
+/// [`SchedulingBlock::tasks`](crate::scheduling_block::SchedulingBlock::tasks).
+/// Results are returned in input order.
+///
+/// # Example
+///
+/// ```no_run
+/// use virolai::algorithms::est::metrics::compute_all;
+/// # use qtty::Second;
+/// # use virolai::solution_space::{Horizon, Interval};
+/// # use virolai::synthetic::{generate, SyntheticConfig};
+/// # let horizon = Interval::<Second>::from_f64(0.0, 86_400.0);
+/// # let instance = generate(&SyntheticConfig::new(20, Horizon::new(horizon).unwrap()));
+/// # let block = instance.block;
+/// # let solution_space = instance.solution_space;
+///
+/// let report = compute_all(block.tasks(), &solution_space, horizon);
+/// for m in report.iter().filter(|m| m.is_impossible()) {
+///     println!("{} cannot be scheduled", m.task_id);
+/// }
+/// ```
+pub fn compute_all<'a, T, A, I, F>(
+    tasks: I,
+    feasibility: &F,
+    horizon: Interval<A>,
+) -> Vec<TaskMetrics<A>>
+where
+    I: IntoIterator<Item = (&'a str, &'a T)>,
+    T: Task<A> + 'a,
+    A: Unit,
+    F: Feasibility<A> + ?Sized,
+{
+    tasks
+        .into_iter()
+        .map(|(task_id, task)| compute_metrics(task, task_id, feasibility, horizon))
+        .collect()
+}
 
-        // Skip windows that start after horizon ends (intervals are sorted)
-        if interval.start().value() >= horizon.end().value() {
-            break;
+/// [`TaskMetrics`] cached per task for a fixed horizon.
+///
+/// A task's metrics depend only on its size, its solution-space entry and the
+/// horizon, so they stay valid until that entry changes. After editing the
+/// space (e.g. with [`SolutionSpace::update_task`]), call [`sync`](Self::sync)
+/// to drop exactly the entries the space reports dirty.
+///
+/// Changes to a task itself (such as its size) are not tracked; use
+/// [`invalidate`](Self::invalidate) for those.
+///
+/// # Example
+///
+/// ```no_run
+/// # use virolai::algorithms::est::metrics::MetricsCache;
+/// # use virolai::constraints::{ConstraintExpr, IntervalConstraint};
+/// # use qtty::Second;
+/// # use virolai::solution_space::{Horizon, Interval};
+/// # use virolai::synthetic::{generate, SyntheticConfig};
+/// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+/// # let instance = generate(&SyntheticConfig::new(20, horizon));
+/// # let blocks = vec![instance.block];
+/// # let space = instance.solution_space;
+/// # let block = &blocks[0];
+/// # let mut space = space;
+/// # let new_constraints = ConstraintExpr::leaf(IntervalConstraint::new(Interval::from_f64(0.0, 3_600.0)));
+/// let mut cache = MetricsCache::new(horizon.interval());
+/// for (id, task) in block.tasks() {
+///     cache.get_or_compute(task, id, &space);
+/// }
+///
+/// space.update_task("obs-42", &new_constraints, horizon);
+/// cache.sync(&mut space);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MetricsCache<A: Unit> {
+    horizon: Interval<A>,
+    entries: HashMap<Id, TaskMetrics<A>>,
+}
+
+impl<A: Unit> MetricsCache<A> {
+    /// Creates an empty cache for metrics computed against `horizon`.
+    pub fn new(horizon: Interval<A>) -> Self {
+        Self {
+            horizon,
+            entries: HashMap::new(),
         }
+    }
 
-        // Compute intersection: window ∩ horizon
-        if let Some(intersection) = interval.intersection(&horizon) {
-            let intersection_duration = intersection.duration().value();
-            let task_duration = task_size.value();
+    /// The horizon metrics are computed against.
+    pub fn horizon(&self) -> Interval<A> {
+        self.horizon
+    }
+
+    /// Number of cached tasks.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
 
-            // Check if task fits within the effective window
-            if task_duration <= intersection_duration {
-                flexibility += intersection_duration / task_duration;
-            }
+    /// Cached metrics of `task_id`, if any.
+    pub fn get(&self, task_id: &str) -> Option<&TaskMetrics<A>> {
+        self.entries.get(task_id)
+    }
+
+    /// Returns the cached metrics of `task_id`, computing them on a miss.
+    pub fn get_or_compute<T>(
+        &mut self,
+        task: &T,
+        task_id: &str,
+        solution_space: &SolutionSpace<A>,
+    ) -> &TaskMetrics<A>
+    where
+        T: Task<A>,
+    {
+        let horizon = self.horizon;
+        self.entries
+            .entry(task_id.to_string())
+            .or_insert_with(|| compute_metrics(task, task_id, solution_space, horizon))
+    }
+
+    /// Drops the cached metrics of `task_id`. Returns true if they were cached.
+    pub fn invalidate(&mut self, task_id: &str) -> bool {
+        self.entries.remove(task_id).is_some()
+    }
+
+    /// Drops the metrics of every entry `solution_space` reports dirty and
+    /// clears its dirty set.
+    ///
+    /// Returns the IDs whose cached metrics were dropped, sorted.
+    pub fn sync(&mut self, solution_space: &mut SolutionSpace<A>) -> Vec<Id> {
+        solution_space
+            .take_dirty()
+            .into_iter()
+            .filter(|id| self.invalidate(id))
+            .collect()
+    }
+
+    /// Drops all cached metrics.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Nominal vs expected value of a schedule.
+///
+/// A task's nominal value is its priority; its expected value is the priority
+/// weighted by [`Task::success_probability`] at its scheduled start.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ValueReport {
+    /// Sum of priorities of the scheduled tasks.
+    pub nominal_total: f64,
+    /// Sum of priority × success probability of the scheduled tasks.
+    pub expected_total: f64,
+    /// Sum of priorities of all tasks, scheduled or not.
+    pub nominal_available: f64,
+    /// Number of scheduled tasks.
+    pub scheduled: usize,
+}
+
+/// Computes the [`ValueReport`] of `schedule` over the given tasks.
+///
+/// Accepts anything yielding `(&str, &T)`, such as
+/// [`SchedulingBlock::tasks`](crate::scheduling_block::SchedulingBlock::tasks).
+/// Scheduled IDs that are not among `tasks` are ignored.
+pub fn value_report<'a, T, A, I>(tasks: I, schedule: &Schedule<A>) -> ValueReport
+where
+    I: IntoIterator<Item = (&'a str, &'a T)>,
+    T: Task<A> + 'a,
+    A: Unit,
+{
+    let mut report = ValueReport::default();
+    for (task_id, task) in tasks {
+        let priority = task.priority() as f64;
+        report.nominal_available += priority;
+        if let Some(interval) = schedule.get_interval(task_id) {
+            let probability = task.success_probability(interval.start()).clamp(0.0, 1.0);
+            report.nominal_total += priority;
+            report.expected_total += priority * probability;
+            report.scheduled += 1;
         }
     }
+    report
+}
 
-    Quantity::new(flexibility)
+/// The smallest changes that would let an unscheduled task in.
+///
+/// Each field describes one independent remedy; any of them alone is enough.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelayCost<A: Unit> {
+    /// ID of the unscheduled task.
+    pub task_id: Id,
+    /// Longest feasible window inside the horizon.
+    pub longest_window: Quantity<A>,
+    /// How much longer that window would have to be for the task to fit;
+    /// zero if some window is already long enough.
+    pub window_shortfall: Quantity<A>,
+    /// Scheduled tasks to remove so the task fits in its windows. Picks the
+    /// position with the fewest blockers (earliest on ties); empty when the
+    /// task has no window long enough.
+    pub blocking: Vec<Id>,
+    /// How far the horizon end must move for the task to fit, or `None` if
+    /// no window reaches beyond the horizon.
+    pub horizon_extension: Option<Quantity<A>>,
+}
+
+/// Explains every task of `tasks` missing from `schedule`, in input order.
+///
+/// Accepts anything yielding `(&str, &T)`, such as
+/// [`SchedulingBlock::tasks`](crate::scheduling_block::SchedulingBlock::tasks).
+pub fn cost_of_delay<'a, T, A, I>(
+    tasks: I,
+    solution_space: &SolutionSpace<A>,
+    schedule: &Schedule<A>,
+    horizon: Interval<A>,
+) -> Vec<DelayCost<A>>
+where
+    I: IntoIterator<Item = (&'a str, &'a T)>,
+    T: Task<A> + 'a,
+    A: Unit,
+{
+    tasks
+        .into_iter()
+        .filter(|(task_id, _)| !schedule.contains_task(task_id))
+        .map(|(task_id, task)| delay_cost(task, task_id, solution_space, schedule, horizon))
+        .collect()
+}
+
+fn delay_cost<T, A>(
+    task: &T,
+    task_id: &str,
+    solution_space: &SolutionSpace<A>,
+    schedule: &Schedule<A>,
+    horizon: Interval<A>,
+) -> DelayCost<A>
+where
+    T: Task<A>,
+    A: Unit,
+{
+    let size = task.size_on_axis().value();
+    let windows: &[Interval<A>] = solution_space
+        .get_intervals(task_id)
+        .map_or(&[], |set| set.as_slice());
+    let clipped: Vec<Interval<A>> = windows
+        .iter()
+        .filter_map(|w| w.intersection(&horizon))
+        .collect();
+
+    let longest = clipped
+        .iter()
+        .map(|w| w.duration().value())
+        .fold(0.0, f64::max);
+
+    let blocking = clipped
+        .iter()
+        .filter(|w| w.duration().value() >= size)
+        .flat_map(|w| {
+            // Optimal positions start at the window start or right after a
+            // scheduled task inside the window.
+            let ends = schedule
+                .intervals()
+                .map(|iv| iv.end().value())
+                .filter(|&e| e > w.start().value() && e + size <= w.end().value());
+            std::iter::once(w.start().value()).chain(ends)
+        })
+        .map(|start| {
+            let slot = Interval::new(Quantity::new(start), Quantity::new(start + size));
+            let blockers: Vec<Id> = schedule
+                .conflicts_vec(slot)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect();
+            (blockers.len(), start, blockers)
+        })
+        .min_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+        .map(|(_, _, blockers)| blockers)
+        .unwrap_or_default();
+
+    let horizon_end = horizon.end().value();
+    let horizon_extension = windows
+        .iter()
+        .filter(|w| w.end().value() > horizon_end)
+        .filter_map(|w| {
+            let bounds = [(w.start().value(), w.end().value())];
+            find_earliest_non_overlapping(
+                &bounds,
+                size,
+                horizon.start().value(),
+                w.end().value(),
+                schedule,
+            )
+        })
+        .map(|start| (start + size - horizon_end).max(0.0))
+        .min_by(f64::total_cmp)
+        .map(Quantity::new);
+
+    DelayCost {
+        task_id: task_id.to_string(),
+        longest_window: Quantity::new(longest),
+        window_shortfall: Quantity::new((size - longest).max(0.0)),
+        blocking,
+        horizon_extension,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::solution_space::SolutionSpace;
-    use crate::test_utils::{iv, q, TestTask};
+    use crate::test_utils::{hz, iv, q, TestTask};
     use qtty::Second;
 
     fn make_space(id: &str, intervals: Vec<Interval<Second>>) -> SolutionSpace<Second> {
@@ -330,4 +682,176 @@ mod tests {
         // Intersection = [50, 80], duration = 30, 30/10 = 3.0
         assert!((flex.value() - 3.0).abs() < 1e-9);
     }
+
+    // ── compute_metrics / compute_all ─────────────────────────────────
+
+    #[test]
+    fn metrics_match_individual_functions() {
+        let task = TestTask::new("t", 10.0);
+        let ss = make_space("t", vec![iv(0.0, 5.0), iv(20.0, 50.0), iv(60.0, 100.0)]);
+        let horizon = iv(25.0, 90.0);
+
+        let m = compute_metrics(&task, "t", &ss, horizon);
+        assert_eq!(m.task_id, "t");
+        assert_eq!(m.metrics.est, compute_est(&task, "t", &ss, horizon));
+        assert_eq!(
+            m.metrics.deadline,
+            compute_deadline(&task, "t", &ss, horizon)
+        );
+        assert_eq!(
+            m.metrics.flexibility.value(),
+            compute_flexibility(&task, "t", &ss, horizon).value()
+        );
+    }
+
+    #[test]
+    fn metrics_unknown_task_is_impossible() {
+        let task = TestTask::new("t", 10.0);
+        let ss = SolutionSpace::<Second>::new();
+        let m = compute_metrics(&task, "t", &ss, iv(0.0, 100.0));
+        assert!(m.is_impossible());
+        assert_eq!(m.metrics.deadline, None);
+        assert_eq!(m.metrics.flexibility.value(), 0.0);
+    }
+
+    #[test]
+    fn candidate_metrics_carry_remaining_capacity() {
+        let task = TestTask::new("t", 10.0);
+        let ss = make_space("t", vec![iv(0.0, 5.0), iv(20.0, 50.0), iv(60.0, 100.0)]);
+        let horizon = iv(25.0, 90.0);
+
+        let m = compute_candidate_metrics(&task, "t", &ss, horizon);
+        // [25, 50) and [60, 90) fit; [0, 5) is outside the horizon.
+        assert_eq!(m.remaining_capacity.value(), 55.0);
+        assert_eq!(m.flexibility.value(), 5.5);
+        assert!(m.is_flexible(q(5.0)) && !m.is_endangered(q(5.0)));
+        assert!(m.is_endangered(q(6.0)));
+        assert_eq!(compute_metrics(&task, "t", &ss, horizon).metrics, m);
+
+        let none = CandidateMetrics::<Second>::impossible();
+        assert!(none.is_impossible() && !none.is_endangered(q(5.0)) && !none.is_flexible(q(0.0)));
+    }
+
+    #[test]
+    fn compute_all_preserves_input_order() {
+        let a = TestTask::new("a", 10.0);
+        let b = TestTask::new("b", 60.0);
+        let mut ss = make_space("a", vec![iv(40.0, 100.0)]);
+        ss.set_intervals("b".to_string(), vec![iv(0.0, 50.0)]);
+
+        let report = compute_all([("b", &b), ("a", &a)], &ss, iv(0.0, 100.0));
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].task_id, "b");
+        assert!(report[0].is_impossible());
+        assert_eq!(report[1].task_id, "a");
+        assert_eq!(report[1].metrics.est, Some(q(40.0)));
+        assert_eq!(report[1].metrics.deadline, Some(q(90.0)));
+    }
+
+    // ── MetricsCache ──────────────────────────────────────────────────
+
+    #[test]
+    fn cache_recomputes_only_dirty_tasks() {
+        use crate::constraints::{ConstraintExpr, IntervalConstraint};
+
+        let a = TestTask::new("a", 10.0);
+        let b = TestTask::new("b", 10.0);
+        let horizon = hz(0.0, 100.0);
+        let mut ss = make_space("a", vec![iv(0.0, 50.0)]);
+        ss.set_intervals("b".to_string(), vec![iv(20.0, 60.0)]);
+        ss.take_dirty();
+
+        let mut cache = MetricsCache::new(horizon.interval());
+        assert_eq!(cache.get_or_compute(&a, "a", &ss).metrics.est, Some(q(0.0)));
+        assert_eq!(
+            cache.get_or_compute(&b, "b", &ss).metrics.est,
+            Some(q(20.0))
+        );
+        assert!(cache.sync(&mut ss).is_empty());
+
+        let moved = ConstraintExpr::leaf(IntervalConstraint::new(iv(30.0, 80.0)));
+        assert!(ss.update_task("a", &moved, horizon));
+        assert!(!ss.update_task("a", &moved, horizon));
+        assert_eq!(cache.sync(&mut ss), vec!["a".to_string()]);
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.len(), 1);
+
+        let m = cache.get_or_compute(&a, "a", &ss);
+        assert_eq!(m.metrics.est, Some(q(30.0)));
+        assert_eq!(m.metrics.deadline, Some(q(70.0)));
+        assert_eq!(cache.get("b").unwrap().metrics.est, Some(q(20.0)));
+    }
+
+    // ── value_report ──────────────────────────────────────────────────
+
+    #[test]
+    fn value_report_weights_by_success_probability() {
+        let a = TestTask::new("a", 10.0)
+            .with_priority(4)
+            .with_success_probability(0.5);
+        let b = TestTask::new("b", 10.0).with_priority(2);
+        let c = TestTask::new("c", 10.0).with_priority(3);
+        let mut schedule = Schedule::new();
+        schedule.add("a", iv(0.0, 10.0)).unwrap();
+        schedule.add("b", iv(10.0, 20.0)).unwrap();
+
+        let report = value_report([("a", &a), ("b", &b), ("c", &c)], &schedule);
+        assert_eq!(report.scheduled, 2);
+        assert_eq!(report.nominal_total, 6.0);
+        assert_eq!(report.expected_total, 4.0);
+        assert_eq!(report.nominal_available, 9.0);
+    }
+
+    // ── cost_of_delay ─────────────────────────────────────────────────
+
+    #[test]
+    fn cost_of_delay_skips_scheduled_tasks() {
+        let a = TestTask::new("a", 10.0);
+        let ss = make_space("a", vec![iv(0.0, 100.0)]);
+        let mut schedule = Schedule::new();
+        schedule.add("a", iv(0.0, 10.0)).unwrap();
+        assert!(cost_of_delay([("a", &a)], &ss, &schedule, iv(0.0, 100.0)).is_empty());
+    }
+
+    #[test]
+    fn cost_of_delay_reports_window_shortfall() {
+        let t = TestTask::new("t", 30.0);
+        let ss = make_space("t", vec![iv(0.0, 20.0), iv(50.0, 75.0)]);
+        let report = cost_of_delay([("t", &t)], &ss, &Schedule::new(), iv(0.0, 100.0));
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].longest_window, q(25.0));
+        assert_eq!(report[0].window_shortfall, q(5.0));
+        assert!(report[0].blocking.is_empty());
+        assert_eq!(report[0].horizon_extension, None);
+    }
+
+    #[test]
+    fn cost_of_delay_picks_fewest_blockers() {
+        let t = TestTask::new("t", 10.0);
+        let ss = make_space("t", vec![iv(0.0, 40.0)]);
+        let mut schedule = Schedule::new();
+        schedule.add("x", iv(0.0, 8.0)).unwrap();
+        schedule.add("y", iv(8.0, 16.0)).unwrap();
+        schedule.add("z", iv(20.0, 40.0)).unwrap();
+
+        let report = cost_of_delay([("t", &t)], &ss, &schedule, iv(0.0, 100.0));
+        assert_eq!(report[0].window_shortfall, q(0.0));
+        // Starting right after "x" (or "y") collides with a single task;
+        // the earlier position wins.
+        assert_eq!(report[0].blocking, vec!["y".to_string()]);
+    }
+
+    #[test]
+    fn cost_of_delay_horizon_extension() {
+        let t = TestTask::new("t", 20.0);
+        let ss = make_space("t", vec![iv(80.0, 200.0)]);
+        let mut schedule = Schedule::new();
+        schedule.add("x", iv(80.0, 95.0)).unwrap();
+
+        let report = cost_of_delay([("t", &t)], &ss, &schedule, iv(0.0, 100.0));
+        assert_eq!(report[0].longest_window, q(20.0));
+        assert_eq!(report[0].window_shortfall, q(0.0));
+        assert_eq!(report[0].blocking, vec!["x".to_string()]);
+        assert_eq!(report[0].horizon_extension, Some(q(15.0)));
+    }
 }
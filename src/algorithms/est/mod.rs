@@ -34,6 +34,10 @@
 //!   2. Advance cursor by: `task.end() + task.gap_after()`
 //!   3. Re-compute candidate metrics on the remaining horizon `[cursor, end]`
 //! - Continue until no schedulable tasks remain or cursor exceeds horizon
+//! - [`schedule_dynamic`](ESTScheduler::schedule_dynamic) also honors dynamic
+//!   constraints: a task waits until its reference tasks are settled, and the
+//!   cursor never moves past a predecessor's end plus its successors'
+//!   [`max_wait`](crate::constraints::DynamicConstraint::max_wait)
 //!
 //! ## 4. Task Gaps
 //!
@@ -44,83 +48,268 @@
 //! # Module Structure
 //!
 //! - [`candidate`] - Task candidate with computed metrics
-//! - [`metrics`] - Metric computation functions (EST, deadline, flexibility)
+//! - [`capacity`] - Demand against capacity before scheduling
+//! - [`metrics`] - Metric computation functions (EST, deadline, flexibility), public for reporting
 //! - [`ordering`] - Candidate comparison and priority logic
 //! - [`engine`] - Core scheduling loop and candidate updates
+//! - [`rejection`] - Callbacks for tasks EST gives up on
+//! - [`segmented`] - Per-segment runs with carry-over (e.g. one per night)
 
 mod candidate;
-mod engine;
-mod metrics;
+pub mod capacity;
+pub(crate) mod engine;
+pub mod metrics;
 mod ordering;
+#[cfg(test)]
+mod parity;
+pub mod rejection;
+pub mod segmented;
 
+use crate::algorithms::Objective;
+use crate::constraints::{DynamicConstraint, DynamicConstraintIndex};
 use crate::schedule::Schedule;
 use crate::scheduling_block::{SchedulingBlock, Task};
-use crate::solution_space::Interval;
 use crate::solution_space::SolutionSpace;
-use qtty::Unit;
+use crate::solution_space::{Feasibility, Horizon};
+use qtty::{Quantity, Second, Unit};
 
 use candidate::Candidate;
-use engine::schedule_segment;
+use engine::schedule_segment_with_hook;
+use rejection::{Rejection, RejectionHook};
 
 /// Early Starting Time scheduler.
-pub struct ESTScheduler {
-    endangered_threshold: u32,
+pub struct ESTScheduler<U: Unit = Second> {
+    endangered_threshold: Quantity<U>,
+    objective: Objective,
 }
 
-impl ESTScheduler {
+impl<U: Unit> ESTScheduler<U> {
     /// Creates a new EST scheduler with the given endangered threshold.
     ///
     /// # Arguments
     ///
-    /// * `endangered_threshold` - Tasks with flexibility <= this value are considered endangered
-    pub fn new(endangered_threshold: u32) -> Self {
+    /// * `endangered_threshold` - Tasks with flexibility below this value are considered endangered
+    pub fn new(endangered_threshold: Quantity<U>) -> Self {
         Self {
             endangered_threshold,
+            objective: Objective::Nominal,
         }
     }
+
+    /// Returns the endangered threshold.
+    pub fn endangered_threshold(&self) -> Quantity<U> {
+        self.endangered_threshold
+    }
+
+    /// Sets what the scheduler maximizes when ranking candidates.
+    ///
+    /// With [`Objective::ExpectedValue`], candidates of the same kind and EST
+    /// are ranked by `priority × success_probability(est)` instead of priority.
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
 }
 
-impl Default for ESTScheduler {
+impl<U: Unit> Default for ESTScheduler<U> {
     /// Creates a new EST scheduler with default threshold of 1.
     fn default() -> Self {
-        Self::new(1)
+        Self::new(Quantity::new(1.0))
     }
 }
 
-impl<T, U, D, E> crate::algorithms::SchedulingAlgorithm<T, U, D, E> for ESTScheduler
-where
-    T: Task<U> + Clone,
-    U: Unit,
-    E: petgraph::EdgeType,
-{
-    fn schedule(
+impl<U: Unit> ESTScheduler<U> {
+    /// Schedules like [`schedule`](crate::algorithms::SchedulingAlgorithm::schedule),
+    /// reading feasible starts from any [`Feasibility`] instead of a
+    /// [`SolutionSpace`].
+    ///
+    /// With [`CandidateStarts`](crate::solution_space::CandidateStarts), every
+    /// task is placed exactly at one of its allowed starts.
+    pub fn schedule_with_feasibility<T, D, E, F>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        feasibility: &F,
+        horizon: Horizon<U>,
+    ) -> Schedule<U>
+    where
+        T: Task<U> + Clone,
+        E: petgraph::EdgeType,
+        F: Feasibility<U> + ?Sized,
+    {
+        self.schedule_with_hook(blocks, feasibility, horizon, &mut |_: &Rejection<U>| {})
+    }
+
+    /// Schedules like [`schedule`](crate::algorithms::SchedulingAlgorithm::schedule),
+    /// reporting every task EST rejects to `hook` as soon as it is rejected.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use qtty::Quantity;
+    /// # use virolai::algorithms::est::rejection::Rejection;
+    /// # use virolai::algorithms::ESTScheduler;
+    /// # use qtty::Second;
+    /// # use virolai::solution_space::{Horizon, Interval};
+    /// # use virolai::synthetic::{generate, SyntheticConfig};
+    /// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+    /// # let instance = generate(&SyntheticConfig::new(20, horizon));
+    /// # let blocks = vec![instance.block];
+    /// # let space = instance.solution_space;
+    /// # let solution_space = space;
+    /// let mut rejected = Vec::new();
+    /// let schedule = ESTScheduler::new(Quantity::new(1.0)).schedule_with_hook(
+    ///     &blocks,
+    ///     &solution_space,
+    ///     horizon,
+    ///     &mut |r: &Rejection<Second>| rejected.push((r.metrics.task_id.clone(), r.reason)),
+    /// );
+    /// ```
+    ///
+    /// `solution_space` may be any [`Feasibility`], such as a
+    /// [`CandidateStarts`](crate::solution_space::CandidateStarts) list.
+    pub fn schedule_with_hook<T, D, E, F, H>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &F,
+        horizon: Horizon<U>,
+        hook: &mut H,
+    ) -> Schedule<U>
+    where
+        T: Task<U> + Clone,
+        E: petgraph::EdgeType,
+        F: Feasibility<U> + ?Sized,
+        H: RejectionHook<U> + ?Sized,
+    {
+        let mut schedule = Schedule::new();
+        schedule_segment_with_hook(
+            &mut schedule,
+            candidates(blocks),
+            solution_space,
+            horizon.interval(),
+            self.endangered_threshold,
+            self.objective,
+            hook,
+        );
+        schedule
+    }
+
+    /// Schedules like [`schedule`](crate::algorithms::SchedulingAlgorithm::schedule),
+    /// also honoring the dynamic constraints on the blocks' edges.
+    ///
+    /// A task is considered once every task it references is placed or
+    /// rejected, within its windows narrowed by those constraints. Tasks
+    /// whose references are never placed are rejected.
+    pub fn schedule_dynamic<T, D, E>(
         &self,
         blocks: &[SchedulingBlock<T, U, D, E>],
         solution_space: &SolutionSpace<U>,
-        horizon: Interval<U>,
-    ) -> Schedule<U> {
+        horizon: Horizon<U>,
+    ) -> Schedule<U>
+    where
+        T: Task<U> + Clone,
+        D: DynamicConstraint<U>,
+        E: petgraph::EdgeType,
+    {
+        self.schedule_dynamic_with_hook(blocks, solution_space, horizon, &mut |_: &Rejection<U>| {})
+    }
+
+    /// Like [`schedule_dynamic`](Self::schedule_dynamic), reporting every
+    /// task EST rejects to `hook`.
+    pub fn schedule_dynamic_with_hook<T, D, E, H>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+        hook: &mut H,
+    ) -> Schedule<U>
+    where
+        T: Task<U> + Clone,
+        D: DynamicConstraint<U>,
+        E: petgraph::EdgeType,
+        H: RejectionHook<U> + ?Sized,
+    {
+        let mut schedule = Schedule::new();
+        engine::schedule_segment_dynamic(
+            &mut schedule,
+            candidates(blocks),
+            solution_space,
+            &DynamicConstraintIndex::from_blocks(blocks),
+            horizon.interval(),
+            self.endangered_threshold,
+            self.objective,
+            hook,
+        );
+        schedule
+    }
+
+    /// Like [`schedule_with_feasibility`](Self::schedule_with_feasibility),
+    /// but recomputes candidate metrics on the rayon thread pool at every
+    /// iteration.
+    ///
+    /// The metric update dominates the run time on large instances (it is
+    /// quadratic in the number of tasks overall) and each candidate is
+    /// independent, so this pays off from a few thousand tasks on. The
+    /// schedule is identical to the serial one regardless of thread count.
+    #[cfg(feature = "parallel")]
+    pub fn schedule_par<T, D, E, F>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        feasibility: &F,
+        horizon: Horizon<U>,
+    ) -> Schedule<U>
+    where
+        T: Task<U> + Clone,
+        U: Send + Sync,
+        E: petgraph::EdgeType,
+        F: Feasibility<U> + Sync + ?Sized,
+    {
         let mut schedule = Schedule::new();
+        engine::schedule_segment_par_with_hook(
+            &mut schedule,
+            candidates(blocks),
+            feasibility,
+            horizon.interval(),
+            self.endangered_threshold,
+            self.objective,
+            &mut |_: &Rejection<U>| {},
+        );
+        schedule
+    }
+}
 
-        // Collect all tasks from all blocks
-        let candidates: Vec<Candidate<T, U>> = blocks
+/// One candidate per task of every block.
+fn candidates<T, U, D, E>(blocks: &[SchedulingBlock<T, U, D, E>]) -> Vec<Candidate<T, U>>
+where
+    T: Task<U> + Clone,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    alloc_phase!(
+        Population,
+        blocks
             .iter()
             .flat_map(|block| {
                 block
                     .tasks()
                     .map(|(id, task)| Candidate::new(task.clone(), id))
             })
-            .collect();
-
-        // Schedule
-        schedule_segment(
-            &mut schedule,
-            candidates,
-            solution_space,
-            horizon,
-            self.endangered_threshold,
-        );
+            .collect()
+    )
+}
 
-        schedule
+impl<T, U, D, E> crate::algorithms::SchedulingAlgorithm<T, U, D, E> for ESTScheduler<U>
+where
+    T: Task<U> + Clone,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    fn schedule(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+    ) -> Schedule<U> {
+        self.schedule_with_hook(blocks, solution_space, horizon, &mut |_: &Rejection<U>| {})
     }
 }
 
@@ -128,6 +317,8 @@ where
 mod tests {
     use super::*;
     use crate::constraints::IntervalConstraint;
+    use crate::solution_space::Interval;
+    use crate::test_utils::{hz, q};
     use engine::find_next_endangered_index;
     use qtty::Second;
 
@@ -189,12 +380,12 @@ mod tests {
         let mut candidate = Candidate::new(task, "boundary");
 
         // Set EST so candidate is not impossible
-        candidate.est = Some(qtty::Quantity::new(0.0));
+        candidate.metrics.est = Some(qtty::Quantity::new(0.0));
 
-        let threshold = 5;
+        let threshold = qtty::Quantity::new(5.0);
 
         // flexibility = 4.9 < 5 → endangered
-        candidate.flexibility = qtty::Quantity::new(4.9);
+        candidate.metrics.flexibility = qtty::Quantity::new(4.9);
         assert!(
             candidate.is_endangered(threshold),
             "4.9 < 5 should be endangered"
@@ -205,7 +396,7 @@ mod tests {
         );
 
         // flexibility = 5.0 >= 5 → flexible (boundary case)
-        candidate.flexibility = qtty::Quantity::new(5.0);
+        candidate.metrics.flexibility = qtty::Quantity::new(5.0);
         assert!(
             !candidate.is_endangered(threshold),
             "5.0 >= 5 should not be endangered"
@@ -216,7 +407,7 @@ mod tests {
         );
 
         // flexibility = 5.1 >= 5 → flexible
-        candidate.flexibility = qtty::Quantity::new(5.1);
+        candidate.metrics.flexibility = qtty::Quantity::new(5.1);
         assert!(
             !candidate.is_endangered(threshold),
             "5.1 >= 5 should not be endangered"
@@ -227,8 +418,8 @@ mod tests {
         );
 
         // flexibility < 1.0 with no EST → impossible
-        candidate.flexibility = qtty::Quantity::new(0.5);
-        candidate.est = None;
+        candidate.metrics.flexibility = qtty::Quantity::new(0.5);
+        candidate.metrics.est = None;
         assert!(candidate.is_impossible(), "No EST means impossible");
     }
 
@@ -318,7 +509,8 @@ mod tests {
         // Both tasks share one long window. Without cursor-aware recomputation,
         // both get EST=0 and the second task is dropped due to overlap.
 
-        let horizon = Interval::new(qtty::Quantity::new(0.0), qtty::Quantity::new(100.0));
+        let horizon =
+            Horizon::from_bounds(qtty::Quantity::new(0.0), qtty::Quantity::new(100.0)).unwrap();
 
         let mut block1: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
         let task1_id = block1.add_task(task1);
@@ -341,7 +533,7 @@ mod tests {
             )],
         );
 
-        let scheduler = ESTScheduler::new(5);
+        let scheduler = ESTScheduler::new(q(5.0));
         let schedule = scheduler.schedule(&[block1, block2], &solution_space, horizon);
 
         assert_eq!(schedule.len(), 2, "Both tasks should be scheduled");
@@ -391,24 +583,242 @@ mod tests {
         ];
 
         // Set EST for all candidates so they're not impossible
-        candidates[0].est = Some(qtty::Quantity::new(0.0));
-        candidates[1].est = Some(qtty::Quantity::new(10.0));
-        candidates[2].est = Some(qtty::Quantity::new(20.0));
+        candidates[0].metrics.est = Some(qtty::Quantity::new(0.0));
+        candidates[1].metrics.est = Some(qtty::Quantity::new(10.0));
+        candidates[2].metrics.est = Some(qtty::Quantity::new(20.0));
 
         // Set flexibility values
-        candidates[0].flexibility = qtty::Quantity::new(10.0); // Flexible
-        candidates[1].flexibility = qtty::Quantity::new(2.0); // Endangered (< 5)
-        candidates[2].flexibility = qtty::Quantity::new(8.0); // Flexible
+        candidates[0].metrics.flexibility = qtty::Quantity::new(10.0); // Flexible
+        candidates[1].metrics.flexibility = qtty::Quantity::new(2.0); // Endangered (< 5)
+        candidates[2].metrics.flexibility = qtty::Quantity::new(8.0); // Flexible
 
-        let threshold = 5;
+        let threshold = q(5.0);
 
         // Find next endangered - should be at index 1
         let next_endangered = find_next_endangered_index(&candidates, threshold);
         assert_eq!(next_endangered, 1, "Next endangered should be at index 1");
 
         // If no endangered tasks, should return len()
-        candidates[1].flexibility = qtty::Quantity::new(10.0); // Make all flexible
+        candidates[1].metrics.flexibility = qtty::Quantity::new(10.0); // Make all flexible
         let next_endangered = find_next_endangered_index(&candidates, threshold);
         assert_eq!(next_endangered, 3, "No endangered should return len()");
     }
+
+    #[test]
+    fn schedule_with_hook_reports_rejections() {
+        use crate::algorithms::SchedulingAlgorithm;
+        use crate::solution_space::Interval;
+        use rejection::RejectionReason;
+
+        let task = |name: &str, size: f64| TestTask {
+            name: name.to_string(),
+            size: qtty::Quantity::new(size),
+            priority: 0,
+            delay: qtty::Quantity::new(0.0),
+        };
+        let mut block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
+        let mut ss = SolutionSpace::new();
+        for (name, size, end) in [
+            ("fits", 10.0, 50.0),
+            ("huge", 200.0, 50.0),
+            ("late", 10.0, 15.0),
+        ] {
+            let id = block
+                .add_task_with_id(task(name, size), Some(name.to_string()))
+                .unwrap();
+            ss.add_interval(id, Interval::from_f64(0.0, end));
+        }
+        let horizon = hz(0.0, 100.0);
+
+        let mut rejected = Vec::new();
+        let scheduler = ESTScheduler::new(q(1.0));
+        let schedule =
+            scheduler.schedule_with_hook(&[block.clone()], &ss, horizon, &mut |r: &Rejection<
+                Second,
+            >| {
+                rejected.push((r.metrics.task_id.clone(), r.reason))
+            });
+
+        // "late" (flexibility 1.5) goes before "fits" (5.0); "fits" still fits after it.
+        assert_eq!(schedule.len(), 2);
+        assert_eq!(
+            rejected,
+            vec![("huge".to_string(), RejectionReason::Impossible)]
+        );
+        assert_eq!(
+            scheduler.schedule(&[block], &ss, horizon).len(),
+            schedule.len()
+        );
+    }
+
+    #[test]
+    fn rejection_carries_metric_snapshot() {
+        use crate::solution_space::Interval;
+
+        let mut block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
+        let mut ss = SolutionSpace::new();
+        for name in ["a", "b"] {
+            let id = block
+                .add_task_with_id(
+                    TestTask {
+                        name: name.to_string(),
+                        size: qtty::Quantity::new(10.0),
+                        priority: 0,
+                        delay: qtty::Quantity::new(0.0),
+                    },
+                    Some(name.to_string()),
+                )
+                .unwrap();
+            ss.add_interval(id, Interval::from_f64(0.0, 15.0));
+        }
+
+        let mut rejected = Vec::new();
+        let schedule = ESTScheduler::new(q(1.0)).schedule_with_hook(
+            &[block],
+            &ss,
+            hz(0.0, 100.0),
+            &mut |r: &Rejection<Second>| rejected.push(r.clone()),
+        );
+
+        assert_eq!(schedule.len(), 1);
+        assert_eq!(rejected.len(), 1);
+        let r = &rejected[0];
+        assert_eq!(r.metrics.task_id, "b");
+        assert!(r.metrics.is_impossible());
+    }
+
+    /// An exposure taking 40 s at t = 0 and 1 s less per second after, down
+    /// to 10 s from t = 30 on; `start + duration` never goes back.
+    #[derive(Debug, Clone)]
+    struct Exposure {
+        name: String,
+        shrinking: bool,
+        priority: i32,
+    }
+
+    impl Task<Second> for Exposure {
+        type SizeUnit = Second;
+        type ConstraintLeaf = IntervalConstraint<Second>;
+
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn size(&self) -> qtty::Quantity<Second> {
+            qtty::Quantity::new(if self.shrinking { 10.0 } else { 5.0 })
+        }
+
+        fn priority(&self) -> i32 {
+            self.priority
+        }
+
+        fn duration_at(&self, start: qtty::Quantity<Second>) -> qtty::Quantity<Second> {
+            if self.shrinking {
+                qtty::Quantity::new((40.0 - start.value()).max(10.0))
+            } else {
+                self.size()
+            }
+        }
+    }
+
+    #[test]
+    fn durations_follow_the_start() {
+        use crate::algorithms::SchedulingAlgorithm;
+
+        let mut block = SchedulingBlock::<Exposure, Second>::new();
+        let mut ss = SolutionSpace::new();
+        for (name, shrinking, priority, window) in [
+            ("calib", false, 9, (0.0, 100.0)),
+            ("deep", true, 0, (0.0, 100.0)),
+            ("short_night", true, 0, (0.0, 30.0)),
+        ] {
+            let task = Exposure {
+                name: name.to_string(),
+                shrinking,
+                priority,
+            };
+            let id = block
+                .add_task_with_id(task, Some(name.to_string()))
+                .unwrap();
+            ss.add_interval(id, Interval::from_f64(window.0, window.1));
+        }
+
+        let schedule =
+            ESTScheduler::new(q(1.0)).schedule(std::slice::from_ref(&block), &ss, hz(0.0, 100.0));
+
+        // "deep" starts after "calib" and takes 40 - 5 s; "short_night"
+        // would need 40 s at t = 0 and never ends before t = 40.
+        assert_eq!(
+            schedule.get_interval("deep"),
+            Some(Interval::from_f64(5.0, 40.0))
+        );
+        assert!(!schedule.contains_task("short_night"));
+        assert!(schedule.mismatched_durations(block.tasks()).is_empty());
+
+        let mut edited = Schedule::new();
+        edited.add("deep", Interval::from_f64(50.0, 90.0)).unwrap();
+        assert_eq!(edited.mismatched_durations(block.tasks()), vec!["deep"]);
+    }
+
+    #[test]
+    fn milestones_take_points_without_blocking() {
+        use crate::algorithms::SchedulingAlgorithm;
+        use crate::test_utils::{block_and_space, iv};
+
+        let (block, ss) = block_and_space(&[
+            ("a", 10.0, 0, iv(0.0, 30.0)),
+            ("calibrated", 0.0, 0, iv(5.0, 30.0)),
+            ("b", 10.0, 0, iv(0.0, 30.0)),
+            ("night_end", 0.0, 0, iv(30.0, 40.0)),
+        ]);
+
+        let schedule = ESTScheduler::new(q(2.0)).schedule(&[block], &ss, hz(0.0, 40.0));
+        let placed: Vec<_> = schedule.iter().collect();
+        assert_eq!(
+            placed,
+            [
+                ("a".to_string(), iv(0.0, 10.0)),
+                ("calibrated".to_string(), iv(5.0, 5.0)),
+                ("b".to_string(), iv(10.0, 20.0)),
+                ("night_end".to_string(), iv(30.0, 30.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn background_tasks_run_alongside() {
+        use crate::algorithms::{GreedyScheduler, SchedulingAlgorithm};
+        use crate::test_utils::{iv, TestTask};
+
+        let mut block = SchedulingBlock::<TestTask, Second>::new();
+        let mut ss = SolutionSpace::new();
+        for (id, task, window) in [
+            ("a", TestTask::new("a", 10.0), iv(0.0, 30.0)),
+            (
+                "monitor",
+                TestTask::new("monitor", 25.0).in_background(),
+                iv(5.0, 40.0),
+            ),
+            ("b", TestTask::new("b", 10.0), iv(0.0, 30.0)),
+            (
+                "too_long",
+                TestTask::new("too_long", 50.0).in_background(),
+                iv(0.0, 40.0),
+            ),
+        ] {
+            block.add_task_with_id(task, Some(id.to_string())).unwrap();
+            ss.add_interval(id, window);
+        }
+        let blocks = [block];
+
+        let est = ESTScheduler::new(q(2.0)).schedule(&blocks, &ss, hz(0.0, 40.0));
+        let greedy = GreedyScheduler::new().schedule(&blocks, &ss, hz(0.0, 40.0));
+        for schedule in [est, greedy] {
+            let primary: Vec<_> = schedule.iter().map(|(id, _)| id).collect();
+            assert_eq!(primary, ["a", "b"]);
+            assert_eq!(schedule.get_interval("b"), Some(iv(10.0, 20.0)));
+            let background: Vec<_> = schedule.background().collect();
+            assert_eq!(background, [("monitor".to_string(), iv(5.0, 30.0))]);
+        }
+    }
 }
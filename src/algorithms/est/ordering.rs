@@ -1,7 +1,7 @@
 //! Candidate comparison and ordering logic.
 
 use crate::scheduling_block::Task;
-use qtty::Unit;
+use qtty::{Quantity, Unit};
 use std::cmp::Ordering;
 
 use super::candidate::Candidate;
@@ -69,7 +69,7 @@ where
 
         // Check if scheduling flexible first would block endangered
         // Account for inter-task delay between flexible and endangered
-        let flexible_end = est_f + flexible.task().size_on_axis();
+        let flexible_end = est_f + flexible.task().duration_at(est_f);
         let required_gap = endangered.task().compute_gap_after(flexible.task());
         let endangered_start_after_flexible = flexible_end + required_gap;
 
@@ -88,7 +88,7 @@ where
 pub fn compare_candidates<T, U>(
     a: &Candidate<T, U>,
     b: &Candidate<T, U>,
-    endangered_threshold: u32,
+    endangered_threshold: Quantity<U>,
 ) -> Ordering
 where
     T: Task<U>,
@@ -122,7 +122,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::TestTask;
+    use crate::test_utils::{q, TestTask};
     use qtty::Second;
     use std::cmp::Ordering;
 
@@ -139,9 +139,9 @@ mod tests {
             .with_priority(priority)
             .with_delay(delay);
         let mut c = Candidate::new(task, id);
-        c.est = est.map(qtty::Quantity::new);
-        c.deadline = deadline.map(qtty::Quantity::new);
-        c.flexibility = qtty::Quantity::new(flexibility);
+        c.metrics.est = est.map(qtty::Quantity::new);
+        c.metrics.deadline = deadline.map(qtty::Quantity::new);
+        c.metrics.flexibility = qtty::Quantity::new(flexibility);
         c
     }
 
@@ -243,11 +243,11 @@ mod tests {
         let possible = make_candidate("a", 10.0, 0, Some(0.0), None, 5.0, 0.0);
         let impossible = make_candidate("b", 10.0, 0, None, None, 0.0, 0.0);
         assert_eq!(
-            compare_candidates(&possible, &impossible, 5),
+            compare_candidates(&possible, &impossible, q(5.0)),
             Ordering::Less
         );
         assert_eq!(
-            compare_candidates(&impossible, &possible, 5),
+            compare_candidates(&impossible, &possible, q(5.0)),
             Ordering::Greater
         );
     }
@@ -256,7 +256,7 @@ mod tests {
     fn two_impossible_ordered_by_id() {
         let a = make_candidate("aaa", 10.0, 0, None, None, 0.0, 0.0);
         let b = make_candidate("zzz", 10.0, 0, None, None, 0.0, 0.0);
-        assert_eq!(compare_candidates(&a, &b, 5), Ordering::Less);
+        assert_eq!(compare_candidates(&a, &b, q(5.0)), Ordering::Less);
     }
 
     #[test]
@@ -264,7 +264,7 @@ mod tests {
         let endangered = make_candidate("e", 10.0, 0, Some(0.0), Some(50.0), 2.0, 0.0);
         let flexible = make_candidate("f", 10.0, 0, Some(20.0), None, 10.0, 0.0);
         // est_e = 0 <= est_f = 20, so endangered goes first
-        let result = compare_candidates(&endangered, &flexible, 5);
+        let result = compare_candidates(&endangered, &flexible, q(5.0));
         assert_eq!(result, Ordering::Less);
     }
 
@@ -273,7 +273,7 @@ mod tests {
         let a = make_candidate("a", 10.0, 0, Some(0.0), None, 10.0, 0.0);
         let b = make_candidate("b", 10.0, 0, Some(5.0), None, 10.0, 0.0);
         // Both flexible, same kind → earlier EST first
-        assert_eq!(compare_candidates(&a, &b, 5), Ordering::Less);
+        assert_eq!(compare_candidates(&a, &b, q(5.0)), Ordering::Less);
     }
 
     #[test]
@@ -281,6 +281,6 @@ mod tests {
         let a = make_candidate("a", 10.0, 0, Some(0.0), Some(20.0), 2.0, 0.0);
         let b = make_candidate("b", 10.0, 0, Some(5.0), Some(25.0), 3.0, 0.0);
         // Both endangered, same kind → earlier EST first
-        assert_eq!(compare_candidates(&a, &b, 5), Ordering::Less);
+        assert_eq!(compare_candidates(&a, &b, q(5.0)), Ordering::Less);
     }
 }
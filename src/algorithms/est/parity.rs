@@ -0,0 +1,162 @@
+//! Golden-file harness for the EST scheduler.
+//!
+//! Replays every golden scenario in `golden/` (format in `golden/README.md`)
+//! and requires the schedule to match the expected one bit for bit, in start
+//! order. A failure names the scenario and the first diverging placement.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::ESTScheduler;
+use crate::algorithms::SchedulingAlgorithm;
+use crate::scheduling_block::SchedulingBlock;
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+use crate::test_utils::{iv, q, TestTask};
+
+/// A placement, compared on the bits of its bounds.
+#[derive(PartialEq)]
+struct Placement {
+    id: String,
+    start: u64,
+    end: u64,
+}
+
+impl Placement {
+    fn new(id: &str, interval: Interval<qtty::Second>) -> Self {
+        Self {
+            id: id.to_string(),
+            start: interval.start().value().to_bits(),
+            end: interval.end().value().to_bits(),
+        }
+    }
+}
+
+impl fmt::Debug for Placement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (start, end) = (f64::from_bits(self.start), f64::from_bits(self.end));
+        write!(f, "{} [{start:?}, {end:?})", self.id)
+    }
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/algorithms/est/golden")
+}
+
+fn number(value: &Value, what: &str) -> f64 {
+    value
+        .as_f64()
+        .unwrap_or_else(|| panic!("{what} is not a number: {value}"))
+}
+
+fn span(value: &Value, what: &str) -> Interval<qtty::Second> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([start, end]) => iv(number(start, what), number(end, what)),
+        _ => panic!("{what} is not a [start, end] pair: {value}"),
+    }
+}
+
+/// What a scenario produced next to what it expects.
+struct Replay {
+    placements: Vec<Placement>,
+    expected: Vec<Placement>,
+    unscheduled: Vec<String>,
+    expected_unscheduled: Vec<String>,
+}
+
+/// Runs the scenario in `path`.
+fn replay(path: &Path) -> Replay {
+    let text = fs::read_to_string(path).expect("readable scenario");
+    let scenario: Value = serde_json::from_str(&text).expect("valid JSON");
+    let threshold = q(scenario["threshold"].as_f64().expect("threshold"));
+    let horizon = Horizon::new(span(&scenario["horizon"], "horizon")).expect("non-empty horizon");
+
+    let mut block = SchedulingBlock::<TestTask, qtty::Second>::new();
+    let mut space = SolutionSpace::new();
+    let mut ids = Vec::new();
+    for task in scenario["tasks"].as_array().expect("tasks") {
+        let id = task["id"].as_str().expect("task id");
+        let mut test_task = TestTask::new(id, number(&task["size"], "size"))
+            .with_priority(task["priority"].as_i64().unwrap_or(0) as i32);
+        if let Some(gap) = task.get("gap_after") {
+            test_task = test_task.with_delay(number(gap, "gap_after"));
+        }
+        for window in task["windows"].as_array().expect("windows") {
+            space.add_interval(id, span(window, "window"));
+        }
+        block
+            .add_task_with_id(test_task, Some(id.to_string()))
+            .expect("unique task IDs");
+        ids.push(id.to_string());
+    }
+
+    let schedule = ESTScheduler::new(threshold).schedule(&[block], &space, horizon);
+    let placements: Vec<_> = schedule
+        .iter()
+        .map(|(id, interval)| Placement::new(&id, interval))
+        .collect();
+    let expected: Vec<_> = scenario["expected"]
+        .as_array()
+        .expect("expected")
+        .iter()
+        .map(|p| {
+            let interval = iv(number(&p["start"], "start"), number(&p["end"], "end"));
+            Placement::new(p["id"].as_str().expect("placement id"), interval)
+        })
+        .collect();
+
+    let mut unscheduled: Vec<_> = ids
+        .into_iter()
+        .filter(|id| !schedule.contains_task(id))
+        .collect();
+    unscheduled.sort();
+    let mut expected_unscheduled: Vec<_> = scenario
+        .get("unscheduled")
+        .and_then(Value::as_array)
+        .map(|ids| {
+            ids.iter()
+                .map(|id| id.as_str().expect("unscheduled id").to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    expected_unscheduled.sort();
+
+    Replay {
+        placements,
+        expected,
+        unscheduled,
+        expected_unscheduled,
+    }
+}
+
+#[test]
+fn est_matches_golden_scenarios() {
+    let mut paths: Vec<_> = fs::read_dir(golden_dir())
+        .expect("golden directory")
+        .map(|entry| entry.expect("directory entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no golden scenarios found");
+
+    for path in &paths {
+        let name = path.file_name().unwrap().to_string_lossy();
+        let run = replay(path);
+        let (actual, expected) = (&run.placements, &run.expected);
+        if let Some(i) =
+            (0..actual.len().max(expected.len())).find(|&i| actual.get(i) != expected.get(i))
+        {
+            panic!(
+                "{name}: placement {i} diverges: got {:?}, expected {:?}",
+                actual.get(i),
+                expected.get(i)
+            );
+        }
+        assert_eq!(
+            run.unscheduled, run.expected_unscheduled,
+            "{name}: unscheduled tasks"
+        );
+    }
+}
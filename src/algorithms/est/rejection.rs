@@ -0,0 +1,81 @@
+//! Rejection hooks for the EST scheduler.
+//!
+//! A [`RejectionHook`] is invoked while EST runs, each time a task leaves the
+//! candidate list without being scheduled. Applications can use it to log,
+//! alert, or reroute rejected tasks to another facility in real time instead
+//! of diffing the output schedule afterward.
+
+use std::fmt;
+
+use qtty::Unit;
+
+use super::metrics::TaskMetrics;
+
+/// Why EST gave up on a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RejectionReason {
+    /// No window in the remaining horizon can hold the task.
+    Impossible,
+    /// The task was selected but could not be placed in the schedule.
+    Dropped,
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RejectionReason::Impossible => write!(f, "impossible"),
+            RejectionReason::Dropped => write!(f, "dropped"),
+        }
+    }
+}
+
+/// A task rejected by EST, with the metrics it had when it was rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejection<A: Unit> {
+    /// Why the task was rejected.
+    pub reason: RejectionReason,
+    /// EST, deadline and flexibility at the time of rejection.
+    pub metrics: TaskMetrics<A>,
+}
+
+/// Callback invoked by [`ESTScheduler::schedule_with_hook`](super::ESTScheduler::schedule_with_hook)
+/// for every rejected task.
+///
+/// Implemented for any `FnMut(&Rejection<A>)`.
+pub trait RejectionHook<A: Unit> {
+    /// Called once per rejected task, in rejection order.
+    fn on_rejection(&mut self, rejection: &Rejection<A>);
+}
+
+impl<A: Unit, F: FnMut(&Rejection<A>)> RejectionHook<A> for F {
+    fn on_rejection(&mut self, rejection: &Rejection<A>) {
+        self(rejection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::est::metrics::CandidateMetrics;
+    use qtty::Second;
+
+    #[test]
+    fn closures_are_hooks() {
+        let mut seen = Vec::new();
+        let mut hook = |r: &Rejection<Second>| seen.push(r.metrics.task_id.clone());
+        hook.on_rejection(&Rejection {
+            reason: RejectionReason::Dropped,
+            metrics: TaskMetrics {
+                task_id: "t".into(),
+                metrics: CandidateMetrics::impossible(),
+            },
+        });
+        assert_eq!(seen, vec!["t".to_string()]);
+    }
+
+    #[test]
+    fn reason_display() {
+        assert_eq!(RejectionReason::Impossible.to_string(), "impossible");
+        assert_eq!(RejectionReason::Dropped.to_string(), "dropped");
+    }
+}
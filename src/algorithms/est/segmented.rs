@@ -0,0 +1,252 @@
+//! Segmented EST: one rolling horizon per natural segment (e.g. per night).
+//!
+//! Treating a long period as a single rolling horizon distorts flexibility:
+//! a task visible on every night of a month looks very flexible even though
+//! each individual night may be tight. [`ESTScheduler::schedule_segmented`]
+//! splits the horizon at caller-supplied boundaries and runs the EST loop on
+//! each segment in turn, with metrics computed against that segment only.
+//! Tasks left unscheduled in a segment are carried over to the next one.
+
+use qtty::{Quantity, Unit};
+
+use super::candidate::Candidate;
+use super::engine::schedule_segment_with_hook;
+use super::metrics::compute_candidate_metrics;
+use super::rejection::Rejection;
+use super::ESTScheduler;
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+use crate::Id;
+
+/// What happened inside one segment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentStats<U: Unit> {
+    /// The segment's span.
+    pub segment: Interval<U>,
+    /// Tasks that could fit in the segment when it started.
+    pub candidates: usize,
+    /// Of those, how many were endangered (flexibility below the threshold).
+    pub endangered: usize,
+    /// Tasks placed in the segment, in placement order.
+    pub scheduled: Vec<Id>,
+    /// Candidates left unscheduled and carried over to later segments.
+    pub carried_over: usize,
+    /// Of those, how many were endangered in this segment.
+    pub endangered_carried_over: usize,
+    /// Total duration scheduled in the segment.
+    pub scheduled_duration: Quantity<U>,
+}
+
+impl<U: Unit> SegmentStats<U> {
+    /// Fraction of the segment occupied by scheduled tasks.
+    pub fn utilization(&self) -> f64 {
+        let length = self.segment.duration().value();
+        if length > 0.0 {
+            self.scheduled_duration.value() / length
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Result of [`ESTScheduler::schedule_segmented`].
+#[derive(Debug, Clone)]
+pub struct SegmentedOutcome<U: Unit> {
+    /// The combined schedule over all segments.
+    pub schedule: Schedule<U>,
+    /// Per-segment statistics, in time order.
+    pub segments: Vec<SegmentStats<U>>,
+    /// Tasks not scheduled in any segment.
+    pub unscheduled: Vec<Id>,
+}
+
+/// Splits `horizon` at the boundaries strictly inside it.
+fn split_horizon<U: Unit>(horizon: Horizon<U>, boundaries: &[Quantity<U>]) -> Vec<Interval<U>> {
+    let (start, end) = (horizon.start().value(), horizon.end().value());
+    let mut cuts: Vec<f64> = boundaries
+        .iter()
+        .map(|b| b.value())
+        .filter(|&b| b > start && b < end)
+        .collect();
+    cuts.sort_by(f64::total_cmp);
+    cuts.dedup();
+
+    let mut segments = Vec::with_capacity(cuts.len() + 1);
+    let mut from = start;
+    for cut in cuts.into_iter().chain(std::iter::once(end)) {
+        segments.push(Interval::new(Quantity::new(from), Quantity::new(cut)));
+        from = cut;
+    }
+    segments
+}
+
+impl<U: Unit> ESTScheduler<U> {
+    /// Runs EST separately on each segment of `horizon` delimited by
+    /// `boundaries` (e.g. the start of every night).
+    ///
+    /// Metrics (EST, deadline, flexibility) are computed against the current
+    /// segment only. Every task not placed in a segment, endangered ones
+    /// included, remains a candidate in the following segments. Boundaries
+    /// outside the horizon are ignored; with no boundaries this is equivalent
+    /// to a single EST run.
+    pub fn schedule_segmented<T, D, E>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+        boundaries: &[Quantity<U>],
+    ) -> SegmentedOutcome<U>
+    where
+        T: Task<U> + Clone,
+        E: petgraph::EdgeType,
+    {
+        let mut schedule = Schedule::new();
+        let mut remaining: Vec<(&str, &T)> = blocks.iter().flat_map(|b| b.tasks()).collect();
+        let threshold = self.endangered_threshold.value();
+        let mut segments = Vec::new();
+
+        for segment in split_horizon(horizon, boundaries) {
+            let mut candidates = 0;
+            let mut endangered: Vec<&str> = Vec::new();
+            for &(id, task) in &remaining {
+                let metrics = compute_candidate_metrics(task, id, solution_space, segment);
+                if !metrics.is_impossible() {
+                    candidates += 1;
+                    if metrics.flexibility.value() < threshold {
+                        endangered.push(id);
+                    }
+                }
+            }
+
+            let before = schedule.len();
+            schedule_segment_with_hook(
+                &mut schedule,
+                remaining
+                    .iter()
+                    .map(|&(id, task)| Candidate::new(task.clone(), id))
+                    .collect(),
+                solution_space,
+                segment,
+                self.endangered_threshold,
+                self.objective,
+                &mut |_: &Rejection<U>| {},
+            );
+
+            let mut scheduled: Vec<(Quantity<U>, Id)> = remaining
+                .iter()
+                .filter_map(|&(id, _)| {
+                    schedule
+                        .get_interval(id)
+                        .map(|iv| (iv.start(), id.to_string()))
+                })
+                .collect();
+            scheduled.sort_by(|a, b| a.0.value().total_cmp(&b.0.value()));
+            debug_assert_eq!(scheduled.len(), schedule.len() - before);
+            remaining.retain(|(id, _)| !schedule.contains_task(id));
+
+            let scheduled_duration = scheduled
+                .iter()
+                .filter_map(|(_, id)| schedule.get_interval(id))
+                .fold(Quantity::new(0.0), |acc, iv| acc + iv.duration());
+            segments.push(SegmentStats {
+                segment,
+                candidates,
+                endangered: endangered.len(),
+                carried_over: candidates - scheduled.len(),
+                endangered_carried_over: endangered
+                    .iter()
+                    .filter(|id| !schedule.contains_task(id))
+                    .count(),
+                scheduled: scheduled.into_iter().map(|(_, id)| id).collect(),
+                scheduled_duration,
+            });
+        }
+
+        SegmentedOutcome {
+            schedule,
+            segments,
+            unscheduled: remaining
+                .into_iter()
+                .map(|(id, _)| id.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::SchedulingAlgorithm;
+    use crate::test_utils::{block_and_space, hz, iv, q};
+
+    #[test]
+    fn split_ignores_outside_and_duplicate_boundaries() {
+        let parts = split_horizon(hz(0.0, 30.0), &[q(20.0), q(10.0), q(10.0), q(0.0), q(45.0)]);
+        assert_eq!(parts, vec![iv(0.0, 10.0), iv(10.0, 20.0), iv(20.0, 30.0)]);
+        assert_eq!(split_horizon(hz(0.0, 30.0), &[]), vec![iv(0.0, 30.0)]);
+    }
+
+    #[test]
+    fn no_boundaries_matches_plain_est() {
+        let (block, ss) = block_and_space(&[
+            ("a", 10.0, 0, iv(0.0, 50.0)),
+            ("b", 20.0, 0, iv(10.0, 40.0)),
+        ]);
+        let est = ESTScheduler::new(q(1.0));
+        let out = est.schedule_segmented(std::slice::from_ref(&block), &ss, hz(0.0, 100.0), &[]);
+        let plain = est.schedule(&[block], &ss, hz(0.0, 100.0));
+        assert_eq!(
+            out.schedule.iter().collect::<Vec<_>>(),
+            plain.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(out.segments.len(), 1);
+        assert_eq!(out.segments[0].scheduled.len(), 2);
+    }
+
+    #[test]
+    fn unscheduled_tasks_carry_over() {
+        // Night 1 [0, 10) only fits one of the two tasks; both are visible on night 2.
+        let (block, mut ss) = block_and_space(&[
+            ("a", 10.0, 0, iv(0.0, 10.0)),
+            ("b", 10.0, 0, iv(0.0, 10.0)),
+            ("c", 30.0, 0, iv(0.0, 20.0)),
+        ]);
+        ss.add_interval("a", iv(10.0, 20.0));
+        ss.add_interval("b", iv(10.0, 20.0));
+        let out =
+            ESTScheduler::new(q(2.0)).schedule_segmented(&[block], &ss, hz(0.0, 20.0), &[q(10.0)]);
+
+        let night1 = &out.segments[0];
+        assert_eq!(night1.segment, iv(0.0, 10.0));
+        assert_eq!(night1.candidates, 2);
+        assert_eq!(night1.endangered, 2);
+        assert_eq!(night1.scheduled, vec!["a".to_string()]);
+        assert_eq!(night1.carried_over, 1);
+        assert_eq!(night1.endangered_carried_over, 1);
+        assert!((night1.utilization() - 1.0).abs() < 1e-12);
+
+        let night2 = &out.segments[1];
+        assert_eq!(night2.candidates, 1);
+        assert_eq!(night2.scheduled, vec!["b".to_string()]);
+        assert_eq!(night2.carried_over, 0);
+
+        assert_eq!(out.schedule.len(), 2);
+        assert_eq!(out.unscheduled, vec!["c".to_string()]);
+    }
+
+    #[test]
+    fn segment_metrics_are_local() {
+        // Over the whole horizon "a" looks flexible (two windows); per night it is endangered.
+        let (block, mut ss) = block_and_space(&[("a", 8.0, 0, iv(0.0, 10.0))]);
+        ss.add_interval("a", iv(12.0, 20.0));
+        let whole =
+            compute_candidate_metrics(block.tasks().next().unwrap().1, "a", &ss, iv(0.0, 20.0));
+        assert!(whole.flexibility.value() >= 2.0);
+
+        let out =
+            ESTScheduler::new(q(2.0)).schedule_segmented(&[block], &ss, hz(0.0, 20.0), &[q(10.0)]);
+        assert_eq!(out.segments[0].endangered, 1);
+        assert_eq!(out.schedule.get_interval("a"), Some(iv(0.0, 8.0)));
+    }
+}
@@ -0,0 +1,544 @@
+//! Greedy scheduling algorithm.
+//!
+//! A simple single-pass baseline: every task is scored once, tasks are sorted
+//! by descending score, and each is placed at the earliest position inside its
+//! feasible windows that does not overlap the tasks already placed.
+//!
+//! # Ordering
+//!
+//! - Score descending (any [`TaskScorer`], such as a [`GreedyScore`];
+//!   default [`PriorityUrgency`])
+//! - NaN scores go last
+//! - Ties are broken by task ID, so results are deterministic
+//!
+//! # Placement
+//!
+//! Each task is anchored according to its
+//! [`Task::placement_preference`]: left-aligned by default, or latest,
+//! centered, or spread out inside the windows left free by earlier placements.
+//! Left-aligned tasks whose windows carry
+//! [quality scores](SolutionSpace::annotate_quality) try the best window
+//! first, the earliest of equally good ones.
+//! [Background tasks](Task::is_background) go in the schedule's background
+//! lane at the start of their best fitting window, whatever the other
+//! placements.
+//!
+//! # Module Structure
+//!
+//! - [`scoring`] - Scoring trait and the default priority × urgency score
+//! - `placement` - Earliest-fit placement against a partial schedule
+
+mod placement;
+pub mod scoring;
+
+use crate::algorithms::est::metrics::compute_metrics;
+use crate::algorithms::scoring::{ScoringContext, TaskScorer};
+use crate::algorithms::Objective;
+use crate::schedule::Schedule;
+use crate::scheduling_block::{PlacementPreference, SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+use crate::Id;
+use qtty::{Quantity, Unit};
+
+use placement::{anchor_in_free_windows, find_earliest_fit};
+pub(crate) use placement::{find_earliest_non_overlapping, free_windows};
+pub use scoring::{GreedyScore, PriorityUrgency, WeightedScore};
+
+/// Reason a task was left out of a greedy schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unplaced {
+    /// The task has no entry in the solution space.
+    NotInSolutionSpace,
+    /// No feasible window within the horizon is long enough for the task.
+    NoFittingWindow,
+    /// Windows were long enough, but earlier placements occupied them.
+    Blocked,
+}
+
+/// Result of a greedy run, with per-task outcome details.
+#[derive(Debug, Clone)]
+pub struct GreedyOutcome<U: Unit> {
+    /// The produced schedule.
+    pub schedule: Schedule<U>,
+    /// IDs of the placed tasks, in placement order.
+    pub placed: Vec<Id>,
+    /// IDs of the tasks that could not be placed, with the reason.
+    pub unplaced: Vec<(Id, Unplaced)>,
+}
+
+/// A task ready for placement: scored, with its fitting windows clipped to the horizon.
+struct ScoredTask<'a, T, U: Unit> {
+    id: Id,
+    task: &'a T,
+    score: f64,
+    fitting: Vec<(f64, f64)>,
+    placement: PlacementPreference<U>,
+    background: bool,
+}
+
+/// Greedy scheduler with pluggable scoring.
+///
+/// # Example
+///
+/// ```no_run
+/// use virolai::algorithms::greedy::GreedyScheduler;
+/// # use virolai::algorithms::SchedulingAlgorithm;
+/// # use virolai::scheduling_block::Task;
+/// # use virolai::synthetic::SyntheticTask;
+/// # type MyTask = SyntheticTask<Second>;
+/// # use qtty::Second;
+/// # use virolai::solution_space::{Horizon, Interval};
+/// # use virolai::synthetic::{generate, SyntheticConfig};
+/// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+/// # let instance = generate(&SyntheticConfig::new(20, horizon));
+/// # let blocks = vec![instance.block];
+/// # let space = instance.solution_space;
+///
+/// // Default priority × urgency score
+/// let schedule = GreedyScheduler::new().schedule(&blocks, &space, horizon);
+///
+/// // Custom score: longest task first
+/// let longest_first = GreedyScheduler::with_score(|task: &MyTask, _: &str, _| task.size().value());
+/// let outcome = longest_first.schedule_with_outcome(&blocks, &space, horizon);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GreedyScheduler<S = PriorityUrgency> {
+    score: S,
+    objective: Objective,
+}
+
+impl GreedyScheduler<PriorityUrgency> {
+    /// Creates a greedy scheduler using the default [`PriorityUrgency`] score.
+    pub fn new() -> Self {
+        Self {
+            score: PriorityUrgency,
+            objective: Objective::Nominal,
+        }
+    }
+}
+
+impl<S> GreedyScheduler<S> {
+    /// Creates a greedy scheduler using a custom score.
+    pub fn with_score(score: S) -> Self {
+        Self {
+            score,
+            objective: Objective::Nominal,
+        }
+    }
+
+    /// Sets what the scheduler maximizes.
+    ///
+    /// With [`Objective::ExpectedValue`], each score is multiplied by the
+    /// task's success probability at its earliest feasible start.
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Returns the scoring function.
+    pub fn score(&self) -> &S {
+        &self.score
+    }
+
+    /// Schedules the tasks and reports which were placed and why others were not.
+    pub fn schedule_with_outcome<T, U, D, E>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+    ) -> GreedyOutcome<U>
+    where
+        S: TaskScorer<T, U>,
+        T: Task<U>,
+        U: Unit,
+        E: petgraph::EdgeType,
+    {
+        let mut outcome = GreedyOutcome {
+            schedule: Schedule::new(),
+            placed: Vec::new(),
+            unplaced: Vec::new(),
+        };
+
+        let mut entries: Vec<ScoredTask<T, U>> = Vec::new();
+
+        alloc_phase!(
+            Population,
+            for block in blocks {
+                for (id, task) in block.tasks() {
+                    if solution_space.get_intervals(id).is_none() {
+                        outcome
+                            .unplaced
+                            .push((id.to_string(), Unplaced::NotInSolutionSpace));
+                        continue;
+                    }
+                    let mut fitting: Vec<(f64, f64, f64)> = solution_space
+                        .windows_with_quality(id)
+                        .filter_map(|(window, quality)| {
+                            window
+                                .intersection(&horizon.interval())
+                                .map(|w| (w, quality))
+                        })
+                        .filter(|(window, _)| {
+                            (window.start() + task.duration_at(window.start())).value()
+                                <= window.end().value()
+                        })
+                        .map(|(window, quality)| {
+                            (window.start().value(), window.end().value(), quality)
+                        })
+                        .collect();
+                    if fitting.is_empty() {
+                        outcome
+                            .unplaced
+                            .push((id.to_string(), Unplaced::NoFittingWindow));
+                        continue;
+                    }
+
+                    let capacity: f64 = fitting.iter().map(|(s, e, _)| e - s).sum();
+                    let context = ScoringContext {
+                        task,
+                        solution_space,
+                        horizon: horizon.interval(),
+                        capacity: Quantity::new(capacity),
+                    };
+                    let metrics = compute_metrics(task, id, solution_space, horizon.interval());
+                    let mut score = self.score.score(id, &metrics, &context);
+                    if self.objective == Objective::ExpectedValue {
+                        let earliest = Quantity::new(fitting[0].0);
+                        score *= task.success_probability(earliest).clamp(0.0, 1.0);
+                    }
+                    let score = if score.is_nan() {
+                        f64::NEG_INFINITY
+                    } else {
+                        score
+                    };
+                    // Earliest-fit tasks try their best windows first; the
+                    // other preferences place by time. Stable, so equally
+                    // good windows stay in time order.
+                    let placement = task.placement_preference();
+                    if matches!(placement, PlacementPreference::Earliest) || task.is_background() {
+                        fitting.sort_by(|a, b| b.2.total_cmp(&a.2));
+                    }
+                    entries.push(ScoredTask {
+                        id: id.to_string(),
+                        task,
+                        score,
+                        fitting: fitting.into_iter().map(|(s, e, _)| (s, e)).collect(),
+                        placement,
+                        background: task.is_background(),
+                    });
+                }
+            }
+        );
+
+        alloc_phase!(
+            Candidates,
+            entries.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)))
+        );
+
+        let horizon_start = horizon.start().value();
+        let horizon_end = horizon.end().value();
+        for ScoredTask {
+            id,
+            task,
+            fitting,
+            placement,
+            background,
+            ..
+        } in entries
+        {
+            let duration = |start: f64| task.duration_at(Quantity::new(start)).value();
+            if background {
+                let start = fitting[0].0;
+                let interval =
+                    Interval::new(Quantity::new(start), Quantity::new(start + duration(start)));
+                if alloc_phase!(Build, outcome.schedule.add_background(id.clone(), interval))
+                    .is_ok()
+                {
+                    outcome.placed.push(id);
+                } else {
+                    outcome.unplaced.push((id, Unplaced::Blocked));
+                }
+                continue;
+            }
+            let start = alloc_phase!(
+                Candidates,
+                match placement {
+                    PlacementPreference::Earliest => find_earliest_fit(
+                        &fitting,
+                        duration,
+                        horizon_start,
+                        horizon_end,
+                        &outcome.schedule,
+                    ),
+                    _ => anchor_in_free_windows(
+                        &placement,
+                        &fitting,
+                        duration,
+                        horizon_start,
+                        horizon_end,
+                        &outcome.schedule,
+                    ),
+                }
+            );
+            let placed = start.is_some_and(|start| {
+                let interval =
+                    Interval::new(Quantity::new(start), Quantity::new(start + duration(start)));
+                alloc_phase!(Build, outcome.schedule.add(id.clone(), interval)).is_ok()
+            });
+            if placed {
+                outcome.placed.push(id);
+            } else {
+                outcome.unplaced.push((id, Unplaced::Blocked));
+            }
+        }
+
+        outcome
+    }
+}
+
+impl<S, T, U, D, E> crate::algorithms::SchedulingAlgorithm<T, U, D, E> for GreedyScheduler<S>
+where
+    S: TaskScorer<T, U>,
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    fn schedule(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+    ) -> Schedule<U> {
+        self.schedule_with_outcome(blocks, solution_space, horizon)
+            .schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::SchedulingAlgorithm;
+    use crate::test_utils::{hz, iv, TestTask};
+    use qtty::Second;
+
+    fn block_with(tasks: &[TestTask]) -> (SchedulingBlock<TestTask, Second>, Vec<Id>) {
+        let mut block = SchedulingBlock::new();
+        let ids = tasks
+            .iter()
+            .map(|t| {
+                block
+                    .add_task_with_id(t.clone(), Some(t.name.clone()))
+                    .unwrap()
+            })
+            .collect();
+        (block, ids)
+    }
+
+    // ── ordering ──────────────────────────────────────────────────────
+
+    #[test]
+    fn higher_priority_placed_first() {
+        let (block, _) = block_with(&[
+            TestTask::new("low", 10.0).with_priority(1),
+            TestTask::new("high", 10.0).with_priority(5),
+        ]);
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("low", iv(0.0, 100.0));
+        ss.add_interval("high", iv(0.0, 100.0));
+
+        let outcome = GreedyScheduler::new().schedule_with_outcome(&[block], &ss, hz(0.0, 100.0));
+        assert_eq!(outcome.placed, vec!["high", "low"]);
+        assert_eq!(outcome.schedule.get_interval("high"), Some(iv(0.0, 10.0)));
+        assert_eq!(outcome.schedule.get_interval("low"), Some(iv(10.0, 20.0)));
+    }
+
+    #[test]
+    fn ties_broken_by_task_id() {
+        let (block, _) = block_with(&[TestTask::new("b", 10.0), TestTask::new("a", 10.0)]);
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("a", iv(0.0, 100.0));
+        ss.add_interval("b", iv(0.0, 100.0));
+
+        let outcome = GreedyScheduler::new().schedule_with_outcome(&[block], &ss, hz(0.0, 100.0));
+        assert_eq!(outcome.placed, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn custom_score_changes_order() {
+        let (block, _) = block_with(&[TestTask::new("short", 5.0), TestTask::new("long", 20.0)]);
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("short", iv(0.0, 100.0));
+        ss.add_interval("long", iv(0.0, 100.0));
+
+        let longest_first =
+            GreedyScheduler::with_score(|task: &TestTask, _: &str, _: Quantity<Second>| {
+                task.size().value()
+            });
+        let outcome = longest_first.schedule_with_outcome(&[block], &ss, hz(0.0, 100.0));
+        assert_eq!(outcome.placed, vec!["long", "short"]);
+    }
+
+    // ── outcome reporting ─────────────────────────────────────────────
+
+    #[test]
+    fn reports_unplaced_reasons() {
+        let (block, _) = block_with(&[
+            TestTask::new("missing", 10.0),
+            TestTask::new("too-big", 50.0),
+            TestTask::new("first", 10.0).with_priority(9),
+            TestTask::new("blocked", 10.0),
+        ]);
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("too-big", iv(0.0, 20.0));
+        ss.add_interval("first", iv(0.0, 10.0));
+        ss.add_interval("blocked", iv(0.0, 10.0));
+
+        let outcome = GreedyScheduler::new().schedule_with_outcome(&[block], &ss, hz(0.0, 100.0));
+        assert_eq!(outcome.placed, vec!["first"]);
+        assert!(outcome
+            .unplaced
+            .contains(&("missing".to_string(), Unplaced::NotInSolutionSpace)));
+        assert!(outcome
+            .unplaced
+            .contains(&("too-big".to_string(), Unplaced::NoFittingWindow)));
+        assert!(outcome
+            .unplaced
+            .contains(&("blocked".to_string(), Unplaced::Blocked)));
+    }
+
+    #[test]
+    fn windows_clipped_to_horizon() {
+        let (block, _) = block_with(&[TestTask::new("t", 10.0)]);
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("t", iv(0.0, 100.0));
+
+        let outcome = GreedyScheduler::new().schedule_with_outcome(&[block], &ss, hz(95.0, 200.0));
+        assert_eq!(
+            outcome.unplaced,
+            vec![("t".to_string(), Unplaced::NoFittingWindow)]
+        );
+    }
+
+    #[test]
+    fn schedule_trait_matches_outcome() {
+        let (block, _) = block_with(&[TestTask::new("a", 10.0), TestTask::new("b", 10.0)]);
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("a", iv(0.0, 100.0));
+        ss.add_interval("b", iv(0.0, 100.0));
+        let blocks = [block];
+
+        let scheduler = GreedyScheduler::new();
+        let schedule = scheduler.schedule(&blocks, &ss, hz(0.0, 100.0));
+        let outcome = scheduler.schedule_with_outcome(&blocks, &ss, hz(0.0, 100.0));
+        assert_eq!(
+            schedule.iter().collect::<Vec<_>>(),
+            outcome.schedule.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn placements_use_the_duration_at_the_start() {
+        let (block, _) = block_with(&[
+            TestTask::new("long", 10.0).with_duration(20.0),
+            TestTask::new("late", 10.0)
+                .with_duration(20.0)
+                .with_placement(PlacementPreference::Latest),
+            TestTask::new("bg", 10.0)
+                .with_duration(20.0)
+                .in_background(),
+            TestTask::new("tight", 10.0).with_duration(20.0),
+        ]);
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("long", iv(0.0, 50.0));
+        ss.add_interval("late", iv(50.0, 100.0));
+        ss.add_interval("bg", iv(0.0, 100.0));
+        ss.add_interval("tight", iv(0.0, 15.0));
+
+        let outcome = GreedyScheduler::new().schedule_with_outcome(&[block], &ss, hz(0.0, 100.0));
+        let schedule = &outcome.schedule;
+        assert_eq!(schedule.get_interval("long"), Some(iv(0.0, 20.0)));
+        assert_eq!(schedule.get_interval("late"), Some(iv(80.0, 100.0)));
+        assert_eq!(schedule.get_interval("bg"), Some(iv(0.0, 20.0)));
+        assert!(outcome
+            .unplaced
+            .contains(&("tight".to_string(), Unplaced::NoFittingWindow)));
+    }
+
+    // ── placement preferences ─────────────────────────────────────────
+
+    #[test]
+    fn latest_preference_right_aligns_in_free_space() {
+        let (block, _) = block_with(&[
+            TestTask::new("first", 10.0).with_priority(9),
+            TestTask::new("late", 10.0).with_placement(PlacementPreference::Latest),
+        ]);
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("first", iv(90.0, 100.0));
+        ss.add_interval("late", iv(0.0, 100.0));
+
+        let outcome = GreedyScheduler::new().schedule_with_outcome(&[block], &ss, hz(0.0, 100.0));
+        assert_eq!(outcome.schedule.get_interval("late"), Some(iv(80.0, 90.0)));
+    }
+
+    #[test]
+    fn spread_preference_centers_between_neighbors() {
+        let (block, _) = block_with(&[
+            TestTask::new("left", 10.0).with_priority(9),
+            TestTask::new("right", 10.0).with_priority(9),
+            TestTask::new("mid", 10.0).with_placement(PlacementPreference::MaxNeighborDistance),
+        ]);
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("left", iv(0.0, 10.0));
+        ss.add_interval("right", iv(90.0, 100.0));
+        ss.add_interval("mid", iv(0.0, 100.0));
+
+        let outcome = GreedyScheduler::new().schedule_with_outcome(&[block], &ss, hz(0.0, 100.0));
+        assert_eq!(outcome.schedule.get_interval("mid"), Some(iv(45.0, 55.0)));
+    }
+
+    #[test]
+    fn earliest_fit_prefers_rated_windows() {
+        let (block, _) = block_with(&[
+            TestTask::new("first", 10.0).with_priority(9),
+            TestTask::new("seeing", 10.0),
+        ]);
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("first", iv(40.0, 50.0));
+        ss.set_intervals(
+            "seeing",
+            vec![iv(0.0, 20.0), iv(30.0, 60.0), iv(70.0, 80.0)],
+        );
+        // The two good windows tie; the earlier one is full past 40.
+        ss.set_window_quality("seeing", vec![0.5, 2.0, 2.0]);
+
+        let outcome = GreedyScheduler::new().schedule_with_outcome(&[block], &ss, hz(0.0, 100.0));
+        assert_eq!(
+            outcome.schedule.get_interval("seeing"),
+            Some(iv(30.0, 40.0))
+        );
+    }
+
+    // ── objectives ────────────────────────────────────────────────────
+
+    #[test]
+    fn expected_value_objective_weights_scores() {
+        let (block, _) = block_with(&[
+            TestTask::new("risky", 10.0)
+                .with_priority(5)
+                .with_success_probability(0.2),
+            TestTask::new("safe", 10.0).with_priority(2),
+        ]);
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("risky", iv(0.0, 100.0));
+        ss.add_interval("safe", iv(0.0, 100.0));
+        let blocks = [block];
+
+        let nominal = GreedyScheduler::new().schedule_with_outcome(&blocks, &ss, hz(0.0, 100.0));
+        assert_eq!(nominal.placed, vec!["risky", "safe"]);
+
+        let expected = GreedyScheduler::new()
+            .with_objective(Objective::ExpectedValue)
+            .schedule_with_outcome(&blocks, &ss, hz(0.0, 100.0));
+        assert_eq!(expected.placed, vec!["safe", "risky"]);
+    }
+}
@@ -0,0 +1,169 @@
+//! Earliest-fit placement against an existing schedule.
+
+use crate::schedule::Schedule;
+use crate::scheduling_block::PlacementPreference;
+use crate::solution_space::Interval;
+use qtty::{Quantity, Unit};
+
+/// Finds the earliest placement start that fits within feasible windows
+/// without overlapping already-scheduled intervals.
+///
+/// `intervals` are tried in order, so the result is the earliest fit when
+/// they are sorted by start. Placement never begins before `cursor` nor ends
+/// after `horizon_end`.
+pub(crate) fn find_earliest_non_overlapping<U: Unit>(
+    intervals: &[(f64, f64)],
+    size: f64,
+    cursor: f64,
+    horizon_end: f64,
+    schedule: &Schedule<U>,
+) -> Option<f64> {
+    find_earliest_fit(intervals, |_| size, cursor, horizon_end, schedule)
+}
+
+/// [`find_earliest_non_overlapping`] for a task whose duration depends on
+/// its start, as given by `duration`.
+///
+/// Ending times must not go backwards as the start grows, as required by
+/// [`Task::duration_at`](crate::scheduling_block::Task::duration_at).
+pub(crate) fn find_earliest_fit<U: Unit>(
+    intervals: &[(f64, f64)],
+    duration: impl Fn(f64) -> f64,
+    cursor: f64,
+    horizon_end: f64,
+    schedule: &Schedule<U>,
+) -> Option<f64> {
+    for &(win_start, win_end) in intervals {
+        let mut candidate_start = win_start.max(cursor);
+        loop {
+            let candidate_end = candidate_start + duration(candidate_start);
+            if candidate_end > win_end || candidate_end > horizon_end {
+                break;
+            }
+
+            let query = Interval::new(Quantity::new(candidate_start), Quantity::new(candidate_end));
+            match schedule.conflicts_ref(query).map(|mut c| c.next()) {
+                Ok(None) => return Some(candidate_start),
+                Ok(Some((_, conflict_iv))) => candidate_start = conflict_iv.end().value(),
+                Err(_) => break,
+            }
+        }
+    }
+    None
+}
+
+/// Returns the parts of `intervals` (clipped to `[cursor, horizon_end)`) not
+/// occupied by scheduled tasks, sorted by start.
+pub(crate) fn free_windows<U: Unit>(
+    intervals: &[(f64, f64)],
+    cursor: f64,
+    horizon_end: f64,
+    schedule: &Schedule<U>,
+) -> Vec<Interval<U>> {
+    let mut free = Vec::new();
+    for &(win_start, win_end) in intervals {
+        let mut start = win_start.max(cursor);
+        let end = win_end.min(horizon_end);
+        if start >= end {
+            continue;
+        }
+
+        let window = Interval::new(Quantity::new(start), Quantity::new(end));
+        if let Ok(conflicts) = schedule.conflicts_ref(window) {
+            for (_, busy) in conflicts {
+                if busy.start().value() > start {
+                    free.push(Interval::new(Quantity::new(start), busy.start()));
+                }
+                start = start.max(busy.end().value());
+            }
+        }
+        if start < end {
+            free.push(Interval::new(Quantity::new(start), Quantity::new(end)));
+        }
+    }
+    free
+}
+
+/// Anchors a task by its `placement` preference inside the parts of
+/// `intervals` left free by `schedule`.
+///
+/// The preference picks a start for the duration at the earliest fit. That
+/// start is kept if the task still fits there with its own duration;
+/// otherwise the task goes to the earliest fit.
+pub(crate) fn anchor_in_free_windows<U: Unit>(
+    placement: &PlacementPreference<U>,
+    intervals: &[(f64, f64)],
+    duration: impl Fn(f64) -> f64,
+    cursor: f64,
+    horizon_end: f64,
+    schedule: &Schedule<U>,
+) -> Option<f64> {
+    let earliest = find_earliest_fit(intervals, &duration, cursor, horizon_end, schedule)?;
+    let free = free_windows(intervals, cursor, horizon_end, schedule);
+    let start = placement
+        .choose_start(&free, Quantity::new(duration(earliest)))
+        .map_or(earliest, |start| start.value());
+    let end = start + duration(start);
+    let fits = free
+        .iter()
+        .any(|w| w.start().value() <= start && end <= w.end().value());
+    Some(if fits { start } else { earliest })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::iv;
+    use qtty::Second;
+
+    #[test]
+    fn places_at_window_start_when_free() {
+        let schedule = Schedule::<Second>::new();
+        let start = find_earliest_non_overlapping(&[(10.0, 50.0)], 5.0, 0.0, 100.0, &schedule);
+        assert_eq!(start, Some(10.0));
+    }
+
+    #[test]
+    fn skips_past_existing_tasks() {
+        let mut schedule = Schedule::<Second>::new();
+        schedule.add("a", iv(10.0, 20.0)).unwrap();
+        schedule.add("b", iv(20.0, 30.0)).unwrap();
+        let start = find_earliest_non_overlapping(&[(10.0, 50.0)], 5.0, 0.0, 100.0, &schedule);
+        assert_eq!(start, Some(30.0));
+    }
+
+    #[test]
+    fn moves_to_next_window_when_blocked() {
+        let mut schedule = Schedule::<Second>::new();
+        schedule.add("a", iv(0.0, 8.0)).unwrap();
+        let start =
+            find_earliest_non_overlapping(&[(0.0, 10.0), (40.0, 60.0)], 5.0, 0.0, 100.0, &schedule);
+        assert_eq!(start, Some(40.0));
+    }
+
+    #[test]
+    fn free_windows_subtract_scheduled_tasks() {
+        let mut schedule = Schedule::<Second>::new();
+        schedule.add("a", iv(10.0, 20.0)).unwrap();
+        schedule.add("b", iv(45.0, 70.0)).unwrap();
+        let free = free_windows(&[(0.0, 50.0), (60.0, 120.0)], 5.0, 100.0, &schedule);
+        assert_eq!(free, vec![iv(5.0, 10.0), iv(20.0, 45.0), iv(70.0, 100.0)]);
+    }
+
+    #[test]
+    fn earliest_fit_uses_the_duration_at_each_start() {
+        let mut schedule = Schedule::<Second>::new();
+        schedule.add("a", iv(0.0, 10.0)).unwrap();
+        // Ends at 30 when started by 10, then lasts 20 s: [10, 30) fits.
+        let duration = |start: f64| (30.0 - start).max(20.0);
+        let start = find_earliest_fit(&[(0.0, 30.0)], duration, 0.0, 100.0, &schedule);
+        assert_eq!(start, Some(10.0));
+    }
+
+    #[test]
+    fn respects_horizon_end() {
+        let schedule = Schedule::<Second>::new();
+        let start = find_earliest_non_overlapping(&[(90.0, 200.0)], 20.0, 0.0, 100.0, &schedule);
+        assert_eq!(start, None);
+    }
+}
@@ -0,0 +1,137 @@
+//! Scoring functions that decide the order in which the greedy scheduler
+//! attempts to place tasks.
+
+use crate::scheduling_block::Task;
+use qtty::{Quantity, Unit};
+
+/// Scores a task for greedy placement. Higher scores are placed first.
+///
+/// `capacity` is the total duration of the task's feasible windows within the
+/// scheduling horizon (only windows the task fits in are counted).
+///
+/// Any closure `Fn(&T, &str, Quantity<U>) -> f64` implements this trait.
+pub trait GreedyScore<T, U>
+where
+    T: Task<U>,
+    U: Unit,
+{
+    /// Returns the score of `task`. NaN scores are ordered after all others.
+    fn score(&self, task: &T, task_id: &str, capacity: Quantity<U>) -> f64;
+}
+
+impl<F, T, U> GreedyScore<T, U> for F
+where
+    F: Fn(&T, &str, Quantity<U>) -> f64,
+    T: Task<U>,
+    U: Unit,
+{
+    fn score(&self, task: &T, task_id: &str, capacity: Quantity<U>) -> f64 {
+        self(task, task_id, capacity)
+    }
+}
+
+/// Default score: `max(priority, 1) × urgency`, where urgency is the inverse
+/// of the remaining capacity.
+///
+/// High-priority tasks with little room to move are placed first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriorityUrgency;
+
+impl<T, U> GreedyScore<T, U> for PriorityUrgency
+where
+    T: Task<U>,
+    U: Unit,
+{
+    fn score(&self, task: &T, _task_id: &str, capacity: Quantity<U>) -> f64 {
+        priority_urgency(task.priority(), capacity.value())
+    }
+}
+
+/// `max(priority, 1)^priority × urgency^urgency`, a [`PriorityUrgency`] with
+/// tunable weights.
+///
+/// The weights are exponents, so they weigh the logarithms of the two
+/// factors against each other; the default `(1, 1)` scores exactly like
+/// [`PriorityUrgency`] and `(1, 0)` ranks by priority alone.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedScore {
+    /// Weight of the priority.
+    pub priority: f64,
+    /// Weight of the urgency.
+    pub urgency: f64,
+}
+
+impl Default for WeightedScore {
+    fn default() -> Self {
+        Self {
+            priority: 1.0,
+            urgency: 1.0,
+        }
+    }
+}
+
+impl<T, U> GreedyScore<T, U> for WeightedScore
+where
+    T: Task<U>,
+    U: Unit,
+{
+    fn score(&self, task: &T, _task_id: &str, capacity: Quantity<U>) -> f64 {
+        let priority = (task.priority() as f64).max(1.0);
+        let urgency = priority_urgency(1, capacity.value());
+        priority.powf(self.priority) * urgency.powf(self.urgency)
+    }
+}
+
+/// Raw `priority × urgency` score shared with the RL scheduler's fallback phase.
+pub(crate) fn priority_urgency(priority: i32, remaining_capacity: f64) -> f64 {
+    let eps = 1e-6;
+    let urgency = 1.0 / (eps + remaining_capacity);
+    (priority as f64).max(1.0) * urgency
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{q, TestTask};
+
+    #[test]
+    fn priority_urgency_prefers_tight_tasks() {
+        let task = TestTask::new("t", 10.0).with_priority(2);
+        let tight = PriorityUrgency.score(&task, "t", q(10.0));
+        let loose = PriorityUrgency.score(&task, "t", q(100.0));
+        assert!(tight > loose);
+    }
+
+    #[test]
+    fn priority_urgency_clamps_low_priority() {
+        let zero = TestTask::new("a", 10.0);
+        let negative = TestTask::new("b", 10.0).with_priority(-5);
+        assert_eq!(
+            PriorityUrgency.score(&zero, "a", q(50.0)),
+            PriorityUrgency.score(&negative, "b", q(50.0))
+        );
+    }
+
+    #[test]
+    fn default_weights_match_priority_urgency() {
+        let task = TestTask::new("t", 10.0).with_priority(3);
+        let weighted = WeightedScore::default();
+        assert_eq!(
+            weighted.score(&task, "t", q(40.0)),
+            PriorityUrgency.score(&task, "t", q(40.0))
+        );
+
+        let by_priority = WeightedScore {
+            priority: 1.0,
+            urgency: 0.0,
+        };
+        assert_eq!(by_priority.score(&task, "t", q(40.0)), 3.0);
+    }
+
+    #[test]
+    fn closures_are_scores() {
+        let by_size = |task: &TestTask, _: &str, _: Quantity<qtty::Second>| task.size().value();
+        assert_eq!(by_size.score(&TestTask::new("t", 7.0), "t", q(0.0)), 7.0);
+    }
+}
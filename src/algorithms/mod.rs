@@ -1,7 +1,28 @@
+pub mod anytime;
+pub mod arbitration;
+pub mod audit;
+pub mod balancing;
+pub mod beam;
+pub mod coalition;
+pub mod commitment;
+pub mod config;
 pub mod est;
+pub mod greedy;
+pub mod registry;
 pub mod rl;
+pub mod scoring;
+pub mod timing;
 
+pub use anytime::{AnytimeAlgorithm, AnytimeContext};
+pub use arbitration::{Arbitration, ArbitrationCandidate, ArbitrationPolicy};
+pub use audit::{Audited, DecisionLog};
+pub use balancing::{LeastLoadedScheduler, RoundRobinScheduler};
+pub use beam::BeamSearchScheduler;
+pub use coalition::CoalitionScheduler;
+pub use config::AlgorithmConfig;
 pub use est::ESTScheduler;
+pub use greedy::GreedyScheduler;
+pub use registry::AlgorithmRegistry;
 #[cfg(feature = "rl-nn")]
 pub use rl::policy_scheduler::RLScheduler;
 
@@ -9,9 +30,36 @@ use std::collections::HashMap;
 
 use crate::schedule::Schedule;
 use crate::scheduling_block::{SchedulingBlock, Task};
-use crate::solution_space::{Interval, SolutionSpace};
+use crate::solution_space::{Horizon, SolutionSpace};
 use crate::Id;
 
+/// What a scheduler maximizes when ranking tasks.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Objective {
+    /// Rank by nominal priority.
+    #[default]
+    Nominal,
+    /// Rank by expected completed value: priority × success probability at
+    /// the candidate start time.
+    ExpectedValue,
+}
+
+impl Objective {
+    /// Returns the value of placing `task` at `start` under this objective.
+    pub fn task_value<T, U>(&self, task: &T, start: qtty::Quantity<U>) -> f64
+    where
+        T: Task<U>,
+        U: qtty::Unit,
+    {
+        let priority = task.priority() as f64;
+        match self {
+            Self::Nominal => priority,
+            Self::ExpectedValue => priority * task.success_probability(start).clamp(0.0, 1.0),
+        }
+    }
+}
+
 /// Algorithm for scheduling tasks from one or more scheduling blocks.
 ///
 /// # Type Parameters
@@ -32,7 +80,7 @@ where
     ///
     /// * `blocks` - Collection of scheduling blocks containing tasks to schedule
     /// * `solution_space` - Valid intervals where each task can be placed
-    /// * `horizon` - The time window for scheduling
+    /// * `horizon` - The span the schedule must lie within
     ///
     /// # Returns
     ///
@@ -41,7 +89,7 @@ where
         &self,
         blocks: &[SchedulingBlock<T, U, D, E>],
         solution_space: &SolutionSpace<U>,
-        horizon: Interval<U>,
+        horizon: Horizon<U>,
     ) -> Schedule<U>;
 }
 
@@ -78,7 +126,7 @@ where
         &self,
         blocks: &[SchedulingBlock<T, U, D, E>],
         resource_spaces: &HashMap<Id, SolutionSpace<U>>,
-        horizon: Interval<U>,
+        horizon: Horizon<U>,
     ) -> HashMap<Id, Schedule<U>>;
 }
 
@@ -86,14 +134,15 @@ where
 ///
 /// This is the simplest multi-resource strategy: each resource gets its own schedule
 /// computed in isolation. Tasks may be scheduled on multiple resources if they appear
-/// in multiple solution spaces (no deduplication).
+/// in multiple solution spaces (no deduplication). See [`balancing`] for
+/// adapters that give each task to a single resource.
 ///
 /// # Example
 ///
 /// ```ignore
 /// use virolai::algorithms::{ESTScheduler, IndependentScheduler, MultiResourceAlgorithm};
 ///
-/// let multi = IndependentScheduler::new(ESTScheduler::new(100));
+/// let multi = IndependentScheduler::new(ESTScheduler::new(Quantity::new(100.0)));
 /// let schedules = multi.schedule_multi(&blocks, &resource_spaces, horizon);
 /// ```
 pub struct IndependentScheduler<A> {
@@ -118,7 +167,7 @@ where
         &self,
         blocks: &[SchedulingBlock<T, U, D, E>],
         resource_spaces: &HashMap<Id, SolutionSpace<U>>,
-        horizon: Interval<U>,
+        horizon: Horizon<U>,
     ) -> HashMap<Id, Schedule<U>> {
         resource_spaces
             .iter()
@@ -129,3 +178,71 @@ where
             .collect()
     }
 }
+
+#[cfg(feature = "parallel")]
+impl<A> IndependentScheduler<A> {
+    /// Like [`schedule_multi`](MultiResourceAlgorithm::schedule_multi), but
+    /// schedules the resources in parallel on the rayon thread pool.
+    ///
+    /// Resources are processed independently, so the result is identical to
+    /// the serial path regardless of thread count or completion order.
+    pub fn schedule_multi_par<T, U, D, E>(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        resource_spaces: &HashMap<Id, SolutionSpace<U>>,
+        horizon: Horizon<U>,
+    ) -> HashMap<Id, Schedule<U>>
+    where
+        A: SchedulingAlgorithm<T, U, D, E> + Sync,
+        T: Task<U>,
+        U: qtty::Unit + Send + Sync,
+        D: Sync,
+        E: petgraph::EdgeType + Sync,
+    {
+        use rayon::prelude::*;
+
+        resource_spaces
+            .par_iter()
+            .map(|(resource_id, space)| {
+                let schedule = self.inner.schedule(blocks, space, horizon);
+                (resource_id.clone(), schedule)
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use super::*;
+    use crate::test_utils::{block_of, hz, iv, q, TestTask};
+
+    #[test]
+    fn parallel_matches_serial() {
+        let block = block_of([
+            TestTask::new("a", 10.0),
+            TestTask::new("b", 20.0),
+            TestTask::new("c", 5.0),
+        ]);
+        let mut spaces = HashMap::new();
+        for r in 0..8 {
+            let mut ss = SolutionSpace::new();
+            for name in ["a", "b", "c"] {
+                ss.add_interval(name, iv(r as f64 * 3.0, 100.0));
+            }
+            spaces.insert(format!("r{r}"), ss);
+        }
+
+        let multi = IndependentScheduler::new(ESTScheduler::new(q(1.0)));
+        let blocks = [block];
+        let serial = multi.schedule_multi(&blocks, &spaces, hz(0.0, 100.0));
+        let parallel = multi.schedule_multi_par(&blocks, &spaces, hz(0.0, 100.0));
+
+        assert_eq!(parallel.len(), serial.len());
+        for (resource, schedule) in &serial {
+            assert_eq!(
+                parallel[resource].iter().collect::<Vec<_>>(),
+                schedule.iter().collect::<Vec<_>>()
+            );
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! Algorithms looked up by name.
+//!
+//! An [`AlgorithmRegistry`] maps names to factories that turn a key/value
+//! configuration (the shape of [`RunSpec::config`](crate::runs::RunSpec))
+//! into a boxed [`SchedulingAlgorithm`]. Downstream crates register their own
+//! algorithms at startup and hand the registry to whatever layer picks the
+//! algorithm from a command line, a request or a scenario file, so a plugin
+//! needs no change to virolai.
+//!
+//! [`AlgorithmRegistry::with_builtins`] starts from `est`, `greedy` and
+//! `beam`, configured with the same parameter names as
+//! [`AlgorithmConfig`](super::AlgorithmConfig). Keys a factory does not read
+//! are ignored.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use petgraph::Directed;
+//! # use virolai::algorithms::registry::{parameter, Parameters};
+//! # use virolai::algorithms::{AlgorithmRegistry, BeamSearchScheduler};
+//! # use virolai::synthetic::SyntheticTask;
+//! # type MyTask = SyntheticTask<Second>;
+//! # struct Lookahead;
+//! # impl Lookahead {
+//! #     fn new(depth: usize) -> BeamSearchScheduler { BeamSearchScheduler::new(4, depth) }
+//! # }
+//! # struct Spec { algorithm: String, config: Parameters }
+//! # let spec = Spec { algorithm: "lookahead".into(), config: Parameters::new() };
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let space = instance.solution_space;
+//! let mut registry = AlgorithmRegistry::<MyTask, Second, (), Directed>::with_builtins();
+//! registry.register("lookahead", |config| {
+//!     let depth = parameter(config, "depth", 3)?;
+//!     Ok(Box::new(Lookahead::new(depth)))
+//! })?;
+//!
+//! let scheduler = registry.build(&spec.algorithm, &spec.config)?;
+//! let schedule = scheduler.schedule(&blocks, &space, horizon);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::algorithms::config::{
+    AlgorithmConfigError, BeamConfig, BeamScorer, EstConfig, GreedyConfig,
+};
+use crate::algorithms::greedy::WeightedScore;
+use crate::algorithms::{AlgorithmConfig, Objective, SchedulingAlgorithm};
+use crate::scheduling_block::Task;
+use qtty::Quantity;
+
+/// Key/value configuration handed to a factory.
+pub type Parameters = BTreeMap<String, String>;
+
+/// Builds an algorithm from its configuration.
+pub type Factory<T, U, D, E> = Box<
+    dyn Fn(&Parameters) -> Result<Box<dyn SchedulingAlgorithm<T, U, D, E>>, RegistryError>
+        + Send
+        + Sync,
+>;
+
+/// Reads a built-in algorithm's configuration.
+type ParseConfig = fn(&Parameters) -> Result<AlgorithmConfig, RegistryError>;
+
+/// A reason the registry cannot register or build an algorithm.
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("An algorithm named {0:?} is already registered")]
+    Duplicate(String),
+
+    #[error("Unknown algorithm {name:?}, expected one of {available:?}")]
+    Unknown {
+        name: String,
+        available: Vec<String>,
+    },
+
+    #[error("Invalid value {value:?} for parameter {key:?}")]
+    InvalidParameter { key: String, value: String },
+
+    #[error(transparent)]
+    Config(#[from] AlgorithmConfigError),
+}
+
+/// Named [`SchedulingAlgorithm`] factories.
+pub struct AlgorithmRegistry<T, U, D, E> {
+    factories: BTreeMap<String, Factory<T, U, D, E>>,
+}
+
+impl<T, U, D, E> AlgorithmRegistry<T, U, D, E>
+where
+    T: Task<U>,
+    U: qtty::Unit,
+    E: petgraph::EdgeType,
+{
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self {
+            factories: BTreeMap::new(),
+        }
+    }
+
+    /// Registers `factory` under `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RegistryError::Duplicate`] if `name` is taken.
+    pub fn register<F>(&mut self, name: impl Into<String>, factory: F) -> Result<(), RegistryError>
+    where
+        F: Fn(&Parameters) -> Result<Box<dyn SchedulingAlgorithm<T, U, D, E>>, RegistryError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let name = name.into();
+        if self.factories.contains_key(&name) {
+            return Err(RegistryError::Duplicate(name));
+        }
+        self.factories.insert(name, Box::new(factory));
+        Ok(())
+    }
+
+    /// Returns `true` if an algorithm is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// Registered names, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.factories.keys().map(String::as_str)
+    }
+
+    /// Builds the algorithm registered under `name` with `config`.
+    ///
+    /// # Errors
+    ///
+    /// - [`RegistryError::Unknown`] if nothing is registered under `name`
+    /// - whatever the factory returns for an invalid configuration
+    pub fn build(
+        &self,
+        name: &str,
+        config: &Parameters,
+    ) -> Result<Box<dyn SchedulingAlgorithm<T, U, D, E>>, RegistryError> {
+        let factory = self
+            .factories
+            .get(name)
+            .ok_or_else(|| RegistryError::Unknown {
+                name: name.to_string(),
+                available: self.factories.keys().cloned().collect(),
+            })?;
+        factory(config)
+    }
+}
+
+impl<T, U, D, E> AlgorithmRegistry<T, U, D, E>
+where
+    T: Task<U> + Clone + 'static,
+    U: qtty::Unit + 'static,
+    D: 'static,
+    E: petgraph::EdgeType + 'static,
+{
+    /// Creates a registry holding `est`, `greedy` and `beam`.
+    ///
+    /// Parameters, all optional:
+    ///
+    /// - `est`: `endangered_threshold`, `objective`
+    /// - `greedy`: `objective`, `priority` and `urgency` (score weights)
+    /// - `beam`: `beam_width`, `depth`, `endangered_threshold`
+    ///
+    /// `objective` is `nominal` or `expected_value`.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        let builtins: [(&str, ParseConfig); 3] = [
+            ("est", |config| {
+                let defaults = EstConfig::default();
+                Ok(AlgorithmConfig::Est(EstConfig {
+                    endangered_threshold: Quantity::new(parameter(
+                        config,
+                        "endangered_threshold",
+                        defaults.endangered_threshold.value(),
+                    )?),
+                    objective: objective(config)?,
+                }))
+            }),
+            ("greedy", |config| {
+                let defaults = WeightedScore::default();
+                Ok(AlgorithmConfig::Greedy(GreedyConfig {
+                    objective: objective(config)?,
+                    weights: WeightedScore {
+                        priority: parameter(config, "priority", defaults.priority)?,
+                        urgency: parameter(config, "urgency", defaults.urgency)?,
+                    },
+                }))
+            }),
+            ("beam", |config| {
+                let defaults = BeamConfig::default();
+                Ok(AlgorithmConfig::Beam(BeamConfig {
+                    beam_width: parameter(config, "beam_width", defaults.beam_width)?,
+                    depth: parameter(config, "depth", defaults.depth)?,
+                    endangered_threshold: Quantity::new(parameter(
+                        config,
+                        "endangered_threshold",
+                        defaults.endangered_threshold.value(),
+                    )?),
+                    scorer: BeamScorer::Est,
+                }))
+            }),
+        ];
+        for (name, parse) in builtins {
+            registry
+                .register(name, move |config| Ok(parse(config)?.build()?))
+                .expect("built-in names are distinct");
+        }
+        registry
+    }
+}
+
+impl<T, U, D, E> Default for AlgorithmRegistry<T, U, D, E>
+where
+    T: Task<U>,
+    U: qtty::Unit,
+    E: petgraph::EdgeType,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, U, D, E> fmt::Debug for AlgorithmRegistry<T, U, D, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlgorithmRegistry")
+            .field("names", &self.factories.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Parses `config[key]`, or returns `default` if the key is absent.
+///
+/// # Errors
+///
+/// Returns [`RegistryError::InvalidParameter`] if the value does not parse.
+pub fn parameter<P: FromStr>(
+    config: &Parameters,
+    key: &str,
+    default: P,
+) -> Result<P, RegistryError> {
+    match config.get(key) {
+        None => Ok(default),
+        Some(value) => value.parse().map_err(|_| RegistryError::InvalidParameter {
+            key: key.to_string(),
+            value: value.clone(),
+        }),
+    }
+}
+
+/// The `objective` parameter.
+fn objective(config: &Parameters) -> Result<Objective, RegistryError> {
+    match config.get("objective").map(String::as_str) {
+        None | Some("nominal") => Ok(Objective::Nominal),
+        Some("expected_value") => Ok(Objective::ExpectedValue),
+        Some(value) => Err(RegistryError::InvalidParameter {
+            key: "objective".to_string(),
+            value: value.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{ESTScheduler, GreedyScheduler};
+    use crate::scheduling_block::SchedulingBlock;
+    use crate::solution_space::SolutionSpace;
+    use crate::test_utils::{hz, iv, q, TestTask};
+    use petgraph::Directed;
+    use qtty::Second;
+
+    #[test]
+    fn builds_builtins_and_plugins_by_name() {
+        let mut block = SchedulingBlock::new();
+        let mut space = SolutionSpace::new();
+        for (id, priority) in [("a", 1), ("b", 5)] {
+            let task = TestTask::new(id, 10.0).with_priority(priority);
+            block.add_task_with_id(task, Some(id.to_string())).unwrap();
+            space.add_interval(id, iv(0.0, 30.0));
+        }
+        let blocks = [block];
+        let horizon = hz(0.0, 30.0);
+
+        let mut registry = AlgorithmRegistry::<TestTask, Second, (), Directed>::with_builtins();
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            ["beam", "est", "greedy"]
+        );
+
+        let config = Parameters::from([("endangered_threshold".into(), "3".into())]);
+        let built = registry.build("est", &config).unwrap();
+        let direct = ESTScheduler::new(q(3.0)).schedule(&blocks, &space, horizon);
+        assert_eq!(
+            built
+                .schedule(&blocks, &space, horizon)
+                .iter()
+                .collect::<Vec<_>>(),
+            direct.iter().collect::<Vec<_>>()
+        );
+
+        // A plugin reuses the parameter parsing.
+        registry
+            .register("urgent-first", |config| {
+                let urgency = parameter(config, "urgency", 1.0)?;
+                Ok(Box::new(GreedyScheduler::with_score(WeightedScore {
+                    priority: 0.0,
+                    urgency,
+                })))
+            })
+            .unwrap();
+        assert!(registry.contains("urgent-first"));
+        assert!(registry.build("urgent-first", &Parameters::new()).is_ok());
+
+        assert!(matches!(
+            registry.register("est", |_| unreachable!()),
+            Err(RegistryError::Duplicate(name)) if name == "est"
+        ));
+        assert!(matches!(
+            registry.build("nope", &Parameters::new()),
+            Err(RegistryError::Unknown { available, .. }) if available.len() == 4
+        ));
+        let bad = Parameters::from([("depth".into(), "deep".into())]);
+        assert!(matches!(
+            registry.build("beam", &bad),
+            Err(RegistryError::InvalidParameter { key, .. }) if key == "depth"
+        ));
+        let nan = Parameters::from([("priority".into(), "NaN".into())]);
+        assert!(matches!(
+            registry.build("greedy", &nan),
+            Err(RegistryError::Config(_))
+        ));
+    }
+}
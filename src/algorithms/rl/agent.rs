@@ -1,6 +1,9 @@
 //! Agent state and dynamics for the RL environment.
 
+use qtty::Unit;
+
 use super::config::RLConfig;
+use super::obstacles::steer;
 use super::task_pool::TaskInstance;
 use super::types::{AgentType, Position};
 use crate::Id;
@@ -8,7 +11,8 @@ use crate::Id;
 /// State of a single agent in the RL environment.
 ///
 /// Each agent has a position in the 2D domain, a type (which determines its
-/// maximum movement speed), and an optional current target task.
+/// maximum movement speed), an optional current target task and, with an
+/// [`EnergyModel`](super::energy::EnergyModel), a battery.
 #[derive(Debug, Clone)]
 pub struct AgentState {
     /// Unique identifier for this agent.
@@ -21,6 +25,8 @@ pub struct AgentState {
     pub current_target: usize,
     /// Distance to target at end of previous step (for progress shaping).
     pub prev_distance_to_target: Option<f64>,
+    /// Energy left in the battery (`None` without an energy model).
+    pub energy: Option<f64>,
 }
 
 impl AgentState {
@@ -32,6 +38,19 @@ impl AgentState {
             agent_type,
             current_target: 0,
             prev_distance_to_target: None,
+            energy: None,
+        }
+    }
+
+    /// Returns true if the agent has a battery and it is empty.
+    pub fn is_depleted(&self) -> bool {
+        self.energy.is_some_and(|energy| energy <= 0.0)
+    }
+
+    /// Spends `amount` of energy, never going below empty.
+    pub fn spend_energy(&mut self, amount: f64) {
+        if let Some(energy) = &mut self.energy {
+            *energy = (*energy - amount).max(0.0);
         }
     }
 
@@ -41,10 +60,23 @@ impl AgentState {
     /// - Action 1..=M: index into the Top-M candidate task list.
     ///
     /// The agent moves toward the target task's position at most
-    /// `v(τ) × Δt` distance, clamped to domain bounds.
-    pub fn step(&mut self, action: usize, top_m_tasks: &[&TaskInstance], config: &RLConfig) {
+    /// `v(τ) × Δt` distance, clamped to domain bounds. With an energy model,
+    /// it moves no farther than its battery allows and pays for the
+    /// distance covered. Obstacles stop it at their border, see
+    /// [`steer`](super::obstacles::steer).
+    pub fn step<U: Unit>(
+        &mut self,
+        action: usize,
+        top_m_tasks: &[&TaskInstance],
+        config: &RLConfig<U>,
+    ) {
         self.current_target = action;
-        let max_dist = config.speed_for(self.agent_type) * config.delta_t;
+        let speed_dist = config.speed_for(self.agent_type) * config.delta_t;
+        let range = match (&config.energy, self.energy) {
+            (Some(model), Some(energy)) => model.range(energy),
+            _ => f64::INFINITY,
+        };
+        let max_dist = speed_dist.min(range);
 
         if action == 0 || action > top_m_tasks.len() {
             // Patrol: no movement (agent holds position)
@@ -54,9 +86,24 @@ impl AgentState {
 
         let task = &top_m_tasks[action - 1];
         let target = task.position;
-        self.prev_distance_to_target = Some(self.position.distance_to(&target));
+        let distance = self.position.distance_to(&target);
+        self.prev_distance_to_target = Some(distance);
+        let before = self.position;
         self.position
             .move_toward(&target, max_dist, config.world_width, config.world_height);
+        let mut travelled = before.distance_to(&self.position);
+        let mut blocked = false;
+        if !config.obstacles.is_empty() {
+            (self.position, travelled, blocked) = steer(&config.obstacles, before, self.position);
+        }
+        if let Some(model) = &config.energy {
+            if distance > range && range <= speed_dist && !blocked {
+                // Used up the whole battery; avoids leaving rounding crumbs.
+                self.energy = Some(0.0);
+            } else {
+                self.spend_energy(model.move_cost * travelled);
+            }
+        }
     }
 
     /// Returns the distance to the current target task, if any.
@@ -82,7 +129,7 @@ impl AgentState {
     /// Encodes agent state as a feature vector for observation.
     ///
     /// Returns `[x_norm, y_norm, one_hot_young, one_hot_middle, one_hot_old]`.
-    pub fn features(&self, config: &RLConfig) -> Vec<f64> {
+    pub fn features<U: Unit>(&self, config: &RLConfig<U>) -> Vec<f64> {
         let (nx, ny) = self
             .position
             .normalized(config.world_width, config.world_height);
@@ -94,6 +141,7 @@ impl AgentState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::algorithms::rl::energy::EnergyModel;
 
     fn default_config() -> RLConfig {
         RLConfig::default()
@@ -144,9 +192,30 @@ mod tests {
             type_requirements: AgentTypeRequirements::default(),
             collection_radius: 1.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         };
         agent.step(1, &[&task], &config);
         // Young agent speed = 3.0, should move 3 units toward (10, 0)
         assert!((agent.position.x - 3.0).abs() < 1e-10);
+
+        // A battery for 4 units of distance runs out during the second step.
+        let config = RLConfig {
+            energy: Some(EnergyModel {
+                capacity: 1.0,
+                move_cost: 0.25,
+                ..EnergyModel::default()
+            }),
+            ..default_config()
+        };
+        agent.position = Position::new(0.0, 0.0);
+        agent.energy = Some(1.0);
+        agent.step(1, &[&task], &config);
+        assert!((agent.energy.unwrap() - 0.25).abs() < 1e-10);
+        agent.step(1, &[&task], &config);
+        assert!((agent.position.x - 4.0).abs() < 1e-10);
+        assert!(agent.is_depleted());
+        agent.step(1, &[&task], &config);
+        assert!((agent.position.x - 4.0).abs() < 1e-10);
     }
 }
@@ -1,28 +1,370 @@
 //! Configuration for the RL scheduling environment and training.
+//!
+//! [`RLConfig`] is a plain struct, but its fields are interdependent: network
+//! shapes follow from `top_m`, and the collection radius only makes sense
+//! relative to the world size. Build it through [`RLConfig::builder`] (or
+//! call [`RLConfig::validate`]) so mistakes are reported as
+//! [`RLConfigError`]s instead of shape-mismatch panics later on.
+//!
+//! The environment counts time in whole steps. [`RLConfig::step_duration`]
+//! ties a step to the scheduling axis, so deadlines and horizons convert
+//! with [`RLConfig::steps_for`] and [`RLConfig::time_at`] instead of
+//! ad-hoc factors. The unit defaults to seconds; pick another one with
+//! [`RLConfig::with_step_duration`].
 
 use std::collections::HashMap;
 
-use super::types::AgentType;
+use qtty::{Quantity, Second, Unit};
+use thiserror::Error;
+
+use super::energy::EnergyModel;
+use super::obstacles::Obstacle;
+use super::types::{AgentType, Position};
+
+/// A reason an [`RLConfig`] is unusable.
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum RLConfigError {
+    #[error("World size must be positive, got {width} x {height}")]
+    InvalidWorldSize { width: f64, height: f64 },
+
+    #[error("Episode horizon must be at least one step")]
+    ZeroHorizon,
+
+    #[error("Time step must be positive, got {0}")]
+    InvalidDeltaT(f64),
+
+    #[error("Step duration must be positive, got {0}")]
+    InvalidStepDuration(f64),
+
+    #[error("Speed of {agent_type:?} agents must be positive, got {speed}")]
+    InvalidSpeed { agent_type: AgentType, speed: f64 },
+
+    #[error(
+        "Collection radius must be positive and smaller than the world ({world}), got {radius}"
+    )]
+    InvalidCollectionRadius { radius: f64, world: f64 },
+
+    #[error("Spawn rate must be within [0, 1], got {0}")]
+    InvalidSpawnRate(f64),
+
+    #[error("Maximum number of active tasks must be at least one")]
+    ZeroMaxActiveTasks,
+
+    #[error("top_m must be at least one")]
+    ZeroTopM,
+
+    #[error("Reward coefficient {name} must be finite and non-negative, got {value}")]
+    InvalidRewardCoefficient { name: &'static str, value: f64 },
+
+    #[error("Discount factor must be within (0, 1], got {0}")]
+    InvalidGamma(f64),
+
+    #[error("Recurrent actor hidden dimension must be at least one")]
+    ZeroHiddenDim,
+
+    #[error("Action range of {agent_type:?} agents must be positive, got {range}")]
+    InvalidActionRange { agent_type: AgentType, range: f64 },
+
+    #[error("Progressive collection needs at least one work unit per task")]
+    ZeroWorkUnits,
+
+    #[error("Observations must include at least one task feature")]
+    NoTaskFeatures,
+
+    #[error("Energy model field {field} is out of range, got {value}")]
+    InvalidEnergyModel { field: &'static str, value: f64 },
+
+    #[error("Sensing field {field} must be finite and {bound}, got {value}")]
+    InvalidSensing {
+        field: &'static str,
+        bound: &'static str,
+        value: f64,
+    },
+
+    #[error("Obstacle {index} does not enclose an area")]
+    InvalidObstacle { index: usize },
+
+    #[error("Obstacle {index} covers the center of the world, where agents start")]
+    ObstacleCoversStart { index: usize },
+}
+
+/// Architecture of the neural actor (used with the `rl-nn` feature).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActorKind {
+    /// MLP over the current observation only.
+    #[default]
+    FeedForward,
+    /// GRU actor carrying a per-agent hidden state across steps, so agents
+    /// remember tasks that have left their Top-M.
+    Recurrent {
+        /// Size of the GRU hidden state.
+        hidden_dim: usize,
+    },
+}
+
+/// How agents collect tasks.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CollectionMode {
+    /// A task is collected, and its full value earned, on the first step
+    /// its coalition is within range.
+    #[default]
+    Instant,
+    /// Tasks take work: every step the coalition is within range adds one
+    /// work unit and credits the matching share of the task's value. A task
+    /// is collected once it has received all its units; tasks expiring
+    /// before that keep the credit already earned.
+    Progressive {
+        /// Work units per task, unless its template overrides them.
+        work_units: u32,
+    },
+}
+
+/// What agents can see without a global view.
+///
+/// Each agent senses the tasks within `sensing_radius` of it and hears from
+/// the agents within `comm_radius`, which share what they sense. An agent's
+/// candidate tasks are the ones sensed by itself or by an agent it hears,
+/// one hop only; a `comm_radius` of 0 disables sharing.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sensing {
+    /// Distance up to which an agent senses tasks.
+    pub sensing_radius: f64,
+    /// Distance up to which agents share what they sense.
+    pub comm_radius: f64,
+}
+
+impl Sensing {
+    /// Sensing within `sensing_radius`, sharing within `comm_radius`.
+    pub fn new(sensing_radius: f64, comm_radius: f64) -> Self {
+        Self {
+            sensing_radius,
+            comm_radius,
+        }
+    }
+}
+
+/// Actions available to one agent type.
+///
+/// Patrol (action 0) is always allowed. Candidate slots beyond
+/// `max_targets`, and candidates farther away than `max_range`, are masked
+/// out of the shared `0..=top_m` action space.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ActionSpace {
+    /// Number of Top-M slots the agent may target (`None` = all of them).
+    pub max_targets: Option<usize>,
+    /// Maximum distance to a targeted task (`None` = unlimited).
+    pub max_range: Option<f64>,
+}
+
+impl ActionSpace {
+    /// Every action is allowed.
+    pub fn unrestricted() -> Self {
+        Self::default()
+    }
+
+    /// Whether targeting candidate `slot` (1-based) at `distance` is allowed.
+    pub fn allows(&self, slot: usize, distance: f64) -> bool {
+        slot == 0
+            || (self.max_targets.is_none_or(|m| slot <= m)
+                && self.max_range.is_none_or(|r| distance <= r))
+    }
+}
+
+/// A group of per-task observation features, see [`TaskFeatures`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskFeature {
+    /// Position normalized to the world size: x, y.
+    Position,
+    /// Value relative to the most valuable active task.
+    Value,
+    /// Remaining time relative to the episode horizon.
+    TimeLeft,
+    /// Agents required of each type: young, middle, old.
+    Requirements,
+    /// Remaining appearances of the task's template, divided by 10.
+    Appearances,
+    /// Agents of the young and middle types heading to the task.
+    Heading,
+}
+
+impl TaskFeature {
+    /// Every group, in observation order.
+    pub const ALL: [TaskFeature; 6] = [
+        TaskFeature::Position,
+        TaskFeature::Value,
+        TaskFeature::TimeLeft,
+        TaskFeature::Requirements,
+        TaskFeature::Appearances,
+        TaskFeature::Heading,
+    ];
+
+    /// Number of values the group contributes per task.
+    pub fn dim(self) -> usize {
+        match self {
+            TaskFeature::Position | TaskFeature::Heading => 2,
+            TaskFeature::Requirements => 3,
+            TaskFeature::Value | TaskFeature::TimeLeft | TaskFeature::Appearances => 1,
+        }
+    }
+}
+
+/// Per-task features included in observations.
+///
+/// Enabled groups appear in [`TaskFeature::ALL`] order; the observation
+/// dimension follows from the selection (see
+/// [`RLConfig::task_feature_dim`]). Everything is included by default.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskFeatures {
+    pub position: bool,
+    pub value: bool,
+    pub time_left: bool,
+    pub requirements: bool,
+    pub appearances: bool,
+    pub heading: bool,
+}
+
+impl TaskFeatures {
+    /// Every feature.
+    pub fn all() -> Self {
+        Self {
+            position: true,
+            value: true,
+            time_left: true,
+            requirements: true,
+            appearances: true,
+            heading: true,
+        }
+    }
+
+    /// No feature, to start a selection with [`with`](Self::with).
+    pub fn none() -> Self {
+        Self {
+            position: false,
+            value: false,
+            time_left: false,
+            requirements: false,
+            appearances: false,
+            heading: false,
+        }
+    }
+
+    /// Includes `feature`.
+    pub fn with(mut self, feature: TaskFeature) -> Self {
+        *self.flag(feature) = true;
+        self
+    }
+
+    /// Leaves `feature` out.
+    pub fn without(mut self, feature: TaskFeature) -> Self {
+        *self.flag(feature) = false;
+        self
+    }
+
+    fn flag(&mut self, feature: TaskFeature) -> &mut bool {
+        match feature {
+            TaskFeature::Position => &mut self.position,
+            TaskFeature::Value => &mut self.value,
+            TaskFeature::TimeLeft => &mut self.time_left,
+            TaskFeature::Requirements => &mut self.requirements,
+            TaskFeature::Appearances => &mut self.appearances,
+            TaskFeature::Heading => &mut self.heading,
+        }
+    }
+
+    /// Whether `feature` is included.
+    pub fn contains(&self, feature: TaskFeature) -> bool {
+        match feature {
+            TaskFeature::Position => self.position,
+            TaskFeature::Value => self.value,
+            TaskFeature::TimeLeft => self.time_left,
+            TaskFeature::Requirements => self.requirements,
+            TaskFeature::Appearances => self.appearances,
+            TaskFeature::Heading => self.heading,
+        }
+    }
+
+    /// Number of values per task.
+    pub fn dim(&self) -> usize {
+        self.iter().map(TaskFeature::dim).sum()
+    }
+
+    /// Position of `feature`'s first value within a task's features, or
+    /// `None` if it is left out.
+    pub fn offset(&self, feature: TaskFeature) -> Option<usize> {
+        self.contains(feature).then(|| {
+            self.iter()
+                .take_while(|&f| f != feature)
+                .map(TaskFeature::dim)
+                .sum()
+        })
+    }
+
+    /// Included features, in observation order.
+    pub fn iter(&self) -> impl Iterator<Item = TaskFeature> + '_ {
+        TaskFeature::ALL.into_iter().filter(|&f| self.contains(f))
+    }
+
+    /// The selection as a bit set, bit `i` standing for
+    /// `TaskFeature::ALL[i]`; stored with checkpoints.
+    pub fn bits(&self) -> u32 {
+        TaskFeature::ALL
+            .iter()
+            .enumerate()
+            .filter(|(_, &f)| self.contains(f))
+            .map(|(i, _)| 1 << i)
+            .sum()
+    }
+}
+
+impl Default for TaskFeatures {
+    fn default() -> Self {
+        Self::all()
+    }
+}
 
 /// Configuration for the RL scheduling environment.
 ///
 /// Controls environment geometry, agent dynamics, task spawning,
 /// observation encoding, and reward shaping.
+///
+/// With the `serde` feature, missing fields deserialize to their
+/// [`Default`] values.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default = "default_config", bound = "")
+)]
 #[derive(Debug, Clone)]
-pub struct RLConfig {
+pub struct RLConfig<U: Unit = Second> {
     // --- Environment geometry ---
     /// Width of the 2D domain Ω.
     pub world_width: f64,
     /// Height of the 2D domain Ω.
     pub world_height: f64,
+    /// Keep-out zones agents may not enter.
+    pub obstacles: Vec<Obstacle>,
     /// Episode horizon (number of time steps T).
     pub episode_horizon: u32,
     /// Duration of one time step Δt.
     pub delta_t: f64,
+    /// Scheduling time one step stands for.
+    pub step_duration: Quantity<U>,
 
     // --- Agent dynamics ---
     /// Maximum speed per agent type (displacement per Δt).
     pub speeds: HashMap<AgentType, f64>,
+    /// Action restrictions per agent type; missing types are unrestricted.
+    pub action_spaces: HashMap<AgentType, ActionSpace>,
 
     // --- Task parameters ---
     /// Global default collection radius ρ.
@@ -31,10 +373,21 @@ pub struct RLConfig {
     pub spawn_rate: f64,
     /// Maximum number of simultaneously active tasks.
     pub max_active_tasks: usize,
+    /// Instant or progressive (partial-credit) collection.
+    pub collection: CollectionMode,
+
+    // --- Energy ---
+    /// Battery model of the agents; `None` for unlimited endurance.
+    pub energy: Option<EnergyModel>,
 
     // --- Observation ---
     /// Number of Top-M candidate tasks to include in observations.
     pub top_m: usize,
+    /// Local sensing and communication limits; `None` for the global view,
+    /// where every agent ranks every active task.
+    pub sensing: Option<Sensing>,
+    /// Per-task features included in observations.
+    pub task_features: TaskFeatures,
 
     // --- Reward shaping ---
     /// Time penalty per step c_time (subtracted each step).
@@ -45,13 +398,24 @@ pub struct RLConfig {
     pub reward_expiry_beta: f64,
     /// Coverage shaping coefficient γ.
     pub reward_coverage_gamma: f64,
+    /// Weight of the utilization gained by placing a collected task in the
+    /// schedule under construction (see
+    /// [`ScheduleShaping`](super::schedule_reward::ScheduleShaping)).
+    pub reward_schedule_utilization: f64,
+    /// Weight of the normalized priority of a collected task that was placed
+    /// in the schedule under construction.
+    pub reward_schedule_priority: f64,
 
     // --- Discount ---
     /// Discount factor for RL returns.
     pub gamma: f64,
+
+    // --- Policy ---
+    /// Actor architecture.
+    pub actor: ActorKind,
 }
 
-impl RLConfig {
+impl<U: Unit> RLConfig<U> {
     /// Returns the maximum speed for a given agent type.
     pub fn speed_for(&self, agent_type: AgentType) -> f64 {
         self.speeds
@@ -60,49 +424,578 @@ impl RLConfig {
             .unwrap_or_else(|| agent_type.default_max_speed())
     }
 
-    /// Observation dimension per agent: own features + top_m × task features.
+    /// Returns the action space for a given agent type.
+    pub fn action_space_for(&self, agent_type: AgentType) -> ActionSpace {
+        self.action_spaces
+            .get(&agent_type)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Work units a task needs under this configuration: 1 with instant
+    /// collection, else the template's `override_units` or the default.
+    pub fn work_units_for(&self, override_units: Option<u32>) -> u32 {
+        match self.collection {
+            CollectionMode::Instant => 1,
+            CollectionMode::Progressive { work_units } => {
+                override_units.unwrap_or(work_units).max(1)
+            }
+        }
+    }
+
+    /// Observation dimension per agent: own features + top_m × task features,
+    /// followed by one progress feature per candidate with progressive
+    /// collection, one blocked-path feature per candidate with obstacles and
+    /// the agent's charge with an energy model.
     pub fn observation_dim(&self) -> usize {
-        Self::AGENT_FEATURE_DIM + self.top_m * Self::TASK_FEATURE_DIM
+        RLConfig::AGENT_FEATURE_DIM
+            + self.top_m * self.task_feature_dim()
+            + self.progress_dim(self.top_m)
+            + self.obstacle_dim(self.top_m)
+            + self.energy_dim()
     }
 
-    /// Number of features encoding a single agent.
-    pub const AGENT_FEATURE_DIM: usize = 5; // x, y, one_hot(3)
+    /// Number of features encoding a single task candidate with the
+    /// selected [`task_features`](Self::task_features).
+    pub fn task_feature_dim(&self) -> usize {
+        self.task_features.dim()
+    }
 
-    /// Number of features encoding a single task candidate.
-    pub const TASK_FEATURE_DIM: usize = 10; // x, y, value, time_left, r_young, r_middle, r_old, k_rem, heading_young, heading_middle (+ heading_old implied)
+    /// Number of progress features for `tasks` task slots: `tasks` with
+    /// progressive collection, 0 otherwise so instant layouts are unchanged.
+    pub fn progress_dim(&self, tasks: usize) -> usize {
+        match self.collection {
+            CollectionMode::Instant => 0,
+            CollectionMode::Progressive { .. } => tasks,
+        }
+    }
+
+    /// Number of blocked-path features for `tasks` task slots: `tasks` with
+    /// [`obstacles`](Self::obstacles), 0 otherwise.
+    pub fn obstacle_dim(&self, tasks: usize) -> usize {
+        if self.obstacles.is_empty() {
+            0
+        } else {
+            tasks
+        }
+    }
+
+    /// Number of energy features per agent: 1 with an
+    /// [`energy`](Self::energy) model, 0 otherwise so layouts without one
+    /// are unchanged.
+    pub fn energy_dim(&self) -> usize {
+        usize::from(self.energy.is_some())
+    }
 
     /// Number of possible actions: 0 = patrol, 1..=top_m = target task.
     pub fn action_dim(&self) -> usize {
         self.top_m + 1
     }
+
+    /// Scheduling time at the start of `step`, from the start of the
+    /// episode.
+    pub fn time_at(&self, step: u32) -> Quantity<U> {
+        Quantity::new(f64::from(step) * self.step_duration.value())
+    }
+
+    /// Scheduling time a whole episode covers.
+    pub fn horizon_duration(&self) -> Quantity<U> {
+        self.time_at(self.episode_horizon)
+    }
+
+    /// Number of steps needed to cover `duration`, rounded up; 0 for an
+    /// empty or negative duration.
+    pub fn steps_for(&self, duration: Quantity<U>) -> u32 {
+        (duration.value() / self.step_duration.value()).ceil() as u32
+    }
+
+    /// The same configuration with steps of `step_duration`, possibly in
+    /// another unit.
+    pub fn with_step_duration<V: Unit>(self, step_duration: Quantity<V>) -> RLConfig<V> {
+        RLConfig {
+            world_width: self.world_width,
+            world_height: self.world_height,
+            obstacles: self.obstacles,
+            episode_horizon: self.episode_horizon,
+            delta_t: self.delta_t,
+            step_duration,
+            speeds: self.speeds,
+            action_spaces: self.action_spaces,
+            collection_radius: self.collection_radius,
+            spawn_rate: self.spawn_rate,
+            max_active_tasks: self.max_active_tasks,
+            collection: self.collection,
+            energy: self.energy,
+            top_m: self.top_m,
+            sensing: self.sensing,
+            task_features: self.task_features,
+            reward_time_penalty: self.reward_time_penalty,
+            reward_progress_alpha: self.reward_progress_alpha,
+            reward_expiry_beta: self.reward_expiry_beta,
+            reward_coverage_gamma: self.reward_coverage_gamma,
+            reward_schedule_utilization: self.reward_schedule_utilization,
+            reward_schedule_priority: self.reward_schedule_priority,
+            gamma: self.gamma,
+            actor: self.actor,
+        }
+    }
+
+    /// Checks that every field is in range and that the fields are
+    /// consistent with each other.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first problem found, in field order.
+    pub fn validate(&self) -> Result<(), RLConfigError> {
+        let positive = |x: f64| x.is_finite() && x > 0.0;
+
+        if !positive(self.world_width) || !positive(self.world_height) {
+            return Err(RLConfigError::InvalidWorldSize {
+                width: self.world_width,
+                height: self.world_height,
+            });
+        }
+        let center = Position::new(self.world_width / 2.0, self.world_height / 2.0);
+        for (index, obstacle) in self.obstacles.iter().enumerate() {
+            if !obstacle.is_valid() {
+                return Err(RLConfigError::InvalidObstacle { index });
+            }
+            if obstacle.contains(&center) {
+                return Err(RLConfigError::ObstacleCoversStart { index });
+            }
+        }
+        if self.episode_horizon == 0 {
+            return Err(RLConfigError::ZeroHorizon);
+        }
+        if !positive(self.delta_t) {
+            return Err(RLConfigError::InvalidDeltaT(self.delta_t));
+        }
+        if !positive(self.step_duration.value()) {
+            return Err(RLConfigError::InvalidStepDuration(
+                self.step_duration.value(),
+            ));
+        }
+        for agent_type in AgentType::all() {
+            let speed = self.speed_for(agent_type);
+            if !positive(speed) {
+                return Err(RLConfigError::InvalidSpeed { agent_type, speed });
+            }
+            if let Some(range) = self.action_space_for(agent_type).max_range {
+                if !positive(range) {
+                    return Err(RLConfigError::InvalidActionRange { agent_type, range });
+                }
+            }
+        }
+        let world = self.world_width.min(self.world_height);
+        if !positive(self.collection_radius) || self.collection_radius >= world {
+            return Err(RLConfigError::InvalidCollectionRadius {
+                radius: self.collection_radius,
+                world,
+            });
+        }
+        if !(0.0..=1.0).contains(&self.spawn_rate) {
+            return Err(RLConfigError::InvalidSpawnRate(self.spawn_rate));
+        }
+        if self.max_active_tasks == 0 {
+            return Err(RLConfigError::ZeroMaxActiveTasks);
+        }
+        if self.collection == (CollectionMode::Progressive { work_units: 0 }) {
+            return Err(RLConfigError::ZeroWorkUnits);
+        }
+        if let Some(energy) = &self.energy {
+            let zones = energy
+                .charging_zones
+                .iter()
+                .map(|z| ("charging_zones.radius", z.radius, true));
+            for (field, value, strict) in [
+                ("capacity", energy.capacity, true),
+                ("move_cost", energy.move_cost, false),
+                ("collect_cost", energy.collect_cost, false),
+                ("charge_rate", energy.charge_rate, false),
+                ("depletion_penalty", energy.depletion_penalty, false),
+            ]
+            .into_iter()
+            .chain(zones)
+            {
+                if !value.is_finite() || value < 0.0 || (strict && value == 0.0) {
+                    return Err(RLConfigError::InvalidEnergyModel { field, value });
+                }
+            }
+        }
+        if self.top_m == 0 {
+            return Err(RLConfigError::ZeroTopM);
+        }
+        if self.task_feature_dim() == 0 {
+            return Err(RLConfigError::NoTaskFeatures);
+        }
+        if let Some(sensing) = self.sensing {
+            if !positive(sensing.sensing_radius) {
+                return Err(RLConfigError::InvalidSensing {
+                    field: "sensing_radius",
+                    bound: "positive",
+                    value: sensing.sensing_radius,
+                });
+            }
+            if !sensing.comm_radius.is_finite() || sensing.comm_radius < 0.0 {
+                return Err(RLConfigError::InvalidSensing {
+                    field: "comm_radius",
+                    bound: "non-negative",
+                    value: sensing.comm_radius,
+                });
+            }
+        }
+        for (name, value) in [
+            ("reward_time_penalty", self.reward_time_penalty),
+            ("reward_progress_alpha", self.reward_progress_alpha),
+            ("reward_expiry_beta", self.reward_expiry_beta),
+            ("reward_coverage_gamma", self.reward_coverage_gamma),
+            (
+                "reward_schedule_utilization",
+                self.reward_schedule_utilization,
+            ),
+            ("reward_schedule_priority", self.reward_schedule_priority),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(RLConfigError::InvalidRewardCoefficient { name, value });
+            }
+        }
+        if !(self.gamma > 0.0 && self.gamma <= 1.0) {
+            return Err(RLConfigError::InvalidGamma(self.gamma));
+        }
+        if self.actor == (ActorKind::Recurrent { hidden_dim: 0 }) {
+            return Err(RLConfigError::ZeroHiddenDim);
+        }
+        Ok(())
+    }
 }
 
-impl Default for RLConfig {
-    fn default() -> Self {
-        let mut speeds = HashMap::new();
-        speeds.insert(AgentType::Young, 3.0);
-        speeds.insert(AgentType::Middle, 2.0);
-        speeds.insert(AgentType::Old, 1.0);
+impl RLConfig {
+    /// Number of features encoding a single agent.
+    pub const AGENT_FEATURE_DIM: usize = 5; // x, y, one_hot(3)
+
+    /// Number of features encoding a single task candidate with every
+    /// [`TaskFeature`] included.
+    pub const TASK_FEATURE_DIM: usize = 10; // x, y, value, time_left, r_young, r_middle, r_old, k_rem, heading_young, heading_middle (+ heading_old implied)
+
+    /// Starts a [`RLConfigBuilder`] from the default (medium) configuration.
+    pub fn builder() -> RLConfigBuilder {
+        RLConfigBuilder::new()
+    }
+
+    /// Small world for smoke tests and quick experiments.
+    pub fn small() -> Self {
+        Self {
+            world_width: 5.0,
+            world_height: 5.0,
+            episode_horizon: 50,
+            max_active_tasks: 8,
+            top_m: 3,
+            ..Self::default()
+        }
+    }
+
+    /// The default configuration.
+    pub fn medium() -> Self {
+        Self::default()
+    }
+
+    /// Large world with many concurrent tasks.
+    pub fn large() -> Self {
+        Self {
+            world_width: 50.0,
+            world_height: 50.0,
+            episode_horizon: 500,
+            collection_radius: 2.0,
+            spawn_rate: 0.5,
+            max_active_tasks: 100,
+            top_m: 10,
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(feature = "rl-nn")]
+impl<U: Unit> RLConfig<U> {
+    /// Saves the observation layout (dimension and [`TaskFeatures`]) to a
+    /// safetensors file, stored with checkpoints as `layout.safetensors`.
+    pub fn save_layout(&self, path: impl AsRef<std::path::Path>) -> Result<(), tch::TchError> {
+        tch::Tensor::write_safetensors(
+            &[
+                (
+                    "observation_dim",
+                    tch::Tensor::from_slice(&[self.observation_dim() as i64]),
+                ),
+                (
+                    "task_features",
+                    tch::Tensor::from_slice(&[i64::from(self.task_features.bits())]),
+                ),
+            ],
+            path,
+        )
+    }
 
+    /// Checks that a layout saved by [`save_layout`](Self::save_layout)
+    /// matches this configuration's observations.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`tch::TchError::FileFormat`] naming both layouts if they
+    /// differ, so a checkpoint trained on other features is rejected before
+    /// it fails with a shape mismatch.
+    pub fn check_layout(&self, path: impl AsRef<std::path::Path>) -> Result<(), tch::TchError> {
+        let path = path.as_ref();
+        let mut dim = None;
+        let mut bits = None;
+        for (name, tensor) in tch::Tensor::read_safetensors(path)? {
+            let values = Vec::<i64>::try_from(&tensor)?;
+            match name.as_str() {
+                "observation_dim" => dim = values.first().copied(),
+                "task_features" => bits = values.first().copied(),
+                _ => {}
+            }
+        }
+        let expected = (
+            self.observation_dim() as i64,
+            i64::from(self.task_features.bits()),
+        );
+        match (dim, bits) {
+            (Some(dim), Some(bits)) if (dim, bits) == expected => Ok(()),
+            (Some(dim), Some(bits)) => Err(tch::TchError::FileFormat(format!(
+                "{} holds observations of dimension {dim} (task features {bits:#b}), \
+                 the configuration produces dimension {} (task features {:#b})",
+                path.display(),
+                expected.0,
+                expected.1
+            ))),
+            _ => Err(tch::TchError::FileFormat(format!(
+                "{} does not hold an observation layout",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// Builder for a validated [`RLConfig`].
+///
+/// Observation and action dimensions are not set directly; they follow from
+/// [`top_m`](Self::top_m).
+///
+/// # Example
+///
+/// ```no_run
+/// # use qtty::Minutes;
+/// # use virolai::algorithms::rl::RLConfig;
+/// let config = RLConfig::builder()
+///     .world_size(20.0, 20.0)
+///     .spawn_rate(0.4)
+///     .top_m(8)
+///     .step_duration(Minutes::new(5.0))
+///     .build()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct RLConfigBuilder<U: Unit = Second> {
+    config: RLConfig<U>,
+}
+
+impl RLConfigBuilder {
+    /// Starts from the default configuration.
+    pub fn new() -> Self {
         Self {
-            world_width: 10.0,
-            world_height: 10.0,
-            episode_horizon: 100,
-            delta_t: 1.0,
-            speeds,
-            collection_radius: 1.0,
-            spawn_rate: 0.3,
-            max_active_tasks: 20,
-            top_m: 5,
-            reward_time_penalty: 0.01,
-            reward_progress_alpha: 0.1,
-            reward_expiry_beta: 0.5,
-            reward_coverage_gamma: 0.05,
-            gamma: 0.99,
+            config: RLConfig::default(),
         }
     }
 }
 
+impl<U: Unit> RLConfigBuilder<U> {
+    /// Sets the width and height of the world.
+    pub fn world_size(mut self, width: f64, height: f64) -> Self {
+        self.config.world_width = width;
+        self.config.world_height = height;
+        self
+    }
+
+    /// Adds a keep-out zone.
+    pub fn obstacle(mut self, obstacle: Obstacle) -> Self {
+        self.config.obstacles.push(obstacle);
+        self
+    }
+
+    /// Sets the number of steps per episode.
+    pub fn episode_horizon(mut self, steps: u32) -> Self {
+        self.config.episode_horizon = steps;
+        self
+    }
+
+    /// Sets the duration of one time step.
+    pub fn delta_t(mut self, delta_t: f64) -> Self {
+        self.config.delta_t = delta_t;
+        self
+    }
+
+    /// Sets the scheduling time one step stands for, which also sets the
+    /// unit of the configuration.
+    pub fn step_duration<V: Unit>(self, step_duration: Quantity<V>) -> RLConfigBuilder<V> {
+        RLConfigBuilder {
+            config: self.config.with_step_duration(step_duration),
+        }
+    }
+
+    /// Sets the maximum speed of one agent type.
+    pub fn speed(mut self, agent_type: AgentType, speed: f64) -> Self {
+        self.config.speeds.insert(agent_type, speed);
+        self
+    }
+
+    /// Restricts the actions of one agent type.
+    pub fn action_space(mut self, agent_type: AgentType, space: ActionSpace) -> Self {
+        self.config.action_spaces.insert(agent_type, space);
+        self
+    }
+
+    /// Sets the global collection radius.
+    pub fn collection_radius(mut self, radius: f64) -> Self {
+        self.config.collection_radius = radius;
+        self
+    }
+
+    /// Sets the per-step task spawn probability.
+    pub fn spawn_rate(mut self, rate: f64) -> Self {
+        self.config.spawn_rate = rate;
+        self
+    }
+
+    /// Sets the maximum number of simultaneously active tasks.
+    pub fn max_active_tasks(mut self, count: usize) -> Self {
+        self.config.max_active_tasks = count;
+        self
+    }
+
+    /// Sets how agents collect tasks.
+    pub fn collection(mut self, mode: CollectionMode) -> Self {
+        self.config.collection = mode;
+        self
+    }
+
+    /// Gives agents a battery.
+    pub fn energy(mut self, energy: EnergyModel) -> Self {
+        self.config.energy = Some(energy);
+        self
+    }
+
+    /// Sets the number of candidate tasks per observation.
+    pub fn top_m(mut self, top_m: usize) -> Self {
+        self.config.top_m = top_m;
+        self
+    }
+
+    /// Limits what agents see to their surroundings.
+    pub fn sensing(mut self, sensing: Sensing) -> Self {
+        self.config.sensing = Some(sensing);
+        self
+    }
+
+    /// Selects the per-task observation features.
+    pub fn task_features(mut self, features: TaskFeatures) -> Self {
+        self.config.task_features = features;
+        self
+    }
+
+    /// Sets the reward shaping coefficients: time penalty, progress α,
+    /// expiry β and coverage γ.
+    pub fn rewards(mut self, time_penalty: f64, progress: f64, expiry: f64, coverage: f64) -> Self {
+        self.config.reward_time_penalty = time_penalty;
+        self.config.reward_progress_alpha = progress;
+        self.config.reward_expiry_beta = expiry;
+        self.config.reward_coverage_gamma = coverage;
+        self
+    }
+
+    /// Sets the schedule-aware reward weights: utilization gain and placed
+    /// priority.
+    pub fn schedule_rewards(mut self, utilization: f64, priority: f64) -> Self {
+        self.config.reward_schedule_utilization = utilization;
+        self.config.reward_schedule_priority = priority;
+        self
+    }
+
+    /// Sets the discount factor.
+    pub fn gamma(mut self, gamma: f64) -> Self {
+        self.config.gamma = gamma;
+        self
+    }
+
+    /// Sets the actor architecture.
+    pub fn actor(mut self, actor: ActorKind) -> Self {
+        self.config.actor = actor;
+        self
+    }
+
+    /// Validates and returns the configuration.
+    ///
+    /// # Errors
+    ///
+    /// See [`RLConfig::validate`].
+    pub fn build(self) -> Result<RLConfig<U>, RLConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+impl Default for RLConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<U: Unit> From<RLConfig<U>> for RLConfigBuilder<U> {
+    /// Starts from an existing configuration, such as a preset.
+    fn from(config: RLConfig<U>) -> Self {
+        Self { config }
+    }
+}
+
+impl Default for RLConfig {
+    fn default() -> Self {
+        default_config()
+    }
+}
+
+/// The default configuration, with steps of one `U`.
+fn default_config<U: Unit>() -> RLConfig<U> {
+    let mut speeds = HashMap::new();
+    speeds.insert(AgentType::Young, 3.0);
+    speeds.insert(AgentType::Middle, 2.0);
+    speeds.insert(AgentType::Old, 1.0);
+
+    RLConfig {
+        world_width: 10.0,
+        world_height: 10.0,
+        obstacles: Vec::new(),
+        episode_horizon: 100,
+        delta_t: 1.0,
+        step_duration: Quantity::new(1.0),
+        speeds,
+        action_spaces: HashMap::new(),
+        collection_radius: 1.0,
+        spawn_rate: 0.3,
+        max_active_tasks: 20,
+        collection: CollectionMode::Instant,
+        energy: None,
+        top_m: 5,
+        sensing: None,
+        task_features: TaskFeatures::all(),
+        reward_time_penalty: 0.01,
+        reward_progress_alpha: 0.1,
+        reward_expiry_beta: 0.5,
+        reward_coverage_gamma: 0.05,
+        reward_schedule_utilization: 1.0,
+        reward_schedule_priority: 1.0,
+        gamma: 0.99,
+        actor: ActorKind::FeedForward,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,4 +1023,304 @@ mod tests {
         assert_eq!(cfg.speed_for(AgentType::Young), 3.0);
         assert_eq!(cfg.speed_for(AgentType::Old), 1.0);
     }
+
+    // ── validation ────────────────────────────────────────────────────
+
+    #[test]
+    fn presets_are_valid() {
+        for cfg in [RLConfig::small(), RLConfig::medium(), RLConfig::large()] {
+            assert_eq!(cfg.validate(), Ok(()));
+        }
+        assert!(RLConfig::small().top_m < RLConfig::large().top_m);
+    }
+
+    #[test]
+    fn builder_sets_fields() {
+        let cfg = RLConfig::builder()
+            .world_size(20.0, 30.0)
+            .top_m(8)
+            .speed(AgentType::Old, 0.5)
+            .gamma(1.0)
+            .build()
+            .unwrap();
+        assert_eq!((cfg.world_width, cfg.world_height), (20.0, 30.0));
+        assert_eq!(cfg.action_dim(), 9);
+        assert_eq!(cfg.speed_for(AgentType::Old), 0.5);
+    }
+
+    #[test]
+    fn builder_starts_from_preset() {
+        let cfg = RLConfigBuilder::from(RLConfig::large())
+            .spawn_rate(0.1)
+            .build()
+            .unwrap();
+        assert_eq!(cfg.top_m, 10);
+        assert_eq!(cfg.spawn_rate, 0.1);
+    }
+
+    #[test]
+    fn invalid_fields_are_rejected() {
+        let err = |b: RLConfigBuilder| b.build().unwrap_err();
+        assert_eq!(
+            err(RLConfig::builder().episode_horizon(0)),
+            RLConfigError::ZeroHorizon
+        );
+        assert_eq!(
+            err(RLConfig::builder().spawn_rate(1.5)),
+            RLConfigError::InvalidSpawnRate(1.5)
+        );
+        assert_eq!(err(RLConfig::builder().top_m(0)), RLConfigError::ZeroTopM);
+        assert_eq!(
+            err(RLConfig::builder().world_size(0.0, 10.0)),
+            RLConfigError::InvalidWorldSize {
+                width: 0.0,
+                height: 10.0
+            }
+        );
+        assert_eq!(
+            err(RLConfig::builder().speed(AgentType::Young, -1.0)),
+            RLConfigError::InvalidSpeed {
+                agent_type: AgentType::Young,
+                speed: -1.0
+            }
+        );
+        assert_eq!(
+            err(RLConfig::builder().gamma(0.0)),
+            RLConfigError::InvalidGamma(0.0)
+        );
+        assert!(matches!(
+            err(RLConfig::builder().schedule_rewards(1.0, -1.0)),
+            RLConfigError::InvalidRewardCoefficient {
+                name: "reward_schedule_priority",
+                ..
+            }
+        ));
+        assert!(matches!(
+            err(RLConfig::builder().rewards(0.01, f64::NAN, 0.5, 0.05)),
+            RLConfigError::InvalidRewardCoefficient {
+                name: "reward_progress_alpha",
+                ..
+            }
+        ));
+        assert!(matches!(
+            err(RLConfig::builder().sensing(Sensing::new(0.0, 1.0))),
+            RLConfigError::InvalidSensing {
+                field: "sensing_radius",
+                ..
+            }
+        ));
+        assert!(matches!(
+            err(RLConfig::builder().sensing(Sensing::new(2.0, -1.0))),
+            RLConfigError::InvalidSensing {
+                field: "comm_radius",
+                ..
+            }
+        ));
+        assert!(RLConfig::builder()
+            .sensing(Sensing::new(2.0, 0.0))
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn step_duration_maps_steps_onto_time() {
+        use qtty::Minute;
+
+        let config = RLConfig::builder()
+            .episode_horizon(48)
+            .step_duration(Quantity::<Minute>::new(15.0))
+            .build()
+            .unwrap();
+        assert_eq!(config.time_at(4), Quantity::<Minute>::new(60.0));
+        assert_eq!(config.horizon_duration(), Quantity::<Minute>::new(720.0));
+        assert_eq!(config.steps_for(Quantity::new(60.0)), 4);
+        assert_eq!(config.steps_for(Quantity::new(61.0)), 5);
+        assert_eq!(config.steps_for(Quantity::new(-5.0)), 0);
+
+        assert_eq!(
+            RLConfig::default()
+                .with_step_duration(Quantity::<Minute>::new(0.0))
+                .validate(),
+            Err(RLConfigError::InvalidStepDuration(0.0))
+        );
+    }
+
+    #[test]
+    fn collection_radius_must_fit_the_world() {
+        let err = RLConfig::builder()
+            .world_size(4.0, 10.0)
+            .collection_radius(5.0)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            RLConfigError::InvalidCollectionRadius {
+                radius: 5.0,
+                world: 4.0
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Collection radius must be positive and smaller than the world (4), got 5"
+        );
+    }
+
+    #[test]
+    fn action_spaces_restrict_per_type() {
+        let old = ActionSpace {
+            max_targets: Some(2),
+            max_range: Some(3.0),
+        };
+        let cfg = RLConfig::builder()
+            .action_space(AgentType::Old, old)
+            .build()
+            .unwrap();
+        assert_eq!(cfg.action_space_for(AgentType::Old), old);
+        assert_eq!(
+            cfg.action_space_for(AgentType::Young),
+            ActionSpace::unrestricted()
+        );
+
+        assert!(old.allows(0, 100.0));
+        assert!(old.allows(2, 3.0));
+        assert!(!old.allows(3, 1.0));
+        assert!(!old.allows(1, 3.5));
+        assert!(ActionSpace::unrestricted().allows(5, 1e9));
+
+        assert_eq!(
+            RLConfig::builder()
+                .action_space(
+                    AgentType::Middle,
+                    ActionSpace {
+                        max_targets: None,
+                        max_range: Some(0.0),
+                    },
+                )
+                .build()
+                .unwrap_err(),
+            RLConfigError::InvalidActionRange {
+                agent_type: AgentType::Middle,
+                range: 0.0
+            }
+        );
+    }
+
+    #[test]
+    fn task_features_set_the_observation_dim() {
+        let features = TaskFeatures::all()
+            .without(TaskFeature::Appearances)
+            .without(TaskFeature::Heading);
+        assert_eq!(features.dim(), 7);
+        assert_eq!(features.offset(TaskFeature::Requirements), Some(4));
+        assert_eq!(features.offset(TaskFeature::Heading), None);
+        assert_eq!(features.bits(), 0b001111);
+        assert_eq!(TaskFeatures::all().dim(), RLConfig::TASK_FEATURE_DIM);
+
+        let cfg = RLConfig::builder().task_features(features).build().unwrap();
+        assert_eq!(cfg.observation_dim(), 5 + cfg.top_m * 7);
+        assert_eq!(
+            RLConfig::builder()
+                .task_features(TaskFeatures::none())
+                .build()
+                .unwrap_err(),
+            RLConfigError::NoTaskFeatures
+        );
+    }
+
+    #[test]
+    fn progressive_collection_adds_progress_features() {
+        let cfg = RLConfig::builder()
+            .collection(CollectionMode::Progressive { work_units: 4 })
+            .build()
+            .unwrap();
+        assert_eq!(cfg.observation_dim(), 5 + cfg.top_m * 11);
+        assert_eq!(cfg.work_units_for(None), 4);
+        assert_eq!(cfg.work_units_for(Some(9)), 9);
+        assert_eq!(RLConfig::default().work_units_for(Some(9)), 1);
+        assert_eq!(
+            RLConfig::builder()
+                .collection(CollectionMode::Progressive { work_units: 0 })
+                .build()
+                .unwrap_err(),
+            RLConfigError::ZeroWorkUnits
+        );
+    }
+
+    #[test]
+    fn energy_adds_a_charge_feature() {
+        use crate::algorithms::rl::energy::ChargingZone;
+        use crate::algorithms::rl::types::Position;
+
+        let cfg = RLConfig::builder()
+            .energy(EnergyModel::default())
+            .build()
+            .unwrap();
+        assert_eq!(cfg.observation_dim(), 5 + cfg.top_m * 10 + 1);
+        assert_eq!(
+            RLConfig::builder()
+                .energy(EnergyModel {
+                    capacity: 0.0,
+                    ..EnergyModel::default()
+                })
+                .build()
+                .unwrap_err(),
+            RLConfigError::InvalidEnergyModel {
+                field: "capacity",
+                value: 0.0
+            }
+        );
+        assert_eq!(
+            RLConfig::builder()
+                .energy(EnergyModel {
+                    charging_zones: vec![ChargingZone::new(Position::origin(), -1.0)],
+                    ..EnergyModel::default()
+                })
+                .build()
+                .unwrap_err(),
+            RLConfigError::InvalidEnergyModel {
+                field: "charging_zones.radius",
+                value: -1.0
+            }
+        );
+    }
+
+    #[test]
+    fn obstacles_add_blocked_features() {
+        let wall = Obstacle::rect(Position::new(2.0, 0.0), Position::new(3.0, 6.0));
+        let cfg = RLConfig::builder().obstacle(wall).build().unwrap();
+        assert_eq!(cfg.observation_dim(), 5 + cfg.top_m * 11);
+
+        let err = |obstacle| RLConfig::builder().obstacle(obstacle).build().unwrap_err();
+        assert_eq!(
+            err(Obstacle::polygon(vec![
+                Position::origin(),
+                Position::new(1.0, 1.0)
+            ])),
+            RLConfigError::InvalidObstacle { index: 0 }
+        );
+        assert_eq!(
+            err(Obstacle::rect(
+                Position::new(4.0, 4.0),
+                Position::new(6.0, 6.0)
+            )),
+            RLConfigError::ObstacleCoversStart { index: 0 }
+        );
+    }
+
+    #[test]
+    fn recurrent_actor_needs_hidden_state() {
+        let cfg = RLConfig::builder()
+            .actor(ActorKind::Recurrent { hidden_dim: 32 })
+            .build()
+            .unwrap();
+        assert_eq!(cfg.actor, ActorKind::Recurrent { hidden_dim: 32 });
+        assert_eq!(RLConfig::default().actor, ActorKind::FeedForward);
+        assert_eq!(
+            RLConfig::builder()
+                .actor(ActorKind::Recurrent { hidden_dim: 0 })
+                .build()
+                .unwrap_err(),
+            RLConfigError::ZeroHiddenDim
+        );
+    }
 }
@@ -0,0 +1,503 @@
+//! Policy distillation into a lightweight task scorer.
+//!
+//! A trained actor needs libtorch at inference time, which some deployments
+//! cannot ship. [`DistilledScorer`] is a linear model or a one-hidden-layer
+//! MLP over a handful of scheduling-domain [`task_features`], trained with a
+//! pairwise ranking loss to reproduce the order in which the actor picks
+//! tasks (see `RLScheduler::distill`, feature `rl-nn`). [`DistilledScheduler`]
+//! then schedules with it in plain Rust: tasks are placed at their earliest
+//! free position in descending score order.
+//!
+//! Training and inference are plain `f64` arithmetic; weights can be stored
+//! with serde or pasted into source with [`DistilledScorer::to_rust`].
+
+use qtty::{Quantity, Unit};
+
+use crate::algorithms::greedy::find_earliest_non_overlapping;
+use crate::algorithms::SchedulingAlgorithm;
+use crate::schedule::errors::ScheduleError;
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+use crate::synthetic::SplitMix64;
+
+/// Number of features produced by [`task_features`].
+pub const TASK_FEATURES: usize = 6;
+
+/// Features of a task as seen by a [`DistilledScorer`].
+///
+/// Durations are relative to the horizon length:
+///
+/// | index | feature |
+/// |---|---|
+/// | 0 | priority |
+/// | 1 | size |
+/// | 2 | total window length inside the horizon |
+/// | 3 | `ln(1 + window length / size)` (flexibility) |
+/// | 4 | offset of the first window start from the horizon start |
+/// | 5 | `ln(1 + number of windows)` |
+///
+/// Returns `None` if the task has no window inside the horizon.
+pub fn task_features<T, U>(
+    task: &T,
+    id: &str,
+    space: &SolutionSpace<U>,
+    horizon: Interval<U>,
+) -> Option<[f64; TASK_FEATURES]>
+where
+    T: Task<U>,
+    U: Unit,
+{
+    let (h_start, h_end) = (horizon.start().value(), horizon.end().value());
+    let length = h_end - h_start;
+    if length <= 0.0 {
+        return None;
+    }
+    let windows: Vec<(f64, f64)> = space
+        .get_intervals(id)?
+        .iter()
+        .map(|w| (w.start().value().max(h_start), w.end().value().min(h_end)))
+        .filter(|(s, e)| e > s)
+        .collect();
+    let first = windows.first()?.0;
+
+    let size = task.size_on_axis().value();
+    let capacity: f64 = windows.iter().map(|(s, e)| e - s).sum();
+    let flexibility = if size > 0.0 {
+        (capacity / size).ln_1p()
+    } else {
+        0.0
+    };
+    Some([
+        task.priority() as f64,
+        size / length,
+        capacity / length,
+        flexibility,
+        (first - h_start) / length,
+        (windows.len() as f64).ln_1p(),
+    ])
+}
+
+/// One training example: the features of a problem's tasks and the
+/// teacher's preferred order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankingExample {
+    /// Features of every task, in any order.
+    pub features: Vec<[f64; TASK_FEATURES]>,
+    /// Indices into `features`, most preferred first. Tasks not listed rank
+    /// below all listed ones.
+    pub order: Vec<usize>,
+}
+
+impl RankingExample {
+    /// Pairs `(preferred, other)` implied by the order.
+    fn pairs(&self) -> Vec<(usize, usize)> {
+        let mut rank = vec![usize::MAX; self.features.len()];
+        for (r, &i) in self.order.iter().enumerate() {
+            if let Some(slot) = rank.get_mut(i) {
+                *slot = (*slot).min(r);
+            }
+        }
+        let mut pairs = Vec::new();
+        for (i, &ri) in rank.iter().enumerate() {
+            for (j, &rj) in rank.iter().enumerate() {
+                if ri < rj {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    }
+}
+
+/// Hyperparameters of [`DistilledScorer::fit`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistillConfig {
+    /// Hidden units; `0` trains a linear scorer.
+    pub hidden: usize,
+    /// Passes over all preference pairs.
+    pub epochs: usize,
+    /// Gradient-descent step size.
+    pub learning_rate: f64,
+    /// L2 penalty on the weights.
+    pub weight_decay: f64,
+    /// Seed of the weight initialization.
+    pub seed: u64,
+}
+
+impl Default for DistillConfig {
+    fn default() -> Self {
+        Self {
+            hidden: 8,
+            epochs: 200,
+            learning_rate: 0.05,
+            weight_decay: 1e-4,
+            seed: 0,
+        }
+    }
+}
+
+/// Small task-scoring model: higher scores are scheduled first.
+///
+/// Linear when `w1` is empty (`score = w2 · x + b2`), otherwise a ReLU MLP
+/// (`score = w2 · relu(w1 x + b1) + b2`).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistilledScorer {
+    /// Hidden-layer weights, one row per hidden unit.
+    pub w1: Vec<[f64; TASK_FEATURES]>,
+    /// Hidden-layer biases.
+    pub b1: Vec<f64>,
+    /// Output weights over hidden units (or over features when linear).
+    pub w2: Vec<f64>,
+    /// Output bias.
+    pub b2: f64,
+}
+
+impl DistilledScorer {
+    /// A linear scorer with the given feature weights.
+    pub fn linear(weights: [f64; TASK_FEATURES], bias: f64) -> Self {
+        Self {
+            w1: Vec::new(),
+            b1: Vec::new(),
+            w2: weights.to_vec(),
+            b2: bias,
+        }
+    }
+
+    /// Whether this scorer has no hidden layer.
+    pub fn is_linear(&self) -> bool {
+        self.w1.is_empty()
+    }
+
+    /// Score of a task's features.
+    pub fn score(&self, x: &[f64; TASK_FEATURES]) -> f64 {
+        if self.is_linear() {
+            return dot(&self.w2, x) + self.b2;
+        }
+        let hidden = self.hidden(x);
+        dot(&self.w2, &hidden) + self.b2
+    }
+
+    fn hidden(&self, x: &[f64; TASK_FEATURES]) -> Vec<f64> {
+        self.w1
+            .iter()
+            .zip(&self.b1)
+            .map(|(w, b)| (dot(w, x) + b).max(0.0))
+            .collect()
+    }
+
+    /// Trains a scorer to reproduce the teachers' orders.
+    ///
+    /// Minimizes the pairwise logistic loss `ln(1 + e^{s_j − s_i})` over every
+    /// pair where task `i` is preferred to task `j`, with full-batch gradient
+    /// descent.
+    pub fn fit(examples: &[RankingExample], config: &DistillConfig) -> Self {
+        let mut rng = SplitMix64::new(config.seed);
+        let scale = 1.0 / (TASK_FEATURES as f64).sqrt();
+        let mut model = if config.hidden == 0 {
+            Self::linear([0.0; TASK_FEATURES], 0.0)
+        } else {
+            Self {
+                w1: (0..config.hidden)
+                    .map(|_| std::array::from_fn(|_| rng.range(-1.0, 1.0) * scale))
+                    .collect(),
+                b1: vec![0.0; config.hidden],
+                w2: (0..config.hidden)
+                    .map(|_| rng.range(-1.0, 1.0) * scale)
+                    .collect(),
+                b2: 0.0,
+            }
+        };
+
+        let pairs: Vec<(&RankingExample, Vec<(usize, usize)>)> =
+            examples.iter().map(|e| (e, e.pairs())).collect();
+        let n_pairs: usize = pairs.iter().map(|(_, p)| p.len()).sum();
+        if n_pairs == 0 {
+            return model;
+        }
+
+        for _ in 0..config.epochs {
+            let mut grad = model.zeros_like();
+            for (example, example_pairs) in &pairs {
+                for &(i, j) in example_pairs {
+                    let (xi, xj) = (&example.features[i], &example.features[j]);
+                    // d/d(s_i − s_j) of ln(1 + e^{−(s_i − s_j)})
+                    let margin = model.score(xi) - model.score(xj);
+                    let coef = -1.0 / (1.0 + margin.exp());
+                    model.accumulate(&mut grad, xi, coef);
+                    model.accumulate(&mut grad, xj, -coef);
+                }
+            }
+            model.apply(&grad, n_pairs as f64, config);
+        }
+        model
+    }
+
+    fn zeros_like(&self) -> Self {
+        Self {
+            w1: vec![[0.0; TASK_FEATURES]; self.w1.len()],
+            b1: vec![0.0; self.b1.len()],
+            w2: vec![0.0; self.w2.len()],
+            b2: 0.0,
+        }
+    }
+
+    /// Adds `coef · ∂score(x)/∂θ` to `grad`.
+    fn accumulate(&self, grad: &mut Self, x: &[f64; TASK_FEATURES], coef: f64) {
+        grad.b2 += coef;
+        if self.is_linear() {
+            for (g, v) in grad.w2.iter_mut().zip(x) {
+                *g += coef * v;
+            }
+            return;
+        }
+        for (k, h) in self.hidden(x).into_iter().enumerate() {
+            grad.w2[k] += coef * h;
+            if h > 0.0 {
+                let upstream = coef * self.w2[k];
+                grad.b1[k] += upstream;
+                for (g, v) in grad.w1[k].iter_mut().zip(x) {
+                    *g += upstream * v;
+                }
+            }
+        }
+    }
+
+    fn apply(&mut self, grad: &Self, n: f64, config: &DistillConfig) {
+        let (lr, decay) = (config.learning_rate, config.weight_decay);
+        let step = |w: &mut f64, g: f64| *w -= lr * (g / n + decay * *w);
+        for (row, grow) in self.w1.iter_mut().zip(&grad.w1) {
+            for (w, &g) in row.iter_mut().zip(grow) {
+                step(w, g);
+            }
+        }
+        for (w, &g) in self.b1.iter_mut().zip(&grad.b1) {
+            *w -= lr * g / n;
+        }
+        for (w, &g) in self.w2.iter_mut().zip(&grad.w2) {
+            step(w, g);
+        }
+        self.b2 -= lr * grad.b2 / n;
+    }
+
+    /// Fraction of the examples' preference pairs ordered correctly.
+    ///
+    /// Returns 1.0 when there are no pairs.
+    pub fn agreement(&self, examples: &[RankingExample]) -> f64 {
+        let (mut right, mut total) = (0usize, 0usize);
+        for example in examples {
+            for (i, j) in example.pairs() {
+                total += 1;
+                if self.score(&example.features[i]) > self.score(&example.features[j]) {
+                    right += 1;
+                }
+            }
+        }
+        if total == 0 {
+            1.0
+        } else {
+            right as f64 / total as f64
+        }
+    }
+
+    /// A Rust expression that rebuilds this scorer, for embedding trained
+    /// weights in source code.
+    pub fn to_rust(&self) -> String {
+        format!(
+            "DistilledScorer {{ w1: vec!{:?}, b1: vec!{:?}, w2: vec!{:?}, b2: {:?} }}",
+            self.w1, self.b1, self.w2, self.b2
+        )
+    }
+}
+
+fn dot(w: &[f64], x: &[f64]) -> f64 {
+    w.iter().zip(x).map(|(a, b)| a * b).sum()
+}
+
+/// Schedules tasks in descending [`DistilledScorer`] order.
+///
+/// Each task is placed at the earliest position inside its windows and the
+/// horizon that does not overlap already scheduled tasks. Ties are broken by
+/// task ID. Needs no neural-network runtime.
+#[derive(Debug, Clone)]
+pub struct DistilledScheduler {
+    scorer: DistilledScorer,
+}
+
+impl DistilledScheduler {
+    /// Creates a scheduler using `scorer`.
+    pub fn new(scorer: DistilledScorer) -> Self {
+        Self { scorer }
+    }
+
+    /// The scorer in use.
+    pub fn scorer(&self) -> &DistilledScorer {
+        &self.scorer
+    }
+}
+
+impl<T, U, D, E> SchedulingAlgorithm<T, U, D, E> for DistilledScheduler
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    fn schedule(
+        &self,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+    ) -> Schedule<U> {
+        let mut ranked: Vec<(f64, &str, f64)> = blocks
+            .iter()
+            .flat_map(|b| b.tasks())
+            .filter_map(|(id, task)| {
+                let features = task_features(task, id, solution_space, horizon.interval())?;
+                Some((
+                    self.scorer.score(&features),
+                    id,
+                    task.size_on_axis().value(),
+                ))
+            })
+            .collect();
+        // Highest score first and NaN scores last, ties by task ID.
+        ranked.sort_by(|a, b| {
+            a.0.is_nan()
+                .cmp(&b.0.is_nan())
+                .then_with(|| b.0.total_cmp(&a.0))
+                .then_with(|| a.1.cmp(b.1))
+        });
+
+        let (h_start, h_end) = (horizon.start().value(), horizon.end().value());
+        let mut schedule = Schedule::new();
+        for (_, id, size) in ranked {
+            let windows: Vec<(f64, f64)> = solution_space
+                .get_intervals(id)
+                .into_iter()
+                .flatten()
+                .map(|w| (w.start().value(), w.end().value()))
+                .collect();
+            if let Some(start) =
+                find_earliest_non_overlapping(&windows, size, h_start, h_end, &schedule)
+            {
+                let interval = Interval::new(Quantity::new(start), Quantity::new(start + size));
+                // Placements never overlap; an ID repeated across blocks
+                // keeps its first entry, as in EST.
+                if let Err(err) = schedule.add(id, interval) {
+                    debug_assert!(matches!(err, ScheduleError::DuplicateTaskId(_)), "{err}");
+                }
+            }
+        }
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{block_and_space, hz, iv, TestTask};
+
+    fn example(priorities: &[f64]) -> RankingExample {
+        // Teacher prefers higher priority.
+        let features: Vec<[f64; TASK_FEATURES]> = priorities
+            .iter()
+            .map(|&p| [p, 0.1, 0.5, 1.0, 0.0, 0.7])
+            .collect();
+        let mut order: Vec<usize> = (0..priorities.len()).collect();
+        order.sort_by(|&a, &b| priorities[b].total_cmp(&priorities[a]));
+        RankingExample { features, order }
+    }
+
+    #[test]
+    fn features_are_relative_to_horizon() {
+        let mut ss = SolutionSpace::new();
+        ss.set_intervals("a", vec![iv(-10.0, 20.0), iv(50.0, 70.0), iv(200.0, 300.0)]);
+        let task = TestTask::new("a", 10.0).with_priority(3);
+
+        let f = task_features(&task, "a", &ss, iv(0.0, 100.0)).unwrap();
+        assert_eq!(f[0], 3.0);
+        assert!((f[1] - 0.1).abs() < 1e-12);
+        assert!((f[2] - 0.4).abs() < 1e-12);
+        assert!((f[3] - 5.0f64.ln()).abs() < 1e-12);
+        assert_eq!(f[4], 0.0);
+        assert!((f[5] - 3.0f64.ln()).abs() < 1e-12);
+
+        assert!(task_features(&task, "a", &ss, iv(400.0, 500.0)).is_none());
+        assert!(task_features(&task, "missing", &ss, iv(0.0, 100.0)).is_none());
+    }
+
+    #[test]
+    fn unlisted_tasks_rank_last() {
+        let e = RankingExample {
+            features: vec![[0.0; TASK_FEATURES]; 3],
+            order: vec![2],
+        };
+        let mut pairs = e.pairs();
+        pairs.sort_unstable();
+        assert_eq!(pairs, vec![(2, 0), (2, 1)]);
+    }
+
+    #[test]
+    fn fit_learns_teacher_order() {
+        let examples = [example(&[1.0, 5.0, 3.0]), example(&[2.0, 0.0, 4.0, 6.0])];
+        for hidden in [0, 4] {
+            let scorer = DistilledScorer::fit(
+                &examples,
+                &DistillConfig {
+                    hidden,
+                    ..DistillConfig::default()
+                },
+            );
+            assert_eq!(scorer.is_linear(), hidden == 0);
+            assert_eq!(scorer.agreement(&examples), 1.0);
+        }
+    }
+
+    #[test]
+    fn fit_is_deterministic() {
+        let examples = [example(&[1.0, 2.0])];
+        let config = DistillConfig::default();
+        assert_eq!(
+            DistilledScorer::fit(&examples, &config),
+            DistilledScorer::fit(&examples, &config)
+        );
+    }
+
+    #[test]
+    fn to_rust_lists_weights() {
+        let scorer = DistilledScorer::linear([1.0, 0.0, 0.0, 0.0, 0.0, -0.5], 0.25);
+        assert_eq!(
+            scorer.to_rust(),
+            "DistilledScorer { w1: vec![], b1: vec![], w2: vec![1.0, 0.0, 0.0, 0.0, 0.0, -0.5], b2: 0.25 }"
+        );
+    }
+
+    #[test]
+    fn scheduler_places_by_score() {
+        let (block, ss) = block_and_space(&[
+            ("low", 10.0, 1, iv(0.0, 15.0)),
+            ("high", 10.0, 9, iv(0.0, 15.0)),
+        ]);
+        let by_priority = DistilledScorer::linear([1.0, 0.0, 0.0, 0.0, 0.0, 0.0], 0.0);
+
+        let schedule = DistilledScheduler::new(by_priority).schedule(&[block], &ss, hz(0.0, 100.0));
+
+        assert_eq!(schedule.get_interval("high"), Some(iv(0.0, 10.0)));
+        assert!(!schedule.contains_task("low"));
+    }
+
+    #[test]
+    fn scheduler_ranks_nan_scores_last() {
+        // An infinite priority weight scores "a" (priority 0) NaN, "b" +∞.
+        let (block, ss) =
+            block_and_space(&[("a", 10.0, 0, iv(0.0, 15.0)), ("b", 10.0, 9, iv(0.0, 15.0))]);
+        let scorer = DistilledScorer::linear([f64::INFINITY, 0.0, 0.0, 0.0, 0.0, 0.0], 0.0);
+
+        let schedule = DistilledScheduler::new(scorer).schedule(&[block], &ss, hz(0.0, 100.0));
+
+        assert_eq!(schedule.get_interval("b"), Some(iv(0.0, 10.0)));
+        assert!(!schedule.contains_task("a"));
+    }
+}
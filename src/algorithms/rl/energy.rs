@@ -0,0 +1,119 @@
+//! Battery model for agents with limited endurance.
+//!
+//! Without an [`EnergyModel`] in
+//! [`RLConfig::energy`](super::config::RLConfig::energy), agents move and
+//! collect for free. With one, every agent starts an episode with a full
+//! battery of [`capacity`](EnergyModel::capacity):
+//!
+//! - moving costs [`move_cost`](EnergyModel::move_cost) per unit of
+//!   distance, and an agent never moves farther than its battery allows;
+//! - every step an agent helps work on a task costs
+//!   [`collect_cost`](EnergyModel::collect_cost);
+//! - an agent inside a [`ChargingZone`] at the end of a step regains
+//!   [`charge_rate`](EnergyModel::charge_rate), up to the capacity.
+//!
+//! An agent with an empty battery stays where it is and does not count
+//! towards any coalition until it is recharged, which it can only be if it
+//! ran out inside a charging zone. The reward subtracts
+//! [`depletion_penalty`](EnergyModel::depletion_penalty) per empty agent and
+//! step, and observations end with each agent's charge (see
+//! [`RLConfig::energy_dim`](super::config::RLConfig::energy_dim)).
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use virolai::algorithms::rl::{ChargingZone, EnergyModel, Position, RLConfig};
+//! let config = RLConfig::builder()
+//!     .energy(EnergyModel {
+//!         charging_zones: vec![ChargingZone::new(Position::new(5.0, 5.0), 1.0)],
+//!         ..EnergyModel::default()
+//!     })
+//!     .build()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use super::agent::AgentState;
+use super::types::Position;
+
+/// A disc where agents recharge.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChargingZone {
+    /// Center of the zone.
+    pub center: Position,
+    /// Radius of the zone.
+    pub radius: f64,
+}
+
+impl ChargingZone {
+    /// Creates a zone of `radius` around `center`.
+    pub fn new(center: Position, radius: f64) -> Self {
+        Self { center, radius }
+    }
+
+    /// Returns true if `position` is inside the zone, border included.
+    pub fn contains(&self, position: &Position) -> bool {
+        self.center.distance_to(position) <= self.radius
+    }
+}
+
+/// Energy budget of the agents, see the [module documentation](self).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnergyModel {
+    /// Energy of a full battery.
+    pub capacity: f64,
+    /// Energy spent per unit of distance moved.
+    pub move_cost: f64,
+    /// Energy spent per step of work on a task.
+    pub collect_cost: f64,
+    /// Energy regained per step inside a charging zone.
+    pub charge_rate: f64,
+    /// Where agents recharge.
+    pub charging_zones: Vec<ChargingZone>,
+    /// Reward penalty per agent with an empty battery and step.
+    pub depletion_penalty: f64,
+}
+
+impl EnergyModel {
+    /// Distance an agent with `energy` left can still move.
+    pub fn range(&self, energy: f64) -> f64 {
+        if self.move_cost > 0.0 {
+            energy.max(0.0) / self.move_cost
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// Returns true if `position` is inside a charging zone.
+    pub fn charges_at(&self, position: &Position) -> bool {
+        self.charging_zones
+            .iter()
+            .any(|zone| zone.contains(position))
+    }
+
+    /// Recharges `agent` if it is inside a charging zone.
+    pub(crate) fn recharge(&self, agent: &mut AgentState) {
+        if !self.charges_at(&agent.position) {
+            return;
+        }
+        if let Some(energy) = &mut agent.energy {
+            *energy = (*energy + self.charge_rate).min(self.capacity);
+        }
+    }
+}
+
+impl Default for EnergyModel {
+    /// A battery for 20 units of distance, recharged in 5 steps, without
+    /// charging zones.
+    fn default() -> Self {
+        Self {
+            capacity: 1.0,
+            move_cost: 0.05,
+            collect_cost: 0.01,
+            charge_rate: 0.2,
+            charging_zones: Vec::new(),
+            depletion_penalty: 0.1,
+        }
+    }
+}
@@ -3,37 +3,78 @@
 //! Implements the simulation loop described in §8 of the algorithm design:
 //! spawn → act → move → tick → collect → expire → reward → transition.
 
+use qtty::{Quantity, Second, Unit};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 
 use super::agent::AgentState;
-use super::config::RLConfig;
+use super::config::{RLConfig, RLConfigError};
+use super::hooks::{StepContext, StepHook};
 use super::observation::ObservationBuilder;
 use super::reward::RewardComputer;
 use super::task_pool::{TaskPool, TaskTemplate};
 use super::types::{AgentType, Position};
+use crate::Id;
 
 /// Result of a single environment step.
 #[derive(Debug, Clone)]
-pub struct StepResult {
+pub struct StepResult<U: Unit = Second> {
     /// Per-agent observations after the step.
     pub observations: Vec<Vec<f64>>,
+    /// Per-agent valid-action masks matching `observations` (see
+    /// [`ObservationBuilder::action_mask`]).
+    pub action_masks: Vec<Vec<bool>>,
     /// Cooperative reward (shared across all agents).
     pub reward: f64,
     /// Whether the episode is done (horizon reached).
     pub done: bool,
     /// Current time step.
     pub time_step: u32,
+    /// Scheduling time elapsed since the start of the episode, see
+    /// [`RLConfig::time_at`].
+    pub time: Quantity<U>,
     /// Number of tasks collected this step.
     pub tasks_collected: usize,
     /// Number of tasks expired this step.
     pub tasks_expired: usize,
-    /// Total value of tasks collected this step.
+    /// Value credited this step: the value of collected tasks, plus partial
+    /// credit with [`CollectionMode::Progressive`](super::config::CollectionMode::Progressive).
     pub collected_value: f64,
     /// Total value of tasks that expired this step.
     pub expired_value: f64,
     /// Global state (for centralized critic, if needed).
     pub global_state: Vec<f64>,
+    /// State reported by the environment's hooks after the step, as
+    /// `("{hook}.{key}", value)` pairs.
+    pub hook_state: Vec<(String, f64)>,
+}
+
+/// One recorded step of an [`EpisodeTrace`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceStep<U: Unit = Second> {
+    /// Time step after the step.
+    pub time_step: u32,
+    /// Scheduling time after the step.
+    pub time: Quantity<U>,
+    /// Actions as executed, after hook rewrites and action masking.
+    pub actions: Vec<usize>,
+    /// Step reward, after any hook adjustments.
+    pub reward: f64,
+    /// Hook state after the step (see [`StepResult::hook_state`]).
+    pub hook_state: Vec<(String, f64)>,
+}
+
+/// Step-by-step record of the current episode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpisodeTrace<U: Unit = Second> {
+    /// Recorded steps, in order.
+    pub steps: Vec<TraceStep<U>>,
+}
+
+impl<U: Unit> Default for EpisodeTrace<U> {
+    fn default() -> Self {
+        Self { steps: Vec::new() }
+    }
 }
 
 /// The multi-agent RL scheduling environment.
@@ -48,10 +89,13 @@ pub struct StepResult {
 /// 2. Call [`RLEnvironment::reset`] to initialize an episode.
 /// 3. Repeatedly call [`RLEnvironment::step`] with agent actions until `done`.
 /// 4. Inspect [`StepResult`] for rewards, observations, and episode status.
+///
+/// Steps are counted as integers; [`RLConfig::step_duration`] maps them onto
+/// the scheduling axis in unit `U`.
 #[derive(Debug)]
-pub struct RLEnvironment {
+pub struct RLEnvironment<U: Unit = Second> {
     /// Environment configuration.
-    pub config: RLConfig,
+    pub config: RLConfig<U>,
     /// Agent states.
     pub agents: Vec<AgentState>,
     /// Task pool.
@@ -64,16 +108,20 @@ pub struct RLEnvironment {
     seed: u64,
     /// Cumulative reward this episode.
     pub cumulative_reward: f64,
+    /// Custom dynamics run around every step.
+    hooks: Vec<Box<dyn StepHook<U>>>,
+    /// Trace of the current episode, if recording.
+    trace: Option<EpisodeTrace<U>>,
 }
 
-impl RLEnvironment {
+impl<U: Unit> RLEnvironment<U> {
     /// Creates a new environment with the given configuration and RNG seed.
     ///
     /// # Arguments
     ///
     /// * `config` - Environment and reward configuration
     /// * `seed` - Random seed for reproducible episodes
-    pub fn new(config: RLConfig, seed: u64) -> Self {
+    pub fn new(config: RLConfig<U>, seed: u64) -> Self {
         Self {
             config,
             agents: Vec::new(),
@@ -82,11 +130,23 @@ impl RLEnvironment {
             rng: StdRng::seed_from_u64(seed),
             seed,
             cumulative_reward: 0.0,
+            hooks: Vec::new(),
+            trace: None,
         }
     }
 
+    /// Like [`new`](Self::new), but rejects an invalid configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first problem reported by [`RLConfig::validate`].
+    pub fn try_new(config: RLConfig<U>, seed: u64) -> Result<Self, RLConfigError> {
+        config.validate()?;
+        Ok(Self::new(config, seed))
+    }
+
     /// Creates a new environment with custom task templates.
-    pub fn with_templates(config: RLConfig, templates: Vec<TaskTemplate>, seed: u64) -> Self {
+    pub fn with_templates(config: RLConfig<U>, templates: Vec<TaskTemplate>, seed: u64) -> Self {
         Self {
             config,
             agents: Vec::new(),
@@ -95,6 +155,8 @@ impl RLEnvironment {
             rng: StdRng::seed_from_u64(seed),
             seed,
             cumulative_reward: 0.0,
+            hooks: Vec::new(),
+            trace: None,
         }
     }
 
@@ -113,16 +175,36 @@ impl RLEnvironment {
                     self.config.world_width / 2.0,
                     self.config.world_height / 2.0,
                 );
-                self.agents.push(AgentState::new(
-                    format!("agent_{}", idx),
-                    position,
-                    *agent_type,
-                ));
+                let mut agent = AgentState::new(format!("agent_{}", idx), position, *agent_type);
+                agent.energy = self.config.energy.as_ref().map(|e| e.capacity);
+                self.agents.push(agent);
                 idx += 1;
             }
         }
     }
 
+    /// Registers a hook run around every step, after those already added.
+    pub fn add_hook(&mut self, hook: impl StepHook<U> + 'static) {
+        self.hooks.push(Box::new(hook));
+    }
+
+    /// Returns the registered hooks.
+    pub fn hooks(&self) -> &[Box<dyn StepHook<U>>] {
+        &self.hooks
+    }
+
+    /// Starts or stops recording an [`EpisodeTrace`].
+    ///
+    /// The trace is cleared on every [`reset`](Self::reset).
+    pub fn record_trace(&mut self, enabled: bool) {
+        self.trace = enabled.then(EpisodeTrace::default);
+    }
+
+    /// Trace of the current episode, if recording.
+    pub fn trace(&self) -> Option<&EpisodeTrace<U>> {
+        self.trace.as_ref()
+    }
+
     /// Resets the environment for a new episode.
     ///
     /// Repositions all agents to the center, clears the task pool,
@@ -140,10 +222,17 @@ impl RLEnvironment {
             agent.position = Position::new(cx, cy);
             agent.current_target = 0;
             agent.prev_distance_to_target = None;
+            agent.energy = self.config.energy.as_ref().map(|e| e.capacity);
         }
 
         // Reset task pool
         self.task_pool.reset();
+        for hook in &mut self.hooks {
+            hook.reset();
+        }
+        if let Some(trace) = &mut self.trace {
+            trace.steps.clear();
+        }
 
         // Initial spawn
         self.task_pool.spawn(&mut self.rng, &self.config);
@@ -152,22 +241,54 @@ impl RLEnvironment {
         ObservationBuilder::build_all(&self.agents, &self.task_pool, &self.config)
     }
 
+    /// Valid-action masks for the current state, one per agent.
+    ///
+    /// Use after [`reset`](Self::reset); [`step`](Self::step) returns them in
+    /// [`StepResult::action_masks`].
+    pub fn action_masks(&self) -> Vec<Vec<bool>> {
+        ObservationBuilder::action_masks_all(&self.agents, &self.task_pool, &self.config)
+    }
+
     /// Executes one environment step.
     ///
     /// Follows the loop from §8:
-    /// 1. Spawn tasks
+    /// 1. Spawn tasks, then run [`StepHook::pre_step`]
     /// 2. Agents choose actions (provided as input)
     /// 3. Move agents toward targets
     /// 4. Update task timers
-    /// 5. Collect tasks (check coalitions)
+    /// 5. Collect tasks (check coalitions), then recharge agents in
+    ///    charging zones
     /// 6. Expire tasks
-    /// 7. Compute reward (with shaping)
+    /// 7. Compute reward (with shaping), then run [`StepHook::post_step`]
     /// 8. Build next observations
     ///
     /// # Arguments
     ///
     /// * `actions` - One action per agent. Action 0 = patrol, 1..=M = target task index.
-    pub fn step(&mut self, actions: Vec<usize>) -> StepResult {
+    ///   Actions outside the agent's action mask are executed as patrol.
+    pub fn step(&mut self, actions: Vec<usize>) -> StepResult<U> {
+        self.step_inner(actions, None)
+    }
+
+    /// Like [`step`](Self::step), additionally running `hook` after the
+    /// registered hooks for this step only.
+    ///
+    /// Useful for hooks that borrow caller state or whose results the caller
+    /// reads back afterwards, such as
+    /// [`ScheduleShaping`](super::schedule_reward::ScheduleShaping).
+    pub fn step_with_hook(
+        &mut self,
+        actions: Vec<usize>,
+        hook: &mut dyn StepHook<U>,
+    ) -> StepResult<U> {
+        self.step_inner(actions, Some(hook))
+    }
+
+    fn step_inner(
+        &mut self,
+        mut actions: Vec<usize>,
+        mut extra: Option<&mut dyn StepHook<U>>,
+    ) -> StepResult<U> {
         assert_eq!(
             actions.len(),
             self.agents.len(),
@@ -176,32 +297,81 @@ impl RLEnvironment {
 
         // 1. Spawn new tasks
         self.task_pool.spawn(&mut self.rng, &self.config);
+        for hook in self
+            .hooks
+            .iter_mut()
+            .map(|h| h.as_mut() as &mut dyn StepHook<U>)
+            .chain(extra.as_deref_mut())
+        {
+            let mut ctx = StepContext {
+                config: &self.config,
+                agents: &mut self.agents,
+                task_pool: &mut self.task_pool,
+                rng: &mut self.rng,
+                t: self.t,
+            };
+            hook.pre_step(&mut ctx, &mut actions);
+        }
 
         // 2-3. Agents choose actions and move
-        let agent_positions: Vec<_> = self.agents.iter().map(|a| a.position).collect();
         // Capture pre-movement positions/types for coverage delta computation
         let prev_agent_pos_types: Vec<_> = self
             .agents
             .iter()
             .map(|a| (a.position, a.agent_type))
             .collect();
-        let top_m = self.task_pool.top_m(&agent_positions, self.config.top_m);
-        let top_m_refs: Vec<&_> = top_m.to_vec();
+        let candidates =
+            ObservationBuilder::candidates_all(&self.agents, &self.task_pool, &self.config);
 
         for (i, agent) in self.agents.iter_mut().enumerate() {
-            agent.step(actions[i], &top_m_refs, &self.config);
+            let mask = ObservationBuilder::mask_for(agent, &candidates[i], &self.config);
+            if !mask.get(actions[i]).copied().unwrap_or(false) {
+                actions[i] = 0;
+            }
+            agent.step(actions[i], &candidates[i], &self.config);
         }
 
         // 4. Update task timers
         self.task_pool.tick();
 
-        // 5. Collect tasks
+        // 5. Collect tasks; agents with an empty battery cannot help
         let agent_info: Vec<_> = self
             .agents
             .iter()
+            .filter(|a| !a.is_depleted())
             .map(|a| (a.position, a.agent_type))
             .collect();
+        let work_before: Vec<(Id, u32)> = self
+            .task_pool
+            .active
+            .iter()
+            .map(|t| (t.id.clone(), t.work_done))
+            .collect();
         let (collected, collected_value) = self.task_pool.try_collect(&agent_info);
+        if let Some(model) = &self.config.energy {
+            let worked: Vec<(Position, f64)> = self
+                .task_pool
+                .active
+                .iter()
+                .filter(|t| {
+                    work_before
+                        .iter()
+                        .any(|(id, done)| *id == t.id && *done < t.work_done)
+                })
+                .chain(&collected)
+                .map(|t| (t.position, t.collection_radius))
+                .collect();
+            for agent in &mut self.agents {
+                let working = !agent.is_depleted()
+                    && worked
+                        .iter()
+                        .any(|(pos, radius)| agent.position.distance_to(pos) <= *radius);
+                if working {
+                    agent.spend_energy(model.collect_cost);
+                }
+                model.recharge(agent);
+            }
+        }
 
         // 6. Expire tasks
         let expired = self.task_pool.expire();
@@ -213,39 +383,82 @@ impl RLEnvironment {
             .task_pool
             .top_m(&agent_positions_after, self.config.top_m);
         let top_m_after_refs: Vec<&_> = top_m_after.to_vec();
+        let candidates_after =
+            ObservationBuilder::candidates_all(&self.agents, &self.task_pool, &self.config);
 
-        let reward = RewardComputer::compute(
+        let mut reward = RewardComputer::compute_with_candidates(
             collected_value,
             &expired,
             &self.agents,
             &prev_agent_pos_types,
             &top_m_after_refs,
+            &candidates_after,
             &self.config,
         );
+        for hook in self
+            .hooks
+            .iter_mut()
+            .map(|h| h.as_mut() as &mut dyn StepHook<U>)
+            .chain(extra.as_deref_mut())
+        {
+            let mut ctx = StepContext {
+                config: &self.config,
+                agents: &mut self.agents,
+                task_pool: &mut self.task_pool,
+                rng: &mut self.rng,
+                t: self.t,
+            };
+            hook.post_step(&mut ctx, &mut reward);
+        }
         self.cumulative_reward += reward;
 
         // Advance time
         self.t += 1;
         let done = self.t >= self.config.episode_horizon;
+        let time = self.config.time_at(self.t);
 
         // 8. Build next observations
         let observations =
             ObservationBuilder::build_all(&self.agents, &self.task_pool, &self.config);
+        let action_masks = self.action_masks();
         let global_state =
             ObservationBuilder::build_global_state(&self.agents, &self.task_pool, &self.config);
 
         let expired_value: f64 = expired.iter().map(|t| t.value).sum();
+        let hook_state: Vec<(String, f64)> = self
+            .hooks
+            .iter()
+            .map(|h| &**h)
+            .chain(extra.as_deref().map(|h| h as &dyn StepHook<U>))
+            .flat_map(|hook| {
+                hook.state()
+                    .into_iter()
+                    .map(move |(key, value)| (format!("{}.{key}", hook.name()), value))
+            })
+            .collect();
+        if let Some(trace) = &mut self.trace {
+            trace.steps.push(TraceStep {
+                time_step: self.t,
+                time,
+                actions,
+                reward,
+                hook_state: hook_state.clone(),
+            });
+        }
 
         StepResult {
             observations,
+            action_masks,
             reward,
             done,
             time_step: self.t,
+            time,
             tasks_collected: collected.len(),
             tasks_expired: expired.len(),
             collected_value,
             expired_value,
             global_state,
+            hook_state,
         }
     }
 
@@ -263,6 +476,7 @@ impl RLEnvironment {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::algorithms::rl::config::ActionSpace;
 
     fn make_env() -> RLEnvironment {
         let config = RLConfig::default();
@@ -312,6 +526,131 @@ mod tests {
         }
     }
 
+    #[test]
+    fn steps_report_scheduling_time() {
+        use qtty::Minute;
+
+        let config = RLConfig {
+            episode_horizon: 4,
+            ..RLConfig::small()
+        }
+        .with_step_duration(Quantity::<Minute>::new(15.0));
+        let mut env = RLEnvironment::new(config, 7);
+        env.set_agents(&[(1, AgentType::Young)]);
+        env.reset();
+        let times: Vec<_> = (0..4).map(|_| env.step(vec![0]).time.value()).collect();
+        assert_eq!(times, [15.0, 30.0, 45.0, 60.0]);
+        assert_eq!(env.config.horizon_duration(), Quantity::<Minute>::new(60.0));
+    }
+
+    #[test]
+    fn batteries_drain_and_recharge() {
+        use crate::algorithms::rl::energy::{ChargingZone, EnergyModel};
+        use crate::algorithms::rl::task_pool::TaskInstance;
+        use crate::algorithms::rl::types::AgentTypeRequirements;
+
+        let config = RLConfig {
+            spawn_rate: 0.0,
+            reward_time_penalty: 0.0,
+            reward_progress_alpha: 0.0,
+            reward_coverage_gamma: 0.0,
+            energy: Some(EnergyModel {
+                capacity: 1.0,
+                move_cost: 0.5,
+                collect_cost: 0.0,
+                charge_rate: 0.5,
+                charging_zones: vec![ChargingZone::new(Position::new(4.5, 4.5), 0.5)],
+                depletion_penalty: 2.0,
+            }),
+            ..RLConfig::small()
+        };
+        let mut env = RLEnvironment::new(config, 0);
+        env.set_agents(&[(1, AgentType::Young)]);
+        let obs = env.reset();
+        assert_eq!(env.agents[0].energy, Some(1.0));
+        assert_eq!(obs[0].len(), env.config.observation_dim());
+        assert_eq!(*obs[0].last().unwrap(), 1.0);
+        env.task_pool.active.push(TaskInstance {
+            id: "corner".into(),
+            position: Position::new(0.0, 0.0),
+            value: 1.0,
+            deadline: 50,
+            remaining_time: 50,
+            type_requirements: AgentTypeRequirements::default(),
+            collection_radius: 0.5,
+            remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
+        });
+
+        // The battery lasts 2 of the 3.5 units to the task.
+        let result = env.step(vec![1]);
+        assert!(env.agents[0].is_depleted());
+        assert_eq!(result.tasks_collected, 0);
+        let stuck = env.agents[0].position;
+        assert!((stuck.distance_to(&Position::new(2.5, 2.5)) - 2.0).abs() < 1e-10);
+        let result = env.step(vec![1]);
+        assert_eq!(env.agents[0].position, stuck);
+        assert_eq!(result.reward, -2.0);
+        assert_eq!(*result.observations[0].last().unwrap(), 0.0);
+
+        // Inside a charging zone, the battery fills up again.
+        env.agents[0].position = Position::new(4.5, 4.5);
+        env.step(vec![0]);
+        assert_eq!(env.agents[0].energy, Some(0.5));
+        env.step(vec![0]);
+        env.step(vec![0]);
+        assert_eq!(env.agents[0].energy, Some(1.0));
+    }
+
+    #[test]
+    fn obstacles_stop_agents_and_spawns() {
+        use crate::algorithms::rl::obstacles::Obstacle;
+        use crate::algorithms::rl::task_pool::TaskInstance;
+        use crate::algorithms::rl::types::AgentTypeRequirements;
+
+        let wall = Obstacle::rect(Position::new(6.0, 0.0), Position::new(7.0, 10.0));
+        let config = RLConfig {
+            spawn_rate: 1.0,
+            obstacles: vec![wall.clone()],
+            ..RLConfig::default()
+        };
+        let mut env = RLEnvironment::new(config, 3);
+        env.set_agents(&[(1, AgentType::Young)]);
+        for _ in 0..5 {
+            env.reset();
+            assert!(env
+                .task_pool
+                .active
+                .iter()
+                .all(|t| !wall.contains(&t.position)));
+        }
+
+        env.task_pool.active.clear();
+        env.task_pool.active.push(TaskInstance {
+            id: "behind".into(),
+            position: Position::new(9.0, 5.0),
+            value: 1.0,
+            deadline: 50,
+            remaining_time: 50,
+            type_requirements: AgentTypeRequirements::default(),
+            collection_radius: 0.5,
+            remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
+        });
+        env.config.spawn_rate = 0.0;
+        let blocked_at = env.config.observation_dim() - env.config.top_m;
+        let result = env.step(vec![1]);
+        assert_eq!(result.observations[0][blocked_at], 1.0);
+        // Young agents move 3 per step: the wall stops them at x = 6.
+        let x = env.agents[0].position.x;
+        assert!(x < 6.0 && x > 6.0 - 1e-3);
+        env.step(vec![1]);
+        assert!(env.agents[0].position.x < 6.0);
+        assert_eq!(env.task_pool.active.len(), 1);
+    }
+
     #[test]
     fn agents_initialized_at_center() {
         let mut env = make_env();
@@ -323,4 +662,119 @@ mod tests {
             assert!((agent.position.y - cy).abs() < 1e-10);
         }
     }
+
+    /// Pushes agents east and halves task values every step.
+    #[derive(Debug, Default)]
+    struct WindAndDecay {
+        steps: u32,
+    }
+
+    impl StepHook for WindAndDecay {
+        fn name(&self) -> &str {
+            "wind"
+        }
+
+        fn reset(&mut self) {
+            self.steps = 0;
+        }
+
+        fn pre_step(&mut self, _ctx: &mut StepContext<'_>, actions: &mut [usize]) {
+            // Agent 0 has failed and can only patrol.
+            actions[0] = 0;
+        }
+
+        fn post_step(&mut self, ctx: &mut StepContext<'_>, reward: &mut f64) {
+            for agent in ctx.agents.iter_mut() {
+                agent.position.x = (agent.position.x + 1.0).min(ctx.config.world_width);
+            }
+            for task in &mut ctx.task_pool.active {
+                task.value *= 0.5;
+            }
+            *reward -= 1.0;
+            self.steps += 1;
+        }
+
+        fn state(&self) -> Vec<(String, f64)> {
+            vec![("steps".to_string(), self.steps as f64)]
+        }
+    }
+
+    #[test]
+    fn hooks_alter_dynamics_and_are_traced() {
+        let mut env = make_env();
+        env.add_hook(WindAndDecay::default());
+        env.record_trace(true);
+        let cx = env.config.world_width / 2.0;
+
+        env.reset();
+        let initial: Vec<(String, f64)> = env
+            .task_pool
+            .active
+            .iter()
+            .map(|t| (t.id.clone(), t.value))
+            .collect();
+        env.step(vec![1; env.n_agents()]);
+        let result = env.step(vec![1; env.n_agents()]);
+
+        // The failed agent only moves with the wind.
+        assert!((env.agents[0].position.x - cx - 2.0).abs() < 1e-10);
+        assert_eq!(env.agents[0].current_target, 0);
+        for (id, value) in &initial {
+            if let Some(task) = env.task_pool.active.iter().find(|t| &t.id == id) {
+                assert!((task.value - value * 0.25).abs() < 1e-12);
+            }
+        }
+        assert_eq!(result.hook_state, vec![("wind.steps".to_string(), 2.0)]);
+
+        let trace = env.trace().unwrap();
+        assert_eq!(trace.steps.len(), 2);
+        assert_eq!(trace.steps[1].time_step, 2);
+        assert_eq!(trace.steps[1].time, Quantity::<Second>::new(2.0));
+        assert_eq!(trace.steps[1].actions[0], 0);
+        assert_eq!(trace.steps[1].reward, result.reward);
+
+        env.reset();
+        assert!(env.trace().unwrap().steps.is_empty());
+        assert_eq!(env.step(vec![0; env.n_agents()]).hook_state[0].1, 1.0);
+    }
+
+    #[test]
+    fn masked_actions_execute_as_patrol() {
+        let config = RLConfig::builder()
+            .action_space(
+                AgentType::Old,
+                ActionSpace {
+                    max_targets: Some(0),
+                    max_range: None,
+                },
+            )
+            .build()
+            .unwrap();
+        let mut env = RLEnvironment::new(config, 3);
+        env.set_agents(&[(1, AgentType::Young), (1, AgentType::Old)]);
+        env.record_trace(true);
+        env.reset();
+
+        let masks = env.action_masks();
+        assert!(masks[1][1..].iter().all(|&valid| !valid));
+        let result = env.step(vec![1, 1]);
+
+        assert_eq!(env.agents[1].current_target, 0);
+        assert_eq!(env.trace().unwrap().steps[0].actions[1], 0);
+        assert_eq!(result.action_masks.len(), 2);
+        assert!(result.action_masks.iter().all(|m| m[0]));
+    }
+
+    #[test]
+    fn try_new_rejects_invalid_config() {
+        let config = RLConfig {
+            top_m: 0,
+            ..RLConfig::default()
+        };
+        assert_eq!(
+            RLEnvironment::try_new(config, 0).err(),
+            Some(RLConfigError::ZeroTopM)
+        );
+        assert!(RLEnvironment::try_new(RLConfig::small(), 0).is_ok());
+    }
 }
@@ -0,0 +1,91 @@
+//! Pre- and post-step hooks for custom environment dynamics.
+//!
+//! A [`StepHook`] registered with
+//! [`RLEnvironment::add_hook`](super::environment::RLEnvironment::add_hook)
+//! is called around every step and may mutate agents, active tasks, the
+//! chosen actions and the reward. This covers dynamics the core loop does not
+//! model — wind drift on agents, task value decay, stochastic agent failures
+//! — without touching the environment itself.
+//!
+//! Hooks draw randomness from the environment's RNG, so seeded episodes stay
+//! reproducible. Whatever a hook reports through [`StepHook::state`] is
+//! attached to every [`StepResult`](super::environment::StepResult) and
+//! recorded in the episode trace.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use virolai::algorithms::rl::{RLConfig, RLEnvironment, StepContext, StepHook};
+//! # let mut env = RLEnvironment::new(RLConfig::default(), 0);
+//! #[derive(Debug)]
+//! struct Wind { dx: f64 }
+//!
+//! impl StepHook for Wind {
+//!     fn name(&self) -> &str { "wind" }
+//!     fn post_step(&mut self, ctx: &mut StepContext<'_>, _reward: &mut f64) {
+//!         for agent in ctx.agents.iter_mut() {
+//!             agent.position.x = (agent.position.x + self.dx).clamp(0.0, ctx.config.world_width);
+//!         }
+//!     }
+//! }
+//!
+//! env.add_hook(Wind { dx: 0.5 });
+//! ```
+
+use std::fmt;
+
+use qtty::{Quantity, Second, Unit};
+use rand::rngs::StdRng;
+
+use super::agent::AgentState;
+use super::config::RLConfig;
+use super::task_pool::TaskPool;
+
+/// Mutable view of the environment handed to hooks.
+pub struct StepContext<'a, U: Unit = Second> {
+    /// Environment configuration.
+    pub config: &'a RLConfig<U>,
+    /// Agent states.
+    pub agents: &'a mut [AgentState],
+    /// Task pool.
+    pub task_pool: &'a mut TaskPool,
+    /// Environment RNG.
+    pub rng: &'a mut StdRng,
+    /// Time step being executed (before it is advanced).
+    pub t: u32,
+}
+
+impl<U: Unit> StepContext<'_, U> {
+    /// Scheduling time at the start of the step being executed.
+    pub fn time(&self) -> Quantity<U> {
+        self.config.time_at(self.t)
+    }
+}
+
+/// Callbacks around [`RLEnvironment::step`](super::environment::RLEnvironment::step).
+///
+/// All methods have no-op defaults; implement only the ones needed. `U` is
+/// the unit of the environment's [`RLConfig::step_duration`].
+pub trait StepHook<U: Unit = Second>: fmt::Debug + Send {
+    /// Name used to prefix this hook's [`state`](Self::state) keys.
+    fn name(&self) -> &str;
+
+    /// Called by [`RLEnvironment::reset`](super::environment::RLEnvironment::reset)
+    /// before the first spawn of an episode.
+    fn reset(&mut self) {}
+
+    /// Called after tasks spawn and before agents move. May rewrite
+    /// `actions`, e.g. to force failed agents to patrol.
+    fn pre_step(&mut self, _ctx: &mut StepContext<'_, U>, _actions: &mut [usize]) {}
+
+    /// Called after the reward is computed and before observations are
+    /// built, so changes are visible to the next observation. May adjust
+    /// the step `reward`.
+    fn post_step(&mut self, _ctx: &mut StepContext<'_, U>, _reward: &mut f64) {}
+
+    /// Named values describing the hook's current state, recorded after
+    /// every step.
+    fn state(&self) -> Vec<(String, f64)> {
+        Vec::new()
+    }
+}
@@ -1,12 +1,20 @@
 //! Evaluation metrics for the RL scheduling environment (§10).
 //!
 //! Tracks episode-level performance metrics and provides aggregation
-//! over multiple evaluation episodes.
+//! over multiple evaluation episodes. Episodic reward is only a proxy, so
+//! [`ScheduleKpis`] also measures the schedules a policy-driven scheduler
+//! produces on held-out scheduling problems.
 
 use std::fmt;
 
+use qtty::Unit;
+
 use super::environment::RLEnvironment;
 use super::policy::Policy;
+use crate::algorithms::SchedulingAlgorithm;
+use crate::runs::RunKpis;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, SolutionSpace};
 
 /// Aggregated evaluation metrics over multiple episodes.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -26,6 +34,89 @@ pub struct EvaluationMetrics {
     pub mean_tasks_expired: f64,
     /// Number of episodes evaluated.
     pub n_episodes: usize,
+    /// Schedule-domain KPIs, when evaluated with
+    /// [`evaluate_end_to_end`](Self::evaluate_end_to_end).
+    pub schedule: Option<ScheduleKpis>,
+}
+
+/// A scheduling problem kept out of training, used to check that reward
+/// gains carry over to actual schedules.
+#[derive(Debug)]
+pub struct HeldOutProblem<T, U, D = (), E = petgraph::Directed>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    /// Tasks and dependencies.
+    pub blocks: Vec<SchedulingBlock<T, U, D, E>>,
+    /// Feasible windows of the tasks.
+    pub solution_space: SolutionSpace<U>,
+    /// Scheduling horizon.
+    pub horizon: Horizon<U>,
+}
+
+/// Schedule KPIs averaged over held-out problems.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScheduleKpis {
+    /// Mean fraction of the horizon covered by scheduled tasks.
+    pub mean_utilization: f64,
+    /// Mean sum of priorities of the scheduled tasks.
+    pub mean_priority_scheduled: f64,
+    /// Mean fraction of the total priority that was scheduled.
+    pub mean_priority_fraction: f64,
+    /// Mean number of tasks left out of the schedule.
+    pub mean_dropped_tasks: f64,
+    /// Number of problems evaluated.
+    pub n_problems: usize,
+}
+
+impl ScheduleKpis {
+    /// Runs `scheduler` on every problem and averages the resulting KPIs.
+    ///
+    /// Returns all-zero KPIs for an empty problem set.
+    pub fn evaluate<A, T, U, D, E>(scheduler: &A, problems: &[HeldOutProblem<T, U, D, E>]) -> Self
+    where
+        A: SchedulingAlgorithm<T, U, D, E> + ?Sized,
+        T: Task<U>,
+        U: Unit,
+        E: petgraph::EdgeType,
+    {
+        let mut kpis = Self {
+            n_problems: problems.len(),
+            ..Self::default()
+        };
+        if problems.is_empty() {
+            return kpis;
+        }
+
+        for problem in problems {
+            let schedule =
+                scheduler.schedule(&problem.blocks, &problem.solution_space, problem.horizon);
+            let run = RunKpis::measure(&problem.blocks, &schedule, problem.horizon);
+            let available: f64 = problem
+                .blocks
+                .iter()
+                .flat_map(|b| b.tasks())
+                .map(|(_, task)| task.priority() as f64)
+                .sum();
+
+            kpis.mean_utilization += run.utilization;
+            kpis.mean_priority_scheduled += run.priority_scheduled;
+            if available > 0.0 {
+                kpis.mean_priority_fraction += run.priority_scheduled / available;
+            }
+            kpis.mean_dropped_tasks += run.unscheduled as f64;
+        }
+
+        let n = problems.len() as f64;
+        kpis.mean_utilization /= n;
+        kpis.mean_priority_scheduled /= n;
+        kpis.mean_priority_fraction /= n;
+        kpis.mean_dropped_tasks /= n;
+        kpis
+    }
 }
 
 /// Tracks per-episode statistics during evaluation.
@@ -51,10 +142,12 @@ impl EvaluationMetrics {
 
         for _ in 0..n_episodes {
             let mut obs = env.reset();
+            let mut masks = env.action_masks();
+            policy.reset();
             let mut stats = EpisodeStats::default();
 
             loop {
-                let actions = policy.select_actions(&obs);
+                let actions = policy.select_actions_masked(&obs, &masks);
                 let result = env.step(actions);
 
                 stats.tasks_collected += result.tasks_collected as u32;
@@ -63,6 +156,7 @@ impl EvaluationMetrics {
                 stats.total_expired_value += result.expired_value;
 
                 obs = result.observations;
+                masks = result.action_masks;
 
                 if result.done {
                     stats.cumulative_reward = env.cumulative_reward;
@@ -113,6 +207,29 @@ impl EvaluationMetrics {
             mean_tasks_collected,
             mean_tasks_expired,
             n_episodes,
+            schedule: None,
+        }
+    }
+
+    /// Like [`evaluate`](Self::evaluate), and also runs `scheduler` (usually
+    /// an `RLScheduler` wrapping the same policy) on
+    /// `problems` to fill in [`schedule`](Self::schedule).
+    pub fn evaluate_end_to_end<A, T, U, D, E>(
+        env: &mut RLEnvironment,
+        policy: &mut dyn Policy,
+        n_episodes: usize,
+        scheduler: &A,
+        problems: &[HeldOutProblem<T, U, D, E>],
+    ) -> Self
+    where
+        A: SchedulingAlgorithm<T, U, D, E> + ?Sized,
+        T: Task<U>,
+        U: Unit,
+        E: petgraph::EdgeType,
+    {
+        Self {
+            schedule: Some(ScheduleKpis::evaluate(scheduler, problems)),
+            ..Self::evaluate(env, policy, n_episodes)
         }
     }
 }
@@ -149,6 +266,36 @@ impl fmt::Display for EvaluationMetrics {
             f,
             "  Mean cumulative reward:  {:.2}",
             self.mean_cumulative_reward
+        )?;
+        if let Some(kpis) = &self.schedule {
+            write!(f, "{}", kpis)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ScheduleKpis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "--- Schedules ({} held-out problems) ---",
+            self.n_problems
+        )?;
+        writeln!(
+            f,
+            "  Mean utilization:        {:.1}%",
+            self.mean_utilization * 100.0
+        )?;
+        writeln!(
+            f,
+            "  Mean priority scheduled: {:.2} ({:.1}%)",
+            self.mean_priority_scheduled,
+            self.mean_priority_fraction * 100.0
+        )?;
+        writeln!(
+            f,
+            "  Mean dropped tasks:      {:.1}",
+            self.mean_dropped_tasks
         )
     }
 }
@@ -156,7 +303,23 @@ impl fmt::Display for EvaluationMetrics {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::algorithms::est::ESTScheduler;
     use crate::algorithms::rl::{AgentType, RLConfig, RandomPolicy};
+    use crate::test_utils::{block_and_space, hz, iv, q, TestTask};
+    use qtty::Second;
+
+    fn problem(tasks: &[(&str, f64, i32)]) -> HeldOutProblem<TestTask, Second> {
+        let tasks: Vec<_> = tasks
+            .iter()
+            .map(|&(name, size, priority)| (name, size, priority, iv(0.0, 100.0)))
+            .collect();
+        let (block, solution_space) = block_and_space(&tasks);
+        HeldOutProblem {
+            blocks: vec![block],
+            solution_space,
+            horizon: hz(0.0, 100.0),
+        }
+    }
 
     #[test]
     fn evaluate_completes() {
@@ -219,4 +382,51 @@ mod tests {
             "mean_collected_value should be non-negative"
         );
     }
+
+    #[test]
+    fn schedule_kpis_average_over_problems() {
+        // First problem fits entirely; in the second only one of two 60s tasks fits.
+        let problems = [
+            problem(&[("a", 30.0, 2), ("b", 20.0, 2)]),
+            problem(&[("c", 60.0, 3), ("d", 60.0, 1)]),
+        ];
+        let kpis = ScheduleKpis::evaluate(&ESTScheduler::new(q(1.0)), &problems);
+
+        assert_eq!(kpis.n_problems, 2);
+        assert!((kpis.mean_utilization - 0.55).abs() < 1e-9);
+        assert!((kpis.mean_dropped_tasks - 0.5).abs() < 1e-9);
+        assert!(kpis.mean_priority_fraction > 0.5 && kpis.mean_priority_fraction < 1.0);
+        assert_eq!(
+            ScheduleKpis::evaluate(
+                &ESTScheduler::new(q(1.0)),
+                &[] as &[HeldOutProblem<TestTask, Second>]
+            ),
+            ScheduleKpis::default()
+        );
+    }
+
+    #[test]
+    fn end_to_end_reports_both_domains() {
+        let config = RLConfig {
+            episode_horizon: 5,
+            ..RLConfig::default()
+        };
+        let mut env = RLEnvironment::new(config.clone(), 7);
+        env.set_agents(&[(1, AgentType::Young)]);
+        let mut policy = RandomPolicy::new(config.action_dim());
+        let problems = [problem(&[("a", 10.0, 1)])];
+
+        let metrics = EvaluationMetrics::evaluate_end_to_end(
+            &mut env,
+            &mut policy,
+            2,
+            &ESTScheduler::new(q(1.0)),
+            &problems,
+        );
+
+        assert_eq!(metrics.n_episodes, 2);
+        let kpis = metrics.schedule.as_ref().unwrap();
+        assert_eq!(kpis.mean_dropped_tasks, 0.0);
+        assert!(metrics.to_string().contains("held-out problems"));
+    }
 }
@@ -5,9 +5,11 @@
 //! policies and MAPPO training additionally require the `rl-nn` feature flag.
 //!
 //! The [`RLScheduler`] (feature `rl-nn`) bridges a trained policy to the
-//! [`SchedulingAlgorithm`](crate::algorithms::SchedulingAlgorithm) trait.
+//! [`SchedulingAlgorithm`](crate::algorithms::SchedulingAlgorithm) trait;
+//! [`DistilledScheduler`] runs a policy distilled into plain Rust weights.
 
 // Always available — no extra dependencies.
+pub mod distilled;
 pub mod types;
 
 // Modules that require the `rl` feature (rand dependency).
@@ -16,16 +18,26 @@ pub mod agent;
 #[cfg(feature = "rl")]
 pub mod config;
 #[cfg(feature = "rl")]
+pub mod energy;
+#[cfg(feature = "rl")]
 pub mod environment;
 #[cfg(feature = "rl")]
+pub mod hooks;
+#[cfg(feature = "rl")]
 pub mod metrics;
 #[cfg(feature = "rl")]
+pub mod normalization;
+#[cfg(feature = "rl")]
 pub mod observation;
 #[cfg(feature = "rl")]
+pub mod obstacles;
+#[cfg(feature = "rl")]
 pub mod policy;
 #[cfg(feature = "rl")]
 pub mod reward;
 #[cfg(feature = "rl")]
+pub mod schedule_reward;
+#[cfg(feature = "rl")]
 pub mod task_pool;
 
 #[cfg(feature = "rl-nn")]
@@ -36,24 +48,38 @@ pub mod policy_scheduler;
 pub mod training;
 
 // Public re-exports — always available.
+pub use distilled::{DistillConfig, DistilledScheduler, DistilledScorer};
 pub use types::{AgentType, AgentTypeRequirements, Position};
 
 // Re-exports gated behind `rl` feature.
 #[cfg(feature = "rl")]
 pub use agent::AgentState;
 #[cfg(feature = "rl")]
-pub use config::RLConfig;
+pub use config::{
+    ActionSpace, ActorKind, CollectionMode, RLConfig, RLConfigBuilder, RLConfigError, Sensing,
+    TaskFeature, TaskFeatures,
+};
 #[cfg(feature = "rl")]
-pub use environment::{RLEnvironment, StepResult};
+pub use energy::{ChargingZone, EnergyModel};
 #[cfg(feature = "rl")]
-pub use metrics::EvaluationMetrics;
+pub use environment::{EpisodeTrace, RLEnvironment, StepResult, TraceStep};
+#[cfg(feature = "rl")]
+pub use hooks::{StepContext, StepHook};
+#[cfg(feature = "rl")]
+pub use metrics::{EvaluationMetrics, HeldOutProblem, ScheduleKpis};
+#[cfg(feature = "rl")]
+pub use normalization::{RewardNormalizer, RunningMeanStd};
 #[cfg(feature = "rl")]
 pub use observation::ObservationBuilder;
 #[cfg(feature = "rl")]
+pub use obstacles::Obstacle;
+#[cfg(feature = "rl")]
 pub use policy::{GreedyHeuristicPolicy, Policy, RandomPolicy};
 #[cfg(feature = "rl")]
 pub use reward::RewardComputer;
 #[cfg(feature = "rl")]
+pub use schedule_reward::ScheduleShaping;
+#[cfg(feature = "rl")]
 pub use task_pool::{TaskInstance, TaskPool};
 
 #[cfg(feature = "rl-nn")]
@@ -1,27 +1,46 @@
 //! Neural network policy using tch-rs (PyTorch bindings).
 //!
-//! Provides MLP-based actor and critic networks for MAPPO training.
+//! Provides MLP-based actor and critic networks for MAPPO training, and an
+//! optional GRU actor for partial observability (see [`ActorKind`]).
 //! This module is only available with the `rl-nn` feature.
 
-use tch::{nn, nn::Module, Device, Kind, Tensor};
+use qtty::Unit;
+use tch::{nn, nn::Module, nn::RNN, Device, Kind, Tensor};
 
 use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
 
-use super::config::RLConfig;
+use super::config::{ActorKind, RLConfig};
+use super::normalization::RunningMeanStd;
 use super::policy::Policy;
 
-/// MLP actor network that outputs action probabilities.
+/// Layers of an [`ActorNetwork`].
+enum ActorBody {
+    FeedForward(nn::Sequential),
+    Recurrent {
+        encoder: nn::Linear,
+        gru: nn::GRU,
+        head: nn::Linear,
+        hidden_dim: usize,
+    },
+}
+
+/// Actor network that outputs action probabilities.
+///
+/// Feed-forward architecture: `obs_dim → 128 → 64 → action_dim` with ReLU
+/// activations and softmax output.
 ///
-/// Architecture: `obs_dim → 128 → 64 → action_dim` with ReLU activations
-/// and softmax output.
+/// Recurrent architecture: `obs_dim → 128 → GRU(hidden_dim) → action_dim`.
+/// Hidden states are `[batch, hidden_dim]` tensors, one row per agent, and
+/// are threaded through [`forward_step`](Self::forward_step) by the caller.
 pub struct ActorNetwork {
     vs: nn::VarStore,
-    net: nn::Sequential,
+    body: ActorBody,
     action_dim: usize,
 }
 
 impl ActorNetwork {
-    /// Creates a new actor network.
+    /// Creates a new feed-forward actor network.
     pub fn new(obs_dim: usize, action_dim: usize, device: Device) -> Self {
         let vs = nn::VarStore::new(device);
         let p = &vs.root();
@@ -44,26 +63,142 @@ impl ActorNetwork {
 
         Self {
             vs,
-            net,
+            body: ActorBody::FeedForward(net),
             action_dim,
         }
     }
 
+    /// Creates a new recurrent (GRU) actor network.
+    pub fn recurrent(obs_dim: usize, action_dim: usize, hidden_dim: usize, device: Device) -> Self {
+        let vs = nn::VarStore::new(device);
+        let p = &vs.root();
+        let encoder = nn::linear(p / "encoder", obs_dim as i64, 128, Default::default());
+        let gru = nn::gru(p / "gru", 128, hidden_dim as i64, Default::default());
+        let head = nn::linear(
+            p / "head",
+            hidden_dim as i64,
+            action_dim as i64,
+            Default::default(),
+        );
+
+        Self {
+            vs,
+            body: ActorBody::Recurrent {
+                encoder,
+                gru,
+                head,
+                hidden_dim,
+            },
+            action_dim,
+        }
+    }
+
+    /// Creates the actor selected by [`RLConfig::actor`].
+    pub fn from_config<U: Unit>(config: &RLConfig<U>, device: Device) -> Self {
+        let obs_dim = config.observation_dim();
+        let action_dim = config.action_dim();
+        match config.actor {
+            ActorKind::FeedForward => Self::new(obs_dim, action_dim, device),
+            ActorKind::Recurrent { hidden_dim } => {
+                Self::recurrent(obs_dim, action_dim, hidden_dim, device)
+            }
+        }
+    }
+
+    /// Whether this actor carries a hidden state between steps.
+    pub fn is_recurrent(&self) -> bool {
+        matches!(self.body, ActorBody::Recurrent { .. })
+    }
+
+    /// Size of the hidden state, or `None` for a feed-forward actor.
+    pub fn hidden_dim(&self) -> Option<usize> {
+        match self.body {
+            ActorBody::FeedForward(_) => None,
+            ActorBody::Recurrent { hidden_dim, .. } => Some(hidden_dim),
+        }
+    }
+
+    /// Zero hidden state for `batch` agents, or `None` for a feed-forward
+    /// actor.
+    pub fn initial_hidden(&self, batch: usize) -> Option<Tensor> {
+        self.hidden_dim()
+            .map(|h| Tensor::zeros([batch as i64, h as i64], (Kind::Float, self.vs.device())))
+    }
+
     /// Forward pass: returns log-probabilities over actions.
+    ///
+    /// A recurrent actor is run for a single step from a zero hidden state.
     pub fn forward(&self, obs: &Tensor) -> Tensor {
-        let logits = self.net.forward(obs);
-        logits.log_softmax(-1, Kind::Float)
+        self.forward_step(obs, None).0
+    }
+
+    /// One step of the actor for `obs` of shape `[batch, obs_dim]`.
+    ///
+    /// Returns the log-probabilities and the next hidden state. `hidden`
+    /// defaults to zeros; both are ignored (and `None` returned) by a
+    /// feed-forward actor.
+    pub fn forward_step(&self, obs: &Tensor, hidden: Option<&Tensor>) -> (Tensor, Option<Tensor>) {
+        match &self.body {
+            ActorBody::FeedForward(net) => (net.forward(obs).log_softmax(-1, Kind::Float), None),
+            ActorBody::Recurrent {
+                encoder, gru, head, ..
+            } => {
+                let x = encoder.forward(obs).relu();
+                let state = match hidden {
+                    Some(h) => nn::GRUState(h.unsqueeze(0)),
+                    None => gru.zero_state(obs.size()[0]),
+                };
+                let next = gru.step(&x, &state).value().squeeze_dim(0);
+                let log_probs = head.forward(&next).log_softmax(-1, Kind::Float);
+                (log_probs, Some(next))
+            }
+        }
+    }
+
+    /// Runs the actor over sequences `obs` of shape `[batch, steps, obs_dim]`
+    /// starting from `h0` (`[batch, hidden_dim]`, zeros if `None`).
+    ///
+    /// Returns log-probabilities of shape `[batch, steps, action_dim]`. A
+    /// feed-forward actor treats every step independently.
+    pub fn forward_sequence(&self, obs: &Tensor, h0: Option<&Tensor>) -> Tensor {
+        match &self.body {
+            ActorBody::FeedForward(net) => net.forward(obs).log_softmax(-1, Kind::Float),
+            ActorBody::Recurrent {
+                encoder, gru, head, ..
+            } => {
+                let x = encoder.forward(obs).relu();
+                let state = match h0 {
+                    Some(h) => nn::GRUState(h.unsqueeze(0)),
+                    None => gru.zero_state(obs.size()[0]),
+                };
+                let (out, _) = gru.seq_init(&x, &state);
+                head.forward(&out).log_softmax(-1, Kind::Float)
+            }
+        }
     }
 
     /// Samples actions from the policy distribution.
     pub fn sample_actions(&self, obs: &Tensor) -> (Tensor, Tensor) {
-        let log_probs = self.forward(obs);
-        let probs = log_probs.exp();
-        let actions = probs.multinomial(1, true).squeeze_dim(-1);
-        let selected_log_probs = log_probs
-            .gather(-1, &actions.unsqueeze(-1), false)
-            .squeeze_dim(-1);
-        (actions, selected_log_probs)
+        sample(&self.forward(obs))
+    }
+
+    /// Samples actions for one step of a (possibly recurrent) actor,
+    /// restricted to `mask` (see [`mask_log_probs`]) if given.
+    ///
+    /// Returns `(actions, log_probs, next_hidden)`.
+    pub fn sample_actions_step(
+        &self,
+        obs: &Tensor,
+        hidden: Option<&Tensor>,
+        mask: Option<&Tensor>,
+    ) -> (Tensor, Tensor, Option<Tensor>) {
+        let (log_probs, next) = self.forward_step(obs, hidden);
+        let log_probs = match mask {
+            Some(mask) => mask_log_probs(&log_probs, mask),
+            None => log_probs,
+        };
+        let (actions, selected) = sample(&log_probs);
+        (actions, selected, next)
     }
 
     /// Returns log-probabilities for given actions.
@@ -92,6 +227,39 @@ impl ActorNetwork {
     }
 }
 
+/// Renormalizes `log_probs` over the actions where the boolean `mask` (same
+/// shape) is true.
+///
+/// Masked actions get a large negative log-probability rather than `-inf`,
+/// so entropies computed from the result stay finite.
+pub fn mask_log_probs(log_probs: &Tensor, mask: &Tensor) -> Tensor {
+    log_probs
+        .masked_fill(&mask.logical_not(), -1e9)
+        .log_softmax(-1, Kind::Float)
+}
+
+/// Stacks per-agent action masks into a `[n_agents, action_dim]` boolean
+/// tensor on `device`.
+pub fn masks_to_tensor(masks: &[Vec<bool>], device: Device) -> Tensor {
+    let action_dim = masks.first().map_or(0, Vec::len);
+    let flat: Vec<bool> = masks.iter().flatten().copied().collect();
+    Tensor::from_slice(&flat)
+        .reshape([masks.len() as i64, action_dim as i64])
+        .to_device(device)
+}
+
+/// Samples one action per row of `log_probs`.
+///
+/// Returns the actions and their log-probabilities.
+fn sample(log_probs: &Tensor) -> (Tensor, Tensor) {
+    let probs = log_probs.exp();
+    let actions = probs.multinomial(1, true).squeeze_dim(-1);
+    let selected_log_probs = log_probs
+        .gather(-1, &actions.unsqueeze(-1), false)
+        .squeeze_dim(-1);
+    (actions, selected_log_probs)
+}
+
 /// MLP critic network (centralized value function).
 ///
 /// Architecture: `global_state_dim → 128 → 64 → 1` with ReLU activations.
@@ -140,9 +308,21 @@ impl CriticNetwork {
 ///
 /// Implements the [`Policy`] trait for use in the RL environment.
 /// Can operate in greedy (argmax) or stochastic (sample) mode.
+///
+/// With a recurrent actor the policy keeps one hidden state per agent,
+/// cleared by [`Policy::reset`] at the start of every episode. If the actor
+/// was trained on normalized observations, the same (frozen) statistics must
+/// be set with [`set_observation_normalizer`](Self::set_observation_normalizer).
+///
+/// The actor sits behind a mutex, so a configured policy can be shared as
+/// `Arc<NeuralPolicy>` and serve concurrent requests through
+/// [`select_actions_batch`](Self::select_actions_batch) without loading the
+/// actor once per request.
 pub struct NeuralPolicy {
-    actor: ActorNetwork,
+    actor: Mutex<ActorNetwork>,
     greedy: bool,
+    hidden: Option<Tensor>,
+    obs_norm: Option<RunningMeanStd>,
 }
 
 impl NeuralPolicy {
@@ -153,12 +333,12 @@ impl NeuralPolicy {
     /// * `config` - Environment config (determines observation/action dimensions)
     /// * `device` - Device to run on (CPU or CUDA)
     /// * `greedy` - If true, uses argmax; if false, samples from distribution
-    pub fn new(config: &RLConfig, device: Device) -> Self {
-        let obs_dim = config.observation_dim();
-        let action_dim = config.action_dim();
+    pub fn new<U: Unit>(config: &RLConfig<U>, device: Device) -> Self {
         Self {
-            actor: ActorNetwork::new(obs_dim, action_dim, device),
+            actor: Mutex::new(ActorNetwork::from_config(config, device)),
             greedy: false,
+            hidden: None,
+            obs_norm: None,
         }
     }
 
@@ -167,14 +347,31 @@ impl NeuralPolicy {
         self.greedy = greedy;
     }
 
-    /// Returns a reference to the underlying actor network.
-    pub fn actor(&self) -> &ActorNetwork {
-        &self.actor
+    /// Sets the observation statistics applied before every forward pass.
+    pub fn set_observation_normalizer(&mut self, normalizer: Option<RunningMeanStd>) {
+        self.obs_norm = normalizer;
+    }
+
+    /// Observation statistics in use, if any.
+    pub fn observation_normalizer(&self) -> Option<&RunningMeanStd> {
+        self.obs_norm.as_ref()
+    }
+
+    /// Loads observation statistics saved with a training checkpoint
+    /// (`obs_norm.safetensors`).
+    pub fn load_observation_normalizer(&mut self, path: &Path) -> Result<(), tch::TchError> {
+        self.obs_norm = Some(RunningMeanStd::load(path)?);
+        Ok(())
+    }
+
+    /// Locks and returns the underlying actor network.
+    pub fn actor(&self) -> MutexGuard<'_, ActorNetwork> {
+        self.actor.lock().expect("actor mutex poisoned")
     }
 
     /// Returns a mutable reference to the underlying actor network.
     pub fn actor_mut(&mut self) -> &mut ActorNetwork {
-        &mut self.actor
+        self.actor.get_mut().expect("actor mutex poisoned")
     }
 
     /// Loads pre-trained actor weights from a file.
@@ -183,36 +380,138 @@ impl NeuralPolicy {
     ///
     /// * `path` - Path to a saved actor checkpoint (e.g. `actor.safetensors`)
     pub fn load_actor(&mut self, path: &Path) -> Result<(), tch::TchError> {
-        self.actor.var_store_mut().load(path)
+        self.actor_mut().var_store_mut().load(path)
     }
 
     /// Creates a `NeuralPolicy` that shares weights with an existing `VarStore`.
     ///
     /// This is used during evaluation to create a read-only copy that mirrors
     /// the current training actor without copying weights.
-    pub fn from_actor_var_store(vs: &nn::VarStore, config: &RLConfig) -> Self {
-        let obs_dim = config.observation_dim();
-        let action_dim = config.action_dim();
+    pub fn from_actor_var_store<U: Unit>(vs: &nn::VarStore, config: &RLConfig<U>) -> Self {
         let device = vs.device();
         // Create a new actor with matching architecture, then copy weights
-        let mut actor = ActorNetwork::new(obs_dim, action_dim, device);
+        let mut actor = ActorNetwork::from_config(config, device);
         actor
             .var_store_mut()
             .copy(vs)
             .expect("Failed to copy weights from training actor");
         Self {
-            actor,
+            actor: Mutex::new(actor),
             greedy: false,
+            hidden: None,
+            obs_norm: None,
         }
     }
 }
 
 impl Policy for NeuralPolicy {
     fn select_actions(&mut self, observations: &[Vec<f64>]) -> Vec<usize> {
+        self.act(observations, None)
+    }
+
+    fn select_actions_masked(
+        &mut self,
+        observations: &[Vec<f64>],
+        masks: &[Vec<bool>],
+    ) -> Vec<usize> {
+        self.act(observations, Some(masks))
+    }
+
+    fn reset(&mut self) {
+        self.hidden = None;
+    }
+
+    fn name(&self) -> &str {
+        "neural"
+    }
+}
+
+impl NeuralPolicy {
+    /// Selects actions for several independent observation sets in one
+    /// forward pass, e.g. one set per concurrent scheduling request.
+    ///
+    /// Unlike [`Policy::select_actions`] this takes `&self` and keeps no
+    /// state: recurrent actors start every set from a fresh hidden state.
+    /// Returns one action vector per set, in order.
+    pub fn select_actions_batch(&self, obs_sets: &[Vec<Vec<f64>>]) -> Vec<Vec<usize>> {
+        self.act_batch(obs_sets, None)
+    }
+
+    /// Like [`select_actions_batch`](Self::select_actions_batch), restricted
+    /// to the actions marked valid in each set's masks.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless there is one mask set per observation set and one mask
+    /// per observation.
+    pub fn select_actions_batch_masked(
+        &self,
+        obs_sets: &[Vec<Vec<f64>>],
+        mask_sets: &[Vec<Vec<bool>>],
+    ) -> Vec<Vec<usize>> {
+        assert_eq!(
+            mask_sets.len(),
+            obs_sets.len(),
+            "one mask set per observation set"
+        );
+        for (i, (obs, masks)) in obs_sets.iter().zip(mask_sets).enumerate() {
+            assert_eq!(masks.len(), obs.len(), "set {i}: one mask per observation");
+        }
+        self.act_batch(obs_sets, Some(mask_sets))
+    }
+
+    fn act_batch(
+        &self,
+        obs_sets: &[Vec<Vec<f64>>],
+        mask_sets: Option<&[Vec<Vec<bool>>]>,
+    ) -> Vec<Vec<usize>> {
+        let observations: Vec<Vec<f64>> = obs_sets.iter().flatten().cloned().collect();
+        let masks: Option<Vec<Vec<bool>>> =
+            mask_sets.map(|sets| sets.iter().flatten().cloned().collect());
+        let (actions, _) =
+            tch::no_grad(|| self.forward_actions(&observations, None, masks.as_deref()));
+
+        let mut actions = actions.into_iter();
+        obs_sets
+            .iter()
+            .map(|set| actions.by_ref().take(set.len()).collect())
+            .collect()
+    }
+
+    /// Runs the actor on `observations`, restricted to `masks` if given.
+    fn act(&mut self, observations: &[Vec<f64>], masks: Option<&[Vec<bool>]>) -> Vec<usize> {
+        // A change in the number of agents starts a fresh hidden state.
+        let hidden = self
+            .hidden
+            .take()
+            .filter(|h| h.size()[0] == observations.len() as i64);
+        let (actions, next_hidden) = self.forward_actions(observations, hidden, masks);
+        self.hidden = next_hidden;
+        actions
+    }
+
+    /// One locked forward pass from `hidden`, returning the actions and the
+    /// next hidden state.
+    fn forward_actions(
+        &self,
+        observations: &[Vec<f64>],
+        hidden: Option<Tensor>,
+        masks: Option<&[Vec<bool>]>,
+    ) -> (Vec<usize>, Option<Tensor>) {
         let n_agents = observations.len();
         if n_agents == 0 {
-            return vec![];
+            return (vec![], None);
         }
+        let actor = self.actor();
+
+        let normalized;
+        let observations = match &self.obs_norm {
+            Some(norm) => {
+                normalized = norm.normalize_batch(observations);
+                &normalized
+            }
+            None => observations,
+        };
 
         let obs_dim = observations[0].len();
         let flat: Vec<f64> = observations
@@ -221,24 +520,31 @@ impl Policy for NeuralPolicy {
             .collect();
         let obs_tensor = Tensor::from_slice(&flat)
             .reshape([n_agents as i64, obs_dim as i64])
-            .to_kind(Kind::Float);
+            .to_kind(Kind::Float)
+            .to_device(actor.var_store().device());
+
+        let (log_probs, next_hidden) = actor.forward_step(&obs_tensor, hidden.as_ref());
+        let log_probs = match masks {
+            Some(masks) if masks.len() == n_agents => {
+                mask_log_probs(&log_probs, &masks_to_tensor(masks, log_probs.device()))
+            }
+            _ => log_probs,
+        };
 
         let actions = if self.greedy {
-            let log_probs = self.actor.forward(&obs_tensor);
             log_probs.argmax(-1, false)
         } else {
-            let (actions, _) = self.actor.sample_actions(&obs_tensor);
-            actions
+            sample(&log_probs).0
         };
 
         let actions_vec: Vec<i64> = actions
+            .to_device(Device::Cpu)
             .try_into()
             .expect("NeuralPolicy actions tensor must be convertible to Vec<i64>");
-        actions_vec.iter().map(|&a| a as usize).collect()
-    }
-
-    fn name(&self) -> &str {
-        "neural"
+        (
+            actions_vec.iter().map(|&a| a as usize).collect(),
+            next_hidden,
+        )
     }
 }
 
@@ -273,4 +579,136 @@ mod tests {
             assert!(*a < config.action_dim());
         }
     }
+
+    #[test]
+    fn masked_policy_only_picks_valid_actions() {
+        let config = RLConfig::default();
+        let mut policy = NeuralPolicy::new(&config, Device::Cpu);
+        let obs = vec![vec![0.5; config.observation_dim()]; 2];
+        let mut only_two = vec![false; config.action_dim()];
+        only_two[2] = true;
+        let masks = vec![vec![true, false, false, false, false, false], only_two];
+        for _ in 0..20 {
+            assert_eq!(policy.select_actions_masked(&obs, &masks), vec![0, 2]);
+        }
+
+        let log_probs = Tensor::from_slice(&[0.5f32, 0.5]).log();
+        let mask = Tensor::from_slice(&[false, true]);
+        let masked = mask_log_probs(&log_probs, &mask);
+        assert!((masked.double_value(&[1])).abs() < 1e-6);
+        let entropy = -(masked.exp() * &masked).sum(Kind::Float);
+        assert!(entropy.double_value(&[]).is_finite());
+    }
+
+    #[test]
+    fn recurrent_actor_threads_hidden_state() {
+        let actor = ActorNetwork::recurrent(55, 6, 32, Device::Cpu);
+        assert!(actor.is_recurrent());
+        let obs = Tensor::randn([4, 55], (Kind::Float, Device::Cpu));
+        let h0 = actor.initial_hidden(4).unwrap();
+        assert_eq!(h0.size(), &[4, 32]);
+
+        let (log_probs, h1) = actor.forward_step(&obs, Some(&h0));
+        assert_eq!(log_probs.size(), &[4, 6]);
+        let h1 = h1.unwrap();
+        assert_eq!(h1.size(), &[4, 32]);
+
+        // Same observation, different memory → different output.
+        let (again, _) = actor.forward_step(&obs, Some(&h1));
+        assert!(f64::try_from((&again - &log_probs).abs().sum(Kind::Float)).unwrap() > 0.0);
+    }
+
+    #[test]
+    fn sequence_matches_unrolled_steps() {
+        let actor = ActorNetwork::recurrent(8, 3, 16, Device::Cpu);
+        let obs = Tensor::randn([2, 5, 8], (Kind::Float, Device::Cpu));
+        let seq = actor.forward_sequence(&obs, None);
+        assert_eq!(seq.size(), &[2, 5, 3]);
+
+        let mut hidden = actor.initial_hidden(2);
+        for t in 0..5 {
+            let (step, next) = actor.forward_step(&obs.select(1, t), hidden.as_ref());
+            let diff = (&step - seq.select(1, t)).abs().max();
+            assert!(f64::try_from(diff).unwrap() < 1e-5);
+            hidden = next;
+        }
+    }
+
+    #[test]
+    fn recurrent_policy_resets_between_episodes() {
+        let config = RLConfig {
+            actor: ActorKind::Recurrent { hidden_dim: 16 },
+            ..RLConfig::default()
+        };
+        let mut policy = NeuralPolicy::new(&config, Device::Cpu);
+        assert!(policy.actor().is_recurrent());
+        let obs = vec![vec![0.5; config.observation_dim()]; 3];
+        assert_eq!(policy.select_actions(&obs).len(), 3);
+        assert!(policy.hidden.is_some());
+        policy.reset();
+        assert!(policy.hidden.is_none());
+    }
+
+    #[test]
+    fn shared_policy_serves_batched_requests() {
+        fn assert_shareable<T: Send + Sync>() {}
+        assert_shareable::<NeuralPolicy>();
+
+        let config = RLConfig::default();
+        let mut policy = NeuralPolicy::new(&config, Device::Cpu);
+        policy.set_greedy(true);
+        let policy = std::sync::Arc::new(policy);
+
+        let a = vec![vec![0.5; config.observation_dim()]; 2];
+        let b = vec![vec![-0.5; config.observation_dim()]; 3];
+        let batched = policy.select_actions_batch(&[a.clone(), vec![], b.clone()]);
+        assert_eq!(batched.len(), 3);
+        assert_eq!(batched[0], policy.select_actions_batch(&[a])[0]);
+        assert!(batched[1].is_empty());
+        assert_eq!(batched[2].len(), 3);
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let policy = std::sync::Arc::clone(&policy);
+                let b = b.clone();
+                std::thread::spawn(move || policy.select_actions_batch(&[b]).remove(0))
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), batched[2]);
+        }
+
+        let mut only_two = vec![false; config.action_dim()];
+        only_two[2] = true;
+        let masked = policy.select_actions_batch_masked(
+            &[vec![vec![0.5; config.observation_dim()]]],
+            &[vec![only_two]],
+        );
+        assert_eq!(masked, vec![vec![2]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "one mask per observation")]
+    fn batch_masks_must_match_observations() {
+        let config = RLConfig::default();
+        let policy = NeuralPolicy::new(&config, Device::Cpu);
+        let obs = vec![vec![0.5; config.observation_dim()]; 2];
+        policy.select_actions_batch_masked(&[obs], &[vec![vec![true; config.action_dim()]]]);
+    }
+
+    #[test]
+    fn policy_applies_observation_normalizer() {
+        let config = RLConfig::default();
+        let mut policy = NeuralPolicy::new(&config, Device::Cpu);
+        let mut stats = RunningMeanStd::new(config.observation_dim());
+        stats.update(&[vec![100.0; config.observation_dim()]]);
+        policy.set_observation_normalizer(Some(stats));
+        policy.set_greedy(true);
+
+        // Observations at the running mean normalize to zeros.
+        let at_mean = policy.select_actions(&[vec![100.0; config.observation_dim()]]);
+        policy.set_observation_normalizer(None);
+        let zeros = policy.select_actions(&[vec![0.0; config.observation_dim()]]);
+        assert_eq!(at_mean, zeros);
+    }
 }
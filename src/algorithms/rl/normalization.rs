@@ -0,0 +1,254 @@
+//! Running-mean observation and reward normalization.
+//!
+//! Raw observation features live on very different scales (world coordinates
+//! next to counts and one-hot flags), and reward magnitudes depend on task
+//! values, both of which destabilize PPO. [`RunningMeanStd`] tracks per-feature
+//! statistics online and standardizes inputs; [`RewardNormalizer`] scales
+//! rewards by the standard deviation of the discounted return.
+//!
+//! Statistics are updated during training only and frozen at inference, so
+//! a policy must always be paired with the statistics it was trained with.
+//! With the `rl-nn` feature they are saved alongside actor checkpoints.
+
+/// Normalized values are clipped to `[-CLIP, CLIP]`.
+const CLIP: f64 = 10.0;
+
+/// Guards against division by zero for constant features.
+const EPSILON: f64 = 1e-8;
+
+/// Per-feature running mean and variance.
+///
+/// Batches are merged with the parallel variance algorithm of Chan et al.,
+/// which is numerically stable for long runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunningMeanStd {
+    mean: Vec<f64>,
+    var: Vec<f64>,
+    count: f64,
+}
+
+impl RunningMeanStd {
+    /// Creates statistics for `dim` features (mean 0, variance 1).
+    pub fn new(dim: usize) -> Self {
+        Self {
+            mean: vec![0.0; dim],
+            var: vec![1.0; dim],
+            count: 0.0,
+        }
+    }
+
+    /// Restores statistics from saved parts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mean` and `var` have different lengths.
+    pub fn from_parts(mean: Vec<f64>, var: Vec<f64>, count: f64) -> Self {
+        assert_eq!(mean.len(), var.len(), "mean and variance lengths differ");
+        Self { mean, var, count }
+    }
+
+    /// Number of features.
+    pub fn dim(&self) -> usize {
+        self.mean.len()
+    }
+
+    /// Per-feature mean.
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+
+    /// Per-feature variance.
+    pub fn var(&self) -> &[f64] {
+        &self.var
+    }
+
+    /// Number of samples seen.
+    pub fn count(&self) -> f64 {
+        self.count
+    }
+
+    /// Merges a batch of samples into the statistics.
+    ///
+    /// Samples whose length differs from [`dim`](Self::dim) are ignored.
+    pub fn update(&mut self, batch: &[Vec<f64>]) {
+        let rows: Vec<&Vec<f64>> = batch.iter().filter(|x| x.len() == self.dim()).collect();
+        if rows.is_empty() {
+            return;
+        }
+        let n = rows.len() as f64;
+        let total = self.count + n;
+
+        for i in 0..self.dim() {
+            let batch_mean = rows.iter().map(|x| x[i]).sum::<f64>() / n;
+            let batch_var = rows
+                .iter()
+                .map(|x| (x[i] - batch_mean).powi(2))
+                .sum::<f64>()
+                / n;
+            let delta = batch_mean - self.mean[i];
+
+            let m2 =
+                self.var[i] * self.count + batch_var * n + delta * delta * self.count * n / total;
+            self.mean[i] += delta * n / total;
+            self.var[i] = m2 / total;
+        }
+        self.count = total;
+    }
+
+    /// Standardizes `x` with the current statistics, clipping to ±10.
+    pub fn normalize(&self, x: &[f64]) -> Vec<f64> {
+        x.iter()
+            .zip(self.mean.iter().zip(&self.var))
+            .map(|(&v, (&m, &s2))| ((v - m) / (s2 + EPSILON).sqrt()).clamp(-CLIP, CLIP))
+            .collect()
+    }
+
+    /// Standardizes every row of `batch`.
+    pub fn normalize_batch(&self, batch: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        batch.iter().map(|x| self.normalize(x)).collect()
+    }
+}
+
+/// Scales rewards by the running standard deviation of the discounted return.
+///
+/// Rewards are divided by that deviation but not re-centred, which keeps the
+/// sign of every reward.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewardNormalizer {
+    stats: RunningMeanStd,
+    discounted_return: f64,
+    gamma: f64,
+}
+
+impl RewardNormalizer {
+    /// Creates a normalizer for returns discounted by `gamma`.
+    pub fn new(gamma: f64) -> Self {
+        Self {
+            stats: RunningMeanStd::new(1),
+            discounted_return: 0.0,
+            gamma,
+        }
+    }
+
+    /// Restores a normalizer from saved return statistics.
+    pub fn from_stats(stats: RunningMeanStd, gamma: f64) -> Self {
+        Self {
+            stats,
+            discounted_return: 0.0,
+            gamma,
+        }
+    }
+
+    /// Statistics of the discounted return.
+    pub fn stats(&self) -> &RunningMeanStd {
+        &self.stats
+    }
+
+    /// Updates the return statistics with `reward` and returns it scaled.
+    ///
+    /// `done` marks the last step of an episode and resets the running
+    /// return.
+    pub fn normalize(&mut self, reward: f64, done: bool) -> f64 {
+        self.discounted_return = self.discounted_return * self.gamma + reward;
+        self.stats.update(&[vec![self.discounted_return]]);
+        if done {
+            self.discounted_return = 0.0;
+        }
+        (reward / (self.stats.var[0] + EPSILON).sqrt()).clamp(-CLIP, CLIP)
+    }
+}
+
+#[cfg(feature = "rl-nn")]
+impl RunningMeanStd {
+    /// Saves the statistics to a safetensors file.
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), tch::TchError> {
+        tch::Tensor::write_safetensors(
+            &[
+                ("mean", tch::Tensor::from_slice(&self.mean)),
+                ("var", tch::Tensor::from_slice(&self.var)),
+                ("count", tch::Tensor::from_slice(&[self.count])),
+            ],
+            path,
+        )
+    }
+
+    /// Loads statistics saved by [`save`](Self::save).
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, tch::TchError> {
+        let path = path.as_ref();
+        let mut mean = None;
+        let mut var = None;
+        let mut count = None;
+        for (name, tensor) in tch::Tensor::read_safetensors(path)? {
+            let values = Vec::<f64>::try_from(&tensor)?;
+            match name.as_str() {
+                "mean" => mean = Some(values),
+                "var" => var = Some(values),
+                "count" => count = values.first().copied(),
+                _ => {}
+            }
+        }
+        match (mean, var, count) {
+            (Some(mean), Some(var), Some(count)) if mean.len() == var.len() => {
+                Ok(Self::from_parts(mean, var, count))
+            }
+            _ => Err(tch::TchError::FileFormat(format!(
+                "{} does not hold normalization statistics",
+                path.display()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_merge_like_a_single_pass() {
+        let data: Vec<Vec<f64>> = (0..10).map(|i| vec![i as f64, 100.0]).collect();
+        let mut split = RunningMeanStd::new(2);
+        split.update(&data[..3]);
+        split.update(&data[3..]);
+        let mut whole = RunningMeanStd::new(2);
+        whole.update(&data);
+
+        assert_eq!(split.count(), 10.0);
+        assert!((split.mean()[0] - 4.5).abs() < 1e-12);
+        assert!((split.var()[0] - 8.25).abs() < 1e-9);
+        assert!((split.var()[0] - whole.var()[0]).abs() < 1e-9);
+        assert_eq!(split.var()[1], 0.0);
+    }
+
+    #[test]
+    fn normalize_standardizes_and_clips() {
+        let mut stats = RunningMeanStd::new(2);
+        stats.update(&[vec![0.0, 5.0], vec![2.0, 5.0]]);
+
+        let x = stats.normalize(&[2.0, 5.0]);
+        assert!((x[0] - 1.0).abs() < 1e-6);
+        // Constant feature: centred to zero, large deviations clipped.
+        assert_eq!(x[1], 0.0);
+        assert_eq!(stats.normalize(&[0.0, 6.0])[1], CLIP);
+    }
+
+    #[test]
+    fn mismatched_rows_are_ignored() {
+        let mut stats = RunningMeanStd::new(2);
+        stats.update(&[vec![1.0]]);
+        assert_eq!(stats.count(), 0.0);
+        assert_eq!(stats, RunningMeanStd::new(2));
+    }
+
+    #[test]
+    fn rewards_keep_sign_and_return_resets() {
+        let mut norm = RewardNormalizer::new(0.9);
+        let scaled: Vec<f64> = [10.0, -10.0, 10.0]
+            .iter()
+            .map(|&r| norm.normalize(r, false))
+            .collect();
+        assert!(scaled[0] > 0.0 && scaled[1] < 0.0 && scaled[2] > 0.0);
+        norm.normalize(1.0, true);
+        assert_eq!(norm.discounted_return, 0.0);
+        assert_eq!(norm.stats().count(), 4.0);
+    }
+}
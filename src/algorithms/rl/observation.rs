@@ -1,10 +1,20 @@
 //! Observation encoding for the RL environment.
 //!
 //! Builds per-agent observation vectors containing the agent's own state
-//! plus features for the Top-M candidate tasks.
+//! plus features for the Top-M candidate tasks, and the matching action
+//! masks that restrict each agent to its type's
+//! [`ActionSpace`](super::config::ActionSpace).
+//!
+//! By default every agent sees every active task. With
+//! [`RLConfig::sensing`] set, an agent's candidates are limited to the
+//! tasks it or an agent within communication range senses (see
+//! [`ObservationBuilder::candidates`]).
+
+use qtty::Unit;
 
 use super::agent::AgentState;
 use super::config::RLConfig;
+use super::obstacles::is_blocked;
 use super::task_pool::{TaskInstance, TaskPool};
 
 /// Builds observation vectors for agents.
@@ -15,10 +25,21 @@ impl ObservationBuilder {
     ///
     /// The observation is a flat `Vec<f64>` with structure:
     /// ```text
-    /// [agent_features(5)] ++ [task_1_features(10)] ++ ... ++ [task_M_features(10)]
+    /// [agent_features(5)] ++ [task_1_features(F)] ++ ... ++ [task_M_features(F)]
+    ///     ++ [task_1_progress, ..., task_M_progress]   (progressive collection only)
+    ///     ++ [task_1_blocked, ..., task_M_blocked]     (obstacles only)
+    ///     ++ [charge]                                   (energy model only)
     /// ```
     ///
-    /// If fewer than M tasks are active, remaining slots are zero-padded.
+    /// `F` is [`RLConfig::task_feature_dim`], 10 with every
+    /// [`TaskFeature`](super::config::TaskFeature) selected.
+    ///
+    /// The task slots hold the agent's [`candidates`](Self::candidates); if
+    /// there are fewer than M, remaining slots are zero-padded.
+    /// Progress, obstacle and energy features come last so the per-task
+    /// layout is the same whichever optional features are on. A task is
+    /// blocked (1.0) if an obstacle lies on the straight line from the agent
+    /// to it; the charge is the fraction of a full battery left.
     ///
     /// # Arguments
     ///
@@ -26,15 +47,15 @@ impl ObservationBuilder {
     /// * `agents` - All agents (needed for heading counts and distance computation)
     /// * `task_pool` - Active task pool
     /// * `config` - Environment configuration
-    pub fn build(
+    pub fn build<U: Unit>(
         agent_idx: usize,
         agents: &[AgentState],
         task_pool: &TaskPool,
-        config: &RLConfig,
+        config: &RLConfig<U>,
     ) -> Vec<f64> {
         let agent = &agents[agent_idx];
-        let agent_positions: Vec<_> = agents.iter().map(|a| a.position).collect();
-        let top_m = task_pool.top_m(&agent_positions, config.top_m);
+        let team = Self::team(agent_idx, agents, config);
+        let top_m = Self::candidates(agent_idx, agents, task_pool, config);
         let max_value = task_pool
             .active
             .iter()
@@ -46,35 +67,189 @@ impl ObservationBuilder {
         for i in 0..config.top_m {
             if i < top_m.len() {
                 let task = top_m[i];
-                let heading_counts = Self::count_heading_agents(agents, task);
+                let heading_counts = Self::count_heading_agents(&team, task);
                 obs.extend(task.features(config, max_value, heading_counts));
             } else {
                 // Zero-padding for missing tasks
-                obs.extend(std::iter::repeat_n(0.0, RLConfig::TASK_FEATURE_DIM));
+                obs.extend(std::iter::repeat_n(0.0, config.task_feature_dim()));
             }
         }
+        Self::extend_progress(&mut obs, &top_m, config.top_m, config);
+        Self::extend_blocked(&mut obs, agent, &top_m, config.top_m, config);
+        Self::extend_energy(&mut obs, std::slice::from_ref(agent), config);
 
         obs
     }
 
+    /// Appends one progress feature per slot, if `config` collects
+    /// progressively.
+    fn extend_progress<U: Unit>(
+        out: &mut Vec<f64>,
+        tasks: &[&TaskInstance],
+        slots: usize,
+        config: &RLConfig<U>,
+    ) {
+        out.extend(
+            (0..config.progress_dim(slots)).map(|i| tasks.get(i).map_or(0.0, |t| t.progress())),
+        );
+    }
+
+    /// Appends one blocked-path feature per slot, if `config` has obstacles.
+    fn extend_blocked<U: Unit>(
+        out: &mut Vec<f64>,
+        agent: &AgentState,
+        tasks: &[&TaskInstance],
+        slots: usize,
+        config: &RLConfig<U>,
+    ) {
+        out.extend((0..config.obstacle_dim(slots)).map(|i| {
+            tasks.get(i).map_or(0.0, |t| {
+                let blocked = is_blocked(&config.obstacles, &agent.position, &t.position);
+                f64::from(u8::from(blocked))
+            })
+        }));
+    }
+
+    /// Appends the charge of each of `agents`, if `config` has an energy
+    /// model.
+    fn extend_energy<U: Unit>(out: &mut Vec<f64>, agents: &[AgentState], config: &RLConfig<U>) {
+        if let Some(model) = &config.energy {
+            out.extend(
+                agents
+                    .iter()
+                    .map(|a| a.energy.map_or(1.0, |e| e / model.capacity)),
+            );
+        }
+    }
+
+    /// Candidate tasks of agent `agent_idx`, in slot order.
+    ///
+    /// Without [`RLConfig::sensing`], this is the Top-M of all active tasks
+    /// ranked by every agent's position, the same for all agents. With it,
+    /// only tasks within the sensing radius of the agent or of an agent within
+    /// its communication range are candidates, ranked by those agents'
+    /// positions.
+    pub fn candidates<'a, U: Unit>(
+        agent_idx: usize,
+        agents: &[AgentState],
+        task_pool: &'a TaskPool,
+        config: &RLConfig<U>,
+    ) -> Vec<&'a TaskInstance> {
+        let team = Self::team(agent_idx, agents, config);
+        let positions: Vec<_> = team.iter().map(|a| a.position).collect();
+        match config.sensing {
+            None => task_pool.top_m(&positions, config.top_m),
+            Some(sensing) => task_pool.top_m_where(&positions, config.top_m, |task| {
+                positions
+                    .iter()
+                    .any(|p| p.distance_to(&task.position) <= sensing.sensing_radius)
+            }),
+        }
+    }
+
+    /// Candidate tasks of every agent, see [`candidates`](Self::candidates).
+    pub fn candidates_all<'a, U: Unit>(
+        agents: &[AgentState],
+        task_pool: &'a TaskPool,
+        config: &RLConfig<U>,
+    ) -> Vec<Vec<&'a TaskInstance>> {
+        if config.sensing.is_none() {
+            let shared = Self::candidates(0, agents, task_pool, config);
+            return vec![shared; agents.len()];
+        }
+        (0..agents.len())
+            .map(|i| Self::candidates(i, agents, task_pool, config))
+            .collect()
+    }
+
+    /// Agents whose observations agent `agent_idx` shares: itself and the
+    /// agents within communication range, or everyone without
+    /// [`RLConfig::sensing`].
+    fn team<'a, U: Unit>(
+        agent_idx: usize,
+        agents: &'a [AgentState],
+        config: &RLConfig<U>,
+    ) -> Vec<&'a AgentState> {
+        match config.sensing {
+            None => agents.iter().collect(),
+            Some(sensing) => {
+                let origin = agents[agent_idx].position;
+                agents
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, a)| {
+                        *i == agent_idx || origin.distance_to(&a.position) <= sensing.comm_radius
+                    })
+                    .map(|(_, a)| a)
+                    .collect()
+            }
+        }
+    }
+
     /// Builds observations for all agents.
-    pub fn build_all(
+    pub fn build_all<U: Unit>(
         agents: &[AgentState],
         task_pool: &TaskPool,
-        config: &RLConfig,
+        config: &RLConfig<U>,
     ) -> Vec<Vec<f64>> {
         (0..agents.len())
             .map(|i| Self::build(i, agents, task_pool, config))
             .collect()
     }
 
+    /// Builds the action mask for a specific agent.
+    ///
+    /// Entry `a` of the returned `action_dim`-long vector is `true` if action
+    /// `a` is valid: patrol always is, a candidate slot is valid if it holds
+    /// one of the agent's [`candidates`](Self::candidates) that its
+    /// [`ActionSpace`](super::config::ActionSpace) allows it to target.
+    pub fn action_mask<U: Unit>(
+        agent_idx: usize,
+        agents: &[AgentState],
+        task_pool: &TaskPool,
+        config: &RLConfig<U>,
+    ) -> Vec<bool> {
+        let top_m = Self::candidates(agent_idx, agents, task_pool, config);
+        Self::mask_for(&agents[agent_idx], &top_m, config)
+    }
+
+    /// Builds action masks for all agents.
+    pub fn action_masks_all<U: Unit>(
+        agents: &[AgentState],
+        task_pool: &TaskPool,
+        config: &RLConfig<U>,
+    ) -> Vec<Vec<bool>> {
+        agents
+            .iter()
+            .zip(Self::candidates_all(agents, task_pool, config))
+            .map(|(agent, top_m)| Self::mask_for(agent, &top_m, config))
+            .collect()
+    }
+
+    /// Action mask of `agent` over an already computed Top-M list.
+    pub(crate) fn mask_for<U: Unit>(
+        agent: &AgentState,
+        top_m: &[&TaskInstance],
+        config: &RLConfig<U>,
+    ) -> Vec<bool> {
+        let space = config.action_space_for(agent.agent_type);
+        (0..config.action_dim())
+            .map(|a| {
+                a == 0
+                    || top_m.get(a - 1).is_some_and(|task| {
+                        space.allows(a, agent.position.distance_to(&task.position))
+                    })
+            })
+            .collect()
+    }
+
     /// Counts how many agents of each type are currently heading toward a task.
     ///
     /// "Heading toward" means the agent's current_target matches the task's
     /// index in the Top-M list. This is an approximation since agents may
     /// have different Top-M orderings, but it provides useful signal for
     /// coordination.
-    fn count_heading_agents(agents: &[AgentState], task: &TaskInstance) -> [u32; 3] {
+    fn count_heading_agents(agents: &[&AgentState], task: &TaskInstance) -> [u32; 3] {
         let mut counts = [0u32; 3];
         for agent in agents {
             // An agent is heading toward this task if they are within
@@ -88,11 +263,14 @@ impl ObservationBuilder {
 
     /// Returns the global state vector (used by the centralized critic).
     ///
-    /// Concatenates all agent features and all active task features.
-    pub fn build_global_state(
+    /// Concatenates all agent features and all active task features, then
+    /// the progress and charge features as in [`build`](Self::build).
+    /// Blocked-path features depend on the agent and are left out. This is
+    /// the centralized view: it ignores [`RLConfig::sensing`].
+    pub fn build_global_state<U: Unit>(
         agents: &[AgentState],
         task_pool: &TaskPool,
-        config: &RLConfig,
+        config: &RLConfig<U>,
     ) -> Vec<f64> {
         let max_value = task_pool
             .active
@@ -100,6 +278,7 @@ impl ObservationBuilder {
             .map(|t| t.value)
             .fold(1.0_f64, f64::max);
 
+        let everyone: Vec<_> = agents.iter().collect();
         let mut state = Vec::new();
 
         // All agent features
@@ -112,26 +291,31 @@ impl ObservationBuilder {
         let top_m = task_pool.top_m(&agent_positions, config.max_active_tasks);
         for i in 0..config.max_active_tasks {
             if i < top_m.len() {
-                let heading = Self::count_heading_agents(agents, top_m[i]);
+                let heading = Self::count_heading_agents(&everyone, top_m[i]);
                 state.extend(top_m[i].features(config, max_value, heading));
             } else {
-                state.extend(std::iter::repeat_n(0.0, RLConfig::TASK_FEATURE_DIM));
+                state.extend(std::iter::repeat_n(0.0, config.task_feature_dim()));
             }
         }
+        Self::extend_progress(&mut state, &top_m, config.max_active_tasks, config);
+        Self::extend_energy(&mut state, agents, config);
 
         state
     }
 
     /// Dimension of the global state vector.
-    pub fn global_state_dim(n_agents: usize, config: &RLConfig) -> usize {
+    pub fn global_state_dim<U: Unit>(n_agents: usize, config: &RLConfig<U>) -> usize {
         n_agents * RLConfig::AGENT_FEATURE_DIM
-            + config.max_active_tasks * RLConfig::TASK_FEATURE_DIM
+            + config.max_active_tasks * config.task_feature_dim()
+            + config.progress_dim(config.max_active_tasks)
+            + n_agents * config.energy_dim()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::algorithms::rl::config::ActionSpace;
     use crate::algorithms::rl::types::{AgentType, AgentTypeRequirements, Position};
 
     fn make_agents() -> Vec<AgentState> {
@@ -152,6 +336,8 @@ mod tests {
             type_requirements: AgentTypeRequirements::new(1, 0, 0),
             collection_radius: 1.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         });
         pool
     }
@@ -177,6 +363,112 @@ mod tests {
         }
     }
 
+    #[test]
+    fn action_mask_follows_type_action_space() {
+        let agents = make_agents();
+        let mut pool = make_pool_with_tasks();
+        pool.active.push(TaskInstance {
+            id: "t1".into(),
+            position: Position::new(9.0, 9.0),
+            ..pool.active[0].clone()
+        });
+        let config = RLConfig::builder()
+            .action_space(
+                AgentType::Old,
+                ActionSpace {
+                    max_targets: None,
+                    max_range: Some(5.0),
+                },
+            )
+            .build()
+            .unwrap();
+
+        let masks = ObservationBuilder::action_masks_all(&agents, &pool, &config);
+        assert_eq!(masks.len(), 2);
+        assert_eq!(masks[0].len(), config.action_dim());
+        // Young agent: patrol and both tasks, empty slots masked.
+        assert_eq!(masks[0], vec![true, true, true, false, false, false]);
+        // Old agent at (5, 5): only the task within range.
+        assert_eq!(
+            masks[1].iter().filter(|&&valid| valid).count(),
+            2,
+            "patrol and the nearby task"
+        );
+        assert_eq!(
+            ObservationBuilder::action_mask(1, &agents, &pool, &config),
+            masks[1]
+        );
+    }
+
+    #[test]
+    fn progress_is_observed_with_progressive_collection() {
+        use crate::algorithms::rl::config::CollectionMode;
+
+        let agents = make_agents();
+        let mut pool = make_pool_with_tasks();
+        pool.active[0].work_units = 4;
+        pool.active[0].work_done = 1;
+        let config = RLConfig::builder()
+            .collection(CollectionMode::Progressive { work_units: 4 })
+            .build()
+            .unwrap();
+
+        let obs = ObservationBuilder::build(0, &agents, &pool, &config);
+        assert_eq!(obs.len(), config.observation_dim());
+        let progress = &obs[obs.len() - config.top_m..];
+        assert_eq!(progress[0], 0.25);
+        assert!(progress[1..].iter().all(|&p| p == 0.0));
+        let state = ObservationBuilder::build_global_state(&agents, &pool, &config);
+        assert_eq!(
+            state.len(),
+            ObservationBuilder::global_state_dim(agents.len(), &config)
+        );
+    }
+
+    #[test]
+    fn sensing_limits_candidates_to_the_team() {
+        use crate::algorithms::rl::config::Sensing;
+
+        let agents = make_agents();
+        let mut pool = make_pool_with_tasks();
+        pool.active.push(TaskInstance {
+            id: "t1".into(),
+            position: Position::new(6.0, 6.0),
+            ..pool.active[0].clone()
+        });
+        let ids = |tasks: Vec<&TaskInstance>| -> Vec<String> {
+            tasks.iter().map(|t| t.id.to_string()).collect()
+        };
+
+        // Agents 5.7 apart, each within sensing range of one task.
+        let alone = RLConfig::builder()
+            .sensing(Sensing::new(2.0, 0.0))
+            .build()
+            .unwrap();
+        let candidates = ObservationBuilder::candidates_all(&agents, &pool, &alone);
+        assert_eq!(ids(candidates[0].clone()), ["t0"]);
+        assert_eq!(ids(candidates[1].clone()), ["t1"]);
+        let obs = ObservationBuilder::build(0, &agents, &pool, &alone);
+        let agent_dim = RLConfig::AGENT_FEATURE_DIM;
+        let task_dim = alone.task_feature_dim();
+        assert!(obs[agent_dim + task_dim..agent_dim + 2 * task_dim]
+            .iter()
+            .all(|&f| f == 0.0));
+        let mask = ObservationBuilder::action_mask(0, &agents, &pool, &alone);
+        assert_eq!(mask, vec![true, true, false, false, false, false]);
+
+        // Within communication range, both see both tasks.
+        let shared = RLConfig::builder()
+            .sensing(Sensing::new(2.0, 6.0))
+            .build()
+            .unwrap();
+        for tasks in ObservationBuilder::candidates_all(&agents, &pool, &shared) {
+            assert_eq!(tasks.len(), 2);
+        }
+        let mask = ObservationBuilder::action_mask(0, &agents, &pool, &shared);
+        assert_eq!(mask, vec![true, true, true, false, false, false]);
+    }
+
     #[test]
     fn global_state_dim_correct() {
         let agents = make_agents();
@@ -0,0 +1,255 @@
+//! Keep-out zones in the 2D domain.
+//!
+//! [`RLConfig::obstacles`](super::config::RLConfig::obstacles) lists static
+//! [`Obstacle`]s agents may not enter. Agents do not plan paths around
+//! them: an agent heading into one stops at its border and spends the rest
+//! of its step sliding along it, on whichever axis still leads toward the
+//! target (see [`steer`]). Policies have to learn detours by picking other
+//! targets, helped by one observation feature per candidate telling whether
+//! the straight line to it is blocked (see
+//! [`RLConfig::obstacle_dim`](super::config::RLConfig::obstacle_dim)).
+//!
+//! Spawn positions inside an obstacle are redrawn, so tasks land there only
+//! in worlds that are mostly obstacle. A task inside one can still be
+//! collected from outside if its collection radius reaches past the border.
+//!
+//! With the `serde` feature, obstacles are tagged by a `shape` field:
+//!
+//! ```text
+//! {"shape": "rect", "min": {"x": 2.0, "y": 0.0}, "max": {"x": 3.0, "y": 6.0}}
+//! {"shape": "polygon", "vertices": [{"x": 0.0, "y": 0.0}, {"x": 1.0, "y": 0.0}, {"x": 0.0, "y": 1.0}]}
+//! ```
+
+use super::types::Position;
+
+/// How far short of an obstacle's border a blocked agent stops, so it is
+/// never on the border itself.
+const CLEARANCE: f64 = 1e-6;
+
+/// A static region agents may not enter.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "shape", rename_all = "snake_case")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Obstacle {
+    /// An axis-aligned rectangle.
+    Rect { min: Position, max: Position },
+    /// A simple polygon, vertices in either winding order.
+    Polygon { vertices: Vec<Position> },
+}
+
+impl Obstacle {
+    /// Creates the axis-aligned rectangle with corners `min` and `max`.
+    pub fn rect(min: Position, max: Position) -> Self {
+        Self::Rect { min, max }
+    }
+
+    /// Creates a polygon from its vertices.
+    pub fn polygon(vertices: Vec<Position>) -> Self {
+        Self::Polygon { vertices }
+    }
+
+    /// Vertices of the obstacle, in order.
+    pub fn vertices(&self) -> Vec<Position> {
+        match self {
+            Self::Rect { min, max } => vec![
+                *min,
+                Position::new(max.x, min.y),
+                *max,
+                Position::new(min.x, max.y),
+            ],
+            Self::Polygon { vertices } => vertices.clone(),
+        }
+    }
+
+    /// Returns true if the obstacle encloses an area: a rectangle with
+    /// `min < max` on both axes, or a polygon with at least 3 vertices, all
+    /// with finite coordinates.
+    pub fn is_valid(&self) -> bool {
+        let finite = |p: &Position| p.x.is_finite() && p.y.is_finite();
+        match self {
+            Self::Rect { min, max } => finite(min) && finite(max) && min.x < max.x && min.y < max.y,
+            Self::Polygon { vertices } => vertices.len() >= 3 && vertices.iter().all(finite),
+        }
+    }
+
+    /// Returns true if `position` is strictly inside the obstacle.
+    pub fn contains(&self, position: &Position) -> bool {
+        if let Self::Rect { min, max } = self {
+            return min.x < position.x
+                && position.x < max.x
+                && min.y < position.y
+                && position.y < max.y;
+        }
+        // Even-odd ray casting toward +x.
+        let mut inside = false;
+        for (a, b) in edges(&self.vertices()) {
+            if (a.y > position.y) != (b.y > position.y) {
+                let x = a.x + (position.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if position.x < x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    /// Distance from `from` along the segment to `to` at which the segment
+    /// first touches the obstacle's border, if it does past `from`.
+    pub fn first_hit(&self, from: &Position, to: &Position) -> Option<f64> {
+        let length = from.distance_to(to);
+        if length == 0.0 {
+            return None;
+        }
+        edges(&self.vertices())
+            .filter_map(|(a, b)| segment_hit(from, to, &a, &b))
+            .map(|t| t * length)
+            .filter(|&d| d > 0.0)
+            .min_by(f64::total_cmp)
+    }
+}
+
+/// Returns true if the straight line from `from` to `to` crosses an
+/// obstacle.
+pub fn is_blocked(obstacles: &[Obstacle], from: &Position, to: &Position) -> bool {
+    obstacles.iter().any(|o| o.first_hit(from, to).is_some())
+}
+
+/// Moves from `from` toward `to`, as far as the obstacles allow.
+///
+/// If the straight segment is blocked, the agent stops just short of the
+/// first obstacle and spends the distance left sliding along the axis
+/// where it is farther from `to`, or along the other axis if that one is
+/// blocked too, never past `to`'s coordinate. Returns the position
+/// reached, the distance travelled and whether an obstacle got in the way.
+pub fn steer(obstacles: &[Obstacle], from: Position, to: Position) -> (Position, f64, bool) {
+    let length = from.distance_to(&to);
+    let (stop, travelled) = advance(obstacles, from, to);
+    if travelled >= length {
+        return (to, travelled, false);
+    }
+    let left = length - travelled;
+    let (gap_x, gap_y) = (to.x - stop.x, to.y - stop.y);
+    let along_x = Position::new(stop.x + gap_x.signum() * gap_x.abs().min(left), stop.y);
+    let along_y = Position::new(stop.x, stop.y + gap_y.signum() * gap_y.abs().min(left));
+    let axes = if gap_x.abs() >= gap_y.abs() {
+        [along_x, along_y]
+    } else {
+        [along_y, along_x]
+    };
+    for target in axes {
+        let (reached, moved) = advance(obstacles, stop, target);
+        if moved > CLEARANCE {
+            return (reached, travelled + moved, true);
+        }
+    }
+    (stop, travelled, true)
+}
+
+/// Moves straight from `from` toward `to`, stopping short of the first
+/// obstacle in the way.
+fn advance(obstacles: &[Obstacle], from: Position, to: Position) -> (Position, f64) {
+    let length = from.distance_to(&to);
+    match obstacles
+        .iter()
+        .filter_map(|o| o.first_hit(&from, &to))
+        .min_by(f64::total_cmp)
+    {
+        None => (to, length),
+        Some(hit) => {
+            let travelled = (hit - CLEARANCE).max(0.0);
+            let (dx, dy) = from.direction_to(&to);
+            (
+                Position::new(from.x + dx * travelled, from.y + dy * travelled),
+                travelled,
+            )
+        }
+    }
+}
+
+/// Edges of a closed polygon.
+fn edges(vertices: &[Position]) -> impl Iterator<Item = (Position, Position)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+/// Fraction along `p → q` where it meets segment `a → b`, if it does.
+fn segment_hit(p: &Position, q: &Position, a: &Position, b: &Position) -> Option<f64> {
+    let (rx, ry) = (q.x - p.x, q.y - p.y);
+    let (sx, sy) = (b.x - a.x, b.y - a.y);
+    let denom = rx * sy - ry * sx;
+    if denom.abs() < 1e-15 {
+        // Parallel segments only touch along the border, which does not
+        // block movement.
+        return None;
+    }
+    let (wx, wy) = (a.x - p.x, a.y - p.y);
+    let t = (wx * sy - wy * sx) / denom;
+    let u = (wx * ry - wy * rx) / denom;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then_some(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wall() -> Obstacle {
+        Obstacle::rect(Position::new(2.0, 0.0), Position::new(3.0, 6.0))
+    }
+
+    #[test]
+    fn shapes_contain_and_block() {
+        let triangle = Obstacle::polygon(vec![
+            Position::new(0.0, 0.0),
+            Position::new(4.0, 0.0),
+            Position::new(0.0, 4.0),
+        ]);
+        assert!(triangle.contains(&Position::new(1.0, 1.0)));
+        assert!(!triangle.contains(&Position::new(3.0, 3.0)));
+        assert!(wall().contains(&Position::new(2.5, 1.0)));
+        assert!(!wall().contains(&Position::new(2.0, 1.0)));
+
+        let hit = wall()
+            .first_hit(&Position::new(0.0, 1.0), &Position::new(5.0, 1.0))
+            .unwrap();
+        assert!((hit - 2.0).abs() < 1e-12);
+        assert!(is_blocked(
+            &[wall()],
+            &Position::new(0.0, 1.0),
+            &Position::new(5.0, 1.0)
+        ));
+        assert!(!is_blocked(
+            &[wall()],
+            &Position::new(0.0, 7.0),
+            &Position::new(5.0, 7.0)
+        ));
+        assert!(!Obstacle::rect(Position::new(1.0, 0.0), Position::new(1.0, 2.0)).is_valid());
+        assert!(!Obstacle::polygon(vec![Position::origin(); 2]).is_valid());
+    }
+
+    #[test]
+    fn steering_stops_at_the_border_and_slides() {
+        // Straight into the wall: stop just before it.
+        let (p, moved, blocked) =
+            steer(&[wall()], Position::new(0.0, 1.0), Position::new(2.5, 1.0));
+        assert!(blocked);
+        assert!((p.x - 2.0).abs() < 1e-5 && p.x < 2.0);
+        assert!((moved - 2.0).abs() < 1e-5);
+
+        // At an angle: the rest of the step slides up the wall.
+        let (p, _, blocked) = steer(&[wall()], Position::new(1.0, 4.0), Position::new(3.0, 6.0));
+        assert!(blocked);
+        assert!(p.x < 2.0);
+        assert!(p.y > 5.0 && !wall().contains(&p));
+
+        let (p, moved, blocked) =
+            steer(&[wall()], Position::new(0.0, 7.0), Position::new(4.0, 7.0));
+        assert!(!blocked);
+        assert_eq!(p, Position::new(4.0, 7.0));
+        assert_eq!(moved, 4.0);
+    }
+}
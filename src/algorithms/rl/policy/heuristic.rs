@@ -3,8 +3,10 @@
 //! Assigns agents to tasks by scoring each task based on value, distance,
 //! and urgency. Fulfills type requirements greedily, allowing excess agents.
 
+use qtty::Unit;
+
 use super::trait_::Policy;
-use crate::algorithms::rl::config::RLConfig;
+use crate::algorithms::rl::config::{RLConfig, TaskFeature, TaskFeatures};
 
 /// Greedy heuristic policy for multi-agent task assignment.
 ///
@@ -20,20 +22,48 @@ use crate::algorithms::rl::config::RLConfig;
 /// This policy serves as a competitive baseline (§7.1) and should outperform
 /// the random policy significantly.
 pub struct GreedyHeuristicPolicy {
-    config: RLConfig,
+    /// Number of candidate tasks per observation.
+    top_m: usize,
+    /// Per-task features included in observations.
+    task_features: TaskFeatures,
 }
 
 impl GreedyHeuristicPolicy {
-    /// Creates a new greedy heuristic policy with the given configuration.
-    pub fn new(config: RLConfig) -> Self {
-        Self { config }
+    /// Creates a new greedy heuristic policy for the observations of the
+    /// given configuration.
+    pub fn new<U: Unit>(config: RLConfig<U>) -> Self {
+        Self {
+            top_m: config.top_m,
+            task_features: config.task_features,
+        }
     }
 }
 
 impl Policy for GreedyHeuristicPolicy {
     fn select_actions(&mut self, observations: &[Vec<f64>]) -> Vec<usize> {
+        self.choose(observations, None)
+    }
+
+    /// Picks the best-scoring task among each agent's valid targets.
+    fn select_actions_masked(
+        &mut self,
+        observations: &[Vec<f64>],
+        masks: &[Vec<bool>],
+    ) -> Vec<usize> {
+        self.choose(observations, Some(masks))
+    }
+
+    fn name(&self) -> &str {
+        "greedy_heuristic"
+    }
+}
+
+impl GreedyHeuristicPolicy {
+    /// Scores candidate tasks per agent, skipping targets masked out by
+    /// `masks` if given.
+    fn choose(&self, observations: &[Vec<f64>], masks: Option<&[Vec<bool>]>) -> Vec<usize> {
         let n_agents = observations.len();
-        let top_m = self.config.top_m;
+        let top_m = self.top_m;
         let eps = 1e-6;
 
         // Track how many agents are heading to each task (for coordination)
@@ -41,9 +71,17 @@ impl Policy for GreedyHeuristicPolicy {
         let mut actions = vec![0usize; n_agents];
 
         // First pass: extract per-agent and per-task info from observations
-        // Observation layout: [agent_features(5)] ++ [task_1(10)] ++ ... ++ [task_M(10)]
+        // Observation layout: [agent_features(5)] ++ [task_1(F)] ++ ... ++ [task_M(F)]
         let agent_feat_dim = RLConfig::AGENT_FEATURE_DIM;
-        let task_feat_dim = RLConfig::TASK_FEATURE_DIM;
+        let task_feat_dim = self.task_features.dim();
+        let features = self.task_features;
+        // Left-out features read as neutral: on the agent, valuable, not
+        // urgent, no requirement.
+        let read = |task_obs: &[f64], feature: TaskFeature, i: usize, default: f64| {
+            features
+                .offset(feature)
+                .map_or(default, |offset| task_obs[offset + i])
+        };
 
         for i in 0..n_agents {
             let obs = &observations[i];
@@ -76,18 +114,34 @@ impl Policy for GreedyHeuristicPolicy {
                     break;
                 }
 
-                let task_x = task_obs[0]; // normalized
-                let task_y = task_obs[1];
-                let task_value = task_obs[2]; // normalized
-                let task_time_left = task_obs[3]; // normalized
+                let task_x = read(task_obs, TaskFeature::Position, 0, agent_x); // normalized
+                let task_y = read(task_obs, TaskFeature::Position, 1, agent_y);
+                let task_value = read(task_obs, TaskFeature::Value, 0, 1.0); // normalized
+                let task_time_left = read(task_obs, TaskFeature::TimeLeft, 0, 1.0); // normalized
                 let task_reqs = [
-                    task_obs[4], // r_young
-                    task_obs[5], // r_middle
-                    task_obs[6], // r_old
+                    read(task_obs, TaskFeature::Requirements, 0, 0.0), // r_young
+                    read(task_obs, TaskFeature::Requirements, 1, 0.0), // r_middle
+                    read(task_obs, TaskFeature::Requirements, 2, 0.0), // r_old
                 ];
 
                 // Skip zero-padded (empty) task slots
-                if task_value.abs() < eps && task_time_left.abs() < eps {
+                let empty = if features.value && features.time_left {
+                    task_value.abs() < eps && task_time_left.abs() < eps
+                } else {
+                    task_obs.iter().all(|v| v.abs() < eps)
+                };
+                if empty {
+                    continue;
+                }
+
+                // Skip targets outside this agent's action space
+                let valid = masks.is_none_or(|m| {
+                    m.get(i)
+                        .and_then(|m| m.get(j + 1))
+                        .copied()
+                        .unwrap_or(false)
+                });
+                if !valid {
                     continue;
                 }
 
@@ -126,10 +180,6 @@ impl Policy for GreedyHeuristicPolicy {
 
         actions
     }
-
-    fn name(&self) -> &str {
-        "greedy_heuristic"
-    }
 }
 
 #[cfg(test)]
@@ -175,7 +225,30 @@ mod tests {
         obs[18] = 0.01; // time_left (very urgent!)
         obs[19] = 1.0; // r_young
 
-        let actions = policy.select_actions(&[obs]);
+        let actions = policy.select_actions(&[obs.clone()]);
         assert_eq!(actions[0], 2); // should pick the urgent task
+
+        // Same tasks without position and requirements features.
+        let mut narrow = RLConfig {
+            top_m: 2,
+            ..RLConfig::default()
+        };
+        narrow.task_features = narrow
+            .task_features
+            .without(TaskFeature::Position)
+            .without(TaskFeature::Requirements);
+        let task_dim = narrow.task_feature_dim();
+        let mut short = obs[..5].to_vec();
+        for task in obs[5..].chunks(10) {
+            short.extend(&task[2..4]);
+            short.extend(&task[7..]);
+        }
+        assert_eq!(short.len(), 5 + 2 * task_dim);
+        let mut policy_narrow = GreedyHeuristicPolicy::new(narrow);
+        assert_eq!(policy_narrow.select_actions(&[short]), vec![2]);
+
+        // Urgent task out of this agent's action space
+        let masks = vec![vec![true, true, false]];
+        assert_eq!(policy.select_actions_masked(&[obs], &masks), vec![1]);
     }
 }
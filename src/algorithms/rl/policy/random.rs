@@ -31,6 +31,33 @@ impl Policy for RandomPolicy {
             .collect()
     }
 
+    /// Samples uniformly among each agent's valid actions.
+    fn select_actions_masked(
+        &mut self,
+        observations: &[Vec<f64>],
+        masks: &[Vec<bool>],
+    ) -> Vec<usize> {
+        let mut rng = rand::rng();
+        (0..observations.len())
+            .map(|i| {
+                let valid: Vec<usize> = (0..self.action_dim)
+                    .filter(|&a| {
+                        masks
+                            .get(i)
+                            .and_then(|m| m.get(a))
+                            .copied()
+                            .unwrap_or(a == 0)
+                    })
+                    .collect();
+                if valid.is_empty() {
+                    0
+                } else {
+                    valid[rng.random_range(0..valid.len())]
+                }
+            })
+            .collect()
+    }
+
     fn name(&self) -> &str {
         "random"
     }
@@ -57,4 +84,13 @@ mod tests {
             assert!(a < 6);
         }
     }
+
+    #[test]
+    fn random_policy_respects_masks() {
+        let mut policy = RandomPolicy::new(6);
+        let obs = vec![vec![0.0; 10]; 50];
+        let masks = vec![vec![true, false, false, true, false, false]; 50];
+        let actions = policy.select_actions_masked(&obs, &masks);
+        assert!(actions.iter().all(|&a| a == 0 || a == 3));
+    }
 }
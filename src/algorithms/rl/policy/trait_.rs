@@ -15,8 +15,39 @@ pub trait Policy: Send {
     /// # Returns
     ///
     /// A vector of actions, one per agent.
+    ///
+    /// Recurrent policies also advance their per-agent hidden state here, so
+    /// calls must follow the episode's step order.
     fn select_actions(&mut self, observations: &[Vec<f64>]) -> Vec<usize>;
 
+    /// Like [`select_actions`](Self::select_actions), restricted to the
+    /// actions marked valid in each agent's mask (see
+    /// [`ObservationBuilder::action_mask`]).
+    ///
+    /// The default selects unrestricted actions and replaces invalid ones
+    /// with patrol; policies that can do better override it.
+    ///
+    /// [`ObservationBuilder::action_mask`]: crate::algorithms::rl::observation::ObservationBuilder::action_mask
+    fn select_actions_masked(
+        &mut self,
+        observations: &[Vec<f64>],
+        masks: &[Vec<bool>],
+    ) -> Vec<usize> {
+        let mut actions = self.select_actions(observations);
+        for (action, mask) in actions.iter_mut().zip(masks) {
+            if !mask.get(*action).copied().unwrap_or(false) {
+                *action = 0;
+            }
+        }
+        actions
+    }
+
+    /// Called at the start of every episode, before the first
+    /// [`select_actions`](Self::select_actions).
+    ///
+    /// Recurrent policies clear their hidden state; the default does nothing.
+    fn reset(&mut self) {}
+
     /// Returns a human-readable name for this policy.
     fn name(&self) -> &str;
 }
@@ -13,23 +13,35 @@
 //!
 //! Time-only, single agent type, one resource per task.
 //! Spatial displacement is ignored (all positions at origin).
+//!
+//! # Time axis
+//!
+//! The scheduler's [`RLConfig`] is in the scheduling axis unit: task
+//! deadlines are the steps of [`RLConfig::step_duration`] their windows
+//! cover, and an episode runs for the steps the horizon covers, both capped
+//! at [`RLConfig::episode_horizon`].
 
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
-use qtty::{Quantity, Unit};
+use qtty::{Quantity, Second, Unit};
 use tch::Device;
 
 use super::config::RLConfig;
+use super::distilled::{task_features, DistillConfig, DistilledScorer, RankingExample};
 use super::environment::RLEnvironment;
+use super::metrics::HeldOutProblem;
 use super::network::NeuralPolicy;
 use super::policy::Policy;
-use super::task_pool::TaskTemplate;
+use super::schedule_reward::ScheduleShaping;
+use super::task_pool::{template_name, TaskTemplate};
 use super::types::{AgentType, AgentTypeRequirements};
+use crate::algorithms::greedy::find_earliest_non_overlapping;
+use crate::algorithms::greedy::scoring::priority_urgency;
 use crate::algorithms::SchedulingAlgorithm;
 use crate::schedule::Schedule;
 use crate::scheduling_block::{SchedulingBlock, Task};
-use crate::solution_space::{Interval, SolutionSpace};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
 
 /// RL scheduler that uses a trained neural policy for task selection ordering.
 ///
@@ -44,14 +56,14 @@ use crate::solution_space::{Interval, SolutionSpace};
 /// let scheduler = RLScheduler::from_checkpoint("checkpoints/actor.safetensors", tch::Device::Cpu)?;
 /// let schedule = scheduler.schedule(&blocks, &solution_space, horizon);
 /// ```
-pub struct RLScheduler {
+pub struct RLScheduler<U: Unit = Second> {
     /// Loaded neural policy (runs in greedy mode for inference).
     ///
     /// Wrapped in `Mutex` because [`SchedulingAlgorithm::schedule`] takes `&self`
     /// but policy inference requires `&mut self` (the [`Policy`] trait).
     policy: Mutex<NeuralPolicy>,
     /// RL environment configuration.
-    config: RLConfig,
+    config: RLConfig<U>,
     /// Path to the actor checkpoint.
     checkpoint_path: PathBuf,
 }
@@ -73,17 +85,38 @@ impl RLScheduler {
     ) -> Result<Self, tch::TchError> {
         Self::from_checkpoint_with_config(checkpoint_path, RLConfig::default(), device)
     }
+}
 
+impl<U: Unit> RLScheduler<U> {
     /// Creates a new RL scheduler with custom RL config.
+    ///
+    /// If an `obs_norm.safetensors` file sits next to the checkpoint, its
+    /// observation statistics are applied to the policy. If a
+    /// `layout.safetensors` file does, it must match `config`'s
+    /// observation layout (see [`RLConfig::check_layout`]).
     pub fn from_checkpoint_with_config(
         checkpoint_path: impl AsRef<Path>,
-        config: RLConfig,
+        config: RLConfig<U>,
         device: Device,
     ) -> Result<Self, tch::TchError> {
+        let layout = checkpoint_path
+            .as_ref()
+            .with_file_name("layout.safetensors");
+        if layout.exists() {
+            config.check_layout(&layout)?;
+        }
         let mut policy = NeuralPolicy::new(&config, device);
         policy.load_actor(checkpoint_path.as_ref())?;
         policy.set_greedy(true);
 
+        // Observation statistics saved next to the actor by the trainer
+        let obs_norm = checkpoint_path
+            .as_ref()
+            .with_file_name("obs_norm.safetensors");
+        if obs_norm.exists() {
+            policy.load_observation_normalizer(&obs_norm)?;
+        }
+
         Ok(Self {
             policy: Mutex::new(policy),
             config,
@@ -94,7 +127,7 @@ impl RLScheduler {
     /// Creates an RL scheduler with an already-loaded policy.
     ///
     /// Useful for evaluation during training without reloading from disk.
-    pub fn with_policy(policy: NeuralPolicy, config: RLConfig) -> Self {
+    pub fn with_policy(policy: NeuralPolicy, config: RLConfig<U>) -> Self {
         Self {
             policy: Mutex::new(policy),
             config,
@@ -107,19 +140,47 @@ impl RLScheduler {
         &self.checkpoint_path
     }
 
+    /// Steps of the configured duration covering `duration`, at least one
+    /// and at most the episode horizon.
+    fn steps(&self, duration: Quantity<U>) -> u32 {
+        self.config
+            .steps_for(duration)
+            .clamp(1, self.config.episode_horizon.max(1))
+    }
+
+    /// Template of a task of `priority` whose fitting windows add up to
+    /// `capacity`: it expires after the steps that capacity covers, so
+    /// more capacity means a later deadline.
+    fn template(&self, name: &str, priority: i32, capacity: Quantity<U>) -> TaskTemplate {
+        // Map priority to value (positive, higher is better)
+        let value = (priority as f64).max(1.0);
+        let deadline = self.steps(capacity);
+        TaskTemplate {
+            name: name.to_string(),
+            value_range: (value, value), // Fixed value (no randomness)
+            deadline_range: (deadline, deadline),
+            type_requirements: AgentTypeRequirements::new(1, 0, 0),
+            collection_radius: Some(self.config.collection_radius),
+            max_appearances: Some(1),
+            appearances_used: 0,
+            work_units: None,
+        }
+    }
+
     /// Runs a single RL episode to determine task selection ordering.
     ///
     /// Converts scheduling tasks to RL task templates, runs the policy in
-    /// the environment, and returns the IDs of collected tasks in the order
-    /// they were collected.
-    fn determine_task_order<T, U>(
+    /// the environment for the steps `horizon` covers, and returns the IDs
+    /// of collected tasks in the order they were collected.
+    fn determine_task_order<T, D, E>(
         &self,
-        blocks: &[SchedulingBlock<T, U, (), petgraph::Directed>],
+        blocks: &[SchedulingBlock<T, U, D, E>],
         solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
     ) -> Vec<String>
     where
-        T: Task<U> + Clone,
-        U: Unit,
+        T: Task<U>,
+        E: petgraph::EdgeType,
     {
         // Convert scheduling tasks to RL task templates.
         let mut templates = Vec::new();
@@ -137,24 +198,9 @@ impl RLScheduler {
                     continue;
                 }
 
-                // Map priority to value (positive, higher is better)
-                let value = (task.priority() as f64).max(1.0);
-
-                // Derive deadline from available capacity: more capacity = later deadline
                 let total_capacity: f64 =
                     intervals.iter().map(|iv| iv.duration().value()).sum::<f64>();
-                let urgency_steps = (total_capacity / task_size).ceil() as u32;
-                let deadline = urgency_steps.max(1).min(self.config.episode_horizon);
-
-                templates.push(TaskTemplate {
-                    name: id.to_string(),
-                    value_range: (value, value), // Fixed value (no randomness)
-                    deadline_range: (deadline, deadline),
-                    type_requirements: AgentTypeRequirements::new(1, 0, 0),
-                    collection_radius: Some(self.config.collection_radius),
-                    max_appearances: Some(1),
-                    appearances_used: 0,
-                });
+                templates.push(self.template(id, task.priority(), Quantity::new(total_capacity)));
             }
         }
 
@@ -168,22 +214,25 @@ impl RLScheduler {
 
         // Run episode with the policy
         let mut obs = env.reset();
+        let mut masks = env.action_masks();
         let mut collected_order = Vec::new();
         let mut policy = self.policy.lock().expect("policy mutex poisoned");
+        policy.reset();
 
-        for _ in 0..self.config.episode_horizon {
-            let actions = policy.select_actions(&obs);
+        for _ in 0..self.steps(horizon.duration()) {
+            let actions = policy.select_actions_masked(&obs, &masks);
             let result = env.step(actions);
 
             // Record newly collected task IDs (instance ID → task ID)
             for instance_id in &env.task_pool.collected_ids {
-                let task_id = instance_id_to_task_id(instance_id);
+                let task_id = template_name(instance_id);
                 if !collected_order.contains(&task_id.to_string()) {
                     collected_order.push(task_id.to_string());
                 }
             }
 
             obs = result.observations;
+            masks = result.action_masks;
             if result.done {
                 break;
             }
@@ -191,9 +240,53 @@ impl RLScheduler {
 
         collected_order
     }
+
+    /// Distills this policy's task preferences into a [`DistilledScorer`].
+    ///
+    /// For every problem, the order in which the policy collects tasks is
+    /// taken as the teacher ranking over the problem's [`task_features`];
+    /// tasks it never collects rank below all collected ones. The result
+    /// runs in a [`DistilledScheduler`](super::distilled::DistilledScheduler)
+    /// without libtorch.
+    pub fn distill<T, D, E>(
+        &self,
+        problems: &[HeldOutProblem<T, U, D, E>],
+        config: &DistillConfig,
+    ) -> DistilledScorer
+    where
+        T: Task<U>,
+        E: petgraph::EdgeType,
+    {
+        let examples: Vec<RankingExample> = problems
+            .iter()
+            .map(|problem| {
+                let (ids, features): (Vec<&str>, Vec<_>) = problem
+                    .blocks
+                    .iter()
+                    .flat_map(|b| b.tasks())
+                    .filter_map(|(id, task)| {
+                        let x = task_features(
+                            task,
+                            id,
+                            &problem.solution_space,
+                            problem.horizon.interval(),
+                        )?;
+                        Some((id, x))
+                    })
+                    .unzip();
+                let order = self
+                    .determine_task_order(&problem.blocks, &problem.solution_space, problem.horizon)
+                    .iter()
+                    .filter_map(|id| ids.iter().position(|x| *x == id.as_str()))
+                    .collect();
+                RankingExample { features, order }
+            })
+            .collect();
+        DistilledScorer::fit(&examples, config)
+    }
 }
 
-impl<T, U, D, E> SchedulingAlgorithm<T, U, D, E> for RLScheduler
+impl<T, U, D, E> SchedulingAlgorithm<T, U, D, E> for RLScheduler<U>
 where
     T: Task<U> + Clone,
     U: Unit,
@@ -203,9 +296,8 @@ where
         &self,
         blocks: &[SchedulingBlock<T, U, D, E>],
         solution_space: &SolutionSpace<U>,
-        horizon: Interval<U>,
+        horizon: Horizon<U>,
     ) -> Schedule<U> {
-        let mut schedule = Schedule::new();
         let horizon_start = horizon.start().value();
         let horizon_end = horizon.end().value();
 
@@ -231,29 +323,15 @@ where
         }
 
         if task_info.is_empty() {
-            return schedule;
+            return Schedule::new();
         }
 
         // Step 1: Get policy-driven task ordering via RL episode.
         // Build task templates and run episode to get priority ordering.
         let mut templates = Vec::new();
-        for (id, size, priority, fitting) in &task_info {
-            let value = (*priority as f64).max(1.0);
+        for (id, _, priority, fitting) in &task_info {
             let total_capacity: f64 = fitting.iter().map(|(s, e)| e - s).sum();
-            let urgency_steps = (total_capacity / size).ceil() as u32;
-            let deadline = urgency_steps
-                .max(1)
-                .min(self.config.episode_horizon);
-
-            templates.push(TaskTemplate {
-                name: id.clone(),
-                value_range: (value, value),
-                deadline_range: (deadline, deadline),
-                type_requirements: AgentTypeRequirements::new(1, 0, 0),
-                collection_radius: Some(self.config.collection_radius),
-                max_appearances: Some(1),
-                appearances_used: 0,
-            });
+            templates.push(self.template(id, *priority, Quantity::new(total_capacity)));
         }
 
         // Run RL episode to determine ordering
@@ -261,70 +339,40 @@ where
             RLEnvironment::with_templates(self.config.clone(), templates, 0);
         env.set_agents(&[(1, AgentType::Young)]);
 
+        // Collected tasks are placed as they are collected, in policy order
+        let mut shaping = ScheduleShaping::new(blocks, solution_space, horizon, &self.config);
         let mut obs = env.reset();
-        let mut policy_order: Vec<String> = Vec::new();
-        let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+        let mut masks = env.action_masks();
         let mut policy = self.policy.lock().expect("policy mutex poisoned");
+        policy.reset();
 
-        for _ in 0..self.config.episode_horizon {
-            let actions = policy.select_actions(&obs);
+        for _ in 0..self.steps(horizon.duration()) {
+            let actions = policy.select_actions_masked(&obs, &masks);
             let result = env.step(actions);
-
-            // Collected instance IDs are "{task_id}_{counter}"; recover original task ID
-            for instance_id in &env.task_pool.collected_ids {
-                let task_id = instance_id_to_task_id(instance_id);
-                if seen.insert(task_id.to_string()) {
-                    policy_order.push(task_id.to_string());
-                }
-            }
+            shaping.record(&env.task_pool);
 
             obs = result.observations;
+            masks = result.action_masks;
             if result.done {
                 break;
             }
         }
 
-        // Release the policy lock before the placement phases
+        // Release the policy lock before the fallback phase
         drop(policy);
 
-        // Build a lookup for task_info
-        let task_lookup: std::collections::HashMap<String, (f64, Vec<(f64, f64)>)> = task_info
-            .iter()
-            .map(|(id, size, _, fitting)| (id.clone(), (*size, fitting.clone())))
-            .collect();
-
-        // Step 2: Place tasks in policy order using constraint-respecting placement.
-        // Tasks collected by the policy go first, remaining tasks follow in greedy order.
-        let mut scheduled_ids: std::collections::HashSet<String> =
-            std::collections::HashSet::new();
-
-        // Phase A: Policy-ordered tasks
-        for id in &policy_order {
-            if let Some((size, fitting)) = task_lookup.get(id) {
-                if let Some(start) = find_earliest_non_overlapping(
-                    fitting,
-                    *size,
-                    horizon_start,
-                    horizon_end,
-                    &schedule,
-                ) {
-                    let end = start + size;
-                    let interval =
-                        Interval::new(Quantity::new(start), Quantity::new(end));
-                    let _ = schedule.add(id, interval);
-                    scheduled_ids.insert(id.clone());
-                }
-            }
-        }
+        // Step 2: Tasks collected by the policy are already placed (phase A);
+        // remaining tasks follow in greedy order.
+        let mut schedule = shaping.into_schedule();
 
         // Phase B: Remaining tasks (not selected by policy) in greedy score order
         let mut remaining: Vec<&(String, f64, i32, Vec<(f64, f64)>)> = task_info
             .iter()
-            .filter(|(id, _, _, _)| !scheduled_ids.contains(id))
+            .filter(|(id, _, _, _)| !schedule.contains_task(id))
             .collect();
         remaining.sort_by(|a, b| {
-            let score_a = greedy_score(a.2, a.3.iter().map(|(s, e)| e - s).sum());
-            let score_b = greedy_score(b.2, b.3.iter().map(|(s, e)| e - s).sum());
+            let score_a = priority_urgency(a.2, a.3.iter().map(|(s, e)| e - s).sum());
+            let score_b = priority_urgency(b.2, b.3.iter().map(|(s, e)| e - s).sum());
             score_b
                 .partial_cmp(&score_a)
                 .unwrap_or(std::cmp::Ordering::Equal)
@@ -349,70 +397,11 @@ where
     }
 }
 
-/// Scores a task for greedy fallback selection.
-fn greedy_score(priority: i32, remaining_capacity: f64) -> f64 {
-    let eps = 1e-6;
-    let urgency = 1.0 / (eps + remaining_capacity);
-    (priority as f64).max(1.0) * urgency
-}
-
-/// Finds the earliest placement start that fits within feasible windows
-/// without overlapping already-scheduled intervals.
-fn find_earliest_non_overlapping<U: Unit>(
-    intervals: &[(f64, f64)],
-    size: f64,
-    cursor: f64,
-    horizon_end: f64,
-    schedule: &Schedule<U>,
-) -> Option<f64> {
-    for &(win_start, win_end) in intervals {
-        let effective_start = win_start.max(cursor);
-        if effective_start + size > win_end || effective_start + size > horizon_end {
-            continue;
-        }
-
-        let mut candidate_start = effective_start;
-        loop {
-            let candidate_end = candidate_start + size;
-            if candidate_end > win_end || candidate_end > horizon_end {
-                break;
-            }
-
-            let query =
-                Interval::new(Quantity::new(candidate_start), Quantity::new(candidate_end));
-            match schedule.is_free(query) {
-                Ok(true) => return Some(candidate_start),
-                Ok(false) => {
-                    if let Ok(conflicts) = schedule.conflicts_vec(query) {
-                        if let Some((_, conflict_iv)) = conflicts.first() {
-                            candidate_start = conflict_iv.end().value();
-                            continue;
-                        }
-                    }
-                    candidate_start += 1e-6;
-                }
-                Err(_) => break,
-            }
-        }
-    }
-    None
-}
-
-/// Extracts the original scheduling task ID from an RL task instance ID.
-///
-/// Instance IDs are formatted as `{template_name}_{counter}` by [`TaskPool::spawn`].
-/// This strips the `_{counter}` suffix to recover the original task name.
-fn instance_id_to_task_id(instance_id: &str) -> &str {
-    instance_id
-        .rsplit_once('_')
-        .map(|(prefix, _)| prefix)
-        .unwrap_or(instance_id)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::constraints::IntervalConstraint;
+    use crate::test_utils::hz;
     use qtty::Second;
 
     #[derive(Debug, Clone)]
@@ -463,7 +452,7 @@ mod tests {
         space.add_interval(&id1, Interval::from_f64(0.0, 500.0));
         space.add_interval(&id2, Interval::from_f64(0.0, 500.0));
 
-        let horizon = Interval::from_f64(0.0, 500.0);
+        let horizon = hz(0.0, 500.0);
         let schedule = scheduler.schedule(&[block], &space, horizon);
 
         assert_eq!(schedule.len(), 2);
@@ -500,10 +489,93 @@ mod tests {
             space.add_interval(id, Interval::from_f64(0.0, 1000.0));
         }
 
-        let horizon = Interval::from_f64(0.0, 1000.0);
+        let horizon = hz(0.0, 1000.0);
         let schedule = scheduler.schedule(&[block], &space, horizon);
 
         // All 5 tasks should fit (5 × 50.0 = 250.0 < 1000.0)
         assert_eq!(schedule.len(), 5);
     }
+
+    #[test]
+    fn step_duration_sets_deadlines_and_episode_length() {
+        // 50 s steps: 500 s of windows is 10 steps, a 2000 s horizon is 40
+        // steps, capped at the 20-step episode.
+        let config = RLConfig {
+            episode_horizon: 20,
+            top_m: 3,
+            ..RLConfig::default()
+        }
+        .with_step_duration(Quantity::<Second>::new(50.0));
+        let policy = NeuralPolicy::new(&config, Device::Cpu);
+        let scheduler = RLScheduler::with_policy(policy, config);
+        assert_eq!(scheduler.steps(Quantity::new(500.0)), 10);
+        assert_eq!(scheduler.steps(Quantity::new(2000.0)), 20);
+        assert_eq!(scheduler.steps(Quantity::new(10.0)), 1);
+        let template = scheduler.template("t", 3, Quantity::new(500.0));
+        assert_eq!(template.deadline_range, (10, 10));
+
+        let mut block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
+        let mut space = SolutionSpace::new();
+        let mut ids = Vec::new();
+        for i in 0..3 {
+            let id = block.add_task(make_task(&format!("t{}", i), 100.0, i + 1));
+            space.add_interval(&id, Interval::from_f64(0.0, 500.0));
+            ids.push(id);
+        }
+        let horizon = hz(0.0, 2000.0);
+        let order = scheduler.determine_task_order(std::slice::from_ref(&block), &space, horizon);
+        assert!(order.iter().all(|id| ids.contains(id)));
+
+        let schedule = scheduler.schedule(&[block], &space, horizon);
+        assert_eq!(schedule.len(), 3);
+        let mut placed: Vec<_> = ids
+            .iter()
+            .map(|id| schedule.get_interval(id).unwrap())
+            .collect();
+        placed.sort_by(|a, b| a.start().value().total_cmp(&b.start().value()));
+        assert!(placed
+            .windows(2)
+            .all(|w| w[0].end().value() <= w[1].start().value()));
+        assert!(placed.iter().all(|iv| iv.end().value() <= 500.0));
+    }
+
+    #[test]
+    fn distilled_scorer_schedules_without_policy() {
+        use crate::algorithms::rl::distilled::DistilledScheduler;
+
+        let config = RLConfig {
+            episode_horizon: 10,
+            top_m: 3,
+            ..RLConfig::default()
+        };
+        let policy = NeuralPolicy::new(&config, Device::Cpu);
+        let scheduler = RLScheduler::with_policy(policy, config);
+
+        let mut block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
+        let mut space = SolutionSpace::new();
+        for i in 0..4 {
+            let id = block.add_task(make_task(&format!("t{}", i), 50.0, i + 1));
+            space.add_interval(&id, Interval::from_f64(0.0, 1000.0));
+        }
+        let problem = HeldOutProblem {
+            blocks: vec![block],
+            solution_space: space,
+            horizon: hz(0.0, 1000.0),
+        };
+
+        let scorer = scheduler.distill(
+            std::slice::from_ref(&problem),
+            &DistillConfig {
+                epochs: 20,
+                ..DistillConfig::default()
+            },
+        );
+        let schedule = DistilledScheduler::new(scorer).schedule(
+            &problem.blocks,
+            &problem.solution_space,
+            problem.horizon,
+        );
+
+        assert_eq!(schedule.len(), 4);
+    }
 }
@@ -3,6 +3,8 @@
 //! Combines collection rewards, time penalties, progress shaping,
 //! requirement coverage shaping, and expiration penalties.
 
+use qtty::Unit;
+
 use super::agent::AgentState;
 use super::config::RLConfig;
 use super::task_pool::TaskInstance;
@@ -16,17 +18,45 @@ impl RewardComputer {
     /// # Components
     ///
     /// 1. **Collection reward**: `+V_j` for each collected task.
-    /// 2. **Expiration penalty**: `-β × V_j` for each expired task.
+    /// 2. **Expiration penalty**: `-β × V_j × (1 - progress_j)` for each
+    ///    expired task, so credit already earned is not penalized.
     /// 3. **Time penalty**: `-c_time` per step.
     /// 4. **Progress shaping**: `+α × Σ_i (dist_prev - dist_now)` for agents moving toward targets.
     /// 5. **Coverage shaping**: `+γ × ΔP_j` — improvement in requirement coverage since last step.
-    pub fn compute(
+    /// 6. **Depletion penalty**: with an energy model, `-depletion_penalty`
+    ///    per agent with an empty battery.
+    pub fn compute<U: Unit>(
         collected_value: f64,
         expired_tasks: &[TaskInstance],
         agents: &[AgentState],
         prev_agent_pos_types: &[(super::types::Position, super::types::AgentType)],
         top_m_tasks: &[&TaskInstance],
-        config: &RLConfig,
+        config: &RLConfig<U>,
+    ) -> f64 {
+        let candidates = vec![top_m_tasks.to_vec(); agents.len()];
+        Self::compute_with_candidates(
+            collected_value,
+            expired_tasks,
+            agents,
+            prev_agent_pos_types,
+            top_m_tasks,
+            &candidates,
+            config,
+        )
+    }
+
+    /// Like [`compute`](Self::compute), measuring each agent's progress
+    /// shaping against its own candidate list, as agents with
+    /// [`RLConfig::sensing`] target tasks in their own
+    /// [candidates](super::observation::ObservationBuilder::candidates).
+    pub fn compute_with_candidates<U: Unit>(
+        collected_value: f64,
+        expired_tasks: &[TaskInstance],
+        agents: &[AgentState],
+        prev_agent_pos_types: &[(super::types::Position, super::types::AgentType)],
+        top_m_tasks: &[&TaskInstance],
+        candidates: &[Vec<&TaskInstance>],
+        config: &RLConfig<U>,
     ) -> f64 {
         let mut reward = 0.0;
 
@@ -35,7 +65,7 @@ impl RewardComputer {
 
         // 2. Expiration penalty
         for task in expired_tasks {
-            reward -= config.reward_expiry_beta * task.value;
+            reward -= config.reward_expiry_beta * task.value * (1.0 - task.progress());
         }
 
         // 3. Time penalty
@@ -44,7 +74,8 @@ impl RewardComputer {
         // 4. Progress shaping
         let progress: f64 = agents
             .iter()
-            .map(|a| a.progress_toward_target(top_m_tasks))
+            .zip(candidates)
+            .map(|(a, tasks)| a.progress_toward_target(tasks))
             .sum();
         reward += config.reward_progress_alpha * progress;
 
@@ -69,6 +100,12 @@ impl RewardComputer {
             reward += config.reward_coverage_gamma * (curr_coverage - prev_coverage);
         }
 
+        // 6. Depletion penalty
+        if let Some(energy) = &config.energy {
+            let depleted = agents.iter().filter(|a| a.is_depleted()).count();
+            reward -= energy.depletion_penalty * depleted as f64;
+        }
+
         reward
     }
 
@@ -131,6 +168,8 @@ mod tests {
             type_requirements: AgentTypeRequirements::default(),
             collection_radius: 1.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         }];
         let reward = RewardComputer::compute(0.0, &expired, &[], &[], &[], &config);
         // Should be: -beta*10 - c_time = -5.0 - 0.01
@@ -148,6 +187,8 @@ mod tests {
             type_requirements: AgentTypeRequirements::new(1, 1, 0),
             collection_radius: 2.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         };
         let agents = vec![
             (Position::new(5.0, 5.0), AgentType::Young),
@@ -168,6 +209,8 @@ mod tests {
             type_requirements: AgentTypeRequirements::new(2, 0, 0),
             collection_radius: 2.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         };
         // Only 1 of 2 required young agents present
         let agents = vec![(Position::new(5.0, 5.0), AgentType::Young)];
@@ -194,6 +237,8 @@ mod tests {
             type_requirements: AgentTypeRequirements::new(1, 0, 0),
             collection_radius: 3.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         };
         let top_m: Vec<&TaskInstance> = vec![&task];
 
@@ -235,6 +280,8 @@ mod tests {
             type_requirements: AgentTypeRequirements::new(1, 0, 0),
             collection_radius: 3.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         };
         let top_m: Vec<&TaskInstance> = vec![&task];
 
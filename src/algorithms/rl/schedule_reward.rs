@@ -0,0 +1,321 @@
+//! Schedule-aware reward shaping for the scheduling bridge.
+//!
+//! The environment reward only sees abstract task values. When the
+//! environment is built from a real scheduling problem (see
+//! [`TaskPool::from_blocks`](super::task_pool::TaskPool::from_blocks)),
+//! [`ScheduleShaping`] also places every collected task into a schedule
+//! under construction — at its earliest free position inside its windows —
+//! and rewards what the placement achieved:
+//!
+//! - utilization gain: `reward_schedule_utilization × size / horizon length`
+//! - priority-weighted completion:
+//!   `reward_schedule_priority × (p − p_min + 1) / (p_max − p_min + 1)`
+//!
+//! Collected tasks that no longer fit earn nothing. Placements are computed
+//! against a [`SchedulingContext`], the same view of the partial schedule
+//! that dynamic constraints receive.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use qtty::Second;
+//! # use virolai::algorithms::rl::{RLConfig, RLEnvironment, ScheduleShaping};
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let space = instance.solution_space;
+//! # let config = RLConfig::default();
+//! # let templates = Vec::new();
+//! # let actions = vec![0; 3];
+//! let mut shaping = ScheduleShaping::new(&blocks, &space, horizon, &config);
+//! let mut env = RLEnvironment::with_templates(config, templates, 0);
+//! env.reset();
+//! let result = env.step_with_hook(actions, &mut shaping);
+//! let schedule = shaping.into_schedule();
+//! ```
+
+use std::collections::HashMap;
+
+use qtty::{Quantity, Unit};
+
+use super::config::RLConfig;
+use super::hooks::{StepContext, StepHook};
+use super::task_pool::{template_name, TaskPool};
+use crate::algorithms::greedy::find_earliest_non_overlapping;
+use crate::constraints::SchedulingContext;
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+use crate::Id;
+
+/// Builds a schedule from collected tasks and rewards each placement.
+///
+/// Usable as a registered [`StepHook`] (for training on a problem) or
+/// passed to
+/// [`RLEnvironment::step_with_hook`](super::environment::RLEnvironment::step_with_hook)
+/// to read the schedule back afterwards.
+#[derive(Debug)]
+pub struct ScheduleShaping<U: Unit> {
+    /// Size and priority per task ID.
+    tasks: HashMap<Id, (f64, i32)>,
+    /// Lowest and highest priority among the tasks.
+    priority_range: (i32, i32),
+    /// Windows of the tasks.
+    solution_space: SolutionSpace<U>,
+    horizon: Horizon<U>,
+    utilization_weight: f64,
+    priority_weight: f64,
+    schedule: Schedule<U>,
+    /// Number of entries of `TaskPool::collected_ids` already handled.
+    processed: usize,
+}
+
+impl<U: Unit> ScheduleShaping<U> {
+    /// Creates shaping for the tasks of `blocks`, taking the reward weights
+    /// from `config`.
+    pub fn new<T, D, E, V: Unit>(
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+        config: &RLConfig<V>,
+    ) -> Self
+    where
+        T: Task<U>,
+        E: petgraph::EdgeType,
+    {
+        let tasks: HashMap<Id, (f64, i32)> = blocks
+            .iter()
+            .flat_map(|b| b.tasks())
+            .map(|(id, task)| {
+                (
+                    id.to_string(),
+                    (task.size_on_axis().value(), task.priority()),
+                )
+            })
+            .collect();
+        let priority_range = tasks
+            .values()
+            .fold(None, |range: Option<(i32, i32)>, &(_, p)| {
+                Some(range.map_or((p, p), |(lo, hi)| (lo.min(p), hi.max(p))))
+            })
+            .unwrap_or((0, 0));
+        let mut windows = SolutionSpace::new();
+        for id in tasks.keys() {
+            if let Some(intervals) = solution_space.get_intervals(id) {
+                windows.set_intervals(id.as_str(), intervals.as_slice().to_vec());
+            }
+        }
+        Self {
+            tasks,
+            priority_range,
+            solution_space: windows,
+            horizon,
+            utilization_weight: config.reward_schedule_utilization,
+            priority_weight: config.reward_schedule_priority,
+            schedule: Schedule::new(),
+            processed: 0,
+        }
+    }
+
+    /// The schedule built so far.
+    pub fn schedule(&self) -> &Schedule<U> {
+        &self.schedule
+    }
+
+    /// Consumes the shaping and returns the schedule built so far.
+    pub fn into_schedule(self) -> Schedule<U> {
+        self.schedule
+    }
+
+    /// Fraction of the horizon covered by the schedule.
+    pub fn utilization(&self) -> f64 {
+        let length = self.horizon.duration().value();
+        if length > 0.0 {
+            self.schedule.total_duration().value() / length
+        } else {
+            0.0
+        }
+    }
+
+    /// Where `task_id` would be placed against `ctx`, and the reward for
+    /// placing it there.
+    ///
+    /// Returns `None` for unknown or already scheduled tasks and for tasks
+    /// that no longer fit.
+    pub fn marginal_reward(
+        &self,
+        ctx: &SchedulingContext<'_, U>,
+        task_id: &str,
+    ) -> Option<(Interval<U>, f64)> {
+        let &(size, priority) = self.tasks.get(task_id)?;
+        if ctx.schedule.contains_task(task_id) {
+            return None;
+        }
+        let windows: Vec<(f64, f64)> = ctx
+            .solution_space
+            .get_intervals(task_id)?
+            .iter()
+            .map(|w| (w.start().value(), w.end().value()))
+            .collect();
+        let start = find_earliest_non_overlapping(
+            &windows,
+            size,
+            self.horizon.start().value(),
+            self.horizon.end().value(),
+            ctx.schedule,
+        )?;
+
+        let length = self.horizon.duration().value();
+        let utilization = if length > 0.0 { size / length } else { 0.0 };
+        let (lo, hi) = self.priority_range;
+        let priority = f64::from(priority - lo + 1) / f64::from(hi - lo + 1);
+        let reward = self.utilization_weight * utilization + self.priority_weight * priority;
+        let interval = Interval::new(Quantity::new(start), Quantity::new(start + size));
+        Some((interval, reward))
+    }
+
+    /// Places the tasks collected in `task_pool` since the last call and
+    /// returns the reward earned.
+    pub fn record(&mut self, task_pool: &TaskPool) -> f64 {
+        let mut reward = 0.0;
+        for instance_id in task_pool.collected_ids.iter().skip(self.processed) {
+            let task_id = template_name(instance_id);
+            let ctx = SchedulingContext::new(&self.schedule, &self.solution_space);
+            if let Some((interval, gain)) = self.marginal_reward(&ctx, task_id) {
+                if self.schedule.add(task_id, interval).is_ok() {
+                    reward += gain;
+                }
+            }
+        }
+        self.processed = task_pool.collected_ids.len();
+        reward
+    }
+}
+
+impl<U: Unit + Send, V: Unit> StepHook<V> for ScheduleShaping<U> {
+    fn name(&self) -> &str {
+        "schedule"
+    }
+
+    fn reset(&mut self) {
+        self.schedule.clear();
+        self.processed = 0;
+    }
+
+    fn post_step(&mut self, ctx: &mut StepContext<'_, V>, reward: &mut f64) {
+        *reward += self.record(ctx.task_pool);
+    }
+
+    fn state(&self) -> Vec<(String, f64)> {
+        vec![
+            ("utilization".to_string(), self.utilization()),
+            ("scheduled".to_string(), self.schedule.len() as f64),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::rl::environment::RLEnvironment;
+    use crate::algorithms::rl::policy::{GreedyHeuristicPolicy, Policy};
+    use crate::algorithms::rl::types::AgentType;
+    use crate::test_utils::{block_and_space, hz, iv, TestTask};
+    use qtty::Second;
+
+    fn problem() -> (
+        Vec<SchedulingBlock<TestTask, Second>>,
+        SolutionSpace<Second>,
+    ) {
+        let (block, ss) = block_and_space(&[
+            ("a", 20.0, 1, iv(0.0, 30.0)),
+            ("b", 20.0, 3, iv(0.0, 30.0)),
+            ("c", 20.0, 5, iv(0.0, 30.0)),
+        ]);
+        (vec![block], ss)
+    }
+
+    #[test]
+    fn marginal_reward_weighs_utilization_and_priority() {
+        let (blocks, ss) = problem();
+        let config = RLConfig::builder()
+            .schedule_rewards(2.0, 1.0)
+            .build()
+            .unwrap();
+        let shaping = ScheduleShaping::new(&blocks, &ss, hz(0.0, 100.0), &config);
+
+        let empty = Schedule::new();
+        let ctx = SchedulingContext::new(&empty, &ss);
+        let (interval, reward) = shaping.marginal_reward(&ctx, "c").unwrap();
+        assert_eq!(interval, iv(0.0, 20.0));
+        // 2 × 20/100 + 1 × (5 − 1 + 1)/(5 − 1 + 1)
+        assert!((reward - 1.4).abs() < 1e-12);
+        let (_, low) = shaping.marginal_reward(&ctx, "a").unwrap();
+        assert!((low - 0.6).abs() < 1e-12);
+
+        // Once "c" holds [0, 20), nothing else fits in [0, 30).
+        let mut taken = Schedule::new();
+        taken.add("c", interval).unwrap();
+        let ctx = SchedulingContext::new(&taken, &ss);
+        assert!(shaping.marginal_reward(&ctx, "a").is_none());
+        assert!(shaping.marginal_reward(&ctx, "c").is_none());
+        assert!(shaping.marginal_reward(&ctx, "unknown").is_none());
+    }
+
+    #[test]
+    fn collected_tasks_are_placed_once() {
+        let (blocks, ss) = problem();
+        let config = RLConfig::default();
+        let mut shaping = ScheduleShaping::new(&blocks, &ss, hz(0.0, 100.0), &config);
+        let mut pool = TaskPool::new(vec![]);
+        pool.collected_ids = vec!["b_0".into(), "a_1".into()];
+
+        let reward = shaping.record(&pool);
+        assert!(reward > 0.0);
+        assert!(shaping.schedule().contains_task("b"));
+        assert!(!shaping.schedule().contains_task("a"));
+        assert_eq!(shaping.record(&pool), 0.0);
+        assert!((shaping.utilization() - 0.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn shaping_adds_to_episode_reward_only() {
+        let (blocks, ss) = problem();
+        let config = RLConfig {
+            episode_horizon: 40,
+            spawn_rate: 1.0,
+            ..RLConfig::small()
+        };
+        let run = |shaping: Option<&mut ScheduleShaping<Second>>| {
+            let pool = TaskPool::from_blocks(&blocks, &ss, &config).unwrap();
+            let mut env = RLEnvironment::with_templates(config.clone(), pool.templates, 0);
+            env.set_agents(&[(3, AgentType::Young)]);
+            let mut policy = GreedyHeuristicPolicy::new(config.clone());
+            let mut obs = env.reset();
+            let mut shaping = shaping;
+            loop {
+                let actions = policy.select_actions(&obs);
+                let result = match shaping.as_deref_mut() {
+                    Some(hook) => env.step_with_hook(actions, hook),
+                    None => env.step(actions),
+                };
+                obs = result.observations;
+                if result.done {
+                    return (env.cumulative_reward, env.task_pool.collected_ids.len());
+                }
+            }
+        };
+
+        let mut shaping = ScheduleShaping::new(&blocks, &ss, hz(0.0, 100.0), &config);
+        let (shaped, collected) = run(Some(&mut shaping));
+        let (plain, same_collected) = run(None);
+
+        // Shaping changes the reward, never the dynamics.
+        assert_eq!(collected, same_collected);
+        assert!(collected > 0);
+        assert!(!shaping.schedule().is_empty());
+        assert!(shaped > plain);
+    }
+}
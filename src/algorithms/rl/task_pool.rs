@@ -1,11 +1,17 @@
 //! Task instance management: spawning, collection, and expiration.
 
+use qtty::{Quantity, Unit};
 use rand::{Rng, RngExt};
 
-use super::config::RLConfig;
+use super::config::{RLConfig, RLConfigError, TaskFeature};
 use super::types::{AgentType, AgentTypeRequirements, Position};
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::SolutionSpace;
 use crate::Id;
 
+/// Times a spawn position inside an obstacle is redrawn before keeping it.
+const MAX_SPAWN_REDRAWS: usize = 32;
+
 /// A concrete task instance active in the RL environment.
 ///
 /// Unlike the abstract [`crate::scheduling_block::Task`] trait, this is a
@@ -29,17 +35,31 @@ pub struct TaskInstance {
     pub collection_radius: f64,
     /// Remaining appearances for this task type (None = unlimited).
     pub remaining_appearances: Option<u32>,
+    /// Work units needed for collection (1 with instant collection).
+    pub work_units: u32,
+    /// Work units received so far.
+    pub work_done: u32,
 }
 
 impl TaskInstance {
+    /// Fraction of the required work received so far, in `[0, 1]`.
+    pub fn progress(&self) -> f64 {
+        if self.work_units == 0 {
+            return 1.0;
+        }
+        (self.work_done as f64 / self.work_units as f64).min(1.0)
+    }
+
     /// Returns this task's features as a vector for observation encoding.
     ///
-    /// Format: `[x_norm, y_norm, value_norm, time_left_norm, r_young, r_middle, r_old, k_rem_norm, heading_young, heading_middle]`
+    /// Format: `[x_norm, y_norm, value_norm, time_left_norm, r_young, r_middle, r_old, k_rem_norm, heading_young, heading_middle]`,
+    /// keeping only the groups selected by
+    /// [`RLConfig::task_features`](super::config::RLConfig::task_features).
     ///
     /// Values are normalized by the provided scales.
-    pub fn features(
+    pub fn features<U: Unit>(
         &self,
-        config: &RLConfig,
+        config: &RLConfig<U>,
         max_value: f64,
         heading_counts: [u32; 3],
     ) -> Vec<f64> {
@@ -58,18 +78,20 @@ impl TaskInstance {
             .map(|k| k as f64 / 10.0)
             .unwrap_or(1.0);
 
-        vec![
-            nx,
-            ny,
-            v_norm,
-            t_norm,
-            reqs[0] as f64,
-            reqs[1] as f64,
-            reqs[2] as f64,
-            k_norm,
-            heading_counts[0] as f64,
-            heading_counts[1] as f64,
-        ]
+        let mut features = Vec::with_capacity(config.task_feature_dim());
+        for feature in config.task_features.iter() {
+            match feature {
+                TaskFeature::Position => features.extend([nx, ny]),
+                TaskFeature::Value => features.push(v_norm),
+                TaskFeature::TimeLeft => features.push(t_norm),
+                TaskFeature::Requirements => features.extend(reqs.map(f64::from)),
+                TaskFeature::Appearances => features.push(k_norm),
+                TaskFeature::Heading => {
+                    features.extend([heading_counts[0] as f64, heading_counts[1] as f64])
+                }
+            }
+        }
+        features
     }
 }
 
@@ -90,6 +112,9 @@ pub struct TaskTemplate {
     pub max_appearances: Option<u32>,
     /// Appearances used so far.
     pub appearances_used: u32,
+    /// Work units override for progressive collection (None = use global
+    /// default).
+    pub work_units: Option<u32>,
 }
 
 impl TaskTemplate {
@@ -140,6 +165,7 @@ impl TaskPool {
                 collection_radius: None,
                 max_appearances: None,
                 appearances_used: 0,
+                work_units: None,
             },
             TaskTemplate {
                 name: "medium".into(),
@@ -149,6 +175,7 @@ impl TaskPool {
                 collection_radius: None,
                 max_appearances: Some(10),
                 appearances_used: 0,
+                work_units: None,
             },
             TaskTemplate {
                 name: "hard".into(),
@@ -158,11 +185,87 @@ impl TaskPool {
                 collection_radius: None,
                 max_appearances: Some(5),
                 appearances_used: 0,
+                work_units: None,
             },
         ];
         Self::new(templates)
     }
 
+    /// Creates a task pool whose templates mirror a real scheduling problem.
+    ///
+    /// Every task of `blocks` with windows in `space` becomes a template that
+    /// spawns at most once per episode:
+    ///
+    /// - **value** is `1 + priority − lowest priority`, so the least important
+    ///   task is worth 1;
+    /// - **deadline** ranges from the task's size to its total window length,
+    ///   in steps of [`RLConfig::step_duration`] capped at the episode
+    ///   horizon, so tight tasks expire quickly and flexible ones linger;
+    /// - **requirements** come from the task's coalition via
+    ///   [`AgentTypeRequirements::from_coalition`], defaulting to one young
+    ///   agent;
+    /// - **work units** are the task's size in steps, so long tasks
+    ///   take longer to collect under
+    ///   [`CollectionMode::Progressive`](super::config::CollectionMode::Progressive).
+    ///
+    /// Tasks without windows are skipped since they can never be scheduled.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first problem reported by [`RLConfig::validate`], such as
+    /// a zero episode horizon.
+    pub fn from_blocks<T, U, D, E>(
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        space: &SolutionSpace<U>,
+        config: &RLConfig<U>,
+    ) -> Result<Self, RLConfigError>
+    where
+        T: Task<U>,
+        U: Unit,
+        E: petgraph::EdgeType,
+    {
+        config.validate()?;
+        let tasks: Vec<(&str, &T)> = blocks
+            .iter()
+            .flat_map(|b| b.tasks())
+            .filter(|(id, _)| space.get_intervals(id).is_some_and(|w| !w.is_empty()))
+            .collect();
+
+        let steps =
+            |duration: Quantity<U>| config.steps_for(duration).clamp(1, config.episode_horizon);
+        let lowest = tasks.iter().map(|(_, t)| t.priority()).min().unwrap_or(0);
+
+        let templates = tasks
+            .into_iter()
+            .map(|(id, task)| {
+                let value = 1.0 + (task.priority() as f64 - lowest as f64);
+                let windows: f64 = space
+                    .get_intervals(id)
+                    .into_iter()
+                    .flatten()
+                    .map(|w| w.duration().value())
+                    .sum();
+                let latest = steps(Quantity::new(windows));
+                let work = steps(task.size_on_axis());
+                let earliest = work.min(latest);
+                TaskTemplate {
+                    name: id.to_string(),
+                    value_range: (value, value),
+                    deadline_range: (earliest, latest),
+                    type_requirements: task
+                        .coalition()
+                        .and_then(AgentTypeRequirements::from_coalition)
+                        .unwrap_or_default(),
+                    collection_radius: None,
+                    max_appearances: Some(1),
+                    appearances_used: 0,
+                    work_units: Some(work),
+                }
+            })
+            .collect();
+        Ok(Self::new(templates))
+    }
+
     /// Resets the pool for a new episode.
     pub fn reset(&mut self) {
         self.active.clear();
@@ -178,7 +281,8 @@ impl TaskPool {
     ///
     /// At each call, for each template with remaining appearances, a task
     /// is spawned with probability `config.spawn_rate`, up to the active limit.
-    pub fn spawn<R: Rng + RngExt>(&mut self, rng: &mut R, config: &RLConfig) {
+    /// Positions inside an obstacle are redrawn, up to a few times.
+    pub fn spawn<R: Rng + RngExt, U: Unit>(&mut self, rng: &mut R, config: &RLConfig<U>) {
         if self.active.len() >= config.max_active_tasks {
             return;
         }
@@ -204,10 +308,20 @@ impl TaskPool {
             let value = rng.random::<f64>() * (template.value_range.1 - template.value_range.0)
                 + template.value_range.0;
             let deadline = rng.random_range(template.deadline_range.0..=template.deadline_range.1);
-            let position = Position::new(
+            let mut position = Position::new(
                 rng.random::<f64>() * config.world_width,
                 rng.random::<f64>() * config.world_height,
             );
+            // Redraw positions inside obstacles; give up on crowded worlds.
+            for _ in 0..MAX_SPAWN_REDRAWS {
+                if !config.obstacles.iter().any(|o| o.contains(&position)) {
+                    break;
+                }
+                position = Position::new(
+                    rng.random::<f64>() * config.world_width,
+                    rng.random::<f64>() * config.world_height,
+                );
+            }
             let radius = template
                 .collection_radius
                 .unwrap_or(config.collection_radius);
@@ -227,6 +341,8 @@ impl TaskPool {
                 remaining_appearances: template
                     .max_appearances
                     .map(|max| max - template.appearances_used),
+                work_units: config.work_units_for(template.work_units),
+                work_done: 0,
             });
         }
     }
@@ -238,14 +354,19 @@ impl TaskPool {
         }
     }
 
-    /// Checks which tasks can be collected and removes them from active.
+    /// Works on the tasks whose coalition is present and removes the
+    /// completed ones from active.
     ///
-    /// A task is collected when agents of each required type within the
-    /// collection radius meet or exceed the minimum counts.
+    /// A task receives one work unit when agents of each required type within
+    /// the collection radius meet or exceed the minimum counts, crediting
+    /// `value / work_units`. It is collected once all its units are done,
+    /// which with instant collection is the first such step.
     ///
     /// # Returns
     ///
-    /// `(collected_tasks, total_value)` — the collected tasks and sum of their values.
+    /// `(collected_tasks, credited_value)` — the completed tasks and the
+    /// value credited this step, including partial credit for tasks still
+    /// in progress.
     pub fn try_collect(
         &mut self,
         agent_positions: &[(Position, AgentType)],
@@ -253,7 +374,7 @@ impl TaskPool {
         let mut collected = Vec::new();
         let mut total_value = 0.0;
 
-        self.active.retain(|task| {
+        self.active.retain_mut(|task| {
             // Count agents by type within collection radius
             let mut counts = [0u32; 3];
             for (pos, atype) in agent_positions {
@@ -266,7 +387,12 @@ impl TaskPool {
                 .type_requirements
                 .is_satisfied_by(counts[0], counts[1], counts[2])
             {
-                total_value += task.value;
+                let units = task.work_units.max(1);
+                task.work_done += 1;
+                total_value += task.value / units as f64;
+                if task.work_done < units {
+                    return true; // keep working
+                }
                 collected.push(task.clone());
                 false // remove from active
             } else {
@@ -310,10 +436,22 @@ impl TaskPool {
     /// * `agent_positions` - Positions of all agents (for distance computation)
     /// * `m` - Number of candidates to return
     pub fn top_m(&self, agent_positions: &[Position], m: usize) -> Vec<&TaskInstance> {
+        self.top_m_where(agent_positions, m, |_| true)
+    }
+
+    /// Like [`top_m`](Self::top_m), ranking only the tasks for which
+    /// `visible` returns true.
+    pub fn top_m_where(
+        &self,
+        agent_positions: &[Position],
+        m: usize,
+        visible: impl Fn(&TaskInstance) -> bool,
+    ) -> Vec<&TaskInstance> {
         let eps = 1e-6;
         let mut scored: Vec<(f64, &TaskInstance)> = self
             .active
             .iter()
+            .filter(|task| visible(task))
             .map(|task| {
                 let min_dist = agent_positions
                     .iter()
@@ -330,6 +468,18 @@ impl TaskPool {
     }
 }
 
+/// Extracts the template name (the original scheduling task ID for pools
+/// built from a problem) from an instance ID.
+///
+/// Instance IDs are formatted as `{template_name}_{counter}` by [`TaskPool::spawn`].
+/// This strips the `_{counter}` suffix to recover the original task name.
+pub(crate) fn template_name(instance_id: &str) -> &str {
+    instance_id
+        .rsplit_once('_')
+        .map(|(prefix, _)| prefix)
+        .unwrap_or(instance_id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -338,6 +488,71 @@ mod tests {
         TaskPool::with_defaults()
     }
 
+    #[test]
+    fn from_blocks_mirrors_workload() {
+        use crate::test_utils::{iv, TestTask};
+        use qtty::Second;
+
+        let mut block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
+        let mut space = SolutionSpace::new();
+        let tasks = [
+            (
+                TestTask::new("tight", 10.0).with_priority(5),
+                vec![iv(0.0, 10.0)],
+            ),
+            (
+                TestTask::new("loose", 10.0)
+                    .with_priority(2)
+                    .with_coalition(AgentTypeRequirements::new(1, 1, 0).into()),
+                vec![iv(0.0, 50.0), iv(60.0, 100.0)],
+            ),
+            (TestTask::new("never", 10.0).with_priority(0), vec![]),
+        ];
+        for (task, windows) in tasks {
+            let name = task.name().to_string();
+            block.add_task_with_id(task, Some(name.clone())).unwrap();
+            space.add_intervals(name, windows);
+        }
+        let config = RLConfig {
+            episode_horizon: 200,
+            step_duration: Quantity::new(0.5),
+            ..RLConfig::default()
+        };
+
+        let blocks = [block];
+        let pool = TaskPool::from_blocks(&blocks, &space, &config).unwrap();
+
+        assert_eq!(pool.templates.len(), 2);
+        let tight = pool.templates.iter().find(|t| t.name == "tight").unwrap();
+        let loose = pool.templates.iter().find(|t| t.name == "loose").unwrap();
+        assert_eq!(tight.value_range, (4.0, 4.0));
+        assert_eq!(loose.value_range, (1.0, 1.0));
+        // Half-second steps: 2 steps per second, capped at the horizon.
+        assert_eq!(tight.deadline_range, (20, 20));
+        assert_eq!(loose.deadline_range, (20, 180));
+        assert_eq!(loose.work_units, Some(20));
+        assert_eq!(tight.type_requirements, AgentTypeRequirements::default());
+        assert_eq!(loose.type_requirements, AgentTypeRequirements::new(1, 1, 0));
+        assert!(pool.templates.iter().all(|t| t.max_appearances == Some(1)));
+
+        let short = RLConfig {
+            episode_horizon: 50,
+            ..config
+        };
+        let pool = TaskPool::from_blocks(&blocks, &space, &short).unwrap();
+        let loose = pool.templates.iter().find(|t| t.name == "loose").unwrap();
+        assert_eq!(loose.deadline_range, (20, 50));
+
+        let zero = RLConfig {
+            episode_horizon: 0,
+            ..short
+        };
+        assert!(matches!(
+            TaskPool::from_blocks(&blocks, &space, &zero),
+            Err(RLConfigError::ZeroHorizon)
+        ));
+    }
+
     #[test]
     fn spawn_respects_max_active() {
         let mut pool = make_pool();
@@ -366,6 +581,8 @@ mod tests {
             type_requirements: AgentTypeRequirements::default(),
             collection_radius: 1.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         });
         pool.tick();
         assert_eq!(pool.active[0].remaining_time, 4);
@@ -383,6 +600,8 @@ mod tests {
             type_requirements: AgentTypeRequirements::new(1, 0, 0),
             collection_radius: 2.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         });
 
         // Agent within radius
@@ -405,6 +624,8 @@ mod tests {
             type_requirements: AgentTypeRequirements::new(2, 0, 0), // need 2 young
             collection_radius: 2.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         });
 
         // Only 1 young agent
@@ -414,6 +635,42 @@ mod tests {
         assert_eq!(pool.active.len(), 1);
     }
 
+    #[test]
+    fn progressive_collection_credits_work() {
+        use crate::algorithms::rl::config::CollectionMode;
+
+        let config = RLConfig {
+            collection: CollectionMode::Progressive { work_units: 4 },
+            spawn_rate: 1.0,
+            ..RLConfig::default()
+        };
+        let mut pool = TaskPool::new(vec![TaskTemplate {
+            name: "long".into(),
+            value_range: (8.0, 8.0),
+            deadline_range: (10, 10),
+            type_requirements: AgentTypeRequirements::new(1, 0, 0),
+            collection_radius: Some(100.0),
+            max_appearances: Some(1),
+            appearances_used: 0,
+            work_units: None,
+        }]);
+        pool.spawn(&mut rand::rng(), &config);
+        assert_eq!(pool.active[0].work_units, 4);
+
+        let agents = vec![(Position::new(0.0, 0.0), AgentType::Young)];
+        for step in 1..4 {
+            let (collected, credit) = pool.try_collect(&agents);
+            assert!(collected.is_empty());
+            assert!((credit - 2.0).abs() < 1e-12);
+            assert!((pool.active[0].progress() - step as f64 / 4.0).abs() < 1e-12);
+        }
+        let (collected, credit) = pool.try_collect(&agents);
+        assert_eq!(collected.len(), 1);
+        assert!((credit - 2.0).abs() < 1e-12);
+        assert!(pool.active.is_empty());
+        assert_eq!(pool.collected_ids, vec!["long_0".to_string()]);
+    }
+
     #[test]
     fn expire_removes_timed_out() {
         let mut pool = TaskPool::new(vec![]);
@@ -426,6 +683,8 @@ mod tests {
             type_requirements: AgentTypeRequirements::default(),
             collection_radius: 1.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         });
 
         let expired = pool.expire();
@@ -447,6 +706,8 @@ mod tests {
             type_requirements: AgentTypeRequirements::default(),
             collection_radius: 1.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         });
         // Far low-value task
         pool.active.push(TaskInstance {
@@ -458,6 +719,8 @@ mod tests {
             type_requirements: AgentTypeRequirements::default(),
             collection_radius: 1.0,
             remaining_appearances: None,
+            work_units: 1,
+            work_done: 0,
         });
 
         let agents = vec![Position::new(0.0, 0.0)];
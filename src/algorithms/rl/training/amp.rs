@@ -0,0 +1,145 @@
+//! Device selection and mixed-precision (AMP) support for training.
+//!
+//! With [`TrainingConfig::mixed_precision`](super::TrainingConfig::mixed_precision)
+//! enabled on a CUDA device, forward passes run under `tch::autocast` (FP16
+//! where safe) and losses are scaled by a [`GradScaler`] so small FP16
+//! gradients do not underflow.
+
+use tch::{nn, Device, Tensor};
+
+/// Picks the training device: the first CUDA device if one is available,
+/// otherwise the CPU.
+pub fn select_device() -> Device {
+    Device::cuda_if_available()
+}
+
+/// Dynamic loss scaler for FP16 training.
+///
+/// Losses are multiplied by the current scale before `backward`, and
+/// gradients divided by it before the optimizer step. If any gradient is
+/// non-finite the step is skipped and the scale reduced; after
+/// `growth_interval` consecutive good steps the scale is increased again.
+#[derive(Debug, Clone)]
+pub struct GradScaler {
+    scale: f64,
+    growth_factor: f64,
+    backoff_factor: f64,
+    growth_interval: u32,
+    good_steps: u32,
+}
+
+impl GradScaler {
+    /// Creates a scaler with PyTorch's defaults: initial scale 2¹⁶, growth
+    /// ×2 every 2000 good steps, backoff ×0.5.
+    pub fn new() -> Self {
+        Self {
+            scale: 65536.0,
+            growth_factor: 2.0,
+            backoff_factor: 0.5,
+            growth_interval: 2000,
+            good_steps: 0,
+        }
+    }
+
+    /// Current loss scale.
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Returns `loss` multiplied by the current scale.
+    pub fn scale_loss(&self, loss: &Tensor) -> Tensor {
+        loss * self.scale
+    }
+
+    /// Divides the gradients in `vs` by the current scale, in place.
+    ///
+    /// Returns `false` if any gradient is NaN or infinite; the optimizer
+    /// step must then be skipped.
+    pub fn unscale(&self, vs: &nn::VarStore) -> bool {
+        let inv_scale = 1.0 / self.scale;
+        let mut finite = true;
+        for var in vs.trainable_variables() {
+            let mut grad = var.grad();
+            if grad.defined() {
+                let _ = grad.multiply_scalar_(inv_scale);
+                finite &= grad.isfinite().all().int64_value(&[]) != 0;
+            }
+        }
+        finite
+    }
+
+    /// Updates the scale after a step; `finite` is the result of
+    /// [`unscale`](Self::unscale).
+    pub fn update(&mut self, finite: bool) {
+        if finite {
+            self.good_steps += 1;
+            if self.good_steps >= self.growth_interval {
+                self.scale *= self.growth_factor;
+                self.good_steps = 0;
+            }
+        } else {
+            self.scale = (self.scale * self.backoff_factor).max(1.0);
+            self.good_steps = 0;
+        }
+    }
+}
+
+impl Default for GradScaler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tch::Kind;
+
+    #[test]
+    fn scale_backs_off_on_overflow() {
+        let mut scaler = GradScaler::new();
+        scaler.update(false);
+        assert_eq!(scaler.scale(), 32768.0);
+        scaler.update(true);
+        assert_eq!(scaler.scale(), 32768.0);
+    }
+
+    #[test]
+    fn scale_grows_after_interval() {
+        let mut scaler = GradScaler::new();
+        for _ in 0..2000 {
+            scaler.update(true);
+        }
+        assert_eq!(scaler.scale(), 131072.0);
+    }
+
+    #[test]
+    fn unscale_detects_non_finite_gradients() {
+        let vs = nn::VarStore::new(Device::Cpu);
+        let _w = vs.root().zeros("w", &[3]);
+        let scaler = GradScaler::new();
+
+        for (_, mut var) in vs.variables() {
+            var.set_grad(&Tensor::full([3], 65536.0, (Kind::Float, Device::Cpu)));
+        }
+        assert!(scaler.unscale(&vs));
+        for (_, var) in vs.variables() {
+            assert_eq!(var.grad().double_value(&[0]), 1.0);
+        }
+
+        for (_, mut var) in vs.variables() {
+            var.set_grad(&Tensor::full(
+                [3],
+                f64::INFINITY,
+                (Kind::Float, Device::Cpu),
+            ));
+        }
+        assert!(!scaler.unscale(&vs));
+    }
+
+    #[test]
+    fn selected_device_is_usable() {
+        let t = Tensor::ones([2], (Kind::Float, select_device()));
+        assert_eq!(t.sum(Kind::Float).double_value(&[]), 2.0);
+    }
+}
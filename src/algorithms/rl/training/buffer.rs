@@ -1,5 +1,7 @@
 //! Rollout buffer for storing episode transitions.
 
+use std::ops::Range;
+
 /// A single transition stored in the buffer.
 #[derive(Debug, Clone)]
 pub struct Transition {
@@ -17,6 +19,12 @@ pub struct Transition {
     pub done: bool,
     /// Global state (for centralized critic).
     pub global_state: Vec<f64>,
+    /// Per-agent actor hidden state before this step, flattened row-major
+    /// (`n_agents × hidden_dim`). Empty for feed-forward actors.
+    pub hidden: Vec<f64>,
+    /// Per-agent valid-action masks the actions were sampled under. Empty
+    /// if actions were unrestricted.
+    pub masks: Vec<Vec<bool>>,
 }
 
 /// Rollout buffer that stores transitions for PPO updates.
@@ -65,6 +73,28 @@ impl RolloutBuffer {
         self.transitions.is_empty()
     }
 
+    /// Splits the stored transitions into contiguous sequences of at most
+    /// `max_len` steps that never cross an episode boundary.
+    ///
+    /// Used to train recurrent actors with truncated backpropagation through
+    /// time: each sequence is replayed from the hidden state stored in its
+    /// first transition.
+    pub fn sequences(&self, max_len: usize) -> Vec<Range<usize>> {
+        let max_len = max_len.max(1);
+        let mut sequences = Vec::new();
+        let mut start = 0;
+        for (i, t) in self.transitions.iter().enumerate() {
+            if t.done || i + 1 - start == max_len {
+                sequences.push(start..i + 1);
+                start = i + 1;
+            }
+        }
+        if start < self.transitions.len() {
+            sequences.push(start..self.transitions.len());
+        }
+        sequences
+    }
+
     /// Sets advantages and returns (called after GAE computation).
     pub fn set_advantages_and_returns(&mut self, advantages: Vec<f64>, returns: Vec<f64>) {
         assert_eq!(advantages.len(), self.transitions.len());
@@ -115,6 +145,8 @@ mod tests {
             value: 0.5,
             done: false,
             global_state: vec![0.0; 10],
+            hidden: vec![],
+            masks: vec![],
         });
 
         assert_eq!(buf.len(), 1);
@@ -134,6 +166,8 @@ mod tests {
                 value: 0.0,
                 done: false,
                 global_state: vec![],
+                hidden: vec![],
+                masks: vec![],
             });
         }
         buf.set_advantages_and_returns(vec![1.0, 2.0, 3.0], vec![1.0, 2.0, 3.0]);
@@ -142,4 +176,25 @@ mod tests {
         let mean: f64 = buf.advantages.iter().sum::<f64>() / buf.advantages.len() as f64;
         assert!(mean.abs() < 1e-6);
     }
+
+    #[test]
+    fn sequences_split_at_episode_ends_and_max_len() {
+        let mut buf = RolloutBuffer::new();
+        for done in [false, false, true, false, false, false, false, true, false] {
+            buf.add(Transition {
+                observations: vec![],
+                actions: vec![],
+                reward: 0.0,
+                log_probs: vec![],
+                value: 0.0,
+                done,
+                global_state: vec![],
+                hidden: vec![],
+                masks: vec![],
+            });
+        }
+        assert_eq!(buf.sequences(3), vec![0..3, 3..6, 6..8, 8..9]);
+        assert_eq!(buf.sequences(16), vec![0..3, 3..8, 8..9]);
+        assert!(RolloutBuffer::new().sequences(4).is_empty());
+    }
 }
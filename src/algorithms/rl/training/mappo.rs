@@ -3,20 +3,32 @@
 //! Implements Centralized Training, Decentralized Execution (CTDE):
 //! - Shared actor network uses local observations.
 //! - Centralized critic uses global state.
+//!
+//! With a recurrent actor ([`ActorKind::Recurrent`](crate::algorithms::rl::ActorKind)),
+//! rollouts record each step's hidden state and updates replay whole
+//! sequences through the GRU (truncated BPTT).
+//!
+//! Actions are sampled under the environment's per-agent action masks, and
+//! the masks are replayed in updates so probabilities stay consistent.
 
+use std::ops::Range;
 use std::path::Path;
 
 use tch::{nn, nn::OptimizerConfig, Device, Kind, Tensor};
 
 use rand::RngExt;
 
+use super::amp::{select_device, GradScaler};
 use super::buffer::{RolloutBuffer, Transition};
 use super::gae::compute_gae;
 use crate::algorithms::rl::agent::AgentState;
 use crate::algorithms::rl::config::RLConfig;
 use crate::algorithms::rl::environment::RLEnvironment;
 use crate::algorithms::rl::metrics::EvaluationMetrics;
-use crate::algorithms::rl::network::{ActorNetwork, CriticNetwork, NeuralPolicy};
+use crate::algorithms::rl::network::{
+    mask_log_probs, masks_to_tensor, ActorNetwork, CriticNetwork, NeuralPolicy,
+};
+use crate::algorithms::rl::normalization::{RewardNormalizer, RunningMeanStd};
 use crate::algorithms::rl::observation::ObservationBuilder;
 use crate::algorithms::rl::types::AgentType;
 
@@ -51,6 +63,21 @@ pub struct TrainingConfig {
     pub eval_episodes: usize,
     /// Save checkpoint every N updates (0 = disabled).
     pub checkpoint_interval: u32,
+    /// Device the networks are trained on. Defaults to CUDA when available
+    /// (see [`select_device`]).
+    pub device: Device,
+    /// Train with automatic mixed precision (FP16 autocast and loss
+    /// scaling). Only takes effect on CUDA devices.
+    pub mixed_precision: bool,
+    /// Maximum sequence length for recurrent actor updates; longer episodes
+    /// are split into chunks replayed from their stored hidden state.
+    pub recurrent_seq_len: usize,
+    /// Standardize actor observations with running mean/std statistics.
+    /// The statistics are saved with checkpoints and reused at inference.
+    pub normalize_observations: bool,
+    /// Scale rewards by the running standard deviation of the discounted
+    /// return.
+    pub normalize_rewards: bool,
 }
 
 impl Default for TrainingConfig {
@@ -70,10 +97,22 @@ impl Default for TrainingConfig {
             eval_interval: 50,
             eval_episodes: 10,
             checkpoint_interval: 100,
+            device: select_device(),
+            mixed_precision: false,
+            recurrent_seq_len: 16,
+            normalize_observations: true,
+            normalize_rewards: true,
         }
     }
 }
 
+impl TrainingConfig {
+    /// Whether mixed precision is actually used: requested and on CUDA.
+    pub fn uses_mixed_precision(&self) -> bool {
+        self.mixed_precision && self.device.is_cuda()
+    }
+}
+
 /// Clips gradient norms for all trainable variables in a `VarStore`.
 ///
 /// Implements the same logic as PyTorch's `torch.nn.utils.clip_grad_norm_`:
@@ -106,6 +145,55 @@ fn clip_grad_norm(vs: &nn::VarStore, max_norm: f64) {
     }
 }
 
+/// Runs `backward` on `loss`, clips gradients and applies one optimizer step.
+///
+/// With a scaler, the loss is scaled before `backward` and the gradients are
+/// unscaled and checked before clipping; a step with non-finite gradients is
+/// skipped.
+fn optimizer_step(
+    optimizer: &mut nn::Optimizer,
+    vs: &nn::VarStore,
+    loss: &Tensor,
+    scaler: Option<&mut GradScaler>,
+    max_grad_norm: f64,
+) {
+    optimizer.zero_grad();
+    match scaler {
+        Some(scaler) => {
+            scaler.scale_loss(&loss.to_kind(Kind::Float)).backward();
+            let finite = scaler.unscale(vs);
+            scaler.update(finite);
+            if !finite {
+                return;
+            }
+        }
+        None => loss.backward(),
+    }
+    clip_grad_norm(vs, max_grad_norm);
+    optimizer.step();
+}
+
+/// Returns `0..n` in random order (Fisher-Yates).
+fn shuffled_indices(n: usize) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..n).collect();
+    let mut rng = rand::rng();
+    for i in (1..indices.len()).rev() {
+        let j = rng.random_range(0..=i);
+        indices.swap(i, j);
+    }
+    indices
+}
+
+/// Applies the recorded action `masks` to `log_probs` (`[n_agents,
+/// action_dim]`); unmasked transitions pass through unchanged.
+fn masked(log_probs: Tensor, masks: &[Vec<bool>]) -> Tensor {
+    if masks.is_empty() {
+        return log_probs;
+    }
+    let mask = masks_to_tensor(masks, log_probs.device());
+    mask_log_probs(&log_probs, &mask)
+}
+
 /// Derives agent composition `(count, AgentType)` pairs from a slice of agents.
 ///
 /// Used to create a separate evaluation environment with the same agent
@@ -152,8 +240,14 @@ pub struct MAPPOTrainer {
     actor_opt: nn::Optimizer,
     /// Critic optimizer.
     critic_opt: nn::Optimizer,
-    /// Device (CPU/CUDA).
+    /// Device (CPU/CUDA), from [`TrainingConfig::device`].
     device: Device,
+    /// Loss scalers for the actor and critic when training in mixed precision.
+    scalers: Option<(GradScaler, GradScaler)>,
+    /// Observation statistics, when [`TrainingConfig::normalize_observations`].
+    obs_norm: Option<RunningMeanStd>,
+    /// Return statistics, when [`TrainingConfig::normalize_rewards`].
+    reward_norm: Option<RewardNormalizer>,
 }
 
 impl MAPPOTrainer {
@@ -164,18 +258,15 @@ impl MAPPOTrainer {
     /// * `env_config` - Environment configuration
     /// * `train_config` - Training hyperparameters
     /// * `n_agents` - Number of agents (needed for global state dim)
-    /// * `device` - Compute device
-    pub fn new(
-        env_config: RLConfig,
-        train_config: TrainingConfig,
-        n_agents: usize,
-        device: Device,
-    ) -> Self {
+    ///
+    /// The networks are placed on [`TrainingConfig::device`].
+    pub fn new(env_config: RLConfig, train_config: TrainingConfig, n_agents: usize) -> Self {
+        let device = train_config.device;
         let obs_dim = env_config.observation_dim();
         let action_dim = env_config.action_dim();
         let global_state_dim = ObservationBuilder::global_state_dim(n_agents, &env_config);
 
-        let mut actor = ActorNetwork::new(obs_dim, action_dim, device);
+        let mut actor = ActorNetwork::from_config(&env_config, device);
         let mut critic = CriticNetwork::new(global_state_dim, device);
 
         let actor_opt = nn::Adam::default()
@@ -185,6 +276,16 @@ impl MAPPOTrainer {
             .build(critic.var_store_mut(), train_config.lr_critic)
             .expect("Failed to create critic optimizer");
 
+        let scalers = train_config
+            .uses_mixed_precision()
+            .then(|| (GradScaler::new(), GradScaler::new()));
+        let obs_norm = train_config
+            .normalize_observations
+            .then(|| RunningMeanStd::new(obs_dim));
+        let reward_norm = train_config
+            .normalize_rewards
+            .then(|| RewardNormalizer::new(train_config.gamma));
+
         Self {
             actor,
             critic,
@@ -193,9 +294,22 @@ impl MAPPOTrainer {
             actor_opt,
             critic_opt,
             device,
+            scalers,
+            obs_norm,
+            reward_norm,
         }
     }
 
+    /// Device the networks live on.
+    pub fn device(&self) -> Device {
+        self.device
+    }
+
+    /// Running observation statistics, if observations are normalized.
+    pub fn observation_normalizer(&self) -> Option<&RunningMeanStd> {
+        self.obs_norm.as_ref()
+    }
+
     /// Runs the full MAPPO training loop.
     ///
     /// # Arguments
@@ -254,6 +368,7 @@ impl MAPPOTrainer {
                 let mut eval_policy =
                     NeuralPolicy::from_actor_var_store(self.actor.var_store(), &self.env_config);
                 eval_policy.set_greedy(true);
+                eval_policy.set_observation_normalizer(self.obs_norm.clone());
 
                 // Build agent composition from the training env
                 let composition = derive_agent_composition(&env.agents);
@@ -286,8 +401,13 @@ impl MAPPOTrainer {
     }
 
     /// Collects one episode of rollout data.
-    fn collect_rollout(&self, env: &mut RLEnvironment, buffer: &mut RolloutBuffer) -> f64 {
+    ///
+    /// Updates the normalization statistics with every step; the buffer
+    /// stores normalized observations and rewards.
+    fn collect_rollout(&mut self, env: &mut RLEnvironment, buffer: &mut RolloutBuffer) -> f64 {
         let mut obs = env.reset();
+        let mut masks = env.action_masks();
+        let mut hidden = self.actor.initial_hidden(obs.len());
 
         loop {
             // Get global state for critic
@@ -300,23 +420,43 @@ impl MAPPOTrainer {
             // Compute value estimate
             let state_tensor = Tensor::from_slice(&global_state)
                 .unsqueeze(0)
-                .to_kind(Kind::Float);
+                .to_kind(Kind::Float)
+                .to_device(self.device);
             let value: f64 = self.critic.forward(&state_tensor).double_value(&[0]);
 
+            // Normalize observations with the running statistics
+            if let Some(norm) = &mut self.obs_norm {
+                norm.update(&obs);
+            }
+            let actor_obs = match &self.obs_norm {
+                Some(norm) => norm.normalize_batch(&obs),
+                None => obs.clone(),
+            };
+
             // Sample actions from actor
-            let n_agents = obs.len();
-            let obs_dim = if n_agents > 0 { obs[0].len() } else { 0 };
-            let flat_obs: Vec<f64> = obs.iter().flat_map(|o| o.iter().copied()).collect();
+            let hidden_before = hidden.as_ref().map(Tensor::shallow_clone);
+            let n_agents = actor_obs.len();
+            let obs_dim = if n_agents > 0 { actor_obs[0].len() } else { 0 };
+            let flat_obs: Vec<f64> = actor_obs.iter().flat_map(|o| o.iter().copied()).collect();
 
             let (actions_vec, log_probs_vec) = if n_agents > 0 {
                 let obs_tensor = Tensor::from_slice(&flat_obs)
                     .reshape([n_agents as i64, obs_dim as i64])
-                    .to_kind(Kind::Float);
-                let (actions_t, log_probs_t) = self.actor.sample_actions(&obs_tensor);
+                    .to_kind(Kind::Float)
+                    .to_device(self.device);
+                let mask_tensor = masks_to_tensor(&masks, self.device);
+                let (actions_t, log_probs_t, next_hidden) = self.actor.sample_actions_step(
+                    &obs_tensor,
+                    hidden.as_ref(),
+                    Some(&mask_tensor),
+                );
+                hidden = next_hidden.map(|h| h.detach());
                 let actions: Vec<i64> = actions_t
+                    .to_device(Device::Cpu)
                     .try_into()
                     .expect("sampled actions must be convertible to Vec<i64>");
                 let log_probs: Vec<f64> = log_probs_t
+                    .to_device(Device::Cpu)
                     .try_into()
                     .expect("sampled log_probs must be convertible to Vec<f64>");
                 (
@@ -327,21 +467,39 @@ impl MAPPOTrainer {
                 (vec![], vec![])
             };
 
+            // Hidden state the actions were sampled from (replayed in updates)
+            let hidden_vec: Vec<f64> = match &hidden_before {
+                Some(h) => h
+                    .flatten(0, -1)
+                    .to_device(Device::Cpu)
+                    .try_into()
+                    .expect("hidden state must be convertible to Vec<f64>"),
+                None => vec![],
+            };
+
             // Step environment
             let result = env.step(actions_vec.clone());
 
+            let reward = match &mut self.reward_norm {
+                Some(norm) => norm.normalize(result.reward, result.done),
+                None => result.reward,
+            };
+
             // Store transition
             buffer.add(Transition {
-                observations: obs.clone(),
+                observations: actor_obs,
                 actions: actions_vec,
-                reward: result.reward,
+                reward,
                 log_probs: log_probs_vec,
                 value,
                 done: result.done,
                 global_state,
+                hidden: hidden_vec,
+                masks,
             });
 
             obs = result.observations;
+            masks = result.action_masks;
 
             if result.done {
                 break;
@@ -360,20 +518,19 @@ impl MAPPOTrainer {
         if n == 0 {
             return (0.0, 0.0);
         }
+        if self.actor.is_recurrent() {
+            return self.ppo_update_recurrent(buffer);
+        }
 
         let batch_size = self.train_config.batch_size.min(n);
+        let amp = self.scalers.is_some();
         let mut total_actor_loss = 0.0;
         let mut total_critic_loss = 0.0;
         let mut n_updates = 0;
 
         for _ in 0..self.train_config.n_epochs {
-            // Shuffle indices for this epoch (Fisher-Yates)
-            let mut indices: Vec<usize> = (0..n).collect();
-            let mut rng = rand::rng();
-            for i in (1..indices.len()).rev() {
-                let j = rng.random_range(0..=i);
-                indices.swap(i, j);
-            }
+            // Shuffle indices for this epoch
+            let indices = shuffled_indices(n);
 
             // Process minibatches
             for batch_start in (0..n).step_by(batch_size) {
@@ -403,19 +560,37 @@ impl MAPPOTrainer {
                         .collect();
                     let obs_tensor = Tensor::from_slice(&flat_obs)
                         .reshape([n_agents as i64, obs_dim as i64])
-                        .to_kind(Kind::Float);
+                        .to_kind(Kind::Float)
+                        .to_device(self.device);
                     let actions_tensor = Tensor::from_slice(
                         &t.actions.iter().map(|&a| a as i64).collect::<Vec<_>>(),
                     )
-                    .to_kind(Kind::Int64);
-                    let old_log_probs_tensor =
-                        Tensor::from_slice(&t.log_probs).to_kind(Kind::Float);
-
-                    let new_log_probs = self.actor.log_prob(&obs_tensor, &actions_tensor);
+                    .to_kind(Kind::Int64)
+                    .to_device(self.device);
+                    let old_log_probs_tensor = Tensor::from_slice(&t.log_probs)
+                        .to_kind(Kind::Float)
+                        .to_device(self.device);
+
+                    // Forward passes run in FP16 where safe under mixed
+                    // precision; losses are computed in FP32.
+                    let (new_log_probs, entropy) = tch::autocast(amp, || {
+                        let log_probs = masked(self.actor.forward(&obs_tensor), &t.masks);
+                        let entropy = -(log_probs.exp() * &log_probs).sum_dim_intlist(
+                            [-1].as_slice(),
+                            false,
+                            Kind::Float,
+                        );
+                        let selected = log_probs
+                            .gather(-1, &actions_tensor.unsqueeze(-1), false)
+                            .squeeze_dim(-1);
+                        (selected, entropy)
+                    });
+                    let new_log_probs = new_log_probs.to_kind(Kind::Float);
                     let ratio = (&new_log_probs - &old_log_probs_tensor).exp();
 
-                    let adv_tensor =
-                        Tensor::from_slice(&vec![advantage as f32; n_agents]).to_kind(Kind::Float);
+                    let adv_tensor = Tensor::from_slice(&vec![advantage as f32; n_agents])
+                        .to_kind(Kind::Float)
+                        .to_device(self.device);
 
                     let surr1 = &ratio * &adv_tensor;
                     let surr2 = ratio.clamp(
@@ -425,7 +600,7 @@ impl MAPPOTrainer {
                     let actor_loss = -surr1.min_other(&surr2).mean(Kind::Float);
 
                     // Entropy bonus
-                    let entropy = self.actor.entropy(&obs_tensor).mean(Kind::Float);
+                    let entropy = entropy.mean(Kind::Float);
                     let actor_total = &actor_loss - self.train_config.entropy_coef * &entropy;
 
                     batch_actor_loss = batch_actor_loss + &actor_total;
@@ -433,9 +608,13 @@ impl MAPPOTrainer {
                     // Critic loss
                     let state_tensor = Tensor::from_slice(&t.global_state)
                         .unsqueeze(0)
+                        .to_kind(Kind::Float)
+                        .to_device(self.device);
+                    let value_pred = tch::autocast(amp, || self.critic.forward(&state_tensor))
                         .to_kind(Kind::Float);
-                    let value_pred = self.critic.forward(&state_tensor);
-                    let ret_tensor = Tensor::from_slice(&[ret as f32]).to_kind(Kind::Float);
+                    let ret_tensor = Tensor::from_slice(&[ret as f32])
+                        .to_kind(Kind::Float)
+                        .to_device(self.device);
                     let critic_loss = (&value_pred - &ret_tensor)
                         .pow_tensor_scalar(2)
                         .mean(Kind::Float)
@@ -452,21 +631,7 @@ impl MAPPOTrainer {
                     // Average over minibatch
                     let mean_actor = &batch_actor_loss / batch_count as f64;
                     let mean_critic = &batch_critic_loss / batch_count as f64;
-
-                    // Actor backward + clip + step
-                    self.actor_opt.zero_grad();
-                    mean_actor.backward();
-                    clip_grad_norm(self.actor.var_store(), self.train_config.max_grad_norm);
-                    self.actor_opt.step();
-
-                    // Critic backward + clip + step
-                    self.critic_opt.zero_grad();
-                    mean_critic.backward();
-                    clip_grad_norm(
-                        self.critic.var_store(),
-                        self.train_config.max_grad_norm,
-                    );
-                    self.critic_opt.step();
+                    self.step_optimizers(&mean_actor, &mean_critic);
 
                     n_updates += batch_count;
                 }
@@ -483,11 +648,205 @@ impl MAPPOTrainer {
         }
     }
 
+    /// PPO update for a recurrent actor.
+    ///
+    /// Minibatches are made of whole sequences from
+    /// [`RolloutBuffer::sequences`], each replayed through the GRU from the
+    /// hidden state recorded at its first step, with the agents as the batch
+    /// dimension. Sequences are added to a minibatch until it holds at least
+    /// `batch_size` steps.
+    ///
+    /// Returns `(mean_actor_loss, mean_critic_loss)`.
+    fn ppo_update_recurrent(&mut self, buffer: &RolloutBuffer) -> (f64, f64) {
+        let sequences = buffer.sequences(self.train_config.recurrent_seq_len);
+        let batch_size = self.train_config.batch_size.max(1);
+        let amp = self.scalers.is_some();
+        let mut total_actor_loss = 0.0;
+        let mut total_critic_loss = 0.0;
+        let mut n_updates = 0;
+
+        for _ in 0..self.train_config.n_epochs {
+            let mut batches: Vec<Vec<Range<usize>>> = Vec::new();
+            let mut batch_len = 0;
+            for k in shuffled_indices(sequences.len()) {
+                if batches.is_empty() || batch_len >= batch_size {
+                    batches.push(Vec::new());
+                    batch_len = 0;
+                }
+                batch_len += sequences[k].len();
+                batches
+                    .last_mut()
+                    .expect("a batch was just pushed")
+                    .push(sequences[k].clone());
+            }
+
+            for batch in batches {
+                let mut batch_actor_loss = Tensor::zeros([], (Kind::Float, self.device));
+                let mut batch_critic_loss = Tensor::zeros([], (Kind::Float, self.device));
+                let mut batch_steps = 0;
+
+                for seq in batch {
+                    let steps = &buffer.transitions[seq.clone()];
+                    let n_agents = steps[0].observations.len();
+                    if n_agents == 0 {
+                        continue;
+                    }
+                    let obs_dim = steps[0].observations[0].len();
+                    let len = steps.len();
+
+                    // Agent-major layout: [n_agents, len, ...]
+                    let flat_obs: Vec<f64> = (0..n_agents)
+                        .flat_map(|a| {
+                            steps
+                                .iter()
+                                .flat_map(move |t| t.observations[a].iter().copied())
+                        })
+                        .collect();
+                    let obs_tensor = Tensor::from_slice(&flat_obs)
+                        .reshape([n_agents as i64, len as i64, obs_dim as i64])
+                        .to_kind(Kind::Float)
+                        .to_device(self.device);
+                    let actions: Vec<i64> = (0..n_agents)
+                        .flat_map(|a| steps.iter().map(move |t| t.actions[a] as i64))
+                        .collect();
+                    let actions_tensor = Tensor::from_slice(&actions)
+                        .reshape([n_agents as i64, len as i64])
+                        .to_device(self.device);
+                    let old_log_probs: Vec<f64> = (0..n_agents)
+                        .flat_map(|a| steps.iter().map(move |t| t.log_probs[a]))
+                        .collect();
+                    let old_log_probs_tensor = Tensor::from_slice(&old_log_probs)
+                        .reshape([n_agents as i64, len as i64])
+                        .to_kind(Kind::Float)
+                        .to_device(self.device);
+                    let h0 = Tensor::from_slice(&steps[0].hidden)
+                        .reshape([n_agents as i64, -1])
+                        .to_kind(Kind::Float)
+                        .to_device(self.device);
+                    // [1, len], broadcast over agents
+                    let adv_tensor = Tensor::from_slice(&buffer.advantages[seq.clone()])
+                        .to_kind(Kind::Float)
+                        .to_device(self.device)
+                        .unsqueeze(0);
+
+                    let log_probs =
+                        tch::autocast(amp, || self.actor.forward_sequence(&obs_tensor, Some(&h0)))
+                            .to_kind(Kind::Float);
+                    // Masks in the same agent-major layout, if recorded
+                    let log_probs = if steps.iter().all(|t| t.masks.len() == n_agents) {
+                        let flat: Vec<bool> = (0..n_agents)
+                            .flat_map(|a| {
+                                steps.iter().flat_map(move |t| t.masks[a].iter().copied())
+                            })
+                            .collect();
+                        let mask = Tensor::from_slice(&flat)
+                            .reshape(log_probs.size())
+                            .to_device(self.device);
+                        mask_log_probs(&log_probs, &mask)
+                    } else {
+                        log_probs
+                    };
+                    let new_log_probs = log_probs
+                        .gather(-1, &actions_tensor.unsqueeze(-1), false)
+                        .squeeze_dim(-1);
+                    let ratio = (&new_log_probs - &old_log_probs_tensor).exp();
+
+                    let surr1 = &ratio * &adv_tensor;
+                    let surr2 = ratio.clamp(
+                        1.0 - self.train_config.clip_eps,
+                        1.0 + self.train_config.clip_eps,
+                    ) * &adv_tensor;
+                    let actor_loss = -surr1.min_other(&surr2).mean(Kind::Float);
+
+                    let entropy = -(log_probs.exp() * &log_probs)
+                        .sum_dim_intlist([-1].as_slice(), false, Kind::Float)
+                        .mean(Kind::Float);
+                    let actor_total = &actor_loss - self.train_config.entropy_coef * &entropy;
+
+                    // Critic loss over the sequence's global states
+                    let states: Vec<f64> = steps
+                        .iter()
+                        .flat_map(|t| t.global_state.iter().copied())
+                        .collect();
+                    let state_tensor = Tensor::from_slice(&states)
+                        .reshape([len as i64, -1])
+                        .to_kind(Kind::Float)
+                        .to_device(self.device);
+                    let value_pred = tch::autocast(amp, || self.critic.forward(&state_tensor))
+                        .to_kind(Kind::Float);
+                    let ret_tensor = Tensor::from_slice(&buffer.returns[seq.clone()])
+                        .to_kind(Kind::Float)
+                        .to_device(self.device);
+                    let critic_loss = (&value_pred - &ret_tensor)
+                        .pow_tensor_scalar(2)
+                        .mean(Kind::Float)
+                        * self.train_config.value_coef;
+
+                    // Weight by length so every step counts once, as in the
+                    // feed-forward update.
+                    batch_actor_loss = batch_actor_loss + &actor_total * len as f64;
+                    batch_critic_loss = batch_critic_loss + &critic_loss * len as f64;
+                    batch_steps += len;
+
+                    total_actor_loss += f64::try_from(&actor_loss).unwrap_or(0.0) * len as f64;
+                    total_critic_loss += f64::try_from(&critic_loss).unwrap_or(0.0) * len as f64;
+                }
+
+                if batch_steps > 0 {
+                    let mean_actor = &batch_actor_loss / batch_steps as f64;
+                    let mean_critic = &batch_critic_loss / batch_steps as f64;
+                    self.step_optimizers(&mean_actor, &mean_critic);
+                    n_updates += batch_steps;
+                }
+            }
+        }
+
+        if n_updates > 0 {
+            (
+                total_actor_loss / n_updates as f64,
+                total_critic_loss / n_updates as f64,
+            )
+        } else {
+            (0.0, 0.0)
+        }
+    }
+
+    /// Backward, clip and step for the actor and critic losses of one
+    /// minibatch.
+    fn step_optimizers(&mut self, actor_loss: &Tensor, critic_loss: &Tensor) {
+        let (actor_scaler, critic_scaler) = match &mut self.scalers {
+            Some((actor, critic)) => (Some(actor), Some(critic)),
+            None => (None, None),
+        };
+
+        // Actor backward + clip + step
+        optimizer_step(
+            &mut self.actor_opt,
+            self.actor.var_store(),
+            actor_loss,
+            actor_scaler,
+            self.train_config.max_grad_norm,
+        );
+
+        // Critic backward + clip + step
+        optimizer_step(
+            &mut self.critic_opt,
+            self.critic.var_store(),
+            critic_loss,
+            critic_scaler,
+            self.train_config.max_grad_norm,
+        );
+    }
+
     /// Saves actor and critic checkpoints to `dir`.
     ///
     /// Creates the directory if it does not exist. Saves:
     /// - `dir/actor.safetensors` — actor network weights
     /// - `dir/critic.safetensors` — critic network weights
+    /// - `dir/obs_norm.safetensors` — observation statistics, if normalized
+    /// - `dir/reward_norm.safetensors` — return statistics, if normalized
+    /// - `dir/layout.safetensors` — observation layout, see
+    ///   [`RLConfig::save_layout`]
     pub fn save_checkpoint(&self, dir: &Path) -> Result<(), tch::TchError> {
         std::fs::create_dir_all(dir).map_err(|e| {
             tch::TchError::FileFormat(format!("Failed to create checkpoint dir: {}", e))
@@ -498,19 +857,45 @@ impl MAPPOTrainer {
         self.critic
             .var_store()
             .save(dir.join("critic.safetensors"))?;
+        if let Some(norm) = &self.obs_norm {
+            norm.save(dir.join("obs_norm.safetensors"))?;
+        }
+        if let Some(norm) = &self.reward_norm {
+            norm.stats().save(dir.join("reward_norm.safetensors"))?;
+        }
+        self.env_config
+            .save_layout(dir.join("layout.safetensors"))?;
         Ok(())
     }
 
     /// Loads actor and critic weights from a checkpoint directory.
     ///
     /// Expects `dir/actor.safetensors` and `dir/critic.safetensors` to exist.
+    /// Normalization statistics are restored when present. A saved
+    /// observation layout that differs from `env_config`'s is an error;
+    /// checkpoints from before layouts were saved are loaded unchecked.
     pub fn load_checkpoint(&mut self, dir: &Path) -> Result<(), tch::TchError> {
+        let layout = dir.join("layout.safetensors");
+        if layout.exists() {
+            self.env_config.check_layout(layout)?;
+        }
         self.actor
             .var_store_mut()
             .load(dir.join("actor.safetensors"))?;
         self.critic
             .var_store_mut()
             .load(dir.join("critic.safetensors"))?;
+        let obs_norm = dir.join("obs_norm.safetensors");
+        if obs_norm.exists() {
+            self.obs_norm = Some(RunningMeanStd::load(obs_norm)?);
+        }
+        let reward_norm = dir.join("reward_norm.safetensors");
+        if reward_norm.exists() {
+            self.reward_norm = Some(RewardNormalizer::from_stats(
+                RunningMeanStd::load(reward_norm)?,
+                self.train_config.gamma,
+            ));
+        }
         Ok(())
     }
 }
@@ -518,13 +903,22 @@ impl MAPPOTrainer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::algorithms::rl::{AgentType, RLConfig, RLEnvironment};
+    use crate::algorithms::rl::{
+        ActionSpace, ActorKind, AgentType, RLConfig, RLEnvironment, TaskFeature,
+    };
+
+    fn cpu_config() -> TrainingConfig {
+        TrainingConfig {
+            device: Device::Cpu,
+            ..TrainingConfig::default()
+        }
+    }
 
     #[test]
     fn trainer_creation() {
         let env_config = RLConfig::default();
-        let train_config = TrainingConfig::default();
-        let _trainer = MAPPOTrainer::new(env_config, train_config, 6, Device::Cpu);
+        let train_config = cpu_config();
+        let _trainer = MAPPOTrainer::new(env_config, train_config, 6);
     }
 
     #[test]
@@ -536,16 +930,43 @@ mod tests {
         let train_config = TrainingConfig {
             n_episodes_per_update: 1,
             n_epochs: 1,
-            ..TrainingConfig::default()
+            ..cpu_config()
         };
         let mut env = RLEnvironment::new(env_config.clone(), 42);
         env.set_agents(&[(1, AgentType::Young), (1, AgentType::Old)]);
-        let mut trainer = MAPPOTrainer::new(env_config, train_config, 2, Device::Cpu);
+        let mut trainer = MAPPOTrainer::new(env_config, train_config, 2);
 
         let curve = trainer.train(&mut env, 2);
         assert_eq!(curve.len(), 2);
     }
 
+    #[test]
+    fn rollouts_respect_action_masks() {
+        let env_config = RLConfig::builder()
+            .episode_horizon(8)
+            .action_space(
+                AgentType::Old,
+                ActionSpace {
+                    max_targets: Some(0),
+                    max_range: None,
+                },
+            )
+            .build()
+            .unwrap();
+        let mut env = RLEnvironment::new(env_config.clone(), 42);
+        env.set_agents(&[(1, AgentType::Young), (1, AgentType::Old)]);
+        let mut trainer = MAPPOTrainer::new(env_config, cpu_config(), 2);
+
+        let mut buffer = RolloutBuffer::new();
+        trainer.collect_rollout(&mut env, &mut buffer);
+
+        for t in &buffer.transitions {
+            assert_eq!(t.masks.len(), 2);
+            assert_eq!(t.actions[1], 0, "old agent may only patrol");
+            assert!(t.log_probs[1].abs() < 1e-5);
+        }
+    }
+
     #[test]
     fn train_with_minibatches() {
         // Ensure batch_size < total samples triggers minibatch splitting
@@ -557,11 +978,11 @@ mod tests {
             n_episodes_per_update: 2,
             n_epochs: 2,
             batch_size: 4, // Small batches to force multiple minibatches
-            ..TrainingConfig::default()
+            ..cpu_config()
         };
         let mut env = RLEnvironment::new(env_config.clone(), 42);
         env.set_agents(&[(1, AgentType::Young), (1, AgentType::Old)]);
-        let mut trainer = MAPPOTrainer::new(env_config, train_config, 2, Device::Cpu);
+        let mut trainer = MAPPOTrainer::new(env_config, train_config, 2);
 
         let curve = trainer.train(&mut env, 2);
         assert_eq!(curve.len(), 2);
@@ -606,8 +1027,8 @@ mod tests {
             episode_horizon: 5,
             ..RLConfig::default()
         };
-        let train_config = TrainingConfig::default();
-        let mut trainer = MAPPOTrainer::new(env_config.clone(), train_config.clone(), 2, Device::Cpu);
+        let train_config = cpu_config();
+        let mut trainer = MAPPOTrainer::new(env_config.clone(), train_config.clone(), 2);
 
         let dir = std::env::temp_dir().join(format!(
             "virolai_test_checkpoint_{}_{}",
@@ -625,9 +1046,18 @@ mod tests {
         // Verify files exist
         assert!(dir.join("actor.safetensors").exists());
         assert!(dir.join("critic.safetensors").exists());
+        assert!(dir.join("obs_norm.safetensors").exists());
+        assert!(dir.join("reward_norm.safetensors").exists());
+        assert!(dir.join("layout.safetensors").exists());
+
+        // A trainer observing fewer task features rejects the checkpoint
+        let mut narrow = env_config.clone();
+        narrow.task_features = narrow.task_features.without(TaskFeature::Heading);
+        let mut mismatched = MAPPOTrainer::new(narrow, train_config.clone(), 2);
+        assert!(mismatched.load_checkpoint(&dir).is_err());
 
         // Load into new trainer
-        let mut trainer2 = MAPPOTrainer::new(env_config, train_config, 2, Device::Cpu);
+        let mut trainer2 = MAPPOTrainer::new(env_config, train_config, 2);
         trainer2
             .load_checkpoint(&dir)
             .expect("load should succeed");
@@ -635,4 +1065,72 @@ mod tests {
         // Clean up
         let _ = std::fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn mixed_precision_requires_cuda() {
+        let config = TrainingConfig {
+            mixed_precision: true,
+            ..cpu_config()
+        };
+        assert!(!config.uses_mixed_precision());
+        let trainer = MAPPOTrainer::new(RLConfig::default(), config, 2);
+        assert!(trainer.scalers.is_none());
+        assert_eq!(trainer.device(), Device::Cpu);
+    }
+
+    #[test]
+    fn train_recurrent_actor() {
+        let env_config = RLConfig {
+            episode_horizon: 10,
+            actor: ActorKind::Recurrent { hidden_dim: 16 },
+            ..RLConfig::default()
+        };
+        let train_config = TrainingConfig {
+            n_episodes_per_update: 2,
+            n_epochs: 2,
+            batch_size: 4,
+            recurrent_seq_len: 4,
+            ..cpu_config()
+        };
+        let mut env = RLEnvironment::new(env_config.clone(), 42);
+        env.set_agents(&[(1, AgentType::Young), (1, AgentType::Old)]);
+        let mut trainer = MAPPOTrainer::new(env_config, train_config, 2);
+        assert!(trainer.actor.is_recurrent());
+
+        let mut buffer = RolloutBuffer::new();
+        trainer.collect_rollout(&mut env, &mut buffer);
+        assert!(buffer.transitions.iter().all(|t| t.hidden.len() == 2 * 16));
+        assert!(buffer.transitions[0].hidden.iter().all(|&h| h == 0.0));
+
+        let curve = trainer.train(&mut env, 2);
+        assert_eq!(curve.len(), 2);
+        assert!(curve.iter().all(|(_, r)| r.is_finite()));
+    }
+
+    #[test]
+    fn rollouts_update_normalization_statistics() {
+        let env_config = RLConfig {
+            episode_horizon: 5,
+            ..RLConfig::default()
+        };
+        let mut env = RLEnvironment::new(env_config.clone(), 42);
+        env.set_agents(&[(2, AgentType::Young)]);
+        let mut trainer = MAPPOTrainer::new(env_config, cpu_config(), 2);
+        assert_eq!(trainer.observation_normalizer().unwrap().count(), 0.0);
+
+        let mut buffer = RolloutBuffer::new();
+        trainer.collect_rollout(&mut env, &mut buffer);
+
+        let stats = trainer.observation_normalizer().unwrap();
+        assert_eq!(stats.count(), 2.0 * buffer.len() as f64);
+        let stored = &buffer.transitions[0].observations[0];
+        assert!(stored.iter().all(|v| v.abs() <= 10.0));
+
+        let raw = TrainingConfig {
+            normalize_observations: false,
+            ..cpu_config()
+        };
+        let trainer = MAPPOTrainer::new(RLConfig::default(), raw, 2);
+        assert!(trainer.observation_normalizer().is_none());
+    }
 }
@@ -1,9 +1,14 @@
 //! Training infrastructure for MAPPO (Multi-Agent PPO).
 //!
-//! Provides rollout buffer, GAE computation, and the MAPPO trainer.
+//! Provides rollout buffer, GAE computation, the MAPPO trainer, device
+//! selection / mixed-precision helpers, and multi-seed sweeps.
 
+pub mod amp;
 pub mod buffer;
 pub mod gae;
 pub mod mappo;
+pub mod sweep;
 
+pub use amp::{select_device, GradScaler};
 pub use mappo::{MAPPOTrainer, TrainingConfig};
+pub use sweep::{run_sweep, Grid, SweepConfig, SweepReport};
@@ -0,0 +1,417 @@
+//! Multi-seed training sweeps.
+//!
+//! [`run_sweep`] trains every variant of a hyperparameter [`Grid`] once per
+//! seed, optionally on several threads, and aggregates the learning curves
+//! into a mean ± standard error per variant. [`SweepReport::write`] saves a
+//! Markdown comparison table and a CSV of the aggregated curves.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::algorithms::rl::training::sweep::{run_sweep, Grid, SweepConfig};
+//! # use std::path::Path;
+//! # use virolai::algorithms::rl::{AgentType, RLConfig, TrainingConfig};
+//!
+//! let variants = Grid::new(TrainingConfig::default())
+//!     .vary("lr_actor", &[1e-4, 3e-4], |c, &lr| c.lr_actor = lr)
+//!     .vary("clip_eps", &[0.1, 0.2], |c, &eps| c.clip_eps = eps)
+//!     .into_variants();
+//! let sweep = SweepConfig {
+//!     seeds: vec![0, 1, 2],
+//!     variants,
+//!     total_updates: 200,
+//!     threads: 4,
+//! };
+//! let report = run_sweep(&RLConfig::default(), &[(3, AgentType::Young)], &sweep);
+//! report.write(Path::new("sweeps/lr_clip"))?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::fmt::{self, Display, Write as _};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use super::mappo::{MAPPOTrainer, TrainingConfig};
+use crate::algorithms::rl::config::RLConfig;
+use crate::algorithms::rl::environment::RLEnvironment;
+use crate::algorithms::rl::types::AgentType;
+
+/// Cartesian product of hyperparameter values applied to a base config.
+#[derive(Debug, Clone)]
+pub struct Grid {
+    variants: Vec<(String, TrainingConfig)>,
+}
+
+impl Grid {
+    /// Starts a grid with the single variant `base`.
+    pub fn new(base: TrainingConfig) -> Self {
+        Self {
+            variants: vec![(String::new(), base)],
+        }
+    }
+
+    /// Multiplies every variant by `values`, applying each with `apply`.
+    ///
+    /// Variant names list their settings, e.g. `lr_actor=0.0001,clip_eps=0.1`.
+    pub fn vary<P: Display>(
+        self,
+        name: &str,
+        values: &[P],
+        apply: impl Fn(&mut TrainingConfig, &P),
+    ) -> Self {
+        let apply = &apply;
+        let variants = self
+            .variants
+            .into_iter()
+            .flat_map(|(label, config)| {
+                values.iter().map(move |value| {
+                    let mut config = config.clone();
+                    apply(&mut config, value);
+                    let sep = if label.is_empty() { "" } else { "," };
+                    (format!("{label}{sep}{name}={value}"), config)
+                })
+            })
+            .collect();
+        Self { variants }
+    }
+
+    /// Named configurations; a grid without [`vary`](Self::vary) calls has a
+    /// single variant named `base`.
+    pub fn into_variants(self) -> Vec<(String, TrainingConfig)> {
+        self.variants
+            .into_iter()
+            .map(|(label, config)| {
+                let label = if label.is_empty() {
+                    "base".to_string()
+                } else {
+                    label
+                };
+                (label, config)
+            })
+            .collect()
+    }
+}
+
+/// What to train in a sweep.
+#[derive(Debug, Clone)]
+pub struct SweepConfig {
+    /// Seeds; every variant is trained once per seed.
+    pub seeds: Vec<u64>,
+    /// Named training configurations, e.g. from [`Grid::into_variants`].
+    pub variants: Vec<(String, TrainingConfig)>,
+    /// Policy updates per run.
+    pub total_updates: u32,
+    /// Number of runs trained concurrently; `0` and `1` run sequentially.
+    pub threads: usize,
+}
+
+/// Learning curve of one run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunResult {
+    /// Variant name.
+    pub variant: String,
+    /// Seed of the run.
+    pub seed: u64,
+    /// `(update, mean_episode_reward)` pairs from [`MAPPOTrainer::train`].
+    pub curve: Vec<(u32, f64)>,
+}
+
+/// One point of an aggregated learning curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurvePoint {
+    /// Update index.
+    pub update: u32,
+    /// Mean reward over seeds.
+    pub mean: f64,
+    /// Standard error of the mean (zero with a single seed).
+    pub stderr: f64,
+}
+
+/// Learning curve of one variant aggregated over its seeds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateCurve {
+    /// Variant name.
+    pub variant: String,
+    /// Number of seeds aggregated.
+    pub n_seeds: usize,
+    /// Per-update mean and standard error.
+    pub points: Vec<CurvePoint>,
+}
+
+impl AggregateCurve {
+    /// The last point of the curve, if any.
+    pub fn last(&self) -> Option<CurvePoint> {
+        self.points.last().copied()
+    }
+
+    /// The point with the highest mean reward, if any.
+    pub fn best(&self) -> Option<CurvePoint> {
+        self.points
+            .iter()
+            .copied()
+            .max_by(|a, b| a.mean.total_cmp(&b.mean))
+    }
+}
+
+/// Results of [`run_sweep`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepReport {
+    /// Every run, ordered by variant then seed.
+    pub runs: Vec<RunResult>,
+    /// One aggregated curve per variant, in variant order.
+    pub curves: Vec<AggregateCurve>,
+}
+
+impl SweepReport {
+    /// Markdown table comparing variants by final and best mean reward.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from(
+            "| variant | seeds | final reward | best reward | best update |\n\
+             |---|---|---|---|---|\n",
+        );
+        for curve in &self.curves {
+            let (last, best) = match (curve.last(), curve.best()) {
+                (Some(last), Some(best)) => (last, best),
+                _ => continue,
+            };
+            let _ = writeln!(
+                out,
+                "| {} | {} | {:.3} ± {:.3} | {:.3} ± {:.3} | {} |",
+                curve.variant,
+                curve.n_seeds,
+                last.mean,
+                last.stderr,
+                best.mean,
+                best.stderr,
+                best.update
+            );
+        }
+        out
+    }
+
+    /// Aggregated curves as CSV: `variant,update,mean,stderr,n_seeds`.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("variant,update,mean,stderr,n_seeds\n");
+        for curve in &self.curves {
+            for p in &curve.points {
+                let _ = writeln!(
+                    out,
+                    "{},{},{},{},{}",
+                    curve.variant.replace(',', ";"),
+                    p.update,
+                    p.mean,
+                    p.stderr,
+                    curve.n_seeds
+                );
+            }
+        }
+        out
+    }
+
+    /// Writes `report.md` and `curves.csv` into `dir`, creating it if needed.
+    pub fn write(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join("report.md"), self.to_markdown())?;
+        std::fs::write(dir.join("curves.csv"), self.to_csv())
+    }
+}
+
+impl fmt::Display for SweepReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_markdown())
+    }
+}
+
+/// Trains every variant once per seed and aggregates the learning curves.
+///
+/// Each run gets a fresh environment seeded with the run's seed and populated
+/// with `agents`, and a fresh trainer; `tch::manual_seed` is set from the
+/// seed before the networks are built. Periodic checkpoints are disabled,
+/// since concurrent runs would overwrite each other's `checkpoints/`
+/// directory. With several threads, runs share libtorch's global RNG, so
+/// network initialization is only reproducible for sequential sweeps.
+pub fn run_sweep(
+    env_config: &RLConfig,
+    agents: &[(u32, AgentType)],
+    sweep: &SweepConfig,
+) -> SweepReport {
+    let jobs: Vec<(usize, u64)> = (0..sweep.variants.len())
+        .flat_map(|v| sweep.seeds.iter().map(move |&seed| (v, seed)))
+        .collect();
+
+    let run = |&(variant, seed): &(usize, u64)| {
+        let (name, config) = &sweep.variants[variant];
+        let config = TrainingConfig {
+            checkpoint_interval: 0,
+            ..config.clone()
+        };
+        let mut env = RLEnvironment::new(env_config.clone(), seed);
+        env.set_agents(agents);
+        tch::manual_seed(seed as i64);
+        let n_agents = env.agents.len();
+        let mut trainer = MAPPOTrainer::new(env_config.clone(), config, n_agents);
+        RunResult {
+            variant: name.clone(),
+            seed,
+            curve: trainer.train(&mut env, sweep.total_updates),
+        }
+    };
+
+    let runs: Vec<RunResult> = if sweep.threads <= 1 {
+        jobs.iter().map(run).collect()
+    } else {
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::with_capacity(jobs.len()));
+        std::thread::scope(|s| {
+            for _ in 0..sweep.threads.min(jobs.len()) {
+                s.spawn(|| loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(job) = jobs.get(i) else {
+                        break;
+                    };
+                    let result = run(job);
+                    results
+                        .lock()
+                        .expect("sweep results mutex poisoned")
+                        .push((i, result));
+                });
+            }
+        });
+        let mut results = results.into_inner().expect("sweep results mutex poisoned");
+        results.sort_by_key(|(i, _)| *i);
+        results.into_iter().map(|(_, r)| r).collect()
+    };
+
+    let curves = sweep
+        .variants
+        .iter()
+        .map(|(name, _)| {
+            let curves: Vec<&[(u32, f64)]> = runs
+                .iter()
+                .filter(|r| &r.variant == name)
+                .map(|r| r.curve.as_slice())
+                .collect();
+            aggregate(name, &curves)
+        })
+        .collect();
+
+    SweepReport { runs, curves }
+}
+
+/// Mean and standard error per update over curves of equal length; longer
+/// curves are truncated to the shortest.
+fn aggregate(variant: &str, curves: &[&[(u32, f64)]]) -> AggregateCurve {
+    let len = curves.iter().map(|c| c.len()).min().unwrap_or(0);
+    let n = curves.len() as f64;
+    let points = (0..len)
+        .map(|i| {
+            let values: Vec<f64> = curves.iter().map(|c| c[i].1).collect();
+            let mean = values.iter().sum::<f64>() / n;
+            let stderr = if values.len() > 1 {
+                let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+                (var / n).sqrt()
+            } else {
+                0.0
+            };
+            CurvePoint {
+                update: curves[0][i].0,
+                mean,
+                stderr,
+            }
+        })
+        .collect();
+    AggregateCurve {
+        variant: variant.to_string(),
+        n_seeds: curves.len(),
+        points,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tch::Device;
+
+    #[test]
+    fn grid_is_a_cartesian_product() {
+        let variants = Grid::new(TrainingConfig {
+            device: Device::Cpu,
+            ..TrainingConfig::default()
+        })
+        .vary("n_epochs", &[1, 2], |c, &n| c.n_epochs = n)
+        .vary("clip_eps", &[0.1, 0.2, 0.3], |c, &e| c.clip_eps = e)
+        .into_variants();
+
+        assert_eq!(variants.len(), 6);
+        assert_eq!(variants[0].0, "n_epochs=1,clip_eps=0.1");
+        assert_eq!(variants[5].0, "n_epochs=2,clip_eps=0.3");
+        assert_eq!(variants[5].1.n_epochs, 2);
+        assert_eq!(variants[5].1.clip_eps, 0.3);
+        assert_eq!(
+            Grid::new(TrainingConfig::default()).into_variants()[0].0,
+            "base"
+        );
+    }
+
+    #[test]
+    fn aggregate_mean_and_stderr() {
+        let a = [(0, 1.0), (1, 2.0)];
+        let b = [(0, 3.0), (1, 2.0), (2, 9.0)];
+        let curve = aggregate("v", &[&a, &b]);
+
+        assert_eq!(curve.n_seeds, 2);
+        assert_eq!(curve.points.len(), 2);
+        assert_eq!(curve.points[0].mean, 2.0);
+        // Sample std √2, stderr √2 / √2 = 1.
+        assert!((curve.points[0].stderr - 1.0).abs() < 1e-12);
+        assert_eq!(curve.points[1].stderr, 0.0);
+        assert_eq!(aggregate("v", &[&a]).points[0].stderr, 0.0);
+    }
+
+    #[test]
+    fn threaded_sweep_matches_job_order() {
+        let env_config = RLConfig {
+            episode_horizon: 3,
+            ..RLConfig::default()
+        };
+        let base = TrainingConfig {
+            device: Device::Cpu,
+            n_episodes_per_update: 1,
+            n_epochs: 1,
+            eval_interval: 0,
+            ..TrainingConfig::default()
+        };
+        let sweep = SweepConfig {
+            seeds: vec![1, 2],
+            variants: Grid::new(base)
+                .vary("clip_eps", &[0.1, 0.2], |c, &e| c.clip_eps = e)
+                .into_variants(),
+            total_updates: 2,
+            threads: 3,
+        };
+
+        let report = run_sweep(&env_config, &[(1, AgentType::Young)], &sweep);
+
+        assert_eq!(report.runs.len(), 4);
+        assert_eq!(
+            report
+                .runs
+                .iter()
+                .map(|r| (r.variant.as_str(), r.seed))
+                .collect::<Vec<_>>(),
+            vec![
+                ("clip_eps=0.1", 1),
+                ("clip_eps=0.1", 2),
+                ("clip_eps=0.2", 1),
+                ("clip_eps=0.2", 2),
+            ]
+        );
+        assert_eq!(report.curves.len(), 2);
+        assert!(report.curves.iter().all(|c| c.points.len() == 2));
+        assert_eq!(report.to_markdown().lines().count(), 4);
+        assert!(report
+            .to_csv()
+            .starts_with("variant,update,mean,stderr,n_seeds\n"));
+    }
+}
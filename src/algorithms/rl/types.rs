@@ -5,9 +5,12 @@
 
 use std::fmt;
 
+use crate::constraints::CoalitionConstraint;
+
 /// Agent age group, which determines maximum movement speed.
 ///
 /// Speed hierarchy: `Young > Middle > Old`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AgentType {
     Young,
@@ -103,6 +106,36 @@ impl AgentTypeRequirements {
     pub fn as_array(&self) -> [u32; 3] {
         [self.young, self.middle, self.old]
     }
+
+    /// Reads the agent-type counts of a coalition, the inverse of
+    /// `From<AgentTypeRequirements> for CoalitionConstraint`.
+    ///
+    /// Resource types other than `"young"`, `"middle"` and `"old"` are
+    /// ignored; returns `None` if none of them is required.
+    pub fn from_coalition(coalition: &CoalitionConstraint) -> Option<Self> {
+        let count = |t: AgentType| coalition.requirement_for(&t.to_string());
+        let req = Self::new(
+            count(AgentType::Young),
+            count(AgentType::Middle),
+            count(AgentType::Old),
+        );
+        (req.total() > 0).then_some(req)
+    }
+}
+
+/// Bridges RL agent-type requirements to the scheduling domain.
+///
+/// Each agent type becomes a resource type named after its [`Display`](fmt::Display)
+/// form (`"young"`, `"middle"`, `"old"`); zero requirements are omitted.
+impl From<AgentTypeRequirements> for CoalitionConstraint {
+    fn from(req: AgentTypeRequirements) -> Self {
+        CoalitionConstraint::new(
+            [AgentType::Young, AgentType::Middle, AgentType::Old]
+                .into_iter()
+                .map(|t| (t.to_string(), req.requirement_for(t)))
+                .filter(|&(_, count)| count > 0),
+        )
+    }
 }
 
 impl Default for AgentTypeRequirements {
@@ -116,6 +149,7 @@ impl Default for AgentTypeRequirements {
 }
 
 /// A 2D position in the environment domain Ω = [0, W] × [0, H].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Position {
     pub x: f64,
@@ -205,6 +239,26 @@ mod tests {
         assert_eq!(AgentType::Old.one_hot(), [0.0, 0.0, 1.0]);
     }
 
+    #[test]
+    fn requirements_into_coalition() {
+        let c: CoalitionConstraint = AgentTypeRequirements::new(2, 0, 1).into();
+        assert_eq!(c.requirement_for("young"), 2);
+        assert_eq!(c.requirement_for("old"), 1);
+        assert_eq!(c.requirements().len(), 2);
+        assert_eq!(c.total_required(), 3);
+    }
+
+    #[test]
+    fn requirements_from_coalition_roundtrip() {
+        let req = AgentTypeRequirements::new(2, 0, 1);
+        assert_eq!(
+            AgentTypeRequirements::from_coalition(&req.into()),
+            Some(req)
+        );
+        let other = CoalitionConstraint::single_type("telescope", 2);
+        assert_eq!(AgentTypeRequirements::from_coalition(&other), None);
+    }
+
     #[test]
     fn requirements_satisfied_exact() {
         let req = AgentTypeRequirements::new(2, 1, 0);
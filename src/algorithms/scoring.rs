@@ -0,0 +1,366 @@
+//! Task scoring shared by the greedy and beam-search schedulers.
+//!
+//! A [`TaskScorer`] turns a task, its EST metrics and the scheduling context
+//! into a single number; higher scores are tried first. The same scorer can
+//! drive [`GreedyScheduler`](crate::algorithms::GreedyScheduler), which sorts
+//! every task by score once, and
+//! [`BeamSearchScheduler`](crate::algorithms::BeamSearchScheduler), which
+//! ranks the candidates of each step by score before EST's own ordering.
+//!
+//! Provided scorers:
+//!
+//! - the default [`PriorityUrgency`], its [`WeightedScore`] variant, and any
+//!   closure `Fn(&T, &str, Quantity<U>) -> f64` as accepted by the greedy
+//!   scheduler
+//! - [`CapacityScore`] - any other [`GreedyScore`]
+//! - [`FlexibilityScore`] - priority per unit of EST flexibility
+//! - [`EstOrder`] - a constant score, leaving EST's ordering in charge
+//! - [`ModelScore`] and [`DistilledScorer`] - external models over
+//!   [`task_features`]; [`FeatureScore`](crate::features::FeatureScore) for
+//!   models over the full [`FeatureVector`](crate::features::FeatureVector)
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::algorithms::scoring::ModelScore;
+//! use virolai::algorithms::{BeamSearchScheduler, GreedyScheduler};
+//! # struct Gbdt;
+//! # impl Gbdt { fn predict(&self, _: &[f64; 6]) -> f64 { 0.0 } }
+//! # let gbdt = Gbdt;
+//!
+//! let model = |x: &[f64; 6]| gbdt.predict(x);
+//! let greedy = GreedyScheduler::with_score(ModelScore::new(model));
+//! let beam: BeamSearchScheduler<_> = BeamSearchScheduler::new(4, 3).with_scorer(ModelScore::new(model));
+//! ```
+
+use crate::algorithms::est::metrics::TaskMetrics;
+use crate::algorithms::greedy::{GreedyScore, PriorityUrgency, WeightedScore};
+use crate::algorithms::rl::distilled::{task_features, DistilledScorer, TASK_FEATURES};
+use crate::scheduling_block::Task;
+use crate::solution_space::{Interval, SolutionSpace};
+use qtty::{Quantity, Unit};
+
+/// What a [`TaskScorer`] knows about the task being scored.
+#[derive(Debug, Clone, Copy)]
+pub struct ScoringContext<'a, T, U: Unit> {
+    /// The task being scored.
+    pub task: &'a T,
+    /// Feasible windows of every task.
+    pub solution_space: &'a SolutionSpace<U>,
+    /// Horizon the task is scored against: the full horizon for greedy, the
+    /// part after the cursor for beam search.
+    pub horizon: Interval<U>,
+    /// Total duration of the task's windows within `horizon` that are long
+    /// enough for it.
+    pub capacity: Quantity<U>,
+}
+
+impl<'a, T, U> ScoringContext<'a, T, U>
+where
+    T: Task<U>,
+    U: Unit,
+{
+    /// Builds the context of `task_id`, computing its capacity in `horizon`.
+    pub fn new(
+        task: &'a T,
+        task_id: &str,
+        solution_space: &'a SolutionSpace<U>,
+        horizon: Interval<U>,
+    ) -> Self {
+        let size = task.size_on_axis().value();
+        let capacity = solution_space
+            .get_intervals(task_id)
+            .map_or(0.0, |windows| {
+                windows
+                    .iter()
+                    .filter_map(|window| window.intersection(&horizon))
+                    .map(|window| window.duration().value())
+                    .filter(|&duration| duration >= size)
+                    .sum()
+            });
+        Self {
+            task,
+            solution_space,
+            horizon,
+            capacity: Quantity::new(capacity),
+        }
+    }
+}
+
+/// Scores a task for placement. Higher scores are placed first.
+///
+/// Any closure `Fn(&T, &str, Quantity<U>) -> f64` implements this trait,
+/// receiving the task, its ID and its capacity.
+pub trait TaskScorer<T, U>
+where
+    T: Task<U>,
+    U: Unit,
+{
+    /// Returns the score of `task_id`. NaN scores are ordered after all
+    /// others.
+    fn score(
+        &self,
+        task_id: &str,
+        metrics: &TaskMetrics<U>,
+        context: &ScoringContext<'_, T, U>,
+    ) -> f64;
+}
+
+impl<F, T, U> TaskScorer<T, U> for F
+where
+    F: Fn(&T, &str, Quantity<U>) -> f64,
+    T: Task<U>,
+    U: Unit,
+{
+    fn score(
+        &self,
+        task_id: &str,
+        _metrics: &TaskMetrics<U>,
+        context: &ScoringContext<'_, T, U>,
+    ) -> f64 {
+        self(context.task, task_id, context.capacity)
+    }
+}
+
+impl<T, U> TaskScorer<T, U> for PriorityUrgency
+where
+    T: Task<U>,
+    U: Unit,
+{
+    fn score(
+        &self,
+        task_id: &str,
+        _metrics: &TaskMetrics<U>,
+        context: &ScoringContext<'_, T, U>,
+    ) -> f64 {
+        GreedyScore::score(self, context.task, task_id, context.capacity)
+    }
+}
+
+impl<T, U> TaskScorer<T, U> for WeightedScore
+where
+    T: Task<U>,
+    U: Unit,
+{
+    fn score(
+        &self,
+        task_id: &str,
+        _metrics: &TaskMetrics<U>,
+        context: &ScoringContext<'_, T, U>,
+    ) -> f64 {
+        GreedyScore::score(self, context.task, task_id, context.capacity)
+    }
+}
+
+/// Adapter turning a [`GreedyScore`] into a [`TaskScorer`].
+///
+/// Closures and [`PriorityUrgency`] need no adapter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CapacityScore<S>(pub S);
+
+impl<S, T, U> TaskScorer<T, U> for CapacityScore<S>
+where
+    S: GreedyScore<T, U>,
+    T: Task<U>,
+    U: Unit,
+{
+    fn score(
+        &self,
+        task_id: &str,
+        _metrics: &TaskMetrics<U>,
+        context: &ScoringContext<'_, T, U>,
+    ) -> f64 {
+        self.0.score(context.task, task_id, context.capacity)
+    }
+}
+
+/// Constant score: ties every task, so the scheduler's own ordering decides.
+///
+/// The default scorer of beam search, which then ranks candidates exactly
+/// as EST does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EstOrder;
+
+impl<T, U> TaskScorer<T, U> for EstOrder
+where
+    T: Task<U>,
+    U: Unit,
+{
+    fn score(&self, _: &str, _: &TaskMetrics<U>, _: &ScoringContext<'_, T, U>) -> f64 {
+        0.0
+    }
+}
+
+/// EST-metrics score: `max(priority, 1) / flexibility`.
+///
+/// Like [`PriorityUrgency`], but tightness is measured by EST's flexibility
+/// (fitting window length over task size) rather than raw capacity, so long
+/// tasks are not penalized for needing long windows. Tasks without an EST
+/// score NaN.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FlexibilityScore;
+
+impl<T, U> TaskScorer<T, U> for FlexibilityScore
+where
+    T: Task<U>,
+    U: Unit,
+{
+    fn score(
+        &self,
+        _task_id: &str,
+        metrics: &TaskMetrics<U>,
+        context: &ScoringContext<'_, T, U>,
+    ) -> f64 {
+        if metrics.is_impossible() {
+            return f64::NAN;
+        }
+        let flexibility = metrics.metrics.flexibility.value().max(1e-6);
+        (context.task.priority() as f64).max(1.0) / flexibility
+    }
+}
+
+/// Adapter for external models scoring [`task_features`].
+///
+/// Wraps any `Fn(&[f64; TASK_FEATURES]) -> f64`, such as a gradient-boosted
+/// tree or a network binding. Tasks without a window in the horizon score
+/// NaN. For the richer [`FeatureVector`](crate::features::FeatureVector),
+/// use [`FeatureScore`](crate::features::FeatureScore).
+#[derive(Debug, Clone, Copy)]
+pub struct ModelScore<M> {
+    model: M,
+}
+
+impl<M> ModelScore<M> {
+    /// Wraps `model`.
+    pub fn new(model: M) -> Self {
+        Self { model }
+    }
+
+    /// Returns the wrapped model.
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+}
+
+impl<M, T, U> TaskScorer<T, U> for ModelScore<M>
+where
+    M: Fn(&[f64; TASK_FEATURES]) -> f64,
+    T: Task<U>,
+    U: Unit,
+{
+    fn score(
+        &self,
+        task_id: &str,
+        _metrics: &TaskMetrics<U>,
+        context: &ScoringContext<'_, T, U>,
+    ) -> f64 {
+        task_features(
+            context.task,
+            task_id,
+            context.solution_space,
+            context.horizon,
+        )
+        .map_or(f64::NAN, |x| (self.model)(&x))
+    }
+}
+
+impl<T, U> TaskScorer<T, U> for DistilledScorer
+where
+    T: Task<U>,
+    U: Unit,
+{
+    fn score(
+        &self,
+        task_id: &str,
+        _metrics: &TaskMetrics<U>,
+        context: &ScoringContext<'_, T, U>,
+    ) -> f64 {
+        task_features(
+            context.task,
+            task_id,
+            context.solution_space,
+            context.horizon,
+        )
+        .map_or(f64::NAN, |x| DistilledScorer::score(self, &x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::est::metrics::compute_metrics;
+    use crate::algorithms::{BeamSearchScheduler, GreedyScheduler, SchedulingAlgorithm};
+    use crate::test_utils::{block_and_space, hz, iv, q, TestTask};
+    use qtty::Second;
+
+    fn score<S: TaskScorer<TestTask, Second>>(
+        scorer: &S,
+        task: &TestTask,
+        space: &SolutionSpace<Second>,
+    ) -> f64 {
+        let horizon = iv(0.0, 100.0);
+        let metrics = compute_metrics(task, &task.name, space, horizon);
+        let context = ScoringContext::new(task, &task.name, space, horizon);
+        scorer.score(&task.name, &metrics, &context)
+    }
+
+    #[test]
+    fn scorers_read_metrics_and_context() {
+        let mut space = SolutionSpace::new();
+        space.set_intervals("t", vec![iv(0.0, 5.0), iv(20.0, 40.0), iv(90.0, 200.0)]);
+        let task = TestTask::new("t", 10.0).with_priority(4);
+
+        let context = ScoringContext::new(&task, "t", &space, iv(0.0, 100.0));
+        assert_eq!(context.capacity, q(30.0));
+        assert_eq!(score(&PriorityUrgency, &task, &space), 4.0 / (1e-6 + 30.0));
+        let closure = |t: &TestTask, _: &str, c: Quantity<Second>| t.size().value() + c.value();
+        assert_eq!(score(&closure, &task, &space), 40.0);
+        assert_eq!(
+            score(&CapacityScore(PriorityUrgency), &task, &space),
+            4.0 / (1e-6 + 30.0)
+        );
+        assert_eq!(score(&EstOrder, &task, &space), 0.0);
+        assert_eq!(score(&FlexibilityScore, &task, &space), 4.0 / 3.0);
+        // Priority is the first model feature.
+        let model = ModelScore::new(|x: &[f64; TASK_FEATURES]| x[0]);
+        assert_eq!(score(&model, &task, &space), 4.0);
+
+        let stranded = TestTask::new("s", 10.0);
+        assert!(score(&FlexibilityScore, &stranded, &space).is_nan());
+        assert!(score(&model, &stranded, &space).is_nan());
+    }
+
+    #[test]
+    fn one_scorer_drives_greedy_and_beam() {
+        // By flexibility, "tight" must go first; EST order starts "loose" at 0.
+        let (block, space) = block_and_space(&[
+            ("loose", 10.0, 0, iv(0.0, 100.0)),
+            ("tight", 10.0, 0, iv(5.0, 15.0)),
+        ]);
+        let blocks = [block];
+        let horizon = hz(0.0, 100.0);
+
+        let est_order = BeamSearchScheduler::new(1, 1).schedule(&blocks, &space, horizon);
+        assert_eq!(est_order.get_interval("loose"), Some(iv(0.0, 10.0)));
+        assert_eq!(est_order.get_interval("tight"), None);
+
+        let beam = BeamSearchScheduler::new(1, 1)
+            .with_scorer(FlexibilityScore)
+            .schedule(&blocks, &space, horizon);
+        let greedy =
+            GreedyScheduler::with_score(FlexibilityScore).schedule(&blocks, &space, horizon);
+        for schedule in [&beam, &greedy] {
+            assert_eq!(schedule.get_interval("tight"), Some(iv(5.0, 15.0)));
+            assert_eq!(schedule.get_interval("loose"), Some(iv(15.0, 25.0)));
+        }
+
+        // A distilled model preferring little window length does the same.
+        let mut weights = [0.0; TASK_FEATURES];
+        weights[2] = -1.0;
+        let scorer = DistilledScorer::linear(weights, 0.0);
+        let beam = BeamSearchScheduler::new(1, 1)
+            .with_scorer(scorer)
+            .schedule(&blocks, &space, horizon);
+        assert_eq!(beam.get_interval("tight"), Some(iv(5.0, 15.0)));
+    }
+}
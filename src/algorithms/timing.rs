@@ -0,0 +1,300 @@
+//! Start-time optimization for a fixed task order.
+//!
+//! Schedulers decide *which* tasks run and in what order, then place each one
+//! with a local rule (earliest fit, a placement preference). Once the order
+//! is fixed, choosing the start times is a small linear program over a chain:
+//!
+//! ```text
+//! lo_i ≤ s_i ≤ hi_i                        (window of task i, within the horizon)
+//! s_{i+1} ≥ s_i + d_i + gap_i              (order and separations)
+//! ```
+//!
+//! [`optimize_timing`] solves it for a [`TimingObjective`]. Each task stays in
+//! the window it currently occupies and the order never changes, so every
+//! dependency already satisfied by the input schedule stays satisfied.
+//!
+//! On a chain, the forward pass `s_i = max(lo_i, s_{i-1} + d_{i-1} + gap_{i-1})`
+//! yields the smallest feasible value of *every* start at once. It therefore
+//! minimizes any cost that grows with the start times — total tardiness
+//! included — and maximizing the smallest gap reduces to finding the largest
+//! extra separation for which that pass stays feasible.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use qtty::Quantity;
+//! # use virolai::algorithms::timing::{optimize_timing, TimingObjective};
+//! # use virolai::algorithms::{ESTScheduler, SchedulingAlgorithm};
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let space = instance.solution_space;
+//! let schedule = ESTScheduler::new(Quantity::new(1.0)).schedule(&blocks, &space, horizon);
+//! let robust = optimize_timing(&schedule, &blocks, &space, horizon, &TimingObjective::MaxMinGap)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::HashMap;
+
+use qtty::{Quantity, Unit};
+use thiserror::Error;
+
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+use crate::Id;
+
+/// What [`optimize_timing`] optimizes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimingObjective<U: Unit> {
+    /// Minimize total tardiness `Σ max(0, end_i − due_i)` against the given
+    /// due times. Tasks without a due time are never tardy.
+    MinTardiness(HashMap<Id, Quantity<U>>),
+    /// Maximize the smallest gap between consecutive tasks (beyond their
+    /// required separations), making the schedule robust to overruns.
+    MaxMinGap,
+}
+
+/// Errors raised by [`optimize_timing`].
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TimingError {
+    /// The order cannot be kept: the task cannot start inside its window
+    /// after its predecessor and the required separation.
+    #[error("task {task_id} cannot be placed after its predecessor within its window")]
+    Infeasible { task_id: Id },
+}
+
+/// One entry of the chain.
+struct Slot {
+    id: Id,
+    size: f64,
+    /// Earliest and latest allowed start.
+    lo: f64,
+    hi: f64,
+    /// Required separation to the next task.
+    gap: f64,
+}
+
+/// Re-times `schedule`, keeping its order and window choices.
+///
+/// Tasks are ordered by their current start. A task keeps the window of
+/// `space` containing its current interval, clipped to `horizon`; tasks
+/// with no such window (or outside the horizon) stay where they are. The
+/// separation after a task is the larger of its
+/// [`gap_after`](Task::gap_after) and the next task's
+/// [`compute_gap_after`](Task::compute_gap_after); tasks missing from
+/// `blocks` need none.
+///
+/// # Errors
+///
+/// Returns [`TimingError::Infeasible`] if the input order cannot be kept,
+/// which only happens when the input schedule violates a separation.
+pub fn optimize_timing<T, U, D, E>(
+    schedule: &Schedule<U>,
+    blocks: &[SchedulingBlock<T, U, D, E>],
+    space: &SolutionSpace<U>,
+    horizon: Horizon<U>,
+    objective: &TimingObjective<U>,
+) -> Result<Schedule<U>, TimingError>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    let task_of = |id: &str| blocks.iter().find_map(|b| b.task_by_id(id));
+    let entries: Vec<(Id, Interval<U>)> = schedule.iter().collect();
+
+    let mut slots: Vec<Slot> = entries
+        .iter()
+        .map(|(id, interval)| {
+            let start = interval.start().value();
+            let size = interval.duration().value();
+            let (lo, hi) = space
+                .find_interval_containing_for(id, interval.start())
+                .filter(|w| w.end().value() >= interval.end().value())
+                .map(|w| {
+                    (
+                        w.start().value().max(horizon.start().value()),
+                        w.end().value().min(horizon.end().value()) - size,
+                    )
+                })
+                .filter(|&(lo, hi)| lo <= start && start <= hi)
+                .unwrap_or((start, start));
+            Slot {
+                id: id.clone(),
+                size,
+                lo,
+                hi,
+                gap: 0.0,
+            }
+        })
+        .collect();
+    for i in 1..slots.len() {
+        if let (Some(prev), Some(next)) = (task_of(&slots[i - 1].id), task_of(&slots[i].id)) {
+            slots[i - 1].gap = prev
+                .gap_after()
+                .value()
+                .max(next.compute_gap_after(prev).value());
+        }
+    }
+
+    let starts = match objective {
+        TimingObjective::MinTardiness(_) => forward_pass(&slots, 0.0)?,
+        TimingObjective::MaxMinGap => {
+            forward_pass(&slots, 0.0)?;
+            // Feasibility is monotone in the extra gap: bisect for the largest.
+            let (mut low, mut high) = (0.0, horizon.duration().value().max(0.0));
+            if forward_pass(&slots, high).is_ok() {
+                low = high;
+            }
+            for _ in 0..64 {
+                let mid = 0.5 * (low + high);
+                if forward_pass(&slots, mid).is_ok() {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            forward_pass(&slots, low)?
+        }
+    };
+
+    let mut result = Schedule::new();
+    for (slot, start) in slots.iter().zip(starts) {
+        let interval = Interval::new(Quantity::new(start), Quantity::new(start + slot.size));
+        result
+            .add(slot.id.clone(), interval)
+            .expect("forward pass keeps tasks apart");
+    }
+    Ok(result)
+}
+
+/// Earliest starts keeping order, windows and separations plus `extra`.
+fn forward_pass(slots: &[Slot], extra: f64) -> Result<Vec<f64>, TimingError> {
+    let mut starts = Vec::with_capacity(slots.len());
+    let mut cursor = f64::NEG_INFINITY;
+    for slot in slots {
+        let start = slot.lo.max(cursor);
+        if start > slot.hi {
+            return Err(TimingError::Infeasible {
+                task_id: slot.id.clone(),
+            });
+        }
+        starts.push(start);
+        cursor = start + slot.size + slot.gap + extra;
+    }
+    Ok(starts)
+}
+
+/// Total tardiness of `schedule` against `due` end times.
+pub fn total_tardiness<U: Unit>(
+    schedule: &Schedule<U>,
+    due: &HashMap<Id, Quantity<U>>,
+) -> Quantity<U> {
+    Quantity::new(
+        schedule
+            .iter()
+            .filter_map(|(id, interval)| {
+                due.get(&id)
+                    .map(|d| (interval.end().value() - d.value()).max(0.0))
+            })
+            .sum(),
+    )
+}
+
+/// Smallest gap between consecutive tasks of `schedule`, or `None` with
+/// fewer than two tasks.
+pub fn min_gap<U: Unit>(schedule: &Schedule<U>) -> Option<Quantity<U>> {
+    let intervals: Vec<Interval<U>> = schedule.intervals().collect();
+    intervals
+        .windows(2)
+        .map(|pair| pair[1].start().value() - pair[0].end().value())
+        .min_by(f64::total_cmp)
+        .map(Quantity::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{block_and_space, hz, iv, q, TestTask};
+    use qtty::Second;
+
+    fn problem(
+        placed: &[(&str, f64, f64)],
+    ) -> (
+        Schedule<Second>,
+        Vec<SchedulingBlock<TestTask, Second>>,
+        SolutionSpace<Second>,
+    ) {
+        let tasks: Vec<_> = placed
+            .iter()
+            .map(|&(id, _, size)| (id, size, 0, iv(0.0, 100.0)))
+            .collect();
+        let (block, space) = block_and_space(&tasks);
+        let mut schedule = Schedule::new();
+        for &(id, start, size) in placed {
+            schedule.add(id, iv(start, start + size)).unwrap();
+        }
+        (schedule, vec![block], space)
+    }
+
+    #[test]
+    fn tardiness_pass_pulls_tasks_forward() {
+        // Placed late (e.g. by a "Latest" preference).
+        let (schedule, blocks, mut space) = problem(&[("a", 40.0, 10.0), ("b", 60.0, 10.0)]);
+        space.set_intervals("b", vec![iv(30.0, 100.0)]);
+        let due = HashMap::from([("a".to_string(), q(20.0)), ("b".to_string(), q(40.0))]);
+        let objective = TimingObjective::MinTardiness(due.clone());
+
+        let timed =
+            optimize_timing(&schedule, &blocks, &space, hz(0.0, 100.0), &objective).unwrap();
+        assert_eq!(timed.get_interval("a"), Some(iv(0.0, 10.0)));
+        assert_eq!(timed.get_interval("b"), Some(iv(30.0, 40.0)));
+        assert_eq!(total_tardiness(&schedule, &due), q(60.0));
+        assert_eq!(total_tardiness(&timed, &due), q(0.0));
+    }
+
+    #[test]
+    fn max_min_gap_spreads_tasks() {
+        let (schedule, blocks, space) =
+            problem(&[("a", 0.0, 10.0), ("b", 10.0, 10.0), ("c", 20.0, 10.0)]);
+        assert_eq!(min_gap(&schedule), Some(q(0.0)));
+
+        let timed = optimize_timing(
+            &schedule,
+            &blocks,
+            &space,
+            hz(0.0, 100.0),
+            &TimingObjective::MaxMinGap,
+        )
+        .unwrap();
+        // 70 s of free time, two gaps between three tasks → 35 s each.
+        let gap = min_gap(&timed).unwrap().value();
+        assert!((gap - 35.0).abs() < 1e-6, "{gap}");
+        assert_eq!(timed.len(), 3);
+        assert!(timed.latest_end().unwrap().value() <= 100.0 + 1e-9);
+    }
+
+    #[test]
+    fn windows_pin_and_bound_tasks() {
+        let (schedule, blocks, mut space) = problem(&[("a", 0.0, 10.0), ("b", 50.0, 10.0)]);
+        // b may only move within [45, 65); a has no window and stays put.
+        space.set_intervals("b", vec![iv(20.0, 30.0), iv(45.0, 65.0)]);
+        space.remove("a");
+
+        let timed = optimize_timing(
+            &schedule,
+            &blocks,
+            &space,
+            hz(0.0, 100.0),
+            &TimingObjective::MaxMinGap,
+        )
+        .unwrap();
+        assert_eq!(timed.get_interval("a"), Some(iv(0.0, 10.0)));
+        let b = timed.get_interval("b").unwrap();
+        assert!((b.start().value() - 55.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,337 @@
+//! Memory usage of scheduling runs (feature `alloc-metrics`).
+//!
+//! [`measure`] runs a closure, typically one scheduling run, and reports the
+//! allocations it made and the peak of live heap bytes it reached, in total
+//! and for each [`Phase`] of the algorithm. EST and greedy split their runs
+//! into phases; other algorithms only fill in the total.
+//!
+//! Counts come from [`CountingAllocator`], which the binary must install as
+//! its global allocator; without it every report is zero (see
+//! [`is_counting`]). Counters are per thread, so concurrent runs on other
+//! threads do not pollute a report, but neither is work a run hands to other
+//! threads, such as EST's parallel metric updates, counted.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::alloc_metrics::{measure, CountingAllocator};
+//! # use virolai::algorithms::{ESTScheduler, SchedulingAlgorithm};
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let space = instance.solution_space;
+//! # let scheduler = ESTScheduler::default();
+//!
+//! #[global_allocator]
+//! static ALLOC: CountingAllocator = CountingAllocator::system();
+//!
+//! let (schedule, report) = measure(|| scheduler.schedule(&blocks, &space, horizon));
+//! println!(
+//!     "peak {} B, {} allocations ({} in the candidate loop)",
+//!     report.total.peak_bytes, report.total.allocations, report.candidates.allocations
+//! );
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A part of a scheduling run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// Building the candidate population from the blocks.
+    Population,
+    /// Evaluating and ordering candidates.
+    Candidates,
+    /// Inserting placed tasks into the schedule.
+    Build,
+}
+
+impl Phase {
+    /// Every phase, in run order.
+    pub const ALL: [Phase; 3] = [Phase::Population, Phase::Candidates, Phase::Build];
+}
+
+/// Allocation activity over a stretch of a run.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocUsage {
+    /// Number of allocations and reallocations.
+    pub allocations: u64,
+    /// Bytes requested by those allocations.
+    pub bytes: u64,
+    /// Highest live heap size reached, above the size at the start.
+    pub peak_bytes: u64,
+}
+
+impl AllocUsage {
+    /// Adds `other`, a later stretch of the same phase.
+    pub fn merge(&mut self, other: AllocUsage) {
+        self.allocations += other.allocations;
+        self.bytes += other.bytes;
+        self.peak_bytes = self.peak_bytes.max(other.peak_bytes);
+    }
+}
+
+/// Allocation activity of a run, see [`measure`].
+///
+/// A phase entered several times, e.g. once per segment, reports the sum of
+/// its allocations and the highest of its peaks. Allocations outside every
+/// phase only show in the total.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    pub population: AllocUsage,
+    pub candidates: AllocUsage,
+    pub build: AllocUsage,
+    /// The whole run.
+    pub total: AllocUsage,
+}
+
+impl MemoryReport {
+    /// The activity of `phase`.
+    pub fn phase(&self, phase: Phase) -> AllocUsage {
+        match phase {
+            Phase::Population => self.population,
+            Phase::Candidates => self.candidates,
+            Phase::Build => self.build,
+        }
+    }
+
+    fn phase_mut(&mut self, phase: Phase) -> &mut AllocUsage {
+        match phase {
+            Phase::Population => &mut self.population,
+            Phase::Candidates => &mut self.candidates,
+            Phase::Build => &mut self.build,
+        }
+    }
+}
+
+/// A global allocator counting the allocations of each thread.
+///
+/// Wraps another allocator, [`System`] by default, and adds two thread-local
+/// updates per call.
+#[derive(Debug, Default)]
+pub struct CountingAllocator<A = System> {
+    inner: A,
+}
+
+impl CountingAllocator {
+    /// Counts allocations served by [`System`].
+    pub const fn system() -> Self {
+        Self::new(System)
+    }
+}
+
+impl<A> CountingAllocator<A> {
+    /// Counts allocations served by `inner`.
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        let _ = COUNTERS.try_with(|c| {
+            let mut counters = c.get();
+            counters.live -= layout.size() as i64;
+            c.set(counters);
+        });
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = self.inner.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            record(new_size, layout.size());
+        }
+        new
+    }
+}
+
+/// Per-thread allocation counters.
+#[derive(Clone, Copy)]
+struct Counters {
+    allocations: u64,
+    bytes: u64,
+    /// Live bytes allocated minus bytes freed on this thread; negative when
+    /// the thread frees memory allocated elsewhere.
+    live: i64,
+    peak: i64,
+}
+
+static COUNTING: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // Const-initialized and without a destructor, so the allocator can use
+    // it at any point of the thread's life.
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters { allocations: 0, bytes: 0, live: 0, peak: 0 })
+    };
+    static REPORT: RefCell<Option<MemoryReport>> = const { RefCell::new(None) };
+}
+
+/// Counts an allocation of `size` bytes replacing one of `freed` bytes.
+fn record(size: usize, freed: usize) {
+    COUNTING.store(true, Ordering::Relaxed);
+    let _ = COUNTERS.try_with(|c| {
+        let mut counters = c.get();
+        counters.allocations += 1;
+        counters.bytes += size as u64;
+        counters.live += size as i64 - freed as i64;
+        counters.peak = counters.peak.max(counters.live);
+        c.set(counters);
+    });
+}
+
+/// Returns `true` once a [`CountingAllocator`] has served an allocation,
+/// i.e. if reports are meaningful.
+pub fn is_counting() -> bool {
+    COUNTING.load(Ordering::Relaxed)
+}
+
+/// Runs `f` on this thread and reports its allocation activity.
+///
+/// Calls may nest: the phases of an inner run also count towards the outer
+/// one.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, MemoryReport) {
+    let outer = REPORT.with(|r| r.replace(Some(MemoryReport::default())));
+    let (result, total) = track(f);
+    let mut report = REPORT
+        .with(|r| r.replace(outer))
+        .expect("a report is open while measuring");
+    report.total = total;
+    REPORT.with(|r| {
+        if let Some(outer) = r.borrow_mut().as_mut() {
+            for phase in Phase::ALL {
+                outer.phase_mut(phase).merge(report.phase(phase));
+            }
+        }
+    });
+    (result, report)
+}
+
+/// Runs `f` as `phase` of the report [`measure`] is taking on this thread,
+/// if any.
+pub(crate) fn phase<R>(phase: Phase, f: impl FnOnce() -> R) -> R {
+    if REPORT.with(|r| r.borrow().is_none()) {
+        return f();
+    }
+    let (result, usage) = track(f);
+    REPORT.with(|r| {
+        if let Some(report) = r.borrow_mut().as_mut() {
+            report.phase_mut(phase).merge(usage);
+        }
+    });
+    result
+}
+
+/// Runs `f` and returns the activity of this thread meanwhile.
+fn track<R>(f: impl FnOnce() -> R) -> (R, AllocUsage) {
+    let start = COUNTERS.with(|c| {
+        let mut counters = c.get();
+        let start = counters;
+        counters.peak = counters.live;
+        c.set(counters);
+        start
+    });
+    let result = f();
+    let end = COUNTERS.with(|c| {
+        let mut counters = c.get();
+        let end = counters;
+        counters.peak = counters.peak.max(start.peak);
+        c.set(counters);
+        end
+    });
+    let usage = AllocUsage {
+        allocations: end.allocations - start.allocations,
+        bytes: end.bytes - start.bytes,
+        peak_bytes: (end.peak - start.live).max(0) as u64,
+    };
+    (result, usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{ESTScheduler, GreedyScheduler, SchedulingAlgorithm};
+    use crate::scheduling_block::SchedulingBlock;
+    use crate::solution_space::SolutionSpace;
+    use crate::test_utils::{hz, iv, q, TestTask};
+
+    #[global_allocator]
+    static ALLOC: CountingAllocator = CountingAllocator::system();
+
+    #[test]
+    fn peaks_and_counts_nest() {
+        let (len, report) = measure(|| {
+            let big = vec![0u8; 4096];
+            drop(big);
+            let ((), inner) = measure(|| phase(Phase::Build, || drop(vec![0u8; 1024])));
+            assert_eq!(inner.build.allocations, 1);
+            assert_eq!(inner.build.peak_bytes, 1024);
+            vec![1u8; 16].len()
+        });
+        assert_eq!(len, 16);
+        assert!(is_counting());
+        assert_eq!(report.total.allocations, 3);
+        assert_eq!(report.total.bytes, 4096 + 1024 + 16);
+        // The first buffer is freed before the second is allocated.
+        assert_eq!(report.total.peak_bytes, 4096);
+        assert_eq!(report.build.bytes, 1024);
+        assert_eq!(report.population, AllocUsage::default());
+
+        // Outside a measurement phases only run their body.
+        assert_eq!(phase(Phase::Candidates, || 7), 7);
+    }
+
+    #[test]
+    fn schedulers_report_their_phases() {
+        let mut block: SchedulingBlock<TestTask> = SchedulingBlock::new();
+        let mut space = SolutionSpace::new();
+        for i in 0..20 {
+            let id = block.add_task(TestTask::new(&format!("t{i}"), 10.0));
+            space.add_interval(&id, iv(0.0, 1_000.0));
+        }
+        let blocks = [block];
+        let horizon = hz(0.0, 1_000.0);
+
+        let (schedule, est) =
+            measure(|| ESTScheduler::new(q(1.0)).schedule(&blocks, &space, horizon));
+        let (_, greedy) = measure(|| GreedyScheduler::new().schedule(&blocks, &space, horizon));
+        assert_eq!(schedule.len(), 20);
+        // Greedy's placement search works on buffers it already holds.
+        assert!(est.candidates.allocations > 0, "{est:?}");
+        for report in [est, greedy] {
+            assert!(report.population.allocations > 0, "{report:?}");
+            assert!(report.build.allocations > 0, "{report:?}");
+            for phase in Phase::ALL {
+                assert!(report.phase(phase).peak_bytes <= report.total.peak_bytes);
+            }
+            let phases: u64 = Phase::ALL
+                .iter()
+                .map(|&p| report.phase(p).allocations)
+                .sum();
+            assert!(phases <= report.total.allocations);
+        }
+    }
+}
@@ -0,0 +1,360 @@
+//! Conflict-graph coloring for resource assignment.
+//!
+//! A task placed anywhere in its windows always occupies its *compulsory
+//! part*: the span from its latest possible start to its earliest possible
+//! end, non-empty when the windows leave it less slack than its own size.
+//! Two tasks whose compulsory parts overlap run at the same time wherever
+//! they are placed, so they need different resources.
+//!
+//! [`coloring`] builds that conflict graph and colors it so that conflicting
+//! tasks get different colors. The graph is an interval graph, so coloring
+//! in order of compulsory-part start is optimal: the number of colors is the
+//! fewest resources on which every task can run without a certain clash.
+//! [`Coloring::partition`] turns the colors into an initial split of the
+//! tasks over actual resources, for a multi-resource scheduler.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::analysis::coloring;
+//! # use std::collections::HashMap;
+//! # use virolai::algorithms::CoalitionScheduler;
+//! # use virolai::solution_space::SolutionSpace;
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let solution_space = instance.solution_space;
+//! # let resource_spaces = HashMap::from([
+//! #     ("north".to_string(), SolutionSpace::new()),
+//! #     ("south".to_string(), SolutionSpace::new()),
+//! # ]);
+//! # let types = [("north", "telescope"), ("south", "telescope")];
+//!
+//! let colors = coloring(&blocks, &solution_space);
+//! println!("at least {} resources needed", colors.resources_needed());
+//! let spaces = colors.partition(&resource_spaces);
+//! let timeline = CoalitionScheduler::new(types).schedule_timeline(&blocks, &spaces, horizon);
+//! ```
+
+use std::collections::HashMap;
+
+use qtty::{Quantity, Unit};
+
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Interval, SolutionSpace};
+use crate::Id;
+
+/// A coloring of the task conflict graph, see [`coloring`].
+#[derive(Debug, Clone)]
+pub struct Coloring<U: Unit> {
+    ids: Vec<Id>,
+    index: HashMap<Id, usize>,
+    sizes: Vec<f64>,
+    compulsory: Vec<Option<Interval<U>>>,
+    /// Per task, indices of the conflicting tasks, sorted.
+    neighbors: Vec<Vec<usize>>,
+    colors: Vec<usize>,
+    color_count: usize,
+}
+
+/// Builds the conflict graph of the tasks of `blocks` with windows in `space`
+/// and colors it.
+///
+/// Tasks without a window long enough for them are left out. Colors are
+/// assigned by sweeping compulsory parts by start (ties by ID), each taking
+/// the smallest color free of its running conflicts; tasks without a
+/// compulsory part get color 0.
+pub fn coloring<T, U, D, E>(
+    blocks: &[SchedulingBlock<T, U, D, E>],
+    space: &SolutionSpace<U>,
+) -> Coloring<U>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    let mut tasks: Vec<(Id, f64, Option<Interval<U>>)> = blocks
+        .iter()
+        .flat_map(|b| b.tasks())
+        .filter_map(|(id, task)| {
+            let size = task.size_on_axis().value();
+            let fitting: Vec<(f64, f64)> = space
+                .get_intervals(id)?
+                .iter()
+                .map(|w| (w.start().value(), w.end().value()))
+                .filter(|(s, e)| e - s >= size)
+                .collect();
+            let latest_start = fitting.iter().map(|(_, e)| e - size).reduce(f64::max)?;
+            let earliest_end = fitting.iter().map(|(s, _)| s + size).reduce(f64::min)?;
+            let compulsory = (latest_start < earliest_end)
+                .then(|| Interval::new(Quantity::new(latest_start), Quantity::new(earliest_end)));
+            Some((id.to_string(), size, compulsory))
+        })
+        .collect();
+    tasks.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let ids: Vec<Id> = tasks.iter().map(|(id, _, _)| id.clone()).collect();
+    let index: HashMap<Id, usize> = ids.iter().cloned().zip(0..).collect();
+    let sizes: Vec<f64> = tasks.iter().map(|&(_, size, _)| size).collect();
+    let compulsory: Vec<Option<Interval<U>>> = tasks.into_iter().map(|(_, _, c)| c).collect();
+
+    let mut order: Vec<usize> = (0..ids.len())
+        .filter(|&i| compulsory[i].is_some())
+        .collect();
+    order.sort_by(|&a, &b| {
+        let (ca, cb) = (compulsory[a].unwrap(), compulsory[b].unwrap());
+        ca.start()
+            .value()
+            .total_cmp(&cb.start().value())
+            .then(a.cmp(&b))
+    });
+
+    let mut neighbors = vec![Vec::new(); ids.len()];
+    let mut colors = vec![0; ids.len()];
+    let mut color_count = usize::from(!ids.is_empty());
+    // Tasks whose compulsory part is still running, with their end.
+    let mut active: Vec<(f64, usize)> = Vec::new();
+    for i in order {
+        let part = compulsory[i].expect("only tasks with a compulsory part");
+        active.retain(|&(end, _)| end > part.start().value());
+        let mut taken: Vec<usize> = active.iter().map(|&(_, j)| colors[j]).collect();
+        taken.sort_unstable();
+        let color = (0..).find(|c| taken.binary_search(c).is_err()).unwrap();
+        for &(_, j) in &active {
+            neighbors[i].push(j);
+            neighbors[j].push(i);
+        }
+        colors[i] = color;
+        color_count = color_count.max(color + 1);
+        active.push((part.end().value(), i));
+    }
+    for row in &mut neighbors {
+        row.sort_unstable();
+    }
+
+    Coloring {
+        ids,
+        index,
+        sizes,
+        compulsory,
+        neighbors,
+        colors,
+        color_count,
+    }
+}
+
+impl<U: Unit> Coloring<U> {
+    /// All colored task IDs, sorted.
+    pub fn ids(&self) -> &[Id] {
+        &self.ids
+    }
+
+    /// Number of colors: the fewest resources that can hold every task
+    /// without two certain conflicts sharing one, 0 without tasks.
+    pub fn resources_needed(&self) -> usize {
+        self.color_count
+    }
+
+    /// Color of task `id`, in `0..resources_needed()`.
+    pub fn color_of(&self, id: &str) -> Option<usize> {
+        self.index.get(id).map(|&i| self.colors[i])
+    }
+
+    /// Span task `id` occupies wherever it is placed, if any.
+    pub fn compulsory_part(&self, id: &str) -> Option<Interval<U>> {
+        self.compulsory[*self.index.get(id)?]
+    }
+
+    /// Returns `true` if `a` and `b` always overlap in time.
+    pub fn conflicts(&self, a: &str, b: &str) -> bool {
+        let (Some(&i), Some(&j)) = (self.index.get(a), self.index.get(b)) else {
+            return false;
+        };
+        self.neighbors[i].binary_search(&j).is_ok()
+    }
+
+    /// Tasks always overlapping `id`, sorted by ID.
+    pub fn neighbors(&self, id: &str) -> impl Iterator<Item = &str> + '_ {
+        self.index
+            .get(id)
+            .map(|&i| self.neighbors[i].as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|&j| self.ids[j].as_str())
+    }
+
+    /// Number of conflicting task pairs.
+    pub fn conflict_count(&self) -> usize {
+        self.neighbors.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    /// Tasks of each color, each sorted by ID.
+    pub fn classes(&self) -> Vec<Vec<Id>> {
+        let mut classes = vec![Vec::new(); self.color_count];
+        for (id, &color) in self.ids.iter().zip(&self.colors) {
+            classes[color].push(id.clone());
+        }
+        classes
+    }
+
+    /// Proposes a split of the colored tasks over `resource_spaces`,
+    /// returning each resource's space restricted to its share.
+    ///
+    /// Every colored task is kept on exactly one resource among those whose
+    /// space contains it, avoiding resources already holding a conflicting
+    /// task where possible. Conflicting tasks go to the resource of their
+    /// color (colors map to resources in ID order, cyclically), the others
+    /// to the least loaded resource. Tasks that were not colored keep all
+    /// their entries.
+    pub fn partition(
+        &self,
+        resource_spaces: &HashMap<Id, SolutionSpace<U>>,
+    ) -> HashMap<Id, SolutionSpace<U>> {
+        let mut resources: Vec<&str> = resource_spaces.keys().map(String::as_str).collect();
+        resources.sort_unstable();
+
+        let mut assigned: Vec<Option<usize>> = vec![None; self.ids.len()];
+        let mut load = vec![0.0_f64; resources.len()];
+        // Conflicting tasks first, in color order, so they claim their
+        // color's resource before the flexible ones fill in.
+        let mut order: Vec<usize> = (0..self.ids.len()).collect();
+        order.sort_by_key(|&i| (self.neighbors[i].is_empty(), self.colors[i], i));
+        for i in order {
+            let eligible: Vec<usize> = (0..resources.len())
+                .filter(|&r| {
+                    resource_spaces[resources[r]]
+                        .get_intervals(&self.ids[i])
+                        .is_some()
+                })
+                .collect();
+            let clear = |r: &usize| self.neighbors[i].iter().all(|&j| assigned[j] != Some(*r));
+            let by_load = |a: &&usize, b: &&usize| load[**a].total_cmp(&load[**b]).then(a.cmp(b));
+            let preferred = (!self.neighbors[i].is_empty() && !resources.is_empty())
+                .then(|| self.colors[i] % resources.len())
+                .filter(|r| eligible.contains(r) && clear(r));
+            let choice = preferred
+                .or_else(|| {
+                    eligible
+                        .iter()
+                        .filter(|r| clear(r))
+                        .min_by(by_load)
+                        .copied()
+                })
+                .or_else(|| eligible.iter().min_by(by_load).copied());
+            if let Some(r) = choice {
+                assigned[i] = Some(r);
+                load[r] += self.sizes[i];
+            }
+        }
+
+        resources
+            .iter()
+            .enumerate()
+            .map(|(r, &resource)| {
+                let source = &resource_spaces[resource];
+                let mut space = SolutionSpace::new();
+                for (id, windows) in source.iter() {
+                    let elsewhere = self
+                        .index
+                        .get(id)
+                        .is_some_and(|&i| assigned[i].is_some_and(|a| a != r));
+                    if !elsewhere {
+                        space.set_intervals(id, windows.as_slice().to_vec());
+                    }
+                }
+                (resource.to_string(), space)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{block_and_space, iv, TestTask};
+    use qtty::Second;
+
+    fn windows(tasks: &[(&str, f64, (f64, f64))]) -> SolutionSpace<Second> {
+        problem(tasks).1
+    }
+
+    fn problem(
+        tasks: &[(&str, f64, (f64, f64))],
+    ) -> (SchedulingBlock<TestTask, Second>, SolutionSpace<Second>) {
+        let tasks: Vec<_> = tasks
+            .iter()
+            .map(|&(id, size, (start, end))| (id, size, 0, iv(start, end)))
+            .collect();
+        block_and_space(&tasks)
+    }
+
+    #[test]
+    fn compulsory_overlaps_need_separate_resources() {
+        // a, b and c are pinned around [10, 20); d can move out of the way.
+        let (block, space) = problem(&[
+            ("a", 10.0, (10.0, 20.0)),
+            ("b", 10.0, (5.0, 20.0)),
+            ("c", 8.0, (12.0, 22.0)),
+            ("d", 10.0, (0.0, 100.0)),
+            ("e", 10.0, (30.0, 40.0)),
+            ("too-long", 50.0, (0.0, 10.0)),
+        ]);
+        let colors = coloring(&[block], &space);
+
+        assert_eq!(colors.ids(), ["a", "b", "c", "d", "e"]);
+        assert_eq!(colors.compulsory_part("b"), Some(iv(10.0, 15.0)));
+        assert_eq!(colors.compulsory_part("d"), None);
+        assert!(colors.conflicts("a", "b") && colors.conflicts("a", "c"));
+        // b occupies [10, 15) and c [14, 20).
+        assert!(colors.conflicts("b", "c"));
+        assert!(!colors.conflicts("a", "e") && !colors.conflicts("a", "d"));
+        assert_eq!(colors.neighbors("a").collect::<Vec<_>>(), ["b", "c"]);
+        assert_eq!(colors.conflict_count(), 3);
+        assert_eq!(colors.resources_needed(), 3);
+        assert_eq!(
+            colors.classes(),
+            [vec!["a", "d", "e"], vec!["b"], vec!["c"]]
+        );
+    }
+
+    #[test]
+    fn partition_respects_conflicts_and_eligibility() {
+        let tasks = [
+            ("a", 10.0, (10.0, 20.0)),
+            ("b", 10.0, (10.0, 20.0)),
+            ("c", 10.0, (0.0, 100.0)),
+            ("d", 10.0, (0.0, 100.0)),
+        ];
+        let (block, space) = problem(&tasks);
+        let colors = coloring(&[block], &space);
+        assert_eq!(colors.resources_needed(), 2);
+
+        let mut spaces: HashMap<Id, SolutionSpace<Second>> = HashMap::new();
+        spaces.insert("r1".into(), windows(&tasks));
+        spaces.insert("r2".into(), windows(&tasks));
+        // r3 only sees b.
+        let mut only_b = SolutionSpace::new();
+        only_b.add_interval("b", iv(10.0, 20.0));
+        spaces.insert("r3".into(), only_b);
+
+        let split = colors.partition(&spaces);
+        let holders = |id: &str| {
+            let mut out: Vec<&str> = split
+                .iter()
+                .filter(|(_, s)| s.get_intervals(id).is_some())
+                .map(|(r, _)| r.as_str())
+                .collect();
+            out.sort_unstable();
+            out
+        };
+        assert_eq!(holders("a"), ["r1"]);
+        assert_eq!(holders("b"), ["r2"]);
+        // The flexible tasks balance the load.
+        assert_eq!(holders("c"), ["r1"]);
+        assert_eq!(holders("d"), ["r2"]);
+        assert!(split["r3"].is_empty());
+    }
+}
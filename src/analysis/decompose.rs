@@ -0,0 +1,346 @@
+//! Decomposition into independent subproblems.
+//!
+//! Two tasks interact if their windows overlap or if one depends on the other.
+//! [`decompose`] groups tasks by the transitive closure of that relation; tasks
+//! in different groups can never compete for time, so each group can be
+//! scheduled on its own (in parallel, if desired) and the schedules combined
+//! with [`merge`].
+
+use std::collections::HashMap;
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use qtty::Unit;
+
+use super::overlap::overlap_matrix;
+use crate::algorithms::SchedulingAlgorithm;
+use crate::schedule::errors::ScheduleError;
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, SolutionSpace};
+
+/// A self-contained part of a scheduling problem.
+#[derive(Debug)]
+pub struct Subproblem<T, U, D, E>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    /// The group's tasks and dependencies, keeping the original block
+    /// boundaries (blocks without members in the group are left out).
+    pub blocks: Vec<SchedulingBlock<T, U, D, E>>,
+    /// Windows of the group's tasks only.
+    pub solution_space: SolutionSpace<U>,
+}
+
+impl<T, U, D, E> Subproblem<T, U, D, E>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    /// IDs of the tasks in this subproblem, sorted.
+    pub fn task_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self
+            .blocks
+            .iter()
+            .flat_map(|b| b.tasks().map(|(id, _)| id))
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Number of tasks in this subproblem.
+    pub fn task_count(&self) -> usize {
+        self.blocks.iter().map(SchedulingBlock::task_count).sum()
+    }
+
+    /// Runs `algorithm` on this subproblem alone.
+    pub fn schedule<A>(&self, algorithm: &A, horizon: Horizon<U>) -> Schedule<U>
+    where
+        A: SchedulingAlgorithm<T, U, D, E> + ?Sized,
+    {
+        algorithm.schedule(&self.blocks, &self.solution_space, horizon)
+    }
+}
+
+/// Per group: a block's share of the group, and old → new node indices.
+type Split<B> = HashMap<usize, (B, HashMap<NodeIndex, NodeIndex>)>;
+
+/// Splits a problem into independent subproblems.
+///
+/// Every task of `blocks` ends up in exactly one subproblem; tasks without
+/// windows in `space` form their own. Subproblems are ordered by their
+/// smallest task ID.
+///
+/// # Example
+///
+/// ```no_run
+/// use virolai::analysis::{decompose, merge};
+/// # use qtty::Quantity;
+/// # use virolai::algorithms::ESTScheduler;
+/// # use qtty::Second;
+/// # use virolai::solution_space::{Horizon, Interval};
+/// # use virolai::synthetic::{generate, SyntheticConfig};
+/// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+/// # let instance = generate(&SyntheticConfig::new(20, horizon));
+/// # let blocks = vec![instance.block];
+/// # let solution_space = instance.solution_space;
+///
+/// let parts = decompose(&blocks, &solution_space);
+/// let schedules = std::thread::scope(|s| {
+///     let handles: Vec<_> = parts
+///         .iter()
+///         .map(|p| s.spawn(|| p.schedule(&ESTScheduler::new(Quantity::new(1.0)), horizon)))
+///         .collect();
+///     handles.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>()
+/// });
+/// let schedule = merge(schedules)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn decompose<T, U, D, E>(
+    blocks: &[SchedulingBlock<T, U, D, E>],
+    space: &SolutionSpace<U>,
+) -> Vec<Subproblem<T, U, D, E>>
+where
+    T: Task<U> + Clone,
+    U: Unit,
+    D: Clone,
+    E: petgraph::EdgeType,
+{
+    let mut ids: Vec<&str> = blocks
+        .iter()
+        .flat_map(|b| b.tasks().map(|(id, _)| id))
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    let index: HashMap<&str, usize> = ids.iter().copied().zip(0..).collect();
+
+    let mut groups = DisjointSets::new(ids.len());
+    for (a, b, _) in overlap_matrix(space).pairs() {
+        if let (Some(&a), Some(&b)) = (index.get(a), index.get(b)) {
+            groups.union(a, b);
+        }
+    }
+    for block in blocks {
+        for edge in block.graph().edge_references() {
+            if let (Some(a), Some(b)) = (block.id_of(edge.source()), block.id_of(edge.target())) {
+                groups.union(index[a], index[b]);
+            }
+        }
+    }
+
+    // Group number of each task, numbered by smallest member ID.
+    let mut group_of = vec![usize::MAX; ids.len()];
+    let mut root_group: HashMap<usize, usize> = HashMap::new();
+    for (i, slot) in group_of.iter_mut().enumerate() {
+        let next = root_group.len();
+        *slot = *root_group.entry(groups.find(i)).or_insert(next);
+    }
+
+    let mut parts: Vec<Subproblem<T, U, D, E>> = (0..root_group.len())
+        .map(|_| Subproblem {
+            blocks: Vec::new(),
+            solution_space: SolutionSpace::new(),
+        })
+        .collect();
+
+    for block in blocks {
+        let mut split: Split<SchedulingBlock<T, U, D, E>> = HashMap::new();
+        for (id, task) in block.tasks() {
+            let group = group_of[index[id]];
+            let (part, nodes) = split.entry(group).or_default();
+            let new_id = part
+                .add_task_with_id(task.clone(), Some(id.to_string()))
+                .expect("task IDs are unique within a block");
+            let old = block.node_of(id).expect("task belongs to the block");
+            nodes.insert(old, part.node_of(&new_id).expect("task was just added"));
+        }
+        for edge in block.graph().edge_references() {
+            let Some(source) = block.id_of(edge.source()) else {
+                continue;
+            };
+            let (part, nodes) = split
+                .get_mut(&group_of[index[source]])
+                .expect("source task was split");
+            part.add_dependency(
+                nodes[&edge.source()],
+                nodes[&edge.target()],
+                edge.weight().clone(),
+            )
+            .expect("edges of an acyclic block stay acyclic");
+        }
+
+        let mut split: Vec<_> = split.into_iter().collect();
+        split.sort_unstable_by_key(|(group, _)| *group);
+        for (group, (part, _)) in split {
+            parts[group].blocks.push(part);
+        }
+    }
+
+    for (&id, &i) in &index {
+        if let Some(windows) = space.get_intervals(id) {
+            parts[group_of[i]]
+                .solution_space
+                .set_intervals(id, windows.as_slice().to_vec());
+        }
+    }
+
+    parts
+}
+
+/// Combines the schedules of independent subproblems into one.
+///
+/// # Errors
+///
+/// Fails if two schedules share a task or overlap, which cannot happen for
+/// schedules of different [`decompose`] subproblems that respect their
+/// windows.
+pub fn merge<U: Unit>(
+    schedules: impl IntoIterator<Item = Schedule<U>>,
+) -> Result<Schedule<U>, ScheduleError> {
+    let mut merged = Schedule::new();
+    for schedule in schedules {
+        for (id, interval, background) in schedule.iter_all() {
+            merged.add_to_lane(id, interval, background)?;
+        }
+    }
+    Ok(merged)
+}
+
+/// Union-find with path halving.
+struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[a.max(b)] = a.min(b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::est::ESTScheduler;
+    use crate::test_utils::{block_of, hz, iv, q, TestTask};
+    use qtty::Second;
+
+    type Block = SchedulingBlock<TestTask, Second>;
+
+    fn block(tasks: &[&str], deps: &[(&str, &str)]) -> Block {
+        let mut block = block_of(tasks.iter().map(|&name| TestTask::new(name, 5.0)));
+        for &(from, to) in deps {
+            let (from, to) = (block.node_of(from).unwrap(), block.node_of(to).unwrap());
+            block.add_dependency(from, to, ()).unwrap();
+        }
+        block
+    }
+
+    fn space(windows: &[(&str, f64, f64)]) -> SolutionSpace<Second> {
+        let mut ss = SolutionSpace::new();
+        for &(id, start, end) in windows {
+            ss.add_interval(id, iv(start, end));
+        }
+        ss
+    }
+
+    fn ids(parts: &[Subproblem<TestTask, Second, (), petgraph::Directed>]) -> Vec<Vec<&str>> {
+        parts.iter().map(Subproblem::task_ids).collect()
+    }
+
+    #[test]
+    fn overlaps_and_dependencies_join_groups() {
+        let blocks = [
+            block(&["a", "b", "c"], &[("a", "c")]),
+            block(&["d", "e"], &[]),
+        ];
+        // a–c joined by dependency, b–d by overlap; e alone.
+        let ss = space(&[
+            ("a", 0.0, 10.0),
+            ("b", 20.0, 30.0),
+            ("c", 100.0, 110.0),
+            ("d", 25.0, 40.0),
+            ("e", 200.0, 210.0),
+        ]);
+
+        let parts = decompose(&blocks, &ss);
+
+        assert_eq!(ids(&parts), vec![vec!["a", "c"], vec!["b", "d"], vec!["e"]]);
+        assert_eq!(parts[0].blocks.len(), 1);
+        assert_eq!(parts[0].blocks[0].dependency_count(), 1);
+        assert_eq!(parts[1].blocks.len(), 2);
+        assert_eq!(parts[1].solution_space.count(), 2);
+        assert!(parts[2].solution_space.get_intervals("e").is_some());
+        assert_eq!(parts.iter().map(Subproblem::task_count).sum::<usize>(), 5);
+    }
+
+    #[test]
+    fn tasks_without_windows_stand_alone() {
+        let blocks = [block(&["a", "b"], &[])];
+        let parts = decompose(&blocks, &space(&[("a", 0.0, 10.0)]));
+        assert_eq!(ids(&parts), vec![vec!["a"], vec!["b"]]);
+        assert_eq!(parts[1].solution_space.count(), 0);
+    }
+
+    #[test]
+    fn merged_subproblem_schedules_match_whole_run() {
+        let blocks = [block(&["a", "b", "c", "d"], &[])];
+        let ss = space(&[
+            ("a", 0.0, 10.0),
+            ("b", 0.0, 10.0),
+            ("c", 50.0, 60.0),
+            ("d", 55.0, 70.0),
+        ]);
+        let horizon = hz(0.0, 100.0);
+        let est = ESTScheduler::new(q(1.0));
+
+        let parts = decompose(&blocks, &ss);
+        assert_eq!(parts.len(), 2);
+        let merged = merge(parts.iter().map(|p| p.schedule(&est, horizon))).unwrap();
+        let whole = est.schedule(&blocks, &ss, horizon);
+
+        assert_eq!(
+            merged.iter().collect::<Vec<_>>(),
+            whole.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn merge_rejects_conflicts() {
+        let mut a = Schedule::new();
+        a.add("x", iv(0.0, 10.0)).unwrap();
+        let mut b = Schedule::new();
+        b.add("y", iv(5.0, 15.0)).unwrap();
+        assert!(merge([a, b]).is_err());
+    }
+
+    #[test]
+    fn merge_keeps_background_lane() {
+        let mut a = Schedule::new();
+        a.add("x", iv(0.0, 10.0)).unwrap();
+        let mut b = Schedule::new();
+        b.add_background("monitor", iv(5.0, 15.0)).unwrap();
+        let merged = merge([a, b]).unwrap();
+        assert_eq!(merged.len(), 1);
+        assert!(merged.is_background("monitor"));
+    }
+}
@@ -0,0 +1,14 @@
+//! Pre-scheduling analyses of a problem instance.
+//!
+//! These tools inspect a [`SolutionSpace`](crate::solution_space::SolutionSpace)
+//! before any scheduler runs, to understand where tasks compete for time and
+//! how the problem could be split into independent subproblems or over
+//! resources.
+
+mod coloring;
+mod decompose;
+mod overlap;
+
+pub use coloring::{coloring, Coloring};
+pub use decompose::{decompose, merge, Subproblem};
+pub use overlap::{overlap_matrix, OverlapMatrix};
@@ -0,0 +1,357 @@
+//! Pairwise window overlap between tasks.
+//!
+//! Two tasks contend for time when their feasible windows overlap. The
+//! [`OverlapMatrix`] records, for every such pair, the total duration of the
+//! overlap. It is sparse: tasks that never share time have no entry. From it,
+//! [`OverlapMatrix::conflict_groups`] splits the tasks into independent
+//! components and [`OverlapMatrix::maximal_cliques`] finds the sets of tasks
+//! that are all pairwise in conflict, the usual contention hot-spots.
+
+use std::collections::HashMap;
+
+use qtty::{Quantity, Unit};
+
+use crate::solution_space::SolutionSpace;
+use crate::Id;
+
+/// Sparse symmetric matrix of window-overlap durations between tasks.
+#[derive(Debug, Clone)]
+pub struct OverlapMatrix<U: Unit> {
+    ids: Vec<Id>,
+    index: HashMap<Id, usize>,
+    /// Per task, `(neighbor index, overlap)` sorted by neighbor index.
+    rows: Vec<Vec<(usize, Quantity<U>)>>,
+}
+
+/// Computes the [`OverlapMatrix`] of every task in `space`.
+///
+/// Runs a sweep over all windows sorted by start, so the cost is
+/// `O(w log w + k)` for `w` windows and `k` overlapping window pairs.
+/// Windows that merely touch (`[0, 10)` and `[10, 20)`) do not overlap.
+///
+/// # Example
+///
+/// ```no_run
+/// use virolai::analysis::overlap_matrix;
+/// # use qtty::Second;
+/// # use virolai::solution_space::{Horizon, Interval};
+/// # use virolai::synthetic::{generate, SyntheticConfig};
+/// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+/// # let instance = generate(&SyntheticConfig::new(20, horizon));
+/// # let solution_space = instance.solution_space;
+///
+/// let matrix = overlap_matrix(&solution_space);
+/// for group in matrix.conflict_groups() {
+///     println!("independent subproblem: {group:?}");
+/// }
+/// ```
+pub fn overlap_matrix<U: Unit>(space: &SolutionSpace<U>) -> OverlapMatrix<U> {
+    let mut ids: Vec<Id> = space.ids().map(str::to_string).collect();
+    ids.sort_unstable();
+    let index: HashMap<Id, usize> = ids.iter().cloned().zip(0..).collect();
+
+    let mut windows: Vec<(f64, f64, usize)> = ids
+        .iter()
+        .enumerate()
+        .flat_map(|(i, id)| {
+            space
+                .get_intervals(id)
+                .into_iter()
+                .flatten()
+                .map(move |w| (w.start().value(), w.end().value(), i))
+        })
+        .collect();
+    windows.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.2.cmp(&b.2)));
+
+    let mut totals: HashMap<(usize, usize), f64> = HashMap::new();
+    let mut active: Vec<(f64, usize)> = Vec::new();
+    for (start, end, task) in windows {
+        active.retain(|&(active_end, _)| active_end > start);
+        for &(active_end, other) in &active {
+            let overlap = active_end.min(end) - start;
+            if other != task && overlap > 0.0 {
+                *totals
+                    .entry((other.min(task), other.max(task)))
+                    .or_default() += overlap;
+            }
+        }
+        active.push((end, task));
+    }
+
+    let mut rows = vec![Vec::new(); ids.len()];
+    for ((a, b), overlap) in totals {
+        rows[a].push((b, Quantity::new(overlap)));
+        rows[b].push((a, Quantity::new(overlap)));
+    }
+    for row in &mut rows {
+        row.sort_unstable_by_key(|&(j, _)| j);
+    }
+
+    OverlapMatrix { ids, index, rows }
+}
+
+impl<U: Unit> OverlapMatrix<U> {
+    /// All task IDs, sorted, including tasks without any overlap.
+    pub fn ids(&self) -> &[Id] {
+        &self.ids
+    }
+
+    /// Number of task pairs with a non-zero overlap.
+    pub fn pair_count(&self) -> usize {
+        self.rows.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    /// Total overlap between the windows of `a` and `b`; zero if they never
+    /// overlap or either is unknown.
+    pub fn overlap(&self, a: &str, b: &str) -> Quantity<U> {
+        let (Some(&i), Some(&j)) = (self.index.get(a), self.index.get(b)) else {
+            return Quantity::new(0.0);
+        };
+        self.rows[i]
+            .binary_search_by_key(&j, |&(k, _)| k)
+            .map(|pos| self.rows[i][pos].1)
+            .unwrap_or(Quantity::new(0.0))
+    }
+
+    /// Tasks overlapping `id`, with the overlap duration, sorted by ID.
+    pub fn neighbors(&self, id: &str) -> impl Iterator<Item = (&str, Quantity<U>)> + '_ {
+        self.index
+            .get(id)
+            .map(|&i| self.rows[i].as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|&(j, overlap)| (self.ids[j].as_str(), overlap))
+    }
+
+    /// Every overlapping pair `(a, b, overlap)` once, with `a < b`.
+    pub fn pairs(&self) -> impl Iterator<Item = (&str, &str, Quantity<U>)> + '_ {
+        self.rows.iter().enumerate().flat_map(move |(i, row)| {
+            row.iter()
+                .filter(move |&&(j, _)| j > i)
+                .map(move |&(j, overlap)| (self.ids[i].as_str(), self.ids[j].as_str(), overlap))
+        })
+    }
+
+    /// Sum of the overlaps of `id` with every other task.
+    pub fn contention(&self, id: &str) -> Quantity<U> {
+        self.neighbors(id)
+            .fold(Quantity::new(0.0), |acc, (_, overlap)| acc + overlap)
+    }
+
+    /// Connected components of the overlap graph with at least two tasks.
+    ///
+    /// Tasks in different groups never compete for time and can be scheduled
+    /// independently. Each group is sorted by ID; groups are sorted by their
+    /// first ID.
+    pub fn conflict_groups(&self) -> Vec<Vec<Id>> {
+        let mut seen = vec![false; self.ids.len()];
+        let mut groups = Vec::new();
+        for root in 0..self.ids.len() {
+            if seen[root] || self.rows[root].is_empty() {
+                continue;
+            }
+            seen[root] = true;
+            let mut stack = vec![root];
+            let mut members = Vec::new();
+            while let Some(i) = stack.pop() {
+                members.push(i);
+                for &(j, _) in &self.rows[i] {
+                    if !seen[j] {
+                        seen[j] = true;
+                        stack.push(j);
+                    }
+                }
+            }
+            members.sort_unstable();
+            groups.push(self.names(&members));
+        }
+        groups
+    }
+
+    /// Maximal sets of at least two tasks whose windows pairwise overlap.
+    ///
+    /// Uses Bron–Kerbosch with pivoting. Each clique is sorted by ID; cliques
+    /// are sorted largest first, then by IDs.
+    pub fn maximal_cliques(&self) -> Vec<Vec<Id>> {
+        let neighbors: Vec<Vec<usize>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|&(j, _)| j).collect())
+            .collect();
+        let mut cliques = Vec::new();
+        bron_kerbosch(
+            &neighbors,
+            &mut Vec::new(),
+            (0..self.ids.len()).collect(),
+            Vec::new(),
+            &mut cliques,
+        );
+
+        let mut named: Vec<Vec<Id>> = cliques
+            .into_iter()
+            .map(|mut c| {
+                c.sort_unstable();
+                self.names(&c)
+            })
+            .collect();
+        named.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        named
+    }
+
+    fn names(&self, indices: &[usize]) -> Vec<Id> {
+        indices.iter().map(|&i| self.ids[i].clone()).collect()
+    }
+}
+
+/// Intersection of a sorted candidate list with a sorted neighbor list.
+fn intersect(set: &[usize], neighbors: &[usize]) -> Vec<usize> {
+    set.iter()
+        .copied()
+        .filter(|v| neighbors.binary_search(v).is_ok())
+        .collect()
+}
+
+fn bron_kerbosch(
+    neighbors: &[Vec<usize>],
+    clique: &mut Vec<usize>,
+    mut candidates: Vec<usize>,
+    mut excluded: Vec<usize>,
+    out: &mut Vec<Vec<usize>>,
+) {
+    if candidates.is_empty() {
+        if excluded.is_empty() && clique.len() >= 2 {
+            out.push(clique.clone());
+        }
+        return;
+    }
+
+    let pivot = candidates
+        .iter()
+        .chain(&excluded)
+        .copied()
+        .max_by_key(|&u| intersect(&candidates, &neighbors[u]).len())
+        .expect("candidates is not empty");
+    let branches: Vec<usize> = candidates
+        .iter()
+        .copied()
+        .filter(|v| neighbors[pivot].binary_search(v).is_err())
+        .collect();
+
+    for v in branches {
+        clique.push(v);
+        bron_kerbosch(
+            neighbors,
+            clique,
+            intersect(&candidates, &neighbors[v]),
+            intersect(&excluded, &neighbors[v]),
+            out,
+        );
+        clique.pop();
+        candidates.retain(|&c| c != v);
+        let pos = excluded.partition_point(|&x| x < v);
+        excluded.insert(pos, v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{iv, q};
+    use qtty::Second;
+
+    fn space(tasks: &[(&str, &[(f64, f64)])]) -> SolutionSpace<Second> {
+        let mut ss = SolutionSpace::new();
+        for &(id, windows) in tasks {
+            ss.set_intervals(id, windows.iter().map(|&(s, e)| iv(s, e)).collect());
+        }
+        ss
+    }
+
+    // ── overlap_matrix ────────────────────────────────────────────────
+
+    #[test]
+    fn overlaps_sum_over_windows() {
+        let ss = space(&[
+            ("a", &[(0.0, 10.0), (20.0, 30.0)]),
+            ("b", &[(5.0, 25.0)]),
+            ("c", &[(30.0, 40.0)]),
+        ]);
+        let m = overlap_matrix(&ss);
+
+        assert_eq!(m.ids(), ["a", "b", "c"]);
+        assert_eq!(m.overlap("a", "b"), q(10.0));
+        assert_eq!(m.overlap("b", "a"), q(10.0));
+        // Touching windows do not overlap.
+        assert_eq!(m.overlap("a", "c"), q(0.0));
+        assert_eq!(m.overlap("a", "missing"), q(0.0));
+        assert_eq!(m.pair_count(), 1);
+        assert_eq!(m.pairs().collect::<Vec<_>>(), vec![("a", "b", q(10.0))]);
+    }
+
+    #[test]
+    fn contention_and_neighbors() {
+        let ss = space(&[
+            ("a", &[(0.0, 100.0)]),
+            ("b", &[(10.0, 20.0)]),
+            ("c", &[(50.0, 80.0)]),
+        ]);
+        let m = overlap_matrix(&ss);
+
+        assert_eq!(
+            m.neighbors("a").collect::<Vec<_>>(),
+            vec![("b", q(10.0)), ("c", q(30.0))]
+        );
+        assert_eq!(m.contention("a"), q(40.0));
+        assert_eq!(m.contention("b"), q(10.0));
+        assert_eq!(m.neighbors("missing").count(), 0);
+    }
+
+    // ── groups and cliques ────────────────────────────────────────────
+
+    #[test]
+    fn conflict_groups_are_components() {
+        let ss = space(&[
+            ("a", &[(0.0, 10.0)]),
+            ("b", &[(5.0, 15.0)]),
+            ("c", &[(12.0, 20.0)]),
+            ("d", &[(50.0, 60.0)]),
+            ("e", &[(55.0, 70.0)]),
+            ("lonely", &[(100.0, 110.0)]),
+        ]);
+        let groups = overlap_matrix(&ss).conflict_groups();
+        assert_eq!(
+            groups,
+            vec![
+                vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                vec!["d".to_string(), "e".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn maximal_cliques_find_hot_spots() {
+        // a, b, c all share [8, 10); c and d share [12, 14).
+        let ss = space(&[
+            ("a", &[(0.0, 10.0)]),
+            ("b", &[(5.0, 11.0)]),
+            ("c", &[(8.0, 14.0)]),
+            ("d", &[(12.0, 20.0)]),
+        ]);
+        let cliques = overlap_matrix(&ss).maximal_cliques();
+        assert_eq!(
+            cliques,
+            vec![
+                vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                vec!["c".to_string(), "d".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_space_has_no_conflicts() {
+        let m = overlap_matrix(&SolutionSpace::<Second>::new());
+        assert!(m.ids().is_empty());
+        assert!(m.conflict_groups().is_empty());
+        assert!(m.maximal_cliques().is_empty());
+    }
+}
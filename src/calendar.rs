@@ -0,0 +1,113 @@
+//! Wall-clock time on the scheduling axis (feature `chrono`).
+//!
+//! The scheduling axis is a plain number line. [`CalendarAxis`] pins it to
+//! the calendar by fixing the UTC instant of axis zero, and converts chrono
+//! date-times to axis positions and back. Conversions go through whole
+//! seconds and nanoseconds, so instants a whole number of seconds apart land
+//! exactly that many seconds apart on the axis, whatever the axis unit.
+//!
+//! Constraints defined in local time, such as
+//! [`RecurringWindow`](crate::constraints::RecurringWindow), use the axis to
+//! produce their intervals.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use chrono::{TimeZone, Utc};
+//! # use qtty::{Quantity, Second};
+//! use virolai::calendar::CalendarAxis;
+//!
+//! let axis = CalendarAxis::new(Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap());
+//! let start: Quantity<Second> = axis.position(&Utc.with_ymd_and_hms(2026, 3, 1, 20, 0, 0).unwrap());
+//! assert_eq!(start.value(), 72_000.0);
+//! ```
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use qtty::{Quantity, Second};
+
+use crate::solution_space::Interval;
+use crate::units::SameDim;
+
+/// Maps between UTC instants and axis positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CalendarAxis {
+    origin: DateTime<Utc>,
+}
+
+impl CalendarAxis {
+    /// An axis whose zero is `origin`.
+    pub const fn new(origin: DateTime<Utc>) -> Self {
+        Self { origin }
+    }
+
+    /// The instant of axis zero.
+    pub const fn origin(&self) -> DateTime<Utc> {
+        self.origin
+    }
+
+    /// Axis position of `time`.
+    pub fn position<U, Tz>(&self, time: &DateTime<Tz>) -> Quantity<U>
+    where
+        U: SameDim<Second>,
+        Tz: TimeZone,
+    {
+        let delta = time.with_timezone(&Utc) - self.origin;
+        let seconds = delta.num_seconds() as f64 + delta.subsec_nanos() as f64 * 1e-9;
+        Quantity::<Second>::new(seconds).to::<U>()
+    }
+
+    /// The instant at `position`, rounded to the nanosecond, or `None` if it
+    /// is not finite or outside chrono's range.
+    pub fn datetime<U>(&self, position: Quantity<U>) -> Option<DateTime<Utc>>
+    where
+        U: SameDim<Second>,
+    {
+        let seconds = position.to::<Second>().value();
+        if !seconds.is_finite() {
+            return None;
+        }
+        let whole = seconds.floor();
+        let nanos = ((seconds - whole) * 1e9).round() as i64;
+        let delta =
+            TimeDelta::try_seconds(whole as i64)?.checked_add(&TimeDelta::nanoseconds(nanos))?;
+        self.origin.checked_add_signed(delta)
+    }
+
+    /// The axis interval from `start` to `end`.
+    pub fn interval<U, Tz>(&self, start: &DateTime<Tz>, end: &DateTime<Tz>) -> Interval<U>
+    where
+        U: SameDim<Second>,
+        Tz: TimeZone,
+    {
+        Interval::new(self.position(start), self.position(end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qtty::Minute;
+
+    #[test]
+    fn positions_round_trip_exactly() {
+        let origin = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let axis = CalendarAxis::new(origin);
+        let later = Utc.with_ymd_and_hms(2026, 3, 2, 1, 30, 15).unwrap();
+
+        let seconds: Quantity<Second> = axis.position(&later);
+        assert_eq!(seconds.value(), 91_815.0);
+        assert_eq!(axis.datetime(seconds), Some(later));
+
+        let minutes: Quantity<Minute> = axis.position(&later);
+        assert_eq!(minutes.value(), 1_530.25);
+        assert_eq!(axis.datetime(minutes), Some(later));
+
+        let before = Utc.with_ymd_and_hms(2026, 2, 28, 23, 59, 59).unwrap();
+        assert_eq!(axis.position::<Second, _>(&before).value(), -1.0);
+        assert_eq!(axis.datetime(Quantity::<Second>::new(f64::INFINITY)), None);
+        assert_eq!(
+            axis.interval::<Second, _>(&origin, &later),
+            Interval::from_f64(0.0, 91_815.0)
+        );
+    }
+}
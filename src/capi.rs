@@ -0,0 +1,616 @@
+//! C ABI for embedding virolai in C and C++ programs (feature `capi`).
+//!
+//! Generic Rust types cannot cross the language boundary, so this layer
+//! fixes them: tasks are [`ImportedTask`]s on a seconds axis, dependencies
+//! carry no data, and the algorithm is [`ESTScheduler`]. Blocks, solution
+//! spaces and schedules are opaque handles created and freed through the
+//! functions below; the header `include/virolai.h` is generated from this
+//! module by the build script when the feature is enabled. The build writes
+//! it to `OUT_DIR`; run it with `VIROLAI_WRITE_HEADER=1` to refresh the
+//! checked-in copy after changing the API.
+//!
+//! Every function taking a handle or string requires it to be valid: a
+//! handle from the matching `_new` function that has not been freed, and a
+//! NUL-terminated UTF-8 string. Null handles and strings are reported as
+//! [`VirolaiStatus::NullPointer`] rather than dereferenced. Functions
+//! returning a status record the reason of a failure, readable with
+//! [`virolai_last_error`] on the same thread. A panic never unwinds into
+//! the caller: it is reported as [`VirolaiStatus::Panic`], or as null or
+//! zero by functions returning a handle or a count.
+//!
+//! Build a static or shared library with
+//! `cargo rustc --release --features capi --crate-type staticlib` (or
+//! `cdylib`).
+//!
+//! # Example
+//!
+//! ```c
+//! VirolaiBlock *block = virolai_block_new();
+//! VirolaiSpace *space = virolai_space_new();
+//! virolai_block_add_task(block, "m31", 600.0, 5);
+//! virolai_space_add_interval(space, "m31", 0.0, 3600.0);
+//!
+//! VirolaiSchedule *schedule = virolai_est_schedule(block, space, 0.0, 86400.0, 1);
+//! for (size_t i = 0; i < virolai_schedule_len(schedule); ++i) {
+//!     VirolaiEntry entry;
+//!     virolai_schedule_entry(schedule, i, &entry);
+//!     printf("%s [%f, %f)\n", entry.task_id, entry.start, entry.end);
+//! }
+//!
+//! virolai_schedule_free(schedule);
+//! virolai_space_free(space);
+//! virolai_block_free(block);
+//! ```
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use qtty::{Quantity, Second};
+
+use crate::algorithms::{ESTScheduler, SchedulingAlgorithm};
+use crate::io::import::ImportedTask;
+use crate::schedule::Schedule;
+use crate::scheduling_block::SchedulingBlock;
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+
+/// Outcome of a C API call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VirolaiStatus {
+    Ok = 0,
+    /// A handle, string or output pointer was null.
+    NullPointer = 1,
+    /// A string was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// A number was NaN, negative where it must not be, or an interval
+    /// ended before it started.
+    InvalidArgument = 3,
+    /// A task with this ID already exists in the block.
+    DuplicateId = 4,
+    /// No task or entry with this ID or index.
+    NotFound = 5,
+    /// The dependency would make the block cyclic.
+    Cycle = 6,
+    /// The library panicked. Handles passed to the call may be left
+    /// partially updated.
+    Panic = 7,
+}
+
+/// A scheduling block of tasks and their dependencies.
+pub struct VirolaiBlock {
+    block: SchedulingBlock<ImportedTask<Second>>,
+}
+
+/// Allowed placement windows per task.
+pub struct VirolaiSpace {
+    space: SolutionSpace<Second>,
+}
+
+/// A schedule produced by [`virolai_est_schedule`]: the primary entries in
+/// start order, then the background ones.
+pub struct VirolaiSchedule {
+    entries: Vec<(CString, Interval<Second>, bool)>,
+}
+
+impl VirolaiSchedule {
+    fn new(schedule: &Schedule<Second>) -> Self {
+        let entries = schedule
+            .iter_all()
+            .map(|(id, interval, background)| {
+                let id = CString::new(id).expect("task IDs come from C strings");
+                (id, interval, background)
+            })
+            .collect();
+        Self { entries }
+    }
+}
+
+/// A scheduled task, borrowed from its [`VirolaiSchedule`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct VirolaiEntry {
+    /// The task ID, valid until the schedule is freed.
+    pub task_id: *const c_char,
+    pub start: f64,
+    pub end: f64,
+    /// Whether the task is in the background lane, where it may overlap
+    /// other entries.
+    pub background: bool,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+/// Records `message` as the thread's last error and returns `status`.
+fn fail(status: VirolaiStatus, message: impl Into<String>) -> VirolaiStatus {
+    let message = CString::new(message.into().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    status
+}
+
+/// Runs `f`, returning `on_panic` and recording the panic message as the
+/// last error if it panics, since unwinding into C is undefined behavior.
+fn guard<R>(on_panic: R, f: impl FnOnce() -> R) -> R {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        fail(VirolaiStatus::Panic, format!("panic: {message}"));
+        on_panic
+    })
+}
+
+/// Reads a C string argument.
+///
+/// # Safety
+///
+/// `s` must be null or point to a NUL-terminated string.
+unsafe fn read_str<'a>(s: *const c_char, what: &str) -> Result<&'a str, VirolaiStatus> {
+    if s.is_null() {
+        return Err(fail(VirolaiStatus::NullPointer, format!("{what} is null")));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| fail(VirolaiStatus::InvalidUtf8, format!("{what} is not UTF-8")))
+}
+
+/// Builds `[start, end)`, rejecting NaN and reversed bounds.
+fn interval(start: f64, end: f64) -> Result<Interval<Second>, VirolaiStatus> {
+    if start.is_nan() || end.is_nan() || start > end {
+        return Err(fail(
+            VirolaiStatus::InvalidArgument,
+            format!("invalid interval [{start}, {end})"),
+        ));
+    }
+    Ok(Interval::from_f64(start, end))
+}
+
+fn horizon(start: f64, end: f64) -> Result<Horizon<Second>, VirolaiStatus> {
+    Horizon::new(interval(start, end)?)
+        .map_err(|err| fail(VirolaiStatus::InvalidArgument, err.to_string()))
+}
+
+/// Returns the message of the last failed call on this thread, or an empty
+/// string. The pointer is valid until the next failing call on the thread.
+#[no_mangle]
+pub extern "C" fn virolai_last_error() -> *const c_char {
+    guard(ptr::null(), || {
+        LAST_ERROR.with(|last| last.borrow().as_ptr())
+    })
+}
+
+/// Returns the library version, a static string.
+#[no_mangle]
+pub extern "C" fn virolai_version() -> *const c_char {
+    guard(ptr::null(), || {
+        concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+    })
+}
+
+/// Creates an empty block. Free it with [`virolai_block_free`].
+#[no_mangle]
+pub extern "C" fn virolai_block_new() -> *mut VirolaiBlock {
+    guard(ptr::null_mut(), || {
+        Box::into_raw(Box::new(VirolaiBlock {
+            block: SchedulingBlock::new(),
+        }))
+    })
+}
+
+/// Frees a block. Null is ignored.
+///
+/// # Safety
+///
+/// `block` must be null or a live handle from [`virolai_block_new`].
+#[no_mangle]
+pub unsafe extern "C" fn virolai_block_free(block: *mut VirolaiBlock) {
+    guard((), || {
+        if !block.is_null() {
+            drop(Box::from_raw(block));
+        }
+    })
+}
+
+/// Adds a task of `size` seconds, named after its ID.
+///
+/// # Safety
+///
+/// `block` must be a live handle and `id` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn virolai_block_add_task(
+    block: *mut VirolaiBlock,
+    id: *const c_char,
+    size: f64,
+    priority: i32,
+) -> VirolaiStatus {
+    guard(VirolaiStatus::Panic, || {
+        let Some(block) = block.as_mut() else {
+            return fail(VirolaiStatus::NullPointer, "block is null");
+        };
+        let id = match read_str(id, "task ID") {
+            Ok(id) => id,
+            Err(status) => return status,
+        };
+        if !(size >= 0.0 && size.is_finite()) {
+            return fail(
+                VirolaiStatus::InvalidArgument,
+                format!("invalid size {size} for task {id}"),
+            );
+        }
+        let task = ImportedTask::new(id, Quantity::new(size), priority, Vec::new());
+        match block.block.add_task_with_id(task, Some(id.to_string())) {
+            Ok(_) => VirolaiStatus::Ok,
+            Err(e) => fail(VirolaiStatus::DuplicateId, e.to_string()),
+        }
+    })
+}
+
+/// Makes task `to` depend on task `from`.
+///
+/// # Safety
+///
+/// `block` must be a live handle and `from` and `to` NUL-terminated
+/// strings.
+#[no_mangle]
+pub unsafe extern "C" fn virolai_block_add_dependency(
+    block: *mut VirolaiBlock,
+    from: *const c_char,
+    to: *const c_char,
+) -> VirolaiStatus {
+    guard(VirolaiStatus::Panic, || {
+        let Some(block) = block.as_mut() else {
+            return fail(VirolaiStatus::NullPointer, "block is null");
+        };
+        let (from, to) = match (read_str(from, "source ID"), read_str(to, "target ID")) {
+            (Ok(from), Ok(to)) => (from, to),
+            (Err(status), _) | (_, Err(status)) => return status,
+        };
+        let nodes = (block.block.node_of(from), block.block.node_of(to));
+        let (Some(from_node), Some(to_node)) = nodes else {
+            let missing = if nodes.0.is_none() { from } else { to };
+            return fail(VirolaiStatus::NotFound, format!("no task {missing}"));
+        };
+        match block.block.add_dependency(from_node, to_node, ()) {
+            Ok(_) => VirolaiStatus::Ok,
+            Err(e) => fail(VirolaiStatus::Cycle, e.to_string()),
+        }
+    })
+}
+
+/// Returns the number of tasks in the block, 0 for null.
+///
+/// # Safety
+///
+/// `block` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn virolai_block_len(block: *const VirolaiBlock) -> usize {
+    guard(0, || block.as_ref().map_or(0, |b| b.block.tasks().count()))
+}
+
+/// Creates an empty solution space. Free it with [`virolai_space_free`].
+#[no_mangle]
+pub extern "C" fn virolai_space_new() -> *mut VirolaiSpace {
+    guard(ptr::null_mut(), || {
+        Box::into_raw(Box::new(VirolaiSpace {
+            space: SolutionSpace::new(),
+        }))
+    })
+}
+
+/// Frees a solution space. Null is ignored.
+///
+/// # Safety
+///
+/// `space` must be null or a live handle from [`virolai_space_new`].
+#[no_mangle]
+pub unsafe extern "C" fn virolai_space_free(space: *mut VirolaiSpace) {
+    guard((), || {
+        if !space.is_null() {
+            drop(Box::from_raw(space));
+        }
+    })
+}
+
+/// Allows task `id` within `[start, end)`, merged with its other windows.
+///
+/// # Safety
+///
+/// `space` must be a live handle and `id` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn virolai_space_add_interval(
+    space: *mut VirolaiSpace,
+    id: *const c_char,
+    start: f64,
+    end: f64,
+) -> VirolaiStatus {
+    guard(VirolaiStatus::Panic, || {
+        let Some(space) = space.as_mut() else {
+            return fail(VirolaiStatus::NullPointer, "space is null");
+        };
+        let (id, window) = match (read_str(id, "task ID"), interval(start, end)) {
+            (Ok(id), Ok(window)) => (id, window),
+            (Err(status), _) | (_, Err(status)) => return status,
+        };
+        space.space.add_interval(id, window);
+        VirolaiStatus::Ok
+    })
+}
+
+/// Schedules `block` with EST within `[horizon_start, horizon_end)`, tasks
+/// with less than `endangered_threshold` seconds of flexibility counting as
+/// endangered.
+///
+/// Returns a schedule to free with [`virolai_schedule_free`], or null on
+/// invalid arguments, including an empty or infinite horizon (see
+/// [`virolai_last_error`]). Tasks EST cannot place are left out of the
+/// schedule.
+///
+/// # Safety
+///
+/// `block` and `space` must be live handles.
+#[no_mangle]
+pub unsafe extern "C" fn virolai_est_schedule(
+    block: *const VirolaiBlock,
+    space: *const VirolaiSpace,
+    horizon_start: f64,
+    horizon_end: f64,
+    endangered_threshold: u32,
+) -> *mut VirolaiSchedule {
+    guard(ptr::null_mut(), || {
+        let (Some(block), Some(space)) = (block.as_ref(), space.as_ref()) else {
+            fail(VirolaiStatus::NullPointer, "block or space is null");
+            return ptr::null_mut();
+        };
+        let Ok(horizon) = horizon(horizon_start, horizon_end) else {
+            return ptr::null_mut();
+        };
+        let threshold = Quantity::new(f64::from(endangered_threshold));
+        let schedule = ESTScheduler::new(threshold).schedule(
+            std::slice::from_ref(&block.block),
+            &space.space,
+            horizon,
+        );
+        Box::into_raw(Box::new(VirolaiSchedule::new(&schedule)))
+    })
+}
+
+/// Frees a schedule. Null is ignored.
+///
+/// # Safety
+///
+/// `schedule` must be null or a live handle from [`virolai_est_schedule`].
+#[no_mangle]
+pub unsafe extern "C" fn virolai_schedule_free(schedule: *mut VirolaiSchedule) {
+    guard((), || {
+        if !schedule.is_null() {
+            drop(Box::from_raw(schedule));
+        }
+    })
+}
+
+/// Returns the number of scheduled tasks, 0 for null.
+///
+/// # Safety
+///
+/// `schedule` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn virolai_schedule_len(schedule: *const VirolaiSchedule) -> usize {
+    guard(0, || schedule.as_ref().map_or(0, |s| s.entries.len()))
+}
+
+/// Writes the `index`-th scheduled task to `out`, in the order described on
+/// [`VirolaiSchedule`].
+///
+/// # Safety
+///
+/// `schedule` must be a live handle and `out` point to writable memory for
+/// one [`VirolaiEntry`].
+#[no_mangle]
+pub unsafe extern "C" fn virolai_schedule_entry(
+    schedule: *const VirolaiSchedule,
+    index: usize,
+    out: *mut VirolaiEntry,
+) -> VirolaiStatus {
+    guard(VirolaiStatus::Panic, || {
+        let (Some(schedule), false) = (schedule.as_ref(), out.is_null()) else {
+            return fail(VirolaiStatus::NullPointer, "schedule or output is null");
+        };
+        let Some((id, interval, background)) = schedule.entries.get(index) else {
+            return fail(
+                VirolaiStatus::NotFound,
+                format!(
+                    "no entry {index} in a schedule of {}",
+                    schedule.entries.len()
+                ),
+            );
+        };
+        out.write(VirolaiEntry {
+            task_id: id.as_ptr(),
+            start: interval.start().value(),
+            end: interval.end().value(),
+            background: *background,
+        });
+        VirolaiStatus::Ok
+    })
+}
+
+/// Writes the interval of task `id` to `start` and `end`.
+///
+/// # Safety
+///
+/// `schedule` must be a live handle, `id` a NUL-terminated string and
+/// `start` and `end` point to writable doubles.
+#[no_mangle]
+pub unsafe extern "C" fn virolai_schedule_interval_of(
+    schedule: *const VirolaiSchedule,
+    id: *const c_char,
+    start: *mut f64,
+    end: *mut f64,
+) -> VirolaiStatus {
+    guard(VirolaiStatus::Panic, || {
+        let Some(schedule) = schedule.as_ref() else {
+            return fail(VirolaiStatus::NullPointer, "schedule is null");
+        };
+        if start.is_null() || end.is_null() {
+            return fail(VirolaiStatus::NullPointer, "output is null");
+        }
+        let id = match read_str(id, "task ID") {
+            Ok(id) => id,
+            Err(status) => return status,
+        };
+        match schedule
+            .entries
+            .iter()
+            .find(|(entry, _, _)| entry.as_bytes() == id.as_bytes())
+        {
+            Some((_, interval, _)) => {
+                start.write(interval.start().value());
+                end.write(interval.end().value());
+                VirolaiStatus::Ok
+            }
+            None => fail(
+                VirolaiStatus::NotFound,
+                format!("task {id} is not scheduled"),
+            ),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    #[test]
+    fn schedules_through_the_c_abi() {
+        unsafe {
+            let block = virolai_block_new();
+            let space = virolai_space_new();
+            for (id, size, priority) in [("a", 10.0, 1), ("b", 10.0, 5)] {
+                let id = c(id);
+                assert_eq!(
+                    virolai_block_add_task(block, id.as_ptr(), size, priority),
+                    VirolaiStatus::Ok
+                );
+                assert_eq!(
+                    virolai_space_add_interval(space, id.as_ptr(), 0.0, 100.0),
+                    VirolaiStatus::Ok
+                );
+            }
+            assert_eq!(
+                virolai_block_add_task(block, c("a").as_ptr(), 1.0, 0),
+                VirolaiStatus::DuplicateId
+            );
+            assert_eq!(
+                virolai_block_add_task(block, ptr::null(), 1.0, 0),
+                VirolaiStatus::NullPointer
+            );
+            assert_eq!(
+                CStr::from_ptr(virolai_last_error()).to_str().unwrap(),
+                "task ID is null"
+            );
+            assert_eq!(
+                virolai_block_add_dependency(block, c("a").as_ptr(), c("x").as_ptr()),
+                VirolaiStatus::NotFound
+            );
+            assert_eq!(
+                virolai_space_add_interval(space, c("a").as_ptr(), 5.0, 1.0),
+                VirolaiStatus::InvalidArgument
+            );
+            assert_eq!(virolai_block_len(block), 2);
+
+            let schedule = virolai_est_schedule(block, space, 0.0, 100.0, 1);
+            assert!(!schedule.is_null());
+            assert_eq!(virolai_schedule_len(schedule), 2);
+            let mut entry = VirolaiEntry {
+                task_id: ptr::null(),
+                start: 0.0,
+                end: 0.0,
+                background: true,
+            };
+            assert_eq!(
+                virolai_schedule_entry(schedule, 0, &mut entry),
+                VirolaiStatus::Ok
+            );
+            assert_eq!(CStr::from_ptr(entry.task_id).to_str().unwrap(), "b");
+            assert_eq!((entry.start, entry.end), (0.0, 10.0));
+            assert!(!entry.background);
+            assert_eq!(
+                virolai_schedule_entry(schedule, 2, &mut entry),
+                VirolaiStatus::NotFound
+            );
+            let (mut start, mut end) = (0.0, 0.0);
+            assert_eq!(
+                virolai_schedule_interval_of(schedule, c("a").as_ptr(), &mut start, &mut end),
+                VirolaiStatus::Ok
+            );
+            assert_eq!((start, end), (10.0, 20.0));
+
+            assert!(virolai_est_schedule(block, space, 5.0, 0.0, 1).is_null());
+            assert!(virolai_est_schedule(ptr::null(), space, 0.0, 1.0, 1).is_null());
+
+            virolai_schedule_free(schedule);
+            virolai_space_free(space);
+            virolai_block_free(block);
+            virolai_block_free(ptr::null_mut());
+        }
+        let version = unsafe { CStr::from_ptr(virolai_version()) };
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn background_entries_are_flagged() {
+        let mut schedule = Schedule::new();
+        schedule
+            .add("science", Interval::from_f64(10.0, 20.0))
+            .unwrap();
+        schedule
+            .add_background("monitor", Interval::from_f64(0.0, 100.0))
+            .unwrap();
+        let schedule = Box::into_raw(Box::new(VirolaiSchedule::new(&schedule)));
+        unsafe {
+            assert_eq!(virolai_schedule_len(schedule), 2);
+            let mut entry = VirolaiEntry {
+                task_id: ptr::null(),
+                start: 0.0,
+                end: 0.0,
+                background: false,
+            };
+            assert_eq!(
+                virolai_schedule_entry(schedule, 1, &mut entry),
+                VirolaiStatus::Ok
+            );
+            assert_eq!(CStr::from_ptr(entry.task_id).to_str().unwrap(), "monitor");
+            assert_eq!((entry.start, entry.end), (0.0, 100.0));
+            assert!(entry.background);
+            virolai_schedule_free(schedule);
+        }
+    }
+
+    #[test]
+    fn checked_in_header_is_current() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/virolai.h"));
+        let checked_in = include_str!("../include/virolai.h");
+        assert!(
+            generated == checked_in,
+            "include/virolai.h is stale; rebuild with VIROLAI_WRITE_HEADER=1"
+        );
+    }
+
+    #[test]
+    fn panics_become_a_status() {
+        let status = guard(VirolaiStatus::Panic, || -> VirolaiStatus { panic!("boom") });
+        assert_eq!(status, VirolaiStatus::Panic);
+        let message = unsafe { CStr::from_ptr(virolai_last_error()) };
+        assert_eq!(message.to_str().unwrap(), "panic: boom");
+        assert!(guard(ptr::null_mut(), || -> *mut VirolaiBlock { panic!() }).is_null());
+    }
+}
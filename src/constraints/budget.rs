@@ -0,0 +1,289 @@
+//! Complexity budgets for constraint-tree evaluation.
+//!
+//! A pathological user constraint (very deep trees, leaves producing millions
+//! of intervals) can stall population of the whole solution space. An
+//! [`EvaluationBudget`] caps a single tree evaluation by nodes visited,
+//! intervals produced and wall-clock time. Evaluation stops with
+//! [`ConstraintError::BudgetExceeded`] as soon as a limit is crossed.
+//!
+//! Limits are checked after each node is evaluated; a single leaf's
+//! `compute_intervals` call is never interrupted.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use super::error::ConstraintError;
+use super::hard::Constraint;
+use super::node::ConstraintExpr;
+use crate::solution_space::{Interval, IntervalSet};
+use qtty::Unit;
+
+/// The budget limit that was exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetLimit {
+    /// More than this many nodes were visited.
+    Nodes(usize),
+    /// A node produced more than this many intervals.
+    Intervals(usize),
+    /// Evaluation took longer than this.
+    Time(Duration),
+}
+
+impl fmt::Display for BudgetLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nodes(n) => write!(f, "more than {n} nodes visited"),
+            Self::Intervals(n) => write!(f, "more than {n} intervals produced"),
+            Self::Time(d) => write!(f, "took longer than {d:?}"),
+        }
+    }
+}
+
+/// Limits applied to a single constraint-tree evaluation.
+///
+/// All limits are disabled by default.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use virolai::constraints::EvaluationBudget;
+/// # use qtty::Second;
+/// # use virolai::constraints::{ConstraintExpr, IntervalConstraint};
+/// # use virolai::solution_space::Interval;
+/// # let range = Interval::<Second>::from_f64(0.0, 86_400.0);
+/// # let tree = ConstraintExpr::leaf(IntervalConstraint::new(Interval::from_f64(0.0, 43_200.0)));
+///
+/// let budget = EvaluationBudget::new()
+///     .with_max_nodes(10_000)
+///     .with_max_intervals(100_000)
+///     .with_time_limit(Duration::from_millis(50));
+/// let windows = tree.compute_intervals_budgeted(range, &budget)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvaluationBudget {
+    /// Maximum number of tree nodes visited.
+    pub max_nodes: Option<usize>,
+    /// Maximum number of intervals any node may produce.
+    pub max_intervals: Option<usize>,
+    /// Maximum wall-clock time for the whole evaluation.
+    pub time_limit: Option<Duration>,
+}
+
+impl EvaluationBudget {
+    /// Creates an unlimited budget.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the number of nodes visited.
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Limits the number of intervals any node may produce.
+    pub fn with_max_intervals(mut self, max_intervals: usize) -> Self {
+        self.max_intervals = Some(max_intervals);
+        self
+    }
+
+    /// Limits the wall-clock time of one evaluation.
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Returns true if no limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_nodes.is_none() && self.max_intervals.is_none() && self.time_limit.is_none()
+    }
+}
+
+/// Running counters for one budgeted evaluation.
+struct Meter<'a> {
+    budget: &'a EvaluationBudget,
+    started: Instant,
+    nodes: usize,
+}
+
+impl Meter<'_> {
+    fn check<U: Unit>(&mut self, produced: &IntervalSet<U>) -> Result<(), ConstraintError> {
+        self.nodes += 1;
+        let exceeded = |limit| Err(ConstraintError::BudgetExceeded(limit));
+
+        if let Some(max) = self.budget.max_nodes {
+            if self.nodes > max {
+                return exceeded(BudgetLimit::Nodes(max));
+            }
+        }
+        if let Some(max) = self.budget.max_intervals {
+            if produced.len() > max {
+                return exceeded(BudgetLimit::Intervals(max));
+            }
+        }
+        if let Some(limit) = self.budget.time_limit {
+            if self.started.elapsed() > limit {
+                return exceeded(BudgetLimit::Time(limit));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<C> ConstraintExpr<C> {
+    /// Computes the tree's intervals within `range`, stopping once `budget`
+    /// is exceeded.
+    ///
+    /// Produces the same result as
+    /// [`Constraint::compute_intervals`] when the budget is not exceeded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConstraintError::BudgetExceeded`] naming the limit that was crossed.
+    pub fn compute_intervals_budgeted<U>(
+        &self,
+        range: Interval<U>,
+        budget: &EvaluationBudget,
+    ) -> Result<IntervalSet<U>, ConstraintError>
+    where
+        U: Unit,
+        C: Constraint<U>,
+    {
+        let mut meter = Meter {
+            budget,
+            started: Instant::now(),
+            nodes: 0,
+        };
+        self.evaluate_metered(range, &mut meter)
+    }
+
+    fn evaluate_metered<U>(
+        &self,
+        range: Interval<U>,
+        meter: &mut Meter<'_>,
+    ) -> Result<IntervalSet<U>, ConstraintError>
+    where
+        U: Unit,
+        C: Constraint<U>,
+    {
+        let result = match self {
+            ConstraintExpr::Leaf(constraint) => constraint.compute_intervals(range),
+            ConstraintExpr::Not { child, .. } => super::operations::compute_complement(
+                child.evaluate_metered(range, meter)?.into_inner(),
+                range,
+            ),
+            ConstraintExpr::Intersection { children, .. } => {
+                let mut acc: Option<IntervalSet<U>> = None;
+                for child in children {
+                    let v = child.evaluate_metered(range, meter)?;
+                    acc = Some(match acc {
+                        Some(acc) => super::operations::compute_intersection(&acc, &v),
+                        None => v,
+                    });
+                }
+                acc.unwrap_or_default()
+            }
+            ConstraintExpr::Union { children, .. } => {
+                let mut acc = IntervalSet::new();
+                for child in children {
+                    let v = child.evaluate_metered(range, meter)?;
+                    acc = super::operations::compute_union(&acc, &v);
+                }
+                acc
+            }
+        };
+        meter.check(&result)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::IntervalConstraint;
+    use crate::test_utils::iv;
+    use qtty::Second;
+
+    fn leaf(start: f64, end: f64) -> ConstraintExpr<IntervalConstraint<Second>> {
+        ConstraintExpr::leaf(IntervalConstraint::new(iv(start, end)))
+    }
+
+    fn comb(n: usize) -> ConstraintExpr<IntervalConstraint<Second>> {
+        ConstraintExpr::union(
+            (0..n)
+                .map(|i| leaf(i as f64 * 10.0, i as f64 * 10.0 + 5.0))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn unlimited_matches_plain_evaluation() {
+        let tree = ConstraintExpr::intersection(vec![
+            leaf(0.0, 50.0),
+            ConstraintExpr::negate(leaf(10.0, 20.0)),
+        ]);
+        let plain = tree.compute_intervals(iv(0.0, 100.0));
+        let budgeted = tree
+            .compute_intervals_budgeted(iv(0.0, 100.0), &EvaluationBudget::new())
+            .unwrap();
+        assert_eq!(plain, budgeted);
+    }
+
+    #[test]
+    fn node_limit_exceeded() {
+        let budget = EvaluationBudget::new().with_max_nodes(3);
+        let err = comb(5)
+            .compute_intervals_budgeted(iv(0.0, 100.0), &budget)
+            .unwrap_err();
+        assert_eq!(err, ConstraintError::BudgetExceeded(BudgetLimit::Nodes(3)));
+    }
+
+    #[test]
+    fn interval_limit_exceeded() {
+        let budget = EvaluationBudget::new().with_max_intervals(4);
+        let err = comb(5)
+            .compute_intervals_budgeted(iv(0.0, 100.0), &budget)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ConstraintError::BudgetExceeded(BudgetLimit::Intervals(4))
+        );
+    }
+
+    #[test]
+    fn time_limit_exceeded() {
+        let budget = EvaluationBudget::new().with_time_limit(Duration::ZERO);
+        let err = comb(2)
+            .compute_intervals_budgeted(iv(0.0, 100.0), &budget)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ConstraintError::BudgetExceeded(BudgetLimit::Time(_))
+        ));
+    }
+
+    #[test]
+    fn within_budget_succeeds() {
+        let budget = EvaluationBudget::new()
+            .with_max_nodes(6)
+            .with_max_intervals(5);
+        let result = comb(5)
+            .compute_intervals_budgeted(iv(0.0, 100.0), &budget)
+            .unwrap();
+        assert_eq!(result.len(), 5);
+    }
+
+    #[test]
+    fn budget_limit_display() {
+        assert_eq!(
+            BudgetLimit::Nodes(10).to_string(),
+            "more than 10 nodes visited"
+        );
+        assert_eq!(
+            BudgetLimit::Intervals(3).to_string(),
+            "more than 3 intervals produced"
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use super::budget::BudgetLimit;
+
 /// Errors that can occur during constraint tree operations.
 #[derive(Debug, Error, Clone, PartialEq, Eq)]
 pub enum ConstraintError {
@@ -8,6 +10,9 @@ pub enum ConstraintError {
 
     #[error("Cannot add child to a NOT node")]
     CannotAddChildToNot,
+
+    #[error("Constraint evaluation budget exceeded: {0}")]
+    BudgetExceeded(BudgetLimit),
 }
 
 #[cfg(test)]
@@ -26,6 +31,15 @@ mod tests {
         assert_eq!(e.to_string(), "Cannot add child to a NOT node");
     }
 
+    #[test]
+    fn budget_exceeded_display() {
+        let e = ConstraintError::BudgetExceeded(BudgetLimit::Nodes(5));
+        assert_eq!(
+            e.to_string(),
+            "Constraint evaluation budget exceeded: more than 5 nodes visited"
+        );
+    }
+
     #[test]
     fn error_equality() {
         assert_eq!(
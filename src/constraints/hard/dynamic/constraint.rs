@@ -8,9 +8,9 @@
 //! See also: [`Constraint`](crate::constraints::Constraint) for the static
 //! counterpart whose windows are fixed before the scheduling loop.
 
-use crate::schedule::Schedule;
+use crate::schedule::{Schedule, ScheduleSnapshot};
 use crate::solution_space::{Interval, IntervalSet, SolutionSpace};
-use qtty::Unit;
+use qtty::{Quantity, Unit};
 use std::fmt::Debug;
 
 /// Runtime state available to dynamic constraints during evaluation.
@@ -20,12 +20,25 @@ use std::fmt::Debug;
 ///
 /// All references are immutable borrows — dynamic constraints **read** state
 /// but never mutate it.
+///
+/// A context built [from a snapshot](Self::from_snapshot) describes a
+/// hypothetical schedule. Constraints should look tasks up through
+/// [`interval_of`](Self::interval_of) and [`is_scheduled`](Self::is_scheduled),
+/// which see the snapshot's changes, rather than through `schedule`, which
+/// is the snapshot's base.
 #[derive(Debug)]
 pub struct SchedulingContext<'a, U: Unit> {
-    /// Current partial schedule (tasks already placed).
+    /// Current partial schedule (tasks already placed); the base of the
+    /// snapshot, if any.
     pub schedule: &'a Schedule<U>,
+    /// Hypothetical changes to `schedule` the context describes.
+    pub snapshot: Option<&'a ScheduleSnapshot<'a, U>>,
     /// Static solution space (pre-computed from static constraints).
     pub solution_space: &'a SolutionSpace<U>,
+    /// Size of the task being evaluated, if known. Constraints bounding the
+    /// task's start, such as [`MaxWaitConstraint`](super::MaxWaitConstraint),
+    /// need it to bound the time the task occupies.
+    pub task_size: Option<Quantity<U>>,
 }
 
 impl<'a, U: Unit> SchedulingContext<'a, U> {
@@ -33,9 +46,45 @@ impl<'a, U: Unit> SchedulingContext<'a, U> {
     pub fn new(schedule: &'a Schedule<U>, solution_space: &'a SolutionSpace<U>) -> Self {
         Self {
             schedule,
+            snapshot: None,
             solution_space,
+            task_size: None,
+        }
+    }
+
+    /// Creates a context describing a hypothetical schedule, without
+    /// copying its base.
+    pub fn from_snapshot(
+        snapshot: &'a ScheduleSnapshot<'a, U>,
+        solution_space: &'a SolutionSpace<U>,
+    ) -> Self {
+        Self {
+            snapshot: Some(snapshot),
+            ..Self::new(snapshot.base(), solution_space)
+        }
+    }
+
+    /// The interval of `task_id` in the schedule the context describes.
+    pub fn interval_of(&self, task_id: &str) -> Option<Interval<U>> {
+        match self.snapshot {
+            Some(snapshot) => snapshot.get_interval(task_id),
+            None => self.schedule.get_interval(task_id),
+        }
+    }
+
+    /// Returns true if `task_id` is in the schedule the context describes.
+    pub fn is_scheduled(&self, task_id: &str) -> bool {
+        match self.snapshot {
+            Some(snapshot) => snapshot.contains_task(task_id),
+            None => self.schedule.contains_task(task_id),
         }
     }
+
+    /// Sets the size of the task being evaluated.
+    pub fn with_task_size(mut self, size: Quantity<U>) -> Self {
+        self.task_size = Some(size);
+        self
+    }
 }
 
 /// Computes intervals where a dynamic scheduling condition is satisfied.
@@ -65,6 +114,15 @@ pub trait DynamicConstraint<U: Unit>: Send + Sync + Debug {
     /// Returns a human-readable description of this constraint.
     fn stringify(&self) -> String;
 
+    /// Longest the target may wait between the end of the reference task
+    /// and its own start, if bounded.
+    ///
+    /// Schedulers that advance a cursor past placed tasks use it to avoid
+    /// skipping over the target's last allowed start.
+    fn max_wait(&self) -> Option<Quantity<U>> {
+        None
+    }
+
     /// Prints this constraint to stdout.
     fn print(&self) {
         println!("{}", self.stringify());
@@ -84,4 +142,23 @@ mod tests {
         assert!(ctx.schedule.is_empty());
         assert!(ctx.solution_space.is_empty());
     }
+
+    #[test]
+    fn snapshot_context_sees_hypothetical_placements() {
+        let mut schedule = Schedule::<Second>::new();
+        schedule.add("a", Interval::from_f64(0.0, 10.0)).unwrap();
+        let solution_space = SolutionSpace::<Second>::new();
+
+        let mut what_if = ScheduleSnapshot::new(&schedule);
+        what_if.unplace("a");
+        what_if.place("b", Interval::from_f64(10.0, 20.0)).unwrap();
+        let ctx = SchedulingContext::from_snapshot(&what_if, &solution_space);
+        assert!(!ctx.is_scheduled("a"));
+        assert_eq!(ctx.interval_of("b"), Some(Interval::from_f64(10.0, 20.0)));
+        // The live schedule is untouched.
+        assert!(ctx.schedule.contains_task("a"));
+
+        let live = SchedulingContext::new(&schedule, &solution_space);
+        assert!(live.is_scheduled("a") && live.interval_of("b").is_none());
+    }
 }
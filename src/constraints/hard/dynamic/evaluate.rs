@@ -21,7 +21,9 @@
 //! [`evaluate()`](DynamicConstraintIndex::evaluate) or
 //! [`compute_effective_intervals()`](DynamicConstraintIndex::compute_effective_intervals)
 //! to obtain the combined valid intervals for a task after all dynamic
-//! constraints are applied.
+//! constraints are applied, or
+//! [`evaluate_many()`](DynamicConstraintIndex::evaluate_many) to evaluate a
+//! whole candidate list against one context at once.
 
 use super::constraint::{DynamicConstraint, SchedulingContext};
 use crate::scheduling_block::{SchedulingBlock, Task};
@@ -98,6 +100,16 @@ impl<'a, D> DynamicConstraintIndex<'a, D> {
     pub fn get_edges(&self, task_id: &str) -> Option<&[(Id, &'a D)]> {
         self.edges.get(task_id).map(|v| v.as_slice())
     }
+
+    /// Every indexed edge as `(target_id, source_id, &constraint)`, in no
+    /// particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, &'a D)> + '_ {
+        self.edges.iter().flat_map(|(target, incoming)| {
+            incoming
+                .iter()
+                .map(move |(source, constraint)| (target.as_str(), source.as_str(), *constraint))
+        })
+    }
 }
 
 impl<'a, D> DynamicConstraintIndex<'a, D> {
@@ -107,6 +119,10 @@ impl<'a, D> DynamicConstraintIndex<'a, D> {
     /// Returns `None` if `task_id` has no dynamic constraints — this lets the
     /// caller skip unnecessary intersection with the static solution space.
     ///
+    /// Constraints bounding the task's start, such as
+    /// [`MaxWaitConstraint`](super::MaxWaitConstraint), read the task's size
+    /// from [`SchedulingContext::task_size`].
+    ///
     /// # Complexity
     ///
     /// O(k · C) where k = number of incoming edges and C = cost of a single
@@ -135,6 +151,64 @@ impl<'a, D> DynamicConstraintIndex<'a, D> {
         Some(result)
     }
 
+    /// Evaluates the dynamic constraints of every task in `task_ids` against
+    /// the same context, as [`evaluate`](Self::evaluate) would one by one.
+    ///
+    /// Edges with the same source and equal constraints give the same
+    /// intervals, so each is computed once and shared: a hundred candidates
+    /// waiting on one predecessor look its interval up once. Tasks without
+    /// dynamic constraints are left out of the map.
+    ///
+    /// `ctx` applies to every task, its [`task_size`](SchedulingContext::task_size)
+    /// included; evaluate tasks of different sizes in separate batches when
+    /// a constraint reads it.
+    ///
+    /// # Complexity
+    ///
+    /// O(k · e) comparisons for k edges and at most e distinct constraints
+    /// per source, plus one evaluation per distinct `(source, constraint)`.
+    pub fn evaluate_many<'t, U, I>(
+        &self,
+        task_ids: I,
+        range: Interval<U>,
+        ctx: &SchedulingContext<U>,
+    ) -> HashMap<Id, IntervalSet<U>>
+    where
+        D: DynamicConstraint<U> + PartialEq,
+        U: Unit,
+        I: IntoIterator<Item = &'t str>,
+    {
+        let mut shared: HashMap<&str, Vec<(&'a D, IntervalSet<U>)>> = HashMap::new();
+        let mut results = HashMap::new();
+
+        for task_id in task_ids {
+            let Some(incoming) = self.edges.get(task_id).filter(|e| !e.is_empty()) else {
+                continue;
+            };
+            let mut result: Option<IntervalSet<U>> = None;
+            for (source_id, constraint) in incoming {
+                let computed = shared.entry(source_id.as_str()).or_default();
+                let index = match computed.iter().position(|(c, _)| *c == *constraint) {
+                    Some(index) => index,
+                    None => {
+                        let intervals = constraint.compute_intervals(range, source_id, ctx);
+                        computed.push((*constraint, intervals));
+                        computed.len() - 1
+                    }
+                };
+                let intervals = &computed[index].1;
+                result = Some(match result {
+                    Some(acc) => {
+                        crate::constraints::operations::compute_intersection(&acc, intervals)
+                    }
+                    None => intervals.clone(),
+                });
+            }
+            results.insert(task_id.to_string(), result.unwrap_or_default());
+        }
+        results
+    }
+
     /// Computes the **effective** intervals for a task by intersecting the
     /// static solution space intervals with the dynamic constraint overlay.
     ///
@@ -359,6 +433,66 @@ mod tests {
         assert_eq!(result[0], iv(30.0, 100.0));
     }
 
+    #[test]
+    fn evaluate_many_shares_common_sources() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        /// `Consecutive`, counting its evaluations.
+        #[derive(Debug, PartialEq)]
+        struct Counted;
+
+        impl DynamicConstraint<Second> for Counted {
+            fn compute_intervals(
+                &self,
+                range: Interval<Second>,
+                ref_task_id: &str,
+                ctx: &SchedulingContext<Second>,
+            ) -> IntervalSet<Second> {
+                CALLS.fetch_add(1, Ordering::Relaxed);
+                DynConstraintKind::Consecutive.compute_intervals(range, ref_task_id, ctx)
+            }
+
+            fn stringify(&self) -> String {
+                "Counted".into()
+            }
+        }
+
+        // A precedes B, C and D; C also precedes D.
+        let mut block: SchedulingBlock<TestTask, Second, Counted> = SchedulingBlock::new();
+        let ids: Vec<Id> = ["A", "B", "C", "D"]
+            .into_iter()
+            .map(|name| block.add_task(TestTask::new(name, 10.0)))
+            .collect();
+        for (from, to) in [(0, 1), (0, 2), (0, 3), (2, 3)] {
+            let from = block.node_of(&ids[from]).unwrap();
+            let to = block.node_of(&ids[to]).unwrap();
+            block.add_dependency(from, to, Counted).unwrap();
+        }
+        let blocks = vec![block];
+        let index = DynamicConstraintIndex::from_blocks(&blocks);
+
+        let mut schedule = Schedule::new();
+        schedule.add(&ids[0], iv(0.0, 10.0)).unwrap();
+        schedule.add(&ids[2], iv(20.0, 30.0)).unwrap();
+        let ss = SolutionSpace::new();
+        let ctx = SchedulingContext::new(&schedule, &ss);
+
+        let targets = ids.iter().map(Id::as_str);
+        let results = index.evaluate_many(targets, iv(0.0, 100.0), &ctx);
+        assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+        assert_eq!(results.len(), 3);
+        assert!(!results.contains_key(&ids[0]));
+        for id in &ids[1..] {
+            assert_eq!(
+                Some(&results[id]),
+                index.evaluate(id, iv(0.0, 100.0), &ctx).as_ref()
+            );
+        }
+        assert_eq!(results[&ids[3]].as_slice(), [iv(30.0, 100.0)]);
+    }
+
     // ── compute_effective_intervals ───────────────────────────────────
 
     #[test]
@@ -74,7 +74,7 @@ impl<U: Unit> DynamicConstraint<U> for DynConstraintKind {
     ) -> IntervalSet<U> {
         match self {
             Self::Dependence => {
-                if ctx.schedule.contains_task(ref_task_id) {
+                if ctx.is_scheduled(ref_task_id) {
                     IntervalSet::from(range)
                 } else {
                     IntervalSet::new()
@@ -82,8 +82,7 @@ impl<U: Unit> DynamicConstraint<U> for DynConstraintKind {
             }
 
             Self::Consecutive => ctx
-                .schedule
-                .get_interval(ref_task_id)
+                .interval_of(ref_task_id)
                 .and_then(|ref_interval| {
                     let start = range.start().max(ref_interval.end());
                     (start < range.end()).then(|| Interval::new(start, range.end()))
@@ -91,7 +90,7 @@ impl<U: Unit> DynamicConstraint<U> for DynConstraintKind {
                 .map_or_else(IntervalSet::new, IntervalSet::from),
 
             Self::Exclusive => {
-                if !ctx.schedule.contains_task(ref_task_id) {
+                if !ctx.is_scheduled(ref_task_id) {
                     IntervalSet::from(range)
                 } else {
                     IntervalSet::new()
@@ -0,0 +1,156 @@
+//! Bounded wait after a predecessor.
+//!
+//! [`MaxWaitConstraint`] chains a task to its reference task: it may start
+//! no earlier than the reference ends and no later than `max_wait` after.
+//! A zero wait gives no-wait chains, such as an exposure that must follow
+//! its acquisition immediately.
+
+use super::constraint::{DynamicConstraint, SchedulingContext};
+use crate::solution_space::{Interval, IntervalSet};
+use qtty::{Quantity, Unit};
+
+/// Target starts within `max_wait` of the reference task's end.
+///
+/// Implies [`Consecutive`](super::DynConstraintKind::Consecutive): if the
+/// reference task is absent, the target cannot be scheduled.
+///
+/// - Reference task scheduled at `[a_start, a_end)` → valid window is
+///   `[a_end, a_end + max_wait + size)` within `range`, `size` being
+///   [`SchedulingContext::task_size`]
+/// - Reference task absent → empty
+///
+/// Without a task size in the context only the lower bound applies, since
+/// the window the task may occupy cannot be bounded.
+///
+/// # Example
+///
+/// ```no_run
+/// # use qtty::{Quantity, Second};
+/// # use virolai::constraints::{IntervalConstraint, MaxWaitConstraint};
+/// # use virolai::scheduling_block::{SchedulingBlock, Task};
+/// # #[derive(Debug)]
+/// # struct Obs(&'static str);
+/// # impl Task<Second> for Obs {
+/// #     type SizeUnit = Second;
+/// #     type ConstraintLeaf = IntervalConstraint<Second>;
+/// #     fn name(&self) -> &str { self.0 }
+/// #     fn size(&self) -> Quantity<Second> { Quantity::new(60.0) }
+/// # }
+/// # let mut block: SchedulingBlock<Obs, Second, MaxWaitConstraint<Second>> = SchedulingBlock::new();
+/// # let acquisition = block.add_task(Obs("acquisition"));
+/// # let acquisition = block.node_of(&acquisition).unwrap();
+/// # let exposure = block.add_task(Obs("exposure"));
+/// # let exposure = block.node_of(&exposure).unwrap();
+/// // The exposure starts when the acquisition ends, or at most 30 s later.
+/// block.add_dependency(acquisition, exposure, MaxWaitConstraint::new(Quantity::new(30.0)))?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaxWaitConstraint<U: Unit + Send + Sync> {
+    max_wait: Quantity<U>,
+}
+
+impl<U: Unit + Send + Sync> MaxWaitConstraint<U> {
+    /// Creates a constraint allowing at most `max_wait` between the
+    /// reference task's end and the target's start.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_wait` is negative or NaN.
+    pub fn new(max_wait: Quantity<U>) -> Self {
+        assert!(
+            max_wait.value() >= 0.0,
+            "maximum wait must be non-negative, got {}",
+            max_wait.value()
+        );
+        Self { max_wait }
+    }
+
+    /// A constraint requiring the target to start exactly when the
+    /// reference task ends.
+    pub fn no_wait() -> Self {
+        Self::new(Quantity::new(0.0))
+    }
+
+    /// The longest allowed wait.
+    pub fn max_wait(&self) -> Quantity<U> {
+        self.max_wait
+    }
+}
+
+impl<U: Unit + Send + Sync> DynamicConstraint<U> for MaxWaitConstraint<U> {
+    fn compute_intervals(
+        &self,
+        range: Interval<U>,
+        ref_task_id: &str,
+        ctx: &SchedulingContext<U>,
+    ) -> IntervalSet<U> {
+        let Some(reference) = ctx.interval_of(ref_task_id) else {
+            return IntervalSet::new();
+        };
+        let start = range.start().max(reference.end());
+        let end = match ctx.task_size {
+            Some(size) => range.end().min(reference.end() + self.max_wait + size),
+            None => range.end(),
+        };
+        if start < end {
+            IntervalSet::from(Interval::new(start, end))
+        } else {
+            IntervalSet::new()
+        }
+    }
+
+    fn stringify(&self) -> String {
+        format!("MaxWait({})", self.max_wait.value())
+    }
+
+    fn max_wait(&self) -> Option<Quantity<U>> {
+        Some(self.max_wait)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::Schedule;
+    use crate::solution_space::SolutionSpace;
+    use crate::test_utils::{iv, q};
+
+    #[test]
+    fn window_spans_the_allowed_wait_plus_the_task() {
+        let mut schedule = Schedule::new();
+        schedule.add("acquire", iv(10.0, 30.0)).unwrap();
+        let ss = SolutionSpace::new();
+        let ctx = SchedulingContext::new(&schedule, &ss).with_task_size(q(15.0));
+
+        let wait = MaxWaitConstraint::new(q(5.0));
+        let result = wait.compute_intervals(iv(0.0, 100.0), "acquire", &ctx);
+        assert_eq!(result.as_slice(), [iv(30.0, 50.0)]);
+
+        // No wait: exactly the task's own span after the reference.
+        let result =
+            MaxWaitConstraint::no_wait().compute_intervals(iv(0.0, 100.0), "acquire", &ctx);
+        assert_eq!(result.as_slice(), [iv(30.0, 45.0)]);
+
+        // Clipped to the range, empty once the range starts too late.
+        let result = wait.compute_intervals(iv(40.0, 100.0), "acquire", &ctx);
+        assert_eq!(result.as_slice(), [iv(40.0, 50.0)]);
+        assert!(wait
+            .compute_intervals(iv(50.0, 100.0), "acquire", &ctx)
+            .is_empty());
+
+        // Without a size only the lower bound applies.
+        let ctx = SchedulingContext::new(&schedule, &ss);
+        let result = wait.compute_intervals(iv(0.0, 100.0), "acquire", &ctx);
+        assert_eq!(result.as_slice(), [iv(30.0, 100.0)]);
+
+        assert!(wait
+            .compute_intervals(iv(0.0, 100.0), "missing", &ctx)
+            .is_empty());
+        assert_eq!(
+            DynamicConstraint::<qtty::Second>::max_wait(&wait),
+            Some(q(5.0))
+        );
+        assert_eq!(DynamicConstraint::stringify(&wait), "MaxWait(5)");
+    }
+}
@@ -22,6 +22,9 @@
 //! | `Consecutive` | Target schedulable only after reference finishes       |
 //! | `Exclusive`   | Target schedulable only if reference is **not** placed |
 //!
+//! [`MaxWaitConstraint`] additionally bounds the wait between the reference's
+//! end and the target's start, for no-wait or limited-wait chains.
+//!
 //! Custom kinds can be added by implementing [`DynamicConstraint`] for a
 //! new type.
 
@@ -29,8 +32,10 @@ pub mod coalition;
 pub mod constraint;
 pub mod evaluate;
 pub mod kinds;
+pub mod max_wait;
 
 pub use coalition::CoalitionConstraint;
 pub use constraint::{DynamicConstraint, SchedulingContext};
 pub use evaluate::DynamicConstraintIndex;
 pub use kinds::DynConstraintKind;
+pub use max_wait::MaxWaitConstraint;
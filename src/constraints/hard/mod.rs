@@ -11,10 +11,12 @@ pub mod static_;
 // Re-export the static API at the `hard` level for convenience.
 pub use static_::Constraint;
 pub use static_::IntervalConstraint;
+#[cfg(feature = "chrono")]
+pub use static_::RecurringWindow;
 pub use static_::ResourceConstraint;
 
 // Re-export key dynamic types for ergonomic access.
 pub use dynamic::{
     CoalitionConstraint, DynConstraintKind, DynamicConstraint, DynamicConstraintIndex,
-    SchedulingContext,
+    MaxWaitConstraint, SchedulingContext,
 };
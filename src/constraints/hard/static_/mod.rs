@@ -3,11 +3,16 @@
 //! Feasibility windows fully determined before the scheduling loop.
 //! Produces a binary accept/reject (hard) decision from fixed (static) data.
 //!
-//! The [`Constraint`] trait and the built-in [`IntervalConstraint`] live here.
+//! The [`Constraint`] trait and the built-in [`IntervalConstraint`] live here,
+//! along with the local-time `RecurringWindow` (feature `chrono`).
 
 pub mod constraint;
+#[cfg(feature = "chrono")]
+pub mod recurring;
 pub mod resource;
 
 pub use constraint::Constraint;
 pub use constraint::IntervalConstraint;
+#[cfg(feature = "chrono")]
+pub use recurring::RecurringWindow;
 pub use resource::ResourceConstraint;
@@ -0,0 +1,263 @@
+//! Daily windows in local wall-clock time (feature `chrono`).
+//!
+//! A [`RecurringWindow`] allows the same local hours every day, or on
+//! selected weekdays: "every night 20:00–05:00 Europe/Madrid", "weekdays
+//! 09:00–17:00 America/New_York". Each occurrence is resolved in its time
+//! zone, so it follows daylight-saving transitions: the night the clocks
+//! jump forward is an hour shorter on the axis, the night they fall back an
+//! hour longer.
+//!
+//! Local times that do not exist (skipped by a forward jump) resolve to the
+//! instant of the jump. Local times that occur twice resolve to their first
+//! occurrence when opening a window and to their last when closing one, so a
+//! window covers every instant at which the clock shows a time inside it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use chrono::{NaiveTime, Weekday};
+//! use chrono_tz::Europe::Madrid;
+//! # use chrono::{TimeZone, Utc};
+//! # use qtty::Second;
+//! # use virolai::calendar::CalendarAxis;
+//! # use virolai::constraints::{Constraint, RecurringWindow};
+//! # use virolai::solution_space::Interval;
+//! # let axis = CalendarAxis::new(Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap());
+//! # let horizon = Interval::<Second>::from_f64(0.0, 7.0 * 86_400.0);
+//!
+//! let working_hours = RecurringWindow::<Second, _>::new(
+//!     axis,
+//!     Madrid,
+//!     NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+//!     NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+//! )
+//! .with_weekdays([Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri]);
+//! let allowed = working_hours.compute_intervals(horizon);
+//! ```
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use chrono::{
+    DateTime, Datelike, LocalResult, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc, Weekday,
+};
+use qtty::Second;
+
+use super::constraint::Constraint;
+use crate::calendar::CalendarAxis;
+use crate::solution_space::{Interval, IntervalSet};
+use crate::units::SameDim;
+
+/// A window recurring daily at the same local times.
+#[derive(Debug, Clone)]
+pub struct RecurringWindow<U, Tz: TimeZone> {
+    axis: CalendarAxis,
+    timezone: Tz,
+    start: NaiveTime,
+    end: NaiveTime,
+    weekdays: [bool; 7],
+    _unit: PhantomData<fn() -> U>,
+}
+
+impl<U, Tz> RecurringWindow<U, Tz>
+where
+    U: SameDim<Second>,
+    Tz: TimeZone,
+{
+    /// A window from `start` to `end` local time in `timezone`, every day.
+    ///
+    /// A window whose `end` is not after its `start` runs into the next day;
+    /// equal times make it last a full local day.
+    pub fn new(axis: CalendarAxis, timezone: Tz, start: NaiveTime, end: NaiveTime) -> Self {
+        Self {
+            axis,
+            timezone,
+            start,
+            end,
+            weekdays: [true; 7],
+            _unit: PhantomData,
+        }
+    }
+
+    /// Keeps only occurrences starting on `weekdays`, in local time.
+    pub fn with_weekdays(mut self, weekdays: impl IntoIterator<Item = Weekday>) -> Self {
+        self.weekdays = [false; 7];
+        for day in weekdays {
+            self.weekdays[day.num_days_from_monday() as usize] = true;
+        }
+        self
+    }
+
+    /// The occurrence opening on local date `date`, on the axis.
+    fn occurrence(&self, date: chrono::NaiveDate) -> Interval<U> {
+        let open = date.and_time(self.start);
+        let close_date = if self.end > self.start {
+            date
+        } else {
+            date + TimeDelta::days(1)
+        };
+        let close = close_date.and_time(self.end);
+        Interval::new(
+            self.axis.position(&self.resolve(open, false)),
+            self.axis.position(&self.resolve(close, true)),
+        )
+    }
+
+    /// The instant `local` names: the first or `last` of two, or the jump
+    /// over it if it was skipped.
+    fn resolve(&self, local: NaiveDateTime, last: bool) -> DateTime<Utc> {
+        match self.timezone.from_local_datetime(&local) {
+            LocalResult::Single(t) => t.with_timezone(&Utc),
+            LocalResult::Ambiguous(first, second) => {
+                let t = if last { second } else { first };
+                t.with_timezone(&Utc)
+            }
+            LocalResult::None => self.jump_over(local),
+        }
+    }
+
+    /// The first instant whose local time is at or after the skipped
+    /// `local`, that is, the instant of the forward jump.
+    fn jump_over(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        // Offsets stay within a day of UTC and transitions are much further
+        // apart, so local time grows monotonically over this bracket.
+        let reached = |seconds: i64| {
+            DateTime::from_timestamp(seconds, 0).is_some_and(|t| {
+                self.timezone
+                    .from_utc_datetime(&t.naive_utc())
+                    .naive_local()
+                    >= local
+            })
+        };
+        let naive = local.and_utc().timestamp();
+        let (mut lo, mut hi) = (naive - 86_400, naive + 86_400);
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if reached(mid) {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+        DateTime::from_timestamp(hi, 0).expect("bracket is within chrono's range")
+    }
+}
+
+impl<U, Tz> Constraint<U> for RecurringWindow<U, Tz>
+where
+    U: SameDim<Second> + Send + Sync,
+    Tz: TimeZone + Send + Sync + Debug,
+    Tz::Offset: Send + Sync,
+{
+    /// Occurrences overlapping `range`, clipped to it. Unbounded ranges
+    /// yield nothing: evaluate within a finite horizon.
+    fn compute_intervals(&self, range: Interval<U>) -> IntervalSet<U> {
+        let (Some(first), Some(last)) = (
+            self.axis.datetime(range.start()),
+            self.axis.datetime(range.end()),
+        ) else {
+            return IntervalSet::new();
+        };
+        let first_day = first.with_timezone(&self.timezone).date_naive();
+        let last_day = last.with_timezone(&self.timezone).date_naive();
+
+        // An occurrence opening the day before `range` may reach into it.
+        let mut day = first_day - TimeDelta::days(1);
+        let mut occurrences = Vec::new();
+        while day <= last_day {
+            if self.weekdays[day.weekday().num_days_from_monday() as usize] {
+                if let Some(part) = self.occurrence(day).intersection(&range) {
+                    occurrences.push(part);
+                }
+            }
+            day += TimeDelta::days(1);
+        }
+        occurrences.into_iter().collect()
+    }
+
+    fn stringify(&self) -> String {
+        let days = if self.weekdays == [true; 7] {
+            "daily".to_string()
+        } else {
+            (0..7)
+                .filter(|&i| self.weekdays[i])
+                .map(|i| Weekday::try_from(i as u8).expect("0..7").to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        format!("{days} {}–{} {:?}", self.start, self.end, self.timezone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Europe::Madrid;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn hours(start: f64, end: f64) -> Interval<Second> {
+        Interval::from_f64(start * 3600.0, end * 3600.0)
+    }
+
+    /// Axis zero at 2026-03-27T00:00Z; Madrid moves from UTC+1 to UTC+2 at
+    /// 2026-03-29T01:00Z, hour 49.
+    fn spring_axis() -> CalendarAxis {
+        CalendarAxis::new(Utc.with_ymd_and_hms(2026, 3, 27, 0, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn nights_follow_the_spring_transition() {
+        let nights =
+            RecurringWindow::<Second, _>::new(spring_axis(), Madrid, time(20, 0), time(5, 0));
+        let result = nights.compute_intervals(hours(0.0, 72.0));
+        assert_eq!(
+            result,
+            vec![
+                hours(0.0, 4.0),   // the night of the 26th, 05:00 CET
+                hours(19.0, 28.0), // 20:00 CET to 05:00 CET: 9 h
+                hours(43.0, 51.0), // 20:00 CET to 05:00 CEST: 8 h
+                hours(66.0, 72.0), // 20:00 CEST, clipped
+            ]
+        );
+        assert!(nights.stringify().starts_with("daily 20:00:00–05:00:00"));
+    }
+
+    #[test]
+    fn skipped_and_repeated_times_resolve_to_the_transition() {
+        // 02:30 does not exist on 2026-03-29: the window opens at the jump.
+        let skipped =
+            RecurringWindow::<Second, _>::new(spring_axis(), Madrid, time(2, 30), time(4, 0));
+        let result = skipped.compute_intervals(hours(48.0, 72.0));
+        assert_eq!(result, vec![hours(49.0, 50.0)]);
+
+        // 02:30 happens twice on 2026-10-25, from 00:30Z and from 01:30Z.
+        let axis = CalendarAxis::new(Utc.with_ymd_and_hms(2026, 10, 25, 0, 0, 0).unwrap());
+        let opening = RecurringWindow::<Second, _>::new(axis, Madrid, time(2, 30), time(6, 0));
+        assert_eq!(
+            opening.compute_intervals(hours(0.0, 12.0)),
+            vec![hours(0.5, 5.0)]
+        );
+        let closing = RecurringWindow::<Second, _>::new(axis, Madrid, time(0, 0), time(2, 30));
+        assert_eq!(
+            closing.compute_intervals(hours(-1.0, 12.0)),
+            vec![hours(-1.0, 1.5)]
+        );
+    }
+
+    #[test]
+    fn weekdays_select_occurrences() {
+        // 2026-03-27 is a Friday.
+        let working_hours =
+            RecurringWindow::<Second, _>::new(spring_axis(), Madrid, time(9, 0), time(17, 0))
+                .with_weekdays([Weekday::Fri, Weekday::Mon]);
+        let result = working_hours.compute_intervals(hours(0.0, 96.0));
+        assert_eq!(result, vec![hours(8.0, 16.0), hours(79.0, 87.0)]);
+        assert!(working_hours.stringify().starts_with("Mon,Fri 09:00:00"));
+        assert!(working_hours
+            .compute_intervals(Interval::from_f64(0.0, f64::INFINITY))
+            .is_empty());
+    }
+}
@@ -1,19 +1,28 @@
+pub mod budget;
 pub mod error;
 pub mod hard;
 pub mod node;
 pub mod operations;
+pub mod partial;
+pub mod provenance;
 pub mod soft;
 
+pub use budget::{BudgetLimit, EvaluationBudget};
 pub use error::ConstraintError;
 pub use hard::Constraint;
 pub use hard::IntervalConstraint;
+#[cfg(feature = "chrono")]
+pub use hard::RecurringWindow;
 pub use hard::ResourceConstraint;
 pub use node::ConstraintExpr;
+pub use partial::LeafResolver;
+pub use provenance::{LabeledInterval, Provenance, ProvenanceSegment};
+pub use soft::static_::WindowQuality;
 
 // Re-export dynamic constraint types at the `constraints` level.
 pub use hard::{
     CoalitionConstraint, DynConstraintKind, DynamicConstraint, DynamicConstraintIndex,
-    SchedulingContext,
+    MaxWaitConstraint, SchedulingContext,
 };
 
 use qtty::{Quantity, Unit};
@@ -475,6 +475,26 @@ where
             ),
         }
     }
+
+    /// The tightest wait of an intersection, the loosest of a union; a
+    /// complement bounds nothing.
+    fn max_wait(&self) -> Option<qtty::Quantity<U>> {
+        use crate::constraints::hard::dynamic::DynamicConstraint as Dynamic;
+        match self {
+            ConstraintExpr::Leaf(constraint) => Dynamic::<U>::max_wait(constraint),
+            ConstraintExpr::Not { .. } => None,
+            ConstraintExpr::Intersection { children, .. } => children
+                .iter()
+                .filter_map(Dynamic::<U>::max_wait)
+                .min_by(|a, b| a.value().total_cmp(&b.value())),
+            ConstraintExpr::Union { children, .. } => {
+                let waits: Option<Vec<_>> = children.iter().map(Dynamic::<U>::max_wait).collect();
+                waits?
+                    .into_iter()
+                    .max_by(|a, b| a.value().total_cmp(&b.value()))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
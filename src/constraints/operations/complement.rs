@@ -3,6 +3,11 @@ use crate::solution_space::IntervalSet;
 use qtty::Unit;
 
 /// Returns the complement of a canonical interval set within `[start, end]`.
+///
+/// Parts of `canonical` outside the bounds are ignored, so the result always
+/// lies within them. Either may be unbounded: the complement of `[0, +∞)`
+/// within `[−∞, +∞)` is `[−∞, 0)`. Empty intervals, in the input or in the
+/// bounds, contain no positions.
 pub fn compute_complement<U: Unit>(
     canonical: Vec<Interval<U>>,
     interval: Interval<U>,
@@ -13,13 +18,19 @@ pub fn compute_complement<U: Unit>(
         "input `canonical` is not in canonical form"
     );
 
-    if canonical.is_empty() {
-        return IntervalSet::from(interval);
+    if interval.is_empty() {
+        return IntervalSet::new();
     }
 
     let mut result = Vec::with_capacity(canonical.len() + 1);
     let mut cursor = interval.start();
     for iv in canonical {
+        if iv.is_empty() || iv.end() <= cursor {
+            continue;
+        }
+        if iv.start() >= interval.end() {
+            break;
+        }
         if iv.start() > cursor {
             result.push(Interval::new(cursor, iv.start()));
         }
@@ -36,7 +47,7 @@ pub fn compute_complement<U: Unit>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use qtty::Second;
+    use qtty::{Quantity, Second};
 
     fn iv(start: f64, end: f64) -> Interval<Second> {
         Interval::from_f64(start, end)
@@ -95,12 +106,85 @@ mod tests {
         assert_eq!(result[0], iv(0.0, 70.0));
     }
 
+    #[test]
+    fn complement_within_unbounded_bounds() {
+        let inf = f64::INFINITY;
+        let all = iv(-inf, inf);
+        assert_eq!(compute_complement(vec![], all), vec![all]);
+        assert_eq!(
+            compute_complement(vec![iv(0.0, inf)], all),
+            vec![iv(-inf, 0.0)]
+        );
+        assert_eq!(
+            compute_complement(vec![iv(-inf, 0.0), iv(10.0, 20.0)], all),
+            vec![iv(0.0, 10.0), iv(20.0, inf)]
+        );
+        assert!(compute_complement(vec![all], all).is_empty());
+    }
+
+    #[test]
+    fn complement_ignores_parts_outside_bounds() {
+        let inf = f64::INFINITY;
+        let result = compute_complement(vec![iv(-inf, -100.0), iv(150.0, inf)], iv(0.0, 100.0));
+        assert_eq!(result, vec![iv(0.0, 100.0)]);
+        let result = compute_complement(vec![iv(-inf, 10.0), iv(90.0, inf)], iv(0.0, 100.0));
+        assert_eq!(result, vec![iv(10.0, 90.0)]);
+    }
+
+    /// Checks complement against point membership for every set of up to
+    /// two disjoint intervals and every bounds drawn from a grid with
+    /// infinite ends.
+    #[test]
+    fn complement_exhaustive_over_extended_grid() {
+        let inf = f64::INFINITY;
+        let grid = [-inf, -10.0, 0.0, 10.0, inf];
+        let probes = [-1e300, -10.0, -5.0, 0.0, 5.0, 10.0, 1e300];
+        let mut intervals = Vec::new();
+        for (i, &a) in grid.iter().enumerate() {
+            for &b in &grid[i..] {
+                intervals.push(iv(a, b));
+            }
+        }
+
+        let mut sets = vec![vec![]];
+        for &x in &intervals {
+            sets.push(vec![x]);
+            for &y in &intervals {
+                if x.end() < y.start() && !x.is_empty() && !y.is_empty() {
+                    sets.push(vec![x, y]);
+                }
+            }
+        }
+
+        for set in &sets {
+            for &bounds in &intervals {
+                let result = compute_complement(set.clone(), bounds);
+                assert!(crate::constraints::operations::assertions::is_canonical(
+                    &result
+                ));
+                for iv in result.iter() {
+                    assert!(!iv.is_empty());
+                    assert!(!iv.duration().value().is_nan());
+                    assert!(bounds.start() <= iv.start() && iv.end() <= bounds.end());
+                }
+                for &p in &probes {
+                    let p = Quantity::new(p);
+                    let expected = bounds.contains(p) && !set.iter().any(|s| s.contains(p));
+                    assert_eq!(
+                        result.iter().any(|r| r.contains(p)),
+                        expected,
+                        "{set:?} within {bounds:?} at {p:?}"
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn complement_inverted_interval_returns_empty() {
-        // start > end → empty result
+        // Interval::new panics if start > end, so we test start == end: the
+        // bounds are empty, and so is the complement.
         let result = compute_complement(vec![], Interval::<Second>::from_f64(100.0, 100.0));
-        // start == end is valid (zero-width), returns zero-width interval only if canonical is empty
-        // But Interval::new panics if start > end, so we test start == end
-        assert_eq!(result.len(), 1);
+        assert!(result.is_empty());
     }
 }
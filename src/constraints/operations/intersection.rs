@@ -29,11 +29,8 @@ pub fn compute_intersection<U: Unit>(a: &[Interval<U>], b: &[Interval<U>]) -> In
         let ia = &a[i];
         let ib = &b[j];
 
-        if ia.overlaps(ib) {
-            result.push(Interval::new(
-                crate::constraints::quantity_max(ia.start(), ib.start()),
-                crate::constraints::quantity_min(ia.end(), ib.end()),
-            ));
+        if let Some(shared) = ia.intersection(ib) {
+            result.push(shared);
         }
 
         match ia.end().partial_cmp(&ib.end()) {
@@ -58,6 +55,24 @@ mod tests {
         Interval::from_f64(start, end)
     }
 
+    #[test]
+    fn intersection_with_unbounded_intervals() {
+        let inf = f64::INFINITY;
+        let a = vec![iv(-inf, 0.0), iv(10.0, inf)];
+        let b = vec![iv(-5.0, 20.0)];
+        assert_eq!(
+            compute_intersection(&a, &b),
+            vec![iv(-5.0, 0.0), iv(10.0, 20.0)]
+        );
+
+        let everything = vec![iv(-inf, inf)];
+        assert_eq!(compute_intersection(&a, &everything), a);
+        assert_eq!(compute_intersection(&everything, &everything), everything);
+        // Half-lines meeting at a point share no position.
+        assert!(compute_intersection(&[iv(-inf, 0.0)], &[iv(0.0, inf)]).is_empty());
+        assert!(compute_intersection(&[iv(inf, inf)], &everything).is_empty());
+    }
+
     #[test]
     fn intersection_disjoint_sets() {
         let a = vec![iv(0.0, 10.0)];
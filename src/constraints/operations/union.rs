@@ -17,6 +17,9 @@ use qtty::Unit;
 /// Two half-open intervals are merged when they overlap **or** are directly adjacent
 /// (`last.end == iv.start`). E.g. `[0, 10)` and `[10, 20)` merge into `[0, 20)`.
 fn merge_into<U: Unit>(result: &mut Vec<Interval<U>>, iv: Interval<U>) {
+    if iv.is_empty() {
+        return;
+    }
     if let Some(last) = result.last_mut() {
         if last.overlaps(&iv) || last.end().value() == iv.start().value() {
             let new_end = crate::constraints::quantity_max(last.end(), iv.end());
@@ -38,13 +41,6 @@ pub fn compute_union<U: Unit>(a: &[Interval<U>], b: &[Interval<U>]) -> IntervalS
     debug_assert!(super::assertions::is_canonical(a));
     debug_assert!(super::assertions::is_canonical(b));
 
-    if a.is_empty() {
-        return IntervalSet::from_sorted_unchecked(b.to_vec());
-    }
-    if b.is_empty() {
-        return IntervalSet::from_sorted_unchecked(a.to_vec());
-    }
-
     let mut result: Vec<Interval<U>> = Vec::with_capacity(a.len() + b.len());
     let mut i = 0usize;
     let mut j = 0usize;
@@ -99,6 +95,30 @@ mod tests {
         assert_eq!(u[0].end().value(), 150.0);
     }
 
+    #[test]
+    fn test_compute_union_unbounded() {
+        let inf = f64::INFINITY;
+        let a = vec![Interval::<Second>::from_f64(-inf, 0.0)];
+        let b = vec![
+            Interval::<Second>::from_f64(-5.0, 10.0),
+            Interval::<Second>::from_f64(20.0, inf),
+        ];
+        let u = compute_union(&a, &b);
+        assert_eq!(
+            u,
+            vec![
+                Interval::from_f64(-inf, 10.0),
+                Interval::from_f64(20.0, inf)
+            ]
+        );
+        assert!(u.iter().all(|iv| iv.duration().value() == inf));
+
+        // Empty intervals, even at infinity, are dropped.
+        let empty = vec![Interval::<Second>::from_f64(inf, inf)];
+        assert!(compute_union(&empty, &[]).is_empty());
+        assert_eq!(compute_union(&a, &empty), a);
+    }
+
     #[test]
     fn test_compute_union_adjacent() {
         let a = vec![Interval::<Second>::from_f64(0.0, 50.0)];
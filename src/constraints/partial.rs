@@ -0,0 +1,187 @@
+//! Constraint-tree evaluation with some leaves already known.
+//!
+//! [`ConstraintExpr::evaluate_with`] evaluates a tree like
+//! [`compute_intervals`](Constraint::compute_intervals), but first asks a
+//! [`LeafResolver`] for each leaf's intervals. Leaves the resolver knows,
+//! such as expensive ephemeris leaves cached from a previous run, are taken
+//! as given; the others are computed fresh and handed back to the resolver,
+//! which may keep them for the next run.
+//!
+//! Leaves are numbered in pre-order, as in
+//! [`provenance`](super::provenance).
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::collections::HashMap;
+//! # use qtty::Second;
+//! # use virolai::constraints::{ConstraintExpr, IntervalConstraint};
+//! # use virolai::solution_space::{Interval, IntervalSet};
+//! # type Leaf = IntervalConstraint<Second>;
+//! # let range = Interval::<Second>::from_f64(0.0, 86_400.0);
+//! # let tree = ConstraintExpr::leaf(IntervalConstraint::new(Interval::from_f64(0.0, 43_200.0)));
+//! # let cached_altitude: HashMap<usize, IntervalSet<Second>> = HashMap::new();
+//! // Reuse yesterday's altitude windows, recompute everything else.
+//! let windows = tree.evaluate_with(range, &mut |leaf: usize, _: &Leaf, _| {
+//!     cached_altitude.get(&leaf).cloned()
+//! });
+//! ```
+
+use super::hard::Constraint;
+use super::node::ConstraintExpr;
+use crate::solution_space::{Interval, IntervalSet};
+use qtty::Unit;
+
+/// Supplies known intervals for leaves of a tree, see
+/// [`ConstraintExpr::evaluate_with`].
+///
+/// Closures `FnMut(usize, &C, Interval<U>) -> Option<IntervalSet<U>>`
+/// implement it.
+pub trait LeafResolver<U: Unit, C> {
+    /// The intervals of leaf number `index` within `range`, or `None` to
+    /// compute them from the leaf.
+    ///
+    /// Intervals outside `range` are ignored, so a result cached over a
+    /// wider range can be returned as is.
+    fn resolve(&mut self, index: usize, leaf: &C, range: Interval<U>) -> Option<IntervalSet<U>>;
+
+    /// Called with the intervals of every leaf [`resolve`](Self::resolve)
+    /// left to compute, e.g. to cache them. Does nothing by default.
+    fn computed(&mut self, index: usize, leaf: &C, range: Interval<U>, intervals: &IntervalSet<U>) {
+        let _ = (index, leaf, range, intervals);
+    }
+}
+
+impl<U, C, F> LeafResolver<U, C> for F
+where
+    U: Unit,
+    F: FnMut(usize, &C, Interval<U>) -> Option<IntervalSet<U>>,
+{
+    fn resolve(&mut self, index: usize, leaf: &C, range: Interval<U>) -> Option<IntervalSet<U>> {
+        self(index, leaf, range)
+    }
+}
+
+impl<C> ConstraintExpr<C> {
+    /// Evaluates the tree within `range`, taking the intervals of the leaves
+    /// `resolver` knows and computing the others.
+    ///
+    /// With a resolver that knows nothing, the result is that of
+    /// [`compute_intervals`](Constraint::compute_intervals).
+    pub fn evaluate_with<U, R>(&self, range: Interval<U>, resolver: &mut R) -> IntervalSet<U>
+    where
+        U: Unit,
+        C: Constraint<U>,
+        R: LeafResolver<U, C> + ?Sized,
+    {
+        self.evaluate_from(range, resolver, &mut 0)
+    }
+
+    /// [`evaluate_with`](Self::evaluate_with) on a subtree whose first leaf
+    /// is number `*next`.
+    fn evaluate_from<U, R>(
+        &self,
+        range: Interval<U>,
+        resolver: &mut R,
+        next: &mut usize,
+    ) -> IntervalSet<U>
+    where
+        U: Unit,
+        C: Constraint<U>,
+        R: LeafResolver<U, C> + ?Sized,
+    {
+        match self {
+            ConstraintExpr::Leaf(leaf) => {
+                let index = *next;
+                *next += 1;
+                match resolver.resolve(index, leaf, range) {
+                    Some(known) => known.intersection(&IntervalSet::from(range)),
+                    None => {
+                        let fresh = leaf.compute_intervals(range);
+                        resolver.computed(index, leaf, range, &fresh);
+                        fresh
+                    }
+                }
+            }
+            ConstraintExpr::Not { child, .. } => {
+                let child = child.evaluate_from(range, resolver, next);
+                child.complement(range)
+            }
+            ConstraintExpr::Intersection { children, .. } => {
+                // Every child is visited, even past an empty result, so the
+                // leaf numbering stays that of the whole tree.
+                let mut result: Option<IntervalSet<U>> = None;
+                for child in children {
+                    let set = child.evaluate_from(range, resolver, next);
+                    result = Some(match result {
+                        Some(acc) => acc.intersection(&set),
+                        None => set,
+                    });
+                }
+                result.unwrap_or_default()
+            }
+            ConstraintExpr::Union { children, .. } => children
+                .iter()
+                .map(|c| c.evaluate_from(range, resolver, next))
+                .fold(IntervalSet::new(), |acc, set| acc.union(&set)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::IntervalConstraint;
+    use crate::test_utils::iv;
+    use qtty::Second;
+    use std::collections::HashMap;
+
+    #[test]
+    fn known_leaves_replace_fresh_ones() {
+        let leaf = |s, e| ConstraintExpr::leaf(IntervalConstraint::<Second>::new(iv(s, e)));
+        // (0..50 ∪ 70..90) ∩ ¬(20..30)
+        let tree = ConstraintExpr::intersection(vec![
+            ConstraintExpr::union(vec![leaf(0.0, 50.0), leaf(70.0, 90.0)]),
+            !leaf(20.0, 30.0),
+        ]);
+        let range = iv(0.0, 100.0);
+
+        let mut nothing = |_: usize, _: &IntervalConstraint<Second>, _| None;
+        assert_eq!(
+            tree.evaluate_with(range, &mut nothing),
+            tree.compute_intervals(range)
+        );
+
+        // Leaf 1 is cached from a run over a wider range.
+        struct Cache(HashMap<usize, IntervalSet<Second>>, Vec<usize>);
+        impl LeafResolver<Second, IntervalConstraint<Second>> for Cache {
+            fn resolve(
+                &mut self,
+                index: usize,
+                _: &IntervalConstraint<Second>,
+                _: Interval<Second>,
+            ) -> Option<IntervalSet<Second>> {
+                self.0.get(&index).cloned()
+            }
+            fn computed(
+                &mut self,
+                index: usize,
+                _: &IntervalConstraint<Second>,
+                _: Interval<Second>,
+                _: &IntervalSet<Second>,
+            ) {
+                self.1.push(index);
+            }
+        }
+        let mut cache = Cache(
+            HashMap::from([(1, IntervalSet::from(iv(60.0, 150.0)))]),
+            vec![],
+        );
+        let result = tree.evaluate_with(range, &mut cache);
+        assert_eq!(
+            result.as_slice(),
+            [iv(0.0, 20.0), iv(30.0, 50.0), iv(60.0, 100.0)]
+        );
+        assert_eq!(cache.1, [0, 2]);
+    }
+}
@@ -3,4 +3,33 @@
 //! Preference-based scoring constraints whose parameters are fixed
 //! before the scheduling loop (e.g., preferred time windows, priority weights).
 //!
-//! Not yet implemented — module reserved for future growth.
+//! A [`WindowQuality`] rates the windows of a task, such as expected seeing
+//! or an airmass merit. [`SolutionSpace::annotate_quality`] stores the
+//! ratings next to the windows, where schedulers and reports can weigh
+//! them.
+//!
+//! [`SolutionSpace::annotate_quality`]: crate::solution_space::SolutionSpace::annotate_quality
+
+use crate::solution_space::Interval;
+use qtty::Unit;
+
+/// Rates a feasible window: higher is better.
+///
+/// A window that is as good as any other rates 1.0, the quality of windows
+/// nobody rated. Ratings must not be NaN.
+///
+/// Closures `Fn(Interval<U>) -> f64` implement it.
+pub trait WindowQuality<U: Unit> {
+    /// The quality of `window`.
+    fn quality(&self, window: Interval<U>) -> f64;
+}
+
+impl<U, F> WindowQuality<U> for F
+where
+    U: Unit,
+    F: Fn(Interval<U>) -> f64,
+{
+    fn quality(&self, window: Interval<U>) -> f64 {
+        self(window)
+    }
+}
@@ -0,0 +1,500 @@
+//! Human-readable formatting for quantities, intervals and schedules.
+//!
+//! The plain [`Display`] implementations print raw axis values (`5025.000`),
+//! which is unreadable on real time scales. [`DisplayOptions`] controls an
+//! alternative rendering where time quantities are humanized (`1h 23m 45s`),
+//! available through the `display_with` methods on [`Interval`],
+//! [`Schedule`], [`SolutionSpace`] and [`SchedulingBlock`], and through
+//! [`Schedule::to_table`] for a compact tabular view.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::display::DisplayOptions;
+//! # use qtty::Second;
+//! # use virolai::schedule::Schedule;
+//! # use virolai::solution_space::Interval;
+//! # let mut schedule: Schedule<Second> = Schedule::new();
+//! # schedule.add("calib", Interval::from_f64(0.0, 5_025.0))?;
+//!
+//! let opts = DisplayOptions::humanized();
+//! println!("{}", schedule.display_with(opts));
+//! println!("{}", schedule.to_table(opts));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::any::TypeId;
+use std::fmt::{self, Display};
+
+use petgraph::EdgeType;
+use qtty::{Quantity, Second, Unit};
+
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Interval, SolutionSpace};
+
+/// Formatting options for quantity-bearing displays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayOptions {
+    humanize: bool,
+    precision: usize,
+    show_unit: bool,
+}
+
+impl DisplayOptions {
+    /// Raw values with three decimals, matching the plain `Display` output.
+    pub const fn new() -> Self {
+        Self {
+            humanize: false,
+            precision: 3,
+            show_unit: false,
+        }
+    }
+
+    /// Time quantities rendered as `1d 2h 3m 4s`.
+    ///
+    /// Quantities of other dimensions fall back to the raw value followed by
+    /// the unit symbol.
+    pub const fn humanized() -> Self {
+        Self {
+            humanize: true,
+            precision: 3,
+            show_unit: true,
+        }
+    }
+
+    /// Sets the number of decimals used for raw values and sub-second
+    /// durations.
+    pub const fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Appends the unit symbol to raw values.
+    pub const fn with_unit(mut self, show_unit: bool) -> Self {
+        self.show_unit = show_unit;
+        self
+    }
+
+    /// Whether time quantities are humanized.
+    pub const fn is_humanized(&self) -> bool {
+        self.humanize
+    }
+
+    /// Decimals used for raw values.
+    pub const fn precision(&self) -> usize {
+        self.precision
+    }
+
+    /// Formats a quantity according to these options.
+    pub fn quantity<U: Unit>(&self, q: Quantity<U>) -> String {
+        if self.humanize && is_time::<U>() {
+            return humanize_seconds(q.value() * U::RATIO / Second::RATIO, self.precision);
+        }
+        let raw = format!("{:.*}", self.precision, q.value());
+        if self.show_unit && !U::SYMBOL.is_empty() {
+            format!("{} {}", raw, U::SYMBOL)
+        } else {
+            raw
+        }
+    }
+
+    /// Formats an interval as `[start, end]` according to these options.
+    pub fn interval<U: Unit>(&self, interval: Interval<U>) -> String {
+        format!(
+            "[{}, {}]",
+            self.quantity(interval.start()),
+            self.quantity(interval.end())
+        )
+    }
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_time<U: Unit>() -> bool {
+    TypeId::of::<U::Dim>() == TypeId::of::<<Second as Unit>::Dim>()
+}
+
+/// Renders a number of seconds as `1d 2h 3m 4s`, omitting zero components.
+///
+/// Values below one second keep up to `precision` decimals (`0.25s`);
+/// longer durations are rounded to whole seconds.
+fn humanize_seconds(seconds: f64, precision: usize) -> String {
+    if !seconds.is_finite() {
+        return format!("{}s", seconds);
+    }
+    let sign = if seconds < 0.0 { "-" } else { "" };
+    let abs = seconds.abs();
+    if abs < 1.0 {
+        let frac = format!("{:.*}", precision, abs);
+        let frac = if frac.contains('.') {
+            frac.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &frac
+        };
+        return if frac == "0" {
+            "0s".to_string()
+        } else {
+            format!("{}{}s", sign, frac)
+        };
+    }
+
+    let mut rest = abs.round() as u64;
+    let mut parts = Vec::with_capacity(4);
+    for (unit, label) in [(86_400, "d"), (3_600, "h"), (60, "m")] {
+        if rest >= unit {
+            parts.push(format!("{}{}", rest / unit, label));
+            rest %= unit;
+        }
+    }
+    if rest > 0 || parts.is_empty() {
+        parts.push(format!("{}s", rest));
+    }
+    format!("{}{}", sign, parts.join(" "))
+}
+
+/// A value paired with [`DisplayOptions`], returned by the `display_with`
+/// methods.
+#[derive(Debug, Clone, Copy)]
+pub struct WithOptions<'a, T> {
+    value: &'a T,
+    options: DisplayOptions,
+}
+
+impl<U: Unit> Interval<U> {
+    /// Displays this interval with the given options.
+    pub fn display_with(&self, options: DisplayOptions) -> WithOptions<'_, Self> {
+        WithOptions {
+            value: self,
+            options,
+        }
+    }
+}
+
+impl<U: Unit> Display for WithOptions<'_, Interval<U>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.options.interval(*self.value))
+    }
+}
+
+impl<U: Unit> Schedule<U> {
+    /// Displays this schedule with the given options.
+    pub fn display_with(&self, options: DisplayOptions) -> WithOptions<'_, Self> {
+        WithOptions {
+            value: self,
+            options,
+        }
+    }
+
+    /// Renders the schedule as a compact, start-ordered table with
+    /// `id`, `start`, `end` and `duration` columns.
+    ///
+    /// Entries of the [background lane](Schedule#background-tasks) follow
+    /// the primary ones, marked in an extra `lane` column present only when
+    /// there are any.
+    pub fn to_table(&self, options: DisplayOptions) -> String {
+        let lanes = self.background_len() > 0;
+        let mut header = vec!["id", "start", "end", "duration"];
+        if lanes {
+            header.push("lane");
+        }
+        let header: Vec<String> = header.into_iter().map(String::from).collect();
+        let rows: Vec<Vec<String>> = self
+            .iter_all()
+            .map(|(id, iv, background)| {
+                let mut row = vec![
+                    id,
+                    options.quantity(iv.start()),
+                    options.quantity(iv.end()),
+                    options.quantity(iv.duration()),
+                ];
+                if lanes {
+                    let lane = if background { "background" } else { "primary" };
+                    row.push(lane.to_string());
+                }
+                row
+            })
+            .collect();
+
+        let mut widths: Vec<usize> = header.iter().map(String::len).collect();
+        for row in &rows {
+            for (w, cell) in widths.iter_mut().zip(row) {
+                *w = (*w).max(cell.chars().count());
+            }
+        }
+
+        let mut out = String::new();
+        for row in std::iter::once(&header).chain(&rows) {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, w)| format!("{:<w$}", cell, w = w))
+                .collect::<Vec<_>>()
+                .join("  ");
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl<U: Unit> Display for WithOptions<'_, Schedule<U>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let schedule = self.value;
+        writeln!(f, "Schedule {{")?;
+        writeln!(f, "  Tasks: {}", schedule.len())?;
+        writeln!(
+            f,
+            "  Total duration: {}",
+            self.options.quantity(schedule.total_duration())
+        )?;
+        for (id, interval) in schedule.iter() {
+            writeln!(f, "    {}: {}", id, self.options.interval(interval))?;
+        }
+        if schedule.background_len() > 0 {
+            writeln!(f, "  Background: {}", schedule.background_len())?;
+            for (id, interval) in schedule.background() {
+                writeln!(f, "    {}: {}", id, self.options.interval(interval))?;
+            }
+        }
+        write!(f, "}}")
+    }
+}
+
+impl<U: Unit> Display for Schedule<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_with(DisplayOptions::default()).fmt(f)
+    }
+}
+
+impl<U: Unit> SolutionSpace<U> {
+    /// Displays this solution space with the given options.
+    ///
+    /// Unlike the plain `Display`, entries are listed in ID order.
+    pub fn display_with(&self, options: DisplayOptions) -> WithOptions<'_, Self> {
+        WithOptions {
+            value: self,
+            options,
+        }
+    }
+}
+
+impl<U: Unit> Display for WithOptions<'_, SolutionSpace<U>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let space = self.value;
+        let opts = &self.options;
+        writeln!(f, "SolutionSpace {{")?;
+        writeln!(f, "  Entries: {}", space.count())?;
+        writeln!(f, "  Total intervals: {}", space.interval_count())?;
+        writeln!(
+            f,
+            "  Total capacity: {}",
+            opts.quantity(space.total_capacity())
+        )?;
+
+        let mut entries: Vec<_> = space.iter().collect();
+        entries.sort_unstable_by_key(|&(id, _)| id);
+        for (id, windows) in entries {
+            writeln!(
+                f,
+                "    id {}: capacity {}",
+                id,
+                opts.quantity(space.capacity(id))
+            )?;
+            for interval in windows {
+                writeln!(f, "      {}", opts.interval(*interval))?;
+            }
+        }
+        write!(f, "}}")
+    }
+}
+
+impl<T, U, D, E> SchedulingBlock<T, U, D, E>
+where
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    /// Displays this block with the given options.
+    pub fn display_with(&self, options: DisplayOptions) -> WithOptions<'_, Self> {
+        WithOptions {
+            value: self,
+            options,
+        }
+    }
+}
+
+impl<T, U, D, E> Display for WithOptions<'_, SchedulingBlock<T, U, D, E>>
+where
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let block = self.value;
+        writeln!(f, "SchedulingBlock {{")?;
+        writeln!(f, "  Tasks: {}", block.task_count())?;
+        writeln!(f, "  Dependencies: {}", block.dependency_count())?;
+        for (id, task) in block.tasks() {
+            writeln!(
+                f,
+                "    {} {} (size: {}, priority: {})",
+                id,
+                task.name(),
+                self.options.quantity(task.size_on_axis()),
+                task.priority()
+            )?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{iv, q, TestTask};
+    use qtty::{Day, Meter};
+
+    // ── humanize ──────────────────────────────────────────────────────
+
+    #[test]
+    fn humanize_components() {
+        assert_eq!(humanize_seconds(5025.0, 3), "1h 23m 45s");
+        assert_eq!(humanize_seconds(90_061.0, 3), "1d 1h 1m 1s");
+        assert_eq!(humanize_seconds(3600.0, 3), "1h");
+        assert_eq!(humanize_seconds(59.6, 3), "1m");
+        assert_eq!(humanize_seconds(-75.0, 3), "-1m 15s");
+    }
+
+    #[test]
+    fn humanize_sub_second() {
+        assert_eq!(humanize_seconds(0.0, 3), "0s");
+        assert_eq!(humanize_seconds(0.25, 3), "0.25s");
+        assert_eq!(humanize_seconds(0.0001, 3), "0s");
+        assert_eq!(humanize_seconds(-0.5, 1), "-0.5s");
+    }
+
+    #[test]
+    fn quantity_respects_unit_ratio() {
+        let opts = DisplayOptions::humanized();
+        assert_eq!(opts.quantity(Quantity::<Day>::new(1.5)), "1d 12h");
+        assert_eq!(opts.quantity(q(61.0)), "1m 1s");
+    }
+
+    #[test]
+    fn non_time_units_fall_back_to_raw() {
+        let opts = DisplayOptions::humanized().with_precision(1);
+        assert_eq!(opts.quantity(Quantity::<Meter>::new(2.0)), "2.0 m");
+    }
+
+    #[test]
+    fn default_options_match_plain_display() {
+        let opts = DisplayOptions::default();
+        assert_eq!(opts.interval(iv(0.0, 5025.0)), iv(0.0, 5025.0).to_string());
+        assert_eq!(
+            DisplayOptions::new().with_unit(true).quantity(q(2.0)),
+            "2.000 s"
+        );
+    }
+
+    // ── adapters ──────────────────────────────────────────────────────
+
+    fn schedule() -> Schedule<Second> {
+        let mut s = Schedule::new();
+        s.add("b", iv(3600.0, 5400.0)).unwrap();
+        s.add("a", iv(0.0, 90.0)).unwrap();
+        s
+    }
+
+    #[test]
+    fn interval_display_with() {
+        let opts = DisplayOptions::humanized();
+        assert_eq!(
+            iv(60.0, 5025.0).display_with(opts).to_string(),
+            "[1m, 1h 23m 45s]"
+        );
+    }
+
+    #[test]
+    fn schedule_display_with_humanized() {
+        let text = schedule()
+            .display_with(DisplayOptions::humanized())
+            .to_string();
+        assert!(text.contains("Tasks: 2"));
+        assert!(text.contains("Total duration: 31m 30s"));
+        assert!(text.contains("a: [0s, 1m 30s]"));
+        assert!(text.find("a:").unwrap() < text.find("b:").unwrap());
+    }
+
+    #[test]
+    fn schedule_display_lists_background_lane() {
+        let mut schedule = schedule();
+        assert!(!schedule.to_string().contains("Background"));
+        assert!(!schedule
+            .to_table(DisplayOptions::humanized())
+            .contains("lane"));
+
+        schedule.add_background("monitor", iv(0.0, 7200.0)).unwrap();
+        let text = schedule
+            .display_with(DisplayOptions::humanized())
+            .to_string();
+        assert!(text.contains("Tasks: 2"));
+        assert!(text.contains("Background: 1\n    monitor: [0s, 2h]"));
+
+        let table = schedule.to_table(DisplayOptions::humanized());
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "id       start  end     duration  lane");
+        assert_eq!(lines[3], "monitor  0s     2h      2h        background");
+    }
+
+    #[test]
+    fn schedule_plain_display_uses_raw_values() {
+        let text = schedule().to_string();
+        assert!(text.contains("b: [3600.000, 5400.000]"));
+    }
+
+    #[test]
+    fn schedule_table_is_aligned() {
+        let table = schedule().to_table(DisplayOptions::humanized());
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "id  start  end     duration");
+        assert_eq!(lines[1], "a   0s     1m 30s  1m 30s");
+        assert_eq!(lines[2], "b   1h     1h 30m  30m");
+    }
+
+    #[test]
+    fn empty_schedule_table_has_header_only() {
+        let table = Schedule::<Second>::new().to_table(DisplayOptions::new());
+        assert_eq!(table, "id  start  end  duration\n");
+    }
+
+    #[test]
+    fn solution_space_display_with_sorted_ids() {
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("z", iv(0.0, 7200.0));
+        ss.add_interval("a", iv(0.0, 60.0));
+        let text = ss.display_with(DisplayOptions::humanized()).to_string();
+        assert!(text.contains("Total capacity: 2h 1m"));
+        assert!(text.find("id a").unwrap() < text.find("id z").unwrap());
+        assert!(text.contains("[0s, 2h]"));
+    }
+
+    #[test]
+    fn block_display_with_humanized_sizes() {
+        let mut block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
+        block
+            .add_task_with_id(
+                TestTask::new("obs", 5025.0).with_priority(4),
+                Some("t1".into()),
+            )
+            .unwrap();
+        let text = block.display_with(DisplayOptions::humanized()).to_string();
+        assert!(text.contains("t1 obs (size: 1h 23m 45s, priority: 4)"));
+    }
+}
@@ -0,0 +1,352 @@
+//! Bit-exact serde encoding of times.
+//!
+//! The default encoding writes times as plain numbers. Not every format or
+//! parser round-trips an `f64` exactly (`serde_json` without its
+//! `float_roundtrip` feature may be one ULP off), and an endpoint shifted by
+//! one ULP is enough to make abutting entries of a previously valid schedule
+//! overlap on import. This encoding writes every time as its IEEE-754 bit
+//! pattern, a string such as `"0x4059000000000000"` for `100.0`, and reads it
+//! back strictly: decimal numbers, malformed patterns, NaN, reversed
+//! intervals and non-canonical interval sets are rejected instead of being
+//! repaired.
+//!
+//! Use it on an [`Interval`], [`IntervalSet`] or [`Schedule`] field with
+//! `#[serde(with = "virolai::exact")]`, or wrap a value in [`Exact`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::exact::Exact;
+//! # use qtty::Second;
+//! # use virolai::schedule::Schedule;
+//! # use virolai::solution_space::Interval;
+//! # let mut schedule: Schedule<Second> = Schedule::new();
+//! # schedule.add("calib", Interval::from_f64(0.1, 60.3))?;
+//!
+//! let json = serde_json::to_string(&Exact(&schedule))?;
+//! let Exact(restored): Exact<Schedule<Second>> = serde_json::from_str(&json)?;
+//! assert!(restored.iter().eq(schedule.iter()));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use qtty::{Quantity, Unit};
+use serde::de::{self, SeqAccess, Visitor};
+use serde::ser::{SerializeSeq, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::schedule::Schedule;
+use crate::solution_space::{Interval, IntervalSet};
+
+/// A value serialized with the bit-exact encoding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exact<T>(pub T);
+
+/// Types [`Exact`] can write with every time as a bit pattern.
+pub trait SerializeExact {
+    /// Serializes `self` with every time as a bit pattern.
+    fn serialize_exact<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+/// Types [`Exact`] can read back from bit patterns.
+pub trait DeserializeExact: Sized {
+    /// Deserializes a value written by [`SerializeExact`], rejecting
+    /// anything else.
+    fn deserialize_exact<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+impl<T: SerializeExact + ?Sized> SerializeExact for &T {
+    fn serialize_exact<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize_exact(serializer)
+    }
+}
+
+/// `serialize_with` half of `#[serde(with = "virolai::exact")]`.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: SerializeExact,
+    S: Serializer,
+{
+    value.serialize_exact(serializer)
+}
+
+/// `deserialize_with` half of `#[serde(with = "virolai::exact")]`.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: DeserializeExact,
+    D: Deserializer<'de>,
+{
+    T::deserialize_exact(deserializer)
+}
+
+impl<T: SerializeExact> Serialize for Exact<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_exact(serializer)
+    }
+}
+
+impl<'de, T: DeserializeExact> Deserialize<'de> for Exact<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize_exact(deserializer).map(Exact)
+    }
+}
+
+/// One time, as the `0x`-prefixed 16-digit hex of its bits.
+struct Bits(f64);
+
+impl Serialize for Bits {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:#018x}", self.0.to_bits()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Bits {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BitsVisitor;
+
+        impl Visitor<'_> for BitsVisitor {
+            type Value = Bits;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a time as a \"0x\"-prefixed 16-digit hex bit pattern")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Bits, E> {
+                let digits = v
+                    .strip_prefix("0x")
+                    .filter(|d| d.len() == 16 && d.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))?;
+                let value = f64::from_bits(u64::from_str_radix(digits, 16).map_err(E::custom)?);
+                if value.is_nan() {
+                    return Err(E::invalid_value(
+                        de::Unexpected::Str(v),
+                        &"a time that is not NaN",
+                    ));
+                }
+                Ok(Bits(value))
+            }
+        }
+
+        deserializer.deserialize_str(BitsVisitor)
+    }
+}
+
+/// Interval with exact endpoints, as written on the wire.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawInterval {
+    start: Bits,
+    end: Bits,
+}
+
+impl RawInterval {
+    fn into_interval<U: Unit, E: de::Error>(self) -> Result<Interval<U>, E> {
+        if self.start.0 > self.end.0 {
+            return Err(E::custom(format!(
+                "interval start {} is after its end {}",
+                self.start.0, self.end.0
+            )));
+        }
+        Ok(Interval::new(
+            Quantity::new(self.start.0),
+            Quantity::new(self.end.0),
+        ))
+    }
+}
+
+/// Borrowed interval with exact endpoints, for serialization.
+struct ExactInterval<'a, U: Unit>(&'a Interval<U>);
+
+impl<U: Unit> Serialize for ExactInterval<'_, U> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Interval", 2)?;
+        s.serialize_field("start", &Bits(self.0.start().value()))?;
+        s.serialize_field("end", &Bits(self.0.end().value()))?;
+        s.end()
+    }
+}
+
+impl<U: Unit> SerializeExact for Interval<U> {
+    fn serialize_exact<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ExactInterval(self).serialize(serializer)
+    }
+}
+
+impl<U: Unit> DeserializeExact for Interval<U> {
+    fn deserialize_exact<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RawInterval::deserialize(deserializer)?.into_interval()
+    }
+}
+
+impl<U: Unit> SerializeExact for IntervalSet<U> {
+    fn serialize_exact<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for interval in self.iter() {
+            seq.serialize_element(&ExactInterval(interval))?;
+        }
+        seq.end()
+    }
+}
+
+/// Accepts only sorted intervals with gaps between them, as an
+/// [`IntervalSet`] stores them.
+impl<U: Unit> DeserializeExact for IntervalSet<U> {
+    fn deserialize_exact<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = Vec::<RawInterval>::deserialize(deserializer)?;
+        let intervals = raw
+            .into_iter()
+            .map(RawInterval::into_interval)
+            .collect::<Result<Vec<Interval<U>>, D::Error>>()?;
+        let canonical = intervals.iter().all(|i| !i.is_empty())
+            && intervals
+                .windows(2)
+                .all(|w| w[0].end().value() < w[1].start().value());
+        if !canonical {
+            return Err(de::Error::custom(
+                "interval set is not sorted, disjoint and free of empty intervals",
+            ));
+        }
+        Ok(IntervalSet::from_sorted_unchecked(intervals))
+    }
+}
+
+/// The minimum gap and the index layout are not serialized.
+impl<U: Unit> SerializeExact for Schedule<U> {
+    fn serialize_exact<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Entry<'a, U: Unit>(&'a str, Interval<U>);
+
+        impl<U: Unit> Serialize for Entry<'_, U> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut s = serializer.serialize_struct("ScheduleEntry", 2)?;
+                s.serialize_field("task", self.0)?;
+                s.serialize_field("interval", &ExactInterval(&self.1))?;
+                s.end()
+            }
+        }
+
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for (id, interval) in self.iter() {
+            seq.serialize_element(&Entry(&id, interval))?;
+        }
+        seq.end()
+    }
+}
+
+/// Entries are checked by [`Schedule::add`], so duplicates and overlaps are
+/// errors.
+impl<U: Unit> DeserializeExact for Schedule<U> {
+    fn deserialize_exact<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Entry {
+            task: String,
+            interval: RawInterval,
+        }
+
+        struct ScheduleVisitor<U>(PhantomData<U>);
+
+        impl<'de, U: Unit> Visitor<'de> for ScheduleVisitor<U> {
+            type Value = Schedule<U>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a sequence of exact schedule entries")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Schedule<U>, A::Error> {
+                let mut schedule = Schedule::new();
+                while let Some(entry) = seq.next_element::<Entry>()? {
+                    let interval = entry.interval.into_interval()?;
+                    schedule
+                        .add(entry.task, interval)
+                        .map_err(de::Error::custom)?;
+                }
+                Ok(schedule)
+            }
+        }
+
+        deserializer.deserialize_seq(ScheduleVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::iv;
+    use qtty::Second;
+
+    #[test]
+    fn schedules_round_trip_bit_for_bit() {
+        // Endpoints that do not survive every decimal round trip.
+        let a = 0.1 + 0.2;
+        let b = a + f64::EPSILON * 4.0;
+        let mut schedule = Schedule::<Second>::new();
+        schedule.add("first", iv(-0.0, a)).unwrap();
+        schedule.add("second", iv(a, b)).unwrap();
+        schedule.add("open", iv(1e300, f64::INFINITY)).unwrap();
+
+        let json = serde_json::to_string(&Exact(&schedule)).unwrap();
+        assert!(json.starts_with(
+            r#"[{"task":"first","interval":{"start":"0x8000000000000000","end":"0x3fd3333333333334"}}"#
+        ));
+        let Exact(restored): Exact<Schedule<Second>> = serde_json::from_str(&json).unwrap();
+        let bits = |s: &Schedule<Second>| -> Vec<(String, u64, u64)> {
+            s.iter()
+                .map(|(id, i)| (id, i.start().value().to_bits(), i.end().value().to_bits()))
+                .collect()
+        };
+        assert_eq!(bits(&restored), bits(&schedule));
+
+        let set = IntervalSet::from(vec![iv(0.0, a), iv(b, 1.0)]);
+        let json = serde_json::to_string(&Exact(&set)).unwrap();
+        let Exact(back): Exact<IntervalSet<Second>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, set);
+    }
+
+    #[test]
+    fn strict_reading_rejects_anything_else() {
+        let read = |json: &str| serde_json::from_str::<Exact<Interval<Second>>>(json).map(|e| e.0);
+        assert_eq!(
+            read(r#"{"start":"0x0000000000000000","end":"0x4059000000000000"}"#).unwrap(),
+            iv(0.0, 100.0)
+        );
+        for bad in [
+            r#"{"start":0.0,"end":"0x4059000000000000"}"#,
+            r#"{"start":"0x0","end":"0x4059000000000000"}"#,
+            r#"{"start":"0x7ff8000000000000","end":"0x4059000000000000"}"#,
+            r#"{"start":"0x4059000000000000","end":"0x0000000000000000"}"#,
+            r#"{"start":"0x0000000000000000","end":"0x4059000000000000","x":1}"#,
+        ] {
+            assert!(read(bad).is_err(), "accepted {bad}");
+        }
+
+        let overlapping = r#"[{"start":"0x0000000000000000","end":"0x4059000000000000"},
+            {"start":"0x4049000000000000","end":"0x4069000000000000"}]"#;
+        assert!(serde_json::from_str::<Exact<IntervalSet<Second>>>(overlapping).is_err());
+        let clash = r#"[
+            {"task":"a","interval":{"start":"0x0000000000000000","end":"0x4059000000000000"}},
+            {"task":"b","interval":{"start":"0x4049000000000000","end":"0x4069000000000000"}}]"#;
+        assert!(serde_json::from_str::<Exact<Schedule<Second>>>(clash).is_err());
+    }
+
+    #[test]
+    fn works_as_a_field_attribute() {
+        #[derive(Serialize, Deserialize)]
+        struct Plan {
+            name: String,
+            #[serde(with = "crate::exact")]
+            night: Interval<Second>,
+        }
+
+        let plan = Plan {
+            name: "n1".into(),
+            night: iv(0.1, 0.7),
+        };
+        let json = serde_json::to_string(&plan).unwrap();
+        assert!(json.contains(r#""start":"0x3fb999999999999a""#));
+        let back: Plan = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.name, "n1");
+        assert_eq!(back.night, plan.night);
+    }
+}
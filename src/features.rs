@@ -0,0 +1,415 @@
+//! Numeric features of scheduling decisions for classical ML.
+//!
+//! [`FeatureContext`] turns a task, in the context of a solution space, a
+//! partial schedule and a horizon, into a flat [`FeatureVector`]: window
+//! statistics, slack, contention with other unscheduled tasks, priority
+//! percentile and dependency counts. The layout is fixed by
+//! [`FEATURE_NAMES`] and versioned by [`FEATURE_SCHEMA_VERSION`], so vectors
+//! can be exported to train a ranker (e.g. gradient-boosted trees) outside
+//! the crate, and the trained model plugged back in through [`FeatureScore`].
+//!
+//! Times are expressed as fractions of the horizon length so that features
+//! are comparable across problems. Features that do not exist for a task —
+//! earliest and latest start of a task that fits nowhere — are NaN, which
+//! tree learners treat as missing.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::io::Write;
+//! # use qtty::Second;
+//! # use virolai::algorithms::GreedyScheduler;
+//! # use virolai::features::{FeatureContext, FeatureScore};
+//! # use virolai::schedule::Schedule;
+//! # use virolai::scheduling_block::Task;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # struct Model;
+//! # impl Model { fn predict(&self, _: &[f64]) -> f64 { 0.0 } }
+//! # let model = Model;
+//! # let mut out = Vec::new();
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let space = instance.solution_space;
+//! let empty = Schedule::new();
+//! let context = FeatureContext::new(&blocks, &space, &empty, horizon);
+//! for (id, _) in blocks[0].tasks() {
+//!     writeln!(out, "{id},{}", context.extract(id).unwrap())?;
+//! }
+//!
+//! // Later, with a trained model:
+//! let scheduler = GreedyScheduler::with_score(FeatureScore::new(context, |f| model.predict(f.values())));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use qtty::{Quantity, Unit};
+
+use crate::algorithms::est::metrics::TaskMetrics;
+use crate::algorithms::greedy::GreedyScore;
+use crate::algorithms::scoring::{ScoringContext, TaskScorer};
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+use crate::Id;
+
+/// Version of the feature layout; bumped whenever [`FEATURE_NAMES`] changes.
+pub const FEATURE_SCHEMA_VERSION: u32 = 1;
+
+/// Number of features in a [`FeatureVector`].
+pub const FEATURE_COUNT: usize = 16;
+
+/// Names of the features, in vector order.
+pub const FEATURE_NAMES: [&str; FEATURE_COUNT] = [
+    "size",
+    "priority",
+    "priority_percentile",
+    "window_count",
+    "window_total",
+    "window_max",
+    "earliest_start",
+    "latest_start",
+    "flexibility",
+    "free_capacity",
+    "slack",
+    "contention",
+    "predecessors",
+    "successors",
+    "unscheduled_predecessors",
+    "scheduled",
+];
+
+/// Features of one task, laid out as in [`FEATURE_NAMES`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeatureVector([f64; FEATURE_COUNT]);
+
+impl FeatureVector {
+    /// The feature values.
+    pub fn values(&self) -> &[f64; FEATURE_COUNT] {
+        &self.0
+    }
+
+    /// Returns the feature called `name`.
+    pub fn get(&self, name: &str) -> Option<f64> {
+        FEATURE_NAMES
+            .iter()
+            .position(|n| *n == name)
+            .map(|i| self.0[i])
+    }
+
+    /// Returns `(name, value)` pairs in vector order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, f64)> + '_ {
+        FEATURE_NAMES.iter().copied().zip(self.0.iter().copied())
+    }
+}
+
+/// Comma-separated values, in the order of [`FEATURE_NAMES`].
+impl Display for FeatureVector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, value) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Problem snapshot features are extracted from.
+///
+/// Window clipping, contention and the priority distribution are computed
+/// once on construction; [`extract`](Self::extract) is then cheap.
+#[derive(Debug)]
+pub struct FeatureContext<'a, T, U, D, E>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    blocks: &'a [SchedulingBlock<T, U, D, E>],
+    schedule: &'a Schedule<U>,
+    horizon: Horizon<U>,
+    /// Windows clipped to the horizon, per task in the solution space.
+    windows: HashMap<Id, Vec<Interval<U>>>,
+    /// Time-weighted number of other unscheduled tasks sharing each task's windows.
+    contention: HashMap<Id, f64>,
+    /// All task priorities, sorted.
+    priorities: Vec<i32>,
+}
+
+impl<'a, T, U, D, E> FeatureContext<'a, T, U, D, E>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    /// Builds the context for the tasks of `blocks`.
+    ///
+    /// `schedule` holds the decisions made so far; pass an empty schedule to
+    /// extract features before scheduling.
+    pub fn new(
+        blocks: &'a [SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        schedule: &'a Schedule<U>,
+        horizon: Horizon<U>,
+    ) -> Self {
+        let tasks = || blocks.iter().flat_map(|b| b.tasks());
+        let windows: HashMap<Id, Vec<Interval<U>>> = tasks()
+            .filter_map(|(id, _)| {
+                let set = solution_space.get_intervals(id)?;
+                let clipped = set
+                    .iter()
+                    .filter_map(|w| w.intersection(&horizon.interval()))
+                    .collect();
+                Some((id.to_string(), clipped))
+            })
+            .collect();
+
+        let open: Vec<(&Id, &Vec<Interval<U>>)> = windows
+            .iter()
+            .filter(|(id, _)| !schedule.contains_task(id))
+            .collect();
+        let contention = windows
+            .iter()
+            .map(|(id, own)| {
+                let total: f64 = own.iter().map(|w| w.duration().value()).sum();
+                let shared: f64 = open
+                    .iter()
+                    .filter(|(other, _)| *other != id)
+                    .map(|(_, theirs)| overlap(own, theirs))
+                    .sum();
+                let value = if total > 0.0 { shared / total } else { 0.0 };
+                (id.clone(), value)
+            })
+            .collect();
+
+        let mut priorities: Vec<i32> = tasks().map(|(_, t)| t.priority()).collect();
+        priorities.sort_unstable();
+
+        Self {
+            blocks,
+            schedule,
+            horizon,
+            windows,
+            contention,
+            priorities,
+        }
+    }
+
+    /// Extracts the features of task `id`, or `None` if no block has it.
+    pub fn extract(&self, id: &str) -> Option<FeatureVector> {
+        let (block, task) = self
+            .blocks
+            .iter()
+            .find_map(|b| b.task_by_id(id).map(|t| (b, t)))?;
+        let length = self.horizon.duration().value();
+        let scale = |v: f64| if length > 0.0 { v / length } else { 0.0 };
+        let position = |q: Quantity<U>| scale(q.value() - self.horizon.start().value());
+
+        let size = task.size_on_axis().value();
+        let windows = self.windows.get(id).map_or(&[][..], Vec::as_slice);
+        let fitting: Vec<&Interval<U>> = windows
+            .iter()
+            .filter(|w| w.duration().value() >= size)
+            .collect();
+        let window_total: f64 = windows.iter().map(|w| w.duration().value()).sum();
+        let window_max = windows
+            .iter()
+            .map(|w| w.duration().value())
+            .fold(0.0, f64::max);
+        let capacity: f64 = fitting.iter().map(|w| w.duration().value()).sum();
+        let earliest = fitting.first().map_or(f64::NAN, |w| position(w.start()));
+        let latest = fitting
+            .last()
+            .map_or(f64::NAN, |w| position(w.end()) - scale(size));
+
+        let taken: f64 = windows
+            .iter()
+            .map(|w| {
+                self.schedule
+                    .conflicts_ref(*w)
+                    .into_iter()
+                    .flatten()
+                    .filter(|(other, _)| *other != id)
+                    .filter_map(|(_, iv)| w.intersection(&iv))
+                    .map(|iv| iv.duration().value())
+                    .sum::<f64>()
+            })
+            .sum();
+        let free = window_total - taken;
+
+        let below = self.priorities.partition_point(|&p| p <= task.priority());
+        let percentile = below as f64 / self.priorities.len().max(1) as f64;
+
+        let node = block.node_of(id).expect("task found by ID");
+        let predecessors = block.predecessors(node);
+        let unscheduled_predecessors = predecessors
+            .iter()
+            .filter_map(|&n| block.id_of(n))
+            .filter(|p| !self.schedule.contains_task(p))
+            .count();
+
+        Some(FeatureVector([
+            scale(size),
+            task.priority() as f64,
+            percentile,
+            fitting.len() as f64,
+            scale(window_total),
+            scale(window_max),
+            earliest,
+            latest,
+            if size > 0.0 { capacity / size } else { 0.0 },
+            scale(free),
+            scale(free - size),
+            self.contention.get(id).copied().unwrap_or(0.0),
+            predecessors.len() as f64,
+            block.successors(node).len() as f64,
+            unscheduled_predecessors as f64,
+            f64::from(u8::from(self.schedule.contains_task(id))),
+        ]))
+    }
+}
+
+/// Total overlap between two sorted interval lists.
+fn overlap<U: Unit>(a: &[Interval<U>], b: &[Interval<U>]) -> f64 {
+    let (mut i, mut j, mut total) = (0, 0, 0.0);
+    while i < a.len() && j < b.len() {
+        if let Some(shared) = a[i].intersection(&b[j]) {
+            total += shared.duration().value();
+        }
+        if a[i].end().value() < b[j].end().value() {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    total
+}
+
+/// Greedy score computed by an external model from task features.
+///
+/// Tasks missing from the context score NaN, which the greedy scheduler
+/// orders last.
+pub struct FeatureScore<'a, T, U, D, E, M>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    context: FeatureContext<'a, T, U, D, E>,
+    model: M,
+}
+
+impl<'a, T, U, D, E, M> FeatureScore<'a, T, U, D, E, M>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+    M: Fn(&FeatureVector) -> f64,
+{
+    /// Scores tasks with `model` applied to their features in `context`.
+    pub fn new(context: FeatureContext<'a, T, U, D, E>, model: M) -> Self {
+        Self { context, model }
+    }
+}
+
+impl<T, U, D, E, M> GreedyScore<T, U> for FeatureScore<'_, T, U, D, E, M>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+    M: Fn(&FeatureVector) -> f64,
+{
+    fn score(&self, _task: &T, task_id: &str, _capacity: Quantity<U>) -> f64 {
+        self.context
+            .extract(task_id)
+            .map_or(f64::NAN, |features| (self.model)(&features))
+    }
+}
+
+impl<T, U, D, E, M> TaskScorer<T, U> for FeatureScore<'_, T, U, D, E, M>
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+    M: Fn(&FeatureVector) -> f64,
+{
+    fn score(
+        &self,
+        task_id: &str,
+        _metrics: &TaskMetrics<U>,
+        context: &ScoringContext<'_, T, U>,
+    ) -> f64 {
+        GreedyScore::score(self, context.task, task_id, context.capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{GreedyScheduler, SchedulingAlgorithm};
+    use crate::test_utils::{block_and_space, hz, iv, TestTask};
+    use qtty::Second;
+
+    /// a: [0, 50), b: [25, 100) (priority 5), c: [60, 70) too small for it.
+    fn problem() -> (
+        Vec<SchedulingBlock<TestTask, Second>>,
+        SolutionSpace<Second>,
+    ) {
+        let (mut block, space) = block_and_space(&[
+            ("a", 10.0, 1, iv(0.0, 50.0)),
+            ("b", 20.0, 5, iv(25.0, 100.0)),
+            ("c", 20.0, 3, iv(60.0, 70.0)),
+        ]);
+        block
+            .add_dependencies(vec![("a".to_string(), "b".to_string(), ())])
+            .unwrap();
+        (vec![block], space)
+    }
+
+    #[test]
+    fn features_follow_the_schema() {
+        let (blocks, space) = problem();
+        let mut schedule = Schedule::new();
+        schedule.add("a", iv(30.0, 40.0)).unwrap();
+        let context = FeatureContext::new(&blocks, &space, &schedule, hz(0.0, 100.0));
+
+        let b = context.extract("b").unwrap();
+        assert_eq!(b.values().len(), FEATURE_NAMES.len());
+        assert_eq!(b.get("size"), Some(0.2));
+        assert_eq!(b.get("priority_percentile"), Some(1.0));
+        assert_eq!(b.get("earliest_start"), Some(0.25));
+        assert_eq!(b.get("latest_start"), Some(0.8));
+        // a takes 10 s of b's 75 s window.
+        assert_eq!(b.get("free_capacity"), Some(0.65));
+        assert_eq!(b.get("slack"), Some(0.45));
+        // Only c is still open and shares 10 s of b's window.
+        assert_eq!(b.get("contention"), Some(10.0 / 75.0));
+        assert_eq!(b.get("predecessors"), Some(1.0));
+        assert_eq!(b.get("unscheduled_predecessors"), Some(0.0));
+        assert_eq!(b.get("scheduled"), Some(0.0));
+
+        let c = context.extract("c").unwrap();
+        assert_eq!(c.get("window_count"), Some(0.0));
+        assert!(c.get("earliest_start").unwrap().is_nan());
+        assert!(context.extract("zzz").is_none());
+        assert_eq!(b.to_string().split(',').count(), FEATURE_COUNT);
+    }
+
+    #[test]
+    fn external_model_drives_greedy_order() {
+        let (blocks, space) = problem();
+        let empty = Schedule::new();
+        // A "model" preferring the longest window: b before a, so b takes
+        // [25, 45) and a still fits in [0, 25).
+        let context = FeatureContext::new(&blocks, &space, &empty, hz(0.0, 100.0));
+        let score = FeatureScore::new(context, |f: &FeatureVector| f.values()[5]);
+        let schedule = GreedyScheduler::with_score(score).schedule(&blocks, &space, hz(0.0, 100.0));
+        assert_eq!(schedule.get_interval("b"), Some(iv(25.0, 45.0)));
+        assert_eq!(schedule.get_interval("a"), Some(iv(0.0, 10.0)));
+    }
+}
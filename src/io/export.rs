@@ -0,0 +1,372 @@
+//! Feasibility windows for calendars and charts.
+//!
+//! A schedule says what will run; observers often want to know when their
+//! target *can* run, whatever got scheduled. These exporters render the
+//! windows of a [`SolutionSpace`] entry, a task's or a resource's:
+//!
+//! - [`windows_to_ics`]: an iCalendar `VFREEBUSY` per entry, free while a
+//!   window is open and busy in between, for calendar applications.
+//! - [`windows_to_svg`]: a band chart with one lane per entry.
+//!
+//! Both clip the windows to a finite range, as a calendar cannot show an
+//! unbounded window.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::io::export::{windows_to_ics, windows_to_svg};
+//! # use std::fs::File;
+//! # use qtty::{Quantity, Second};
+//! # use virolai::display::DisplayOptions;
+//! # use virolai::solution_space::{Interval, SolutionSpace};
+//! # let space: SolutionSpace<Second> = SolutionSpace::new();
+//! # let dusk_unix = Quantity::new(1_772_402_400.0);
+//!
+//! let night = Interval::from_f64(0.0, 36_000.0);
+//! windows_to_ics(&space, &["m31", "m42"], night, dusk_unix, File::create("m31.ics")?)?;
+//! std::fs::write("m31.svg", windows_to_svg(&space, &["m31", "m42"], night, DisplayOptions::humanized()))?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::fmt::Write as _;
+use std::io;
+
+use qtty::{Quantity, Second, Unit};
+
+use crate::display::DisplayOptions;
+use crate::schedule::diff::escape;
+use crate::solution_space::{Interval, SolutionSpace};
+use crate::units::SameDim;
+
+/// Writes the windows of `ids` within `range` as iCalendar (RFC 5545)
+/// free/busy information.
+///
+/// Each ID gets a `VFREEBUSY` spanning `range`, with `UID` and `COMMENT`
+/// set to the ID, one `FREEBUSY;FBTYPE=FREE` period per window and one
+/// `FREEBUSY;FBTYPE=BUSY` period per stretch between windows. IDs without
+/// an entry are busy throughout. Axis positions are placed in time relative
+/// to `origin`, the Unix time of axis zero as in
+/// [`from_ics`](crate::io::import::from_ics), and written in UTC rounded to
+/// the second. `DTSTAMP` is the start of `range`, so the output depends
+/// only on the inputs.
+///
+/// # Errors
+///
+/// - [`io::ErrorKind::InvalidInput`] if `range` is not finite
+/// - errors of `writer`
+pub fn windows_to_ics<U, W>(
+    space: &SolutionSpace<U>,
+    ids: &[&str],
+    range: Interval<U>,
+    origin: Quantity<Second>,
+    mut writer: W,
+) -> io::Result<()>
+where
+    U: SameDim<Second>,
+    W: io::Write,
+{
+    if !range.start().value().is_finite() || !range.end().value().is_finite() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "free/busy export needs a finite range",
+        ));
+    }
+    let time = |q: Quantity<U>| utc(q.to::<Second>().value() + origin.value());
+    let period = |iv: Interval<U>| format!("{}/{}", time(iv.start()), time(iv.end()));
+
+    let mut ics = String::new();
+    line(&mut ics, "BEGIN:VCALENDAR");
+    line(&mut ics, "VERSION:2.0");
+    line(&mut ics, "PRODID:-//virolai//feasibility windows//EN");
+    for id in ids {
+        line(&mut ics, "BEGIN:VFREEBUSY");
+        line(&mut ics, &format!("UID:{}", escape_text(id)));
+        line(&mut ics, &format!("DTSTAMP:{}", time(range.start())));
+        line(&mut ics, &format!("DTSTART:{}", time(range.start())));
+        line(&mut ics, &format!("DTEND:{}", time(range.end())));
+        line(&mut ics, &format!("COMMENT:{}", escape_text(id)));
+        let mut cursor = range.start();
+        for window in clipped(space, id, range) {
+            if window.start().value() > cursor.value() {
+                let busy = Interval::new(cursor, window.start());
+                line(&mut ics, &format!("FREEBUSY;FBTYPE=BUSY:{}", period(busy)));
+            }
+            line(
+                &mut ics,
+                &format!("FREEBUSY;FBTYPE=FREE:{}", period(window)),
+            );
+            cursor = window.end();
+        }
+        if range.end().value() > cursor.value() {
+            let busy = Interval::new(cursor, range.end());
+            line(&mut ics, &format!("FREEBUSY;FBTYPE=BUSY:{}", period(busy)));
+        }
+        line(&mut ics, "END:VFREEBUSY");
+    }
+    line(&mut ics, "END:VCALENDAR");
+    writer.write_all(ics.as_bytes())
+}
+
+const WIDTH: f64 = 960.0;
+const LABEL_WIDTH: f64 = 160.0;
+const MARGIN: f64 = 16.0;
+const AXIS_HEIGHT: f64 = 28.0;
+const LANE_HEIGHT: f64 = 24.0;
+const BAR_HEIGHT: f64 = 14.0;
+const TICKS: usize = 5;
+
+/// Renders the windows of `ids` within `range` as a standalone SVG band
+/// chart.
+///
+/// IDs get one lane each, in the given order, with a band per window.
+/// Rated windows are shaded by their quality relative to the best window of
+/// the lane; every band has a tooltip with its interval and quality. Axis
+/// labels are formatted with `options`. An unbounded `range` is narrowed to
+/// the finite extent of the windows.
+pub fn windows_to_svg<U: Unit>(
+    space: &SolutionSpace<U>,
+    ids: &[&str],
+    range: Interval<U>,
+    options: DisplayOptions,
+) -> String {
+    let lanes: Vec<Vec<(Interval<U>, f64)>> = ids
+        .iter()
+        .map(|id| {
+            let quality = space.windows_with_quality(id);
+            quality
+                .filter_map(|(w, q)| w.intersection(&range).map(|w| (w, q)))
+                .collect()
+        })
+        .collect();
+    let (lo, hi) = extent(range, &lanes);
+    let plot = WIDTH - LABEL_WIDTH - MARGIN;
+    let x = |v: f64| LABEL_WIDTH + (v.clamp(lo, hi) - lo) / (hi - lo) * plot;
+    let height = AXIS_HEIGHT + LANE_HEIGHT * lanes.len() as f64 + MARGIN;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" font-family="sans-serif" font-size="12">"#
+    );
+    for i in 0..=TICKS {
+        let v = lo + (hi - lo) * i as f64 / TICKS as f64;
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{x:.1}" y1="{top}" x2="{x:.1}" y2="{bottom:.1}" stroke="#eee"/><text x="{x:.1}" y="{label}" text-anchor="middle">{text}</text>"##,
+            x = x(v),
+            top = AXIS_HEIGHT - 4.0,
+            bottom = height - MARGIN,
+            label = AXIS_HEIGHT - 10.0,
+            text = escape(&options.quantity(Quantity::<U>::new(v))),
+        );
+    }
+
+    for (i, (id, windows)) in ids.iter().zip(&lanes).enumerate() {
+        let top = AXIS_HEIGHT + LANE_HEIGHT * i as f64;
+        let bar = top + (LANE_HEIGHT - BAR_HEIGHT) / 2.0;
+        let rated = space.window_quality(id).is_some();
+        let best = windows.iter().map(|(_, q)| *q).fold(0.0, f64::max);
+        let _ = writeln!(
+            svg,
+            r#"<g class="windows"><text x="{:.1}" y="{:.1}" text-anchor="end" dominant-baseline="middle">{}</text>"#,
+            LABEL_WIDTH - 6.0,
+            top + LANE_HEIGHT / 2.0,
+            escape(id),
+        );
+        for &(window, quality) in windows {
+            let (x0, x1) = (x(window.start().value()), x(window.end().value()));
+            let opacity = if rated && best > 0.0 {
+                (quality / best).clamp(0.15, 1.0)
+            } else {
+                1.0
+            };
+            let mut title = format!("{}: {}", id, options.interval(window));
+            if rated {
+                let _ = write!(title, " (quality {quality:.2})");
+            }
+            let _ = writeln!(
+                svg,
+                r##"<rect x="{x0:.1}" y="{bar:.1}" width="{:.1}" height="{BAR_HEIGHT}" fill="#1565c0" fill-opacity="{opacity:.2}"><title>{}</title></rect>"##,
+                (x1 - x0).max(1.0),
+                escape(&title),
+            );
+        }
+        svg.push_str("</g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// The windows of `id` within `range`, in time order.
+fn clipped<'a, U: Unit>(
+    space: &'a SolutionSpace<U>,
+    id: &str,
+    range: Interval<U>,
+) -> impl Iterator<Item = Interval<U>> + 'a {
+    space
+        .get_intervals(id)
+        .into_iter()
+        .flat_map(|windows| windows.iter())
+        .filter_map(move |w| w.intersection(&range))
+}
+
+/// Finite range to draw: `range` where bounded, else the windows' extent.
+fn extent<U: Unit>(range: Interval<U>, lanes: &[Vec<(Interval<U>, f64)>]) -> (f64, f64) {
+    let (mut lo, mut hi) = (range.start().value(), range.end().value());
+    let finite = lanes
+        .iter()
+        .flatten()
+        .flat_map(|(w, _)| [w.start().value(), w.end().value()])
+        .filter(|v| v.is_finite());
+    let (min, max) = finite.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    });
+    if !lo.is_finite() {
+        lo = min;
+    }
+    if !hi.is_finite() {
+        hi = max;
+    }
+    if lo < hi {
+        (lo, hi)
+    } else if lo.is_finite() {
+        (lo, lo + 1.0)
+    } else {
+        (0.0, 1.0)
+    }
+}
+
+/// Appends a content line, folded at 75 octets, with a CRLF ending.
+fn line(ics: &mut String, content: &str) {
+    let mut width = 0;
+    for c in content.chars() {
+        if width + c.len_utf8() > 75 {
+            ics.push_str("\r\n ");
+            width = 1;
+        }
+        ics.push(c);
+        width += c.len_utf8();
+    }
+    ics.push_str("\r\n");
+}
+
+/// Escapes text for iCalendar `TEXT` values.
+fn escape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Formats a Unix time as an iCalendar UTC date-time, `YYYYMMDDTHHMMSSZ`.
+fn utc(unix: f64) -> String {
+    let seconds = unix.round() as i64;
+    let (days, time) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Proleptic Gregorian date of a day count since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::import::from_ics;
+    use qtty::Minute;
+
+    fn iv(start: f64, end: f64) -> Interval<Minute> {
+        Interval::from_f64(start, end)
+    }
+
+    #[test]
+    fn windows_as_free_busy_and_bands() {
+        let mut space = SolutionSpace::<Minute>::new();
+        space.set_intervals("m31", vec![iv(-30.0, 60.0), iv(120.0, 150.0)]);
+        space.add_interval("m42, core", iv(200.0, f64::INFINITY));
+        space.set_window_quality("m31", vec![2.0, 1.0]);
+        let night = iv(0.0, 240.0);
+        // 2026-03-01T20:00:00Z
+        let dusk = Quantity::<Second>::new(1_772_395_200.0);
+
+        let mut out = Vec::new();
+        windows_to_ics(&space, &["m31", "m42, core", "gone"], night, dusk, &mut out).unwrap();
+        let ics = String::from_utf8(out).unwrap();
+        assert_eq!(ics.matches('\n').count(), ics.matches("\r\n").count());
+        let m31: Vec<&str> = ics
+            .split("\r\n")
+            .skip_while(|l| *l != "UID:m31")
+            .take_while(|l| *l != "END:VFREEBUSY")
+            .collect();
+        assert_eq!(
+            m31,
+            [
+                "UID:m31",
+                "DTSTAMP:20260301T200000Z",
+                "DTSTART:20260301T200000Z",
+                "DTEND:20260302T000000Z",
+                "COMMENT:m31",
+                "FREEBUSY;FBTYPE=FREE:20260301T200000Z/20260301T210000Z",
+                "FREEBUSY;FBTYPE=BUSY:20260301T210000Z/20260301T220000Z",
+                "FREEBUSY;FBTYPE=FREE:20260301T220000Z/20260301T223000Z",
+                "FREEBUSY;FBTYPE=BUSY:20260301T223000Z/20260302T000000Z",
+            ]
+        );
+        assert!(ics.contains("UID:m42\\, core\r\n"));
+        assert!(ics.contains("FREEBUSY;FBTYPE=FREE:20260301T232000Z/20260302T000000Z\r\n"));
+        assert!(ics.contains(
+            "UID:gone\r\nDTSTAMP:20260301T200000Z\r\nDTSTART:20260301T200000Z\r\n\
+             DTEND:20260302T000000Z\r\nCOMMENT:gone\r\n\
+             FREEBUSY;FBTYPE=BUSY:20260301T200000Z/20260302T000000Z\r\n"
+        ));
+        // Free/busy components are not events.
+        let reread = from_ics::<Minute, _>(ics.as_bytes(), dusk).unwrap();
+        assert_eq!(reread.tasks().count(), 0);
+
+        let unbounded = iv(0.0, f64::INFINITY);
+        let err = windows_to_ics(&space, &["m31"], unbounded, dusk, Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let svg = windows_to_svg(&space, &["m31", "m42, core"], night, DisplayOptions::new());
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.contains("m31: [0.000, 60.000] (quality 2.00)"));
+        assert!(svg.contains(r#"fill-opacity="0.50""#));
+        assert!(svg.contains("m42, core: [200.000, 240.000]</title>"));
+        // The lower-rated m31 window is drawn from 120 to 150 of 240.
+        assert!(svg.contains(r#"<rect x="552.0""#));
+    }
+
+    #[test]
+    fn civil_dates_round_trip() {
+        assert_eq!(utc(0.0), "19700101T000000Z");
+        assert_eq!(utc(-1.0), "19691231T235959Z");
+        assert_eq!(utc(951_782_400.0), "20000229T000000Z");
+        assert_eq!(civil_from_days(-719_468), (0, 3, 1));
+    }
+}
@@ -0,0 +1,211 @@
+//! CSV task lists.
+
+use std::io::BufRead;
+
+use qtty::Unit;
+
+use super::{build, FieldMapping, ImportError, ImportedTask, Record};
+use crate::schedule::io::split;
+use crate::scheduling_block::SchedulingBlock;
+
+/// Reads a task list from CSV with a header row.
+///
+/// Columns are found by the names in `mapping`, in any order; unknown
+/// columns are ignored. Rows repeating an ID add a window and dependencies
+/// to the task of the first such row, so a task with several windows takes
+/// one row per window:
+///
+/// ```text
+/// id,name,duration,priority,window_start,window_end,depends_on
+/// flat,Flat field,600,2,0,3600,
+/// m31,"M31, deep",3600,8,7200,14400,flat
+/// m31,,,,18000,25200,
+/// ```
+///
+/// Empty fields count as missing. Dependencies are separated by `;`.
+///
+/// # Errors
+///
+/// - [`ImportError::MissingField`] if the header lacks the ID or duration
+///   column, or has only one of the window columns, or if a task has no
+///   duration on any of its rows
+/// - [`ImportError::Parse`] for malformed rows, invalid numbers, windows
+///   ending before they start and unknown dependencies
+/// - [`ImportError::Scheduling`] if the dependencies form a cycle
+pub fn from_csv<U, R>(
+    reader: R,
+    mapping: &FieldMapping,
+) -> Result<SchedulingBlock<ImportedTask<U>, U>, ImportError>
+where
+    U: Unit + Send + Sync,
+    R: BufRead,
+{
+    let mut records = Vec::new();
+    let mut columns: Option<Columns> = None;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = split(&line).map_err(|message| ImportError::Parse {
+            line: line_no,
+            message,
+        })?;
+
+        let Some(cols) = &columns else {
+            columns = Some(Columns::from_header(&fields, mapping, line_no)?);
+            continue;
+        };
+
+        let field = |i: Option<usize>| {
+            i.and_then(|i| fields.get(i))
+                .map(|f| f.trim())
+                .filter(|f| !f.is_empty())
+        };
+        let Some(id) = field(Some(cols.id)) else {
+            return Err(ImportError::Parse {
+                line: line_no,
+                message: "empty task ID".to_string(),
+            });
+        };
+
+        let mut record = Record::new(line_no, id.to_string());
+        let number = |record: &Record, name: &str, value: &str| {
+            value
+                .parse::<f64>()
+                .map_err(|_| record.error(format!("invalid {name} {value:?}")))
+        };
+        record.name = field(cols.name).map(str::to_string);
+        if let Some(value) = field(Some(cols.duration)) {
+            let duration = number(&record, "duration", value)?;
+            record.set_duration(duration)?;
+        }
+        if let Some(value) = field(cols.priority) {
+            let priority = value
+                .parse()
+                .map_err(|_| record.error(format!("invalid priority {value:?}")))?;
+            record.priority = Some(priority);
+        }
+        match cols.window.map(|(s, e)| (field(Some(s)), field(Some(e)))) {
+            Some((Some(start), Some(end))) => {
+                let start = number(&record, "window start", start)?;
+                let end = number(&record, "window end", end)?;
+                record.push_window(start, end)?;
+            }
+            Some((Some(_), None)) | Some((None, Some(_))) => {
+                return Err(record.error("window needs both a start and an end"));
+            }
+            _ => {}
+        }
+        if let Some(deps) = field(cols.depends_on) {
+            record.depends_on = deps
+                .split(';')
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        records.push(record);
+    }
+
+    build(records)
+}
+
+/// Column positions found in the header.
+struct Columns {
+    id: usize,
+    name: Option<usize>,
+    duration: usize,
+    priority: Option<usize>,
+    window: Option<(usize, usize)>,
+    depends_on: Option<usize>,
+}
+
+impl Columns {
+    fn from_header(
+        fields: &[String],
+        mapping: &FieldMapping,
+        line: usize,
+    ) -> Result<Self, ImportError> {
+        let find = |name: &str| {
+            fields
+                .iter()
+                .position(|f| f.trim().eq_ignore_ascii_case(name))
+        };
+        let require = |name: &str| {
+            find(name).ok_or_else(|| ImportError::MissingField {
+                line,
+                field: name.to_string(),
+            })
+        };
+        let window = match (find(&mapping.window_start), find(&mapping.window_end)) {
+            (None, None) => None,
+            _ => Some((
+                require(&mapping.window_start)?,
+                require(&mapping.window_end)?,
+            )),
+        };
+        Ok(Self {
+            id: require(&mapping.id)?,
+            name: find(&mapping.name),
+            duration: require(&mapping.duration)?,
+            priority: find(&mapping.priority),
+            window,
+            depends_on: find(&mapping.depends_on),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling_block::Task;
+    use crate::test_utils::{iv, q};
+    use qtty::Second;
+
+    #[test]
+    fn rows_with_the_same_id_add_windows() {
+        let csv = "\
+id,name,duration,priority,window_start,window_end,depends_on
+flat,Flat field,600,2,0,3600,
+m31,\"M31, deep\",3600,8,7200,14400,flat
+m31,,,,18000,25200,
+
+dark,,300,,,,flat; m31
+";
+        let block = from_csv::<Second, _>(csv.as_bytes(), &FieldMapping::default()).unwrap();
+        assert_eq!(block.task_count(), 3);
+
+        let m31 = block.node_of("m31").unwrap();
+        let task = block.get_task(m31).unwrap();
+        assert_eq!(task.name(), "M31, deep");
+        assert_eq!(task.size(), q(3600.0));
+        assert_eq!(task.priority(), 8);
+        assert_eq!(task.windows(), [iv(7200.0, 14400.0), iv(18000.0, 25200.0)]);
+
+        let dark = block.node_of("dark").unwrap();
+        assert_eq!(block.get_task(dark).unwrap().name(), "dark");
+        assert_eq!(block.predecessors(dark).len(), 2);
+    }
+
+    #[test]
+    fn columns_are_found_through_the_mapping() {
+        let mapping = FieldMapping::default()
+            .with_id("Target")
+            .with_duration("Exposure")
+            .with_window("Rise", "Set");
+        let csv = "Exposure,Target,Rise,Set\n120,vega,10,500\n";
+        let block = from_csv::<Second, _>(csv.as_bytes(), &mapping).unwrap();
+        let vega = block.get_task(block.node_of("vega").unwrap()).unwrap();
+        assert_eq!(vega.windows(), [iv(10.0, 500.0)]);
+
+        let err = from_csv::<Second, _>(csv.as_bytes(), &FieldMapping::default()).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: missing the \"id\" field");
+
+        let bad = "Target,Exposure,Rise,Set\nvega,long,10,500\n";
+        let err = from_csv::<Second, _>(bad.as_bytes(), &mapping).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: invalid duration \"long\"");
+    }
+}
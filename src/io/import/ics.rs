@@ -0,0 +1,456 @@
+//! iCalendar events.
+
+use std::collections::HashSet;
+use std::io::BufRead;
+
+use qtty::{Quantity, Second};
+
+use super::{build, ImportError, ImportedTask, Record};
+use crate::scheduling_block::{SchedulingBlock, SchedulingError};
+use crate::units::SameDim;
+
+/// Reads the `VEVENT`s of an iCalendar (RFC 5545) stream as fixed tasks.
+///
+/// Each event becomes a task whose only window is the event itself, so it
+/// can only be placed where the calendar has it. Times are placed on the
+/// axis relative to `origin`, the Unix time of axis zero.
+///
+/// | Property                | Task field                        |
+/// |-------------------------|-----------------------------------|
+/// | `UID`                   | ID (`event-<n>` if missing)       |
+/// | `SUMMARY`               | name (the ID if missing)          |
+/// | `DTSTART`               | window start                      |
+/// | `DTEND` or `DURATION`   | window end                        |
+/// | `PRIORITY`              | `10 - PRIORITY` for 1–9, else 0   |
+///
+/// `PRIORITY` is inverted because iCalendar ranks 1 as the highest.
+/// Events without an end last one day if `DTSTART` is a date and nothing
+/// otherwise. Cancelled events and properties of nested components such as
+/// alarms are ignored. Times must be UTC (`20260314T213000Z`) or dates, which
+/// are read as UTC days; time zones are not resolved, so times with a `TZID`
+/// parameter and floating local times are rejected rather than shifted by an
+/// unknown offset.
+///
+/// # Errors
+///
+/// - [`ImportError::MissingField`] for events without `DTSTART`
+/// - [`ImportError::Parse`] for malformed dates and durations, `TZID` and
+///   floating times, events ending before they start and unterminated events
+/// - [`ImportError::Scheduling`] for repeated `UID`s, including the
+///   `RECURRENCE-ID` overrides of a recurring event
+pub fn from_ics<U, R>(
+    reader: R,
+    origin: Quantity<Second>,
+) -> Result<SchedulingBlock<ImportedTask<U>, U>, ImportError>
+where
+    U: SameDim<Second> + Send + Sync,
+    R: BufRead,
+{
+    let to_axis = |unix: f64| {
+        Quantity::<Second>::new(unix - origin.value())
+            .to::<U>()
+            .value()
+    };
+    let mut records = Vec::new();
+    let mut ids = HashSet::new();
+    let mut event: Option<Event> = None;
+
+    for (line_no, line) in unfold(reader)? {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+        let Some(current) = &mut event else {
+            if name == "BEGIN" && value.eq_ignore_ascii_case("VEVENT") {
+                event = Some(Event::new(line_no));
+            }
+            continue;
+        };
+        let error = |message: String| ImportError::Parse {
+            line: line_no,
+            message,
+        };
+        match name.as_str() {
+            "BEGIN" => current.nesting += 1,
+            "END" if current.nesting > 0 => current.nesting -= 1,
+            "END" => {
+                let finished = event.take().expect("inside an event");
+                if let Some(record) = finished.into_record(records.len(), to_axis)? {
+                    // `build` would merge events sharing a UID into one task
+                    if !ids.insert(record.id.clone()) {
+                        return Err(SchedulingError::DuplicateId(record.id).into());
+                    }
+                    records.push(record);
+                }
+            }
+            _ if current.nesting > 0 => {}
+            "UID" => current.uid = Some(unescape(value)),
+            "SUMMARY" => current.summary = Some(unescape(value)),
+            "STATUS" => current.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            "PRIORITY" => {
+                let priority = value
+                    .trim()
+                    .parse::<i32>()
+                    .map_err(|_| error(format!("invalid priority {value:?}")))?;
+                current.priority = (1..=9).contains(&priority).then(|| 10 - priority);
+            }
+            "DTSTART" => {
+                let time = parse_time(value, params).map_err(error)?;
+                current.all_day = !value.contains('T');
+                current.start = Some(time);
+            }
+            "DTEND" => current.end = Some(parse_time(value, params).map_err(error)?),
+            "DURATION" => current.duration = Some(parse_duration(value).map_err(error)?),
+            _ => {}
+        }
+    }
+    if let Some(event) = event {
+        return Err(ImportError::Parse {
+            line: event.line,
+            message: "event is missing its END:VEVENT".to_string(),
+        });
+    }
+
+    build(records)
+}
+
+/// Properties of the event being read.
+struct Event {
+    line: usize,
+    nesting: usize,
+    uid: Option<String>,
+    summary: Option<String>,
+    priority: Option<i32>,
+    start: Option<f64>,
+    all_day: bool,
+    end: Option<f64>,
+    duration: Option<f64>,
+    cancelled: bool,
+}
+
+impl Event {
+    fn new(line: usize) -> Self {
+        Self {
+            line,
+            nesting: 0,
+            uid: None,
+            summary: None,
+            priority: None,
+            start: None,
+            all_day: false,
+            end: None,
+            duration: None,
+            cancelled: false,
+        }
+    }
+
+    /// The event as a fixed task record on the axis, or `None` if it was
+    /// cancelled.
+    fn into_record(
+        self,
+        index: usize,
+        to_axis: impl Fn(f64) -> f64,
+    ) -> Result<Option<Record>, ImportError> {
+        if self.cancelled {
+            return Ok(None);
+        }
+        let id = self.uid.unwrap_or_else(|| format!("event-{index}"));
+        let mut record = Record::new(self.line, id);
+        let Some(start) = self.start else {
+            return Err(ImportError::MissingField {
+                line: self.line,
+                field: "DTSTART".to_string(),
+            });
+        };
+        let default_length = if self.all_day { 86_400.0 } else { 0.0 };
+        let end = self
+            .end
+            .unwrap_or(start + self.duration.unwrap_or(default_length));
+        record.name = self.summary;
+        record.priority = self.priority;
+        let (start, end) = (to_axis(start), to_axis(end));
+        record.push_window(start, end)?;
+        record.set_duration(end - start)?;
+        Ok(Some(record))
+    }
+}
+
+/// Lines of `reader` with folded continuation lines joined, each with the
+/// number of its first physical line.
+fn unfold<R: BufRead>(reader: R) -> Result<Vec<(usize, String)>, ImportError> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ => lines.push((index + 1, line)),
+        }
+    }
+    Ok(lines)
+}
+
+/// Splits `NAME;PARAM=...:VALUE` into the upper-cased name, the parameters
+/// and the value.
+fn split_property(line: &str) -> Option<(String, &str, &str)> {
+    let (head, value) = line.split_once(':')?;
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name.trim().to_ascii_uppercase(), params, value))
+}
+
+/// Unix time of a `DATE` (`20260314`) or UTC `DATE-TIME`
+/// (`20260314T213000Z`) value.
+fn parse_time(value: &str, params: &str) -> Result<f64, String> {
+    let invalid = || format!("invalid date {value:?}");
+    let value = value.trim();
+    let (date, time) = value.split_once('T').unwrap_or((value, ""));
+    let param = |name: &str| {
+        params.split(';').find_map(|p| {
+            let (key, value) = p.split_once('=')?;
+            key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    };
+    if let Some(zone) = param("TZID") {
+        return Err(format!(
+            "time zone {zone:?} of {value:?} is not supported, use UTC"
+        ));
+    }
+    let date_only = param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE"));
+    if date_only && !time.is_empty() {
+        return Err(invalid());
+    }
+    if !time.is_empty() && !time.ends_with('Z') {
+        return Err(format!(
+            "floating local time {value:?} is not supported, use UTC"
+        ));
+    }
+    let digits = |s: &str, range: std::ops::Range<usize>| -> Result<i64, String> {
+        s.get(range)
+            .filter(|d| d.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|d| d.parse().ok())
+            .ok_or_else(invalid)
+    };
+    if date.len() != 8 {
+        return Err(invalid());
+    }
+    let (year, month, day) = (
+        digits(date, 0..4)?,
+        digits(date, 4..6)?,
+        digits(date, 6..8)?,
+    );
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+    let mut seconds = days_from_civil(year, month, day) * 86_400;
+    if !time.is_empty() {
+        let time = &time[..time.len() - 1];
+        if time.len() != 6 {
+            return Err(invalid());
+        }
+        let (h, m, s) = (
+            digits(time, 0..2)?,
+            digits(time, 2..4)?,
+            digits(time, 4..6)?,
+        );
+        if h > 23 || m > 59 || s > 60 {
+            return Err(invalid());
+        }
+        seconds += h * 3600 + m * 60 + s;
+    }
+    Ok(seconds as f64)
+}
+
+/// Seconds of a `DURATION` value such as `PT1H30M`, `P2D` or `-PT15M`.
+fn parse_duration(value: &str) -> Result<f64, String> {
+    let invalid = || format!("invalid duration {value:?}");
+    let value = value.trim();
+    let (sign, rest) = match value.as_bytes().first() {
+        Some(b'-') => (-1.0, &value[1..]),
+        Some(b'+') => (1.0, &value[1..]),
+        _ => (1.0, value),
+    };
+    let rest = rest.strip_prefix('P').ok_or_else(invalid)?;
+    let mut seconds = 0.0;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        let unit = match (c, in_time) {
+            ('0'..='9', _) => {
+                number.push(c);
+                continue;
+            }
+            ('T', false) if number.is_empty() => {
+                in_time = true;
+                continue;
+            }
+            ('W', false) => 604_800.0,
+            ('D', false) => 86_400.0,
+            ('H', true) => 3600.0,
+            ('M', true) => 60.0,
+            ('S', true) => 1.0,
+            _ => return Err(invalid()),
+        };
+        let n: f64 = number.parse().map_err(|_| invalid())?;
+        seconds += n * unit;
+        number.clear();
+    }
+    if !number.is_empty() || rest.is_empty() {
+        return Err(invalid());
+    }
+    Ok(sign * seconds)
+}
+
+/// Days from 1970-01-01 to a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Undoes iCalendar text escaping.
+fn unescape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n' | 'N')) => {
+                text.push('\n');
+                chars.next();
+            }
+            ('\\', Some(escaped @ (',' | ';' | '\\'))) => {
+                text.push(escaped);
+                chars.next();
+            }
+            _ => text.push(c),
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling_block::Task;
+    use crate::test_utils::iv;
+    use qtty::Minute;
+
+    const CALENDAR: &str = "\
+BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:standup\r
+SUMMARY:Stand-up\\, daily\r
+DTSTART:20260101T090000Z\r
+DURATION:PT15M\r
+PRIORITY:1\r
+BEGIN:VALARM\r
+DURATION:PT5M\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:review\r
+SUMMARY:Quarterly\r
+  review\r
+DTSTART:20260101T100000Z\r
+DTEND:20260101T113000Z\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+DTSTART;VALUE=DATE:20260102\r
+SUMMARY:Holiday\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:dropped\r
+STATUS:CANCELLED\r
+DTSTART:20260101T120000Z\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn events_become_fixed_tasks() {
+        let origin = Quantity::new(1_767_225_600.0); // 2026-01-01T00:00:00Z
+        let block = from_ics::<Second, _>(CALENDAR.as_bytes(), origin).unwrap();
+        assert_eq!(block.task_count(), 3);
+
+        let standup = block.get_task(block.node_of("standup").unwrap()).unwrap();
+        assert_eq!(standup.name(), "Stand-up, daily");
+        assert_eq!(standup.priority(), 9);
+        assert_eq!(standup.windows(), [iv(32_400.0, 33_300.0)]);
+        assert!(standup.is_fixed());
+
+        let review = block.get_task(block.node_of("review").unwrap()).unwrap();
+        assert_eq!(review.name(), "Quarterly review");
+        assert_eq!(review.priority(), 0);
+        assert_eq!(review.windows(), [iv(36_000.0, 41_400.0)]);
+
+        let holiday = block.get_task(block.node_of("event-2").unwrap()).unwrap();
+        assert_eq!(holiday.windows(), [iv(86_400.0, 172_800.0)]);
+
+        let in_minutes = from_ics::<Minute, _>(CALENDAR.as_bytes(), origin).unwrap();
+        let standup = in_minutes
+            .get_task(in_minutes.node_of("standup").unwrap())
+            .unwrap();
+        assert_eq!(standup.size(), Quantity::<Minute>::new(15.0));
+        assert!(standup.is_fixed());
+    }
+
+    #[test]
+    fn malformed_events_report_their_line() {
+        let bad_date = "BEGIN:VEVENT\nDTSTART:2026-01-01\nEND:VEVENT\n";
+        let err = from_ics::<Second, _>(bad_date.as_bytes(), Quantity::new(0.0)).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: invalid date \"2026-01-01\"");
+
+        let no_start = "BEGIN:VEVENT\nUID:x\nEND:VEVENT\n";
+        let err = from_ics::<Second, _>(no_start.as_bytes(), Quantity::new(0.0)).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: missing the \"DTSTART\" field");
+
+        let zoned = "BEGIN:VEVENT\nDTSTART;TZID=Europe/Madrid:20260101T100000\nEND:VEVENT\n";
+        let err = from_ics::<Second, _>(zoned.as_bytes(), Quantity::new(0.0)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: time zone \"Europe/Madrid\" of \"20260101T100000\" is not supported, use UTC"
+        );
+
+        let floating = "BEGIN:VEVENT\nDTSTART:20260101T100000\nEND:VEVENT\n";
+        let err = from_ics::<Second, _>(floating.as_bytes(), Quantity::new(0.0)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: floating local time \"20260101T100000\" is not supported, use UTC"
+        );
+
+        assert_eq!(parse_duration("P1DT2H3M4S"), Ok(93_784.0));
+        assert_eq!(parse_duration("-PT15M"), Ok(-900.0));
+        assert!(parse_duration("P1H").is_err());
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+    }
+
+    #[test]
+    fn repeated_uids_are_rejected() {
+        let overridden = "\
+BEGIN:VEVENT
+UID:weekly
+DTSTART:20260101T090000Z
+DURATION:PT1H
+RRULE:FREQ=WEEKLY
+END:VEVENT
+BEGIN:VEVENT
+UID:weekly
+RECURRENCE-ID:20260108T090000Z
+DTSTART:20260108T100000Z
+DURATION:PT1H
+END:VEVENT
+";
+        let err = from_ics::<Second, _>(overridden.as_bytes(), Quantity::new(0.0)).unwrap_err();
+        assert!(matches!(
+            err,
+            ImportError::Scheduling(SchedulingError::DuplicateId(ref id)) if id == "weekly"
+        ));
+
+        // A cancelled occurrence is dropped before the check.
+        let cancelled =
+            overridden.replace("DTSTART:20260108", "STATUS:CANCELLED\nDTSTART:20260108");
+        let block = from_ics::<Second, _>(cancelled.as_bytes(), Quantity::new(0.0)).unwrap();
+        assert_eq!(block.task_count(), 1);
+    }
+}
@@ -0,0 +1,208 @@
+//! JSON task lists.
+
+use std::collections::HashMap;
+
+use qtty::Unit;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer};
+
+use super::{build, FieldMapping, ImportError, ImportedTask, Record};
+use crate::scheduling_block::SchedulingBlock;
+
+/// Reads a task list from any serde deserializer, typically JSON.
+///
+/// The list is an array of objects with fields named as in `mapping`:
+///
+/// ```text
+/// [
+///   {"id": "flat", "duration": 600, "priority": 2, "window_start": 0, "window_end": 3600},
+///   {"id": "m31", "name": "M31", "duration": 3600,
+///    "windows": [[7200, 14400], [18000, 25200]], "depends_on": ["flat"]}
+/// ]
+/// ```
+///
+/// IDs may be strings or numbers. Dependencies are a list of IDs or a
+/// `;`-separated string. Windows come from the start/end pair and from the
+/// windows list. `null` counts as missing and unknown fields are ignored.
+/// Objects repeating an ID are merged as in [`from_csv`](super::from_csv).
+///
+/// # Errors
+///
+/// - [`ImportError::Parse`] with line 0 if `deserializer` does not hold an
+///   array of objects
+/// - [`ImportError::MissingField`] and [`ImportError::Parse`] with the
+///   1-based position of the offending object
+/// - [`ImportError::Scheduling`] if the dependencies form a cycle
+pub fn from_json<'de, U, D>(
+    deserializer: D,
+    mapping: &FieldMapping,
+) -> Result<SchedulingBlock<ImportedTask<U>, U>, ImportError>
+where
+    U: Unit + Send + Sync,
+    D: Deserializer<'de>,
+{
+    let objects = Vec::<HashMap<String, Value>>::deserialize(deserializer).map_err(|e| {
+        ImportError::Parse {
+            line: 0,
+            message: e.to_string(),
+        }
+    })?;
+
+    let mut records = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        let position = index + 1;
+        let field = |name: &str| {
+            object
+                .iter()
+                .find(|(k, v)| k.eq_ignore_ascii_case(name) && !matches!(v, Value::Null))
+                .map(|(_, v)| v)
+        };
+        let Some(id) = field(&mapping.id).and_then(Value::as_id) else {
+            return Err(ImportError::MissingField {
+                line: position,
+                field: mapping.id.clone(),
+            });
+        };
+
+        let mut record = Record::new(position, id);
+        let number = |record: &Record, name: &str, value: &Value| match value {
+            Value::Number(n) => Ok(*n),
+            _ => Err(record.error(format!("{name} is not a number"))),
+        };
+        record.name = match field(&mapping.name) {
+            Some(Value::Text(name)) => Some(name.clone()),
+            Some(_) => return Err(record.error("name is not a string")),
+            None => None,
+        };
+        if let Some(value) = field(&mapping.duration) {
+            let duration = number(&record, "duration", value)?;
+            record.set_duration(duration)?;
+        }
+        if let Some(value) = field(&mapping.priority) {
+            match value {
+                Value::Number(n) if n.fract() == 0.0 && n.abs() <= i32::MAX as f64 => {
+                    record.priority = Some(*n as i32);
+                }
+                _ => return Err(record.error("priority is not an integer")),
+            }
+        }
+        match (field(&mapping.window_start), field(&mapping.window_end)) {
+            (Some(start), Some(end)) => {
+                let start = number(&record, "window start", start)?;
+                let end = number(&record, "window end", end)?;
+                record.push_window(start, end)?;
+            }
+            (None, None) => {}
+            _ => return Err(record.error("window needs both a start and an end")),
+        }
+        if let Some(value) = field(&mapping.windows) {
+            let Value::List(windows) = value else {
+                return Err(record.error("windows is not a list"));
+            };
+            for window in windows {
+                match window {
+                    Value::List(pair) if pair.len() == 2 => {
+                        let start = number(&record, "window start", &pair[0])?;
+                        let end = number(&record, "window end", &pair[1])?;
+                        record.push_window(start, end)?;
+                    }
+                    _ => return Err(record.error("window is not a [start, end] pair")),
+                }
+            }
+        }
+        record.depends_on = match field(&mapping.depends_on) {
+            Some(Value::Text(ids)) => ids
+                .split(';')
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(str::to_string)
+                .collect(),
+            Some(Value::List(ids)) => ids
+                .iter()
+                .map(|id| {
+                    id.as_id()
+                        .ok_or_else(|| record.error("dependency is not an ID"))
+                })
+                .collect::<Result<_, _>>()?,
+            Some(_) => return Err(record.error("depends_on is not a list")),
+            None => Vec::new(),
+        };
+        records.push(record);
+    }
+
+    build(records)
+}
+
+/// A field value, kept loose so that field names can be mapped after
+/// deserialization.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Value {
+    Null,
+    Number(f64),
+    Text(String),
+    List(Vec<Value>),
+    /// Booleans and objects, which no mapped field takes.
+    Other(IgnoredAny),
+}
+
+impl Value {
+    /// The value as a task ID, for strings and integers.
+    fn as_id(&self) -> Option<String> {
+        match self {
+            Value::Text(id) if !id.trim().is_empty() => Some(id.trim().to_string()),
+            Value::Number(n) if n.fract() == 0.0 => Some(format!("{n}")),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling_block::Task;
+    use crate::test_utils::{iv, q};
+    use qtty::Second;
+
+    #[test]
+    fn documented_format_is_read() {
+        let json = r#"[
+            {"id": "flat", "duration": 600, "priority": 2, "window_start": 0, "window_end": 3600},
+            {"id": "m31", "name": "M31", "duration": 3600, "extra": {"ra": 10.68},
+             "windows": [[7200, 14400], [18000, 25200]], "depends_on": ["flat"]},
+            {"id": 7, "duration": 60, "name": null, "depends_on": "flat; m31"}
+        ]"#;
+        let mut de = serde_json::Deserializer::from_str(json);
+        let block = from_json::<Second, _>(&mut de, &FieldMapping::default()).unwrap();
+        assert_eq!(block.task_count(), 3);
+
+        let m31 = block.node_of("m31").unwrap();
+        let task = block.get_task(m31).unwrap();
+        assert_eq!(task.name(), "M31");
+        assert_eq!(task.windows(), [iv(7200.0, 14400.0), iv(18000.0, 25200.0)]);
+        assert_eq!(block.predecessors(m31).len(), 1);
+
+        let seven = block.node_of("7").unwrap();
+        assert_eq!(block.get_task(seven).unwrap().size(), q(60.0));
+        assert_eq!(block.predecessors(seven).len(), 2);
+    }
+
+    #[test]
+    fn fields_are_mapped_and_errors_carry_the_position() {
+        let mapping = FieldMapping::default()
+            .with_id("key")
+            .with_duration("minutes")
+            .with_windows("slots");
+        let json = r#"[{"key": "a", "minutes": 5, "slots": [[0, 10]]},
+                       {"key": "b", "minutes": "five"}]"#;
+        let mut de = serde_json::Deserializer::from_str(json);
+        let err = from_json::<Second, _>(&mut de, &mapping).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: duration is not a number");
+
+        let mut de = serde_json::Deserializer::from_str(r#"{"key": "a"}"#);
+        assert!(matches!(
+            from_json::<Second, _>(&mut de, &mapping),
+            Err(ImportError::Parse { line: 0, .. })
+        ));
+    }
+}
@@ -0,0 +1,384 @@
+//! Scheduling blocks from external task lists.
+//!
+//! Each adapter reads one format into a [`SchedulingBlock`] of
+//! [`ImportedTask`]s, keyed by the IDs found in the source:
+//!
+//! - [`from_csv`]: a task list with one row per task, or per task window.
+//! - [`from_ics`]: iCalendar `VEVENT`s, imported as fixed tasks that fit
+//!   their event exactly.
+//! - [`from_json`] (feature `serde`): an array of task objects.
+//!
+//! CSV and JSON field names are looked up through a [`FieldMapping`], so
+//! lists exported by other tools can be read without renaming their
+//! columns. Times and durations are read in the block's axis unit.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::io::import::{from_csv, FieldMapping};
+//! # use std::fs::File;
+//! # use std::io::BufReader;
+//! # use qtty::Second;
+//!
+//! let mapping = FieldMapping::default()
+//!     .with_id("target")
+//!     .with_duration("exposure");
+//! let block = from_csv::<Second, _>(File::open("night.csv").map(BufReader::new)?, &mapping)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+mod csv;
+mod ics;
+#[cfg(feature = "serde")]
+mod json;
+
+pub use csv::from_csv;
+pub use ics::from_ics;
+#[cfg(feature = "serde")]
+pub use json::from_json;
+
+use std::collections::HashMap;
+use std::io;
+
+use qtty::{Quantity, Unit};
+use thiserror::Error;
+
+use crate::constraints::{ConstraintExpr, IntervalConstraint};
+use crate::scheduling_block::{SchedulingBlock, SchedulingError, Task};
+use crate::solution_space::Interval;
+use crate::Id;
+
+/// A task read from an external list.
+#[derive(Debug, Clone)]
+pub struct ImportedTask<U: Unit + Send + Sync> {
+    name: String,
+    size: Quantity<U>,
+    priority: i32,
+    windows: Vec<Interval<U>>,
+    constraints: Option<ConstraintExpr<IntervalConstraint<U>>>,
+}
+
+impl<U: Unit + Send + Sync> ImportedTask<U> {
+    /// A task of `size` allowed within any of `windows`, or anywhere if
+    /// there are none.
+    pub fn new(
+        name: impl Into<String>,
+        size: Quantity<U>,
+        priority: i32,
+        windows: Vec<Interval<U>>,
+    ) -> Self {
+        let constraints = (!windows.is_empty()).then(|| {
+            ConstraintExpr::union(
+                windows
+                    .iter()
+                    .map(|w| ConstraintExpr::leaf(IntervalConstraint::new(*w)))
+                    .collect(),
+            )
+        });
+        Self {
+            name: name.into(),
+            size,
+            priority,
+            windows,
+            constraints,
+        }
+    }
+
+    /// The windows the task may be placed in, in source order.
+    pub fn windows(&self) -> &[Interval<U>] {
+        &self.windows
+    }
+
+    /// Whether the task has a single window exactly as long as the task,
+    /// leaving no choice of start.
+    pub fn is_fixed(&self) -> bool {
+        matches!(self.windows.as_slice(), [w] if w.duration() == self.size)
+    }
+}
+
+impl<U: Unit + Send + Sync> Task<U> for ImportedTask<U> {
+    type SizeUnit = U;
+    type ConstraintLeaf = IntervalConstraint<U>;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn size(&self) -> Quantity<U> {
+        self.size
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn constraints(&self) -> Option<&ConstraintExpr<IntervalConstraint<U>>> {
+        self.constraints.as_ref()
+    }
+}
+
+/// Source field names used by [`from_csv`] and [`from_json`].
+///
+/// Names are matched case-insensitively. Only the ID and duration fields are
+/// required; a task's windows come from the start/end pair, from the
+/// windows list (JSON only), or from both.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldMapping {
+    id: String,
+    name: String,
+    duration: String,
+    priority: String,
+    window_start: String,
+    window_end: String,
+    windows: String,
+    depends_on: String,
+}
+
+impl Default for FieldMapping {
+    /// `id`, `name`, `duration`, `priority`, `window_start`, `window_end`,
+    /// `windows` and `depends_on`.
+    fn default() -> Self {
+        Self {
+            id: "id".to_string(),
+            name: "name".to_string(),
+            duration: "duration".to_string(),
+            priority: "priority".to_string(),
+            window_start: "window_start".to_string(),
+            window_end: "window_end".to_string(),
+            windows: "windows".to_string(),
+            depends_on: "depends_on".to_string(),
+        }
+    }
+}
+
+impl FieldMapping {
+    /// Field holding the task ID.
+    pub fn with_id(mut self, field: impl Into<String>) -> Self {
+        self.id = field.into();
+        self
+    }
+
+    /// Field holding the task name. Tasks without one are named after
+    /// their ID.
+    pub fn with_name(mut self, field: impl Into<String>) -> Self {
+        self.name = field.into();
+        self
+    }
+
+    /// Field holding the task duration.
+    pub fn with_duration(mut self, field: impl Into<String>) -> Self {
+        self.duration = field.into();
+        self
+    }
+
+    /// Field holding the integer priority. Tasks without one get 0.
+    pub fn with_priority(mut self, field: impl Into<String>) -> Self {
+        self.priority = field.into();
+        self
+    }
+
+    /// Fields holding the start and end of one window.
+    pub fn with_window(mut self, start: impl Into<String>, end: impl Into<String>) -> Self {
+        self.window_start = start.into();
+        self.window_end = end.into();
+        self
+    }
+
+    /// Field holding a list of `[start, end]` windows (JSON only).
+    pub fn with_windows(mut self, field: impl Into<String>) -> Self {
+        self.windows = field.into();
+        self
+    }
+
+    /// Field holding the IDs of the tasks this one depends on. In CSV the
+    /// IDs are separated by `;`.
+    pub fn with_depends_on(mut self, field: impl Into<String>) -> Self {
+        self.depends_on = field.into();
+        self
+    }
+}
+
+/// Errors raised by the import adapters.
+///
+/// Line numbers are 1-based; JSON errors report the 1-based position of the
+/// task in the array instead.
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("line {line}: missing the {field:?} field")]
+    MissingField { line: usize, field: String },
+
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    #[error(transparent)]
+    Scheduling(#[from] SchedulingError),
+}
+
+/// One row, event or object of a source, before rows sharing an ID are
+/// merged.
+#[derive(Debug, Default)]
+struct Record {
+    line: usize,
+    id: Id,
+    name: Option<String>,
+    duration: Option<f64>,
+    priority: Option<i32>,
+    windows: Vec<(f64, f64)>,
+    depends_on: Vec<Id>,
+}
+
+impl Record {
+    fn new(line: usize, id: Id) -> Self {
+        Self {
+            line,
+            id,
+            ..Self::default()
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ImportError {
+        ImportError::Parse {
+            line: self.line,
+            message: message.into(),
+        }
+    }
+
+    fn set_duration(&mut self, value: f64) -> Result<(), ImportError> {
+        if !value.is_finite() || value < 0.0 {
+            return Err(self.error(format!("invalid duration {value}")));
+        }
+        self.duration = Some(value);
+        Ok(())
+    }
+
+    fn push_window(&mut self, start: f64, end: f64) -> Result<(), ImportError> {
+        if !start.is_finite() || !end.is_finite() {
+            return Err(self.error(format!("invalid window [{start}, {end})")));
+        }
+        if end < start {
+            return Err(self.error(format!("window end {end} is before start {start}")));
+        }
+        self.windows.push((start, end));
+        Ok(())
+    }
+}
+
+/// Builds the block from `records`, merging records that share an ID.
+///
+/// The first record of an ID fixes the task's position; later ones add
+/// windows and dependencies and fill in fields still missing.
+fn build<U>(records: Vec<Record>) -> Result<SchedulingBlock<ImportedTask<U>, U>, ImportError>
+where
+    U: Unit + Send + Sync,
+{
+    let mut merged: Vec<Record> = Vec::new();
+    let mut index: HashMap<Id, usize> = HashMap::new();
+    for record in records {
+        let Some(&i) = index.get(&record.id) else {
+            index.insert(record.id.clone(), merged.len());
+            merged.push(record);
+            continue;
+        };
+        let task = &mut merged[i];
+        task.name = task.name.take().or(record.name);
+        task.duration = task.duration.or(record.duration);
+        task.priority = task.priority.or(record.priority);
+        task.windows.extend(record.windows);
+        task.depends_on.extend(record.depends_on);
+    }
+
+    let mut block = SchedulingBlock::new();
+    for record in &merged {
+        let Some(duration) = record.duration else {
+            return Err(ImportError::MissingField {
+                line: record.line,
+                field: "duration".to_string(),
+            });
+        };
+        let windows = record
+            .windows
+            .iter()
+            .map(|&(start, end)| Interval::new(Quantity::new(start), Quantity::new(end)))
+            .collect();
+        let task = ImportedTask::new(
+            record.name.clone().unwrap_or_else(|| record.id.clone()),
+            Quantity::new(duration),
+            record.priority.unwrap_or(0),
+            windows,
+        );
+        block.add_task_with_id(task, Some(record.id.clone()))?;
+    }
+
+    let mut edges = Vec::new();
+    for record in &merged {
+        for dep in &record.depends_on {
+            if !index.contains_key(dep) {
+                return Err(record.error(format!("unknown dependency {dep:?}")));
+            }
+            edges.push((dep.clone(), record.id.clone(), ()));
+        }
+    }
+    block.add_dependencies(edges)?;
+    Ok(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::Constraint;
+    use crate::test_utils::{iv, q};
+    use qtty::Second;
+
+    #[test]
+    fn repeated_ids_merge_windows_and_dependencies() {
+        let mut a = Record::new(2, "a".into());
+        a.set_duration(10.0).unwrap();
+        a.push_window(0.0, 50.0).unwrap();
+        let mut a_again = Record::new(3, "a".into());
+        a_again.priority = Some(4);
+        a_again.push_window(80.0, 90.0).unwrap();
+        let mut b = Record::new(4, "b".into());
+        b.set_duration(10.0).unwrap();
+        b.depends_on.push("a".into());
+
+        let block = build::<Second>(vec![a, a_again, b]).unwrap();
+        let node = block.node_of("a").unwrap();
+        let task = block.get_task(node).unwrap();
+        assert_eq!(task.name(), "a");
+        assert_eq!(task.priority(), 4);
+        assert_eq!(task.windows(), [iv(0.0, 50.0), iv(80.0, 90.0)]);
+        assert!(!task.is_fixed());
+        assert_eq!(
+            task.constraints()
+                .unwrap()
+                .compute_intervals(iv(0.0, 100.0)),
+            vec![iv(0.0, 50.0), iv(80.0, 90.0)]
+        );
+        let b = block.node_of("b").unwrap();
+        assert_eq!(block.predecessors(b), [node]);
+        assert_eq!(block.get_task(b).unwrap().size(), q(10.0));
+        assert!(block.get_task(b).unwrap().constraints().is_none());
+    }
+
+    #[test]
+    fn invalid_records_are_reported_with_their_line() {
+        let mut record = Record::new(7, "a".into());
+        assert!(matches!(
+            record.push_window(5.0, 1.0),
+            Err(ImportError::Parse { line: 7, .. })
+        ));
+        assert!(matches!(
+            build::<Second>(vec![record]),
+            Err(ImportError::MissingField { line: 7, .. })
+        ));
+
+        let mut orphan = Record::new(3, "a".into());
+        orphan.set_duration(1.0).unwrap();
+        orphan.depends_on.push("ghost".into());
+        let err = build::<Second>(vec![orphan]).unwrap_err();
+        assert_eq!(err.to_string(), "line 3: unknown dependency \"ghost\"");
+    }
+}
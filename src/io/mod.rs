@@ -0,0 +1,12 @@
+//! Exchange with external tools.
+//!
+//! - [`import`] builds scheduling blocks from calendar files, CSV task lists
+//!   and JSON task lists.
+//! - [`export`] renders the feasibility windows of a solution space as
+//!   iCalendar free/busy or an SVG band chart.
+//!
+//! Schedules themselves are read and written by
+//! [`schedule::io`](crate::schedule::io).
+
+pub mod export;
+pub mod import;
@@ -3,12 +3,45 @@
 //! A constraint-based task scheduling library supporting dependency graphs,
 //! solution spaces, and prescheduling utilities.
 
+/// Evaluates `$body` as the given [`alloc_metrics::Phase`] of the run being
+/// measured, or just evaluates it without the `alloc-metrics` feature.
+#[cfg(feature = "alloc-metrics")]
+macro_rules! alloc_phase {
+    ($phase:ident, $body:expr) => {
+        $crate::alloc_metrics::phase($crate::alloc_metrics::Phase::$phase, || $body)
+    };
+}
+#[cfg(not(feature = "alloc-metrics"))]
+macro_rules! alloc_phase {
+    ($phase:ident, $body:expr) => {
+        $body
+    };
+}
+
 pub mod algorithms;
+#[cfg(feature = "alloc-metrics")]
+pub mod alloc_metrics;
+pub mod analysis;
+#[cfg(feature = "chrono")]
+pub mod calendar;
+#[cfg(feature = "capi")]
+pub mod capi;
 pub mod constraints;
+pub mod display;
+#[cfg(feature = "serde")]
+pub mod exact;
+pub mod features;
+pub(crate) mod hash;
+pub mod io;
+pub mod planner;
+pub mod repair;
 pub mod resource;
+pub mod robustness;
+pub mod runs;
 pub mod schedule;
 pub mod scheduling_block;
 pub mod solution_space;
+pub mod synthetic;
 pub mod units;
 
 #[cfg(test)]
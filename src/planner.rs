@@ -0,0 +1,507 @@
+//! What-if checks for interactive schedule editing.
+//!
+//! [`try_place`] answers "what happens if this task goes here?" without
+//! touching the schedule: either the move is valid and its effects on other
+//! tasks are reported, or every reason it is invalid is listed. Editors can
+//! call it on every drag step instead of re-implementing validation.
+//!
+//! A placement is valid when the task exists, keeps its size, lies inside
+//! one of its solution-space windows, overlaps no other scheduled task and
+//! respects its dependencies (every scheduled predecessor ends before it
+//! starts, every scheduled successor starts after it ends).
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use virolai::planner::{try_place, PlacementViolation};
+//! # use virolai::schedule::Schedule;
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let space = instance.solution_space;
+//! # let schedule: Schedule<Second> = Schedule::new();
+//! # let proposed = Interval::from_f64(3_600.0, 4_200.0);
+//! # fn highlight(_: &[PlacementViolation<Second>]) {}
+//! match try_place(&schedule, &space, &blocks, "obs-42", proposed) {
+//!     Ok(effects) => {
+//!         for change in &effects.slack_changes {
+//!             println!("{} loses {:.0} s", change.task_id, change.loss().value());
+//!         }
+//!     }
+//!     Err(rejected) => highlight(&rejected.violations),
+//! }
+//! ```
+//!
+//! [`migrations`] helps balance load between identical instruments: for
+//! every task a multi-resource run dropped, it lists the resources whose
+//! free gaps could still take it, least displaced first.
+//!
+//! ```no_run
+//! # use std::collections::HashMap;
+//! # use virolai::planner::migrations;
+//! # use virolai::schedule::Timeline;
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let timeline: Timeline<Second> = Timeline::with_resources(["north", "south"]);
+//! # let spaces_per_resource = HashMap::from([("north".to_string(), instance.solution_space)]);
+//! for m in migrations(&timeline, &spaces_per_resource, &blocks) {
+//!     println!("{} fits on {} at {}", m.task_id, m.resource, m.interval);
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use petgraph::EdgeType;
+use qtty::{Quantity, Unit};
+
+use crate::schedule::{Schedule, Timeline};
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Interval, IntervalSet, SolutionSpace};
+use crate::Id;
+
+/// Change of one task's slack caused by a placement.
+///
+/// Slack is the free time left in a task's windows once every other
+/// scheduled task is taken into account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlackChange<U: Unit> {
+    /// Affected task.
+    pub task_id: Id,
+    /// Slack before the placement.
+    pub before: Quantity<U>,
+    /// Slack after the placement.
+    pub after: Quantity<U>,
+}
+
+impl<U: Unit> SlackChange<U> {
+    /// How much slack the task loses.
+    pub fn loss(&self) -> Quantity<U> {
+        self.before - self.after
+    }
+}
+
+/// Effects of a valid placement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacementEffects<U: Unit> {
+    /// The placed task.
+    pub task_id: Id,
+    /// Where it would be placed.
+    pub interval: Interval<U>,
+    /// Where it was before, if it was already scheduled.
+    pub previous: Option<Interval<U>>,
+    /// Tasks whose slack shrinks, largest loss first (ties by task ID).
+    pub slack_changes: Vec<SlackChange<U>>,
+}
+
+/// One reason a placement is invalid.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlacementViolation<U: Unit> {
+    /// The task is in none of the blocks.
+    UnknownTask,
+    /// The interval length differs from the task size.
+    WrongDuration {
+        expected: Quantity<U>,
+        actual: Quantity<U>,
+    },
+    /// The interval is not inside any window of the task.
+    OutsideWindows,
+    /// The interval overlaps another scheduled task.
+    Conflict { task_id: Id, interval: Interval<U> },
+    /// A scheduled predecessor ends after the proposed start, or a scheduled
+    /// successor starts before the proposed end.
+    Dependency { predecessor: Id, successor: Id },
+}
+
+/// All the reasons a placement is invalid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacementRejected<U: Unit> {
+    /// The task that was placed.
+    pub task_id: Id,
+    /// Violations, in the order listed in [`PlacementViolation`].
+    pub violations: Vec<PlacementViolation<U>>,
+}
+
+/// Checks placing `task_id` at `interval`, moving it if already scheduled.
+///
+/// `schedule` is not modified.
+///
+/// # Errors
+///
+/// Returns every violated rule if the placement is invalid.
+pub fn try_place<T, U, D, E>(
+    schedule: &Schedule<U>,
+    space: &SolutionSpace<U>,
+    blocks: &[SchedulingBlock<T, U, D, E>],
+    task_id: &str,
+    interval: Interval<U>,
+) -> Result<PlacementEffects<U>, PlacementRejected<U>>
+where
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    let rejected = |violations| PlacementRejected {
+        task_id: task_id.to_string(),
+        violations,
+    };
+    let Some((block, task)) = blocks
+        .iter()
+        .find_map(|b| b.task_by_id(task_id).map(|t| (b, t)))
+    else {
+        return Err(rejected(vec![PlacementViolation::UnknownTask]));
+    };
+
+    let mut violations = Vec::new();
+    let size = task.size_on_axis();
+    if (interval.duration().value() - size.value()).abs() > 1e-9 {
+        violations.push(PlacementViolation::WrongDuration {
+            expected: size,
+            actual: interval.duration(),
+        });
+    }
+    if !space.can_place(task_id, interval.start(), interval.duration()) {
+        violations.push(PlacementViolation::OutsideWindows);
+    }
+    for (other, other_interval) in schedule.conflicts(interval).into_iter().flatten() {
+        if other != task_id {
+            violations.push(PlacementViolation::Conflict {
+                task_id: other,
+                interval: other_interval,
+            });
+        }
+    }
+
+    let node = block.node_of(task_id).expect("task found by ID");
+    for pred in block.predecessors(node) {
+        let Some(pred_id) = block.id_of(pred) else {
+            continue;
+        };
+        if let Some(pred_interval) = schedule.get_interval(pred_id) {
+            if pred_interval.end().value() > interval.start().value() {
+                violations.push(PlacementViolation::Dependency {
+                    predecessor: pred_id.to_string(),
+                    successor: task_id.to_string(),
+                });
+            }
+        }
+    }
+    for succ in block.successors(node) {
+        let Some(succ_id) = block.id_of(succ) else {
+            continue;
+        };
+        if let Some(succ_interval) = schedule.get_interval(succ_id) {
+            if succ_interval.start().value() < interval.end().value() {
+                violations.push(PlacementViolation::Dependency {
+                    predecessor: task_id.to_string(),
+                    successor: succ_id.to_string(),
+                });
+            }
+        }
+    }
+
+    if !violations.is_empty() {
+        return Err(rejected(violations));
+    }
+
+    let previous = schedule.get_interval(task_id);
+    let mut slack_changes = Vec::new();
+    let mut seen = HashSet::new();
+    for (id, _) in blocks.iter().flat_map(|b| b.tasks()) {
+        if id == task_id || !seen.insert(id) {
+            continue;
+        }
+        let Some(windows) = space.get_intervals(id) else {
+            continue;
+        };
+        // Only the moved task's old and new intervals change occupancy.
+        let covered = |iv: Option<Interval<U>>| -> f64 {
+            iv.map_or(0.0, |iv| {
+                windows
+                    .iter()
+                    .filter_map(|w| w.intersection(&iv))
+                    .map(|o| o.duration().value())
+                    .sum()
+            })
+        };
+        let loss = covered(Some(interval)) - covered(previous);
+        if loss > 0.0 {
+            let before = slack(id, windows.as_slice(), schedule);
+            slack_changes.push(SlackChange {
+                task_id: id.to_string(),
+                before: Quantity::new(before),
+                after: Quantity::new(before - loss),
+            });
+        }
+    }
+    slack_changes.sort_by(|a, b| {
+        b.loss()
+            .value()
+            .total_cmp(&a.loss().value())
+            .then_with(|| a.task_id.cmp(&b.task_id))
+    });
+
+    Ok(PlacementEffects {
+        task_id: task_id.to_string(),
+        interval,
+        previous,
+        slack_changes,
+    })
+}
+
+/// Free time in `windows` not taken by scheduled tasks other than `id`.
+fn slack<U: Unit>(id: &str, windows: &[Interval<U>], schedule: &Schedule<U>) -> f64 {
+    windows
+        .iter()
+        .map(|w| {
+            let taken: f64 = schedule
+                .conflicts_ref(*w)
+                .into_iter()
+                .flatten()
+                .filter(|(other, _)| *other != id)
+                .filter_map(|(_, iv)| w.intersection(&iv))
+                .map(|o| o.duration().value())
+                .sum();
+            w.duration().value() - taken
+        })
+        .sum()
+}
+
+/// A free gap a dropped task fits in, see [`migrations`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration<U: Unit> {
+    /// The dropped task.
+    pub task_id: Id,
+    /// Resource offering the gap.
+    pub resource: Id,
+    /// Earliest interval the task fits in on `resource`.
+    pub interval: Interval<U>,
+    /// How far `interval` starts after the task's earliest window on any
+    /// resource.
+    pub displacement: Quantity<U>,
+}
+
+/// Suggests where tasks left off `timeline` could go instead.
+///
+/// `spaces` holds the solution space each resource was scheduled against.
+/// A task of `blocks` is dropped when it is booked nowhere on the timeline;
+/// every resource whose space has windows for it is searched for the
+/// earliest gap, inside those windows, that is free on the resource and its
+/// exclusion partners and keeps every concurrency limit. Each resource
+/// yields at most one suggestion per task.
+///
+/// Suggestions are sorted by displacement, then task and resource ID.
+pub fn migrations<T, U, D, E>(
+    timeline: &Timeline<U>,
+    spaces: &HashMap<Id, SolutionSpace<U>>,
+    blocks: &[SchedulingBlock<T, U, D, E>],
+) -> Vec<Migration<U>>
+where
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    let mut resources: Vec<(&str, &SolutionSpace<U>)> =
+        spaces.iter().map(|(r, s)| (r.as_str(), s)).collect();
+    resources.sort_unstable_by_key(|(r, _)| *r);
+
+    let mut out = Vec::new();
+    let mut seen = HashSet::new();
+    for (id, task) in blocks.iter().flat_map(|b| b.tasks()) {
+        if !seen.insert(id) || timeline.interval_of(id).is_some() {
+            continue;
+        }
+        let candidates: Vec<(&str, &IntervalSet<U>)> = resources
+            .iter()
+            .filter_map(|(r, space)| space.get_intervals(id).map(|w| (*r, w)))
+            .filter(|(_, windows)| !windows.is_empty())
+            .collect();
+        let Some(earliest) = candidates
+            .iter()
+            .map(|(_, windows)| windows[0].start().value())
+            .min_by(f64::total_cmp)
+        else {
+            continue;
+        };
+        let saturated: IntervalSet<U> = timeline.saturated_for(id).into_iter().collect();
+        for (resource, windows) in candidates {
+            let Some(interval) =
+                earliest_gap(timeline, resource, windows, &saturated, task.size_on_axis())
+            else {
+                continue;
+            };
+            out.push(Migration {
+                task_id: id.to_string(),
+                resource: resource.to_string(),
+                interval,
+                displacement: Quantity::new(interval.start().value() - earliest),
+            });
+        }
+    }
+    out.sort_by(|a, b| {
+        a.displacement
+            .value()
+            .total_cmp(&b.displacement.value())
+            .then_with(|| a.task_id.cmp(&b.task_id))
+            .then_with(|| a.resource.cmp(&b.resource))
+    });
+    out
+}
+
+/// Earliest `size`-long interval inside `windows` free on `resource`.
+fn earliest_gap<U: Unit>(
+    timeline: &Timeline<U>,
+    resource: &str,
+    windows: &IntervalSet<U>,
+    saturated: &IntervalSet<U>,
+    size: Quantity<U>,
+) -> Option<Interval<U>> {
+    let mut busy = saturated.clone();
+    for r in std::iter::once(resource).chain(timeline.exclusive_partners(resource)) {
+        if let Some(schedule) = timeline.schedule(r) {
+            busy = busy.union(&schedule.to_busy_intervals());
+        }
+    }
+    let hull = Interval::new(windows[0].start(), windows[windows.len() - 1].end());
+    windows
+        .intersection(&busy.complement(hull))
+        .iter()
+        .filter(|gap| gap.start().value().is_finite() && gap.duration() >= size)
+        .map(|gap| Interval::new(gap.start(), gap.start() + size))
+        // The schedule's minimum gap may still rule the gap's start out.
+        .find(|candidate| timeline.is_free(resource, *candidate).unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{block_of, iv, planned_block, q, PlannedBlock, TestTask};
+    use qtty::Second;
+
+    /// a → b dependency; c independent. Windows [0, 100), c's [10, 100).
+    fn setup() -> PlannedBlock {
+        planned_block(
+            &[
+                ("a", iv(0.0, 100.0), Some(0.0)),
+                ("b", iv(0.0, 100.0), Some(20.0)),
+                ("c", iv(10.0, 100.0), None),
+            ],
+            &[("a", "b")],
+        )
+    }
+
+    #[test]
+    fn valid_placement_reports_slack_losses() {
+        let (schedule, space, blocks) = setup();
+        let effects = try_place(&schedule, &space, &blocks, "c", iv(40.0, 50.0)).unwrap();
+        assert_eq!(effects.previous, None);
+        // a and b each lose the 10 s c now takes from their windows.
+        assert_eq!(
+            effects
+                .slack_changes
+                .iter()
+                .map(|c| c.task_id.as_str())
+                .collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        let a = &effects.slack_changes[0];
+        assert_eq!((a.before, a.after), (q(90.0), q(80.0)));
+        assert_eq!(a.loss(), q(10.0));
+
+        // Moving a frees [0, 5) and takes [10, 15): only c, whose window
+        // starts at 10, loses anything.
+        let effects = try_place(&schedule, &space, &blocks, "a", iv(5.0, 15.0)).unwrap();
+        assert_eq!(effects.previous, Some(iv(0.0, 10.0)));
+        assert_eq!(
+            effects.slack_changes,
+            vec![SlackChange {
+                task_id: "c".into(),
+                before: q(80.0),
+                after: q(75.0)
+            }]
+        );
+    }
+
+    #[test]
+    fn invalid_placement_lists_every_violation() {
+        let (schedule, mut space, blocks) = setup();
+        space.set_intervals("a", vec![iv(0.0, 22.0)]);
+
+        let rejected = try_place(&schedule, &space, &blocks, "a", iv(18.0, 28.0)).unwrap_err();
+        assert_eq!(
+            rejected.violations,
+            vec![
+                PlacementViolation::OutsideWindows,
+                PlacementViolation::Conflict {
+                    task_id: "b".into(),
+                    interval: iv(20.0, 30.0)
+                },
+                PlacementViolation::Dependency {
+                    predecessor: "a".into(),
+                    successor: "b".into()
+                },
+            ]
+        );
+
+        let rejected = try_place(&schedule, &space, &blocks, "c", iv(50.0, 55.0)).unwrap_err();
+        assert_eq!(
+            rejected.violations,
+            vec![PlacementViolation::WrongDuration {
+                expected: q(10.0),
+                actual: q(5.0)
+            }]
+        );
+
+        let rejected = try_place(&schedule, &space, &blocks, "zzz", iv(0.0, 1.0)).unwrap_err();
+        assert_eq!(rejected.violations, vec![PlacementViolation::UnknownTask]);
+    }
+
+    #[test]
+    fn migrations_rank_free_gaps_on_other_resources() {
+        let block = block_of([
+            TestTask::new("a", 10.0),
+            TestTask::new("b", 30.0),
+            TestTask::new("c", 10.0),
+            TestTask::new("d", 20.0),
+        ]);
+        let mut timeline = Timeline::with_resources(["cam1", "cam2", "cam3"])
+            .with_exclusion_group(["cam1", "cam3"]);
+        timeline.book("a", iv(0.0, 10.0), &["cam1"]).unwrap();
+        timeline.book("b", iv(0.0, 30.0), &["cam2"]).unwrap();
+
+        let mut spaces: HashMap<Id, SolutionSpace<Second>> = HashMap::new();
+        for resource in ["cam1", "cam2"] {
+            let space = spaces.entry(resource.to_string()).or_default();
+            space.add_interval("a", iv(0.0, 50.0));
+            space.add_interval("c", iv(0.0, 50.0));
+            space.add_interval("d", iv(0.0, 25.0));
+        }
+        spaces
+            .entry("cam3".to_string())
+            .or_default()
+            .add_interval("c", iv(5.0, 50.0));
+
+        // a is booked; d fits in no gap; c can follow a on cam1 or on cam3,
+        // which shares cam1's exclusion group, or follow b on cam2.
+        let suggestions = migrations(&timeline, &spaces, &[block]);
+        assert_eq!(
+            suggestions
+                .iter()
+                .map(|m| (m.task_id.as_str(), m.resource.as_str(), m.interval))
+                .collect::<Vec<_>>(),
+            [
+                ("c", "cam1", iv(10.0, 20.0)),
+                ("c", "cam3", iv(10.0, 20.0)),
+                ("c", "cam2", iv(30.0, 40.0)),
+            ]
+        );
+        assert_eq!(suggestions[0].displacement, q(10.0));
+        assert_eq!(suggestions[2].displacement, q(30.0));
+    }
+}
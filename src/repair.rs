@@ -0,0 +1,263 @@
+//! Local schedule repair after solution-space changes.
+//!
+//! When the forecast changes slightly — a new blackout, a shortened window —
+//! most of a schedule is still valid. [`repair`] keeps every entry the new
+//! solution space still allows and moves only the violating ones, each to
+//! the free slot closest to where it was. Tasks with no such slot are
+//! removed and reported, so the caller can decide whether a full replan is
+//! worth it.
+//!
+//! Moved tasks stay after their scheduled predecessors and before their
+//! scheduled successors. Successors of a removed task are left in place.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use qtty::Second;
+//! # use virolai::repair::repair;
+//! # use virolai::schedule::Schedule;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let schedule: Schedule<Second> = Schedule::new();
+//! # let updated_space = instance.solution_space;
+//! # fn replan() {}
+//! let repaired = repair(&schedule, &updated_space, &blocks);
+//! for m in &repaired.moved {
+//!     println!("{} moved from {} to {}", m.task_id, m.from, m.to);
+//! }
+//! if !repaired.unrepaired.is_empty() {
+//!     replan();
+//! }
+//! ```
+
+use petgraph::EdgeType;
+use qtty::{Quantity, Unit};
+
+use crate::algorithms::greedy::free_windows;
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Interval, SolutionSpace};
+use crate::Id;
+
+/// A violating task moved to a new slot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepairMove<U: Unit> {
+    /// The moved task.
+    pub task_id: Id,
+    /// Where it was.
+    pub from: Interval<U>,
+    /// Where it is now.
+    pub to: Interval<U>,
+}
+
+impl<U: Unit> RepairMove<U> {
+    /// Distance between the old and new start.
+    pub fn shift(&self) -> Quantity<U> {
+        Quantity::new((self.to.start().value() - self.from.start().value()).abs())
+    }
+}
+
+/// Why a violating task could not be moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RepairFailure {
+    /// The task has no entry in the new solution space.
+    NotInSolutionSpace,
+    /// No free slot in its windows fits the task between its scheduled
+    /// predecessors and successors.
+    NoFreeSlot,
+}
+
+/// Result of [`repair`].
+#[derive(Debug, Clone)]
+pub struct RepairOutcome<U: Unit> {
+    /// The repaired schedule.
+    pub schedule: Schedule<U>,
+    /// Moved tasks, in their original start order.
+    pub moved: Vec<RepairMove<U>>,
+    /// Removed tasks with their previous interval, in original start order.
+    pub unrepaired: Vec<(Id, Interval<U>, RepairFailure)>,
+}
+
+impl<U: Unit> RepairOutcome<U> {
+    /// Whether the schedule was already valid for the new space.
+    pub fn is_unchanged(&self) -> bool {
+        self.moved.is_empty() && self.unrepaired.is_empty()
+    }
+}
+
+/// Repairs `schedule` against an updated solution space.
+///
+/// Entries `new_space` still allows are kept untouched. Violating entries
+/// are re-placed in original start order, each at the free position of its
+/// windows nearest to its old start (earlier on ties), keeping its duration.
+/// Dependencies are read from `blocks`; tasks missing from `blocks` have
+/// none.
+pub fn repair<T, U, D, E>(
+    schedule: &Schedule<U>,
+    new_space: &SolutionSpace<U>,
+    blocks: &[SchedulingBlock<T, U, D, E>],
+) -> RepairOutcome<U>
+where
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    let violating = schedule.infeasible_entries(new_space);
+    let mut outcome = RepairOutcome {
+        schedule: schedule.clone(),
+        moved: Vec::new(),
+        unrepaired: Vec::new(),
+    };
+    for id in &violating {
+        outcome.schedule.remove(id);
+    }
+
+    for id in violating {
+        let from = schedule
+            .get_interval(&id)
+            .expect("violating entry is scheduled");
+        let Some(windows) = new_space.get_intervals(&id) else {
+            outcome
+                .unrepaired
+                .push((id, from, RepairFailure::NotInSolutionSpace));
+            continue;
+        };
+
+        let (lo, hi) = dependency_bounds(&id, blocks, &outcome.schedule);
+        let size = from.duration().value();
+        let windows: Vec<(f64, f64)> = windows
+            .iter()
+            .map(|w| (w.start().value(), w.end().value()))
+            .collect();
+        let target = from.start().value();
+        let start = free_windows(&windows, lo, hi, &outcome.schedule)
+            .into_iter()
+            .filter(|w| w.duration().value() >= size)
+            .map(|w| target.clamp(w.start().value(), w.end().value() - size))
+            .min_by(|a, b| {
+                (a - target)
+                    .abs()
+                    .total_cmp(&(b - target).abs())
+                    .then(a.total_cmp(b))
+            });
+
+        match start {
+            Some(start) => {
+                let to = Interval::new(Quantity::new(start), Quantity::new(start + size));
+                outcome
+                    .schedule
+                    .add(id.clone(), to)
+                    .expect("free slot does not overlap");
+                outcome.moved.push(RepairMove {
+                    task_id: id,
+                    from,
+                    to,
+                });
+            }
+            None => outcome
+                .unrepaired
+                .push((id, from, RepairFailure::NoFreeSlot)),
+        }
+    }
+
+    outcome
+}
+
+/// Range `[lo, hi)` a task must stay in to keep its scheduled predecessors
+/// before it and its scheduled successors after it.
+pub(crate) fn dependency_bounds<T, U, D, E>(
+    task_id: &str,
+    blocks: &[SchedulingBlock<T, U, D, E>],
+    schedule: &Schedule<U>,
+) -> (f64, f64)
+where
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    let mut bounds = (f64::NEG_INFINITY, f64::INFINITY);
+    let Some((block, node)) = blocks
+        .iter()
+        .find_map(|b| b.node_of(task_id).map(|n| (b, n)))
+    else {
+        return bounds;
+    };
+    for pred in block.predecessors(node) {
+        if let Some(iv) = block.id_of(pred).and_then(|id| schedule.get_interval(id)) {
+            bounds.0 = bounds.0.max(iv.end().value());
+        }
+    }
+    for succ in block.successors(node) {
+        if let Some(iv) = block.id_of(succ).and_then(|id| schedule.get_interval(id)) {
+            bounds.1 = bounds.1.min(iv.start().value());
+        }
+    }
+    bounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{iv, planned_block, q, PlannedBlock};
+
+    /// a → b → c, each 10 s, all allowed anywhere in [0, 100).
+    fn setup() -> PlannedBlock {
+        planned_block(
+            &[
+                ("a", iv(0.0, 100.0), Some(0.0)),
+                ("b", iv(0.0, 100.0), Some(30.0)),
+                ("c", iv(0.0, 100.0), Some(60.0)),
+            ],
+            &[("a", "b"), ("b", "c")],
+        )
+    }
+
+    #[test]
+    fn blackout_moves_only_the_violating_task() {
+        let (schedule, mut space, blocks) = setup();
+        let unchanged = repair(&schedule, &space, &blocks);
+        assert!(unchanged.is_unchanged());
+
+        // Blackout over [25, 38): b moves to the nearest free slot after it.
+        space.set_intervals("b", vec![iv(0.0, 25.0), iv(38.0, 100.0)]);
+        let repaired = repair(&schedule, &space, &blocks);
+        assert_eq!(
+            repaired.moved,
+            vec![RepairMove {
+                task_id: "b".into(),
+                from: iv(30.0, 40.0),
+                to: iv(38.0, 48.0)
+            }]
+        );
+        assert_eq!(repaired.moved[0].shift(), q(8.0));
+        assert_eq!(repaired.schedule.get_interval("a"), Some(iv(0.0, 10.0)));
+        assert_eq!(repaired.schedule.get_interval("c"), Some(iv(60.0, 70.0)));
+        assert!(repaired.unrepaired.is_empty());
+    }
+
+    #[test]
+    fn dependencies_bound_moves_and_failures_are_reported() {
+        let (schedule, mut space, blocks) = setup();
+        // b's later window lies after its successor c: it moves earlier.
+        space.set_intervals("b", vec![iv(15.0, 25.0), iv(65.0, 100.0)]);
+        let repaired = repair(&schedule, &space, &blocks);
+        assert_eq!(repaired.schedule.get_interval("b"), Some(iv(15.0, 25.0)));
+
+        // Only room left for b is after its successor c.
+        space.set_intervals("b", vec![iv(75.0, 100.0)]);
+        space.remove("a");
+        let repaired = repair(&schedule, &space, &blocks);
+        assert_eq!(
+            repaired.unrepaired,
+            vec![
+                ("a".into(), iv(0.0, 10.0), RepairFailure::NotInSolutionSpace),
+                ("b".into(), iv(30.0, 40.0), RepairFailure::NoFreeSlot),
+            ]
+        );
+        assert_eq!(repaired.schedule.len(), 1);
+        assert!(repaired.moved.is_empty());
+    }
+}
@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+/// Errors raised while building or querying a [`ResourceHierarchy`](super::ResourceHierarchy).
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ResourceError {
+    #[error("Resource ID already exists: {0}")]
+    DuplicateId(String),
+
+    #[error("Unknown resource: {0}")]
+    UnknownResource(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_id_display() {
+        let e = ResourceError::DuplicateId("lst-1".into());
+        assert_eq!(e.to_string(), "Resource ID already exists: lst-1");
+    }
+
+    #[test]
+    fn unknown_resource_display() {
+        let e = ResourceError::UnknownResource("site-x".into());
+        assert_eq!(e.to_string(), "Unknown resource: site-x");
+    }
+}
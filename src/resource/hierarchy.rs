@@ -0,0 +1,490 @@
+//! Resource hierarchies (site → telescope → instrument).
+//!
+//! A [`ResourceHierarchy`] organizes resources in a forest where every node
+//! inherits the availability of its ancestors: a blackout declared on a site
+//! removes time from every telescope and instrument underneath it. Internal
+//! nodes act as *groups* that a caller can target ("any telescope at site X");
+//! [`ResourceHierarchy::schedule_group`] resolves such a request to concrete
+//! leaf resources, so the output is always keyed by leaf IDs.
+
+use std::collections::HashMap;
+
+use petgraph::EdgeType;
+use qtty::Unit;
+
+use super::{Resource, ResourceError};
+use crate::algorithms::SchedulingAlgorithm;
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, IntervalSet, SolutionSpace};
+use crate::Id;
+
+#[derive(Debug)]
+struct Node<R> {
+    resource: R,
+    parent: Option<Id>,
+    children: Vec<Id>,
+}
+
+/// A forest of resources where parents constrain their children.
+///
+/// Nodes are keyed by [`Resource::resource_id`]. Children are kept in
+/// insertion order, which is also the order in which
+/// [`schedule_group`](Self::schedule_group) fills leaves.
+#[derive(Debug)]
+pub struct ResourceHierarchy<R> {
+    nodes: HashMap<Id, Node<R>>,
+    roots: Vec<Id>,
+}
+
+/// Result of scheduling against a resource group.
+#[derive(Debug, Clone)]
+pub struct GroupSchedule<U: Unit> {
+    /// One schedule per leaf resource of the group, keyed by leaf ID.
+    pub schedules: HashMap<Id, Schedule<U>>,
+    /// The leaf resource each scheduled task was resolved to.
+    pub assignments: HashMap<Id, Id>,
+}
+
+impl<U: Unit> GroupSchedule<U> {
+    /// Returns the leaf resource a task was assigned to, if it was scheduled.
+    pub fn resource_of(&self, task_id: &str) -> Option<&str> {
+        self.assignments.get(task_id).map(String::as_str)
+    }
+}
+
+impl<R> ResourceHierarchy<R> {
+    /// Creates an empty hierarchy.
+    pub fn new() -> Self {
+        Self {
+            nodes: HashMap::new(),
+            roots: Vec::new(),
+        }
+    }
+
+    /// Number of resources in the hierarchy.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the hierarchy holds no resources.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns `true` if a resource with this ID exists.
+    pub fn contains(&self, id: &str) -> bool {
+        self.nodes.contains_key(id)
+    }
+
+    /// Returns the resource registered under `id`.
+    pub fn get(&self, id: &str) -> Option<&R> {
+        self.nodes.get(id).map(|n| &n.resource)
+    }
+
+    /// Root resources, in insertion order.
+    pub fn roots(&self) -> &[Id] {
+        &self.roots
+    }
+
+    /// Parent of a resource, or `None` for roots and unknown IDs.
+    pub fn parent(&self, id: &str) -> Option<&str> {
+        self.nodes.get(id)?.parent.as_deref()
+    }
+
+    /// Direct children of a resource, in insertion order.
+    pub fn children(&self, id: &str) -> &[Id] {
+        self.nodes
+            .get(id)
+            .map(|n| n.children.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns `true` if the resource exists and has no children.
+    pub fn is_leaf(&self, id: &str) -> bool {
+        self.nodes.get(id).is_some_and(|n| n.children.is_empty())
+    }
+
+    /// Ancestors of a resource, nearest first (parent, grandparent, …).
+    pub fn ancestors(&self, id: &str) -> Vec<Id> {
+        let mut out = Vec::new();
+        let mut current = self.parent(id);
+        while let Some(p) = current {
+            out.push(p.to_string());
+            current = self.parent(p);
+        }
+        out
+    }
+
+    /// Leaf resources under `id`, in depth-first insertion order.
+    ///
+    /// A leaf resolves to itself.
+    pub fn leaves_of(&self, id: &str) -> Result<Vec<Id>, ResourceError> {
+        if !self.contains(id) {
+            return Err(ResourceError::UnknownResource(id.to_string()));
+        }
+        let mut out = Vec::new();
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            let children = self.children(current);
+            if children.is_empty() {
+                out.push(current.to_string());
+            } else {
+                stack.extend(children.iter().rev().map(String::as_str));
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl<R> Default for ResourceHierarchy<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> ResourceHierarchy<R> {
+    /// Adds a top-level resource and returns its ID.
+    pub fn add_root<A: Unit>(&mut self, resource: R) -> Result<Id, ResourceError>
+    where
+        R: Resource<A>,
+    {
+        let id = self.insert(resource, None)?;
+        self.roots.push(id.clone());
+        Ok(id)
+    }
+
+    /// Adds `resource` under `parent` and returns its ID.
+    pub fn add_child<A: Unit>(&mut self, parent: &str, resource: R) -> Result<Id, ResourceError>
+    where
+        R: Resource<A>,
+    {
+        if !self.contains(parent) {
+            return Err(ResourceError::UnknownResource(parent.to_string()));
+        }
+        let id = self.insert(resource, Some(parent.to_string()))?;
+        self.nodes
+            .get_mut(parent)
+            .expect("parent checked above")
+            .children
+            .push(id.clone());
+        Ok(id)
+    }
+
+    fn insert<A: Unit>(&mut self, resource: R, parent: Option<Id>) -> Result<Id, ResourceError>
+    where
+        R: Resource<A>,
+    {
+        let id = resource.resource_id().to_string();
+        if self.contains(&id) {
+            return Err(ResourceError::DuplicateId(id));
+        }
+        self.nodes.insert(
+            id.clone(),
+            Node {
+                resource,
+                parent,
+                children: Vec::new(),
+            },
+        );
+        Ok(id)
+    }
+
+    /// Availability of a resource after applying every ancestor's constraints.
+    ///
+    /// The result is the intersection of the resource's own
+    /// [`compute_availability`](Resource::compute_availability) with that of
+    /// each ancestor up to the root.
+    pub fn effective_availability<A: Unit>(
+        &self,
+        id: &str,
+        horizon: Horizon<A>,
+    ) -> Result<IntervalSet<A>, ResourceError>
+    where
+        R: Resource<A>,
+    {
+        let node = self
+            .nodes
+            .get(id)
+            .ok_or_else(|| ResourceError::UnknownResource(id.to_string()))?;
+        let mut available = node.resource.compute_availability(horizon.interval());
+        for ancestor in self.ancestors(id) {
+            if available.is_empty() {
+                break;
+            }
+            let parent = &self.nodes[&ancestor].resource;
+            available = available.intersection(&parent.compute_availability(horizon.interval()));
+        }
+        Ok(available)
+    }
+
+    /// Per-leaf solution spaces for a group.
+    ///
+    /// Every task window in `task_space` is intersected with the effective
+    /// availability of each leaf under `group`. Tasks left with no window on a
+    /// leaf are omitted from that leaf's space.
+    pub fn group_spaces<A: Unit>(
+        &self,
+        group: &str,
+        task_space: &SolutionSpace<A>,
+        horizon: Horizon<A>,
+    ) -> Result<HashMap<Id, SolutionSpace<A>>, ResourceError>
+    where
+        R: Resource<A>,
+    {
+        let mut spaces = HashMap::new();
+        for leaf in self.leaves_of(group)? {
+            let available = self.effective_availability(&leaf, horizon)?;
+            let mut space = SolutionSpace::new();
+            for (task_id, windows) in task_space.iter() {
+                let windows = windows.intersection(&available);
+                if !windows.is_empty() {
+                    space.set_intervals(task_id, windows.into_inner());
+                }
+            }
+            spaces.insert(leaf, space);
+        }
+        Ok(spaces)
+    }
+
+    /// Schedules tasks on "any resource in `group`".
+    ///
+    /// Leaves are filled one at a time in [`leaves_of`](Self::leaves_of)
+    /// order; tasks placed on an earlier leaf are withdrawn from later ones,
+    /// so every task is resolved to at most one concrete leaf.
+    pub fn schedule_group<A, T, D, E, Alg>(
+        &self,
+        algorithm: &Alg,
+        group: &str,
+        blocks: &[SchedulingBlock<T, A, D, E>],
+        task_space: &SolutionSpace<A>,
+        horizon: Horizon<A>,
+    ) -> Result<GroupSchedule<A>, ResourceError>
+    where
+        A: Unit,
+        R: Resource<A>,
+        T: Task<A>,
+        E: EdgeType,
+        Alg: SchedulingAlgorithm<T, A, D, E>,
+    {
+        let mut spaces = self.group_spaces(group, task_space, horizon)?;
+        let mut result = GroupSchedule {
+            schedules: HashMap::new(),
+            assignments: HashMap::new(),
+        };
+        for leaf in self.leaves_of(group)? {
+            let mut space = spaces.remove(&leaf).unwrap_or_default();
+            for task_id in result.assignments.keys() {
+                space.remove(task_id);
+            }
+            let schedule = algorithm.schedule(blocks, &space, horizon);
+            for (task_id, _, _) in schedule.iter_all() {
+                result.assignments.insert(task_id, leaf.clone());
+            }
+            result.schedules.insert(leaf, schedule);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::ESTScheduler;
+    use crate::constraints::{ConstraintExpr, IntervalConstraint};
+    use crate::solution_space::Interval;
+    use crate::test_utils::{block_and_space, block_of, hz, iv, q, TestTask};
+    use qtty::Second;
+
+    #[derive(Debug)]
+    struct Site {
+        id: String,
+        constraints: Option<ConstraintExpr<IntervalConstraint<Second>>>,
+    }
+
+    impl Site {
+        fn open(id: &str) -> Self {
+            Self {
+                id: id.to_string(),
+                constraints: None,
+            }
+        }
+
+        fn within(id: &str, window: Interval<Second>) -> Self {
+            Self {
+                id: id.to_string(),
+                constraints: Some(ConstraintExpr::leaf(IntervalConstraint::new(window))),
+            }
+        }
+    }
+
+    impl Resource<Second> for Site {
+        type ConstraintLeaf = IntervalConstraint<Second>;
+        fn name(&self) -> &str {
+            &self.id
+        }
+        fn constraints(&self) -> Option<&ConstraintExpr<Self::ConstraintLeaf>> {
+            self.constraints.as_ref()
+        }
+    }
+
+    /// site-x (0..80) → { tel-a (10..100) → { cam-1, spec-1 }, tel-b }
+    fn observatory() -> ResourceHierarchy<Site> {
+        let mut h = ResourceHierarchy::new();
+        h.add_root(Site::within("site-x", iv(0.0, 80.0))).unwrap();
+        h.add_child("site-x", Site::within("tel-a", iv(10.0, 100.0)))
+            .unwrap();
+        h.add_child("tel-a", Site::open("cam-1")).unwrap();
+        h.add_child("tel-a", Site::within("spec-1", iv(50.0, 100.0)))
+            .unwrap();
+        h.add_child("site-x", Site::open("tel-b")).unwrap();
+        h
+    }
+
+    // ── structure ─────────────────────────────────────────────────────
+
+    #[test]
+    fn navigation() {
+        let h = observatory();
+        assert_eq!(h.len(), 5);
+        assert_eq!(h.roots(), ["site-x".to_string()]);
+        assert_eq!(h.parent("cam-1"), Some("tel-a"));
+        assert_eq!(h.parent("site-x"), None);
+        assert_eq!(h.ancestors("spec-1"), vec!["tel-a", "site-x"]);
+        assert!(h.is_leaf("tel-b"));
+        assert!(!h.is_leaf("tel-a"));
+        assert!(!h.is_leaf("missing"));
+    }
+
+    #[test]
+    fn leaves_in_depth_first_order() {
+        let h = observatory();
+        assert_eq!(
+            h.leaves_of("site-x").unwrap(),
+            vec!["cam-1", "spec-1", "tel-b"]
+        );
+        assert_eq!(h.leaves_of("cam-1").unwrap(), vec!["cam-1"]);
+        assert_eq!(
+            h.leaves_of("nope"),
+            Err(ResourceError::UnknownResource("nope".into()))
+        );
+    }
+
+    #[test]
+    fn rejects_duplicates_and_orphans() {
+        let mut h = observatory();
+        assert_eq!(
+            h.add_child("site-x", Site::open("tel-a")),
+            Err(ResourceError::DuplicateId("tel-a".into()))
+        );
+        assert_eq!(
+            h.add_child("site-y", Site::open("tel-c")),
+            Err(ResourceError::UnknownResource("site-y".into()))
+        );
+        assert_eq!(h.len(), 5);
+    }
+
+    // ── availability ──────────────────────────────────────────────────
+
+    #[test]
+    fn parent_constraints_apply_to_children() {
+        let h = observatory();
+        let horizon = hz(0.0, 100.0);
+        let cam = h.effective_availability("cam-1", horizon).unwrap();
+        assert_eq!(cam.as_slice(), [iv(10.0, 80.0)]);
+        let spec = h.effective_availability("spec-1", horizon).unwrap();
+        assert_eq!(spec.as_slice(), [iv(50.0, 80.0)]);
+        let tel_b = h.effective_availability("tel-b", horizon).unwrap();
+        assert_eq!(tel_b.as_slice(), [iv(0.0, 80.0)]);
+    }
+
+    #[test]
+    fn group_spaces_drop_tasks_without_windows() {
+        let h = observatory();
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("early", iv(0.0, 20.0));
+        let spaces = h.group_spaces("tel-a", &ss, hz(0.0, 100.0)).unwrap();
+        assert_eq!(spaces.len(), 2);
+        assert_eq!(
+            spaces["cam-1"].get_intervals("early").unwrap().as_slice(),
+            [iv(10.0, 20.0)]
+        );
+        assert!(spaces["spec-1"].get_intervals("early").is_none());
+    }
+
+    // ── group scheduling ──────────────────────────────────────────────
+
+    #[test]
+    fn group_resolves_each_task_to_one_leaf() {
+        let h = observatory();
+        let (block, ss) = block_and_space(&[
+            ("a", 60.0, 0, iv(0.0, 100.0)),
+            ("b", 60.0, 0, iv(0.0, 100.0)),
+            ("c", 60.0, 0, iv(0.0, 100.0)),
+        ]);
+
+        let result = h
+            .schedule_group(
+                &ESTScheduler::new(q(1.0)),
+                "site-x",
+                &[block],
+                &ss,
+                hz(0.0, 100.0),
+            )
+            .unwrap();
+
+        // cam-1 has 70 units, spec-1 only 30, tel-b 80: two tasks fit.
+        assert_eq!(result.assignments.len(), 2);
+        assert!(result.schedules["spec-1"].is_empty());
+        let total: usize = result.schedules.values().map(Schedule::len).sum();
+        assert_eq!(total, 2);
+        for (task, leaf) in &result.assignments {
+            assert!(h.is_leaf(leaf));
+            assert!(result.schedules[leaf].contains_task(task));
+            assert_eq!(result.resource_of(task), Some(leaf.as_str()));
+        }
+    }
+
+    #[test]
+    fn group_assigns_background_tasks_once() {
+        let h = observatory();
+        let block = block_of([TestTask::new("monitor", 20.0).in_background()]);
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("monitor", iv(0.0, 100.0));
+
+        let result = h
+            .schedule_group(
+                &ESTScheduler::new(q(1.0)),
+                "site-x",
+                &[block],
+                &ss,
+                hz(0.0, 100.0),
+            )
+            .unwrap();
+
+        let leaf = result.resource_of("monitor").unwrap();
+        assert!(result.schedules[leaf].is_background("monitor"));
+        let placements = result
+            .schedules
+            .values()
+            .filter(|s| s.contains_task("monitor"))
+            .count();
+        assert_eq!(placements, 1);
+    }
+
+    #[test]
+    fn group_on_unknown_resource_errors() {
+        let h = observatory();
+        let block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
+        let err = h
+            .schedule_group(
+                &ESTScheduler::new(q(1.0)),
+                "site-y",
+                &[block],
+                &SolutionSpace::new(),
+                hz(0.0, 100.0),
+            )
+            .unwrap_err();
+        assert_eq!(err, ResourceError::UnknownResource("site-y".into()));
+    }
+}
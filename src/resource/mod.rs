@@ -10,6 +10,10 @@
 //! we compute them once at the resource level and intersect task windows with the
 //! resource's availability windows.
 
+mod error;
+mod hierarchy;
 mod traits;
 
+pub use error::ResourceError;
+pub use hierarchy::{GroupSchedule, ResourceHierarchy};
 pub use traits::Resource;
@@ -0,0 +1,262 @@
+//! Random schedule perturbation for robustness testing.
+//!
+//! Real executions drift: tasks start late and run longer than planned.
+//! [`perturb`] replays a schedule under a [`NoiseModel`] whose noise is
+//! proportional to each task's duration, so long tasks drift more than short
+//! ones. The perturbed schedule keeps the original order — and therefore
+//! every dependency the original satisfied — and never overlaps: a task
+//! pushed late pushes its successors.
+//!
+//! [`robustness`] runs many perturbations and reports how far tasks end up
+//! past their planned end, an empirical score for comparing schedules.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::robustness::{robustness, NoiseModel};
+//! use virolai::synthetic::SplitMix64;
+//! # use qtty::Quantity;
+//! # use virolai::synthetic::random_schedule;
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let plan_a = random_schedule(&instance, 1);
+//! # let plan_b = random_schedule(&instance, 2);
+//!
+//! let model = NoiseModel::new().with_start_jitter(0.1).with_duration_noise(0.2).late_only();
+//! let mut rng = SplitMix64::new(7);
+//! let packed = robustness(&plan_a, &model, 500, Quantity::new(60.0), &mut rng);
+//! let spread = robustness(&plan_b, &model, 500, Quantity::new(60.0), &mut rng);
+//! assert!(spread.on_time_rate >= packed.on_time_rate);
+//! ```
+
+use qtty::{Quantity, Unit};
+
+use crate::schedule::Schedule;
+use crate::solution_space::Interval;
+use crate::synthetic::SplitMix64;
+
+/// Duration-weighted noise applied by [`perturb`].
+///
+/// Both kinds of noise are uniform and expressed as fractions of the task
+/// duration: a start jitter of `0.1` moves a one-hour task by up to six
+/// minutes. Unbounded tasks are left untouched.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NoiseModel {
+    start_jitter: f64,
+    duration_noise: f64,
+    late_only: bool,
+}
+
+impl NoiseModel {
+    /// A model without noise: [`perturb`] returns the schedule unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves starts by up to `fraction` of the task duration.
+    pub fn with_start_jitter(mut self, fraction: f64) -> Self {
+        self.start_jitter = fraction.max(0.0);
+        self
+    }
+
+    /// Scales durations by up to `fraction` (e.g. `0.2` → ±20%).
+    pub fn with_duration_noise(mut self, fraction: f64) -> Self {
+        self.duration_noise = fraction.max(0.0);
+        self
+    }
+
+    /// Only delays starts and lengthens durations, as overruns do.
+    pub fn late_only(mut self) -> Self {
+        self.late_only = true;
+        self
+    }
+
+    /// Draws a factor in `[-fraction, fraction]`, or `[0, fraction]` if late
+    /// only.
+    fn draw(&self, fraction: f64, rng: &mut SplitMix64) -> f64 {
+        let low = if self.late_only { 0.0 } else { -fraction };
+        rng.range(low, fraction)
+    }
+}
+
+/// Returns a randomly perturbed copy of `schedule`.
+///
+/// Tasks are replayed in start order. Each start is jittered and each
+/// duration scaled according to `model`, then the task is pushed after the
+/// previous one if the noise made them overlap. Background tasks are
+/// perturbed the same way but stay in the background lane, where overlaps
+/// are allowed.
+pub fn perturb<U: Unit>(
+    schedule: &Schedule<U>,
+    model: &NoiseModel,
+    rng: &mut SplitMix64,
+) -> Schedule<U> {
+    let mut perturbed = Schedule::new();
+    let mut cursor = f64::NEG_INFINITY;
+    for (id, interval, background) in schedule.iter_all() {
+        let mut start = interval.start().value();
+        let mut duration = interval.duration().value();
+        if interval.is_bounded() {
+            start += model.draw(model.start_jitter, rng) * duration;
+            duration *= 1.0 + model.draw(model.duration_noise, rng);
+        }
+        let start = if background { start } else { start.max(cursor) };
+        let end = start + duration.max(0.0);
+        if !background {
+            cursor = end;
+        }
+        perturbed
+            .add_to_lane(
+                id,
+                Interval::new(Quantity::new(start), Quantity::new(end)),
+                background,
+            )
+            .expect("replay keeps tasks apart");
+    }
+    perturbed
+}
+
+/// Empirical robustness of a schedule, see [`robustness`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RobustnessReport<U: Unit> {
+    /// Number of perturbations run.
+    pub samples: usize,
+    /// Mean delay of task ends past their planned end.
+    pub mean_delay: Quantity<U>,
+    /// Largest delay seen.
+    pub max_delay: Quantity<U>,
+    /// Fraction of task executions ending within the tolerance of their
+    /// planned end.
+    pub on_time_rate: f64,
+}
+
+/// Perturbs `schedule` `samples` times and measures end delays.
+///
+/// A task is on time when it ends at most `tolerance` after its planned end.
+/// An empty schedule, or zero samples, is perfectly robust.
+pub fn robustness<U: Unit>(
+    schedule: &Schedule<U>,
+    model: &NoiseModel,
+    samples: usize,
+    tolerance: Quantity<U>,
+    rng: &mut SplitMix64,
+) -> RobustnessReport<U> {
+    let mut total_delay = 0.0;
+    let mut max_delay: f64 = 0.0;
+    let mut on_time = 0usize;
+    let mut runs = 0usize;
+
+    for _ in 0..samples {
+        let perturbed = perturb(schedule, model, rng);
+        for (id, planned, _) in schedule.iter_all() {
+            let actual = perturbed
+                .get_interval(&id)
+                .expect("perturbation keeps every task");
+            let delay = (actual.end().value() - planned.end().value()).max(0.0);
+            total_delay += delay;
+            max_delay = max_delay.max(delay);
+            on_time += usize::from(delay <= tolerance.value());
+            runs += 1;
+        }
+    }
+
+    RobustnessReport {
+        samples,
+        mean_delay: Quantity::new(if runs > 0 {
+            total_delay / runs as f64
+        } else {
+            0.0
+        }),
+        max_delay: Quantity::new(max_delay),
+        on_time_rate: if runs > 0 {
+            on_time as f64 / runs as f64
+        } else {
+            1.0
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{iv, q};
+    use qtty::Second;
+
+    fn schedule(entries: &[(&str, f64, f64)]) -> Schedule<Second> {
+        let mut s = Schedule::new();
+        for &(id, start, end) in entries {
+            s.add(id, iv(start, end)).unwrap();
+        }
+        s
+    }
+
+    #[test]
+    fn perturbation_keeps_order_and_separation() {
+        let plan = schedule(&[("a", 0.0, 100.0), ("b", 100.0, 110.0), ("c", 200.0, 400.0)]);
+        let model = NoiseModel::new()
+            .with_start_jitter(0.5)
+            .with_duration_noise(0.5);
+        let mut rng = SplitMix64::new(3);
+        for _ in 0..100 {
+            let p = perturb(&plan, &model, &mut rng);
+            let order: Vec<_> = p.iter().map(|(id, _)| id).collect();
+            assert_eq!(order, ["a", "b", "c"]);
+            let intervals: Vec<_> = p.intervals().collect();
+            assert!(intervals.windows(2).all(|w| w[0].end() <= w[1].start()));
+            // Noise is proportional to duration.
+            let b = p.get_interval("b").unwrap();
+            assert!(b.duration().value() <= 15.0 + 1e-9);
+        }
+
+        let unchanged = perturb(&plan, &NoiseModel::new(), &mut rng);
+        assert_eq!(
+            unchanged.iter().collect::<Vec<_>>(),
+            plan.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn background_tasks_are_perturbed_in_their_lane() {
+        let mut plan = schedule(&[("a", 0.0, 100.0), ("b", 100.0, 200.0)]);
+        plan.add_background("monitor", iv(0.0, 200.0)).unwrap();
+        let model = NoiseModel::new().with_duration_noise(0.5).late_only();
+        let p = perturb(&plan, &model, &mut SplitMix64::new(5));
+        assert!(p.is_background("monitor"));
+        assert_eq!(p.get_interval("monitor").unwrap().start(), q(0.0));
+        assert_eq!(p.len(), 2);
+
+        let report = robustness(&plan, &model, 10, q(1000.0), &mut SplitMix64::new(5));
+        assert_eq!(report.on_time_rate, 1.0);
+        assert!(report.max_delay > q(0.0));
+    }
+
+    #[test]
+    fn slack_makes_schedules_more_robust() {
+        let packed = schedule(&[("a", 0.0, 100.0), ("b", 100.0, 200.0), ("c", 200.0, 300.0)]);
+        let spread = schedule(&[("a", 0.0, 100.0), ("b", 150.0, 250.0), ("c", 300.0, 400.0)]);
+        let model = NoiseModel::new()
+            .with_start_jitter(0.1)
+            .with_duration_noise(0.3)
+            .late_only();
+
+        let packed = robustness(&packed, &model, 200, q(0.0), &mut SplitMix64::new(1));
+        let spread = robustness(&spread, &model, 200, q(0.0), &mut SplitMix64::new(1));
+        assert_eq!(packed.samples, 200);
+        assert!(spread.mean_delay < packed.mean_delay);
+        assert!(spread.max_delay <= packed.max_delay);
+        assert!(spread.on_time_rate <= 1.0 && packed.on_time_rate < 1.0);
+
+        let empty = robustness(
+            &Schedule::<Second>::new(),
+            &model,
+            10,
+            q(0.0),
+            &mut SplitMix64::new(1),
+        );
+        assert_eq!(empty.on_time_rate, 1.0);
+        assert_eq!(empty.mean_delay, q(0.0));
+    }
+}
@@ -0,0 +1,635 @@
+//! Registry of scheduling runs.
+//!
+//! Every call to [`RunRegistry::run`] schedules a problem and keeps a
+//! [`RunRecord`]: a unique run ID, the tenant it belongs to, the algorithm
+//! name and its configuration, a hash of the inputs, when it started and how
+//! long it took, outcome KPIs and the schedule itself. Records answer
+//! questions such as "which plan did we generate last Tuesday at 14:03?"
+//! ([`RunRegistry::latest_at`]) or "have we already solved these exact
+//! inputs?" ([`RunRegistry::find_by_inputs`]).
+//!
+//! Runs are isolated by tenant: every lookup takes the tenant, and a run is
+//! invisible to other tenants even if its ID is known.
+//!
+//! A registry lives in memory ([`RunRegistry::new`]) or is backed by an
+//! append-only text file ([`RunRegistry::open`]) with one tab-separated line
+//! per run, configuration entry and schedule entry:
+//!
+//! ```text
+//! run      <id> <tenant> <algorithm> <started ms> <duration µs> <inputs hash> <scheduled> <unscheduled> <utilization> <priority>
+//! config   <key> <value>
+//! entry    <task> <start> <end> [background]
+//! ```
+//!
+//! Entries of the schedule's background lane carry a trailing `background`
+//! field.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::time::SystemTime;
+//! # use qtty::{Quantity, Second};
+//! # use virolai::algorithms::ESTScheduler;
+//! # use virolai::runs::{RunRegistry, RunSpec};
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let space = instance.solution_space;
+//! # let tuesday_14_03 = SystemTime::now();
+//! let mut registry = RunRegistry::open("runs.tsv")?;
+//! let spec = RunSpec::new("observatory-a", "est").with_config("max_iterations", "1000");
+//! let run_id = registry.run(spec, &ESTScheduler::new(Quantity::new(1000.0)), &blocks, &space, horizon)?.id.clone();
+//!
+//! let tuesday = registry.latest_at("observatory-a", tuesday_14_03).unwrap();
+//! println!("{} scheduled {} tasks", tuesday.id, tuesday.kpis.scheduled);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use qtty::{Quantity, Unit};
+use thiserror::Error;
+
+use crate::algorithms::est::metrics::value_report;
+use crate::algorithms::SchedulingAlgorithm;
+use crate::hash::Fnv1a;
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
+use crate::{generate_id, Id};
+
+/// Errors raised by a [`RunRegistry`].
+#[derive(Debug, Error)]
+pub enum RunError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    #[error("Run {0} is already registered")]
+    DuplicateRun(Id),
+}
+
+/// Who runs what, with which settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunSpec {
+    /// Tenant owning the run.
+    pub tenant: Id,
+    /// Algorithm name, as chosen by the caller.
+    pub algorithm: String,
+    /// Algorithm configuration as key/value pairs.
+    pub config: BTreeMap<String, String>,
+}
+
+impl RunSpec {
+    /// Creates a spec with an empty configuration.
+    pub fn new(tenant: impl Into<Id>, algorithm: impl Into<String>) -> Self {
+        Self {
+            tenant: tenant.into(),
+            algorithm: algorithm.into(),
+            config: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a configuration entry.
+    pub fn with_config(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.config.insert(key.into(), value.to_string());
+        self
+    }
+}
+
+/// Outcome KPIs of a run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunKpis {
+    /// Number of scheduled tasks.
+    pub scheduled: usize,
+    /// Number of tasks left out of the schedule.
+    pub unscheduled: usize,
+    /// Fraction of the horizon covered by scheduled tasks.
+    pub utilization: f64,
+    /// Sum of priorities of the scheduled tasks.
+    pub priority_scheduled: f64,
+}
+
+impl RunKpis {
+    /// Measures `schedule` against the tasks of `blocks` over `horizon`.
+    pub fn measure<T, U, D, E>(
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        schedule: &Schedule<U>,
+        horizon: Horizon<U>,
+    ) -> Self
+    where
+        T: Task<U>,
+        U: Unit,
+        E: petgraph::EdgeType,
+    {
+        let report = value_report(blocks.iter().flat_map(|b| b.tasks()), schedule);
+        let task_count: usize = blocks.iter().map(|b| b.task_count()).sum();
+        let length = horizon.duration().value();
+        Self {
+            scheduled: report.scheduled,
+            unscheduled: task_count - report.scheduled,
+            utilization: if length > 0.0 {
+                schedule.total_duration().value() / length
+            } else {
+                0.0
+            },
+            priority_scheduled: report.nominal_total,
+        }
+    }
+}
+
+/// One recorded scheduling run.
+#[derive(Debug, Clone)]
+pub struct RunRecord<U: Unit> {
+    /// Unique run ID.
+    pub id: Id,
+    /// Tenant, algorithm and configuration.
+    pub spec: RunSpec,
+    /// Hash of the inputs, see [`inputs_hash`].
+    pub inputs_hash: u64,
+    /// Wall-clock start of the run.
+    pub started_at: SystemTime,
+    /// Time spent scheduling.
+    pub duration: Duration,
+    /// Outcome KPIs.
+    pub kpis: RunKpis,
+    /// The produced schedule.
+    pub schedule: Schedule<U>,
+}
+
+/// Stores scheduling runs, in memory or in an append-only file.
+#[derive(Debug, Clone)]
+pub struct RunRegistry<U: Unit> {
+    records: Vec<RunRecord<U>>,
+    path: Option<PathBuf>,
+}
+
+impl<U: Unit> Default for RunRegistry<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<U: Unit> RunRegistry<U> {
+    /// Creates an empty in-memory registry.
+    pub fn new() -> Self {
+        Self {
+            records: Vec::new(),
+            path: None,
+        }
+    }
+
+    /// Opens a file-backed registry, loading the runs already in `path`.
+    ///
+    /// The file is created on the first recorded run if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunError::Io`] if the file cannot be read and
+    /// [`RunError::Parse`] if it is malformed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, RunError> {
+        let path = path.as_ref().to_path_buf();
+        let records = match File::open(&path) {
+            Ok(file) => parse(BufReader::new(file))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Self {
+            records,
+            path: Some(path),
+        })
+    }
+
+    /// Number of recorded runs, across all tenants.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns true if no run was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Schedules the problem with `algorithm` and records the run.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunError::Io`] if a file-backed registry cannot be written.
+    /// The run is not recorded in that case.
+    pub fn run<A, T, D, E>(
+        &mut self,
+        spec: RunSpec,
+        algorithm: &A,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+    ) -> Result<&RunRecord<U>, RunError>
+    where
+        A: SchedulingAlgorithm<T, U, D, E> + ?Sized,
+        T: Task<U>,
+        E: petgraph::EdgeType,
+    {
+        let inputs_hash = inputs_hash(blocks, solution_space, horizon);
+        let started_at = SystemTime::now();
+        let clock = Instant::now();
+        let schedule = algorithm.schedule(blocks, solution_space, horizon);
+        let duration = clock.elapsed();
+
+        let kpis = RunKpis::measure(blocks, &schedule, horizon);
+
+        self.record(RunRecord {
+            id: generate_id(),
+            spec,
+            inputs_hash,
+            started_at,
+            duration,
+            kpis,
+            schedule,
+        })
+    }
+
+    /// Records a run produced elsewhere.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RunError::DuplicateRun`] if the run ID is taken and
+    /// [`RunError::Io`] if a file-backed registry cannot be written.
+    pub fn record(&mut self, record: RunRecord<U>) -> Result<&RunRecord<U>, RunError> {
+        if self.records.iter().any(|r| r.id == record.id) {
+            return Err(RunError::DuplicateRun(record.id));
+        }
+        if let Some(path) = &self.path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            // One write per run keeps concurrent appenders from interleaving lines.
+            file.write_all(format_record(&record).as_bytes())?;
+            file.flush()?;
+        }
+        self.records.push(record);
+        Ok(self.records.last().expect("just pushed"))
+    }
+
+    /// Returns the run `run_id` of `tenant`.
+    pub fn get<'a>(&'a self, tenant: &'a str, run_id: &str) -> Option<&'a RunRecord<U>> {
+        self.runs(tenant).find(|r| r.id == run_id)
+    }
+
+    /// Returns the runs of `tenant` in recording order.
+    pub fn runs<'a>(&'a self, tenant: &'a str) -> impl Iterator<Item = &'a RunRecord<U>> + 'a {
+        self.records.iter().filter(move |r| r.spec.tenant == tenant)
+    }
+
+    /// Returns the latest run of `tenant` started at or before `at`.
+    pub fn latest_at<'a>(&'a self, tenant: &'a str, at: SystemTime) -> Option<&'a RunRecord<U>> {
+        self.runs(tenant)
+            .filter(|r| r.started_at <= at)
+            .max_by_key(|r| r.started_at)
+    }
+
+    /// Returns the runs of `tenant` made on inputs hashing to `hash`.
+    pub fn find_by_inputs<'a>(
+        &'a self,
+        tenant: &'a str,
+        hash: u64,
+    ) -> impl Iterator<Item = &'a RunRecord<U>> + 'a {
+        self.runs(tenant).filter(move |r| r.inputs_hash == hash)
+    }
+}
+
+/// Hashes the scheduling inputs: tasks (ID, name, size, priority),
+/// dependencies, solution-space windows and horizon.
+///
+/// The hash is FNV-1a over a canonical ordering, so it is stable across
+/// processes and platforms and can be compared with hashes stored on disk.
+pub fn inputs_hash<T, U, D, E>(
+    blocks: &[SchedulingBlock<T, U, D, E>],
+    solution_space: &SolutionSpace<U>,
+    horizon: Horizon<U>,
+) -> u64
+where
+    T: Task<U>,
+    U: Unit,
+    E: petgraph::EdgeType,
+{
+    let mut hasher = Fnv1a::new();
+    for block in blocks {
+        let mut tasks: Vec<(&str, &T)> = block.tasks().collect();
+        tasks.sort_unstable_by_key(|(id, _)| *id);
+        hasher.write_usize(tasks.len());
+        for (id, task) in tasks {
+            hasher.write_str(id);
+            hasher.write_str(task.name());
+            hasher.write_f64(task.size_on_axis().value());
+            hasher.write(&task.priority().to_le_bytes());
+
+            let node = block.node_of(id).expect("task listed by the block");
+            let mut successors: Vec<&str> = block
+                .successors(node)
+                .into_iter()
+                .filter_map(|n| block.id_of(n))
+                .collect();
+            successors.sort_unstable();
+            hasher.write_usize(successors.len());
+            for succ in successors {
+                hasher.write_str(succ);
+            }
+        }
+    }
+
+    let mut entries: Vec<_> = solution_space.iter().collect();
+    entries.sort_unstable_by_key(|&(id, _)| id);
+    for (id, windows) in entries {
+        hasher.write_str(id);
+        hasher.write_usize(windows.len());
+        for window in windows.iter() {
+            hasher.write_f64(window.start().value());
+            hasher.write_f64(window.end().value());
+        }
+    }
+    hasher.write_f64(horizon.start().value());
+    hasher.write_f64(horizon.end().value());
+    hasher.finish()
+}
+
+fn format_record<U: Unit>(record: &RunRecord<U>) -> String {
+    let started_ms = record
+        .started_at
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    let mut out = format!(
+        "run\t{}\t{}\t{}\t{}\t{}\t{:016x}\t{}\t{}\t{}\t{}\n",
+        escape(&record.id),
+        escape(&record.spec.tenant),
+        escape(&record.spec.algorithm),
+        started_ms,
+        record.duration.as_micros(),
+        record.inputs_hash,
+        record.kpis.scheduled,
+        record.kpis.unscheduled,
+        record.kpis.utilization,
+        record.kpis.priority_scheduled,
+    );
+    for (key, value) in &record.spec.config {
+        out += &format!("config\t{}\t{}\n", escape(key), escape(value));
+    }
+    for (id, interval, background) in record.schedule.iter_all() {
+        out += &format!(
+            "entry\t{}\t{}\t{}{}\n",
+            escape(&id),
+            interval.start().value(),
+            interval.end().value(),
+            if background { "\tbackground" } else { "" }
+        );
+    }
+    out
+}
+
+fn parse<U: Unit, R: BufRead>(reader: R) -> Result<Vec<RunRecord<U>>, RunError> {
+    let mut records: Vec<RunRecord<U>> = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        let error = |message: String| RunError::Parse {
+            line: line_no,
+            message,
+        };
+        let fields: Vec<String> = line.split('\t').map(unescape).collect();
+        let number = |i: usize, name: &str| -> Result<f64, RunError> {
+            fields[i]
+                .parse::<f64>()
+                .map_err(|_| error(format!("invalid {name} {:?}", fields[i])))
+        };
+
+        match (fields[0].as_str(), fields.len()) {
+            ("run", 11) => {
+                let int = |i: usize, name: &str| -> Result<u128, RunError> {
+                    fields[i]
+                        .parse::<u128>()
+                        .map_err(|_| error(format!("invalid {name} {:?}", fields[i])))
+                };
+                let inputs_hash = u64::from_str_radix(&fields[6], 16)
+                    .map_err(|_| error(format!("invalid inputs hash {:?}", fields[6])))?;
+                records.push(RunRecord {
+                    id: fields[1].clone(),
+                    spec: RunSpec::new(fields[2].clone(), fields[3].clone()),
+                    inputs_hash,
+                    started_at: UNIX_EPOCH + Duration::from_millis(int(4, "start time")? as u64),
+                    duration: Duration::from_micros(int(5, "duration")? as u64),
+                    kpis: RunKpis {
+                        scheduled: int(7, "scheduled count")? as usize,
+                        unscheduled: int(8, "unscheduled count")? as usize,
+                        utilization: number(9, "utilization")?,
+                        priority_scheduled: number(10, "priority")?,
+                    },
+                    schedule: Schedule::new(),
+                });
+            }
+            ("config", 3) => {
+                let record = records
+                    .last_mut()
+                    .ok_or_else(|| error("config line before any run".to_string()))?;
+                record
+                    .spec
+                    .config
+                    .insert(fields[1].clone(), fields[2].clone());
+            }
+            ("entry", n @ (4 | 5)) => {
+                let background = n == 5;
+                if background && fields[4] != "background" {
+                    return Err(error(format!("unknown lane {:?}", fields[4])));
+                }
+                let (start, end) = (number(2, "start")?, number(3, "end")?);
+                if start.is_nan() || end.is_nan() || end < start {
+                    return Err(error(format!("invalid entry interval [{start}, {end})")));
+                }
+                let record = records
+                    .last_mut()
+                    .ok_or_else(|| error("entry line before any run".to_string()))?;
+                record
+                    .schedule
+                    .add_to_lane(
+                        fields[1].as_str(),
+                        Interval::new(Quantity::new(start), Quantity::new(end)),
+                        background,
+                    )
+                    .map_err(|err| error(err.to_string()))?;
+            }
+            (kind, n) => return Err(error(format!("unexpected {kind:?} line with {n} fields"))),
+        }
+    }
+    Ok(records)
+}
+
+/// Escapes backslashes, tabs and line breaks.
+fn escape(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::GreedyScheduler;
+    use crate::test_utils::{block_and_space, hz, iv, TestTask};
+    use qtty::Second;
+
+    fn problem() -> (
+        Vec<SchedulingBlock<TestTask, Second>>,
+        SolutionSpace<Second>,
+    ) {
+        let (block, space) = block_and_space(&[
+            ("a", 30.0, 5, iv(0.0, 100.0)),
+            ("b", 40.0, 3, iv(0.0, 100.0)),
+            ("c", 50.0, 1, iv(0.0, 100.0)),
+        ]);
+        (vec![block], space)
+    }
+
+    #[test]
+    fn runs_are_recorded_per_tenant() {
+        let (blocks, space) = problem();
+        let mut registry = RunRegistry::new();
+        let spec = RunSpec::new("alpha", "greedy").with_config("seed", 7);
+        let run = registry
+            .run(
+                spec,
+                &GreedyScheduler::new(),
+                &blocks,
+                &space,
+                hz(0.0, 100.0),
+            )
+            .unwrap();
+        assert_eq!(run.kpis.scheduled, 2);
+        assert_eq!(run.kpis.unscheduled, 1);
+        assert_eq!(run.kpis.utilization, 0.7);
+        assert_eq!(run.spec.config["seed"], "7");
+        let id = run.id.clone();
+        let hash = run.inputs_hash;
+
+        registry
+            .run(
+                RunSpec::new("beta", "greedy"),
+                &GreedyScheduler::new(),
+                &blocks,
+                &space,
+                hz(0.0, 100.0),
+            )
+            .unwrap();
+        assert_eq!(registry.len(), 2);
+        assert!(registry.get("alpha", &id).is_some());
+        assert!(registry.get("beta", &id).is_none());
+        assert_eq!(registry.runs("beta").count(), 1);
+        assert_eq!(registry.find_by_inputs("alpha", hash).count(), 1);
+
+        let now = SystemTime::now();
+        assert_eq!(registry.latest_at("alpha", now).unwrap().id, id);
+        assert!(registry
+            .latest_at("alpha", now - Duration::from_secs(3600))
+            .is_none());
+    }
+
+    #[test]
+    fn inputs_hash_tracks_inputs() {
+        let (blocks, mut space) = problem();
+        let hash = inputs_hash(&blocks, &space, hz(0.0, 100.0));
+        assert_eq!(hash, inputs_hash(&blocks, &space, hz(0.0, 100.0)));
+        assert_ne!(hash, inputs_hash(&blocks, &space, hz(0.0, 90.0)));
+        space.set_intervals("c", vec![iv(10.0, 100.0)]);
+        assert_ne!(hash, inputs_hash(&blocks, &space, hz(0.0, 100.0)));
+    }
+
+    #[test]
+    fn file_backed_registry_reloads_runs() {
+        let path = std::env::temp_dir().join(format!("virolai-runs-{}.tsv", generate_id()));
+        let (blocks, space) = problem();
+        let spec = RunSpec::new("tab\there", "greedy").with_config("note", "line\nbreak \\ ok");
+
+        let mut registry = RunRegistry::open(&path).unwrap();
+        let recorded = registry
+            .run(
+                spec,
+                &GreedyScheduler::new(),
+                &blocks,
+                &space,
+                hz(0.0, 100.0),
+            )
+            .unwrap()
+            .clone();
+        let duplicate = registry.record(recorded.clone()).unwrap_err();
+        assert!(matches!(duplicate, RunError::DuplicateRun(_)));
+
+        let reloaded = RunRegistry::<Second>::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let run = reloaded.get("tab\there", &recorded.id).unwrap();
+        assert_eq!(run.spec, recorded.spec);
+        assert_eq!(run.kpis, recorded.kpis);
+        assert_eq!(run.inputs_hash, recorded.inputs_hash);
+        assert_eq!(
+            run.schedule.iter().collect::<Vec<_>>(),
+            recorded.schedule.iter().collect::<Vec<_>>()
+        );
+
+        let err = parse::<Second, _>("entry\ta\t0\t1\n".as_bytes()).unwrap_err();
+        assert!(matches!(err, RunError::Parse { line: 1, .. }));
+    }
+
+    #[test]
+    fn background_entries_survive_reload() {
+        let mut schedule = Schedule::new();
+        schedule.add("science", iv(10.0, 20.0)).unwrap();
+        schedule.add_background("monitor", iv(0.0, 100.0)).unwrap();
+        let record = RunRecord {
+            id: generate_id(),
+            spec: RunSpec::new("observatory-a", "manual"),
+            inputs_hash: 0,
+            started_at: UNIX_EPOCH,
+            duration: Duration::ZERO,
+            kpis: RunKpis::default(),
+            schedule,
+        };
+
+        let records = parse::<Second, _>(format_record(&record).as_bytes()).unwrap();
+        let reloaded = &records[0].schedule;
+        assert_eq!(
+            reloaded.iter_all().collect::<Vec<_>>(),
+            record.schedule.iter_all().collect::<Vec<_>>()
+        );
+        assert!(reloaded.is_background("monitor"));
+
+        let line = "run\tr\tt\ta\t0\t0\t0\t0\t0\t0\t0\nentry\tx\t0\t1\tside\n";
+        let err = parse::<Second, _>(line.as_bytes()).unwrap_err();
+        assert!(matches!(err, RunError::Parse { line: 2, .. }));
+    }
+}
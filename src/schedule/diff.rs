@@ -0,0 +1,482 @@
+//! Before/after comparison of schedules.
+//!
+//! [`Schedule::diff`] classifies the tasks of two schedules as added,
+//! removed, moved or unchanged. [`ScheduleDiff::to_svg`] and
+//! [`ScheduleDiff::to_html`] render the result for change review: one lane
+//! per task, the old slot drawn as a dashed outline, the new slot filled,
+//! and an arrow from the old to the new start of every moved task.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::display::DisplayOptions;
+//! # use qtty::Second;
+//! # use virolai::schedule::Schedule;
+//! # use virolai::solution_space::Interval;
+//! # let last_night: Schedule<Second> = Schedule::new();
+//! # let tonight: Schedule<Second> = Schedule::new();
+//!
+//! let diff = last_night.diff(&tonight);
+//! println!("{} moved, {} added, {} removed", diff.moved.len(), diff.added.len(), diff.removed.len());
+//! std::fs::write("review.html", diff.to_html(DisplayOptions::humanized()))?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::fmt::Write;
+
+use qtty::{Quantity, Unit};
+
+use super::Schedule;
+use crate::display::DisplayOptions;
+use crate::solution_space::Interval;
+use crate::Id;
+
+/// A task present in both schedules with a different interval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MovedTask<U: Unit> {
+    /// The moved task.
+    pub task_id: Id,
+    /// Its interval in the old schedule.
+    pub before: Interval<U>,
+    /// Its interval in the new schedule.
+    pub after: Interval<U>,
+}
+
+impl<U: Unit> MovedTask<U> {
+    /// Signed displacement of the start: positive when the task moved later.
+    pub fn shift(&self) -> Quantity<U> {
+        self.after.start() - self.before.start()
+    }
+}
+
+/// Result of [`Schedule::diff`]. Every list is in start order (old start for
+/// removed and moved tasks).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleDiff<U: Unit> {
+    /// Tasks only in the new schedule.
+    pub added: Vec<(Id, Interval<U>)>,
+    /// Tasks only in the old schedule.
+    pub removed: Vec<(Id, Interval<U>)>,
+    /// Tasks in both with a different interval.
+    pub moved: Vec<MovedTask<U>>,
+    /// Tasks in both with the same interval.
+    pub unchanged: Vec<(Id, Interval<U>)>,
+}
+
+impl<U: Unit> Schedule<U> {
+    /// Compares this schedule (the old one) with `after`.
+    pub fn diff(&self, after: &Schedule<U>) -> ScheduleDiff<U> {
+        let mut diff = ScheduleDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            moved: Vec::new(),
+            unchanged: Vec::new(),
+        };
+        for (id, before) in self.iter() {
+            match after.get_interval(&id) {
+                None => diff.removed.push((id, before)),
+                Some(now) if now == before => diff.unchanged.push((id, before)),
+                Some(now) => diff.moved.push(MovedTask {
+                    task_id: id,
+                    before,
+                    after: now,
+                }),
+            }
+        }
+        diff.added = after
+            .iter()
+            .filter(|(id, _)| !self.contains_task(id))
+            .collect();
+        diff
+    }
+}
+
+/// How a task changed, for rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Added,
+    Removed,
+    Moved,
+    Unchanged,
+}
+
+impl Change {
+    fn name(self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Moved => "moved",
+            Change::Unchanged => "unchanged",
+        }
+    }
+
+    fn color(self) -> &'static str {
+        match self {
+            Change::Added => "#2e7d32",
+            Change::Removed => "#c62828",
+            Change::Moved => "#ef6c00",
+            Change::Unchanged => "#9e9e9e",
+        }
+    }
+}
+
+/// One lane of the rendering.
+struct Lane<'a, U: Unit> {
+    id: &'a str,
+    change: Change,
+    before: Option<Interval<U>>,
+    after: Option<Interval<U>>,
+}
+
+const WIDTH: f64 = 960.0;
+const LABEL_WIDTH: f64 = 160.0;
+const MARGIN: f64 = 16.0;
+const AXIS_HEIGHT: f64 = 28.0;
+const LANE_HEIGHT: f64 = 24.0;
+const BAR_HEIGHT: f64 = 14.0;
+const TICKS: usize = 5;
+
+impl<U: Unit> ScheduleDiff<U> {
+    /// Returns `true` if no task was added, removed or moved.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+
+    /// Renders the diff as a standalone SVG image.
+    ///
+    /// Tasks get one lane each, ordered by their earliest start. Old slots
+    /// are dashed outlines, new slots are filled, colored by change; moved
+    /// tasks get an arrow from old to new start. Axis labels are formatted
+    /// with `options`. Unbounded intervals are clipped to the drawn range.
+    pub fn to_svg(&self, options: DisplayOptions) -> String {
+        let lanes = self.lanes();
+        let (lo, hi) = extent(&lanes);
+        let plot = WIDTH - LABEL_WIDTH - MARGIN;
+        let x = |v: f64| LABEL_WIDTH + (v.clamp(lo, hi) - lo) / (hi - lo) * plot;
+        let height = AXIS_HEIGHT + LANE_HEIGHT * lanes.len() as f64 + MARGIN;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" font-family="sans-serif" font-size="12">"#
+        );
+        svg.push_str(
+            r##"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto"><path d="M0,0 L10,5 L0,10 z" fill="#333"/></marker></defs>"##,
+        );
+        svg.push('\n');
+
+        for i in 0..=TICKS {
+            let v = lo + (hi - lo) * i as f64 / TICKS as f64;
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{x:.1}" y1="{top}" x2="{x:.1}" y2="{bottom:.1}" stroke="#eee"/><text x="{x:.1}" y="{label}" text-anchor="middle">{text}</text>"##,
+                x = x(v),
+                top = AXIS_HEIGHT - 4.0,
+                bottom = height - MARGIN,
+                label = AXIS_HEIGHT - 10.0,
+                text = escape(&options.quantity(Quantity::<U>::new(v))),
+            );
+        }
+
+        for (i, lane) in lanes.iter().enumerate() {
+            let top = AXIS_HEIGHT + LANE_HEIGHT * i as f64;
+            let bar = top + (LANE_HEIGHT - BAR_HEIGHT) / 2.0;
+            let mid = top + LANE_HEIGHT / 2.0;
+            let color = lane.change.color();
+            let _ = writeln!(
+                svg,
+                r#"<g class="{}"><title>{}</title>"#,
+                lane.change.name(),
+                escape(&self.describe(lane, options)),
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="end" dominant-baseline="middle">{}</text>"#,
+                LABEL_WIDTH - 6.0,
+                mid,
+                escape(lane.id),
+            );
+            if let Some(before) = lane.before.filter(|_| lane.change != Change::Unchanged) {
+                let paint = format!(r#"fill="none" stroke="{color}" stroke-dasharray="4 2""#);
+                slot(&mut svg, before, &x, bar, &paint);
+            }
+            if let Some(after) = lane.after {
+                slot(&mut svg, after, &x, bar, &format!(r#"fill="{color}""#));
+            }
+            if let (Change::Moved, Some(before), Some(after)) =
+                (lane.change, lane.before, lane.after)
+            {
+                let _ = writeln!(
+                    svg,
+                    r##"<line x1="{:.1}" y1="{mid:.1}" x2="{:.1}" y2="{mid:.1}" stroke="#333" marker-end="url(#arrow)"/>"##,
+                    x(before.start().value()),
+                    x(after.start().value()),
+                );
+            }
+            svg.push_str("</g>\n");
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Renders the diff as a standalone HTML page: a summary line, the
+    /// [`to_svg`](Self::to_svg) image and a table of the changed tasks.
+    pub fn to_html(&self, options: DisplayOptions) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Schedule diff</title>\n\
+             <style>body{font-family:sans-serif}table{border-collapse:collapse}\
+             td,th{border:1px solid #ccc;padding:2px 8px;text-align:left}</style>\n</head>\n<body>\n",
+        );
+        let _ = writeln!(
+            html,
+            "<p>{} moved, {} added, {} removed, {} unchanged</p>",
+            self.moved.len(),
+            self.added.len(),
+            self.removed.len(),
+            self.unchanged.len()
+        );
+        html.push_str(&self.to_svg(options));
+        html.push_str(
+            "<table>\n<tr><th>task</th><th>change</th><th>before</th><th>after</th><th>shift</th></tr>\n",
+        );
+        for lane in self
+            .lanes()
+            .iter()
+            .filter(|l| l.change != Change::Unchanged)
+        {
+            let slot =
+                |iv: Option<Interval<U>>| iv.map(|iv| options.interval(iv)).unwrap_or_default();
+            let shift = match (lane.before, lane.after) {
+                (Some(before), Some(after)) => options.quantity(after.start() - before.start()),
+                _ => String::new(),
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(lane.id),
+                lane.change.name(),
+                escape(&slot(lane.before)),
+                escape(&slot(lane.after)),
+                escape(&shift),
+            );
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+
+    /// Every task with its change, ordered by earliest start then ID.
+    fn lanes(&self) -> Vec<Lane<'_, U>> {
+        let mut lanes: Vec<Lane<'_, U>> = self
+            .unchanged
+            .iter()
+            .map(|(id, iv)| Lane {
+                id,
+                change: Change::Unchanged,
+                before: Some(*iv),
+                after: Some(*iv),
+            })
+            .chain(self.removed.iter().map(|(id, iv)| Lane {
+                id,
+                change: Change::Removed,
+                before: Some(*iv),
+                after: None,
+            }))
+            .chain(self.added.iter().map(|(id, iv)| Lane {
+                id,
+                change: Change::Added,
+                before: None,
+                after: Some(*iv),
+            }))
+            .chain(self.moved.iter().map(|m| Lane {
+                id: &m.task_id,
+                change: Change::Moved,
+                before: Some(m.before),
+                after: Some(m.after),
+            }))
+            .collect();
+        let first = |l: &Lane<'_, U>| {
+            l.before
+                .into_iter()
+                .chain(l.after)
+                .map(|iv| iv.start().value())
+                .fold(f64::INFINITY, f64::min)
+        };
+        lanes.sort_by(|a, b| first(a).total_cmp(&first(b)).then(a.id.cmp(b.id)));
+        lanes
+    }
+
+    fn describe(&self, lane: &Lane<'_, U>, options: DisplayOptions) -> String {
+        match (lane.before, lane.after) {
+            (Some(before), Some(after)) if lane.change == Change::Moved => format!(
+                "{}: {} → {}",
+                lane.id,
+                options.interval(before),
+                options.interval(after)
+            ),
+            (_, Some(iv)) | (Some(iv), None) => format!(
+                "{}: {} ({})",
+                lane.id,
+                options.interval(iv),
+                lane.change.name()
+            ),
+            (None, None) => lane.id.to_string(),
+        }
+    }
+}
+
+/// Draws `interval` as a bar at height `bar`, or as a diamond if it is a
+/// milestone.
+fn slot<U: Unit>(
+    svg: &mut String,
+    interval: Interval<U>,
+    x: &impl Fn(f64) -> f64,
+    bar: f64,
+    paint: &str,
+) {
+    let (x0, x1) = (x(interval.start().value()), x(interval.end().value()));
+    if interval.is_empty() {
+        let (half, mid) = (BAR_HEIGHT / 2.0, bar + BAR_HEIGHT / 2.0);
+        let _ = writeln!(
+            svg,
+            r#"<polygon class="milestone" points="{x0:.1},{bar:.1} {:.1},{mid:.1} {x0:.1},{:.1} {:.1},{mid:.1}" {paint}/>"#,
+            x0 + half,
+            bar + BAR_HEIGHT,
+            x0 - half,
+        );
+    } else {
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{x0:.1}" y="{bar:.1}" width="{:.1}" height="{BAR_HEIGHT}" {paint}/>"#,
+            x1 - x0,
+        );
+    }
+}
+
+/// Finite range covered by every drawn interval, never empty.
+fn extent<U: Unit>(lanes: &[Lane<'_, U>]) -> (f64, f64) {
+    let (lo, hi) = lanes
+        .iter()
+        .flat_map(|l| l.before.into_iter().chain(l.after))
+        .flat_map(|iv| [iv.start().value(), iv.end().value()])
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+    if lo < hi {
+        (lo, hi)
+    } else if lo.is_finite() {
+        (lo, lo + 1.0)
+    } else {
+        (0.0, 1.0)
+    }
+}
+
+/// Escapes text for XML content and attribute values.
+pub(crate) fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qtty::Second;
+
+    fn iv(start: f64, end: f64) -> Interval<Second> {
+        Interval::from_f64(start, end)
+    }
+
+    fn schedule(entries: &[(&str, f64, f64)]) -> Schedule<Second> {
+        let mut s = Schedule::new();
+        for &(id, start, end) in entries {
+            s.add(id, iv(start, end)).unwrap();
+        }
+        s
+    }
+
+    fn nights() -> (Schedule<Second>, Schedule<Second>) {
+        let before = schedule(&[("a", 0.0, 10.0), ("b", 20.0, 30.0), ("c", 40.0, 50.0)]);
+        let after = schedule(&[("a", 0.0, 10.0), ("c", 60.0, 70.0), ("d<&>", 20.0, 25.0)]);
+        (before, after)
+    }
+
+    #[test]
+    fn diff_classifies_tasks() {
+        let (before, after) = nights();
+        let diff = before.diff(&after);
+
+        assert_eq!(diff.unchanged, vec![("a".into(), iv(0.0, 10.0))]);
+        assert_eq!(diff.removed, vec![("b".into(), iv(20.0, 30.0))]);
+        assert_eq!(diff.added, vec![("d<&>".into(), iv(20.0, 25.0))]);
+        assert_eq!(diff.moved.len(), 1);
+        assert_eq!(diff.moved[0].task_id, "c");
+        assert_eq!(diff.moved[0].shift(), Quantity::new(20.0));
+        assert!(!diff.is_empty());
+        assert!(before.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn svg_draws_one_lane_per_task_and_arrows_for_moves() {
+        let (before, after) = nights();
+        let svg = before.diff(&after).to_svg(DisplayOptions::new());
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<g class=").count(), 4);
+        assert_eq!(svg.matches("marker-end").count(), 1);
+        // Old slots of moved and removed tasks are outlines.
+        assert_eq!(svg.matches("stroke-dasharray").count(), 2);
+        assert!(svg.contains("d&lt;&amp;&gt;"));
+        assert!(!svg.contains("d<&>"));
+        // Lanes follow the earliest start: a, b and d (20, by ID), c.
+        let order: Vec<usize> = ["removed", "added", "moved"]
+            .iter()
+            .map(|c| svg.find(&format!("class=\"{c}\"")).unwrap())
+            .collect();
+        assert!(order.windows(2).all(|w| w[0] < w[1]));
+        assert!(svg.contains(">70.000</text>"));
+        assert!(!svg.contains("milestone"));
+    }
+
+    #[test]
+    fn svg_draws_milestones_as_diamonds() {
+        let mut before = Schedule::<Second>::new();
+        before.add("obs", Interval::from_f64(0.0, 10.0)).unwrap();
+        before.add("gate", Interval::from_f64(10.0, 10.0)).unwrap();
+        let mut after = before.clone();
+        after.remove("gate");
+        after.add("gate", Interval::from_f64(5.0, 5.0)).unwrap();
+
+        let svg = before.diff(&after).to_svg(DisplayOptions::new());
+        // Old and new point of the moved milestone; the task stays a bar.
+        assert_eq!(svg.matches(r#"<polygon class="milestone""#).count(), 2);
+        assert_eq!(svg.matches("<rect").count(), 1);
+    }
+
+    #[test]
+    fn html_lists_only_changes() {
+        let (before, after) = nights();
+        let html = before.diff(&after).to_html(DisplayOptions::new());
+
+        assert!(html.contains("<p>1 moved, 1 added, 1 removed, 1 unchanged</p>"));
+        assert!(html.contains("<svg"));
+        assert_eq!(html.matches("<tr><td>").count(), 3);
+        assert!(html.contains(
+            "<tr><td>c</td><td>moved</td><td>[40.000, 50.000]</td><td>[60.000, 70.000]</td><td>20.000</td></tr>"
+        ));
+
+        let empty = Schedule::<Second>::new().diff(&Schedule::new());
+        assert!(empty.to_svg(DisplayOptions::new()).contains("</svg>"));
+    }
+}
@@ -29,6 +29,39 @@ impl PartialOrd for F64Key {
     }
 }
 
+/// Position of an entry in the start index.
+///
+/// Entries are ordered by start, then end, then insertion order (`seq`).
+/// Start alone is not unique once milestones (zero-length entries) are
+/// allowed: several may share an instant, and one may share the start of a
+/// task, ahead of which it sorts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct SlotKey {
+    pub(crate) start: F64Key,
+    pub(crate) end: F64Key,
+    pub(crate) seq: u64,
+}
+
+impl SlotKey {
+    /// The smallest key starting at `start`.
+    pub(crate) fn first_at(start: F64Key) -> Self {
+        Self {
+            start,
+            end: F64Key(f64::NEG_INFINITY),
+            seq: 0,
+        }
+    }
+
+    /// The largest key starting at `start`.
+    pub(crate) fn last_at(start: F64Key) -> Self {
+        Self {
+            start,
+            end: F64Key(f64::INFINITY),
+            seq: u64::MAX,
+        }
+    }
+}
+
 /// An entry in the schedule, mapping a task ID to its interval.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Entry<U: qtty::Unit> {
@@ -12,6 +12,15 @@ pub enum ScheduleError {
     OverlapsExisting { new_id: Id, existing_id: Id },
     /// Task ID was not found in the schedule
     TaskNotFound(Id),
+    /// A task was booked on two resources of the same mutual-exclusion group
+    ExclusiveResources { task_id: Id, first: Id, second: Id },
+    /// A task would exceed the concurrency limit of its class
+    ConcurrencyLimitReached {
+        task_id: Id,
+        key: String,
+        value: String,
+        max: usize,
+    },
 }
 
 impl fmt::Display for ScheduleError {
@@ -32,6 +41,27 @@ impl fmt::Display for ScheduleError {
             ScheduleError::TaskNotFound(id) => {
                 write!(f, "Task ID {id} not found in schedule")
             }
+            ScheduleError::ExclusiveResources {
+                task_id,
+                first,
+                second,
+            } => {
+                write!(
+                    f,
+                    "Task {task_id} cannot use mutually exclusive resources {first} and {second}"
+                )
+            }
+            ScheduleError::ConcurrencyLimitReached {
+                task_id,
+                key,
+                value,
+                max,
+            } => {
+                write!(
+                    f,
+                    "Task {task_id} would exceed the limit of {max} concurrent tasks tagged {key}={value}"
+                )
+            }
         }
     }
 }
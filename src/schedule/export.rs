@@ -0,0 +1,226 @@
+//! Schedules as records for planning UIs (feature `chrono`).
+//!
+//! [`to_view_model`] projects a [`Schedule`] into a JSON:API-style document:
+//! one `scheduled_task` resource per entry, with its start and end as
+//! RFC 3339 strings in the viewer's timezone, its duration in seconds and
+//! the name and priority of the task it schedules. Entries of the
+//! [background lane](Schedule#background-tasks) are included and flagged.
+//! With the `serde` feature
+//! the document serializes as is, so frontends need no conversion layer of
+//! their own.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #[cfg(feature = "serde")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # use chrono::{TimeZone, Utc};
+//! # use qtty::Second;
+//! # use virolai::schedule::Schedule;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! use virolai::schedule::export::to_view_model;
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let blocks = vec![generate(&SyntheticConfig::new(20, horizon)).block];
+//! # let schedule: Schedule<Second> = Schedule::new();
+//! # let night_start = Utc.with_ymd_and_hms(2026, 3, 1, 23, 0, 0).unwrap();
+//!
+//! let view = to_view_model(&schedule, &blocks, night_start, &chrono_tz::Chile::Continental);
+//! let body = serde_json::to_string(&view)?;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "serde"))]
+//! # fn main() {}
+//! ```
+//!
+//! produces
+//!
+//! ```text
+//! {"data":[{"type":"scheduled_task","id":"m31","attributes":{"name":"M31",
+//!   "priority":5,"start":"2026-03-01T21:00:00-03:00","end":"2026-03-01T21:10:00-03:00",
+//!   "duration_seconds":600.0,"milestone":false,
+//!   "background":false}}],
+//!  "meta":{"epoch":"2026-03-01T21:00:00-03:00","count":1}}
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use petgraph::EdgeType;
+use qtty::{Second, Unit};
+
+use super::Schedule;
+use crate::calendar::CalendarAxis;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::units::SameDim;
+use crate::Id;
+
+/// Resource type of every record in a [`ViewModel`].
+pub const SCHEDULED_TASK: &str = "scheduled_task";
+
+/// A schedule projected for a planning UI, see [`to_view_model`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewModel {
+    /// One record per entry of either lane, in start order.
+    pub data: Vec<TaskRecord>,
+    pub meta: ViewMeta,
+}
+
+/// Document-level information of a [`ViewModel`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewMeta {
+    /// The instant of axis zero, in the viewer's timezone.
+    pub epoch: String,
+    /// Number of records.
+    pub count: usize,
+}
+
+/// A scheduled task as a JSON:API resource.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskRecord {
+    /// Always [`SCHEDULED_TASK`].
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub kind: &'static str,
+    pub id: Id,
+    pub attributes: TaskAttributes,
+}
+
+/// What a planning UI shows for a scheduled task.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskAttributes {
+    /// Task name, `None` if the task is not in the blocks.
+    pub name: Option<String>,
+    /// Task priority, `None` if the task is not in the blocks.
+    pub priority: Option<i32>,
+    /// RFC 3339 start, `None` if unbounded or beyond the calendar.
+    pub start: Option<String>,
+    /// RFC 3339 end, `None` if unbounded or beyond the calendar.
+    pub end: Option<String>,
+    pub duration_seconds: f64,
+    /// Whether the entry is a zero-length [milestone](Schedule#milestones).
+    pub milestone: bool,
+    /// Whether the entry is in the [background lane](Schedule#background-tasks).
+    pub background: bool,
+}
+
+/// Projects `schedule` for display in `tz`, axis zero being `epoch`.
+///
+/// Task names and priorities are looked up in `blocks`. Times are rendered
+/// with the offset `tz` has at each instant, to the second unless they
+/// carry a fraction, so records on both sides of a daylight saving change
+/// show their own offset.
+pub fn to_view_model<T, U, D, E, Tz>(
+    schedule: &Schedule<U>,
+    blocks: &[SchedulingBlock<T, U, D, E>],
+    epoch: DateTime<Utc>,
+    tz: &Tz,
+) -> ViewModel
+where
+    T: Task<U>,
+    U: Unit + SameDim<Second>,
+    E: EdgeType,
+    Tz: TimeZone,
+    Tz::Offset: Display,
+{
+    let tasks: HashMap<&str, &T> = blocks.iter().flat_map(|b| b.tasks()).collect();
+    let axis = CalendarAxis::new(epoch);
+    let stamp = |time: DateTime<Utc>| {
+        time.with_timezone(tz)
+            .to_rfc3339_opts(SecondsFormat::AutoSi, false)
+    };
+
+    let mut entries: Vec<_> = schedule.iter_all().collect();
+    // Stable, so primary entries stay ahead of background ones starting at
+    // the same instant.
+    entries.sort_by(|a, b| a.1.start().value().total_cmp(&b.1.start().value()));
+
+    let data: Vec<TaskRecord> = entries
+        .into_iter()
+        .map(|(id, interval, background)| {
+            let task = tasks.get(id.as_str());
+            let attributes = TaskAttributes {
+                name: task.map(|t| t.name().to_string()),
+                priority: task.map(|t| t.priority()),
+                start: axis.datetime(interval.start()).map(stamp),
+                end: axis.datetime(interval.end()).map(stamp),
+                duration_seconds: interval.duration().to::<Second>().value(),
+                milestone: interval.is_empty(),
+                background,
+            };
+            TaskRecord {
+                kind: SCHEDULED_TASK,
+                id,
+                attributes,
+            }
+        })
+        .collect();
+    ViewModel {
+        meta: ViewMeta {
+            epoch: stamp(epoch),
+            count: data.len(),
+        },
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{iv, TestTask};
+
+    #[test]
+    fn records_carry_local_times_and_task_metadata() {
+        let mut block: SchedulingBlock<TestTask> = SchedulingBlock::new();
+        let task = TestTask::new("M31", 600.0).with_priority(5);
+        block.add_task_with_id(task, Some("m31".into())).unwrap();
+        let mut schedule = Schedule::new();
+        schedule.add("m31", iv(0.0, 600.0)).unwrap();
+        schedule.add("done", iv(3_600.5, 3_600.5)).unwrap();
+        schedule.add("open", iv(7_200.0, f64::INFINITY)).unwrap();
+        schedule
+            .add_background("monitor", iv(300.0, 7_200.0))
+            .unwrap();
+
+        // 2026-03-01T00:00Z is 21:00 the day before in Santiago (UTC-3).
+        let epoch = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let view = to_view_model(&schedule, &[block], epoch, &chrono_tz::America::Santiago);
+        assert_eq!(view.meta.epoch, "2026-02-28T21:00:00-03:00");
+        assert_eq!(view.meta.count, 4);
+
+        let m31 = &view.data[0];
+        assert_eq!((m31.kind, m31.id.as_str()), (SCHEDULED_TASK, "m31"));
+        assert_eq!(
+            m31.attributes,
+            TaskAttributes {
+                name: Some("M31".into()),
+                priority: Some(5),
+                start: Some("2026-02-28T21:00:00-03:00".into()),
+                end: Some("2026-02-28T21:10:00-03:00".into()),
+                duration_seconds: 600.0,
+                milestone: false,
+                background: false,
+            }
+        );
+        let monitor = &view.data[1];
+        assert_eq!(monitor.id, "monitor");
+        assert!(monitor.attributes.background);
+        let done = &view.data[2].attributes;
+        assert_eq!(done.start.as_deref(), Some("2026-02-28T22:00:00.500-03:00"));
+        assert!(done.milestone && done.name.is_none());
+        assert_eq!(view.data[3].attributes.end, None);
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_value(&view).unwrap();
+            assert_eq!(json["data"][0]["type"], "scheduled_task");
+            assert_eq!(json["data"][0]["attributes"]["priority"], 5);
+            assert_eq!(json["data"][1]["attributes"]["background"], true);
+            assert!(json["data"][3]["attributes"]["end"].is_null());
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Start-time index behind [`Schedule`](super::Schedule).
+//!
+//! By default entries live in one `BTreeMap` keyed by start (a [`SlotKey`]).
+//! With [`Schedule::with_time_buckets`](super::Schedule::with_time_buckets)
+//! they are split into fixed-width buckets (e.g. one per day), each its own
+//! `BTreeMap`, so a long horizon with hundreds of thousands of entries keeps
+//! small trees and range queries only descend into the buckets they touch.
+//! Both layouts iterate in start order.
+
+use std::collections::{btree_map, BTreeMap};
+use std::ops::{Bound, RangeBounds};
+
+use super::entry_key::{Entry, SlotKey};
+
+type Bucket<U> = BTreeMap<SlotKey, Entry<U>>;
+
+/// Entries keyed by start, flat or in time buckets.
+#[derive(Debug, Clone)]
+pub(crate) enum StartIndex<U: qtty::Unit> {
+    Flat(Bucket<U>),
+    Bucketed {
+        width: f64,
+        buckets: BTreeMap<i64, Bucket<U>>,
+        len: usize,
+    },
+}
+
+/// Entries in a key range, in start order.
+pub(crate) enum Range<'a, U: qtty::Unit> {
+    Flat(btree_map::Range<'a, SlotKey, Entry<U>>),
+    Bucketed(BucketRange<'a, U>),
+}
+
+/// [`Range`] over a bucketed index: the key range within each bucket the
+/// bucket range covers.
+pub(crate) struct BucketRange<'a, U: qtty::Unit> {
+    buckets: btree_map::Range<'a, i64, Bucket<U>>,
+    front: Option<btree_map::Range<'a, SlotKey, Entry<U>>>,
+    back: Option<btree_map::Range<'a, SlotKey, Entry<U>>>,
+    bounds: (Bound<SlotKey>, Bound<SlotKey>),
+}
+
+impl<'a, U: qtty::Unit> Iterator for BucketRange<'a, U> {
+    type Item = (&'a SlotKey, &'a Entry<U>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.front.as_mut().and_then(Iterator::next) {
+                return Some(item);
+            }
+            match self.buckets.next() {
+                Some((_, entries)) => self.front = Some(entries.range(self.bounds)),
+                None => return self.back.as_mut()?.next(),
+            }
+        }
+    }
+}
+
+impl<U: qtty::Unit> DoubleEndedIterator for BucketRange<'_, U> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.back.as_mut().and_then(DoubleEndedIterator::next_back) {
+                return Some(item);
+            }
+            match self.buckets.next_back() {
+                Some((_, entries)) => self.back = Some(entries.range(self.bounds)),
+                None => return self.front.as_mut()?.next_back(),
+            }
+        }
+    }
+}
+
+impl<'a, U: qtty::Unit> Iterator for Range<'a, U> {
+    type Item = (&'a SlotKey, &'a Entry<U>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Range::Flat(range) => range.next(),
+            Range::Bucketed(range) => range.next(),
+        }
+    }
+}
+
+impl<U: qtty::Unit> DoubleEndedIterator for Range<'_, U> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            Range::Flat(range) => range.next_back(),
+            Range::Bucketed(range) => range.next_back(),
+        }
+    }
+}
+
+impl<U: qtty::Unit> StartIndex<U> {
+    /// An empty index bucketed by `width` axis units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is not positive and finite.
+    pub(crate) fn bucketed(width: f64) -> Self {
+        assert!(
+            width > 0.0 && width.is_finite(),
+            "time bucket width must be positive and finite, got {width}"
+        );
+        StartIndex::Bucketed {
+            width,
+            buckets: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// The bucket width, or `None` for a flat index.
+    pub(crate) fn width(&self) -> Option<f64> {
+        match self {
+            StartIndex::Flat(_) => None,
+            StartIndex::Bucketed { width, .. } => Some(*width),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            StartIndex::Flat(map) => map.len(),
+            StartIndex::Bucketed { len, .. } => *len,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn get(&self, key: &SlotKey) -> Option<&Entry<U>> {
+        match self {
+            StartIndex::Flat(map) => map.get(key),
+            StartIndex::Bucketed { width, buckets, .. } => {
+                buckets.get(&bucket(*key, *width))?.get(key)
+            }
+        }
+    }
+
+    pub(crate) fn insert(&mut self, key: SlotKey, entry: Entry<U>) -> Option<Entry<U>> {
+        match self {
+            StartIndex::Flat(map) => map.insert(key, entry),
+            StartIndex::Bucketed {
+                width,
+                buckets,
+                len,
+            } => {
+                let previous = buckets
+                    .entry(bucket(key, *width))
+                    .or_default()
+                    .insert(key, entry);
+                if previous.is_none() {
+                    *len += 1;
+                }
+                previous
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &SlotKey) -> Option<Entry<U>> {
+        match self {
+            StartIndex::Flat(map) => map.remove(key),
+            StartIndex::Bucketed {
+                width,
+                buckets,
+                len,
+            } => {
+                let index = bucket(*key, *width);
+                let entries = buckets.get_mut(&index)?;
+                let removed = entries.remove(key)?;
+                if entries.is_empty() {
+                    buckets.remove(&index);
+                }
+                *len -= 1;
+                Some(removed)
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        match self {
+            StartIndex::Flat(map) => map.clear(),
+            StartIndex::Bucketed { buckets, len, .. } => {
+                buckets.clear();
+                *len = 0;
+            }
+        }
+    }
+
+    /// Entries whose start lies in `range`, in start order.
+    pub(crate) fn range<R: RangeBounds<SlotKey>>(&self, range: R) -> Range<'_, U> {
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        match self {
+            StartIndex::Flat(map) => Range::Flat(map.range(bounds)),
+            StartIndex::Bucketed { width, buckets, .. } => {
+                let outer = (
+                    bucket_bound(bounds.0, *width),
+                    bucket_bound(bounds.1, *width),
+                );
+                Range::Bucketed(BucketRange {
+                    buckets: buckets.range(outer),
+                    front: None,
+                    back: None,
+                    bounds,
+                })
+            }
+        }
+    }
+
+    /// All entries, in start order.
+    pub(crate) fn values(&self) -> impl DoubleEndedIterator<Item = &Entry<U>> + '_ {
+        self.range(..).map(|(_, entry)| entry)
+    }
+}
+
+/// The bucket holding `key`, by its start. Monotonic in the key, so buckets
+/// in order hold keys in order; infinite starts land in the outermost
+/// buckets.
+fn bucket(key: SlotKey, width: f64) -> i64 {
+    (key.start.0 / width).floor() as i64
+}
+
+/// The bucket range covering a key bound.
+fn bucket_bound(bound: Bound<SlotKey>, width: f64) -> Bound<i64> {
+    match bound {
+        Bound::Included(key) | Bound::Excluded(key) => Bound::Included(bucket(key, width)),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
@@ -0,0 +1,447 @@
+//! CSV import and export of schedules.
+//!
+//! The format is one row per task with the columns `task,start,end` and
+//! optional `resource` and `lane` columns, preceded by a header row:
+//!
+//! ```text
+//! task,start,end,resource
+//! flat-field,0,600,cam-1
+//! "M31, deep",900,4500,cam-2
+//! ```
+//!
+//! A `lane` of `background` puts the row in the schedule's
+//! [background lane](Schedule#background-tasks); any other value, or none,
+//! in the primary one. Writers add the column only to schedules with
+//! background entries.
+//!
+//! Times are in the schedule's axis unit. Fields containing commas or quotes
+//! are quoted as in RFC 4180; quoted fields may not span lines. Header names
+//! are matched case-insensitively and may appear in any order; unknown
+//! columns are ignored.
+//!
+//! Rows that cannot be parsed are always errors and carry their line number.
+//! Rows that parse but cannot enter the schedule (duplicate IDs, overlaps)
+//! are handled by the [`OverlapPolicy`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::schedule::io::{from_csv, to_csv, OverlapPolicy};
+//! # use qtty::Second;
+//! # use virolai::schedule::Schedule;
+//! # use virolai::solution_space::Interval;
+//! # let mut schedule: Schedule<Second> = Schedule::new();
+//! # schedule.add("calib", Interval::from_f64(0.0, 60.0))?;
+//!
+//! let mut out = Vec::new();
+//! to_csv(&schedule, &mut out)?;
+//!
+//! let import = from_csv::<Second, _>(out.as_slice(), OverlapPolicy::Lenient)?;
+//! for (line, err) in &import.skipped {
+//!     eprintln!("line {line} skipped: {err}");
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use qtty::{Quantity, Unit};
+use thiserror::Error;
+
+use super::errors::ScheduleError;
+use super::Schedule;
+use crate::solution_space::Interval;
+use crate::Id;
+
+/// How [`from_csv`] handles rows that conflict with earlier rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverlapPolicy {
+    /// Fail on the first duplicate or overlapping row.
+    #[default]
+    Strict,
+    /// Skip conflicting rows and report them in [`CsvImport::skipped`].
+    Lenient,
+}
+
+/// Errors raised by [`from_csv`].
+#[derive(Debug, Error)]
+pub enum CsvError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("line {line}: header is missing the {column:?} column")]
+    MissingColumn { line: usize, column: &'static str },
+
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    #[error("line {line}: {source}")]
+    Conflict {
+        line: usize,
+        #[source]
+        source: ScheduleError,
+    },
+}
+
+/// Result of a CSV import.
+#[derive(Debug, Clone)]
+pub struct CsvImport<U: Unit> {
+    /// Imported entries.
+    pub schedule: Schedule<U>,
+    /// Resource per imported task, for rows with a non-empty `resource`.
+    pub resources: HashMap<Id, Id>,
+    /// Line number and reason of every row skipped under
+    /// [`OverlapPolicy::Lenient`].
+    pub skipped: Vec<(usize, ScheduleError)>,
+}
+
+/// Writes `schedule` as CSV with the columns `task,start,end`, in start order,
+/// followed by the background lane if it has entries.
+pub fn to_csv<U, W>(schedule: &Schedule<U>, writer: W) -> io::Result<()>
+where
+    U: Unit,
+    W: Write,
+{
+    write_rows(schedule, None, writer)
+}
+
+/// Writes `schedule` as CSV with the columns `task,start,end,resource`.
+///
+/// Tasks missing from `resources` get an empty resource field.
+pub fn to_csv_with_resources<U, W>(
+    schedule: &Schedule<U>,
+    resources: &HashMap<Id, Id>,
+    writer: W,
+) -> io::Result<()>
+where
+    U: Unit,
+    W: Write,
+{
+    write_rows(schedule, Some(resources), writer)
+}
+
+fn write_rows<U, W>(
+    schedule: &Schedule<U>,
+    resources: Option<&HashMap<Id, Id>>,
+    mut writer: W,
+) -> io::Result<()>
+where
+    U: Unit,
+    W: Write,
+{
+    let lanes = schedule.background_len() > 0;
+    write!(writer, "task,start,end")?;
+    if resources.is_some() {
+        write!(writer, ",resource")?;
+    }
+    if lanes {
+        write!(writer, ",lane")?;
+    }
+    writeln!(writer)?;
+    for (id, interval, background) in schedule.iter_all() {
+        write!(
+            writer,
+            "{},{},{}",
+            quote(&id),
+            interval.start().value(),
+            interval.end().value()
+        )?;
+        if let Some(resources) = resources {
+            let resource = resources.get(&id).map_or("", String::as_str);
+            write!(writer, ",{}", quote(resource))?;
+        }
+        if lanes {
+            let lane = if background { "background" } else { "primary" };
+            write!(writer, ",{lane}")?;
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()
+}
+
+/// Quotes `field` if it contains a comma, quote or line break.
+pub(super) fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Reads a schedule from CSV written by [`to_csv`] or by a spreadsheet.
+///
+/// Blank lines are ignored. The first non-blank line must be the header.
+///
+/// # Errors
+///
+/// - [`CsvError::MissingColumn`] if the header lacks `task`, `start` or `end`
+/// - [`CsvError::Parse`] for malformed rows, non-numeric or non-finite
+///   times, and rows ending before they start
+/// - [`CsvError::Conflict`] for duplicate or overlapping rows under
+///   [`OverlapPolicy::Strict`]
+pub fn from_csv<U, R>(reader: R, policy: OverlapPolicy) -> Result<CsvImport<U>, CsvError>
+where
+    U: Unit,
+    R: BufRead,
+{
+    let mut import = CsvImport {
+        schedule: Schedule::new(),
+        resources: HashMap::new(),
+        skipped: Vec::new(),
+    };
+    let mut columns: Option<Columns> = None;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = split(&line).map_err(|message| CsvError::Parse {
+            line: line_no,
+            message,
+        })?;
+
+        let Some(cols) = &columns else {
+            columns = Some(Columns::from_header(&fields, line_no)?);
+            continue;
+        };
+
+        let field = |i: usize| fields.get(i).map_or("", |f| f.trim());
+        let parse_error = |message: String| CsvError::Parse {
+            line: line_no,
+            message,
+        };
+
+        let id = field(cols.task);
+        if id.is_empty() {
+            return Err(parse_error("empty task ID".to_string()));
+        }
+        let time = |i: usize, name: &str| -> Result<f64, CsvError> {
+            match field(i).parse::<f64>() {
+                Ok(v) if v.is_finite() => Ok(v),
+                _ => Err(parse_error(format!("invalid {name} time {:?}", field(i)))),
+            }
+        };
+        let start = time(cols.start, "start")?;
+        let end = time(cols.end, "end")?;
+        if end < start {
+            return Err(parse_error(format!("end {end} is before start {start}")));
+        }
+
+        let interval = Interval::new(Quantity::new(start), Quantity::new(end));
+        let background = cols
+            .lane
+            .map(field)
+            .is_some_and(|lane| lane.eq_ignore_ascii_case("background"));
+        let added = if background {
+            import.schedule.add_background(id, interval)
+        } else {
+            import.schedule.add(id, interval)
+        };
+        match added {
+            Ok(()) => {
+                if let Some(resource) = cols.resource.map(field).filter(|r| !r.is_empty()) {
+                    import
+                        .resources
+                        .insert(id.to_string(), resource.to_string());
+                }
+            }
+            Err(err) => match policy {
+                OverlapPolicy::Strict => {
+                    return Err(CsvError::Conflict {
+                        line: line_no,
+                        source: err,
+                    })
+                }
+                OverlapPolicy::Lenient => import.skipped.push((line_no, err)),
+            },
+        }
+    }
+
+    Ok(import)
+}
+
+/// Column positions found in the header.
+struct Columns {
+    task: usize,
+    start: usize,
+    end: usize,
+    resource: Option<usize>,
+    lane: Option<usize>,
+}
+
+impl Columns {
+    fn from_header(fields: &[String], line: usize) -> Result<Self, CsvError> {
+        let find = |name: &str| {
+            fields
+                .iter()
+                .position(|f| f.trim().eq_ignore_ascii_case(name))
+        };
+        let require =
+            |column: &'static str| find(column).ok_or(CsvError::MissingColumn { line, column });
+        Ok(Self {
+            task: require("task")?,
+            start: require("start")?,
+            end: require("end")?,
+            resource: find("resource"),
+            lane: find("lane"),
+        })
+    }
+}
+
+/// Splits one CSV line into fields, unquoting quoted fields.
+pub(crate) fn split(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut in_quotes = false;
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qtty::Second;
+
+    fn iv(start: f64, end: f64) -> Interval<Second> {
+        Interval::from_f64(start, end)
+    }
+
+    #[test]
+    fn round_trip_with_resources() {
+        let mut schedule = Schedule::<Second>::new();
+        schedule.add("flat", iv(0.0, 600.0)).unwrap();
+        schedule.add("M31, \"deep\"", iv(900.5, 4500.0)).unwrap();
+        let resources = HashMap::from([("flat".to_string(), "cam-1".to_string())]);
+
+        let mut out = Vec::new();
+        to_csv_with_resources(&schedule, &resources, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(
+            text,
+            "task,start,end,resource\nflat,0,600,cam-1\n\"M31, \"\"deep\"\"\",900.5,4500,\n"
+        );
+
+        let import = from_csv::<Second, _>(text.as_bytes(), OverlapPolicy::Strict).unwrap();
+        assert_eq!(import.schedule.len(), 2);
+        assert_eq!(
+            import.schedule.get_interval("M31, \"deep\""),
+            Some(iv(900.5, 4500.0))
+        );
+        assert_eq!(import.resources, resources);
+        assert!(import.skipped.is_empty());
+    }
+
+    #[test]
+    fn background_lane_round_trips() {
+        let mut schedule = Schedule::<Second>::new();
+        schedule.add("science", iv(0.0, 100.0)).unwrap();
+        schedule.add_background("monitor", iv(0.0, 300.0)).unwrap();
+
+        let mut out = Vec::new();
+        to_csv(&schedule, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(
+            text,
+            "task,start,end,lane\nscience,0,100,primary\nmonitor,0,300,background\n"
+        );
+
+        let import = from_csv::<Second, _>(text.as_bytes(), OverlapPolicy::Strict).unwrap();
+        assert_eq!(import.schedule.len(), 1);
+        assert!(import.schedule.is_background("monitor"));
+        assert_eq!(
+            import.schedule.get_interval("monitor"),
+            Some(iv(0.0, 300.0))
+        );
+    }
+
+    #[test]
+    fn header_order_and_case_are_free() {
+        let csv = "End,Resource,TASK,Start,note\n\n20,,a,10,x\n";
+        let import = from_csv::<Second, _>(csv.as_bytes(), OverlapPolicy::Strict).unwrap();
+        assert_eq!(import.schedule.get_interval("a"), Some(iv(10.0, 20.0)));
+        assert!(import.resources.is_empty());
+
+        let err =
+            from_csv::<Second, _>("task,start\n".as_bytes(), OverlapPolicy::Strict).unwrap_err();
+        assert!(matches!(
+            err,
+            CsvError::MissingColumn {
+                line: 1,
+                column: "end"
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_errors_carry_line_numbers() {
+        let cases = [
+            (
+                "task,start,end\na,0,10\nb,zero,10\n",
+                3,
+                "invalid start time",
+            ),
+            ("task,start,end\n\nb,20,10\n", 3, "before start"),
+            ("task,start,end\n,0,10\n", 2, "empty task ID"),
+            ("task,start,end\n\"b,0,10\n", 2, "unterminated"),
+            ("task,start,end\nb,0,inf\n", 2, "invalid end time"),
+        ];
+        for (csv, expected_line, needle) in cases {
+            match from_csv::<Second, _>(csv.as_bytes(), OverlapPolicy::Lenient) {
+                Err(CsvError::Parse { line, message }) => {
+                    assert_eq!(line, expected_line, "{csv}");
+                    assert!(message.contains(needle), "{message}");
+                }
+                other => panic!("expected parse error for {csv:?}, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn overlap_policy_strict_vs_lenient() {
+        let csv = "task,start,end\na,0,10\nb,5,15\na,20,30\nc,10,20\n";
+
+        let err = from_csv::<Second, _>(csv.as_bytes(), OverlapPolicy::Strict).unwrap_err();
+        assert!(matches!(
+            err,
+            CsvError::Conflict {
+                line: 3,
+                source: ScheduleError::OverlapsExisting { .. }
+            }
+        ));
+        assert!(err.to_string().starts_with("line 3: "));
+
+        let import = from_csv::<Second, _>(csv.as_bytes(), OverlapPolicy::Lenient).unwrap();
+        assert_eq!(import.schedule.len(), 2);
+        assert_eq!(
+            import.skipped.iter().map(|(l, _)| *l).collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert!(matches!(
+            import.skipped[1].1,
+            ScheduleError::DuplicateTaskId(_)
+        ));
+    }
+}
@@ -0,0 +1,387 @@
+//! Health checks for finished schedules.
+//!
+//! [`lint`] looks for signs of a degraded plan that are not errors: the
+//! schedule is valid, but something about it deserves a second look before
+//! it is published.
+//!
+//! - [`Lint::TightHighPriority`]: two high-priority tasks back to back, so a
+//!   small delay of the first costs the second.
+//! - [`Lint::NearWindowEdge`]: a task within a few percent of its duration
+//!   of a window edge, so a small shift makes it infeasible.
+//! - [`Lint::DroppedTaskFits`]: an idle gap a dropped task would fit in.
+//! - [`Lint::ZeroDuration`]: an entry that takes no time.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::schedule::lint;
+//! # use qtty::Second;
+//! # use virolai::schedule::Schedule;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let blocks = vec![instance.block];
+//! # let space = instance.solution_space;
+//! # let schedule: Schedule<Second> = Schedule::new();
+//!
+//! for warning in lint(&schedule, &blocks, &space) {
+//!     eprintln!("warning: {warning}");
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use petgraph::EdgeType;
+use qtty::{Quantity, Unit};
+
+use super::Schedule;
+use crate::algorithms::greedy::free_windows;
+use crate::repair::dependency_bounds;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Interval, SolutionSpace};
+use crate::Id;
+
+/// Which end of a window a task is close to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowEdge {
+    Start,
+    End,
+}
+
+/// A warning raised by [`lint`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Lint<U: Unit> {
+    /// Consecutive high-priority tasks separated by at most the tight gap.
+    TightHighPriority {
+        first: Id,
+        second: Id,
+        gap: Quantity<U>,
+    },
+    /// A task whose distance to an edge of its window is below the margin.
+    NearWindowEdge {
+        task_id: Id,
+        edge: WindowEdge,
+        margin: Quantity<U>,
+    },
+    /// An unscheduled task that fits in `gap`, a free part of its windows
+    /// between its scheduled predecessors and successors.
+    DroppedTaskFits { task_id: Id, gap: Interval<U> },
+    /// An entry that starts and ends at the same time.
+    ZeroDuration { task_id: Id },
+}
+
+impl<U: Unit> fmt::Display for Lint<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lint::TightHighPriority { first, second, gap } => write!(
+                f,
+                "high-priority tasks {first} and {second} are only {} apart",
+                gap.value()
+            ),
+            Lint::NearWindowEdge {
+                task_id,
+                edge,
+                margin,
+            } => {
+                let edge = match edge {
+                    WindowEdge::Start => "start",
+                    WindowEdge::End => "end",
+                };
+                write!(f, "{task_id} is {} from its window {edge}", margin.value())
+            }
+            Lint::DroppedTaskFits { task_id, gap } => {
+                write!(f, "dropped task {task_id} would fit in idle gap {gap}")
+            }
+            Lint::ZeroDuration { task_id } => write!(f, "{task_id} has zero duration"),
+        }
+    }
+}
+
+/// Thresholds used by [`lint_with`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LintConfig<U: Unit> {
+    high_priority: Option<i32>,
+    tight_gap: Quantity<U>,
+    edge_margin: f64,
+}
+
+impl<U: Unit> Default for LintConfig<U> {
+    /// High priority from the block priorities, back-to-back tasks only, and
+    /// an edge margin of 5% of the task's duration.
+    fn default() -> Self {
+        Self {
+            high_priority: None,
+            tight_gap: Quantity::new(0.0),
+            edge_margin: 0.05,
+        }
+    }
+}
+
+impl<U: Unit> LintConfig<U> {
+    /// Tasks at or above `priority` count as high priority.
+    ///
+    /// By default the threshold is the priority of the top quarter of tasks
+    /// in the blocks; if that is also the lowest priority, no task counts.
+    pub fn with_high_priority(mut self, priority: i32) -> Self {
+        self.high_priority = Some(priority);
+        self
+    }
+
+    /// High-priority tasks at most `gap` apart are reported.
+    pub fn with_tight_gap(mut self, gap: Quantity<U>) -> Self {
+        self.tight_gap = gap;
+        self
+    }
+
+    /// Tasks closer to a window edge than `fraction` of their duration are
+    /// reported.
+    pub fn with_edge_margin(mut self, fraction: f64) -> Self {
+        self.edge_margin = fraction;
+        self
+    }
+}
+
+/// Lints `schedule` with the default [`LintConfig`].
+pub fn lint<T, U, D, E>(
+    schedule: &Schedule<U>,
+    blocks: &[SchedulingBlock<T, U, D, E>],
+    space: &SolutionSpace<U>,
+) -> Vec<Lint<U>>
+where
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    lint_with(schedule, blocks, space, &LintConfig::default())
+}
+
+/// Lints `schedule` against the tasks in `blocks` and their windows in
+/// `space`.
+///
+/// Warnings are grouped by kind, in the order of the [`Lint`] variants, and
+/// in start order within a kind; dropped tasks are listed by ID. Entries
+/// missing from `blocks` are only checked for zero duration and window
+/// edges.
+pub fn lint_with<T, U, D, E>(
+    schedule: &Schedule<U>,
+    blocks: &[SchedulingBlock<T, U, D, E>],
+    space: &SolutionSpace<U>,
+    config: &LintConfig<U>,
+) -> Vec<Lint<U>>
+where
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    let tasks: Vec<(&str, &T)> = blocks.iter().flat_map(|b| b.tasks()).collect();
+    let priorities: HashMap<&str, i32> = tasks.iter().map(|(id, t)| (*id, t.priority())).collect();
+    let entries: Vec<(Id, Interval<U>)> = schedule.iter().collect();
+    let mut lints = Vec::new();
+
+    if let Some(threshold) = config
+        .high_priority
+        .or_else(|| upper_quartile(priorities.values().copied()))
+    {
+        let high = |id: &str| priorities.get(id).is_some_and(|&p| p >= threshold);
+        for pair in entries.windows(2) {
+            let ((first, a), (second, b)) = (&pair[0], &pair[1]);
+            let gap = b.start() - a.end();
+            if gap.value() <= config.tight_gap.value() && high(first) && high(second) {
+                lints.push(Lint::TightHighPriority {
+                    first: first.clone(),
+                    second: second.clone(),
+                    gap,
+                });
+            }
+        }
+    }
+
+    for (task_id, interval) in &entries {
+        let Some(window) = space.get_intervals(task_id).and_then(|ws| {
+            ws.iter()
+                .find(|w| w.start() <= interval.start() && interval.end() <= w.end())
+        }) else {
+            continue;
+        };
+        let limit = config.edge_margin * interval.duration().value();
+        let edges = [
+            (WindowEdge::Start, interval.start() - window.start()),
+            (WindowEdge::End, window.end() - interval.end()),
+        ];
+        for (edge, margin) in edges {
+            if margin.value() < limit {
+                lints.push(Lint::NearWindowEdge {
+                    task_id: task_id.clone(),
+                    edge,
+                    margin,
+                });
+            }
+        }
+    }
+
+    let mut dropped: Vec<(&str, &T)> = tasks
+        .iter()
+        .copied()
+        .filter(|(id, _)| !schedule.contains_task(id))
+        .collect();
+    dropped.sort_unstable_by_key(|(id, _)| *id);
+    for (task_id, task) in dropped {
+        let Some(windows) = space.get_intervals(task_id) else {
+            continue;
+        };
+        let windows: Vec<(f64, f64)> = windows
+            .iter()
+            .map(|w| (w.start().value(), w.end().value()))
+            .collect();
+        let (lo, hi) = dependency_bounds(task_id, blocks, schedule);
+        let size = task.size_on_axis().value();
+        if let Some(gap) = free_windows(&windows, lo, hi, schedule)
+            .into_iter()
+            .find(|gap| gap.duration().value() >= size)
+        {
+            lints.push(Lint::DroppedTaskFits {
+                task_id: task_id.to_string(),
+                gap,
+            });
+        }
+    }
+
+    for (task_id, interval) in &entries {
+        if interval.duration().value() == 0.0 {
+            lints.push(Lint::ZeroDuration {
+                task_id: task_id.clone(),
+            });
+        }
+    }
+
+    lints
+}
+
+/// The priority reached by the top quarter of `priorities`, or `None` if it
+/// does not single out any task.
+fn upper_quartile(priorities: impl Iterator<Item = i32>) -> Option<i32> {
+    let mut priorities: Vec<i32> = priorities.collect();
+    priorities.sort_unstable_by(|a, b| b.cmp(a));
+    let threshold = *priorities.get(priorities.len().div_ceil(4).checked_sub(1)?)?;
+    (threshold > *priorities.last()?).then_some(threshold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{block_and_space, iv, q, TestTask};
+    use qtty::Second;
+
+    fn setup() -> (
+        Vec<SchedulingBlock<TestTask, Second>>,
+        SolutionSpace<Second>,
+    ) {
+        let night = iv(0.0, 1000.0);
+        let (block, space) = block_and_space(&[
+            ("a", 10.0, 9, night),
+            ("b", 10.0, 8, night),
+            ("c", 10.0, 1, night),
+            ("d", 0.0, 1, night),
+            ("e", 10.0, 1, night),
+            ("f", 10.0, 1, night),
+            ("g", 10.0, 1, night),
+            ("h", 50.0, 1, night),
+        ]);
+        (vec![block], space)
+    }
+
+    #[test]
+    fn degraded_plan_raises_each_lint() {
+        let (blocks, mut space) = setup();
+        space.set_intervals("c", vec![iv(40.0, 50.25)]);
+        space.set_intervals("h", vec![iv(100.0, 200.0)]);
+        let mut schedule = Schedule::new();
+        schedule.add("a", iv(0.0, 10.0)).unwrap();
+        schedule.add("b", iv(10.0, 20.0)).unwrap();
+        schedule.add("c", iv(40.0, 50.0)).unwrap();
+        schedule.add("d", iv(60.0, 60.0)).unwrap();
+
+        let lints = lint(&schedule, &blocks, &space);
+        let a_start = Lint::NearWindowEdge {
+            task_id: "a".into(),
+            edge: WindowEdge::Start,
+            margin: q(0.0),
+        };
+        assert_eq!(
+            lints,
+            vec![
+                Lint::TightHighPriority {
+                    first: "a".into(),
+                    second: "b".into(),
+                    gap: q(0.0)
+                },
+                a_start.clone(),
+                Lint::NearWindowEdge {
+                    task_id: "c".into(),
+                    edge: WindowEdge::Start,
+                    margin: q(0.0)
+                },
+                Lint::NearWindowEdge {
+                    task_id: "c".into(),
+                    edge: WindowEdge::End,
+                    margin: q(0.25)
+                },
+                Lint::DroppedTaskFits {
+                    task_id: "e".into(),
+                    gap: iv(20.0, 40.0)
+                },
+                Lint::DroppedTaskFits {
+                    task_id: "f".into(),
+                    gap: iv(20.0, 40.0)
+                },
+                Lint::DroppedTaskFits {
+                    task_id: "g".into(),
+                    gap: iv(20.0, 40.0)
+                },
+                Lint::DroppedTaskFits {
+                    task_id: "h".into(),
+                    gap: iv(100.0, 200.0)
+                },
+                Lint::ZeroDuration {
+                    task_id: "d".into()
+                },
+            ]
+        );
+        assert_eq!(a_start.to_string(), "a is 0 from its window start");
+        assert_eq!(
+            lints[0].to_string(),
+            "high-priority tasks a and b are only 0 apart"
+        );
+    }
+
+    #[test]
+    fn thresholds_are_configurable() {
+        let (blocks, space) = setup();
+        let mut schedule = Schedule::new();
+        schedule.add("c", iv(100.0, 110.0)).unwrap();
+        schedule.add("e", iv(115.0, 125.0)).unwrap();
+        let config = LintConfig::default()
+            .with_high_priority(1)
+            .with_tight_gap(q(5.0))
+            .with_edge_margin(0.0);
+        let lints = lint_with(&schedule, &blocks, &space, &config);
+        assert_eq!(
+            lints[0],
+            Lint::TightHighPriority {
+                first: "c".into(),
+                second: "e".into(),
+                gap: q(5.0)
+            }
+        );
+        assert!(!lints
+            .iter()
+            .any(|l| matches!(l, Lint::NearWindowEdge { .. })));
+
+        // Equal priorities leave no task high priority by default.
+        assert_eq!(upper_quartile([3, 3, 3].into_iter()), None);
+        assert_eq!(upper_quartile([1, 9, 5, 2].into_iter()), Some(9));
+        assert_eq!(upper_quartile(std::iter::empty()), None);
+    }
+}
@@ -0,0 +1,266 @@
+//! Utilization heatmaps for dashboards.
+//!
+//! [`heatmap`] cuts a [`Timeline`] into fixed-width time buckets and reports,
+//! for every bucket, how busy each resource was and how much time tasks of
+//! each tag took. Entries of the schedules' background lanes count like
+//! primary ones. Both matrices are plain `Vec<Vec<f64>>`, one row per
+//! resource or tag, and can be written as CSV for tools that expect a grid.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::schedule::metrics::heatmap;
+//! # use qtty::{Quantity, Second};
+//! # use virolai::schedule::Timeline;
+//! # let timeline: Timeline<Second> = Timeline::with_resources(["north", "south"]);
+//!
+//! let map = heatmap(&timeline, Quantity::<Second>::new(3600.0));
+//! map.resources_to_csv(std::fs::File::create("resources.csv")?)?;
+//! map.tags_to_csv(std::fs::File::create("tags.csv")?)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+use qtty::{Quantity, Unit};
+
+use super::io::quote;
+use super::timeline::Timeline;
+use crate::solution_space::{Interval, IntervalSet};
+use crate::Id;
+
+/// Per-bucket utilization of a timeline, see [`heatmap`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heatmap<U: Unit> {
+    /// Start of the first bucket, a multiple of `bucket_size`.
+    pub origin: Quantity<U>,
+    /// Width of every bucket.
+    pub bucket_size: Quantity<U>,
+    /// Resource IDs, sorted; the rows of `resources`.
+    pub resource_ids: Vec<Id>,
+    /// `resources[r][b]`: fraction of bucket `b` during which resource `r`
+    /// is busy, in `[0, 1]`.
+    pub resources: Vec<Vec<f64>>,
+    /// Task labels as `key=value`, sorted; the rows of `tags`.
+    pub tag_labels: Vec<String>,
+    /// `tags[t][b]`: time taken in bucket `b` by tasks labelled `t`, as a
+    /// fraction of the bucket. Exceeds 1 when such tasks run in parallel; a
+    /// task booked on several resources counts once.
+    pub tags: Vec<Vec<f64>>,
+}
+
+impl<U: Unit> Heatmap<U> {
+    /// Number of buckets, the length of every row.
+    pub fn bucket_count(&self) -> usize {
+        self.resources
+            .first()
+            .or(self.tags.first())
+            .map_or(0, Vec::len)
+    }
+
+    /// Start of bucket `index`.
+    pub fn bucket_start(&self, index: usize) -> Quantity<U> {
+        self.origin + self.bucket_size * index as f64
+    }
+
+    /// Writes the resource matrix as CSV: a `resource` column followed by
+    /// one column per bucket, headed by the bucket start.
+    pub fn resources_to_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.write_matrix("resource", &self.resource_ids, &self.resources, writer)
+    }
+
+    /// Writes the tag matrix as CSV: a `tag` column followed by one column
+    /// per bucket, headed by the bucket start.
+    pub fn tags_to_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.write_matrix("tag", &self.tag_labels, &self.tags, writer)
+    }
+
+    fn write_matrix<W: Write>(
+        &self,
+        label: &str,
+        rows: &[String],
+        matrix: &[Vec<f64>],
+        mut writer: W,
+    ) -> io::Result<()> {
+        write!(writer, "{label}")?;
+        for b in 0..self.bucket_count() {
+            write!(writer, ",{}", self.bucket_start(b).value())?;
+        }
+        writeln!(writer)?;
+        for (name, row) in rows.iter().zip(matrix) {
+            write!(writer, "{}", quote(name))?;
+            for value in row {
+                write!(writer, ",{value}")?;
+            }
+            writeln!(writer)?;
+        }
+        writer.flush()
+    }
+}
+
+/// Builds the utilization heatmap of `timeline` with buckets `bucket_size`
+/// wide, aligned on multiples of it.
+///
+/// The buckets cover every booking, from the bucket holding the earliest
+/// start to the one holding the latest end; unbounded bookings are clipped
+/// to the span of the bounded ones.
+///
+/// # Panics
+///
+/// Panics if `bucket_size` is not positive and finite.
+pub fn heatmap<U: Unit>(timeline: &Timeline<U>, bucket_size: Quantity<U>) -> Heatmap<U> {
+    let size = bucket_size.value();
+    assert!(
+        size > 0.0 && size.is_finite(),
+        "bucket size must be positive and finite, got {size}"
+    );
+
+    let mut resource_ids: Vec<Id> = timeline.resources().map(str::to_string).collect();
+    resource_ids.sort_unstable();
+    let mut tasks: BTreeMap<Id, Interval<U>> = BTreeMap::new();
+    for resource in &resource_ids {
+        if let Some(schedule) = timeline.schedule(resource) {
+            tasks.extend(schedule.iter_all().map(|(id, interval, _)| (id, interval)));
+        }
+    }
+
+    let finite = tasks
+        .values()
+        .flat_map(|i| [i.start().value(), i.end().value()])
+        .filter(|v| v.is_finite());
+    let (lo, hi) = finite.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    });
+    let (origin, count) = if lo <= hi {
+        let first = (lo / size).floor();
+        let last = (hi / size).ceil().max(first + 1.0);
+        (first * size, (last - first) as usize)
+    } else {
+        (0.0, 0)
+    };
+    let span = (origin, origin + count as f64 * size);
+
+    let resources = resource_ids
+        .iter()
+        .map(|resource| {
+            let mut row = vec![0.0; count];
+            if let Some(schedule) = timeline.schedule(resource) {
+                // A background task running alongside another one does not
+                // make the resource any busier.
+                let busy: IntervalSet<U> = schedule
+                    .iter_all()
+                    .map(|(_, interval, _)| interval)
+                    .collect();
+                for &interval in busy.iter() {
+                    accumulate(&mut row, interval, span, size);
+                }
+            }
+            row
+        })
+        .collect();
+
+    let mut tag_rows: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for (id, interval) in &tasks {
+        let labels: BTreeSet<String> = timeline
+            .tags_of(id)
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        for label in labels {
+            let row = tag_rows.entry(label).or_insert_with(|| vec![0.0; count]);
+            accumulate(row, *interval, span, size);
+        }
+    }
+    let (tag_labels, tags) = tag_rows.into_iter().unzip();
+
+    Heatmap {
+        origin: Quantity::new(origin),
+        bucket_size,
+        resource_ids,
+        resources,
+        tag_labels,
+        tags,
+    }
+}
+
+/// Adds the share of every bucket `interval` covers to `row`.
+fn accumulate<U: Unit>(row: &mut [f64], interval: Interval<U>, span: (f64, f64), size: f64) {
+    let start = interval.start().value().max(span.0);
+    let end = interval.end().value().min(span.1);
+    if start >= end {
+        return;
+    }
+    let first = ((start - span.0) / size).floor() as usize;
+    for (b, cell) in row.iter_mut().enumerate().skip(first) {
+        let bucket_start = span.0 + b as f64 * size;
+        if bucket_start >= end {
+            break;
+        }
+        let covered = end.min(bucket_start + size) - start.max(bucket_start);
+        *cell += covered.max(0.0) / size;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::Schedule;
+    use crate::test_utils::{iv, q};
+    use std::collections::HashMap;
+
+    #[test]
+    fn buckets_resources_and_tags() {
+        let mut timeline = Timeline::with_resources(["cam", "spec"]);
+        timeline.tag("flat", "kind", "calibration");
+        timeline.tag("m31", "kind", "science");
+        timeline.tag("pair", "kind", "science");
+        timeline.book("flat", iv(15.0, 30.0), &["cam"]).unwrap();
+        timeline.book("m31", iv(20.0, 50.0), &["spec"]).unwrap();
+        timeline
+            .book("pair", iv(50.0, 55.0), &["cam", "spec"])
+            .unwrap();
+
+        let map = heatmap(&timeline, q(20.0));
+        // Buckets [0, 20), [20, 40), [40, 60).
+        assert_eq!(map.origin, q(0.0));
+        assert_eq!(map.bucket_count(), 3);
+        assert_eq!(map.resource_ids, ["cam", "spec"]);
+        assert_eq!(map.resources[0], [0.25, 0.5, 0.25]);
+        assert_eq!(map.resources[1], [0.0, 1.0, 0.75]);
+
+        // The pair is booked on both resources but counts once.
+        assert_eq!(map.tag_labels, ["kind=calibration", "kind=science"]);
+        assert_eq!(map.tags[0], [0.25, 0.5, 0.0]);
+        assert_eq!(map.tags[1], [0.0, 1.0, 0.75]);
+
+        let mut csv = Vec::new();
+        map.resources_to_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "resource,0,20,40\ncam,0.25,0.5,0.25\nspec,0,1,0.75\n"
+        );
+        let mut csv = Vec::new();
+        map.tags_to_csv(&mut csv).unwrap();
+        assert!(String::from_utf8(csv)
+            .unwrap()
+            .starts_with("tag,0,20,40\nkind=calibration,0.25,0.5,0\n"));
+
+        let empty = heatmap(&Timeline::<qtty::Second>::with_resources(["cam"]), q(5.0));
+        assert_eq!(empty.bucket_count(), 0);
+        assert_eq!(empty.resources, [Vec::<f64>::new()]);
+    }
+
+    #[test]
+    fn background_entries_count_once_per_resource() {
+        let mut schedule = Schedule::new();
+        schedule.add("m31", iv(20.0, 30.0)).unwrap();
+        schedule.add_background("monitor", iv(0.0, 40.0)).unwrap();
+        let mut timeline = Timeline::from(HashMap::from([("cam".to_string(), schedule)]));
+        timeline.tag("monitor", "kind", "monitoring");
+        let map = heatmap(&timeline, q(20.0));
+        assert_eq!(map.resources[0], [1.0, 1.0]);
+        assert_eq!(map.tag_labels, ["kind=monitoring"]);
+        assert_eq!(map.tags[0], [1.0, 1.0]);
+    }
+}
@@ -1,11 +1,30 @@
-use crate::solution_space::Interval;
+use crate::scheduling_block::Task;
+use crate::solution_space::{Feasibility, Interval, IntervalSet};
 use crate::Id;
 use qtty::Quantity;
 use std::collections::{BTreeMap, HashMap};
+pub mod diff;
 pub mod entry_key;
 pub mod errors;
+#[cfg(feature = "chrono")]
+pub mod export;
+mod index;
+pub mod io;
+pub mod lint;
+pub mod metrics;
+pub mod snapshot;
+pub mod suggest;
+pub mod timeline;
+pub mod txn;
+pub use diff::{MovedTask, ScheduleDiff};
 use entry_key::*;
 use errors::*;
+use index::StartIndex;
+pub use lint::{lint, lint_with, Lint, LintConfig, WindowEdge};
+pub use snapshot::ScheduleSnapshot;
+pub use suggest::{AddRejected, InsertSuggestion};
+pub use timeline::{ConcurrencyLimit, ConcurrencyViolation, ExclusionConflict, Timeline};
+pub use txn::ScheduleTxn;
 
 #[cfg(test)]
 mod tests;
@@ -15,16 +34,46 @@ mod tests;
 /// A `Schedule` maintains a collection of non-overlapping intervals (tasks) indexed by task ID,
 /// providing efficient operations for insertion, removal, and conflict detection.
 ///
+/// # Milestones
+///
+/// An entry with a zero-length interval `[t, t)` is a milestone: an instant
+/// such as "calibration done" or "end of night". Milestones occupy no time,
+/// so they never conflict, not even with the [minimum gap](Self::with_min_gap):
+/// any number may share an instant or sit inside a task. They still count
+/// in [`len`](Self::len), iteration (ahead of a task starting at the same
+/// instant) and the schedule's [span](Self::span), and are exported as
+/// rows whose start equals their end.
+///
+/// # Background tasks
+///
+/// Tasks allowed to overlap anything, such as passive monitoring or a
+/// calibration running alongside science, go in a background lane with
+/// [`add_background`](Self::add_background). Background entries never
+/// conflict with other entries and are left out of [`len`](Self::len),
+/// [`iter`](Self::iter), conflict queries and the occupied time, which all
+/// describe the primary lane. Lookups by ID ([`contains_task`](Self::contains_task),
+/// [`get_interval`](Self::get_interval), [`remove`](Self::remove)) cover
+/// both lanes, so an ID is unique across them, and exports include the
+/// background lane, marking its entries.
+///
 /// # Internal Structure
-/// - `by_start`: `BTreeMap` from start time to task entry
-/// - `start_by_id`: `HashMap` from task ID to start time
+/// - `by_start`: `BTreeMap` from start time to task entry, optionally split
+///   into [time buckets](Schedule::with_time_buckets)
+/// - `start_by_id`: `HashMap` from task ID to its key in `by_start`
+/// - `min_gap`: buffer required between consecutive entries (zero by default)
+/// - `by_priority`: entries added with a [priority](Self::add_with_priority),
+///   ordered by priority then start
+/// - `background`, `background_by_id`: the background lane, indexed like
+///   the primary one but without overlap checks
 ///
 /// # Complexity
-/// - `add`: O(log n) with O(1) neighbor overlap checks
+/// - `add`: O(log n) with O(1) neighbor overlap checks, plus any milestones
+///   skipped on the way to the neighbors
 /// - `remove`: O(log n)
 /// - `get_interval`: O(1) hash lookup + O(log n) tree lookup
 /// - `conflicts`: O(log n + k) where k is the number of conflicts
 /// - `task_at`: O(log n)
+/// - `lowest_priority`: O(log n + k) for the first k entries
 ///
 /// # Examples
 ///
@@ -60,15 +109,27 @@ mod tests;
 /// ```
 #[derive(Debug, Clone)]
 pub struct Schedule<U: qtty::Unit> {
-    by_start: BTreeMap<F64Key, Entry<U>>,
-    start_by_id: HashMap<Id, F64Key>,
+    by_start: StartIndex<U>,
+    start_by_id: HashMap<Id, SlotKey>,
+    min_gap: Quantity<U>,
+    next_seq: u64,
+    by_priority: BTreeMap<(i32, SlotKey), Id>,
+    priority_by_id: HashMap<Id, i32>,
+    background: BTreeMap<SlotKey, Entry<U>>,
+    background_by_id: HashMap<Id, SlotKey>,
 }
 
 impl<U: qtty::Unit> Default for Schedule<U> {
     fn default() -> Self {
         Self {
-            by_start: BTreeMap::new(),
+            by_start: StartIndex::Flat(BTreeMap::new()),
             start_by_id: HashMap::new(),
+            min_gap: Quantity::new(0.0),
+            next_seq: 0,
+            by_priority: BTreeMap::new(),
+            priority_by_id: HashMap::new(),
+            background: BTreeMap::new(),
+            background_by_id: HashMap::new(),
         }
     }
 }
@@ -76,9 +137,111 @@ impl<U: qtty::Unit> Default for Schedule<U> {
 impl<U: qtty::Unit> Schedule<U> {
     pub fn new() -> Self {
         Self {
-            by_start: BTreeMap::new(),
+            by_start: StartIndex::Flat(BTreeMap::new()),
             start_by_id: HashMap::new(),
+            min_gap: Quantity::new(0.0),
+            next_seq: 0,
+            by_priority: BTreeMap::new(),
+            priority_by_id: HashMap::new(),
+            background: BTreeMap::new(),
+            background_by_id: HashMap::new(),
+        }
+    }
+
+    /// Requires at least `gap` between consecutive entries, e.g. for a
+    /// detector readout or an instrument reset.
+    ///
+    /// Insertions and conflict queries check intervals widened by `gap` on
+    /// both sides, so an entry closer than `gap` to another is reported as
+    /// [`ScheduleError::OverlapsExisting`]. The gap is a setting of this
+    /// schedule and is not serialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `gap` is negative or NaN.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qtty::{Quantity, Second};
+    /// use virolai::schedule::Schedule;
+    /// use virolai::solution_space::Interval;
+    ///
+    /// let mut schedule = Schedule::<Second>::new().with_min_gap(Quantity::new(5.0));
+    /// schedule.add("a", Interval::from_f64(0.0, 10.0)).unwrap();
+    /// assert!(schedule.add("b", Interval::from_f64(12.0, 20.0)).is_err());
+    /// assert!(schedule.add("b", Interval::from_f64(15.0, 20.0)).is_ok());
+    /// ```
+    pub fn with_min_gap(mut self, gap: Quantity<U>) -> Self {
+        assert!(
+            gap.value() >= 0.0,
+            "minimum gap must be non-negative, got {}",
+            gap.value()
+        );
+        self.min_gap = gap;
+        self
+    }
+
+    /// Returns the buffer required between consecutive entries.
+    pub fn min_gap(&self) -> Quantity<U> {
+        self.min_gap
+    }
+
+    /// `interval` widened by the minimum gap on both sides. Milestones are
+    /// left as they are.
+    fn padded(&self, interval: Interval<U>) -> Interval<U> {
+        if self.min_gap.value() == 0.0 || interval.is_empty() {
+            return interval;
         }
+        Interval::new(
+            interval.start() - self.min_gap,
+            interval.end() + self.min_gap,
+        )
+    }
+
+    /// Splits the start index into buckets `width` wide (e.g. one day), each
+    /// its own `BTreeMap`.
+    ///
+    /// Meant for long horizons with hundreds of thousands of entries: trees
+    /// stay small and range queries only descend into the buckets they
+    /// touch. Behaviour is unchanged. A flat `BTreeMap` range is already
+    /// O(log n + k), and on the `schedule_time_buckets` example the two
+    /// layouts are within noise of each other, so measure on your own data
+    /// before switching. Existing entries are re-indexed; the layout is not
+    /// serialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is not positive and finite.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qtty::{Quantity, Second};
+    /// use virolai::schedule::Schedule;
+    /// use virolai::solution_space::Interval;
+    ///
+    /// let mut year = Schedule::<Second>::new().with_time_buckets(Quantity::new(86_400.0));
+    /// year.add("a", Interval::from_f64(3_600.0, 7_200.0)).unwrap();
+    /// year.add("b", Interval::from_f64(90_000.0, 93_600.0)).unwrap();
+    /// let second_day: Vec<_> = year
+    ///     .starting_in(Interval::from_f64(86_400.0, 172_800.0))
+    ///     .map(|(id, _)| id)
+    ///     .collect();
+    /// assert_eq!(second_day, ["b"]);
+    /// ```
+    pub fn with_time_buckets(mut self, width: Quantity<U>) -> Self {
+        let mut index = StartIndex::bucketed(width.value());
+        for (key, entry) in self.by_start.range(..) {
+            index.insert(*key, entry.clone());
+        }
+        self.by_start = index;
+        self
+    }
+
+    /// Returns the time bucket width, or `None` if the index is flat.
+    pub fn time_bucket_width(&self) -> Option<Quantity<U>> {
+        self.by_start.width().map(Quantity::new)
     }
 
     pub fn len(&self) -> usize {
@@ -106,13 +269,16 @@ impl<U: qtty::Unit> Schedule<U> {
         }
     }
 
-    /// Returns true if task id exists.
+    /// Returns true if task id exists, in either lane.
     pub fn contains_task(&self, id: &str) -> bool {
-        self.start_by_id.contains_key(id)
+        self.start_by_id.contains_key(id) || self.background_by_id.contains_key(id)
     }
 
-    /// Gets the interval for a task id (if present).
+    /// Gets the interval for a task id (if present), in either lane.
     pub fn get_interval(&self, id: &str) -> Option<Interval<U>> {
+        if let Some(key) = self.background_by_id.get(id) {
+            return self.background.get(key).map(|e| e.interval);
+        }
         let start = self.start_by_id.get(id)?;
         self.by_start.get(start).map(|e| e.interval)
     }
@@ -122,10 +288,16 @@ impl<U: qtty::Unit> Schedule<U> {
     /// Requires:
     /// - `id` not already present
     /// - interval times not NaN
-    /// - interval does not overlap any existing interval
+    /// - interval does not overlap any existing interval, nor come closer to
+    ///   one than the [minimum gap](Self::with_min_gap); never the case for
+    ///   a [milestone](Self#milestones)
     ///
     /// Efficiency: only predecessor + successor checks are needed because the schedule
-    /// is maintained as non-overlapping and sorted by start time.
+    /// is maintained as non-overlapping and sorted by start time. Milestones
+    /// are skipped when looking for those neighbors.
+    ///
+    /// [`add_or_suggest`](Self::add_or_suggest) also reports where an
+    /// overlapping interval would fit.
     pub fn add(&mut self, id: impl Into<Id>, interval: Interval<U>) -> Result<(), ScheduleError> {
         let id: Id = id.into();
         if self.contains_task(&id) {
@@ -135,67 +307,269 @@ impl<U: qtty::Unit> Schedule<U> {
         let start_k = Self::key(interval.start())?;
         let end_k = Self::key(interval.end())?;
 
-        // Optional sanity: if your Interval::new already enforces start <= end,
-        // you don't need this. Still, it helps if callers bypass it somehow.
-        if start_k > end_k {
-            // Interval::new would panic; here we just fail fast with NaNTime? not right.
-            // Keep behavior aligned with Interval::new: assume it's valid.
-        }
+        if !interval.is_empty() {
+            let padded = self.padded(interval);
 
-        // Check predecessor (latest interval with start <= new.start).
-        if let Some((_k, prev)) = self.by_start.range(..=start_k).next_back() {
-            if prev.interval.overlaps(&interval) {
-                return Err(ScheduleError::OverlapsExisting {
-                    new_id: id,
-                    existing_id: prev.id.clone(),
-                });
+            // Check predecessor (latest task with start <= new.start).
+            if let Some(prev) = self.task_before(start_k) {
+                if prev.interval.overlaps(&padded) {
+                    return Err(ScheduleError::OverlapsExisting {
+                        new_id: id,
+                        existing_id: prev.id.clone(),
+                    });
+                }
             }
-        }
 
-        // Check successor (earliest interval with start >= new.start).
-        if let Some((_k, next)) = self.by_start.range(start_k..).next() {
-            if next.interval.overlaps(&interval) {
-                return Err(ScheduleError::OverlapsExisting {
-                    new_id: id,
-                    existing_id: next.id.clone(),
-                });
+            // Check successor (earliest task with start >= new.start).
+            if let Some((_k, next)) = self
+                .by_start
+                .range(SlotKey::first_at(start_k)..)
+                .find(|(_k, e)| !e.interval.is_empty())
+            {
+                if next.interval.overlaps(&padded) {
+                    return Err(ScheduleError::OverlapsExisting {
+                        new_id: id,
+                        existing_id: next.id.clone(),
+                    });
+                }
             }
         }
 
+        let key = SlotKey {
+            start: start_k,
+            end: end_k,
+            seq: self.next_seq,
+        };
+        self.next_seq += 1;
         self.by_start.insert(
-            start_k,
+            key,
             Entry {
                 id: id.clone(),
                 interval,
             },
         );
-        self.start_by_id.insert(id, start_k);
+        self.start_by_id.insert(id, key);
         Ok(())
     }
 
-    /// Removes a task by id. Returns its interval if it existed.
+    /// Inserts a task in the [background lane](Self#background-tasks), where
+    /// it may overlap any other entry.
+    ///
+    /// Requires `id` not already present in either lane and interval times
+    /// not NaN; the minimum gap does not apply.
+    pub fn add_background(
+        &mut self,
+        id: impl Into<Id>,
+        interval: Interval<U>,
+    ) -> Result<(), ScheduleError> {
+        let id: Id = id.into();
+        if self.contains_task(&id) {
+            return Err(ScheduleError::DuplicateTaskId(id));
+        }
+        let key = SlotKey {
+            start: Self::key(interval.start())?,
+            end: Self::key(interval.end())?,
+            seq: self.next_seq,
+        };
+        self.next_seq += 1;
+        self.background.insert(
+            key,
+            Entry {
+                id: id.clone(),
+                interval,
+            },
+        );
+        self.background_by_id.insert(id, key);
+        Ok(())
+    }
+
+    /// Returns true if `id` is in the background lane.
+    pub fn is_background(&self, id: &str) -> bool {
+        self.background_by_id.contains_key(id)
+    }
+
+    /// Number of entries in the background lane.
+    pub fn background_len(&self) -> usize {
+        self.background.len()
+    }
+
+    /// Returns an iterator over the background entries in start time order.
+    ///
+    /// Each item is `(id, interval)`.
+    pub fn background(&self) -> impl Iterator<Item = (Id, Interval<U>)> + '_ {
+        self.background.values().map(|e| (e.id.clone(), e.interval))
+    }
+
+    /// Returns an iterator over both lanes: the primary entries in start
+    /// time order, then the background ones.
+    ///
+    /// Each item is `(id, interval, background)`; pass it to
+    /// [`add_to_lane`](Self::add_to_lane) to copy an entry into another
+    /// schedule without losing its lane.
+    pub fn iter_all(&self) -> impl Iterator<Item = (Id, Interval<U>, bool)> + '_ {
+        self.iter()
+            .map(|(id, interval)| (id, interval, false))
+            .chain(self.background().map(|(id, interval)| (id, interval, true)))
+    }
+
+    /// Inserts a task with [`add_background`](Self::add_background) if
+    /// `background` is true, with [`add`](Self::add) otherwise.
+    pub fn add_to_lane(
+        &mut self,
+        id: impl Into<Id>,
+        interval: Interval<U>,
+        background: bool,
+    ) -> Result<(), ScheduleError> {
+        if background {
+            self.add_background(id, interval)
+        } else {
+            self.add(id, interval)
+        }
+    }
+
+    /// Inserts a task like [`add`](Self::add), recording its priority in the
+    /// priority index.
+    ///
+    /// Only entries added this way take part in
+    /// [`lowest_priority`](Self::lowest_priority) and
+    /// [`lowest_priority_conflicts`](Self::lowest_priority_conflicts), e.g.
+    /// the preemptible ones when looking for tasks to evict. Priorities are
+    /// not serialized.
+    pub fn add_with_priority(
+        &mut self,
+        id: impl Into<Id>,
+        interval: Interval<U>,
+        priority: i32,
+    ) -> Result<(), ScheduleError> {
+        let id: Id = id.into();
+        self.add(id.clone(), interval)?;
+        let key = self.start_by_id[&id];
+        self.by_priority.insert((priority, key), id.clone());
+        self.priority_by_id.insert(id, priority);
+        Ok(())
+    }
+
+    /// Returns the priority a task was added with, if any.
+    pub fn priority_of(&self, id: &str) -> Option<i32> {
+        self.priority_by_id.get(id).copied()
+    }
+
+    /// Iterates over the entries added with a priority, lowest priority
+    /// first, then in start order.
+    ///
+    /// The scan is lazy: `lowest_priority().take(k)` costs O(log n + k).
+    pub fn lowest_priority(&self) -> impl Iterator<Item = (&str, Interval<U>, i32)> + '_ {
+        self.by_priority.iter().map(|((priority, key), id)| {
+            let interval = Interval::from_f64(key.start.value(), key.end.value());
+            (id.as_str(), interval, *priority)
+        })
+    }
+
+    /// Returns the `n` lowest-priority entries conflicting with `query`,
+    /// lowest priority first, then in start order.
+    ///
+    /// Conflicts are found as in [`conflicts_ref`](Self::conflicts_ref);
+    /// entries without a priority are skipped. Since entries never overlap,
+    /// the conflicts are contiguous in start order, so the query costs
+    /// O(log n + m log m) for m conflicts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qtty::Second;
+    /// use virolai::schedule::Schedule;
+    /// use virolai::solution_space::Interval;
+    ///
+    /// let mut schedule = Schedule::<Second>::new();
+    /// schedule.add_with_priority("survey", Interval::from_f64(0.0, 10.0), 1).unwrap();
+    /// schedule.add_with_priority("flat", Interval::from_f64(10.0, 20.0), 0).unwrap();
+    /// schedule.add_with_priority("target", Interval::from_f64(20.0, 30.0), 9).unwrap();
+    ///
+    /// // Make room for a target of opportunity at [5, 25).
+    /// let evict = schedule
+    ///     .lowest_priority_conflicts(Interval::from_f64(5.0, 25.0), 2)
+    ///     .unwrap();
+    /// let ids: Vec<_> = evict.iter().map(|(id, _, _)| id.as_str()).collect();
+    /// assert_eq!(ids, ["flat", "survey"]);
+    /// ```
+    pub fn lowest_priority_conflicts(
+        &self,
+        query: Interval<U>,
+        n: usize,
+    ) -> Result<Vec<(Id, Interval<U>, i32)>, ScheduleError> {
+        let mut found: Vec<_> = self
+            .conflicts_ref(query)?
+            .filter_map(|(id, interval)| {
+                let priority = self.priority_of(id)?;
+                Some(((priority, self.start_by_id[id]), id, interval))
+            })
+            .collect();
+        found.sort_unstable_by_key(|(rank, _, _)| *rank);
+        Ok(found
+            .into_iter()
+            .take(n)
+            .map(|((priority, _), id, interval)| (id.to_string(), interval, priority))
+            .collect())
+    }
+
+    /// The latest entry that is not a milestone and starts at or before
+    /// `start`.
+    fn task_before(&self, start: F64Key) -> Option<&Entry<U>> {
+        self.by_start
+            .range(..=SlotKey::last_at(start))
+            .rev()
+            .map(|(_k, e)| e)
+            .find(|e| !e.interval.is_empty())
+    }
+
+    /// Removes a task by id, from either lane. Returns its interval if it
+    /// existed.
     pub fn remove(&mut self, id: &str) -> Option<Interval<U>> {
+        if let Some(key) = self.background_by_id.remove(id) {
+            return self.background.remove(&key).map(|e| e.interval);
+        }
         let start_k = self.start_by_id.remove(id)?;
+        if let Some(priority) = self.priority_by_id.remove(id) {
+            self.by_priority.remove(&(priority, start_k));
+        }
         let entry = self.by_start.remove(&start_k)?;
         Some(entry.interval)
     }
 
-    /// Returns true if `query` overlaps any scheduled task.
+    /// Returns true if `query` overlaps any scheduled task, or comes closer to
+    /// one than the minimum gap.
     pub fn has_conflict(&self, query: Interval<U>) -> Result<bool, ScheduleError> {
-        Ok(self.conflicts(query)?.next().is_some())
+        Ok(self.conflicts_ref(query)?.next().is_some())
     }
 
     /// Iterates over all conflicts (overlapping scheduled tasks) with `query`.
     ///
     /// Complexity: O(log n + k) where k is the number of conflicts.
     ///
-    /// Note: uses `Interval::overlaps` exactly as you defined it (inclusive endpoints).
-    /// If you want “back-to-back tasks are OK”, change Interval semantics to half-open
-    /// and update `overlaps` accordingly.
+    /// Uses [`Interval::overlaps`], so back-to-back tasks do not conflict and
+    /// [milestones](Self#milestones) conflict with nothing.
     pub fn conflicts<'a>(
         &'a self,
         query: Interval<U>,
     ) -> Result<impl Iterator<Item = (Id, Interval<U>)> + 'a, ScheduleError> {
+        Ok(self
+            .conflicts_ref(query)?
+            .map(|(id, interval)| (id.to_string(), interval)))
+    }
+
+    /// Iterates over conflicts with `query`, borrowing task IDs.
+    ///
+    /// Like every conflict query, this checks `query` widened by the
+    /// [minimum gap](Self::with_min_gap).
+    ///
+    /// The scan is lazy: it stops as soon as the iterator is dropped, so
+    /// `conflicts_ref(q)?.next()` finds the first conflict (in start order)
+    /// without visiting or allocating for the others.
+    pub fn conflicts_ref<'a>(
+        &'a self,
+        query: Interval<U>,
+    ) -> Result<impl Iterator<Item = (&'a str, Interval<U>)> + 'a, ScheduleError> {
+        let query = self.padded(query);
         let q_start = query.start().value();
         let q_end = query.end().value();
 
@@ -203,21 +577,11 @@ impl<U: qtty::Unit> Schedule<U> {
         let _q_end_k = Self::key_f64(q_end)?;
 
         // Determine where to start scanning:
-        // - the predecessor of q_start (it might start before q_start but still overlap)
+        // - the task preceding q_start (it might start before q_start but still overlap)
         // - otherwise the first start >= q_start
-        let start_from = if let Some((k, prev)) = self.by_start.range(..=q_start_k).next_back() {
-            if prev.interval.overlaps(&query) {
-                Some(*k)
-            } else {
-                None
-            }
-        } else {
-            None
-        };
-
-        let range_start = match start_from {
-            Some(k) => k,
-            None => q_start_k,
+        let range_start = match self.task_before(q_start_k) {
+            Some(prev) if prev.interval.overlaps(&query) => self.start_by_id[&prev.id],
+            _ => SlotKey::first_at(q_start_k),
         };
 
         // Scan all intervals whose start <= q_end and filter by overlap.
@@ -225,13 +589,26 @@ impl<U: qtty::Unit> Schedule<U> {
         let iter = self
             .by_start
             .range(range_start..)
-            .take_while(move |(k, _e)| k.0 <= q_end)
+            .take_while(move |(k, _e)| k.start.0 <= q_end)
             .filter(move |(_k, e)| e.interval.overlaps(&query))
-            .map(|(_k, e)| (e.id.clone(), e.interval));
+            .map(|(_k, e)| (e.id.as_str(), e.interval));
 
         Ok(iter)
     }
 
+    /// Counts conflicts with `query`, scanning no further than `limit` of
+    /// them.
+    ///
+    /// Returns at most `limit`; `conflicts_count(q, 1)` is a cheap "is there
+    /// any conflict" check.
+    pub fn conflicts_count(
+        &self,
+        query: Interval<U>,
+        limit: usize,
+    ) -> Result<usize, ScheduleError> {
+        Ok(self.conflicts_ref(query)?.take(limit).count())
+    }
+
     /// Convenience: returns conflicts collected into a Vec.
     pub fn conflicts_vec(
         &self,
@@ -250,7 +627,7 @@ impl<U: qtty::Unit> Schedule<U> {
     /// Complexity: O(log n).
     pub fn task_at(&self, pos: Quantity<U>) -> Result<Option<Id>, ScheduleError> {
         let p = Self::key(pos)?;
-        if let Some((_k, e)) = self.by_start.range(..=p).next_back() {
+        if let Some(e) = self.task_before(p) {
             if e.interval.contains(pos) {
                 return Ok(Some(e.id.clone()));
             }
@@ -265,6 +642,24 @@ impl<U: qtty::Unit> Schedule<U> {
         self.by_start.values().map(|e| (e.id.clone(), e.interval))
     }
 
+    /// Iterates over the entries starting in `range`, in start order.
+    ///
+    /// Unlike [`conflicts_ref`](Self::conflicts_ref), an entry that started
+    /// before `range` and runs into it is not included, so consecutive ranges
+    /// (e.g. one per day) visit every entry exactly once.
+    pub fn starting_in(
+        &self,
+        range: Interval<U>,
+    ) -> impl DoubleEndedIterator<Item = (&str, Interval<U>)> + '_ {
+        let bounds = (
+            std::ops::Bound::Included(SlotKey::first_at(F64Key(range.start().value()))),
+            std::ops::Bound::Excluded(SlotKey::first_at(F64Key(range.end().value()))),
+        );
+        self.by_start
+            .range(bounds)
+            .map(|(_, e)| (e.id.as_str(), e.interval))
+    }
+
     /// Returns an iterator over all IDs.
     pub fn ids(&self) -> impl Iterator<Item = Id> + '_ {
         self.start_by_id.keys().cloned()
@@ -279,6 +674,10 @@ impl<U: qtty::Unit> Schedule<U> {
     pub fn clear(&mut self) {
         self.by_start.clear();
         self.start_by_id.clear();
+        self.by_priority.clear();
+        self.priority_by_id.clear();
+        self.background.clear();
+        self.background_by_id.clear();
     }
 
     /// Returns the total scheduled duration (sum of all interval durations).
@@ -297,8 +696,22 @@ impl<U: qtty::Unit> Schedule<U> {
     }
 
     /// Returns the latest end time in the schedule, if any.
+    ///
+    /// A milestone inside a task does not end it, so the last task is looked
+    /// up past any milestones starting after it.
     pub fn latest_end(&self) -> Option<Quantity<U>> {
-        self.by_start.values().next_back().map(|e| e.interval.end())
+        let last = self.by_start.values().next_back()?.interval.end();
+        let task_end = self
+            .by_start
+            .values()
+            .rev()
+            .find(|e| !e.interval.is_empty())
+            .map_or(last, |e| e.interval.end());
+        Some(if task_end.value() > last.value() {
+            task_end
+        } else {
+            last
+        })
     }
 
     /// Returns the time span from earliest start to latest end, if any tasks exist.
@@ -309,6 +722,126 @@ impl<U: qtty::Unit> Schedule<U> {
             None
         }
     }
+
+    /// Returns the time occupied by the schedule, with abutting entries merged.
+    ///
+    /// Together with [`to_free_interval_set`](Self::to_free_interval_set) this
+    /// lets the output of one scheduling layer serve as resource availability
+    /// for the next.
+    pub fn to_busy_intervals(&self) -> IntervalSet<U> {
+        self.intervals().collect()
+    }
+
+    /// Returns the parts of `horizon` no entry occupies.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qtty::Second;
+    /// use virolai::schedule::Schedule;
+    /// use virolai::solution_space::Interval;
+    ///
+    /// let mut facility = Schedule::<Second>::new();
+    /// facility.add("calibration", Interval::from_f64(0.0, 10.0)).unwrap();
+    /// facility.add("maintenance", Interval::from_f64(10.0, 20.0)).unwrap();
+    /// facility.add("survey", Interval::from_f64(50.0, 60.0)).unwrap();
+    ///
+    /// assert_eq!(
+    ///     facility.to_busy_intervals().as_slice(),
+    ///     [Interval::from_f64(0.0, 20.0), Interval::from_f64(50.0, 60.0)]
+    /// );
+    /// let free = facility.to_free_interval_set(Interval::from_f64(0.0, 100.0));
+    /// assert_eq!(
+    ///     free.as_slice(),
+    ///     [Interval::from_f64(20.0, 50.0), Interval::from_f64(60.0, 100.0)]
+    /// );
+    /// ```
+    pub fn to_free_interval_set(&self, horizon: Interval<U>) -> IntervalSet<U> {
+        self.to_busy_intervals().complement(horizon)
+    }
+
+    /// Returns the IDs of entries `feasibility` does not allow, in start order.
+    ///
+    /// Works with continuous windows ([`SolutionSpace`](crate::solution_space::SolutionSpace))
+    /// and discrete start lists ([`CandidateStarts`](crate::solution_space::CandidateStarts)) alike.
+    pub fn infeasible_entries<F>(&self, feasibility: &F) -> Vec<Id>
+    where
+        F: Feasibility<U> + ?Sized,
+    {
+        self.by_start
+            .values()
+            .filter(|e| !feasibility.allows(&e.id, e.interval.start(), e.interval.duration()))
+            .map(|e| e.id.clone())
+            .collect()
+    }
+
+    /// Returns the IDs of entries whose length is not the duration their task
+    /// takes when started there ([`Task::duration_at`]), in start order.
+    ///
+    /// Entries of tasks missing from `tasks` are skipped. Lengths are
+    /// compared with a relative tolerance of `1e-9`, which absorbs the
+    /// rounding of `start + duration`.
+    pub fn mismatched_durations<'a, T>(
+        &self,
+        tasks: impl IntoIterator<Item = (&'a str, &'a T)>,
+    ) -> Vec<Id>
+    where
+        T: Task<U> + 'a,
+    {
+        let tasks: HashMap<&str, &T> = tasks.into_iter().collect();
+        self.by_start
+            .values()
+            .filter(|e| {
+                tasks.get(e.id.as_str()).is_some_and(|task| {
+                    let expected = task.duration_at(e.interval.start()).value();
+                    let actual = e.interval.duration().value();
+                    (actual - expected).abs() > 1e-9 * expected.abs().max(1.0)
+                })
+            })
+            .map(|e| e.id.clone())
+            .collect()
+    }
+
+    /// Converts every entry to another unit of the same dimension.
+    ///
+    /// Unit conversion is a monotonic scaling, so the converted entries
+    /// never overlap. The minimum gap is converted too, but set only after
+    /// the entries are in, so rounding cannot reject one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qtty::{Minute, Second};
+    /// use virolai::schedule::Schedule;
+    /// use virolai::solution_space::Interval;
+    ///
+    /// let mut minutes = Schedule::<Minute>::new();
+    /// minutes.add("a", Interval::from_f64(1.0, 2.5)).unwrap();
+    /// let seconds: Schedule<Second> = minutes.to();
+    /// assert_eq!(seconds.get_interval("a"), Some(Interval::from_f64(60.0, 150.0)));
+    /// ```
+    pub fn to<T: qtty::Unit<Dim = U::Dim>>(&self) -> Schedule<T> {
+        let mut converted = Schedule::new();
+        if let Some(width) = self.time_bucket_width() {
+            converted = converted.with_time_buckets(width.to());
+        }
+        for e in self.by_start.values() {
+            let added = match self.priority_of(&e.id) {
+                Some(priority) => {
+                    converted.add_with_priority(e.id.clone(), e.interval.to(), priority)
+                }
+                None => converted.add(e.id.clone(), e.interval.to()),
+            };
+            added.expect("conversion keeps entries apart");
+        }
+        for e in self.background.values() {
+            converted
+                .add_background(e.id.clone(), e.interval.to())
+                .expect("IDs are unique across lanes");
+        }
+        converted.min_gap = self.min_gap.to();
+        converted
+    }
 }
 
 // =============================================================================
@@ -328,6 +861,7 @@ mod serde_impl {
     struct ScheduleEntryOut<'a, U: qtty::Unit> {
         task: &'a str,
         interval: &'a Interval<U>,
+        background: bool,
     }
 
     impl<U: qtty::Unit> Serialize for ScheduleEntryOut<'_, U> {
@@ -335,9 +869,15 @@ mod serde_impl {
         where
             S: Serializer,
         {
-            let mut s = serializer.serialize_struct("ScheduleEntry", 2)?;
+            let fields = if self.background { 3 } else { 2 };
+            let mut s = serializer.serialize_struct("ScheduleEntry", fields)?;
             s.serialize_field("task", self.task)?;
             s.serialize_field("interval", self.interval)?;
+            // Only background entries carry the flag, so primary entries
+            // read as before.
+            if self.background {
+                s.serialize_field("background", &true)?;
+            }
             s.end()
         }
     }
@@ -347,11 +887,19 @@ mod serde_impl {
         where
             S: Serializer,
         {
-            let mut seq = serializer.serialize_seq(Some(self.len()))?;
+            let mut seq = serializer.serialize_seq(Some(self.len() + self.background_len()))?;
             for (id, interval) in self.iter() {
                 seq.serialize_element(&ScheduleEntryOut {
                     task: &id,
                     interval: &interval,
+                    background: false,
+                })?;
+            }
+            for (id, interval) in self.background() {
+                seq.serialize_element(&ScheduleEntryOut {
+                    task: &id,
+                    interval: &interval,
+                    background: true,
                 })?;
             }
             seq.end()
@@ -378,9 +926,12 @@ mod serde_impl {
                 {
                     let mut schedule = Schedule::new();
                     while let Some(entry) = seq.next_element::<ScheduleEntryIn<U>>()? {
-                        schedule
-                            .add(entry.task, entry.interval)
-                            .map_err(de::Error::custom)?;
+                        let added = if entry.background {
+                            schedule.add_background(entry.task, entry.interval)
+                        } else {
+                            schedule.add(entry.task, entry.interval)
+                        };
+                        added.map_err(de::Error::custom)?;
                     }
                     Ok(schedule)
                 }
@@ -394,6 +945,7 @@ mod serde_impl {
     struct ScheduleEntryIn<U: qtty::Unit> {
         task: String,
         interval: Interval<U>,
+        background: bool,
     }
 
     impl<'de, U: qtty::Unit> Deserialize<'de> for ScheduleEntryIn<U> {
@@ -418,6 +970,7 @@ mod serde_impl {
                 {
                     let mut task: Option<String> = None;
                     let mut interval: Option<Interval<U>> = None;
+                    let mut background = false;
 
                     while let Some(key) = map.next_key::<String>()? {
                         match key.as_str() {
@@ -433,6 +986,7 @@ mod serde_impl {
                                 }
                                 interval = Some(map.next_value()?);
                             }
+                            "background" => background = map.next_value()?,
                             _ => {
                                 // Ignore unknown fields
                                 let _ = map.next_value::<serde::de::IgnoredAny>()?;
@@ -443,7 +997,11 @@ mod serde_impl {
                     let task = task.ok_or_else(|| de::Error::missing_field("task"))?;
                     let interval = interval.ok_or_else(|| de::Error::missing_field("interval"))?;
 
-                    Ok(ScheduleEntryIn { task, interval })
+                    Ok(ScheduleEntryIn {
+                        task,
+                        interval,
+                        background,
+                    })
                 }
             }
 
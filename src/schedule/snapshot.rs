@@ -0,0 +1,212 @@
+//! Hypothetical schedules on top of a real one.
+//!
+//! Speculative searches (beam search, what-if tools) try many placements
+//! that are mostly thrown away. A [`ScheduleSnapshot`] borrows the live
+//! schedule and records placements and removals on top of it, so each
+//! branch costs its own changes rather than a copy of the whole schedule.
+//! Forking a branch clones only those changes.
+//!
+//! Dynamic constraints see a snapshot through
+//! [`SchedulingContext::from_snapshot`](crate::constraints::SchedulingContext::from_snapshot).
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use qtty::{Quantity, Second};
+//! # use virolai::constraints::{
+//! #     DynamicConstraintIndex, IntervalConstraint, MaxWaitConstraint, SchedulingContext,
+//! # };
+//! # use virolai::schedule::{Schedule, ScheduleSnapshot};
+//! # use virolai::scheduling_block::{SchedulingBlock, Task};
+//! # use virolai::solution_space::{Interval, SolutionSpace};
+//! # #[derive(Debug)]
+//! # struct Obs;
+//! # impl Task<Second> for Obs {
+//! #     type SizeUnit = Second;
+//! #     type ConstraintLeaf = IntervalConstraint<Second>;
+//! #     fn name(&self) -> &str { "obs" }
+//! #     fn size(&self) -> Quantity<Second> { Quantity::new(60.0) }
+//! # }
+//! # let mut block: SchedulingBlock<Obs, Second, MaxWaitConstraint<Second>> = SchedulingBlock::new();
+//! # block.add_task_with_id(Obs, Some("calib".into()))?;
+//! # block.add_task_with_id(Obs, Some("science".into()))?;
+//! # block.add_dependencies([("calib".into(), "science".into(), MaxWaitConstraint::no_wait())])?;
+//! # let blocks = [block];
+//! # let index = DynamicConstraintIndex::from_blocks(&blocks);
+//! # let schedule: Schedule<Second> = Schedule::new();
+//! # let space = SolutionSpace::new();
+//! # let horizon = Interval::from_f64(0.0, 86_400.0);
+//! let mut branch = ScheduleSnapshot::new(&schedule);
+//! branch.place("calib", Interval::from_f64(0.0, 60.0))?;
+//! let ctx = SchedulingContext::from_snapshot(&branch, &space);
+//! let windows = index.evaluate("science", horizon, &ctx);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use qtty::Unit;
+
+use super::errors::ScheduleError;
+use super::Schedule;
+use crate::solution_space::Interval;
+use crate::Id;
+
+/// A borrowed schedule with placements and removals applied on top.
+///
+/// The base schedule is never modified. Placements are not checked for
+/// overlaps, neither with each other nor with the base: a snapshot answers
+/// "what if", and [`to_schedule`](Self::to_schedule) checks.
+#[derive(Debug, Clone)]
+pub struct ScheduleSnapshot<'a, U: Unit> {
+    base: &'a Schedule<U>,
+    placed: HashMap<Id, Interval<U>>,
+    removed: HashSet<Id>,
+}
+
+impl<'a, U: Unit> ScheduleSnapshot<'a, U> {
+    /// A snapshot of `base` with no changes yet.
+    pub fn new(base: &'a Schedule<U>) -> Self {
+        Self {
+            base,
+            placed: HashMap::new(),
+            removed: HashSet::new(),
+        }
+    }
+
+    /// The schedule the snapshot is built on.
+    pub fn base(&self) -> &'a Schedule<U> {
+        self.base
+    }
+
+    /// Places `id` at `interval` in the snapshot.
+    ///
+    /// # Errors
+    ///
+    /// - [`ScheduleError::DuplicateTaskId`] if `id` is already in the
+    ///   snapshot; [`unplace`](Self::unplace) it first to move it
+    /// - [`ScheduleError::NaNTime`] if a time of `interval` is NaN
+    pub fn place(&mut self, id: impl Into<Id>, interval: Interval<U>) -> Result<(), ScheduleError> {
+        let id = id.into();
+        if self.contains_task(&id) {
+            return Err(ScheduleError::DuplicateTaskId(id));
+        }
+        if interval.start().value().is_nan() || interval.end().value().is_nan() {
+            return Err(ScheduleError::NaNTime);
+        }
+        self.placed.insert(id, interval);
+        Ok(())
+    }
+
+    /// Removes `id` from the snapshot. Returns its interval if it was in it.
+    pub fn unplace(&mut self, id: &str) -> Option<Interval<U>> {
+        if let Some(interval) = self.placed.remove(id) {
+            return Some(interval);
+        }
+        let interval = self.base.get_interval(id)?;
+        self.removed.insert(id.to_string()).then_some(interval)
+    }
+
+    /// Returns true if `id` is in the snapshot.
+    pub fn contains_task(&self, id: &str) -> bool {
+        self.get_interval(id).is_some()
+    }
+
+    /// The interval of `id` in the snapshot.
+    pub fn get_interval(&self, id: &str) -> Option<Interval<U>> {
+        if let Some(interval) = self.placed.get(id) {
+            return Some(*interval);
+        }
+        if self.removed.contains(id) {
+            return None;
+        }
+        self.base.get_interval(id)
+    }
+
+    /// Number of primary entries in the snapshot. Like
+    /// [`Schedule::len`], the base's background lane is not counted.
+    pub fn len(&self) -> usize {
+        let removed = self
+            .removed
+            .iter()
+            .filter(|id| !self.base.is_background(id))
+            .count();
+        self.base.len() - removed + self.placed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if the snapshot has no changes.
+    pub fn is_unchanged(&self) -> bool {
+        self.placed.is_empty() && self.removed.is_empty()
+    }
+
+    /// The snapshot as a schedule of its own, copying the base.
+    ///
+    /// Placements enter the primary lane, in start order.
+    ///
+    /// # Errors
+    ///
+    /// The first error of [`Schedule::add`], e.g. a placement overlapping
+    /// another entry.
+    pub fn to_schedule(&self) -> Result<Schedule<U>, ScheduleError> {
+        let mut schedule = self.base.clone();
+        for id in &self.removed {
+            schedule.remove(id);
+        }
+        let mut placed: Vec<_> = self.placed.iter().collect();
+        placed.sort_by(|a, b| a.1.start().value().total_cmp(&b.1.start().value()));
+        for (id, interval) in placed {
+            schedule.add(id.clone(), *interval)?;
+        }
+        Ok(schedule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::iv;
+    use qtty::Second;
+
+    #[test]
+    fn changes_stay_in_the_snapshot() {
+        let mut schedule = Schedule::<Second>::new();
+        schedule.add("a", iv(0.0, 10.0)).unwrap();
+        schedule.add("b", iv(20.0, 30.0)).unwrap();
+
+        let mut what_if = ScheduleSnapshot::new(&schedule);
+        assert!(what_if.is_unchanged());
+        // Move b earlier and add c.
+        assert_eq!(what_if.unplace("b"), Some(iv(20.0, 30.0)));
+        assert_eq!(what_if.unplace("b"), None);
+        what_if.place("b", iv(10.0, 20.0)).unwrap();
+        what_if.place("c", iv(20.0, 25.0)).unwrap();
+        assert!(matches!(
+            what_if.place("a", iv(40.0, 50.0)),
+            Err(ScheduleError::DuplicateTaskId(_))
+        ));
+
+        let mut branch = what_if.clone();
+        branch.unplace("a");
+        assert!(!branch.contains_task("a") && what_if.contains_task("a"));
+        assert_eq!(branch.len(), 2);
+
+        assert_eq!(what_if.len(), 3);
+        assert_eq!(what_if.get_interval("b"), Some(iv(10.0, 20.0)));
+        assert_eq!(schedule.get_interval("b"), Some(iv(20.0, 30.0)));
+        assert!(!schedule.contains_task("c"));
+
+        let applied = what_if.to_schedule().unwrap();
+        let entries: Vec<_> = applied.iter().map(|(id, _)| id).collect();
+        assert_eq!(entries, ["a", "b", "c"]);
+
+        what_if.place("clash", iv(5.0, 15.0)).unwrap();
+        assert!(matches!(
+            what_if.to_schedule(),
+            Err(ScheduleError::OverlapsExisting { .. })
+        ));
+    }
+}
@@ -0,0 +1,191 @@
+//! Where a rejected entry would fit instead.
+//!
+//! When [`Schedule::add`] refuses an interval because it overlaps an entry,
+//! an interactive tool would rather offer a fix than a bare error.
+//! [`Schedule::add_or_suggest`] adds the interval like `add` and, on an
+//! overlap, reports the nearest earlier and later starts at which an
+//! interval of the same length would fit, honouring the minimum gap.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use qtty::Second;
+//! # use virolai::schedule::Schedule;
+//! # use virolai::solution_space::Interval;
+//! # use virolai::schedule::errors::ScheduleError;
+//! # use virolai::schedule::AddRejected;
+//! # fn offer(_: String) {}
+//! # fn insert(schedule: &mut Schedule<Second>, slot: Interval<Second>) -> Result<(), ScheduleError> {
+//! match schedule.add_or_suggest("m31", slot) {
+//!     Ok(()) => {}
+//!     Err(AddRejected { suggestion: Some(fix), .. }) => {
+//!         if let Some(start) = fix.later {
+//!             offer(format!("Move to {start}?"));
+//!         }
+//!     }
+//!     Err(rejected) => return Err(rejected.error),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use qtty::{Quantity, Unit};
+
+use super::errors::ScheduleError;
+use super::Schedule;
+use crate::solution_space::Interval;
+use crate::Id;
+
+/// Starts at which an interval would fit, see [`Schedule::suggest_starts`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InsertSuggestion<U: Unit> {
+    /// Latest fitting start at or before the requested one, `None` if an
+    /// entry open towards the past leaves no room.
+    pub earlier: Option<Quantity<U>>,
+    /// Earliest fitting start at or after the requested one, `None` if an
+    /// open-ended entry leaves no room.
+    pub later: Option<Quantity<U>>,
+}
+
+/// Error of [`Schedule::add_or_suggest`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddRejected<U: Unit> {
+    /// Why [`Schedule::add`] refused the entry.
+    pub error: ScheduleError,
+    /// Where the entry would fit, for [`ScheduleError::OverlapsExisting`]
+    /// only.
+    pub suggestion: Option<InsertSuggestion<U>>,
+}
+
+impl<U: Unit> fmt::Display for AddRejected<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl<U: Unit> std::error::Error for AddRejected<U> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<U: Unit> Schedule<U> {
+    /// Inserts a task like [`add`](Self::add); if it overlaps an entry, also
+    /// reports where it would fit, see [`suggest_starts`](Self::suggest_starts).
+    pub fn add_or_suggest(
+        &mut self,
+        id: impl Into<Id>,
+        interval: Interval<U>,
+    ) -> Result<(), AddRejected<U>> {
+        match self.add(id, interval) {
+            Ok(()) => Ok(()),
+            Err(error @ ScheduleError::OverlapsExisting { .. }) => Err(AddRejected {
+                error,
+                suggestion: self.suggest_starts(interval).ok(),
+            }),
+            Err(error) => Err(AddRejected {
+                error,
+                suggestion: None,
+            }),
+        }
+    }
+
+    /// The nearest starts, before and after `interval.start()`, at which an
+    /// interval as long as `interval` conflicts with no entry.
+    ///
+    /// Both are the requested start if `interval` is already free. Each
+    /// step jumps past the conflicting entries, so the search visits only
+    /// the entries between the requested start and the answers.
+    ///
+    /// # Errors
+    ///
+    /// [`ScheduleError::NaNTime`] if a time of `interval` is NaN.
+    pub fn suggest_starts(
+        &self,
+        interval: Interval<U>,
+    ) -> Result<InsertSuggestion<U>, ScheduleError> {
+        let duration = interval.duration();
+        let gap = self.min_gap();
+
+        let mut later = Some(interval.start());
+        while let Some(start) = later {
+            let slot = Interval::new(start, start + duration);
+            let Some(end) = self
+                .conflicts_ref(slot)?
+                .map(|(_, e)| e.end())
+                .reduce(|a, b| a.max(b))
+            else {
+                break;
+            };
+            // Stop on open-ended entries and on rounding that makes no
+            // progress.
+            let next = end + gap;
+            later = (next.value().is_finite() && next.value() > start.value()).then_some(next);
+        }
+
+        let mut earlier = Some(interval.start());
+        while let Some(start) = earlier {
+            let slot = Interval::new(start, start + duration);
+            // Conflicts come in start order.
+            let Some((_, first)) = self.conflicts_ref(slot)?.next() else {
+                break;
+            };
+            let next = first.start() - gap - duration;
+            earlier = (next.value().is_finite() && next.value() < start.value()).then_some(next);
+        }
+
+        Ok(InsertSuggestion { earlier, later })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{iv, q};
+    use qtty::Second;
+
+    #[test]
+    fn suggests_the_nearest_free_starts() {
+        let mut schedule = Schedule::<Second>::new().with_min_gap(q(2.0));
+        schedule.add("a", iv(10.0, 20.0)).unwrap();
+        schedule.add("b", iv(25.0, 30.0)).unwrap();
+        schedule.add("c", iv(40.0, 50.0)).unwrap();
+
+        // Too long for the gaps around b: the search jumps over a, b and c.
+        let rejected = schedule.add_or_suggest("x", iv(18.0, 26.0)).unwrap_err();
+        assert!(matches!(
+            rejected.error,
+            ScheduleError::OverlapsExisting { .. }
+        ));
+        assert_eq!(
+            rejected.suggestion,
+            Some(InsertSuggestion {
+                earlier: Some(q(0.0)),
+                later: Some(q(52.0)),
+            })
+        );
+        assert!(!schedule.contains_task("x"));
+
+        // The suggestions are accepted as they are.
+        schedule.add_or_suggest("x", iv(52.0, 60.0)).unwrap();
+        schedule.add_or_suggest("y", iv(0.0, 8.0)).unwrap();
+
+        // Short enough for the room between b and c. A schedule has no
+        // horizon, so the earlier start may precede every entry.
+        let fix = schedule.suggest_starts(iv(29.0, 33.0)).unwrap();
+        assert_eq!((fix.earlier, fix.later), (Some(q(-6.0)), Some(q(32.0))));
+
+        let duplicate = schedule.add_or_suggest("a", iv(70.0, 80.0)).unwrap_err();
+        assert_eq!(duplicate.suggestion, None);
+        assert_eq!(
+            duplicate.to_string(),
+            "Task ID a already exists in schedule"
+        );
+
+        schedule.add("open", iv(100.0, f64::INFINITY)).unwrap();
+        let fix = schedule.suggest_starts(iv(95.0, 105.0)).unwrap();
+        assert_eq!((fix.earlier, fix.later), (Some(q(88.0)), None));
+    }
+}
@@ -589,6 +589,21 @@ mod serde_tests {
         assert!(err.contains("overlaps"));
     }
 
+    #[test]
+    fn background_entries_are_flagged() {
+        let mut schedule = TestSchedule::new();
+        schedule.add("science", iv(0.0, 10.0)).unwrap();
+        schedule.add_background("monitor", iv(0.0, 50.0)).unwrap();
+
+        let json = serde_json::to_value(&schedule).unwrap();
+        assert!(json[0].get("background").is_none());
+        assert_eq!(json[1]["background"], true);
+
+        let restored: TestSchedule = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.len(), 1);
+        assert!(restored.is_background("monitor"));
+    }
+
     #[test]
     fn test_empty_schedule_serialize_deserialize() {
         let schedule = TestSchedule::new();
@@ -599,3 +614,308 @@ mod serde_tests {
         assert!(restored.is_empty());
     }
 }
+
+#[test]
+fn infeasible_entries_checks_windows_and_starts() {
+    use crate::solution_space::{CandidateStarts, SolutionSpace};
+
+    let mut s = Schedule::new();
+    s.add("a", iv(0.0, 10.0)).unwrap();
+    s.add("b", iv(20.0, 30.0)).unwrap();
+    s.add("c", iv(40.0, 45.0)).unwrap();
+
+    let mut ss = SolutionSpace::new();
+    ss.set_intervals("a", vec![iv(0.0, 50.0)]);
+    ss.set_intervals("b", vec![iv(0.0, 25.0)]);
+    assert_eq!(
+        s.infeasible_entries(&ss),
+        vec!["b".to_string(), "c".to_string()]
+    );
+
+    let mut starts = CandidateStarts::new();
+    starts.set_starts("a", vec![q(0.0)]);
+    starts.set_starts("b", vec![q(15.0), q(25.0)]);
+    starts.set_starts("c", vec![q(40.0)]);
+    assert_eq!(s.infeasible_entries(&starts), vec!["b".to_string()]);
+}
+
+#[test]
+fn borrowed_conflicts_stop_at_the_limit() {
+    let mut s = Schedule::new();
+    s.add("a", iv(0.0, 10.0)).unwrap();
+    s.add("b", iv(10.0, 20.0)).unwrap();
+    s.add("c", iv(25.0, 30.0)).unwrap();
+
+    let query = iv(5.0, 27.0);
+    let borrowed: Vec<(&str, TestInterval)> = s.conflicts_ref(query).unwrap().collect();
+    assert_eq!(
+        borrowed,
+        vec![
+            ("a", iv(0.0, 10.0)),
+            ("b", iv(10.0, 20.0)),
+            ("c", iv(25.0, 30.0))
+        ]
+    );
+    assert_eq!(
+        s.conflicts_ref(query).unwrap().next(),
+        Some(("a", iv(0.0, 10.0)))
+    );
+
+    assert_eq!(s.conflicts_count(query, usize::MAX).unwrap(), 3);
+    assert_eq!(s.conflicts_count(query, 2).unwrap(), 2);
+    assert_eq!(s.conflicts_count(query, 0).unwrap(), 0);
+    assert_eq!(s.conflicts_count(iv(20.0, 25.0), 5).unwrap(), 0);
+}
+
+#[test]
+fn free_set_is_the_complement_of_busy_time() {
+    let mut s = Schedule::new();
+    s.add("a", iv(0.0, 10.0)).unwrap();
+    s.add("b", iv(10.0, 20.0)).unwrap();
+    s.add("c", iv(80.0, 120.0)).unwrap();
+
+    assert_eq!(
+        s.to_busy_intervals().as_slice(),
+        [iv(0.0, 20.0), iv(80.0, 120.0)]
+    );
+    // Entries outside the horizon are clipped away.
+    let free = s.to_free_interval_set(iv(5.0, 100.0));
+    assert_eq!(free.as_slice(), [iv(20.0, 80.0)]);
+    assert_eq!(
+        TestSchedule::new()
+            .to_free_interval_set(iv(0.0, 50.0))
+            .as_slice(),
+        [iv(0.0, 50.0)]
+    );
+}
+
+#[test]
+fn min_gap_pads_insertions_and_queries() {
+    let mut s = TestSchedule::new().with_min_gap(Quantity::new(5.0));
+    assert_eq!(s.min_gap().value(), 5.0);
+    s.add("a", iv(10.0, 20.0)).unwrap();
+
+    assert!(matches!(
+        s.add("b", iv(22.0, 30.0)),
+        Err(ScheduleError::OverlapsExisting { existing_id, .. }) if existing_id == "a"
+    ));
+    assert!(matches!(
+        s.add("b", iv(0.0, 7.0)),
+        Err(ScheduleError::OverlapsExisting { existing_id, .. }) if existing_id == "a"
+    ));
+    assert!(!s.is_free(iv(21.0, 22.0)).unwrap());
+    assert_eq!(s.conflicts_vec(iv(0.0, 6.0)).unwrap().len(), 1);
+
+    // Exactly the gap apart is fine on either side.
+    s.add("b", iv(25.0, 30.0)).unwrap();
+    s.add("c", iv(0.0, 5.0)).unwrap();
+    assert!(s.is_free(iv(35.0, 40.0)).unwrap());
+
+    let minutes: Schedule<qtty::Minute> = s.to();
+    assert_eq!(minutes.min_gap().value(), 5.0 / 60.0);
+}
+
+#[test]
+#[should_panic(expected = "minimum gap must be non-negative")]
+fn negative_min_gap_is_rejected() {
+    let _ = TestSchedule::new().with_min_gap(Quantity::new(-1.0));
+}
+
+#[test]
+fn time_buckets_match_the_flat_index() {
+    let mut flat = TestSchedule::new();
+    let mut bucketed = TestSchedule::new().with_time_buckets(Quantity::new(10.0));
+    assert_eq!(bucketed.time_bucket_width(), Some(Quantity::new(10.0)));
+    assert_eq!(flat.time_bucket_width(), None);
+
+    // Entries straddling bucket edges, negative starts and an unbounded one.
+    let entries = [
+        ("a", iv(-15.0, -5.0)),
+        ("b", iv(-5.0, 3.0)),
+        ("c", iv(8.0, 27.0)),
+        ("d", iv(30.0, 30.0)),
+        ("e", iv(31.0, 39.5)),
+        ("f", iv(100.0, f64::INFINITY)),
+    ];
+    for (id, interval) in entries {
+        flat.add(id, interval).unwrap();
+        bucketed.add(id, interval).unwrap();
+    }
+    assert_eq!(flat.remove("e"), bucketed.remove("e"));
+    assert!(bucketed.add("c2", iv(20.0, 25.0)).is_err());
+
+    assert_eq!(bucketed.len(), flat.len());
+    assert!(flat.iter().eq(bucketed.iter()));
+    for query in [
+        iv(-20.0, 0.0),
+        iv(19.0, 21.0),
+        iv(26.0, 200.0),
+        iv(40.0, 99.0),
+    ] {
+        assert_eq!(flat.conflicts_vec(query), bucketed.conflicts_vec(query));
+        assert!(flat.starting_in(query).eq(bucketed.starting_in(query)));
+    }
+    for pos in [-10.0, 9.0, 25.0, 30.0, 1e9] {
+        assert_eq!(flat.task_at(q(pos)), bucketed.task_at(q(pos)));
+    }
+    assert_eq!(bucketed.latest_end(), Some(q(f64::INFINITY)));
+    assert_eq!(bucketed.earliest_start(), Some(q(-15.0)));
+
+    // Only entries starting in the range, once each over consecutive ranges.
+    let ids: Vec<_> = bucketed
+        .starting_in(iv(0.0, 30.0))
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(ids, ["c"]);
+
+    let mut rebucketed = flat.clone().with_time_buckets(Quantity::new(7.0));
+    assert!(rebucketed.iter().eq(flat.iter()));
+    rebucketed.clear();
+    assert!(rebucketed.is_empty());
+    assert_eq!(rebucketed.time_bucket_width(), Some(Quantity::new(7.0)));
+}
+
+#[test]
+fn milestones_never_conflict() {
+    let mut s = TestSchedule::new().with_min_gap(Quantity::new(2.0));
+    s.add("observe", iv(10.0, 20.0)).unwrap();
+    // Inside a task, at its start, twice at one instant, and within the gap.
+    s.add("mid", iv(15.0, 15.0)).unwrap();
+    s.add("begin", iv(10.0, 10.0)).unwrap();
+    s.add("begin-too", iv(10.0, 10.0)).unwrap();
+    s.add("after", iv(21.0, 21.0)).unwrap();
+    assert_eq!(s.len(), 5);
+
+    // Milestones do not hide the task from its neighbors or queries.
+    assert!(s.add("clash", iv(16.0, 18.0)).is_err());
+    assert!(s.add("close", iv(21.0, 25.0)).is_err());
+    s.add("next", iv(22.0, 30.0)).unwrap();
+    assert_eq!(s.task_at(q(15.0)).unwrap(), Some("observe".to_string()));
+    let conflicts = s.conflicts_vec(iv(12.0, 13.0)).unwrap();
+    assert_eq!(conflicts, [("observe".to_string(), iv(10.0, 20.0))]);
+    assert!(s.is_free(iv(15.0, 15.0)).unwrap());
+
+    let order: Vec<_> = s.iter().map(|(id, _)| id).collect();
+    assert_eq!(
+        order,
+        ["begin", "begin-too", "observe", "mid", "after", "next"]
+    );
+    assert_eq!(s.get_interval("begin-too"), Some(iv(10.0, 10.0)));
+    assert_eq!(s.remove("begin"), Some(iv(10.0, 10.0)));
+    assert_eq!(s.get_interval("begin-too"), Some(iv(10.0, 10.0)));
+    assert_eq!(s.total_duration(), q(18.0));
+    assert_eq!(
+        s.to_busy_intervals().as_slice(),
+        [iv(10.0, 20.0), iv(22.0, 30.0)]
+    );
+
+    let mut tail = TestSchedule::new();
+    tail.add("observe", iv(0.0, 10.0)).unwrap();
+    tail.add("mid", iv(5.0, 5.0)).unwrap();
+    assert_eq!(tail.latest_end(), Some(q(10.0)));
+    tail.add("done", iv(12.0, 12.0)).unwrap();
+    assert_eq!(tail.latest_end(), Some(q(12.0)));
+}
+
+#[test]
+fn background_lane_overlaps_freely() {
+    let mut s = TestSchedule::new().with_min_gap(q(5.0));
+    s.add("science", iv(10.0, 20.0)).unwrap();
+    s.add_background("monitor", iv(0.0, 100.0)).unwrap();
+    s.add_background("calib", iv(15.0, 25.0)).unwrap();
+    // The primary lane still checks overlaps and gaps among its own entries.
+    s.add("next", iv(25.0, 30.0)).unwrap();
+    assert!(s.add("clash", iv(18.0, 22.0)).is_err());
+
+    // IDs are unique across lanes.
+    assert!(matches!(
+        s.add("monitor", iv(50.0, 60.0)),
+        Err(ScheduleError::DuplicateTaskId(_))
+    ));
+    assert!(s.add_background("science", iv(50.0, 60.0)).is_err());
+
+    assert_eq!((s.len(), s.background_len()), (2, 2));
+    assert!(s.contains_task("calib") && s.is_background("calib"));
+    assert!(!s.is_background("science"));
+    assert_eq!(s.get_interval("monitor"), Some(iv(0.0, 100.0)));
+    assert_eq!(s.task_at(q(50.0)).unwrap(), None);
+    assert!(s.is_free(iv(40.0, 50.0)).unwrap());
+    assert_eq!(s.total_duration(), q(15.0));
+    let background: Vec<_> = s.background().map(|(id, _)| id).collect();
+    assert_eq!(background, ["monitor", "calib"]);
+
+    let minutes: Schedule<qtty::Minute> = s.to();
+    assert_eq!(
+        minutes.get_interval("calib").map(|i| i.to::<Second>()),
+        Some(iv(15.0, 25.0))
+    );
+    assert!(minutes.is_background("calib"));
+
+    assert_eq!(s.remove("calib"), Some(iv(15.0, 25.0)));
+    assert!(!s.contains_task("calib"));
+    s.clear();
+    assert_eq!(s.background_len(), 0);
+}
+
+#[test]
+fn iter_all_copies_both_lanes() {
+    let mut s = TestSchedule::new();
+    s.add("science", iv(10.0, 20.0)).unwrap();
+    s.add_background("monitor", iv(0.0, 100.0)).unwrap();
+    let entries: Vec<_> = s.iter_all().collect();
+    assert_eq!(
+        entries,
+        [
+            ("science".to_string(), iv(10.0, 20.0), false),
+            ("monitor".to_string(), iv(0.0, 100.0), true),
+        ]
+    );
+
+    let mut copy = TestSchedule::new();
+    for (id, interval, background) in s.iter_all() {
+        copy.add_to_lane(id, interval, background).unwrap();
+    }
+    assert!(copy.is_background("monitor"));
+    assert!(!copy.is_background("science"));
+    assert!(copy.add_to_lane("clash", iv(15.0, 25.0), false).is_err());
+}
+
+#[test]
+fn priority_index_finds_eviction_candidates() {
+    let mut s = TestSchedule::new();
+    s.add_with_priority("a", iv(0.0, 10.0), 3).unwrap();
+    s.add_with_priority("b", iv(10.0, 20.0), 1).unwrap();
+    s.add("fixed", iv(20.0, 30.0)).unwrap();
+    s.add_with_priority("c", iv(30.0, 40.0), 1).unwrap();
+    s.add_with_priority("d", iv(40.0, 50.0), 0).unwrap();
+    assert!(s.add_with_priority("clash", iv(5.0, 15.0), 0).is_err());
+    assert_eq!(s.priority_of("clash"), None);
+    assert_eq!(s.priority_of("b"), Some(1));
+    assert_eq!(s.priority_of("fixed"), None);
+
+    // Lowest priority first, ties in start order.
+    let order: Vec<_> = s.lowest_priority().map(|(id, _, p)| (id, p)).collect();
+    assert_eq!(order, [("d", 0), ("b", 1), ("c", 1), ("a", 3)]);
+
+    let evict = s.lowest_priority_conflicts(iv(5.0, 35.0), 2).unwrap();
+    assert_eq!(
+        evict,
+        [
+            ("b".to_string(), iv(10.0, 20.0), 1),
+            ("c".to_string(), iv(30.0, 40.0), 1)
+        ]
+    );
+    assert!(s
+        .lowest_priority_conflicts(iv(20.0, 30.0), 5)
+        .unwrap()
+        .is_empty());
+
+    s.remove("b");
+    let order: Vec<_> = s.lowest_priority().map(|(id, _, _)| id).collect();
+    assert_eq!(order, ["d", "c", "a"]);
+
+    let minutes: Schedule<qtty::Minute> = s.to();
+    assert_eq!(minutes.priority_of("a"), Some(3));
+    s.clear();
+    assert_eq!(s.lowest_priority().count(), 0);
+}
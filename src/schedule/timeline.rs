@@ -0,0 +1,647 @@
+//! Multi-resource timeline with simultaneous bookings.
+//!
+//! A [`Timeline`] holds one [`Schedule`] per resource and books a task on
+//! several resources at once, all-or-nothing. This is how coalition tasks
+//! (tasks that need `k` resources simultaneously) appear in the output: the
+//! same task ID occupies the same interval in every member's schedule.
+//!
+//! Resources can also be grouped for mutual exclusion: two instruments
+//! sharing one focal station cannot operate at the same time, even though
+//! each has its own schedule. Bookings that would make two members of an
+//! exclusion group busy at once are rejected.
+//!
+//! Task classes can be capped across all resources: "at most two tasks
+//! tagged `link = downlink` at once" models a downlink shared by every
+//! spacecraft. Tasks are tagged on the timeline with [`Timeline::tag`]; a
+//! task booked on several resources counts once.
+
+use std::collections::{BTreeSet, HashMap};
+
+use qtty::Quantity;
+
+use super::errors::ScheduleError;
+use super::Schedule;
+use crate::scheduling_block::Tags;
+use crate::solution_space::Interval;
+use crate::Id;
+
+/// Two bookings violating a mutual-exclusion group, see
+/// [`Timeline::exclusion_conflicts`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExclusionConflict<U: qtty::Unit> {
+    /// The two resources, sorted.
+    pub resources: [Id; 2],
+    /// The task on each resource (the same task if booked on both).
+    pub tasks: [Id; 2],
+    /// When both are busy.
+    pub overlap: Interval<U>,
+}
+
+/// At most `max` tasks tagged `key = value` may run at any time, across all
+/// resources.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConcurrencyLimit {
+    pub key: String,
+    pub value: String,
+    pub max: usize,
+}
+
+impl ConcurrencyLimit {
+    /// Returns `true` if the limit counts a task with `tags`.
+    pub fn applies_to(&self, tags: &Tags) -> bool {
+        tags.get(&self.key) == Some(&self.value)
+    }
+}
+
+/// A stretch of time over which a [`ConcurrencyLimit`] is exceeded, see
+/// [`Timeline::concurrency_violations`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConcurrencyViolation<U: qtty::Unit> {
+    pub limit: ConcurrencyLimit,
+    /// Maximal interval with more than `limit.max` tasks running.
+    pub interval: Interval<U>,
+    /// Every task of the class running during `interval`, sorted.
+    pub tasks: Vec<Id>,
+}
+
+/// Per-resource schedules supporting atomic multi-resource bookings.
+#[derive(Debug, Clone)]
+pub struct Timeline<U: qtty::Unit> {
+    schedules: HashMap<Id, Schedule<U>>,
+    exclusion_groups: Vec<Vec<Id>>,
+    concurrency_limits: Vec<ConcurrencyLimit>,
+    tags: HashMap<Id, Tags>,
+}
+
+impl<U: qtty::Unit> Timeline<U> {
+    /// Creates an empty timeline with no resources.
+    pub fn new() -> Self {
+        Self {
+            schedules: HashMap::new(),
+            exclusion_groups: Vec::new(),
+            concurrency_limits: Vec::new(),
+            tags: HashMap::new(),
+        }
+    }
+
+    /// Creates a timeline with an empty schedule for each resource.
+    pub fn with_resources(resources: impl IntoIterator<Item = impl Into<Id>>) -> Self {
+        Self {
+            schedules: resources
+                .into_iter()
+                .map(|id| (id.into(), Schedule::new()))
+                .collect(),
+            exclusion_groups: Vec::new(),
+            concurrency_limits: Vec::new(),
+            tags: HashMap::new(),
+        }
+    }
+
+    /// Adds a mutual-exclusion group: at most one of `resources` may be busy
+    /// at any time.
+    ///
+    /// Groups only constrain later bookings; use
+    /// [`exclusion_conflicts`](Self::exclusion_conflicts) to check existing
+    /// ones.
+    pub fn with_exclusion_group(
+        mut self,
+        resources: impl IntoIterator<Item = impl Into<Id>>,
+    ) -> Self {
+        self.add_exclusion_group(resources);
+        self
+    }
+
+    /// Adds a mutual-exclusion group, see
+    /// [`with_exclusion_group`](Self::with_exclusion_group).
+    pub fn add_exclusion_group(&mut self, resources: impl IntoIterator<Item = impl Into<Id>>) {
+        let mut group: Vec<Id> = resources.into_iter().map(Into::into).collect();
+        group.sort_unstable();
+        group.dedup();
+        if group.len() > 1 {
+            self.exclusion_groups.push(group);
+        }
+    }
+
+    /// The mutual-exclusion groups, each sorted by resource ID.
+    pub fn exclusion_groups(&self) -> &[Vec<Id>] {
+        &self.exclusion_groups
+    }
+
+    /// Resources sharing an exclusion group with `resource`, sorted.
+    pub fn exclusive_partners(&self, resource: &str) -> Vec<&str> {
+        let mut partners: Vec<&str> = self
+            .exclusion_groups
+            .iter()
+            .filter(|group| group.iter().any(|r| r == resource))
+            .flatten()
+            .map(String::as_str)
+            .filter(|&r| r != resource)
+            .collect();
+        partners.sort_unstable();
+        partners.dedup();
+        partners
+    }
+
+    /// Returns `true` if `a` and `b` share an exclusion group.
+    pub fn are_exclusive(&self, a: &str, b: &str) -> bool {
+        a != b
+            && self
+                .exclusion_groups
+                .iter()
+                .any(|group| group.iter().any(|r| r == a) && group.iter().any(|r| r == b))
+    }
+
+    /// Caps the number of tasks tagged `key = value` running at once across
+    /// all resources.
+    ///
+    /// Like exclusion groups, limits only constrain later bookings; use
+    /// [`concurrency_violations`](Self::concurrency_violations) to check
+    /// existing ones.
+    pub fn with_concurrency_limit(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+        max: usize,
+    ) -> Self {
+        self.add_concurrency_limit(key, value, max);
+        self
+    }
+
+    /// Adds a concurrency limit, see
+    /// [`with_concurrency_limit`](Self::with_concurrency_limit).
+    pub fn add_concurrency_limit(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+        max: usize,
+    ) {
+        self.concurrency_limits.push(ConcurrencyLimit {
+            key: key.into(),
+            value: value.into(),
+            max,
+        });
+    }
+
+    /// The concurrency limits, in insertion order.
+    pub fn concurrency_limits(&self) -> &[ConcurrencyLimit] {
+        &self.concurrency_limits
+    }
+
+    /// Sets the label `key` of task `id` to `value`, returning the previous
+    /// value. Tag tasks before booking them for limits to apply.
+    pub fn tag(
+        &mut self,
+        id: impl Into<Id>,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Option<String> {
+        self.tags
+            .entry(id.into())
+            .or_default()
+            .insert(key.into(), value.into())
+    }
+
+    /// Returns the labels of task `id` (empty for untagged tasks).
+    pub fn tags_of(&self, id: &str) -> Tags {
+        self.tags.get(id).cloned().unwrap_or_default()
+    }
+
+    /// Intervals, sorted by start, over which some concurrency limit
+    /// applying to `task_id` is already reached, so that booking it there
+    /// would exceed the limit.
+    pub fn saturated_for(&self, task_id: &str) -> Vec<Interval<U>> {
+        let mut out: Vec<Interval<U>> = self
+            .limits_of(task_id)
+            .flat_map(|limit| match limit.max {
+                0 => vec![Interval::new(
+                    Quantity::new(f64::NEG_INFINITY),
+                    Quantity::new(f64::INFINITY),
+                )],
+                max => self
+                    .crowded(limit, max)
+                    .into_iter()
+                    .map(|(interval, _)| interval)
+                    .collect(),
+            })
+            .collect();
+        out.sort_by(|a, b| a.start().value().total_cmp(&b.start().value()));
+        out
+    }
+
+    /// Returns the schedule of a resource.
+    pub fn schedule(&self, resource: &str) -> Option<&Schedule<U>> {
+        self.schedules.get(resource)
+    }
+
+    /// IDs of all resources on the timeline.
+    pub fn resources(&self) -> impl Iterator<Item = &str> + '_ {
+        self.schedules.keys().map(String::as_str)
+    }
+
+    /// Returns `true` if `interval` is free on `resource` and on every
+    /// resource sharing an exclusion group with it.
+    ///
+    /// Resources not on the timeline are considered free.
+    pub fn is_free(&self, resource: &str, interval: Interval<U>) -> Result<bool, ScheduleError> {
+        for r in std::iter::once(resource).chain(self.exclusive_partners(resource)) {
+            if let Some(schedule) = self.schedules.get(r) {
+                if !schedule.is_free(interval)? {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Books `task_id` on every resource in `resources` over `interval`.
+    ///
+    /// The booking is atomic: if any resource already holds the task, has a
+    /// conflicting interval, or shares an exclusion group with a busy or
+    /// requested resource, nothing is booked and the first error is
+    /// returned. Resources not yet on the timeline are added.
+    pub fn book<R: AsRef<str>>(
+        &mut self,
+        task_id: impl Into<Id>,
+        interval: Interval<U>,
+        resources: &[R],
+    ) -> Result<(), ScheduleError> {
+        let task_id = task_id.into();
+        let mut members: Vec<&str> = resources.iter().map(AsRef::as_ref).collect();
+        members.sort_unstable();
+        members.dedup();
+
+        for (i, &resource) in members.iter().enumerate() {
+            if let Some(&other) = members[i + 1..]
+                .iter()
+                .find(|&&other| self.are_exclusive(resource, other))
+            {
+                return Err(ScheduleError::ExclusiveResources {
+                    task_id,
+                    first: resource.to_string(),
+                    second: other.to_string(),
+                });
+            }
+            if self
+                .schedules
+                .get(resource)
+                .is_some_and(|schedule| schedule.contains_task(&task_id))
+            {
+                return Err(ScheduleError::DuplicateTaskId(task_id));
+            }
+            for r in std::iter::once(resource).chain(self.exclusive_partners(resource)) {
+                let Some(schedule) = self.schedules.get(r) else {
+                    continue;
+                };
+                if let Some((existing_id, _)) = schedule.conflicts_ref(interval)?.next() {
+                    return Err(ScheduleError::OverlapsExisting {
+                        new_id: task_id,
+                        existing_id: existing_id.to_string(),
+                    });
+                }
+            }
+        }
+        if !members.is_empty() {
+            if let Some(limit) = self.limits_of(&task_id).find(|limit| {
+                limit.max == 0
+                    || self
+                        .crowded(limit, limit.max)
+                        .iter()
+                        .any(|(busy, _)| busy.overlaps(&interval))
+            }) {
+                return Err(ScheduleError::ConcurrencyLimitReached {
+                    task_id,
+                    key: limit.key.clone(),
+                    value: limit.value.clone(),
+                    max: limit.max,
+                });
+            }
+        }
+
+        for resource in members {
+            self.schedules
+                .entry(resource.to_string())
+                .or_default()
+                .add(task_id.clone(), interval)
+                .expect("booking validated above");
+        }
+        Ok(())
+    }
+
+    /// Resources a task is booked on, sorted by ID.
+    pub fn resources_of(&self, task_id: &str) -> Vec<&str> {
+        let mut out: Vec<&str> = self
+            .schedules
+            .iter()
+            .filter(|(_, s)| s.contains_task(task_id))
+            .map(|(id, _)| id.as_str())
+            .collect();
+        out.sort_unstable();
+        out
+    }
+
+    /// Interval a task is booked over, if booked anywhere.
+    pub fn interval_of(&self, task_id: &str) -> Option<Interval<U>> {
+        self.schedules
+            .values()
+            .find_map(|s| s.get_interval(task_id))
+    }
+
+    /// Number of distinct tasks booked on the timeline.
+    pub fn task_count(&self) -> usize {
+        let mut ids: Vec<Id> = self.schedules.values().flat_map(Schedule::ids).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids.len()
+    }
+
+    /// Pairs of bookings on resources of a shared exclusion group that
+    /// overlap in time, sorted by resources then tasks.
+    ///
+    /// Empty for timelines built only through [`book`](Self::book).
+    pub fn exclusion_conflicts(&self) -> Vec<ExclusionConflict<U>> {
+        let mut conflicts = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for group in &self.exclusion_groups {
+            for (i, a) in group.iter().enumerate() {
+                for b in &group[i + 1..] {
+                    if !seen.insert((a, b)) {
+                        continue;
+                    }
+                    let (Some(sa), Some(sb)) = (self.schedules.get(a), self.schedules.get(b))
+                    else {
+                        continue;
+                    };
+                    for (task_a, iv_a) in sa.iter() {
+                        for (task_b, iv_b) in sb.conflicts_ref(iv_a).into_iter().flatten() {
+                            conflicts.push(ExclusionConflict {
+                                resources: [a.clone(), b.clone()],
+                                tasks: [task_a.clone(), task_b.to_string()],
+                                overlap: iv_a.intersection(&iv_b).expect("conflicts overlap"),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        conflicts.sort_by(|x, y| (&x.resources, &x.tasks).cmp(&(&y.resources, &y.tasks)));
+        conflicts
+    }
+
+    /// Maximal intervals over which a concurrency limit is exceeded, sorted
+    /// by limit then start.
+    ///
+    /// Empty for timelines built only through [`book`](Self::book) with
+    /// tasks tagged beforehand.
+    pub fn concurrency_violations(&self) -> Vec<ConcurrencyViolation<U>> {
+        self.concurrency_limits
+            .iter()
+            .flat_map(|limit| {
+                self.crowded(limit, limit.max + 1)
+                    .into_iter()
+                    .map(|(interval, tasks)| ConcurrencyViolation {
+                        limit: limit.clone(),
+                        interval,
+                        tasks,
+                    })
+            })
+            .collect()
+    }
+
+    /// Limits counting task `id`.
+    fn limits_of<'a>(&'a self, id: &str) -> impl Iterator<Item = &'a ConcurrencyLimit> + 'a {
+        let tags = self.tags.get(id);
+        self.concurrency_limits
+            .iter()
+            .filter(move |limit| tags.is_some_and(|tags| limit.applies_to(tags)))
+    }
+
+    /// Maximal intervals over which at least `threshold` (> 0) tasks counted
+    /// by `limit` run, with those tasks.
+    fn crowded(&self, limit: &ConcurrencyLimit, threshold: usize) -> Vec<(Interval<U>, Vec<Id>)> {
+        // A task booked on several resources is one booking.
+        let mut bookings: Vec<(Id, Interval<U>)> = self
+            .schedules
+            .values()
+            .flat_map(Schedule::iter)
+            .filter(|(id, _)| self.tags.get(id).is_some_and(|t| limit.applies_to(t)))
+            .collect();
+        bookings.sort_by(|(a, x), (b, y)| {
+            (a, x.start().value(), x.end().value())
+                .partial_cmp(&(b, y.start().value(), y.end().value()))
+                .expect("schedules hold no NaN")
+        });
+        bookings.dedup();
+
+        let mut events: Vec<(f64, bool, &str)> = bookings
+            .iter()
+            .flat_map(|(id, iv)| {
+                [
+                    (iv.start().value(), true, id.as_str()),
+                    (iv.end().value(), false, id.as_str()),
+                ]
+            })
+            .collect();
+        events.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut out = Vec::new();
+        let mut running: Vec<&str> = Vec::new();
+        let mut open: Option<(f64, BTreeSet<&str>)> = None;
+        let mut i = 0;
+        while i < events.len() {
+            let t = events[i].0;
+            while i < events.len() && events[i].0 == t {
+                let (_, starts, id) = events[i];
+                if starts {
+                    running.push(id);
+                } else if let Some(pos) = running.iter().position(|&r| r == id) {
+                    running.swap_remove(pos);
+                }
+                i += 1;
+            }
+            if running.len() >= threshold {
+                open.get_or_insert_with(|| (t, BTreeSet::new()))
+                    .1
+                    .extend(running.iter().copied());
+            } else if let Some((start, tasks)) = open.take() {
+                out.push((
+                    Interval::new(Quantity::new(start), Quantity::new(t)),
+                    tasks.into_iter().map(str::to_string).collect(),
+                ));
+            }
+        }
+        out
+    }
+
+    /// Consumes the timeline, returning the per-resource schedules.
+    pub fn into_schedules(self) -> HashMap<Id, Schedule<U>> {
+        self.schedules
+    }
+}
+
+impl<U: qtty::Unit> Default for Timeline<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<U: qtty::Unit> From<HashMap<Id, Schedule<U>>> for Timeline<U> {
+    fn from(schedules: HashMap<Id, Schedule<U>>) -> Self {
+        Self {
+            schedules,
+            exclusion_groups: Vec::new(),
+            concurrency_limits: Vec::new(),
+            tags: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qtty::Second;
+
+    fn iv(start: f64, end: f64) -> Interval<Second> {
+        Interval::from_f64(start, end)
+    }
+
+    #[test]
+    fn book_on_several_resources() {
+        let mut t: Timeline<Second> = Timeline::with_resources(["r1", "r2", "r3"]);
+        t.book("obs", iv(0.0, 10.0), &["r2", "r1"]).unwrap();
+
+        assert_eq!(t.resources_of("obs"), vec!["r1", "r2"]);
+        assert_eq!(t.interval_of("obs"), Some(iv(0.0, 10.0)));
+        assert!(t.schedule("r3").unwrap().is_empty());
+        assert_eq!(t.task_count(), 1);
+    }
+
+    #[test]
+    fn booking_is_atomic() {
+        let mut t: Timeline<Second> = Timeline::with_resources(["r1", "r2"]);
+        t.book("a", iv(5.0, 15.0), &["r2"]).unwrap();
+
+        let err = t.book("b", iv(0.0, 10.0), &["r1", "r2"]).unwrap_err();
+        assert_eq!(
+            err,
+            ScheduleError::OverlapsExisting {
+                new_id: "b".into(),
+                existing_id: "a".into()
+            }
+        );
+        assert!(t.schedule("r1").unwrap().is_empty());
+        assert!(t.resources_of("b").is_empty());
+    }
+
+    #[test]
+    fn duplicate_task_rejected() {
+        let mut t: Timeline<Second> = Timeline::new();
+        t.book("a", iv(0.0, 1.0), &["r1"]).unwrap();
+        assert_eq!(
+            t.book("a", iv(5.0, 6.0), &["r1", "r2"]),
+            Err(ScheduleError::DuplicateTaskId("a".into()))
+        );
+        assert!(t.schedule("r2").is_none());
+    }
+
+    #[test]
+    fn unknown_resources_are_added_and_free() {
+        let mut t: Timeline<Second> = Timeline::new();
+        assert!(t.is_free("r9", iv(0.0, 1.0)).unwrap());
+        t.book("a", iv(0.0, 1.0), &["r9", "r9"]).unwrap();
+        assert!(!t.is_free("r9", iv(0.5, 2.0)).unwrap());
+        assert_eq!(t.into_schedules()["r9"].len(), 1);
+    }
+
+    #[test]
+    fn exclusion_groups_block_partners() {
+        let mut t: Timeline<Second> =
+            Timeline::with_resources(["cam", "spec", "r3"]).with_exclusion_group(["spec", "cam"]);
+        assert_eq!(t.exclusive_partners("cam"), ["spec"]);
+        assert!(t.are_exclusive("spec", "cam") && !t.are_exclusive("cam", "r3"));
+
+        t.book("a", iv(0.0, 10.0), &["cam"]).unwrap();
+        assert!(!t.is_free("spec", iv(5.0, 15.0)).unwrap());
+        assert_eq!(
+            t.book("b", iv(5.0, 15.0), &["spec", "r3"]),
+            Err(ScheduleError::OverlapsExisting {
+                new_id: "b".into(),
+                existing_id: "a".into()
+            })
+        );
+        assert!(t.resources_of("b").is_empty());
+        assert_eq!(
+            t.book("c", iv(20.0, 30.0), &["spec", "cam"]),
+            Err(ScheduleError::ExclusiveResources {
+                task_id: "c".into(),
+                first: "cam".into(),
+                second: "spec".into()
+            })
+        );
+        t.book("b", iv(10.0, 15.0), &["spec", "r3"]).unwrap();
+        assert!(t.exclusion_conflicts().is_empty());
+    }
+
+    #[test]
+    fn exclusion_conflicts_report_existing_overlaps() {
+        let mut cam = Schedule::new();
+        cam.add("a", iv(0.0, 10.0)).unwrap();
+        let mut spec = Schedule::new();
+        spec.add("b", iv(5.0, 20.0)).unwrap();
+        let schedules: HashMap<Id, Schedule<Second>> =
+            [("cam".into(), cam), ("spec".into(), spec)].into();
+        let mut t = Timeline::from(schedules);
+        assert!(t.exclusion_conflicts().is_empty());
+
+        t.add_exclusion_group(["spec", "cam"]);
+        assert_eq!(
+            t.exclusion_conflicts(),
+            vec![ExclusionConflict {
+                resources: ["cam".into(), "spec".into()],
+                tasks: ["a".into(), "b".into()],
+                overlap: iv(5.0, 10.0),
+            }]
+        );
+    }
+
+    #[test]
+    fn concurrency_limit_counts_tasks_across_resources() {
+        let mut t: Timeline<Second> = Timeline::with_resources(["sat-1", "sat-2", "sat-3"])
+            .with_concurrency_limit("link", "dl", 1);
+        for id in ["a", "b", "c"] {
+            t.tag(id, "link", "dl");
+        }
+        // One task on two resources counts once.
+        t.book("a", iv(0.0, 10.0), &["sat-1", "sat-2"]).unwrap();
+        assert_eq!(t.saturated_for("b"), vec![iv(0.0, 10.0)]);
+        assert!(t.saturated_for("untagged").is_empty());
+        assert_eq!(
+            t.book("b", iv(5.0, 15.0), &["sat-3"]),
+            Err(ScheduleError::ConcurrencyLimitReached {
+                task_id: "b".into(),
+                key: "link".into(),
+                value: "dl".into(),
+                max: 1
+            })
+        );
+        t.book("b", iv(10.0, 20.0), &["sat-3"]).unwrap();
+        t.book("x", iv(0.0, 20.0), &["sat-3"]).unwrap_err();
+        t.book("x", iv(20.0, 30.0), &["sat-3"]).unwrap();
+
+        // Tagging after booking is reported by validation.
+        let mut c = Schedule::new();
+        c.add("c", iv(8.0, 12.0)).unwrap();
+        let mut schedules = t.clone().into_schedules();
+        schedules.insert("sat-4".into(), c);
+        let mut checked = Timeline::from(schedules);
+        checked.add_concurrency_limit("link", "dl", 1);
+        assert!(checked.concurrency_violations().is_empty());
+        for id in ["a", "b", "c"] {
+            checked.tag(id, "link", "dl");
+        }
+        let violations = checked.concurrency_violations();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].interval, iv(8.0, 12.0));
+        assert_eq!(violations[0].tasks, ["a", "b", "c"]);
+        assert_eq!(violations[0].limit, t.concurrency_limits()[0]);
+    }
+}
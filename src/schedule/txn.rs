@@ -0,0 +1,288 @@
+//! All-or-nothing edits of a schedule.
+//!
+//! Interactive tools and repair often change several entries at once: move
+//! one task to make room, then slot another into the gap. Applied one by
+//! one, such an edit can fail halfway and leave the schedule in a state
+//! nobody asked for. [`Schedule::begin`] opens a [`ScheduleTxn`] that stages
+//! adds, removes and moves, checking each one against the schedule as it
+//! would be with every staged change applied. [`commit`](ScheduleTxn::commit)
+//! then applies them all; [`rollback`](ScheduleTxn::rollback), or dropping
+//! the transaction, discards them.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use qtty::Second;
+//! # use virolai::schedule::Schedule;
+//! # use virolai::solution_space::Interval;
+//! # let mut schedule: Schedule<Second> = Schedule::new();
+//! # schedule.add("calib", Interval::from_f64(600.0, 660.0))?;
+//! let mut txn = schedule.begin();
+//! txn.move_to("calib", Interval::from_f64(0.0, 60.0))?;
+//! txn.add("science", Interval::from_f64(60.0, 600.0))?;
+//! txn.commit();
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use qtty::Unit;
+
+use super::errors::ScheduleError;
+use super::Schedule;
+use crate::solution_space::Interval;
+use crate::Id;
+
+/// A staged entry: where it goes and how it was scheduled before.
+#[derive(Debug, Clone, Copy)]
+struct Staged<U: Unit> {
+    interval: Interval<U>,
+    background: bool,
+    priority: Option<i32>,
+}
+
+/// Staged edits of a schedule, see the [module documentation](self).
+///
+/// The schedule is borrowed mutably until the transaction ends, so nothing
+/// else can change it in between and [`commit`](Self::commit) cannot fail.
+#[derive(Debug)]
+pub struct ScheduleTxn<'a, U: Unit> {
+    schedule: &'a mut Schedule<U>,
+    staged: HashMap<Id, Staged<U>>,
+    removed: HashSet<Id>,
+}
+
+impl<U: Unit> Schedule<U> {
+    /// Starts a transaction on the schedule.
+    pub fn begin(&mut self) -> ScheduleTxn<'_, U> {
+        ScheduleTxn {
+            schedule: self,
+            staged: HashMap::new(),
+            removed: HashSet::new(),
+        }
+    }
+}
+
+impl<U: Unit> ScheduleTxn<'_, U> {
+    /// Stages adding `id` at `interval` in the primary lane.
+    ///
+    /// # Errors
+    ///
+    /// Those of [`Schedule::add`], against the schedule with the staged
+    /// changes applied.
+    pub fn add(&mut self, id: impl Into<Id>, interval: Interval<U>) -> Result<(), ScheduleError> {
+        let id = id.into();
+        if self.contains_task(&id) {
+            return Err(ScheduleError::DuplicateTaskId(id));
+        }
+        let staged = Staged {
+            interval,
+            background: false,
+            priority: None,
+        };
+        self.check(&id, staged)?;
+        self.staged.insert(id, staged);
+        Ok(())
+    }
+
+    /// Stages removing `id`, returning its interval.
+    ///
+    /// # Errors
+    ///
+    /// [`ScheduleError::TaskNotFound`] if `id` is not in the schedule with
+    /// the staged changes applied.
+    pub fn remove(&mut self, id: &str) -> Result<Interval<U>, ScheduleError> {
+        if let Some(staged) = self.staged.remove(id) {
+            return Ok(staged.interval);
+        }
+        match self.schedule.get_interval(id) {
+            Some(interval) if self.removed.insert(id.to_string()) => Ok(interval),
+            _ => Err(ScheduleError::TaskNotFound(id.to_string())),
+        }
+    }
+
+    /// Stages moving `id` to `interval`, keeping its lane and priority. The
+    /// entry's current interval does not get in its way.
+    ///
+    /// # Errors
+    ///
+    /// - [`ScheduleError::TaskNotFound`] if `id` is not in the schedule
+    ///   with the staged changes applied
+    /// - those of [`Schedule::add`] for the new interval; the entry then
+    ///   stays where it was
+    pub fn move_to(&mut self, id: &str, interval: Interval<U>) -> Result<(), ScheduleError> {
+        let current = match self.staged.get(id) {
+            Some(staged) => *staged,
+            None if !self.removed.contains(id) => Staged {
+                interval: self
+                    .schedule
+                    .get_interval(id)
+                    .ok_or_else(|| ScheduleError::TaskNotFound(id.to_string()))?,
+                background: self.schedule.is_background(id),
+                priority: self.schedule.priority_of(id),
+            },
+            None => return Err(ScheduleError::TaskNotFound(id.to_string())),
+        };
+        let moved = Staged {
+            interval,
+            ..current
+        };
+
+        let was_staged = self.staged.remove(id).is_some();
+        let newly_removed = !was_staged && self.removed.insert(id.to_string());
+        if let Err(err) = self.check(id, moved) {
+            if was_staged {
+                self.staged.insert(id.to_string(), current);
+            }
+            if newly_removed {
+                self.removed.remove(id);
+            }
+            return Err(err);
+        }
+        self.staged.insert(id.to_string(), moved);
+        Ok(())
+    }
+
+    /// Returns true if `id` is in the schedule with the staged changes
+    /// applied.
+    pub fn contains_task(&self, id: &str) -> bool {
+        self.get_interval(id).is_some()
+    }
+
+    /// The interval of `id` with the staged changes applied.
+    pub fn get_interval(&self, id: &str) -> Option<Interval<U>> {
+        if let Some(staged) = self.staged.get(id) {
+            return Some(staged.interval);
+        }
+        if self.removed.contains(id) {
+            return None;
+        }
+        self.schedule.get_interval(id)
+    }
+
+    /// Returns true if nothing is staged.
+    pub fn is_unchanged(&self) -> bool {
+        self.staged.is_empty() && self.removed.is_empty()
+    }
+
+    /// Applies the staged changes.
+    pub fn commit(self) {
+        for id in &self.removed {
+            self.schedule.remove(id);
+        }
+        let mut staged: Vec<_> = self.staged.into_iter().collect();
+        staged.sort_by(|a, b| {
+            let start = |s: &Staged<U>| s.interval.start().value();
+            start(&a.1).total_cmp(&start(&b.1))
+        });
+        for (id, entry) in staged {
+            let added = if entry.background {
+                self.schedule.add_background(id, entry.interval)
+            } else if let Some(priority) = entry.priority {
+                self.schedule
+                    .add_with_priority(id, entry.interval, priority)
+            } else {
+                self.schedule.add(id, entry.interval)
+            };
+            added.expect("staged changes were checked against the staged state");
+        }
+    }
+
+    /// Discards the staged changes, like dropping the transaction.
+    pub fn rollback(self) {}
+
+    /// Checks that `entry` fits next to the staged state, `id` aside.
+    fn check(&self, id: &str, entry: Staged<U>) -> Result<(), ScheduleError> {
+        let interval = entry.interval;
+        if interval.start().value().is_nan() || interval.end().value().is_nan() {
+            return Err(ScheduleError::NaNTime);
+        }
+        if entry.background || interval.is_empty() {
+            return Ok(());
+        }
+        let clash = |existing: &str| ScheduleError::OverlapsExisting {
+            new_id: id.to_string(),
+            existing_id: existing.to_string(),
+        };
+        if let Some((existing, _)) = self
+            .schedule
+            .conflicts_ref(interval)?
+            .find(|(other, _)| !self.removed.contains(*other) && !self.staged.contains_key(*other))
+        {
+            return Err(clash(existing));
+        }
+        let gap = self.schedule.min_gap();
+        let padded = Interval::new(interval.start() - gap, interval.end() + gap);
+        match self
+            .staged
+            .iter()
+            .filter(|(other, s)| other.as_str() != id && !s.background)
+            .find(|(_, s)| s.interval.overlaps(&padded))
+        {
+            Some((existing, _)) => Err(clash(existing)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{iv, q};
+    use qtty::Second;
+
+    #[test]
+    fn edits_apply_together_or_not_at_all() {
+        let mut schedule = Schedule::<Second>::new().with_min_gap(q(1.0));
+        schedule.add("a", iv(0.0, 10.0)).unwrap();
+        schedule.add_with_priority("b", iv(20.0, 30.0), 5).unwrap();
+        schedule.add_background("log", iv(0.0, 100.0)).unwrap();
+
+        // Swap a and b, which no single move can do.
+        let mut txn = schedule.begin();
+        assert!(matches!(
+            txn.move_to("a", iv(25.0, 35.0)),
+            Err(ScheduleError::OverlapsExisting { .. })
+        ));
+        assert_eq!(txn.get_interval("a"), Some(iv(0.0, 10.0)));
+        assert_eq!(txn.remove("a"), Ok(iv(0.0, 10.0)));
+        txn.move_to("b", iv(0.0, 10.0)).unwrap();
+        // The gap after b's new slot applies.
+        assert!(matches!(
+            txn.add("a", iv(10.5, 20.0)),
+            Err(ScheduleError::OverlapsExisting { .. })
+        ));
+        txn.add("a", iv(20.0, 30.0)).unwrap();
+        txn.add("c", iv(40.0, 50.0)).unwrap();
+        assert!(matches!(
+            txn.add("d", iv(45.0, 55.0)),
+            Err(ScheduleError::OverlapsExisting { .. })
+        ));
+        assert!(matches!(
+            txn.add("c", iv(60.0, 70.0)),
+            Err(ScheduleError::DuplicateTaskId(_))
+        ));
+        txn.move_to("log", iv(50.0, 150.0)).unwrap();
+        assert_eq!(txn.remove("c"), Ok(iv(40.0, 50.0)));
+        assert!(matches!(
+            txn.remove("c"),
+            Err(ScheduleError::TaskNotFound(_))
+        ));
+        txn.commit();
+
+        assert_eq!(schedule.get_interval("a"), Some(iv(20.0, 30.0)));
+        assert_eq!(schedule.get_interval("b"), Some(iv(0.0, 10.0)));
+        assert_eq!(schedule.priority_of("b"), Some(5));
+        assert_eq!(schedule.get_interval("log"), Some(iv(50.0, 150.0)));
+        assert!(schedule.is_background("log"));
+        assert!(!schedule.contains_task("c"));
+
+        let mut txn = schedule.begin();
+        assert_eq!(txn.remove("a"), Ok(iv(20.0, 30.0)));
+        txn.add("x", iv(15.0, 35.0)).unwrap();
+        assert!(!txn.is_unchanged());
+        txn.rollback();
+        assert_eq!(schedule.get_interval("a"), Some(iv(20.0, 30.0)));
+        assert!(!schedule.contains_task("x"));
+    }
+}
@@ -3,11 +3,32 @@ use super::task::Task;
 use crate::Id;
 use petgraph::algo::{has_path_connecting, toposort};
 use petgraph::stable_graph::StableGraph;
+use petgraph::visit::EdgeRef;
 use petgraph::{Directed, Direction, EdgeType};
 use qtty::{Quantity, Second, Unit};
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, VecDeque};
 use std::fmt::Display;
 
+/// String key-value labels attached to a task.
+pub type Tags = BTreeMap<String, String>;
+
+/// Labels of untagged tasks.
+static NO_TAGS: Tags = BTreeMap::new();
+
+/// What [`SchedulingBlock::remove_task_cascade`] does with the tasks that
+/// depend on the removed one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RemovalPolicy {
+    /// Removes every task depending on it, directly or transitively.
+    RemoveDependents,
+    /// Makes each of its successors depend on each of its predecessors, so
+    /// the ordering it implied survives.
+    Reconnect,
+    /// Refuses to remove a task other tasks depend on.
+    Fail,
+}
+
 /// DAG-based task scheduler with dependency tracking.
 ///
 /// # Invariants
@@ -36,6 +57,8 @@ where
     id_by_node: HashMap<petgraph::graph::NodeIndex, Id>,
     /// Maps ID → node index for reverse lookup.
     node_by_id: HashMap<Id, petgraph::graph::NodeIndex>,
+    /// Labels per task ID; untagged tasks have no entry.
+    tags: HashMap<Id, Tags>,
     _phantom: std::marker::PhantomData<U>,
 }
 
@@ -50,6 +73,7 @@ where
             graph: StableGraph::default(),
             id_by_node: HashMap::new(),
             node_by_id: HashMap::new(),
+            tags: HashMap::new(),
             _phantom: std::marker::PhantomData,
         }
     }
@@ -66,6 +90,7 @@ where
             graph: StableGraph::default(),
             id_by_node: HashMap::new(),
             node_by_id: HashMap::new(),
+            tags: HashMap::new(),
             _phantom: std::marker::PhantomData,
         }
     }
@@ -157,6 +182,119 @@ where
         Ok(())
     }
 
+    /// Adds a batch of dependency edges `(from, to, dep)` given by task ID.
+    ///
+    /// The batch is all-or-nothing: if any edge fails, the edges already
+    /// inserted from this batch are removed again and the block is left as it
+    /// was. Unlike [`add_dependency`](Self::add_dependency), a rejected edge
+    /// is reported with the cycle it would close, so the culprit can be found
+    /// among thousands of imported dependencies.
+    ///
+    /// Returns the number of edges added.
+    ///
+    /// # Errors
+    ///
+    /// - `UnknownId` if an edge refers to a task not in this block
+    /// - `DependencyCycle` with the cycle path `[from, to, …, from]` if an
+    ///   edge would create a cycle
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use qtty::{Quantity, Second};
+    /// # use virolai::constraints::IntervalConstraint;
+    /// # use virolai::scheduling_block::{SchedulingBlock, SchedulingError, Task};
+    /// # #[derive(Debug)]
+    /// # struct Obs;
+    /// # impl Task<Second> for Obs {
+    /// #     type SizeUnit = Second;
+    /// #     type ConstraintLeaf = IntervalConstraint<Second>;
+    /// #     fn name(&self) -> &str { "obs" }
+    /// #     fn size(&self) -> Quantity<Second> { Quantity::new(60.0) }
+    /// # }
+    /// # let mut block: SchedulingBlock<Obs> = SchedulingBlock::new();
+    /// # block.add_task_with_id(Obs, Some("a".into()))?;
+    /// # block.add_task_with_id(Obs, Some("b".into()))?;
+    /// let edges = vec![("a".into(), "b".into(), ()), ("b".into(), "a".into(), ())];
+    /// match block.add_dependencies(edges) {
+    ///     Err(SchedulingError::DependencyCycle(path)) => eprintln!("cycle: {}", path.join(" -> ")),
+    ///     other => { other?; }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_dependencies(
+        &mut self,
+        edges: impl IntoIterator<Item = (Id, Id, D)>,
+    ) -> Result<usize, SchedulingError> {
+        let mut added = Vec::new();
+        for (from_id, to_id, dep) in edges {
+            match self.try_add_dependency(&from_id, &to_id, dep) {
+                Ok(edge) => added.push(edge),
+                Err(err) => {
+                    for edge in added {
+                        self.graph.remove_edge(edge);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        Ok(added.len())
+    }
+
+    /// Adds one edge of [`add_dependencies`](Self::add_dependencies).
+    fn try_add_dependency(
+        &mut self,
+        from_id: &str,
+        to_id: &str,
+        dep: D,
+    ) -> Result<petgraph::graph::EdgeIndex, SchedulingError> {
+        let lookup = |id: &str| {
+            self.node_of(id)
+                .ok_or_else(|| SchedulingError::UnknownId(id.to_string()))
+        };
+        let from = lookup(from_id)?;
+        let to = lookup(to_id)?;
+
+        if let Some(path) = self.path_between(to, from) {
+            let cycle = std::iter::once(from)
+                .chain(path)
+                .map(|n| self.id_by_node[&n].clone())
+                .collect();
+            return Err(SchedulingError::DependencyCycle(cycle));
+        }
+        Ok(self.graph.add_edge(from, to, dep))
+    }
+
+    /// Shortest path `start → … → goal` (both included), found by BFS.
+    fn path_between(
+        &self,
+        start: petgraph::graph::NodeIndex,
+        goal: petgraph::graph::NodeIndex,
+    ) -> Option<Vec<petgraph::graph::NodeIndex>> {
+        let mut parent = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        parent.insert(start, start);
+        while let Some(node) = queue.pop_front() {
+            if node == goal {
+                let mut path = vec![goal];
+                let mut current = goal;
+                while current != start {
+                    current = parent[&current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in self.graph.neighbors(node) {
+                if let std::collections::hash_map::Entry::Vacant(slot) = parent.entry(next) {
+                    slot.insert(node);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
     /// Returns task nodes in topological order.
     ///
     /// # Errors
@@ -167,6 +305,73 @@ where
         toposort(&self.graph, None).map_err(|_| SchedulingError::GraphContainsCycle)
     }
 
+    /// Returns task nodes in a deterministic topological order.
+    ///
+    /// Kahn's algorithm: among the tasks whose predecessors are all placed,
+    /// the one with the smallest `key` comes next, ties broken by task ID.
+    /// Unlike [`topo_order`](Self::topo_order), the result depends only on
+    /// the tasks and their dependencies, so list schedulers and exporters
+    /// produce the same output on every run. Use [`std::cmp::Reverse`] to
+    /// put the largest key first, e.g. the highest priority.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GraphContainsCycle` if the graph has a cycle.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use qtty::Second;
+    /// # use virolai::scheduling_block::Task;
+    /// # use virolai::solution_space::{Horizon, Interval};
+    /// # use virolai::synthetic::{generate, SyntheticConfig};
+    /// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+    /// # let block = generate(&SyntheticConfig::new(20, horizon)).block;
+    /// let order = block.topo_order_by(|task| std::cmp::Reverse(task.priority()))?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn topo_order_by<K, F>(
+        &self,
+        mut key: F,
+    ) -> Result<Vec<petgraph::graph::NodeIndex>, SchedulingError>
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        let mut pending: HashMap<petgraph::graph::NodeIndex, usize> = HashMap::new();
+        let mut ready = BinaryHeap::new();
+        let mut entry = |node| Reverse((key(&self.graph[node]), &self.id_by_node[&node], node));
+        for node in self.graph.node_indices() {
+            let count = self
+                .graph
+                .neighbors_directed(node, Direction::Incoming)
+                .count();
+            if count == 0 {
+                ready.push(entry(node));
+            } else {
+                pending.insert(node, count);
+            }
+        }
+
+        let mut order = Vec::with_capacity(self.graph.node_count());
+        while let Some(Reverse((_, _, node))) = ready.pop() {
+            order.push(node);
+            for next in self.graph.neighbors_directed(node, Direction::Outgoing) {
+                let count = pending.get_mut(&next).expect("successor has predecessors");
+                *count -= 1;
+                if *count == 0 {
+                    pending.remove(&next);
+                    ready.push(entry(next));
+                }
+            }
+        }
+        if order.len() == self.graph.node_count() {
+            Ok(order)
+        } else {
+            Err(SchedulingError::GraphContainsCycle)
+        }
+    }
+
     /// Returns tasks with no predecessors (entry points).
     pub fn roots(&self) -> Vec<petgraph::graph::NodeIndex> {
         self.graph
@@ -213,9 +418,52 @@ where
     pub fn remove_task(&mut self, id: &str) -> Option<T> {
         let node = self.node_by_id.remove(id)?;
         self.id_by_node.remove(&node);
+        self.tags.remove(id);
         self.graph.remove_node(node)
     }
 
+    /// Sets the label `key` of task `id` to `value`, returning the previous
+    /// value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SchedulingError::UnknownId`] if `id` is not registered.
+    pub fn tag(
+        &mut self,
+        id: &str,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<Option<String>, SchedulingError> {
+        if !self.node_by_id.contains_key(id) {
+            return Err(SchedulingError::UnknownId(id.to_string()));
+        }
+        Ok(self
+            .tags
+            .entry(id.to_string())
+            .or_default()
+            .insert(key.into(), value.into()))
+    }
+
+    /// Removes the label `key` from task `id`, returning its value.
+    pub fn untag(&mut self, id: &str, key: &str) -> Option<String> {
+        let tags = self.tags.get_mut(id)?;
+        let value = tags.remove(key);
+        if tags.is_empty() {
+            self.tags.remove(id);
+        }
+        value
+    }
+
+    /// Returns the labels of task `id` (empty for untagged or unknown tasks).
+    pub fn tags_of(&self, id: &str) -> &Tags {
+        self.tags.get(id).unwrap_or(&NO_TAGS)
+    }
+
+    /// Returns the value of label `key` on task `id`.
+    pub fn tag_value(&self, id: &str, key: &str) -> Option<&str> {
+        self.tags.get(id)?.get(key).map(String::as_str)
+    }
+
     pub fn get_task(&self, node: petgraph::graph::NodeIndex) -> Option<&T> {
         self.graph.node_weight(node)
     }
@@ -240,6 +488,92 @@ where
     }
 }
 
+impl<T, U, D, E> SchedulingBlock<T, U, D, E>
+where
+    T: Task<U>,
+    U: Unit,
+    D: Clone,
+    E: EdgeType,
+{
+    /// Removes task `id`, handling its dependents according to `policy`.
+    ///
+    /// Unlike [`remove_task`](Self::remove_task), which leaves dependents
+    /// without the prerequisite they were waiting for, this either removes
+    /// them too, rewires them to the task's predecessors or fails. With
+    /// [`RemovalPolicy::Reconnect`], the edge `predecessor → successor`
+    /// carries the data of the removed `id → successor` edge, and is not
+    /// added if it already exists.
+    ///
+    /// Returns the removed tasks with their IDs, `id` first, then its
+    /// dependents in breadth-first order.
+    ///
+    /// # Errors
+    ///
+    /// - `UnknownId` if `id` is not registered
+    /// - `HasDependents` with the IDs of its direct successors, sorted, if
+    ///   `policy` is [`RemovalPolicy::Fail`] and any task depends on it
+    pub fn remove_task_cascade(
+        &mut self,
+        id: &str,
+        policy: RemovalPolicy,
+    ) -> Result<Vec<(Id, T)>, SchedulingError> {
+        let node = self
+            .node_of(id)
+            .ok_or_else(|| SchedulingError::UnknownId(id.to_string()))?;
+        let mut doomed = vec![node];
+        match policy {
+            RemovalPolicy::Fail => {
+                let mut dependents: Vec<String> = self
+                    .successors(node)
+                    .into_iter()
+                    .map(|n| self.id_by_node[&n].clone())
+                    .collect();
+                if !dependents.is_empty() {
+                    dependents.sort_unstable();
+                    dependents.dedup();
+                    return Err(SchedulingError::HasDependents {
+                        id: id.to_string(),
+                        dependents,
+                    });
+                }
+            }
+            RemovalPolicy::Reconnect => {
+                let outgoing: Vec<_> = self
+                    .graph
+                    .edges_directed(node, Direction::Outgoing)
+                    .map(|edge| (edge.target(), edge.weight().clone()))
+                    .collect();
+                for pred in self.predecessors(node) {
+                    for (succ, dep) in &outgoing {
+                        if self.graph.find_edge(pred, *succ).is_none() {
+                            self.graph.add_edge(pred, *succ, dep.clone());
+                        }
+                    }
+                }
+            }
+            RemovalPolicy::RemoveDependents => {
+                let mut queue = VecDeque::from([node]);
+                while let Some(current) = queue.pop_front() {
+                    for next in self.successors(current) {
+                        if !doomed.contains(&next) {
+                            doomed.push(next);
+                            queue.push_back(next);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(doomed
+            .into_iter()
+            .filter_map(|n| {
+                let id = self.id_by_node.get(&n)?.clone();
+                self.remove_task(&id).map(|task| (id, task))
+            })
+            .collect())
+    }
+}
+
 // Dynamic constraint evaluation methods.
 //
 // Available only when edge data `D` implements `DynamicConstraint<U>`.
@@ -307,6 +641,10 @@ where
     /// Returns total duration (in axis units) and the sequence of nodes
     /// on the critical path.
     ///
+    /// [Milestones](Task::is_milestone) take part like any task: a chain
+    /// opened or closed by milestones keeps them on the path even though
+    /// they add no duration.
+    ///
     /// # Errors
     ///
     /// Returns `EmptyGraph` if there are no tasks.
@@ -334,14 +672,18 @@ where
                 let new_start = node_start + task_duration;
                 let succ_start = earliest_start.entry(successor).or_insert(0.0);
 
-                if new_start > *succ_start {
+                // A tie with no predecessor yet still links, so a leading
+                // zero-duration milestone stays on the path.
+                if new_start > *succ_start
+                    || (new_start == *succ_start && predecessor[&successor].is_none())
+                {
                     *succ_start = new_start;
                     predecessor.insert(successor, Some(node));
                 }
             }
         }
 
-        let mut max_finish = 0.0_f64;
+        let mut max_finish = f64::NEG_INFINITY;
         let mut end_node = None;
 
         for node in self.graph.node_indices() {
@@ -354,6 +696,15 @@ where
             }
         }
 
+        // Extend the path through trailing milestones that finish with it.
+        while let Some(next) = end_node.and_then(|last| {
+            self.successors(last)
+                .into_iter()
+                .find(|&s| predecessor[&s] == Some(last) && self.graph[s].is_milestone())
+        }) {
+            end_node = Some(next);
+        }
+
         let mut path = Vec::new();
         let mut current = end_node;
 
@@ -401,7 +752,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::TestTask;
+    use crate::test_utils::{block_of, TestTask};
 
     // ── Construction ──────────────────────────────────────────────────
 
@@ -675,6 +1026,29 @@ mod tests {
         assert!(pos_b < pos_c);
     }
 
+    #[test]
+    fn topo_order_by_breaks_ties_by_key_then_id() {
+        // low → high; c and b independent, b and c share a priority.
+        let mut block = block_of(
+            [("low", 1), ("c", 5), ("high", 9), ("b", 5)]
+                .map(|(id, priority)| TestTask::new(id, 10.0).with_priority(priority)),
+        );
+        block
+            .add_dependencies([("low".into(), "high".into(), ())])
+            .unwrap();
+
+        let ids = |order: Vec<petgraph::graph::NodeIndex>| -> Vec<String> {
+            order
+                .into_iter()
+                .map(|n| block.id_of(n).unwrap().to_string())
+                .collect()
+        };
+        let order = block.topo_order_by(|t| Reverse(t.priority())).unwrap();
+        assert_eq!(ids(order), ["b", "c", "low", "high"]);
+        let order = block.topo_order_by(|t| t.priority()).unwrap();
+        assert_eq!(ids(order), ["low", "b", "c", "high"]);
+    }
+
     #[test]
     fn topo_order_empty_block() {
         let block: SchedulingBlock<TestTask> = SchedulingBlock::new();
@@ -843,6 +1217,53 @@ mod tests {
         assert_eq!(path, vec![na, nb, nd]);
     }
 
+    #[test]
+    fn critical_path_keeps_milestones() {
+        // kickoff -> A(10) -> done, all zero-duration but A.
+        let mut block = block_of([
+            TestTask::new("kickoff", 0.0),
+            TestTask::new("A", 10.0),
+            TestTask::new("done", 0.0),
+        ]);
+        let [nk, na, nd] = ["kickoff", "A", "done"].map(|id| block.node_of(id).unwrap());
+        block.add_dependency(nk, na, ()).unwrap();
+        block.add_dependency(na, nd, ()).unwrap();
+
+        let (cost, path) = block.critical_path().unwrap();
+        assert!((cost - 10.0).abs() < 1e-9);
+        assert_eq!(path, vec![nk, na, nd]);
+
+        let mut lone: SchedulingBlock<TestTask> = SchedulingBlock::new();
+        let id = lone.add_task(TestTask::new("gate", 0.0));
+        let node = lone.node_of(&id).unwrap();
+        assert_eq!(lone.critical_path(), Ok((0.0, vec![node])));
+    }
+
+    // ── Tags ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn tags_are_set_read_and_removed() {
+        let mut block = block_of([TestTask::new("a", 10.0)]);
+
+        assert_eq!(block.tag("a", "kind", "science"), Ok(None));
+        assert_eq!(
+            block.tag("a", "kind", "calibration"),
+            Ok(Some("science".to_string()))
+        );
+        assert_eq!(block.tag_value("a", "kind"), Some("calibration"));
+        assert_eq!(
+            block.tag("zzz", "kind", "x"),
+            Err(SchedulingError::UnknownId("zzz".into()))
+        );
+
+        assert_eq!(block.untag("a", "kind"), Some("calibration".to_string()));
+        assert!(block.tags_of("a").is_empty());
+
+        block.tag("a", "kind", "science").unwrap();
+        block.remove_task("a");
+        assert!(block.tags_of("a").is_empty());
+    }
+
     // ── Counts ────────────────────────────────────────────────────────
 
     #[test]
@@ -919,6 +1340,62 @@ mod tests {
         assert_eq!(block.dependency_count(), 0);
     }
 
+    #[test]
+    fn remove_task_cascade_follows_policy() {
+        // a → b → c, a → d, x → b.
+        let build = || {
+            let mut block = block_of(["a", "b", "c", "d", "x"].map(|id| TestTask::new(id, 10.0)));
+            let edges = [("a", "b"), ("b", "c"), ("a", "d"), ("x", "b")];
+            block
+                .add_dependencies(edges.map(|(f, t)| (f.into(), t.into(), ())))
+                .unwrap();
+            block
+        };
+        let edge = |block: &SchedulingBlock<TestTask>, from: &str, to: &str| {
+            let (from, to) = (block.node_of(from).unwrap(), block.node_of(to).unwrap());
+            block.graph().find_edge(from, to).is_some()
+        };
+
+        let mut block = build();
+        assert_eq!(
+            block
+                .remove_task_cascade("b", RemovalPolicy::Fail)
+                .unwrap_err(),
+            SchedulingError::HasDependents {
+                id: "b".into(),
+                dependents: vec!["c".into()]
+            }
+        );
+        assert_eq!(block.task_count(), 5);
+        let removed = block.remove_task_cascade("c", RemovalPolicy::Fail).unwrap();
+        assert_eq!(removed[0].0, "c");
+
+        let mut block = build();
+        let removed = block
+            .remove_task_cascade("b", RemovalPolicy::Reconnect)
+            .unwrap();
+        assert_eq!(removed.len(), 1);
+        assert!(edge(&block, "a", "c") && edge(&block, "x", "c"));
+        assert_eq!(block.dependency_count(), 3);
+
+        let mut block = build();
+        let removed = block
+            .remove_task_cascade("a", RemovalPolicy::RemoveDependents)
+            .unwrap();
+        let mut ids: Vec<_> = removed.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids[0], "a");
+        ids.sort_unstable();
+        assert_eq!(ids, ["a", "b", "c", "d"]);
+        assert_eq!(block.tasks().map(|(id, _)| id).collect::<Vec<_>>(), ["x"]);
+
+        assert_eq!(
+            block
+                .remove_task_cascade("a", RemovalPolicy::Fail)
+                .unwrap_err(),
+            SchedulingError::UnknownId("a".into())
+        );
+    }
+
     #[test]
     fn critical_path_still_correct_after_removal() {
         // A(10) -> B(20) -> C(30), then remove B; leaves disconnected A(10) and C(30).
@@ -1013,4 +1490,61 @@ mod tests {
         let result = block.add_dependency(nc, na, ());
         assert_eq!(result, Err(SchedulingError::CycleDetected));
     }
+
+    // ── Batch dependencies ────────────────────────────────────────────
+
+    fn block_with(ids: &[&str]) -> SchedulingBlock<TestTask> {
+        block_of(ids.iter().map(|id| TestTask::new(id, 10.0)))
+    }
+
+    fn edge(from: &str, to: &str) -> (Id, Id, ()) {
+        (from.to_string(), to.to_string(), ())
+    }
+
+    #[test]
+    fn add_dependencies_inserts_batch() {
+        let mut block = block_with(&["a", "b", "c"]);
+        let added = block.add_dependencies(vec![edge("a", "b"), edge("b", "c"), edge("a", "c")]);
+        assert_eq!(added, Ok(3));
+        assert_eq!(block.dependency_count(), 3);
+    }
+
+    #[test]
+    fn add_dependencies_reports_cycle_path_and_rolls_back() {
+        let mut block = block_with(&["a", "b", "c", "d"]);
+        block.add_dependencies(vec![edge("a", "b")]).unwrap();
+
+        let result = block.add_dependencies(vec![edge("b", "c"), edge("c", "d"), edge("d", "a")]);
+        assert_eq!(
+            result,
+            Err(SchedulingError::DependencyCycle(vec![
+                "d".into(),
+                "a".into(),
+                "b".into(),
+                "c".into(),
+                "d".into()
+            ]))
+        );
+        // Only the edge from the first batch remains.
+        assert_eq!(block.dependency_count(), 1);
+        assert!(block.topo_order().is_ok());
+
+        assert_eq!(
+            block.add_dependencies(vec![edge("c", "c")]),
+            Err(SchedulingError::DependencyCycle(vec![
+                "c".into(),
+                "c".into()
+            ]))
+        );
+    }
+
+    #[test]
+    fn add_dependencies_unknown_id() {
+        let mut block = block_with(&["a"]);
+        assert_eq!(
+            block.add_dependencies(vec![edge("a", "zzz")]),
+            Err(SchedulingError::UnknownId("zzz".into()))
+        );
+        assert_eq!(block.dependency_count(), 0);
+    }
 }
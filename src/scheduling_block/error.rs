@@ -17,6 +17,17 @@ pub enum SchedulingError {
 
     #[error("Task ID already exists: {0}")]
     DuplicateId(String),
+
+    #[error("Unknown task ID: {0}")]
+    UnknownId(String),
+
+    /// The offending cycle as task IDs, starting and ending with the source
+    /// of the rejected dependency.
+    #[error("Dependencies would create a cycle: {}", .0.join(" → "))]
+    DependencyCycle(Vec<String>),
+
+    #[error("Cannot remove task {id}: {} depend on it", .dependents.join(", "))]
+    HasDependents { id: String, dependents: Vec<String> },
 }
 
 #[cfg(test)]
@@ -63,6 +74,29 @@ mod tests {
         assert_eq!(e.to_string(), "Task ID already exists: my-task");
     }
 
+    #[test]
+    fn dependency_cycle_display() {
+        let e =
+            SchedulingError::DependencyCycle(vec!["c".into(), "a".into(), "b".into(), "c".into()]);
+        assert_eq!(
+            e.to_string(),
+            "Dependencies would create a cycle: c → a → b → c"
+        );
+        assert_eq!(
+            SchedulingError::UnknownId("x".into()).to_string(),
+            "Unknown task ID: x"
+        );
+    }
+
+    #[test]
+    fn has_dependents_display() {
+        let e = SchedulingError::HasDependents {
+            id: "a".into(),
+            dependents: vec!["b".into(), "c".into()],
+        };
+        assert_eq!(e.to_string(), "Cannot remove task a: b, c depend on it");
+    }
+
     #[test]
     fn error_equality() {
         assert_eq!(
@@ -1,11 +1,17 @@
 pub mod error;
+pub mod placement;
 pub mod spatial;
 pub mod task;
 
 mod block;
-pub use block::SchedulingBlock;
+mod stats;
+mod view;
+pub use block::{RemovalPolicy, SchedulingBlock, Tags};
+pub use stats::BlockStats;
+pub use view::BlockView;
 
 pub use error::SchedulingError;
+pub use placement::{PlacementPreference, PlacementRule};
 pub use spatial::SpatialTask;
 pub use task::Task;
 
@@ -0,0 +1,158 @@
+//! Where inside a feasible window a task is anchored.
+
+use crate::solution_space::Interval;
+use qtty::{Quantity, Unit};
+
+/// Custom placement rule: `(windows, size) -> start`.
+pub type PlacementRule<A> = fn(&[Interval<A>], Quantity<A>) -> Option<Quantity<A>>;
+
+/// Placement preference of a task inside its feasible windows.
+///
+/// Schedulers first decide *which* windows a task may use; the preference
+/// then picks the start time within them. Windows passed to
+/// [`choose_start`](Self::choose_start) are expected to be free of other
+/// tasks, so anchoring in the middle of one maximizes the distance to both
+/// neighbors.
+///
+/// # Examples
+///
+/// ```
+/// use qtty::{Quantity, Second};
+/// use virolai::scheduling_block::PlacementPreference;
+/// use virolai::solution_space::Interval;
+///
+/// let windows = [Interval::<Second>::from_f64(0.0, 100.0)];
+/// let size = Quantity::new(10.0);
+///
+/// let transit = PlacementPreference::CenteredOn(Quantity::new(60.0));
+/// assert_eq!(transit.choose_start(&windows, size), Some(Quantity::new(55.0)));
+/// assert_eq!(
+///     PlacementPreference::Latest.choose_start(&windows, size),
+///     Some(Quantity::new(90.0))
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub enum PlacementPreference<A: Unit> {
+    /// Start as early as possible (left-aligned in the first window).
+    #[default]
+    Earliest,
+    /// End as late as possible (right-aligned in the last window).
+    Latest,
+    /// Center the task on the given time, or as close to it as the windows allow.
+    CenteredOn(Quantity<A>),
+    /// Center the task in the widest window, maximizing distance to neighbors.
+    MaxNeighborDistance,
+    /// Custom rule: receives the windows and task size, returns the start.
+    ///
+    /// A returned start that does not fit inside one of the windows is
+    /// treated as "no placement".
+    Custom(PlacementRule<A>),
+}
+
+impl<A: Unit> PlacementPreference<A> {
+    /// Picks a start time for a task of `size` inside one of `windows`.
+    ///
+    /// `windows` must be sorted by start and non-overlapping. Windows shorter
+    /// than `size` are ignored. Returns `None` if the task fits nowhere.
+    pub fn choose_start(&self, windows: &[Interval<A>], size: Quantity<A>) -> Option<Quantity<A>> {
+        let size_value = size.value();
+        let mut fitting = windows
+            .iter()
+            .filter(|w| w.duration().value() >= size_value);
+
+        match self {
+            Self::Earliest => fitting.next().map(|w| w.start()),
+            Self::Latest => fitting.next_back().map(|w| w.end() - size),
+            Self::CenteredOn(target) => {
+                let desired = target.value() - size_value / 2.0;
+                fitting
+                    .map(|w| {
+                        let start = desired.clamp(w.start().value(), w.end().value() - size_value);
+                        (start, (start - desired).abs())
+                    })
+                    // First window wins ties
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(start, _)| Quantity::new(start))
+            }
+            Self::MaxNeighborDistance => fitting
+                // Stable: the earliest of equally wide windows wins
+                .fold(None::<&Interval<A>>, |best, w| match best {
+                    Some(b) if b.duration().value() >= w.duration().value() => Some(b),
+                    _ => Some(w),
+                })
+                .map(|w| {
+                    let slack = w.duration().value() - size_value;
+                    Quantity::new(w.start().value() + slack / 2.0)
+                }),
+            Self::Custom(rule) => {
+                let start = rule(windows, size)?;
+                let end = start.value() + size_value;
+                windows
+                    .iter()
+                    .any(|w| start.value() >= w.start().value() && end <= w.end().value())
+                    .then_some(start)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{iv, q};
+    use qtty::Second;
+
+    fn windows() -> Vec<Interval<Second>> {
+        vec![iv(0.0, 20.0), iv(40.0, 100.0), iv(120.0, 125.0)]
+    }
+
+    #[test]
+    fn earliest_left_aligns_first_fitting_window() {
+        let start = PlacementPreference::Earliest.choose_start(&windows(), q(10.0));
+        assert_eq!(start, Some(q(0.0)));
+    }
+
+    #[test]
+    fn latest_right_aligns_last_fitting_window() {
+        // [120, 125) is too short
+        let start = PlacementPreference::Latest.choose_start(&windows(), q(10.0));
+        assert_eq!(start, Some(q(90.0)));
+    }
+
+    #[test]
+    fn centered_on_picks_closest_window() {
+        let pref = PlacementPreference::CenteredOn(q(70.0));
+        assert_eq!(pref.choose_start(&windows(), q(10.0)), Some(q(65.0)));
+
+        // Target in a gap: clamp to the nearer window edge
+        let pref = PlacementPreference::CenteredOn(q(28.0));
+        assert_eq!(pref.choose_start(&windows(), q(10.0)), Some(q(10.0)));
+    }
+
+    #[test]
+    fn max_neighbor_distance_centers_in_widest_window() {
+        let start = PlacementPreference::MaxNeighborDistance.choose_start(&windows(), q(10.0));
+        assert_eq!(start, Some(q(65.0)));
+    }
+
+    #[test]
+    fn custom_rule_is_validated() {
+        let fixed = PlacementPreference::Custom(|_, _| Some(Quantity::new(45.0)));
+        assert_eq!(fixed.choose_start(&windows(), q(10.0)), Some(q(45.0)));
+
+        let outside = PlacementPreference::Custom(|_, _| Some(Quantity::new(15.0)));
+        assert_eq!(outside.choose_start(&windows(), q(10.0)), None);
+    }
+
+    #[test]
+    fn no_fitting_window_returns_none() {
+        for pref in [
+            PlacementPreference::Earliest,
+            PlacementPreference::Latest,
+            PlacementPreference::CenteredOn(q(50.0)),
+            PlacementPreference::MaxNeighborDistance,
+        ] {
+            assert_eq!(pref.choose_start(&windows(), q(200.0)), None);
+        }
+    }
+}
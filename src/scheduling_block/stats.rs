@@ -0,0 +1,185 @@
+//! Graph metrics and health report for a [`SchedulingBlock`].
+//!
+//! [`SchedulingBlock::stats`] summarizes the shape of the dependency graph —
+//! how deep and wide it is, how it splits into components, and which tasks
+//! are not connected to anything — so that imported problems can be
+//! sanity-checked before a scheduling run.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use petgraph::graph::NodeIndex;
+use petgraph::{Direction, EdgeType};
+use qtty::Unit;
+
+use super::block::SchedulingBlock;
+use super::task::Task;
+use crate::Id;
+
+/// Structural summary of a block's dependency graph.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockStats {
+    /// Number of tasks.
+    pub task_count: usize,
+    /// Number of dependency edges.
+    pub dependency_count: usize,
+    /// Number of tasks on the longest dependency chain, or `None` if the
+    /// graph has no topological order.
+    pub depth: Option<usize>,
+    /// Largest number of tasks at the same depth, or `None` if the graph has
+    /// no topological order.
+    ///
+    /// Tasks at the same depth never depend on each other, so this is a lower
+    /// bound on the largest set of tasks that could run in parallel (the
+    /// maximum antichain).
+    pub width: Option<usize>,
+    /// Number of weakly connected components.
+    pub components: usize,
+    /// Number of tasks per in-degree.
+    pub in_degree: BTreeMap<usize, usize>,
+    /// Number of tasks per out-degree.
+    pub out_degree: BTreeMap<usize, usize>,
+    /// IDs of tasks with no dependencies in either direction, sorted.
+    pub isolated: Vec<Id>,
+}
+
+impl BlockStats {
+    /// Highest in-degree of any task (0 for an empty block).
+    pub fn max_in_degree(&self) -> usize {
+        self.in_degree.keys().next_back().copied().unwrap_or(0)
+    }
+
+    /// Highest out-degree of any task (0 for an empty block).
+    pub fn max_out_degree(&self) -> usize {
+        self.out_degree.keys().next_back().copied().unwrap_or(0)
+    }
+}
+
+impl<T, U, D, E> SchedulingBlock<T, U, D, E>
+where
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    /// Computes graph metrics for validation before scheduling.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use qtty::Second;
+    /// # use virolai::solution_space::{Horizon, Interval};
+    /// # use virolai::synthetic::{generate, SyntheticConfig};
+    /// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+    /// # let block = generate(&SyntheticConfig::new(20, horizon)).block;
+    /// let stats = block.stats();
+    /// if !stats.isolated.is_empty() {
+    ///     eprintln!("{} tasks have no dependencies", stats.isolated.len());
+    /// }
+    /// println!("depth {:?}, width {:?}", stats.depth, stats.width);
+    /// ```
+    pub fn stats(&self) -> BlockStats {
+        let graph = self.graph();
+        let mut stats = BlockStats {
+            task_count: graph.node_count(),
+            dependency_count: graph.edge_count(),
+            ..BlockStats::default()
+        };
+
+        for node in graph.node_indices() {
+            let incoming = graph.neighbors_directed(node, Direction::Incoming).count();
+            let outgoing = graph.neighbors_directed(node, Direction::Outgoing).count();
+            *stats.in_degree.entry(incoming).or_default() += 1;
+            *stats.out_degree.entry(outgoing).or_default() += 1;
+            if graph.neighbors_undirected(node).next().is_none() {
+                if let Some(id) = self.id_of(node) {
+                    stats.isolated.push(id.to_string());
+                }
+            }
+        }
+        stats.isolated.sort();
+
+        if let Ok(order) = self.topo_order() {
+            // Depth of a task = 1 + depth of its deepest predecessor.
+            let mut level: HashMap<NodeIndex, usize> = HashMap::new();
+            for &node in &order {
+                let depth = self
+                    .predecessors(node)
+                    .iter()
+                    .filter_map(|p| level.get(p))
+                    .max()
+                    .map_or(1, |d| d + 1);
+                level.insert(node, depth);
+            }
+            let mut per_level: HashMap<usize, usize> = HashMap::new();
+            for &depth in level.values() {
+                *per_level.entry(depth).or_default() += 1;
+            }
+            stats.depth = Some(per_level.keys().max().copied().unwrap_or(0));
+            stats.width = Some(per_level.values().max().copied().unwrap_or(0));
+        }
+
+        let mut seen = HashSet::new();
+        for start in graph.node_indices() {
+            if !seen.insert(start) {
+                continue;
+            }
+            stats.components += 1;
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                stack.extend(graph.neighbors_undirected(node).filter(|n| seen.insert(*n)));
+            }
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{block_of, TestTask};
+
+    fn block(ids: &[&str], edges: &[(&str, &str)]) -> SchedulingBlock<TestTask> {
+        let mut block = block_of(ids.iter().map(|id| TestTask::new(id, 10.0)));
+        block
+            .add_dependencies(
+                edges
+                    .iter()
+                    .map(|(a, b)| (a.to_string(), b.to_string(), ())),
+            )
+            .unwrap();
+        block
+    }
+
+    #[test]
+    fn stats_of_empty_block() {
+        let stats = block(&[], &[]).stats();
+        assert_eq!(stats.task_count, 0);
+        assert_eq!(stats.depth, Some(0));
+        assert_eq!(stats.width, Some(0));
+        assert_eq!(stats.components, 0);
+        assert_eq!(stats.max_in_degree(), 0);
+    }
+
+    #[test]
+    fn stats_describe_shape() {
+        // a → b → d, a → c → d (diamond), e → f, g isolated
+        let stats = block(
+            &["a", "b", "c", "d", "e", "f", "g"],
+            &[("a", "b"), ("a", "c"), ("b", "d"), ("c", "d"), ("e", "f")],
+        )
+        .stats();
+
+        assert_eq!(stats.task_count, 7);
+        assert_eq!(stats.dependency_count, 5);
+        assert_eq!(stats.depth, Some(3));
+        // Level 1 holds a, e and g.
+        assert_eq!(stats.width, Some(3));
+        assert_eq!(stats.components, 3);
+        assert_eq!(stats.isolated, vec!["g".to_string()]);
+        assert_eq!(stats.in_degree, BTreeMap::from([(0, 3), (1, 3), (2, 1)]));
+        assert_eq!(stats.out_degree, BTreeMap::from([(0, 3), (1, 3), (2, 1)]));
+        assert_eq!(stats.max_in_degree(), 2);
+        assert_eq!(stats.max_out_degree(), 2);
+    }
+}
@@ -1,6 +1,7 @@
 use std::fmt::Debug;
 
-use crate::constraints::{Constraint, ConstraintExpr};
+use super::placement::PlacementPreference;
+use crate::constraints::{CoalitionConstraint, Constraint, ConstraintExpr};
 use crate::units::SameDim;
 use qtty::{Quantity, Unit};
 
@@ -72,6 +73,51 @@ pub trait Task<A: Unit>: Send + Sync + Debug + 'static {
         self.size().to::<A>()
     }
 
+    /// Returns the task's duration if it starts at `start`, in axis units.
+    ///
+    /// Override for tasks whose duration depends on when they run, e.g. an
+    /// exposure that must be longer at high airmass. The EST scheduler uses
+    /// it to check fits, to place the task and to validate entries.
+    ///
+    /// Ending times must not go backwards: `start + duration_at(start)` may
+    /// not decrease as `start` grows (a task started later never finishes
+    /// earlier). The result must be positive, or zero throughout for a
+    /// [milestone](Self::is_milestone).
+    ///
+    /// Default implementation returns [`size_on_axis`](Self::size_on_axis).
+    fn duration_at(&self, _start: Quantity<A>) -> Quantity<A> {
+        self.size_on_axis()
+    }
+
+    /// Returns `true` for a zero-duration task marking an instant, such as
+    /// "calibration done" or "end of night".
+    ///
+    /// A milestone is placed at a point (`[t, t)`), never conflicts with
+    /// other entries (see [`Schedule`](crate::schedule::Schedule#milestones)),
+    /// takes part in dependencies and the critical path like any task, and
+    /// may sit on the closing point of one of its windows. The EST scheduler
+    /// places it at the earliest such point, without waiting for the tasks
+    /// that occupy the resource.
+    ///
+    /// Default implementation checks for a zero [`size_on_axis`](Self::size_on_axis).
+    fn is_milestone(&self) -> bool {
+        self.size_on_axis().value() == 0.0
+    }
+
+    /// Returns true if the task may run alongside others, such as passive
+    /// monitoring or a calibration sharing the telescope with science.
+    ///
+    /// Background tasks go in the schedule's
+    /// [background lane](crate::schedule::Schedule#background-tasks): they
+    /// still need a fitting window, but neither wait for nor block the
+    /// tasks occupying the resource. The EST and greedy schedulers place
+    /// them at their earliest fitting start.
+    ///
+    /// Default implementation returns `false`.
+    fn is_background(&self) -> bool {
+        false
+    }
+
     fn priority(&self) -> i32 {
         0
     }
@@ -79,6 +125,34 @@ pub trait Task<A: Unit>: Send + Sync + Debug + 'static {
         None
     }
 
+    /// Returns the probability that this task succeeds if started at `start`.
+    ///
+    /// Used by expected-value scheduling, where a task's value is its
+    /// `priority()` weighted by this probability. May depend on the start time
+    /// or on any conditions the task knows about. Values are clamped to `[0, 1]`.
+    ///
+    /// Default implementation returns 1.0 (always succeeds).
+    fn success_probability(&self, _start: Quantity<A>) -> f64 {
+        1.0
+    }
+
+    /// Returns how many resources of each type this task needs simultaneously.
+    ///
+    /// Honored by coalition-aware multi-resource algorithms such as
+    /// [`CoalitionScheduler`](crate::algorithms::CoalitionScheduler).
+    ///
+    /// Default implementation returns `None` (any single resource).
+    fn coalition(&self) -> Option<&CoalitionConstraint> {
+        None
+    }
+
+    /// Returns where inside a feasible window this task should be anchored.
+    ///
+    /// Default implementation returns [`PlacementPreference::Earliest`].
+    fn placement_preference(&self) -> PlacementPreference<A> {
+        PlacementPreference::Earliest
+    }
+
     /// Returns the required gap after this task completes.
     ///
     /// This gap is added to the cursor when advancing the scheduling timeline,
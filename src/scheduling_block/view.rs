@@ -0,0 +1,199 @@
+//! Filtered views of a scheduling block.
+//!
+//! A [`BlockView`] restricts a [`SchedulingBlock`] to the tasks matching a
+//! predicate — typically on their [tags](SchedulingBlock::tag) — without
+//! cloning or pruning the block. Schedulers only place tasks that have an
+//! entry in the solution space, so a view is scheduled by handing the
+//! scheduler the whole block together with a solution space restricted to the
+//! view's tasks.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use qtty::Second;
+//! # use virolai::algorithms::ESTScheduler;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let mut block = instance.block;
+//! # let space = instance.solution_space;
+//! # let calib_id = "task-0";
+//! block.tag(&calib_id, "kind", "calibration")?;
+//!
+//! let tonight = block.tagged("kind", "calibration");
+//! let schedule = tonight.schedule(&ESTScheduler::default(), &space, horizon);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::BTreeSet;
+
+use petgraph::EdgeType;
+use qtty::Unit;
+
+use super::block::{SchedulingBlock, Tags};
+use super::task::Task;
+use crate::algorithms::SchedulingAlgorithm;
+use crate::schedule::Schedule;
+use crate::solution_space::{Horizon, SolutionSpace};
+use crate::Id;
+
+/// A block restricted to a subset of its tasks.
+///
+/// Dependencies between tasks inside and outside the view are kept in the
+/// underlying block; the view does not alter them.
+#[derive(Debug, Clone)]
+pub struct BlockView<'a, T, U, D, E>
+where
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    block: &'a SchedulingBlock<T, U, D, E>,
+    ids: BTreeSet<Id>,
+}
+
+impl<'a, T, U, D, E> BlockView<'a, T, U, D, E>
+where
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    /// The underlying block.
+    pub fn block(&self) -> &'a SchedulingBlock<T, U, D, E> {
+        self.block
+    }
+
+    /// Number of tasks in the view.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns true if no task matched.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns true if task `id` is in the view.
+    pub fn contains(&self, id: &str) -> bool {
+        self.ids.contains(id)
+    }
+
+    /// IDs of the tasks in the view, sorted.
+    pub fn ids(&self) -> impl Iterator<Item = &str> + '_ {
+        self.ids.iter().map(String::as_str)
+    }
+
+    /// Returns an iterator over the `(Id, &Task)` pairs in the view.
+    pub fn tasks(&self) -> impl Iterator<Item = (&'a str, &'a T)> + '_ {
+        self.block
+            .tasks()
+            .filter(move |(id, _)| self.ids.contains(*id))
+    }
+
+    /// Copies the entries of the view's tasks out of `solution_space`.
+    pub fn restrict(&self, solution_space: &SolutionSpace<U>) -> SolutionSpace<U> {
+        let mut restricted = SolutionSpace::with_capacity(self.ids.len());
+        for id in &self.ids {
+            if let Some(set) = solution_space.get_intervals(id) {
+                restricted.set_intervals(id.as_str(), set.as_slice().to_vec());
+            }
+        }
+        restricted.take_dirty();
+        restricted
+    }
+
+    /// Schedules only the view's tasks with `algorithm`.
+    pub fn schedule<A>(
+        &self,
+        algorithm: &A,
+        solution_space: &SolutionSpace<U>,
+        horizon: Horizon<U>,
+    ) -> Schedule<U>
+    where
+        A: SchedulingAlgorithm<T, U, D, E> + ?Sized,
+    {
+        let restricted = self.restrict(solution_space);
+        algorithm.schedule(std::slice::from_ref(self.block), &restricted, horizon)
+    }
+}
+
+impl<T, U, D, E> SchedulingBlock<T, U, D, E>
+where
+    T: Task<U>,
+    U: Unit,
+    E: EdgeType,
+{
+    /// Returns a view of the tasks for which `predicate(id, task, tags)`
+    /// holds.
+    pub fn filter_view<P>(&self, mut predicate: P) -> BlockView<'_, T, U, D, E>
+    where
+        P: FnMut(&str, &T, &Tags) -> bool,
+    {
+        let ids = self
+            .tasks()
+            .filter(|(id, task)| predicate(id, task, self.tags_of(id)))
+            .map(|(id, _)| id.to_string())
+            .collect();
+        BlockView { block: self, ids }
+    }
+
+    /// Returns a view of the tasks whose label `key` equals `value`.
+    pub fn tagged(&self, key: &str, value: &str) -> BlockView<'_, T, U, D, E> {
+        self.filter_view(|_, _, tags| tags.get(key).is_some_and(|v| v == value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::GreedyScheduler;
+    use crate::test_utils::{block_and_space, hz, iv, TestTask};
+    use qtty::Second;
+
+    fn tagged_block() -> (SchedulingBlock<TestTask, Second>, SolutionSpace<Second>) {
+        let (mut block, space) = block_and_space(&[
+            ("c1", 10.0, 1, iv(0.0, 100.0)),
+            ("s1", 10.0, 9, iv(0.0, 100.0)),
+            ("c2", 10.0, 2, iv(0.0, 100.0)),
+        ]);
+        for (id, kind) in [
+            ("c1", "calibration"),
+            ("s1", "science"),
+            ("c2", "calibration"),
+        ] {
+            block.tag(id, "kind", kind).unwrap();
+        }
+        (block, space)
+    }
+
+    #[test]
+    fn views_select_by_tag_or_predicate() {
+        let (block, space) = tagged_block();
+        let calib = block.tagged("kind", "calibration");
+        assert_eq!(calib.ids().collect::<Vec<_>>(), ["c1", "c2"]);
+        assert!(calib.contains("c1") && !calib.contains("s1"));
+        assert_eq!(calib.tasks().count(), 2);
+
+        let restricted = calib.restrict(&space);
+        assert_eq!(restricted.count(), 2);
+        assert!(restricted.get_intervals("s1").is_none());
+
+        let urgent = block.filter_view(|_, task, _| task.priority() > 5);
+        assert_eq!(urgent.ids().collect::<Vec<_>>(), ["s1"]);
+        assert!(block.tagged("kind", "none").is_empty());
+    }
+
+    #[test]
+    fn view_schedules_only_its_tasks() {
+        let (block, space) = tagged_block();
+        let schedule = block.tagged("kind", "calibration").schedule(
+            &GreedyScheduler::new(),
+            &space,
+            hz(0.0, 100.0),
+        );
+        assert_eq!(schedule.len(), 2);
+        assert!(schedule.contains_task("c1") && schedule.contains_task("c2"));
+        assert!(!schedule.contains_task("s1"));
+    }
+}
@@ -0,0 +1,470 @@
+//! Common interface over continuous windows and discrete start lists.
+//!
+//! [`SolutionSpace`] describes where a task may run as continuous windows.
+//! Some upstream tools already quantize opportunities (e.g. satellite passes)
+//! and only hand over a list of allowed start times; [`CandidateStarts`]
+//! stores those directly. Both implement [`Feasibility`], which is what the
+//! EST scheduler and [`Schedule::infeasible_entries`](crate::schedule::Schedule::infeasible_entries)
+//! consume.
+
+use std::collections::HashMap;
+
+use super::interval::Interval;
+use super::space::SolutionSpace;
+use crate::scheduling_block::PlacementPreference;
+use crate::Id;
+use qtty::{Quantity, Unit};
+
+/// Earliest start, latest start and flexibility of a task within a horizon.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StartRange<U: Unit> {
+    /// Earliest feasible start, or `None` if the task cannot fit.
+    pub earliest: Option<Quantity<U>>,
+    /// Latest feasible start, or `None` if the task cannot fit.
+    pub latest: Option<Quantity<U>>,
+    /// How many times the task could fit, as a dimensionless count.
+    /// Infinite for a milestone in a continuous window.
+    pub flexibility: f64,
+}
+
+impl<U: Unit> StartRange<U> {
+    /// A range with no feasible start.
+    pub fn empty() -> Self {
+        Self {
+            earliest: None,
+            latest: None,
+            flexibility: 0.0,
+        }
+    }
+}
+
+/// Answers "where may this task start?" for a scheduler.
+///
+/// Implementations must agree with themselves: every start returned by
+/// [`start_range`](Self::start_range) or [`anchor`](Self::anchor) must be
+/// accepted by [`allows`](Self::allows).
+pub trait Feasibility<U: Unit> {
+    /// Returns `true` if the task has an entry, even an empty one.
+    fn contains(&self, id: &str) -> bool;
+
+    /// Earliest start, latest start and flexibility of a task of `size`
+    /// that must lie entirely within `horizon`.
+    fn start_range(&self, id: &str, size: Quantity<U>, horizon: Interval<U>) -> StartRange<U>;
+
+    /// Like [`start_range`](Self::start_range), for a task that takes
+    /// `duration(start)` when started at `start`.
+    ///
+    /// `duration` must keep ending times from going backwards, as required
+    /// by [`Task::duration_at`](crate::scheduling_block::Task::duration_at).
+    /// The default implementation uses the duration at the start of
+    /// `horizon` throughout.
+    fn start_range_with(
+        &self,
+        id: &str,
+        duration: &dyn Fn(Quantity<U>) -> Quantity<U>,
+        horizon: Interval<U>,
+    ) -> StartRange<U> {
+        self.start_range(id, duration(horizon.start()), horizon)
+    }
+
+    /// Picks the start to use for a task whose earliest start is `est`.
+    ///
+    /// The start stays in the opportunity containing `est`, so the scheduling
+    /// order is unaffected; `preference` may only move it within that
+    /// opportunity. Returns `None` if `est` is not a feasible start.
+    fn anchor(
+        &self,
+        id: &str,
+        est: Quantity<U>,
+        size: Quantity<U>,
+        preference: &PlacementPreference<U>,
+        horizon: Interval<U>,
+    ) -> Option<Quantity<U>>;
+
+    /// Returns `true` if the task may occupy `[start, start + size)`.
+    fn allows(&self, id: &str, start: Quantity<U>, size: Quantity<U>) -> bool;
+}
+
+impl<U: Unit> Feasibility<U> for SolutionSpace<U> {
+    fn contains(&self, id: &str) -> bool {
+        self.get_intervals(id).is_some()
+    }
+
+    /// Flexibility is the sum of `window_duration / size` over the windows
+    /// (clipped to the horizon) that can hold the task.
+    fn start_range(&self, id: &str, size: Quantity<U>, horizon: Interval<U>) -> StartRange<U> {
+        self.start_range_with(id, &|_| size, horizon)
+    }
+
+    /// A window can hold the task if it fits when started at the window's
+    /// opening, whose duration also divides the window for flexibility.
+    fn start_range_with(
+        &self,
+        id: &str,
+        duration: &dyn Fn(Quantity<U>) -> Quantity<U>,
+        horizon: Interval<U>,
+    ) -> StartRange<U> {
+        let mut range = StartRange::empty();
+        let Some(intervals) = self.get_intervals(id) else {
+            return range;
+        };
+
+        for interval in intervals {
+            // Skip windows that end before horizon begins
+            if interval.end().value() <= horizon.start().value() {
+                continue;
+            }
+
+            // Skip windows that start after horizon ends (intervals are sorted)
+            if interval.start().value() >= horizon.end().value() {
+                break;
+            }
+
+            if let Some(intersection) = interval.intersection(&horizon) {
+                let intersection_duration = intersection.duration().value();
+                let size = duration(intersection.start());
+                if size.value() <= intersection_duration {
+                    range.earliest.get_or_insert(intersection.start());
+                    range.latest = Some(latest_start(intersection, size, duration));
+                    range.flexibility += intersection_duration / size.value();
+                }
+            }
+        }
+
+        range
+    }
+
+    fn anchor(
+        &self,
+        id: &str,
+        est: Quantity<U>,
+        size: Quantity<U>,
+        preference: &PlacementPreference<U>,
+        horizon: Interval<U>,
+    ) -> Option<Quantity<U>> {
+        let window = self
+            .find_interval_containing_for(id, est)?
+            .intersection(&horizon)?;
+        preference.choose_start(&[window], size)
+    }
+
+    fn allows(&self, id: &str, start: Quantity<U>, size: Quantity<U>) -> bool {
+        self.can_place(id, start, size)
+    }
+}
+
+/// Latest start in `window` of a task that fits when started at its opening,
+/// taking `first` there.
+///
+/// If the task takes `first` again at `window.end() - first`, that start is
+/// returned as is; otherwise the last fitting start is found by bisection,
+/// which ending times that never go backwards make sound.
+fn latest_start<U: Unit>(
+    window: Interval<U>,
+    first: Quantity<U>,
+    duration: &dyn Fn(Quantity<U>) -> Quantity<U>,
+) -> Quantity<U> {
+    let guess = window.end() - first;
+    if duration(guess).value() == first.value() {
+        return guess;
+    }
+    let end = window.end().value();
+    let fits = |start: f64| start + duration(Quantity::new(start)).value() <= end;
+    let (mut lo, mut hi) = (window.start().value(), end);
+    loop {
+        let mid = lo + (hi - lo) / 2.0;
+        if mid <= lo || mid >= hi {
+            return Quantity::new(lo);
+        }
+        if fits(mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+}
+
+/// Discrete list of allowed start times per task.
+///
+/// A task may start only at one of its listed times. Starts are kept sorted
+/// and deduplicated.
+///
+/// # Example
+///
+/// ```no_run
+/// use virolai::solution_space::{CandidateStarts, Feasibility};
+/// # use qtty::Quantity;
+/// # use virolai::algorithms::ESTScheduler;
+/// # use qtty::Second;
+/// # use virolai::solution_space::{Horizon, Interval};
+/// # use virolai::synthetic::{generate, SyntheticConfig};
+/// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+/// # let instance = generate(&SyntheticConfig::new(20, horizon));
+/// # let blocks = vec![instance.block];
+///
+/// let mut passes = CandidateStarts::new();
+/// passes.add_starts("downlink", [Quantity::new(120.0), Quantity::new(5_520.0)]);
+/// let schedule = ESTScheduler::new(Quantity::new(2.0)).schedule_with_feasibility(&blocks, &passes, horizon);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CandidateStarts<U: Unit>(HashMap<Id, Vec<Quantity<U>>>);
+
+impl<U: Unit> CandidateStarts<U> {
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    /// Adds one allowed start for a task.
+    pub fn add_start(&mut self, id: impl Into<Id>, start: Quantity<U>) {
+        self.add_starts(id, [start]);
+    }
+
+    /// Adds several allowed starts for a task.
+    pub fn add_starts(&mut self, id: impl Into<Id>, starts: impl IntoIterator<Item = Quantity<U>>) {
+        let list = self.0.entry(id.into()).or_default();
+        list.extend(starts);
+        normalize(list);
+    }
+
+    /// Sets the allowed starts for a task, replacing any existing ones.
+    pub fn set_starts(&mut self, id: impl Into<Id>, starts: Vec<Quantity<U>>) {
+        let mut starts = starts;
+        normalize(&mut starts);
+        self.0.insert(id.into(), starts);
+    }
+
+    /// Returns the sorted allowed starts of a task.
+    pub fn get_starts(&self, id: &str) -> Option<&[Quantity<U>]> {
+        self.0.get(id).map(Vec::as_slice)
+    }
+
+    /// Returns an iterator over all task IDs.
+    pub fn ids(&self) -> impl Iterator<Item = &str> + '_ {
+        self.0.keys().map(String::as_str)
+    }
+
+    /// Returns the number of tasks.
+    pub fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Removes a task, returning `true` if it was present.
+    pub fn remove(&mut self, id: &str) -> bool {
+        self.0.remove(id).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Allowed starts of a task whose `[start, start + size)` lies in `horizon`.
+    fn starts_within(&self, id: &str, size: Quantity<U>, horizon: Interval<U>) -> &[Quantity<U>] {
+        let Some(starts) = self.0.get(id) else {
+            return &[];
+        };
+        let from = starts.partition_point(|s| s.value() < horizon.start().value());
+        let last_start = horizon.end().value() - size.value();
+        let to = starts.partition_point(|s| s.value() <= last_start);
+        &starts[from..to.max(from)]
+    }
+}
+
+fn normalize<U: Unit>(starts: &mut Vec<Quantity<U>>) {
+    starts.retain(|s| !s.value().is_nan());
+    starts.sort_by(|a, b| a.value().total_cmp(&b.value()));
+    starts.dedup_by(|a, b| a.value() == b.value());
+}
+
+impl<U: Unit> Default for CandidateStarts<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<U: Unit> Feasibility<U> for CandidateStarts<U> {
+    fn contains(&self, id: &str) -> bool {
+        self.0.contains_key(id)
+    }
+
+    /// Flexibility is the number of allowed starts inside the horizon.
+    fn start_range(&self, id: &str, size: Quantity<U>, horizon: Interval<U>) -> StartRange<U> {
+        let starts = self.starts_within(id, size, horizon);
+        StartRange {
+            earliest: starts.first().copied(),
+            latest: starts.last().copied(),
+            flexibility: starts.len() as f64,
+        }
+    }
+
+    /// Each allowed start inside the horizon is checked with its own
+    /// duration.
+    fn start_range_with(
+        &self,
+        id: &str,
+        duration: &dyn Fn(Quantity<U>) -> Quantity<U>,
+        horizon: Interval<U>,
+    ) -> StartRange<U> {
+        let mut range = StartRange::empty();
+        let Some(starts) = self.0.get(id) else {
+            return range;
+        };
+        let from = starts.partition_point(|s| s.value() < horizon.start().value());
+        let end = horizon.end().value();
+        for &start in starts[from..].iter().take_while(|s| s.value() <= end) {
+            if start.value() <= end - duration(start).value() {
+                range.earliest.get_or_insert(start);
+                range.latest = Some(start);
+                range.flexibility += 1.0;
+            }
+        }
+        range
+    }
+
+    /// Each start is its own opportunity, so the task is placed exactly at
+    /// `est` and `preference` is ignored.
+    fn anchor(
+        &self,
+        id: &str,
+        est: Quantity<U>,
+        size: Quantity<U>,
+        _preference: &PlacementPreference<U>,
+        horizon: Interval<U>,
+    ) -> Option<Quantity<U>> {
+        let fits_horizon =
+            est.value() >= horizon.start().value() && (est + size).value() <= horizon.end().value();
+        (fits_horizon && self.allows(id, est, size)).then_some(est)
+    }
+
+    fn allows(&self, id: &str, start: Quantity<U>, _size: Quantity<U>) -> bool {
+        self.get_starts(id).is_some_and(|starts| {
+            starts
+                .binary_search_by(|s| s.value().total_cmp(&start.value()))
+                .is_ok()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{iv, q};
+    use qtty::Second;
+
+    fn starts(values: &[f64]) -> Vec<Quantity<Second>> {
+        values.iter().map(|&v| q(v)).collect()
+    }
+
+    // ── SolutionSpace ─────────────────────────────────────────────────
+
+    #[test]
+    fn windows_range_clips_to_horizon() {
+        let mut ss = SolutionSpace::<Second>::new();
+        ss.set_intervals("a", vec![iv(0.0, 20.0), iv(30.0, 35.0), iv(50.0, 80.0)]);
+
+        let range = ss.start_range("a", q(10.0), iv(5.0, 70.0));
+        assert_eq!(range.earliest, Some(q(5.0)));
+        assert_eq!(range.latest, Some(q(60.0)));
+        assert!((range.flexibility - 3.5).abs() < 1e-12);
+        assert_eq!(
+            ss.start_range("missing", q(1.0), iv(0.0, 10.0)),
+            StartRange::empty()
+        );
+    }
+
+    #[test]
+    fn windows_anchor_uses_preference() {
+        let mut ss = SolutionSpace::<Second>::new();
+        ss.set_intervals("a", vec![iv(0.0, 50.0)]);
+        let start = ss.anchor(
+            "a",
+            q(0.0),
+            q(10.0),
+            &PlacementPreference::Latest,
+            iv(0.0, 30.0),
+        );
+        assert_eq!(start, Some(q(20.0)));
+        assert!(ss.allows("a", q(20.0), q(10.0)));
+        assert!(!ss.allows("a", q(45.0), q(10.0)));
+    }
+
+    #[test]
+    fn windows_range_follows_duration() {
+        let mut ss = SolutionSpace::<Second>::new();
+        ss.set_intervals("a", vec![iv(0.0, 20.0), iv(40.0, 140.0)]);
+        // 30 s at t = 40, shrinking by 0.2 s per second: ends never go back.
+        let duration = |start: Quantity<Second>| q(38.0 - 0.2 * start.value());
+
+        let range = ss.start_range_with("a", &duration, iv(0.0, 200.0));
+        assert_eq!(range.earliest, Some(q(40.0)));
+        // start + 38 - 0.2 start <= 140 up to start = 127.5.
+        assert!((range.latest.unwrap().value() - 127.5).abs() < 1e-9);
+        assert!((range.flexibility - 100.0 / 30.0).abs() < 1e-12);
+
+        // A constant duration takes the exact path.
+        let fixed = ss.start_range_with("a", &|_| q(10.0), iv(0.0, 200.0));
+        assert_eq!(fixed, ss.start_range("a", q(10.0), iv(0.0, 200.0)));
+        assert_eq!(fixed.latest, Some(q(130.0)));
+    }
+
+    // ── CandidateStarts ───────────────────────────────────────────────
+
+    #[test]
+    fn starts_are_sorted_and_deduplicated() {
+        let mut cs = CandidateStarts::<Second>::new();
+        cs.add_starts("a", starts(&[30.0, 10.0, 30.0]));
+        cs.add_start("a", q(20.0));
+        assert_eq!(
+            cs.get_starts("a").unwrap(),
+            starts(&[10.0, 20.0, 30.0]).as_slice()
+        );
+
+        cs.set_starts("a", starts(&[5.0, 5.0]));
+        assert_eq!(cs.get_starts("a").unwrap(), starts(&[5.0]).as_slice());
+        assert_eq!(cs.count(), 1);
+        assert!(cs.remove("a"));
+        assert!(cs.is_empty());
+    }
+
+    #[test]
+    fn discrete_range_counts_starts_that_fit() {
+        let mut cs = CandidateStarts::<Second>::new();
+        cs.set_starts("a", starts(&[0.0, 10.0, 40.0, 55.0, 90.0]));
+
+        let range = cs.start_range("a", q(10.0), iv(5.0, 65.0));
+        assert_eq!(range.earliest, Some(q(10.0)));
+        assert_eq!(range.latest, Some(q(55.0)));
+        assert_eq!(range.flexibility, 3.0);
+
+        let none = cs.start_range("a", q(10.0), iv(60.0, 80.0));
+        assert_eq!(none, StartRange::empty());
+    }
+
+    #[test]
+    fn discrete_range_checks_each_start_with_its_duration() {
+        let mut cs = CandidateStarts::<Second>::new();
+        cs.set_starts("a", starts(&[0.0, 50.0, 80.0, 90.0]));
+        let duration = |start: Quantity<Second>| q(30.0 - 0.2 * start.value());
+
+        // 90 + 12 overruns the horizon; 80 + 14 does not.
+        let range = cs.start_range_with("a", &duration, iv(0.0, 100.0));
+        assert_eq!(range.earliest, Some(q(0.0)));
+        assert_eq!(range.latest, Some(q(80.0)));
+        assert_eq!(range.flexibility, 3.0);
+    }
+
+    #[test]
+    fn discrete_anchor_is_exact() {
+        let mut cs = CandidateStarts::<Second>::new();
+        cs.set_starts("a", starts(&[10.0, 40.0]));
+        let pref = PlacementPreference::Latest;
+
+        assert_eq!(
+            cs.anchor("a", q(10.0), q(5.0), &pref, iv(0.0, 100.0)),
+            Some(q(10.0))
+        );
+        assert_eq!(cs.anchor("a", q(12.0), q(5.0), &pref, iv(0.0, 100.0)), None);
+        assert_eq!(cs.anchor("a", q(40.0), q(5.0), &pref, iv(0.0, 42.0)), None);
+        assert!(cs.allows("a", q(40.0), q(5.0)));
+        assert!(!cs.allows("b", q(40.0), q(5.0)));
+    }
+}
@@ -0,0 +1,277 @@
+//! The span a scheduler works on.
+//!
+//! A horizon and a visibility window are both intervals, and mixing them up
+//! is an easy mistake. [`Horizon`] is an interval known to be a valid
+//! horizon — non-empty and finite — with the operations schedulers apply to
+//! one. Schedulers and [`SolutionSpace::populate`](super::SolutionSpace::populate)
+//! take a `Horizon`; feasibility and metric primitives take the bare
+//! interval, [`Horizon::interval`], since the span left after a cursor can
+//! be empty.
+
+use qtty::{Quantity, Unit};
+use thiserror::Error;
+
+use super::{Interval, IntervalSet};
+
+/// A reason an interval cannot be a [`Horizon`].
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum HorizonError {
+    #[error("Horizon [{start}, {end}) is empty")]
+    Empty { start: f64, end: f64 },
+
+    #[error("Horizon [{start}, {end}) is not finite")]
+    NotFinite { start: f64, end: f64 },
+
+    #[error("Cannot split horizon [{start}, {end}) into periods of {period}")]
+    InvalidPeriod { start: f64, end: f64, period: f64 },
+}
+
+/// A non-empty, finite scheduling horizon `[start, end)`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use qtty::{Quantity, Second};
+/// # use virolai::algorithms::{ESTScheduler, SchedulingAlgorithm};
+/// # use virolai::solution_space::{Horizon, Interval};
+/// # use virolai::synthetic::{generate, SyntheticConfig};
+/// # let day = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+/// # let instance = generate(&SyntheticConfig::new(20, day));
+/// # let blocks = vec![instance.block];
+/// # let space = instance.solution_space;
+/// # let scheduler = ESTScheduler::default();
+/// let night = Horizon::new(Interval::from_f64(0.0, 12.0 * 3600.0))?;
+/// for hour in night.split(Quantity::new(3600.0))? {
+///     let visible = hour.clip(space.get_intervals("m31").unwrap());
+///     // ...
+/// }
+/// let schedule = scheduler.schedule(&blocks, &space, night);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Horizon<U: Unit>(Interval<U>);
+
+impl<U: Unit> Horizon<U> {
+    /// Checks that `interval` can be a horizon.
+    ///
+    /// # Errors
+    ///
+    /// - [`HorizonError::NotFinite`] if an endpoint is infinite
+    /// - [`HorizonError::Empty`] if `start == end`
+    pub fn new(interval: Interval<U>) -> Result<Self, HorizonError> {
+        let (start, end) = (interval.start().value(), interval.end().value());
+        if !interval.is_bounded() {
+            Err(HorizonError::NotFinite { start, end })
+        } else if interval.is_empty() {
+            Err(HorizonError::Empty { start, end })
+        } else {
+            Ok(Self(interval))
+        }
+    }
+
+    /// The horizon `[start, end)`, see [`new`](Self::new).
+    ///
+    /// # Errors
+    ///
+    /// As [`new`](Self::new); also [`HorizonError::Empty`] if `end < start`.
+    pub fn from_bounds(start: Quantity<U>, end: Quantity<U>) -> Result<Self, HorizonError> {
+        if end.value() < start.value() {
+            return Err(HorizonError::Empty {
+                start: start.value(),
+                end: end.value(),
+            });
+        }
+        Self::new(Interval::new(start, end))
+    }
+
+    /// The horizon as an interval, as feasibility and metric primitives take it.
+    pub const fn interval(&self) -> Interval<U> {
+        self.0
+    }
+
+    pub const fn start(&self) -> Quantity<U> {
+        self.0.start()
+    }
+
+    pub const fn end(&self) -> Quantity<U> {
+        self.0.end()
+    }
+
+    pub fn duration(&self) -> Quantity<U> {
+        self.0.duration()
+    }
+
+    /// Returns true if `interval` lies entirely within the horizon.
+    ///
+    /// An empty interval (a milestone) is within it if its point is, the
+    /// end of the horizon included.
+    pub fn contains(&self, interval: &Interval<U>) -> bool {
+        self.start().value() <= interval.start().value()
+            && interval.end().value() <= self.end().value()
+    }
+
+    /// Returns true if `position` ∈ `[start, end)`.
+    pub fn contains_position(&self, position: Quantity<U>) -> bool {
+        self.0.contains(position)
+    }
+
+    /// The part of `set` within the horizon.
+    pub fn clip(&self, set: &IntervalSet<U>) -> IntervalSet<U> {
+        set.intersection(&IntervalSet::from(self.0))
+    }
+
+    /// Consecutive horizons of length `period` covering this one, the last
+    /// one shorter if `period` does not divide the duration.
+    ///
+    /// Boundaries are rounded to the nearest representable position, so
+    /// parts may be slightly longer or shorter than `period`; a part that
+    /// rounding would leave empty is merged into the next one.
+    ///
+    /// # Errors
+    ///
+    /// [`HorizonError::InvalidPeriod`] if `period` is not positive and
+    /// finite, or so small next to the horizon's endpoints that adding it
+    /// does not move them.
+    pub fn split(&self, period: Quantity<U>) -> Result<Vec<Horizon<U>>, HorizonError> {
+        let step = period.value();
+        let (start, end) = (self.start().value(), self.end().value());
+        let magnitude = start.abs().max(end.abs());
+        if !(step > 0.0 && step.is_finite()) || magnitude + step == magnitude {
+            return Err(HorizonError::InvalidPeriod {
+                start,
+                end,
+                period: step,
+            });
+        }
+        let mut parts = Vec::new();
+        let mut from = start;
+        let mut k = 1.0;
+        while from < end {
+            // Multiplying instead of accumulating keeps boundaries exact.
+            let to = (start + k * step).min(end);
+            k += 1.0;
+            if to > from {
+                parts.push(Horizon(Interval::new(
+                    Quantity::new(from),
+                    Quantity::new(to),
+                )));
+                from = to;
+            }
+        }
+        Ok(parts)
+    }
+
+    /// What is left of the horizon from `cursor` on, or `None` once the
+    /// cursor reached its end.
+    ///
+    /// A cursor before the start leaves the whole horizon.
+    pub fn remaining_after(&self, cursor: Quantity<U>) -> Option<Horizon<U>> {
+        let start = self.start().max(cursor);
+        (start.value() < self.end().value()).then(|| Horizon(Interval::new(start, self.end())))
+    }
+}
+
+impl<U: Unit> From<Horizon<U>> for Interval<U> {
+    fn from(horizon: Horizon<U>) -> Self {
+        horizon.0
+    }
+}
+
+impl<U: Unit> TryFrom<Interval<U>> for Horizon<U> {
+    type Error = HorizonError;
+
+    fn try_from(interval: Interval<U>) -> Result<Self, Self::Error> {
+        Self::new(interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{iv, q};
+    use qtty::Second;
+
+    #[test]
+    fn validates_and_splits() {
+        assert_eq!(
+            Horizon::<Second>::new(iv(5.0, 5.0)),
+            Err(HorizonError::Empty {
+                start: 5.0,
+                end: 5.0
+            })
+        );
+        assert!(matches!(
+            Horizon::<Second>::new(iv(0.0, f64::INFINITY)),
+            Err(HorizonError::NotFinite { .. })
+        ));
+        assert!(matches!(
+            Horizon::<Second>::from_bounds(q(10.0), q(0.0)),
+            Err(HorizonError::Empty { .. })
+        ));
+
+        let horizon = Horizon::new(iv(0.0, 25.0)).unwrap();
+        let parts: Vec<Interval<Second>> = horizon
+            .split(q(10.0))
+            .unwrap()
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        assert_eq!(parts, [iv(0.0, 10.0), iv(10.0, 20.0), iv(20.0, 25.0)]);
+        assert_eq!(horizon.split(q(25.0)), Ok(vec![horizon]));
+        assert!(matches!(
+            horizon.split(q(0.0)),
+            Err(HorizonError::InvalidPeriod { .. })
+        ));
+    }
+
+    #[test]
+    fn tiny_periods_never_yield_empty_parts() {
+        // At 1e9 floats are 1.2e-7 apart: a 1e-8 period cannot move the
+        // start at all.
+        let far = Horizon::new(iv(1e9, 1e9 + 1e-6)).unwrap();
+        assert_eq!(
+            far.split(q(1e-8)),
+            Err(HorizonError::InvalidPeriod {
+                start: 1e9,
+                end: 1e9 + 1e-6,
+                period: 1e-8
+            })
+        );
+
+        // A period around the float spacing rounds some boundaries onto
+        // the previous one; those parts are merged, not returned empty.
+        let spacing = f64::from_bits(1e9_f64.to_bits() + 1) - 1e9;
+        let period = 0.7 * spacing;
+        let parts = far.split(q(period)).unwrap();
+        assert!(!parts.is_empty());
+        assert!(parts.iter().all(|p| p.end() > p.start()));
+        assert_eq!(parts[0].start(), far.start());
+        assert_eq!(parts.last().unwrap().end(), far.end());
+        assert!(parts.windows(2).all(|w| w[0].end() == w[1].start()));
+    }
+
+    #[test]
+    fn clips_and_advances() {
+        let horizon = Horizon::new(iv(10.0, 50.0)).unwrap();
+        assert!(horizon.contains(&iv(10.0, 50.0)));
+        assert!(horizon.contains(&iv(50.0, 50.0)));
+        assert!(!horizon.contains(&iv(40.0, 60.0)));
+        assert!(horizon.contains_position(q(10.0)));
+        assert!(!horizon.contains_position(q(50.0)));
+
+        let mut windows = IntervalSet::from(iv(0.0, 20.0));
+        windows.push(iv(30.0, 40.0));
+        windows.push(iv(45.0, 90.0));
+        assert_eq!(
+            horizon.clip(&windows).as_slice(),
+            [iv(10.0, 20.0), iv(30.0, 40.0), iv(45.0, 50.0)]
+        );
+
+        assert_eq!(
+            horizon.remaining_after(q(30.0)).map(Interval::from),
+            Some(iv(30.0, 50.0))
+        );
+        assert_eq!(horizon.remaining_after(q(0.0)), Some(horizon));
+        assert_eq!(horizon.remaining_after(q(50.0)), None);
+    }
+}
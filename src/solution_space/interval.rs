@@ -9,6 +9,10 @@ use qtty::{Quantity, Unit};
 /// The interval is **half-open**: `start` is inclusive, `end` is exclusive.
 /// This avoids ambiguity at shared boundaries (two abutting intervals never
 /// overlap) and eliminates the need for epsilon offsets in the scheduler.
+///
+/// Endpoints may be infinite: `[0, +∞)` is every position from 0 on. An
+/// interval with `start == end` is empty, including `[+∞, +∞)` and
+/// `[−∞, −∞)`, and has zero duration.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Interval<U: Unit> {
     start: Quantity<U>,
@@ -41,10 +45,26 @@ impl<U: Unit> Interval<U> {
         self.end
     }
 
+    /// Returns `end - start`: zero for empty intervals, `+∞` for non-empty
+    /// unbounded ones.
     pub fn duration(&self) -> Quantity<U> {
+        if self.is_empty() {
+            // Avoids ∞ − ∞ = NaN for [±∞, ±∞).
+            return Quantity::new(0.0);
+        }
         self.end - self.start
     }
 
+    /// Returns true if the interval contains no position (`start == end`).
+    pub const fn is_empty(&self) -> bool {
+        self.start.value() == self.end.value()
+    }
+
+    /// Returns true if both endpoints are finite.
+    pub const fn is_bounded(&self) -> bool {
+        self.start.value().is_finite() && self.end.value().is_finite()
+    }
+
     /// Converts this interval to another unit of the same dimension.
     ///
     /// # Example
@@ -73,11 +93,18 @@ impl<U: Unit> Interval<U> {
     /// Returns true if this interval shares any interior point with `other`.
     ///
     /// Because intervals are half-open, abutting intervals (`self.end == other.start`)
-    /// do **not** overlap — they are back-to-back with no shared point.
+    /// do **not** overlap — they are back-to-back with no shared point. An
+    /// empty interval (a milestone's `[t, t)`) has no points and overlaps
+    /// nothing, even when `t` lies inside `other`.
     pub const fn overlaps(&self, other: &Interval<U>) -> bool {
-        self.start.value() < other.end.value() && other.start.value() < self.end.value()
+        self.start.value() < other.end.value()
+            && other.start.value() < self.end.value()
+            && !self.is_empty()
+            && !other.is_empty()
     }
 
+    /// Returns the positions shared by both intervals, or `None` if there
+    /// are none (empty intervals share nothing).
     pub fn intersection(&self, other: &Interval<U>) -> Option<Interval<U>> {
         if self.overlaps(other) {
             let start = if self.start.value() > other.start.value() {
@@ -97,7 +124,14 @@ impl<U: Unit> Interval<U> {
     }
 
     /// Returns true if task of `size` fits starting at `start_position`.
+    ///
+    /// A zero-size task (a milestone) fits at any point of the window,
+    /// closing point included, so it can mark the end of a window.
     pub fn can_fit(&self, start_position: Quantity<U>, size: Quantity<U>) -> bool {
+        if size.value() == 0.0 {
+            return self.start.value() <= start_position.value()
+                && start_position.value() <= self.end.value();
+        }
         self.contains(start_position) && (start_position + size).value() <= self.end.value()
     }
 }
@@ -187,6 +221,8 @@ mod tests {
         assert!(interval.can_fit(Quantity::<Second>::new(0.0), Quantity::<Second>::new(50.0)));
         assert!(interval.can_fit(Quantity::<Second>::new(50.0), Quantity::<Second>::new(50.0)));
         assert!(!interval.can_fit(Quantity::<Second>::new(60.0), Quantity::<Second>::new(50.0)));
+        assert!(interval.can_fit(Quantity::<Second>::new(100.0), Quantity::<Second>::new(0.0)));
+        assert!(!interval.can_fit(Quantity::<Second>::new(100.5), Quantity::<Second>::new(0.0)));
     }
 
     #[test]
@@ -204,6 +240,11 @@ mod tests {
         assert!(interval1.overlaps(&interval2));
         assert!(interval2.overlaps(&interval1));
         assert!(!interval1.overlaps(&interval3));
+
+        let milestone = Interval::<Second>::from_f64(50.0, 50.0);
+        assert!(!interval1.overlaps(&milestone));
+        assert!(!milestone.overlaps(&interval1));
+        assert!(!milestone.overlaps(&milestone));
     }
 
     // ── Gap coverage tests ────────────────────────────────────────────
@@ -251,6 +292,26 @@ mod tests {
         assert!(a.intersection(&b).is_none());
     }
 
+    #[test]
+    fn test_unbounded_and_empty_intervals() {
+        let inf = f64::INFINITY;
+        let half_line = Interval::<Second>::from_f64(0.0, inf);
+        assert!(!half_line.is_bounded() && !half_line.is_empty());
+        assert_eq!(half_line.duration().value(), inf);
+        assert!(half_line.contains(Quantity::new(1e300)));
+
+        for (start, end) in [(inf, inf), (-inf, -inf), (5.0, 5.0)] {
+            let empty = Interval::<Second>::from_f64(start, end);
+            assert!(empty.is_empty());
+            assert_eq!(empty.duration().value(), 0.0);
+            assert!(empty.intersection(&Interval::from_f64(-inf, inf)).is_none());
+        }
+        assert_eq!(
+            Interval::<Second>::from_f64(-inf, inf).duration().value(),
+            inf
+        );
+    }
+
     #[test]
     fn test_display_format() {
         let interval = Interval::new(Quantity::<Second>::new(1.5), Quantity::<Second>::new(99.25));
@@ -2,7 +2,9 @@
 //!
 //! [`IntervalSet`] wraps a `Vec<Interval<U>>` and guarantees the **canonical
 //! invariant** at all times: intervals are sorted by start and no two intervals
-//! overlap or abut (touching intervals are merged).
+//! overlap or abut (touching intervals are merged). Empty intervals
+//! (`start == end`, including `[±∞, ±∞)`) contain no positions and are
+//! dropped; unbounded intervals are kept as they are.
 //!
 //! Read access is fully transparent via `Deref<Target = [Interval<U>]>`, so
 //! existing code that consumes `&[Interval<U>]` works without changes.
@@ -12,7 +14,7 @@ use std::fmt::Display;
 use std::ops::{Deref, Index, RangeFull};
 
 use super::interval::Interval;
-use qtty::Unit;
+use qtty::{Quantity, Unit};
 
 /// A sorted, non-overlapping set of half-open intervals.
 ///
@@ -75,8 +77,10 @@ impl<U: Unit> IntervalSet<U> {
 // ─────────────────────────────────────────────────────────────────────
 
 impl<U: Unit> IntervalSet<U> {
-    /// Sorts by start and merges overlapping / touching intervals in place.
+    /// Drops empty intervals, sorts by start and merges overlapping /
+    /// touching intervals in place.
     fn normalize(&mut self) {
+        self.0.retain(|interval| !interval.is_empty());
         if self.0.len() <= 1 {
             return;
         }
@@ -116,6 +120,9 @@ impl<U: Unit> IntervalSet<U> {
     /// neighbours. O(n) worst-case due to the shift, but O(1) amortized
     /// when intervals are appended in order.
     pub fn push(&mut self, interval: Interval<U>) {
+        if interval.is_empty() {
+            return;
+        }
         if self.0.is_empty() {
             self.0.push(interval);
             return;
@@ -172,6 +179,13 @@ impl<U: Unit> IntervalSet<U> {
     pub fn as_slice(&self) -> &[Interval<U>] {
         &self.0
     }
+
+    /// Converts every interval to another unit of the same dimension.
+    ///
+    /// The result is re-normalized, in case rounding makes intervals abut.
+    pub fn to<T: Unit<Dim = U::Dim>>(&self) -> IntervalSet<T> {
+        self.0.iter().map(|interval| interval.to()).collect()
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────
@@ -195,6 +209,70 @@ impl<U: Unit> IntervalSet<U> {
     }
 }
 
+// ─────────────────────────────────────────────────────────────────────
+// Searches
+// ─────────────────────────────────────────────────────────────────────
+
+impl<U: Unit> IntervalSet<U> {
+    /// Returns the interval containing `position`, if any. O(log n).
+    pub fn find_containing(&self, position: Quantity<U>) -> Option<&Interval<U>> {
+        let idx = self
+            .0
+            .partition_point(|i| i.end().value() <= position.value());
+        self.0.get(idx).filter(|i| i.contains(position))
+    }
+
+    /// Returns the first interval starting at or after `position`. O(log n).
+    ///
+    /// An interval containing `position` but starting before it is skipped;
+    /// see [`find_containing`](Self::find_containing).
+    pub fn next_at_or_after(&self, position: Quantity<U>) -> Option<&Interval<U>> {
+        let idx = self
+            .0
+            .partition_point(|i| i.start().value() < position.value());
+        self.0.get(idx)
+    }
+
+    /// Returns the last interval ending at or before `position`. O(log n).
+    ///
+    /// An interval containing `position` is skipped; see
+    /// [`find_containing`](Self::find_containing).
+    pub fn prev_at_or_before(&self, position: Quantity<U>) -> Option<&Interval<U>> {
+        let idx = self
+            .0
+            .partition_point(|i| i.end().value() <= position.value());
+        idx.checked_sub(1).map(|i| &self.0[i])
+    }
+
+    /// Returns the earliest start at or after `position` at which `size`
+    /// fits inside one interval, see [`Interval::can_fit`].
+    ///
+    /// O(log n + k), where k is the number of intervals too short to hold
+    /// `size` from the search point on.
+    pub fn first_fit_at_or_after(
+        &self,
+        position: Quantity<U>,
+        size: Quantity<U>,
+    ) -> Option<Quantity<U>> {
+        // A zero size may sit on the closing point of an interval.
+        let idx = if size.value() == 0.0 {
+            self.0
+                .partition_point(|i| i.end().value() < position.value())
+        } else {
+            self.0
+                .partition_point(|i| i.end().value() <= position.value())
+        };
+        self.0[idx..].iter().find_map(|i| {
+            let start = if i.start().value() < position.value() {
+                position
+            } else {
+                i.start()
+            };
+            i.can_fit(start, size).then_some(start)
+        })
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────────
 // Transparent read access
 // ─────────────────────────────────────────────────────────────────────
@@ -290,8 +368,12 @@ impl<U: Unit> From<Vec<Interval<U>>> for IntervalSet<U> {
 }
 
 impl<U: Unit> From<Interval<U>> for IntervalSet<U> {
-    /// Creates a single-element `IntervalSet` (always canonical).
+    /// Creates a single-element `IntervalSet`, or an empty one if the
+    /// interval is empty.
     fn from(interval: Interval<U>) -> Self {
+        if interval.is_empty() {
+            return Self::new();
+        }
         Self(vec![interval])
     }
 }
@@ -451,6 +533,25 @@ mod tests {
         assert_eq!(set[1], iv(20.0, 30.0));
     }
 
+    #[test]
+    fn empty_intervals_are_dropped() {
+        let inf = f64::INFINITY;
+        let set = IntervalSet::from(vec![
+            iv(inf, inf),
+            iv(0.0, inf),
+            iv(-inf, -inf),
+            iv(-3.0, -3.0),
+        ]);
+        assert_eq!(set, vec![iv(0.0, inf)]);
+        assert!(IntervalSet::from(iv(inf, inf)).is_empty());
+
+        let mut set = IntervalSet::from(iv(-inf, 0.0));
+        set.push(iv(5.0, 5.0));
+        set.push(iv(0.0, 10.0));
+        assert_eq!(set, vec![iv(-inf, 10.0)]);
+        assert_eq!(set.complement(iv(-inf, inf)), vec![iv(10.0, inf)]);
+    }
+
     #[test]
     fn from_overlapping_merges() {
         let set = IntervalSet::from(vec![iv(0.0, 60.0), iv(40.0, 100.0)]);
@@ -650,6 +751,75 @@ mod tests {
         assert_eq!(c[2], iv(80.0, 100.0));
     }
 
+    // ── Searches ──────────────────────────────────────────────────────
+
+    fn searched() -> IntervalSet<Second> {
+        IntervalSet::from(vec![iv(0.0, 10.0), iv(20.0, 25.0), iv(40.0, 80.0)])
+    }
+
+    #[test]
+    fn find_containing_is_half_open() {
+        let set = searched();
+        assert_eq!(
+            set.find_containing(Quantity::new(0.0)),
+            Some(&iv(0.0, 10.0))
+        );
+        assert_eq!(
+            set.find_containing(Quantity::new(22.0)),
+            Some(&iv(20.0, 25.0))
+        );
+        assert_eq!(set.find_containing(Quantity::new(10.0)), None);
+        assert_eq!(set.find_containing(Quantity::new(90.0)), None);
+        assert_eq!(
+            IntervalSet::<Second>::new().find_containing(Quantity::new(0.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn neighbours_of_a_position() {
+        let set = searched();
+        assert_eq!(
+            set.next_at_or_after(Quantity::new(5.0)),
+            Some(&iv(20.0, 25.0))
+        );
+        assert_eq!(
+            set.next_at_or_after(Quantity::new(20.0)),
+            Some(&iv(20.0, 25.0))
+        );
+        assert_eq!(set.next_at_or_after(Quantity::new(41.0)), None);
+        assert_eq!(
+            set.prev_at_or_before(Quantity::new(30.0)),
+            Some(&iv(20.0, 25.0))
+        );
+        assert_eq!(
+            set.prev_at_or_before(Quantity::new(25.0)),
+            Some(&iv(20.0, 25.0))
+        );
+        assert_eq!(
+            set.prev_at_or_before(Quantity::new(22.0)),
+            Some(&iv(0.0, 10.0))
+        );
+        assert_eq!(set.prev_at_or_before(Quantity::new(5.0)), None);
+    }
+
+    #[test]
+    fn first_fit_skips_short_intervals() {
+        let set = searched();
+        let fit = |pos: f64, size: f64| {
+            set.first_fit_at_or_after(Quantity::new(pos), Quantity::new(size))
+                .map(|q| q.value())
+        };
+        assert_eq!(fit(0.0, 5.0), Some(0.0));
+        assert_eq!(fit(7.0, 5.0), Some(20.0));
+        assert_eq!(fit(7.0, 6.0), Some(40.0));
+        assert_eq!(fit(50.0, 30.0), Some(50.0));
+        assert_eq!(fit(51.0, 30.0), None);
+        // A milestone may mark the end of an interval.
+        assert_eq!(fit(10.0, 0.0), Some(10.0));
+        assert_eq!(fit(12.0, 0.0), Some(20.0));
+    }
+
     // ── Display ───────────────────────────────────────────────────────
 
     #[test]
@@ -0,0 +1,237 @@
+//! Masking a solution space with an existing schedule.
+//!
+//! Incremental and online workflows repeatedly need to remove time that is
+//! already taken by a [`Schedule`] from every task's windows before planning
+//! the remaining work. [`SolutionSpace::mask_schedule`] performs that
+//! subtraction, optionally inflating each occupied interval by setup and
+//! teardown margins.
+
+use super::{Interval, IntervalSet, SolutionSpace};
+use crate::schedule::Schedule;
+use crate::Id;
+use qtty::{Quantity, Unit};
+
+/// How [`SolutionSpace::mask_schedule`] subtracts occupied time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaskPolicy<U: Unit> {
+    setup: Quantity<U>,
+    teardown: Quantity<U>,
+    mask_own_slot: bool,
+}
+
+impl<U: Unit> MaskPolicy<U> {
+    /// Subtracts scheduled intervals exactly, leaving each scheduled task's
+    /// own slot in its windows.
+    pub fn new() -> Self {
+        Self {
+            setup: Quantity::new(0.0),
+            teardown: Quantity::new(0.0),
+            mask_own_slot: false,
+        }
+    }
+
+    /// Inflates every occupied interval by `setup` before its start and
+    /// `teardown` after its end. Negative margins are treated as zero.
+    pub fn with_margins(mut self, setup: Quantity<U>, teardown: Quantity<U>) -> Self {
+        self.setup = Quantity::new(setup.value().max(0.0));
+        self.teardown = Quantity::new(teardown.value().max(0.0));
+        self
+    }
+
+    /// Also removes a scheduled task's own slot from its windows.
+    ///
+    /// By default a task owns the interval it is scheduled in, so that slot
+    /// (and only that slot) stays available to it.
+    pub fn masking_own_slot(mut self) -> Self {
+        self.mask_own_slot = true;
+        self
+    }
+
+    /// Margin subtracted before each occupied interval.
+    pub fn setup(&self) -> Quantity<U> {
+        self.setup
+    }
+
+    /// Margin subtracted after each occupied interval.
+    pub fn teardown(&self) -> Quantity<U> {
+        self.teardown
+    }
+
+    /// Whether scheduled tasks lose their own slot as well.
+    pub fn masks_own_slot(&self) -> bool {
+        self.mask_own_slot
+    }
+
+    fn inflate(&self, interval: Interval<U>) -> Interval<U> {
+        Interval::new(
+            interval.start() - self.setup,
+            interval.end() + self.teardown,
+        )
+    }
+}
+
+impl<U: Unit> Default for MaskPolicy<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Outcome of [`SolutionSpace::mask_schedule`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MaskReport {
+    /// Number of tasks whose windows shrank.
+    pub affected: usize,
+    /// Tasks that had windows before masking and have none left, sorted by ID.
+    pub infeasible: Vec<Id>,
+}
+
+impl MaskReport {
+    /// Number of tasks that became infeasible.
+    pub fn infeasible_count(&self) -> usize {
+        self.infeasible.len()
+    }
+}
+
+impl<U: Unit> SolutionSpace<U> {
+    /// Removes the time occupied by `schedule` from every task's windows.
+    ///
+    /// Each scheduled interval is inflated by the policy's setup/teardown
+    /// margins before subtraction. Unless the policy says otherwise, a task
+    /// present in `schedule` keeps its own slot. Tasks left without windows
+    /// stay in the space with an empty interval list.
+    pub fn mask_schedule(&mut self, schedule: &Schedule<U>, policy: &MaskPolicy<U>) -> MaskReport {
+        let occupied: IntervalSet<U> = schedule.intervals().map(|i| policy.inflate(i)).collect();
+        let mut report = MaskReport::default();
+        if occupied.is_empty() {
+            return report;
+        }
+
+        let mut masked = Vec::new();
+        for (id, windows) in self.iter() {
+            if windows.is_empty() {
+                continue;
+            }
+            let (first, last) = (windows[0], windows[windows.len() - 1]);
+            let bounds = Interval::new(first.start(), last.end());
+            let mut free = windows.intersection(&occupied.complement(bounds));
+            if !policy.mask_own_slot {
+                if let Some(own) = schedule.get_interval(id) {
+                    let own: IntervalSet<U> = IntervalSet::from(own).intersection(windows);
+                    free = free.union(&own);
+                }
+            }
+            if free.as_slice() == windows.as_slice() {
+                continue;
+            }
+            report.affected += 1;
+            if free.is_empty() {
+                report.infeasible.push(id.to_string());
+            }
+            masked.push((id.to_string(), free));
+        }
+        for (id, free) in masked {
+            self.set_intervals(id, free.into_inner());
+        }
+        report.infeasible.sort();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{iv, q};
+    use qtty::Second;
+
+    fn schedule(entries: &[(&str, Interval<Second>)]) -> Schedule<Second> {
+        let mut s = Schedule::new();
+        for &(id, interval) in entries {
+            s.add(id, interval).unwrap();
+        }
+        s
+    }
+
+    #[test]
+    fn subtracts_occupied_time() {
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("a", iv(0.0, 100.0));
+        ss.add_interval("b", iv(60.0, 70.0));
+        ss.add_interval("c", iv(80.0, 90.0));
+
+        let report = ss.mask_schedule(&schedule(&[("x", iv(20.0, 75.0))]), &MaskPolicy::new());
+
+        assert_eq!(
+            ss.get_intervals("a").unwrap().as_slice(),
+            [iv(0.0, 20.0), iv(75.0, 100.0)]
+        );
+        assert!(ss.get_intervals("b").unwrap().is_empty());
+        assert_eq!(ss.get_intervals("c").unwrap().as_slice(), [iv(80.0, 90.0)]);
+        assert_eq!(report.affected, 2);
+        assert_eq!(report.infeasible, vec!["b".to_string()]);
+        assert_eq!(report.infeasible_count(), 1);
+    }
+
+    #[test]
+    fn margins_inflate_occupied_intervals() {
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("a", iv(0.0, 100.0));
+        ss.add_interval("c", iv(80.0, 90.0));
+
+        let policy = MaskPolicy::new().with_margins(q(5.0), q(10.0));
+        let report = ss.mask_schedule(&schedule(&[("x", iv(20.0, 75.0))]), &policy);
+
+        assert_eq!(
+            ss.get_intervals("a").unwrap().as_slice(),
+            [iv(0.0, 15.0), iv(85.0, 100.0)]
+        );
+        assert_eq!(ss.get_intervals("c").unwrap().as_slice(), [iv(85.0, 90.0)]);
+        assert_eq!(report.affected, 2);
+        assert!(report.infeasible.is_empty());
+    }
+
+    #[test]
+    fn scheduled_task_keeps_its_own_slot() {
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("a", iv(0.0, 100.0));
+        let sched = schedule(&[("a", iv(10.0, 30.0)), ("x", iv(50.0, 60.0))]);
+
+        let report = ss.mask_schedule(&sched, &MaskPolicy::new().with_margins(q(5.0), q(0.0)));
+
+        assert_eq!(
+            ss.get_intervals("a").unwrap().as_slice(),
+            [iv(0.0, 5.0), iv(10.0, 45.0), iv(60.0, 100.0)]
+        );
+        assert!(report.infeasible.is_empty());
+    }
+
+    #[test]
+    fn masking_own_slot_removes_it_too() {
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("a", iv(10.0, 30.0));
+
+        let report = ss.mask_schedule(
+            &schedule(&[("a", iv(10.0, 30.0))]),
+            &MaskPolicy::new().masking_own_slot(),
+        );
+
+        assert!(ss.get_intervals("a").unwrap().is_empty());
+        assert_eq!(report.infeasible, vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn empty_schedule_is_a_no_op() {
+        let mut ss = SolutionSpace::new();
+        ss.add_interval("a", iv(0.0, 10.0));
+        let report = ss.mask_schedule(&Schedule::new(), &MaskPolicy::new());
+        assert_eq!(report, MaskReport::default());
+        assert_eq!(ss.get_intervals("a").unwrap().as_slice(), [iv(0.0, 10.0)]);
+    }
+
+    #[test]
+    fn negative_margins_clamp_to_zero() {
+        let policy: MaskPolicy<Second> = MaskPolicy::new().with_margins(q(-3.0), q(-1.0));
+        assert_eq!(policy.setup(), q(0.0));
+        assert_eq!(policy.teardown(), q(0.0));
+        assert!(!policy.masks_own_slot());
+    }
+}
@@ -4,12 +4,21 @@
 //! feasible positions. Can be used for both tasks and resources (instruments).
 //! Users populate it with intervals computed from constraints.
 
+mod feasibility;
+mod horizon;
 mod interval;
 mod interval_set;
+mod mask;
+mod placements;
 mod populate;
+mod quality;
 mod space;
 
+pub use feasibility::{CandidateStarts, Feasibility, StartRange};
+pub use horizon::{Horizon, HorizonError};
 pub use interval::Interval;
 pub use interval_set::IntervalSet;
+pub use mask::{MaskPolicy, MaskReport};
+pub use placements::Placement;
 pub use populate::collect_intervals;
 pub use space::SolutionSpace;
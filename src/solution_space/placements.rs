@@ -0,0 +1,224 @@
+//! Ranked candidate placements for a single task.
+//!
+//! [`SolutionSpace::best_placements`] proposes several start times instead of
+//! a single choice, for interactive tools that let operators pick. Every
+//! window that fits the task contributes three candidates — flush left, flush
+//! right and centered — which are ranked by a scoring closure. Ready-made
+//! scorers are provided as associated functions of [`Placement`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use qtty::Quantity;
+//! # use virolai::solution_space::Placement;
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let space = instance.solution_space;
+//! let options = space.best_placements("obs-42", Quantity::new(600.0), 3, Placement::most_centered);
+//! for (placement, score) in options {
+//!     println!("{} (score {score:.1})", placement.interval());
+//! }
+//! ```
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use qtty::{Quantity, Unit};
+
+use super::{Interval, SolutionSpace};
+
+/// A candidate start for a task inside one of its windows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement<U: Unit> {
+    /// Candidate start time.
+    pub start: Quantity<U>,
+    /// Task size.
+    pub size: Quantity<U>,
+    /// Window the task would occupy.
+    pub window: Interval<U>,
+}
+
+impl<U: Unit> Placement<U> {
+    /// The interval the task would occupy.
+    pub fn interval(&self) -> Interval<U> {
+        Interval::new(self.start, self.start + self.size)
+    }
+
+    /// Free time left in the window before and after the task.
+    pub fn gaps(&self) -> (Quantity<U>, Quantity<U>) {
+        (
+            self.start - self.window.start(),
+            self.window.end() - (self.start + self.size),
+        )
+    }
+
+    /// Scorer preferring earlier starts.
+    pub fn earliest(placement: &Placement<U>) -> f64 {
+        -placement.start.value()
+    }
+
+    /// Scorer preferring placements centered in their window, which keeps
+    /// the most room on both sides.
+    pub fn most_centered(placement: &Placement<U>) -> f64 {
+        let (before, after) = placement.gaps();
+        -(before.value() - after.value()).abs()
+    }
+
+    /// Scorer preferring placements that leave the largest contiguous free
+    /// piece of their window, i.e. flush against one of its edges.
+    pub fn least_fragmenting(placement: &Placement<U>) -> f64 {
+        let (before, after) = placement.gaps();
+        before.value().max(after.value())
+    }
+}
+
+/// Heap entry ordered by score, then by earlier start.
+struct Ranked<U: Unit> {
+    score: f64,
+    placement: Placement<U>,
+}
+
+impl<U: Unit> Ord for Ranked<U> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score.total_cmp(&other.score).then_with(|| {
+            other
+                .placement
+                .start
+                .value()
+                .total_cmp(&self.placement.start.value())
+        })
+    }
+}
+
+impl<U: Unit> PartialOrd for Ranked<U> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<U: Unit> PartialEq for Ranked<U> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<U: Unit> Eq for Ranked<U> {}
+
+impl<U: Unit> SolutionSpace<U> {
+    /// Returns the `k` best candidate starts for task `id`, best first.
+    ///
+    /// Candidates are the flush-left, flush-right and centered starts of
+    /// every window of `id` that fits `size`. Higher scores are better; ties
+    /// go to the earlier start, and candidates scored NaN are dropped.
+    /// Returns an empty list for unknown tasks or when nothing fits.
+    pub fn best_placements<F>(
+        &self,
+        id: &str,
+        size: Quantity<U>,
+        k: usize,
+        mut scorer: F,
+    ) -> Vec<(Placement<U>, f64)>
+    where
+        F: FnMut(&Placement<U>) -> f64,
+    {
+        let Some(windows) = self.get_intervals(id) else {
+            return Vec::new();
+        };
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // Min-heap of the k best candidates seen so far.
+        let mut best: BinaryHeap<Reverse<Ranked<U>>> = BinaryHeap::with_capacity(k + 1);
+        for &window in windows.iter() {
+            let slack = window.duration().value() - size.value();
+            if slack < 0.0 {
+                continue;
+            }
+            let mut starts = vec![0.0, slack / 2.0, slack];
+            starts.dedup();
+            for offset in starts {
+                let placement = Placement {
+                    start: Quantity::new(window.start().value() + offset),
+                    size,
+                    window,
+                };
+                let score = scorer(&placement);
+                if score.is_nan() {
+                    continue;
+                }
+                best.push(Reverse(Ranked { score, placement }));
+                if best.len() > k {
+                    best.pop();
+                }
+            }
+        }
+
+        best.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(r)| (r.placement, r.score))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{iv, q};
+    use qtty::Second;
+
+    fn space() -> SolutionSpace<Second> {
+        let mut ss = SolutionSpace::new();
+        ss.set_intervals("a", vec![iv(0.0, 10.0), iv(20.0, 60.0), iv(70.0, 74.0)]);
+        ss
+    }
+
+    fn starts(ranked: &[(Placement<Second>, f64)]) -> Vec<f64> {
+        ranked.iter().map(|(p, _)| p.start.value()).collect()
+    }
+
+    #[test]
+    fn earliest_ranks_by_start() {
+        let ranked = space().best_placements("a", q(10.0), 4, Placement::earliest);
+        // [0, 10) fits only once; [70, 74) is too short.
+        assert_eq!(starts(&ranked), vec![0.0, 20.0, 35.0, 50.0]);
+        assert_eq!(ranked[0].1, 0.0);
+    }
+
+    #[test]
+    fn centered_and_least_fragmenting_disagree() {
+        let ss = space();
+        let centered = ss.best_placements("a", q(10.0), 2, Placement::most_centered);
+        assert_eq!(starts(&centered), vec![0.0, 35.0]);
+        assert_eq!(centered[1].0.gaps(), (q(15.0), q(15.0)));
+
+        let compact = ss.best_placements("a", q(10.0), 2, Placement::least_fragmenting);
+        assert_eq!(starts(&compact), vec![20.0, 50.0]);
+        assert_eq!(compact[0].0.interval(), iv(20.0, 30.0));
+    }
+
+    #[test]
+    fn custom_scorer_and_edge_cases() {
+        let ss = space();
+        let near_40 = ss.best_placements("a", q(10.0), 1, |p: &Placement<Second>| {
+            -(p.start.value() - 40.0).abs()
+        });
+        assert_eq!(starts(&near_40), vec![35.0]);
+
+        assert!(ss
+            .best_placements("a", q(10.0), 0, Placement::earliest)
+            .is_empty());
+        assert!(ss
+            .best_placements("a", q(100.0), 3, Placement::earliest)
+            .is_empty());
+        assert!(ss
+            .best_placements("zzz", q(1.0), 3, Placement::earliest)
+            .is_empty());
+        assert!(ss
+            .best_placements("a", q(1.0), 3, |_: &Placement<Second>| f64::NAN)
+            .is_empty());
+    }
+}
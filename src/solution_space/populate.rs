@@ -1,7 +1,7 @@
 //! Solution space population utilities.
 
-use super::Interval;
-use crate::constraints::Constraint;
+use super::{Horizon, Interval, IntervalSet};
+use crate::constraints::{Constraint, ConstraintError, ConstraintExpr, EvaluationBudget};
 use crate::scheduling_block::{SchedulingBlock, Task};
 use crate::Id;
 use qtty::{Quantity, Unit};
@@ -39,8 +39,8 @@ impl<U: Unit> super::SolutionSpace<U> {
     /// Populates a solution space from multiple scheduling blocks.
     ///
     /// For each task in each block:
-    /// - If the task has constraints, computes valid intervals within the given range
-    /// - If the task has no constraints, uses the full range as a single interval
+    /// - If the task has constraints, computes valid intervals within the horizon
+    /// - If the task has no constraints, uses the whole horizon as a single interval
     ///
     /// The solution space maps task IDs to their intervals,
     /// allowing cross-block scheduling with stable task identification.
@@ -48,7 +48,7 @@ impl<U: Unit> super::SolutionSpace<U> {
     /// # Arguments
     ///
     /// * `blocks` - One or more scheduling blocks to process
-    /// * `range` - The scheduling window (interval) to compute valid placements within
+    /// * `horizon` - The scheduling horizon to compute valid placements within
     ///
     /// # Returns
     ///
@@ -57,48 +57,308 @@ impl<U: Unit> super::SolutionSpace<U> {
     /// # Example
     ///
     /// ```ignore
-    /// use v_rolai::solution_space::{SolutionSpace, Interval};
+    /// use v_rolai::solution_space::{Horizon, SolutionSpace};
     /// use v_rolai::scheduling_block::SchedulingBlock;
     /// use qtty::{Quantity, Second};
     ///
     /// let block = SchedulingBlock::new();
     /// // ... add tasks ...
     ///
-    /// let range = Interval::new(
+    /// let horizon = Horizon::from_bounds(
     ///     Quantity::<Second>::new(0.0),
     ///     Quantity::<Second>::new(86400.0)
-    /// );
-    /// let solution_space = SolutionSpace::populate(&[block], range);
+    /// )?;
+    /// let solution_space = SolutionSpace::populate(&[block], horizon);
     /// ```
     pub fn populate<T, D, E>(
         blocks: &[crate::scheduling_block::SchedulingBlock<T, U, D, E>],
-        range: Interval<U>,
+        horizon: Horizon<U>,
     ) -> Self
     where
         T: crate::scheduling_block::Task<U>,
         E: petgraph::EdgeType,
     {
+        let range = horizon.interval();
         let map = blocks
             .iter()
             .flat_map(|block| block.tasks())
-            .map(|(id, task)| {
-                // Use size_on_axis() to get duration in axis units
-                let task_size = task.size_on_axis();
-                let intervals = task.constraints().map_or_else(
-                    || vec![range],
-                    |ct| {
-                        ct.compute_intervals(range)
-                            .into_iter()
-                            .filter(|i| i.duration().value() >= task_size.value())
-                            .collect::<Vec<_>>()
-                    },
-                );
-                (id.to_owned(), intervals)
-            })
+            .map(|(id, task)| (id.to_owned(), task_windows(task, range).into_inner()))
             .collect::<HashMap<Id, Vec<Interval<U>>>>();
 
         Self::from_hashmap(map)
     }
+
+    /// Populates a solution space, capping each task's constraint evaluation.
+    ///
+    /// Behaves like [`populate`](Self::populate), except that every constraint
+    /// tree is evaluated under `budget`. A task whose evaluation exceeds the
+    /// budget gets no windows, and is reported alongside the error instead of
+    /// stalling the whole population.
+    ///
+    /// # Returns
+    ///
+    /// The solution space and the flagged tasks, sorted by task ID.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use virolai::constraints::EvaluationBudget;
+    /// # use virolai::solution_space::SolutionSpace;
+    /// # use qtty::Second;
+    /// # use virolai::solution_space::{Horizon, Interval};
+    /// # use virolai::synthetic::{generate, SyntheticConfig};
+    /// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+    /// # let instance = generate(&SyntheticConfig::new(20, horizon));
+    /// # let blocks = vec![instance.block];
+    /// let budget = EvaluationBudget::new().with_time_limit(Duration::from_millis(100));
+    /// let (space, flagged) = SolutionSpace::populate_with_budget(&blocks, horizon, &budget);
+    /// for (id, err) in &flagged {
+    ///     eprintln!("task {id} skipped: {err}");
+    /// }
+    /// ```
+    pub fn populate_with_budget<T, D, E>(
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        horizon: Horizon<U>,
+        budget: &EvaluationBudget,
+    ) -> (Self, Vec<(Id, ConstraintError)>)
+    where
+        T: Task<U>,
+        E: petgraph::EdgeType,
+    {
+        let range = horizon.interval();
+        let mut map: HashMap<Id, Vec<Interval<U>>> = HashMap::new();
+        let mut flagged = Vec::new();
+
+        for (id, task) in blocks.iter().flat_map(|block| block.tasks()) {
+            let windows = task_windows_budgeted(task, range, budget).unwrap_or_else(|err| {
+                flagged.push((id.to_owned(), err));
+                IntervalSet::new()
+            });
+            map.insert(id.to_owned(), windows.into_inner());
+        }
+
+        flagged.sort_by(|a, b| a.0.cmp(&b.0));
+        (Self::from_hashmap(map), flagged)
+    }
+
+    /// Populates a solution space, recording the provenance of every entry.
+    ///
+    /// Behaves like [`populate`](Self::populate), and additionally keeps,
+    /// for each task with constraints, the
+    /// [`Provenance`](crate::constraints::Provenance) of its windows:
+    /// which constraint leaves produced or clipped each window, and which
+    /// removed each excluded part of `horizon`. Windows shorter than the task
+    /// are dropped as in `populate`; provenance still describes them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use virolai::solution_space::SolutionSpace;
+    /// # use qtty::Second;
+    /// # use virolai::solution_space::{Horizon, Interval};
+    /// # use virolai::synthetic::{generate, SyntheticConfig};
+    /// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+    /// # let instance = generate(&SyntheticConfig::new(20, horizon));
+    /// # let blocks = vec![instance.block];
+    /// # let night_slot = Interval::from_f64(72_000.0, 75_600.0);
+    /// let space = SolutionSpace::populate_with_provenance(&blocks, horizon);
+    /// let provenance = space.provenance("obs-42").unwrap();
+    /// for leaf in provenance.removed_by(night_slot) {
+    ///     println!("removed by {}", provenance.label(leaf).unwrap());
+    /// }
+    /// ```
+    pub fn populate_with_provenance<T, D, E>(
+        blocks: &[SchedulingBlock<T, U, D, E>],
+        horizon: Horizon<U>,
+    ) -> Self
+    where
+        T: Task<U>,
+        E: petgraph::EdgeType,
+    {
+        let mut space = Self::populate(blocks, horizon);
+        let range = horizon.interval();
+        for (id, task) in blocks.iter().flat_map(|block| block.tasks()) {
+            if let Some(ct) = task.constraints() {
+                space
+                    .provenance
+                    .insert(id.to_owned(), ct.compute_with_provenance(range));
+            }
+        }
+        space
+    }
+
+    /// Moves the space from `old_horizon` to `new_horizon`, evaluating
+    /// constraints only over the part of `new_horizon` that is new.
+    ///
+    /// Rolling a year-long horizon forward by a day then costs a day of
+    /// constraint evaluation instead of a year. Every entry is clipped to
+    /// `new_horizon`; each task of `blocks` has its constraints evaluated over
+    /// the added range, widened into the old one by the task's size so that a
+    /// window the old boundary cut too short is recovered, and the result is
+    /// merged in. Tasks missing from the space, or every task if the ranges
+    /// do not overlap, are evaluated over the whole of `new_horizon`.
+    ///
+    /// For constraints whose windows do not depend on the evaluated range,
+    /// the result equals [`populate`](Self::populate) over `new_horizon`. Only
+    /// entries whose intervals changed are marked dirty.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use qtty::{Quantity, Second};
+    /// # use virolai::solution_space::{Horizon, Interval, SolutionSpace};
+    /// # use virolai::synthetic::{generate, SyntheticConfig};
+    /// # let year = Horizon::new(Interval::<Second>::from_f64(0.0, 365.0 * 86_400.0)).unwrap();
+    /// # let blocks = vec![generate(&SyntheticConfig::new(20, year)).block];
+    /// # let day = Quantity::new(86_400.0);
+    /// let mut space = SolutionSpace::populate(&blocks, year);
+    /// let next = Horizon::from_bounds(year.start() + day, year.end() + day)?;
+    /// space.extend_horizon(year, next, &blocks);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn extend_horizon<T, D, E>(
+        &mut self,
+        old_horizon: Horizon<U>,
+        new_horizon: Horizon<U>,
+        blocks: &[SchedulingBlock<T, U, D, E>],
+    ) where
+        T: Task<U>,
+        E: petgraph::EdgeType,
+    {
+        let (old_range, new_range) = (old_horizon.interval(), new_horizon.interval());
+        let bounds = IntervalSet::from_iter([new_range]);
+        let kept = old_range.intersection(&new_range);
+        let added = IntervalSet::from_iter([old_range]).complement(new_range);
+
+        for (id, task) in blocks.iter().flat_map(|block| block.tasks()) {
+            let intervals = match (kept, self.get_intervals(id)) {
+                (Some(kept), Some(existing)) => {
+                    let size = task.size_on_axis();
+                    let mut merged = existing.intersection(&bounds);
+                    // Strips on both sides of a short kept range overlap and
+                    // merge, so a window spanning all of it is rebuilt whole.
+                    let strips: IntervalSet<U> = added
+                        .iter()
+                        .map(|piece| {
+                            if piece.start() == kept.end() {
+                                let start = (kept.end() - size).value().max(kept.start().value());
+                                Interval::new(Quantity::new(start), piece.end())
+                            } else {
+                                let end = (kept.start() + size).value().min(kept.end().value());
+                                Interval::new(piece.start(), Quantity::new(end))
+                            }
+                        })
+                        .collect();
+                    for strip in strips.iter() {
+                        merged = merged.union(&task_windows(task, *strip));
+                    }
+                    if task.constraints().is_some() {
+                        merged.retain(|i| i.duration().value() >= size.value());
+                    }
+                    merged
+                }
+                _ => task_windows(task, new_range),
+            };
+            if self.get_intervals(id) != Some(&intervals) {
+                self.set_intervals(id, intervals.into_inner());
+            }
+        }
+
+        let ids: Vec<Id> = self.ids().map(str::to_owned).collect();
+        for id in ids {
+            let clipped = self.get_intervals(&id).map(|set| set.intersection(&bounds));
+            if let Some(clipped) = clipped.filter(|c| self.get_intervals(&id) != Some(c)) {
+                self.set_intervals(id, clipped.into_inner());
+            }
+        }
+    }
+
+    /// Recomputes the entry of a single task after its constraints changed.
+    ///
+    /// Evaluates `constraint_expr` within `horizon` and replaces the task's
+    /// intervals, leaving every other entry untouched. The entry is marked
+    /// dirty only if its intervals actually changed, so caches keyed on the
+    /// space (such as EST's [`MetricsCache`](crate::algorithms::est::metrics::MetricsCache))
+    /// keep their other results.
+    ///
+    /// Unlike [`populate`](Self::populate), windows are not filtered by task
+    /// size; metric computations skip windows the task does not fit in.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the task's intervals changed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use virolai::algorithms::est::metrics::MetricsCache;
+    /// # use virolai::constraints::{ConstraintExpr, IntervalConstraint};
+    /// # use virolai::solution_space::SolutionSpace;
+    /// # use qtty::Second;
+    /// # use virolai::solution_space::{Horizon, Interval};
+    /// # use virolai::synthetic::{generate, SyntheticConfig};
+    /// # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+    /// # let instance = generate(&SyntheticConfig::new(20, horizon));
+    /// # let blocks = vec![instance.block];
+    /// # let new_constraints = ConstraintExpr::leaf(IntervalConstraint::new(Interval::from_f64(0.0, 3_600.0)));
+    /// let mut space = SolutionSpace::populate(&blocks, horizon);
+    /// let mut cache = MetricsCache::new(horizon.interval());
+    ///
+    /// space.update_task("obs-42", &new_constraints, horizon);
+    /// cache.sync(&mut space); // only "obs-42" is recomputed
+    /// ```
+    pub fn update_task<C>(
+        &mut self,
+        id: impl Into<Id>,
+        constraint_expr: &ConstraintExpr<C>,
+        horizon: Horizon<U>,
+    ) -> bool
+    where
+        C: Constraint<U>,
+    {
+        let id = id.into();
+        let intervals = constraint_expr.compute_intervals(horizon.interval());
+        if self.get_intervals(&id) == Some(&intervals) {
+            return false;
+        }
+        self.set_intervals(id, intervals.into_inner());
+        true
+    }
+}
+
+/// Windows of `task` within `range`: its constraint windows the task fits
+/// in, or the whole range if it has no constraints.
+fn task_windows<T, U>(task: &T, range: Interval<U>) -> IntervalSet<U>
+where
+    T: Task<U>,
+    U: Unit,
+{
+    task_windows_budgeted(task, range, &EvaluationBudget::new())
+        .expect("an unlimited budget is never exceeded")
+}
+
+/// Like [`task_windows`], giving up once evaluating the task's constraints
+/// exceeds `budget`.
+fn task_windows_budgeted<T, U>(
+    task: &T,
+    range: Interval<U>,
+    budget: &EvaluationBudget,
+) -> Result<IntervalSet<U>, ConstraintError>
+where
+    T: Task<U>,
+    U: Unit,
+{
+    let windows = match task.constraints() {
+        None => return Ok(IntervalSet::from_iter([range])),
+        Some(ct) if budget.is_unlimited() => ct.compute_intervals(range),
+        Some(ct) => ct.compute_intervals_budgeted(range, budget)?,
+    };
+    let task_size = task.size_on_axis();
+    Ok(windows
+        .into_iter()
+        .filter(|i| i.duration().value() >= task_size.value())
+        .collect())
 }
 
 #[cfg(test)]
@@ -106,7 +366,7 @@ mod tests {
     use super::*;
     use crate::constraints::{ConstraintExpr, IntervalConstraint};
     use crate::scheduling_block::SchedulingBlock;
-    use crate::test_utils::TestTask;
+    use crate::test_utils::{hz, TestTask};
     use qtty::Second;
 
     #[test]
@@ -149,7 +409,7 @@ mod tests {
         let mut block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
         let task_id = block.add_task(task);
 
-        let range = Interval::from_f64(0.0, 100.0);
+        let range = hz(0.0, 100.0);
         let space = super::super::SolutionSpace::populate(&[block], range);
 
         let intervals = space.get_intervals(&task_id).unwrap();
@@ -164,11 +424,175 @@ mod tests {
         let mut block2: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
         let id2 = block2.add_task(TestTask::new("t2", 10.0));
 
-        let range = Interval::from_f64(0.0, 100.0);
+        let range = hz(0.0, 100.0);
         let space = super::super::SolutionSpace::populate(&[block1, block2], range);
 
         assert_eq!(space.count(), 2);
         assert!(space.get_intervals(&id1).is_some());
         assert!(space.get_intervals(&id2).is_some());
     }
+
+    #[test]
+    fn populate_with_budget_flags_expensive_tasks() {
+        use crate::constraints::{BudgetLimit, EvaluationBudget};
+
+        let cheap = ConstraintExpr::leaf(IntervalConstraint::new(Interval::from_f64(0.0, 50.0)));
+        let expensive = ConstraintExpr::union(
+            (0..10)
+                .map(|i| {
+                    let start = i as f64 * 10.0;
+                    ConstraintExpr::leaf(IntervalConstraint::new(Interval::from_f64(
+                        start,
+                        start + 5.0,
+                    )))
+                })
+                .collect(),
+        );
+
+        let mut block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
+        let cheap_id = block.add_task(TestTask::new("cheap", 10.0).with_constraints(cheap));
+        let expensive_id =
+            block.add_task(TestTask::new("expensive", 1.0).with_constraints(expensive));
+        let free_id = block.add_task(TestTask::new("free", 10.0));
+
+        let budget = EvaluationBudget::new().with_max_nodes(5);
+        let range = hz(0.0, 100.0);
+        let (space, flagged) =
+            super::super::SolutionSpace::populate_with_budget(&[block], range, &budget);
+
+        assert_eq!(
+            flagged,
+            vec![(
+                expensive_id.clone(),
+                ConstraintError::BudgetExceeded(BudgetLimit::Nodes(5))
+            )]
+        );
+        assert!(space.get_intervals(&expensive_id).unwrap().is_empty());
+        assert_eq!(space.get_intervals(&cheap_id).unwrap().len(), 1);
+        assert_eq!(space.get_intervals(&free_id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn update_task_recomputes_one_entry() {
+        let mut block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
+        let a = block.add_task(TestTask::new("a", 10.0));
+        let b = block.add_task(TestTask::new("b", 10.0));
+        let range = hz(0.0, 100.0);
+        let mut space = super::super::SolutionSpace::populate(&[block], range);
+        assert!(!space.has_dirty());
+
+        let narrowed =
+            ConstraintExpr::leaf(IntervalConstraint::new(Interval::from_f64(20.0, 40.0)));
+        assert!(space.update_task(a.as_str(), &narrowed, range));
+        assert_eq!(
+            space.get_intervals(&a).unwrap().as_slice(),
+            [Interval::from_f64(20.0, 40.0)]
+        );
+        assert_eq!(
+            space.get_intervals(&b).unwrap().as_slice(),
+            [range.interval()]
+        );
+        assert!(space.is_dirty(&a));
+        assert!(!space.is_dirty(&b));
+
+        // Same constraints again: nothing changes, nothing is marked.
+        assert_eq!(space.take_dirty(), vec![a.clone()]);
+        assert!(!space.update_task(a.as_str(), &narrowed, range));
+        assert!(!space.has_dirty());
+    }
+
+    #[test]
+    fn provenance_mode_explains_windows_until_modified() {
+        let mut block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
+        let blackout = IntervalConstraint::new(Interval::from_f64(20.0, 30.0));
+        let constrained = block.add_task(TestTask::new("c", 5.0).with_constraints(
+            ConstraintExpr::intersection(vec![
+                ConstraintExpr::leaf(IntervalConstraint::new(Interval::from_f64(0.0, 50.0))),
+                !ConstraintExpr::leaf(blackout),
+            ]),
+        ));
+        let free = block.add_task(TestTask::new("f", 5.0));
+        let range = hz(0.0, 100.0);
+
+        let blocks = [block];
+        let mut space = super::super::SolutionSpace::populate_with_provenance(&blocks, range);
+        let plain = super::super::SolutionSpace::populate(&blocks, range);
+        assert_eq!(
+            space.get_intervals(&constrained),
+            plain.get_intervals(&constrained)
+        );
+        assert!(space.provenance(&free).is_none());
+        assert!(plain.provenance(&constrained).is_none());
+
+        let provenance = space.provenance(&constrained).unwrap();
+        assert_eq!(
+            provenance.removed_by(Interval::from_f64(22.0, 23.0)),
+            vec![1]
+        );
+        assert_eq!(provenance.label(1), Some(blackout.stringify().as_str()));
+
+        space.set_intervals(constrained.clone(), vec![range.interval()]);
+        assert!(space.provenance(&constrained).is_none());
+    }
+
+    #[test]
+    fn extend_horizon_matches_full_population() {
+        let window = |start, end| {
+            ConstraintExpr::leaf(IntervalConstraint::new(Interval::from_f64(start, end)))
+        };
+        let mut block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
+        // Cut to [90, 100) by the old range, too short until extended.
+        let straddling =
+            block.add_task(TestTask::new("straddling", 15.0).with_constraints(window(90.0, 120.0)));
+        let unchanged =
+            block.add_task(TestTask::new("unchanged", 5.0).with_constraints(window(60.0, 80.0)));
+        let expired =
+            block.add_task(TestTask::new("expired", 5.0).with_constraints(window(10.0, 40.0)));
+        let free = block.add_task(TestTask::new("free", 5.0));
+        let blocks = [block];
+
+        let old_range = hz(0.0, 100.0);
+        let new_range = hz(50.0, 150.0);
+        let mut space = super::super::SolutionSpace::populate(&blocks, old_range);
+        assert!(space.get_intervals(&straddling).unwrap().is_empty());
+
+        space.extend_horizon(old_range, new_range, &blocks);
+        let full = super::super::SolutionSpace::populate(&blocks, new_range);
+        for id in [&straddling, &unchanged, &expired, &free] {
+            assert_eq!(space.get_intervals(id), full.get_intervals(id), "{id}");
+        }
+        assert_eq!(
+            space.get_intervals(&straddling).unwrap().as_slice(),
+            [Interval::from_f64(90.0, 120.0)]
+        );
+        let mut dirty = space.take_dirty();
+        dirty.sort();
+        let mut expected = vec![straddling, expired, free];
+        expected.sort();
+        assert_eq!(dirty, expected);
+    }
+
+    #[test]
+    fn extend_horizon_past_both_ends_rebuilds_spanning_windows() {
+        let mut block: SchedulingBlock<TestTask, Second> = SchedulingBlock::new();
+        let spanning = block.add_task(TestTask::new("spanning", 20.0).with_constraints(
+            ConstraintExpr::leaf(IntervalConstraint::new(Interval::from_f64(74.0, 105.0))),
+        ));
+        let blocks = [block];
+
+        let old_range = hz(82.0, 89.0);
+        let new_range = hz(37.0, 124.0);
+        let mut space = super::super::SolutionSpace::populate(&blocks, old_range);
+        space.extend_horizon(old_range, new_range, &blocks);
+
+        let full = super::super::SolutionSpace::populate(&blocks, new_range);
+        assert_eq!(
+            space.get_intervals(&spanning),
+            full.get_intervals(&spanning)
+        );
+        assert_eq!(
+            space.get_intervals(&spanning).unwrap().as_slice(),
+            [Interval::from_f64(74.0, 105.0)]
+        );
+    }
 }
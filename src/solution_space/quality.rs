@@ -0,0 +1,178 @@
+//! Quality scores of feasible windows.
+//!
+//! Not every feasible window is as good as the others: seeing may be
+//! better in one, airmass lower in another. A [`WindowQuality`] soft
+//! constraint rates each window of an entry, and the space keeps the
+//! ratings next to the windows so placement can prefer the best ones.
+//! Windows nobody rated have quality 1.0.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use qtty::Second;
+//! # use virolai::solution_space::{Horizon, Interval};
+//! # use virolai::synthetic::{generate, SyntheticConfig};
+//! # let horizon = Horizon::new(Interval::<Second>::from_f64(0.0, 86_400.0)).unwrap();
+//! # let instance = generate(&SyntheticConfig::new(20, horizon));
+//! # let mut space = instance.solution_space;
+//! # fn hour_angle(w: Interval<Second>) -> f64 { w.start().value() / 3_600.0 - 24.0 }
+//! // Prefer windows closer to the meridian transit.
+//! space.annotate_quality_all(&|w: Interval<Second>| 1.0 / (1.0 + hour_angle(w).abs()));
+//! let best = space.best_window("m31");
+//! let useful = space.quality_weighted_capacity("m31");
+//! ```
+
+use qtty::{Quantity, Unit};
+
+use super::{Interval, SolutionSpace};
+use crate::constraints::WindowQuality;
+use crate::Id;
+
+impl<U: Unit> SolutionSpace<U> {
+    /// Rates every window of `id` with `quality`, replacing earlier ratings.
+    ///
+    /// Returns false if `id` has no entry. Ratings describe the windows as
+    /// they are: any change to the entry drops them. Rating does not mark
+    /// the entry dirty.
+    pub fn annotate_quality<Q>(&mut self, id: &str, quality: &Q) -> bool
+    where
+        Q: WindowQuality<U> + ?Sized,
+    {
+        let Some(windows) = self.get_intervals(id) else {
+            return false;
+        };
+        let scores = windows.iter().map(|w| quality.quality(*w)).collect();
+        self.quality.insert(id.to_string(), scores);
+        true
+    }
+
+    /// Rates the windows of every entry with `quality`, see
+    /// [`annotate_quality`](Self::annotate_quality).
+    pub fn annotate_quality_all<Q>(&mut self, quality: &Q)
+    where
+        Q: WindowQuality<U> + ?Sized,
+    {
+        self.quality = self
+            .iter()
+            .map(|(id, windows)| {
+                let scores = windows.iter().map(|w| quality.quality(*w)).collect();
+                (id.to_string(), scores)
+            })
+            .collect();
+    }
+
+    /// Sets the quality of each window of `id`, in window order, e.g. from
+    /// ratings computed elsewhere.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` has no entry or `scores` does not have one score per
+    /// window.
+    pub fn set_window_quality(&mut self, id: impl Into<Id>, scores: Vec<f64>) {
+        let id = id.into();
+        let windows = self.get_intervals(&id).map_or(0, |windows| windows.len());
+        assert_eq!(
+            scores.len(),
+            windows,
+            "{id} has {windows} windows but {} quality scores",
+            scores.len()
+        );
+        self.quality.insert(id, scores);
+    }
+
+    /// The quality of each window of `id`, in window order, or `None` if
+    /// they are not rated.
+    pub fn window_quality(&self, id: &str) -> Option<&[f64]> {
+        self.quality.get(id).map(Vec::as_slice)
+    }
+
+    /// The windows of `id` in time order, each with its quality (1.0 if not
+    /// rated). Empty if `id` has no entry.
+    pub fn windows_with_quality(&self, id: &str) -> impl Iterator<Item = (Interval<U>, f64)> + '_ {
+        let scores = self.window_quality(id);
+        self.get_intervals(id)
+            .into_iter()
+            .flat_map(|windows| windows.iter().copied().enumerate())
+            .map(move |(i, window)| (window, scores.map_or(1.0, |s| s[i])))
+    }
+
+    /// The window of `id` with the highest quality, the earliest of equally
+    /// good ones; the first window if none is rated.
+    pub fn best_window(&self, id: &str) -> Option<Interval<U>> {
+        self.windows_with_quality(id)
+            .fold(
+                None::<(Interval<U>, f64)>,
+                |best, (window, quality)| match best {
+                    Some((_, top)) if top >= quality => best,
+                    _ => Some((window, quality)),
+                },
+            )
+            .map(|(window, _)| window)
+    }
+
+    /// Sum of the durations of the windows of `id`, each weighted by its
+    /// quality. Equal to [`capacity`](Self::capacity) if none is rated.
+    pub fn quality_weighted_capacity(&self, id: &str) -> Quantity<U> {
+        Quantity::new(
+            self.windows_with_quality(id)
+                .map(|(window, quality)| window.duration().value() * quality)
+                .sum(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::iv;
+    use qtty::Second;
+
+    #[test]
+    fn ratings_follow_the_windows() {
+        let mut space = SolutionSpace::<Second>::new();
+        space.set_intervals("m31", vec![iv(0.0, 10.0), iv(20.0, 40.0), iv(50.0, 60.0)]);
+        space.add_interval("m42", iv(0.0, 100.0));
+        space.take_dirty();
+
+        assert_eq!(space.best_window("m31"), Some(iv(0.0, 10.0)));
+        assert_eq!(space.quality_weighted_capacity("m31").value(), 40.0);
+
+        // Later windows rate higher, up to the last one.
+        let later_is_better = |w: Interval<Second>| w.start().value().min(20.0) / 10.0;
+        assert!(space.annotate_quality("m31", &later_is_better));
+        assert!(!space.annotate_quality("nowhere", &later_is_better));
+        assert!(!space.has_dirty());
+        assert_eq!(
+            space.window_quality("m31"),
+            Some([0.0, 2.0, 2.0].as_slice())
+        );
+        assert_eq!(space.window_quality("m42"), None);
+        // Ties go to the earliest window.
+        assert_eq!(space.best_window("m31"), Some(iv(20.0, 40.0)));
+        assert_eq!(space.quality_weighted_capacity("m31").value(), 60.0);
+
+        space.set_window_quality("m31", vec![0.5, 0.5, 3.0]);
+        assert_eq!(space.best_window("m31"), Some(iv(50.0, 60.0)));
+        let minutes = space.to::<qtty::Minute>();
+        assert_eq!(
+            minutes.window_quality("m31"),
+            Some([0.5, 0.5, 3.0].as_slice())
+        );
+
+        space.annotate_quality_all(&|_: Interval<Second>| 0.25);
+        assert_eq!(space.quality_weighted_capacity("m42").value(), 25.0);
+
+        // Changing the windows drops their ratings.
+        space.add_interval("m31", iv(70.0, 80.0));
+        assert_eq!(space.window_quality("m31"), None);
+        assert_eq!(space.best_window("m31"), Some(iv(0.0, 10.0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "has 1 windows but 2 quality scores")]
+    fn scores_must_match_the_windows() {
+        let mut space = SolutionSpace::<Second>::new();
+        space.add_interval("m31", iv(0.0, 10.0));
+        space.set_window_quality("m31", vec![1.0, 2.0]);
+    }
+}
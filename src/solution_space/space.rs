@@ -3,11 +3,12 @@
 //! The [`SolutionSpace`] acts as a lookup table that schedulers query to find
 //! feasible positions. Users populate it with intervals computed from constraints.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 
 use super::interval::Interval;
 use super::interval_set::IntervalSet;
+use crate::constraints::Provenance;
 use crate::Id;
 use qtty::{Quantity, Unit};
 
@@ -21,21 +22,25 @@ use qtty::{Quantity, Unit};
 /// - Uses task IDs (`String`) as stable keys, avoiding lifetime issues
 /// - Tasks without constraints get a single interval spanning [start, end]
 /// - Each task maintains its own sorted, non-overlapping interval list
+/// - Entries changed after construction are tracked as *dirty* until
+///   [`take_dirty`](Self::take_dirty) is called, so caches built on top of
+///   the space can recompute only what changed
+/// - In provenance mode, each entry also records which constraint leaves
+///   produced or clipped its intervals
+/// - Windows may carry [quality scores](Self::annotate_quality); like
+///   provenance, they are dropped when the entry changes
 #[derive(Debug)]
-pub struct SolutionSpace<U: Unit>(HashMap<Id, IntervalSet<U>>);
-
-/// Binary search to find interval containing a position in sorted list.
-fn find_interval_containing_sorted<U: Unit>(
-    intervals: &[Interval<U>],
-    position: Quantity<U>,
-) -> Option<&Interval<U>> {
-    let idx = intervals.partition_point(|i| i.end().value() < position.value());
-    intervals.get(idx).filter(|i| i.contains(position))
+pub struct SolutionSpace<U: Unit> {
+    entries: HashMap<Id, IntervalSet<U>>,
+    dirty: HashSet<Id>,
+    pub(super) provenance: HashMap<Id, Provenance<U>>,
+    /// Quality of each window of an entry, in window order.
+    pub(super) quality: HashMap<Id, Vec<f64>>,
 }
 
 impl<U: Unit> SolutionSpace<U> {
     pub fn new() -> Self {
-        Self(HashMap::new())
+        Self::with_capacity(0)
     }
 
     /// Creates a [`SolutionSpace`] from a pre-built map.
@@ -47,11 +52,21 @@ impl<U: Unit> SolutionSpace<U> {
             .into_iter()
             .map(|(id, intervals)| (id, IntervalSet::from(intervals)))
             .collect();
-        Self(canonical)
+        Self {
+            entries: canonical,
+            dirty: HashSet::new(),
+            provenance: HashMap::new(),
+            quality: HashMap::new(),
+        }
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
-        Self(HashMap::with_capacity(capacity))
+        Self {
+            entries: HashMap::with_capacity(capacity),
+            dirty: HashSet::new(),
+            provenance: HashMap::new(),
+            quality: HashMap::new(),
+        }
     }
 
     /// Adds an interval for a specific ID.
@@ -59,7 +74,11 @@ impl<U: Unit> SolutionSpace<U> {
     /// The stored set is kept canonical (sorted, overlaps merged) after
     /// insertion so that binary-search queries remain correct.
     pub fn add_interval(&mut self, id: impl Into<Id>, interval: Interval<U>) {
-        self.0.entry(id.into()).or_default().push(interval);
+        let id = id.into();
+        self.entries.entry(id.clone()).or_default().push(interval);
+        self.provenance.remove(&id);
+        self.quality.remove(&id);
+        self.dirty.insert(id);
     }
 
     /// Adds multiple intervals for a specific ID.
@@ -67,88 +86,183 @@ impl<U: Unit> SolutionSpace<U> {
     /// The stored set is kept canonical (sorted, overlaps merged) after
     /// insertion so that binary-search queries remain correct.
     pub fn add_intervals(&mut self, id: impl Into<Id>, intervals: Vec<Interval<U>>) {
-        self.0.entry(id.into()).or_default().extend(intervals);
+        let id = id.into();
+        self.entries
+            .entry(id.clone())
+            .or_default()
+            .extend(intervals);
+        self.provenance.remove(&id);
+        self.quality.remove(&id);
+        self.dirty.insert(id);
     }
 
     /// Sets the intervals for a specific ID, replacing any existing intervals.
     ///
     /// The supplied list is normalized (sorted, overlaps merged) before storage.
     pub fn set_intervals(&mut self, id: impl Into<Id>, intervals: Vec<Interval<U>>) {
-        self.0.insert(id.into(), IntervalSet::from(intervals));
+        let id = id.into();
+        self.entries
+            .insert(id.clone(), IntervalSet::from(intervals));
+        self.provenance.remove(&id);
+        self.quality.remove(&id);
+        self.dirty.insert(id);
     }
 
     /// Returns intervals for a specific ID.
     pub fn get_intervals(&self, id: &str) -> Option<&IntervalSet<U>> {
-        self.0.get(id)
+        self.entries.get(id)
+    }
+
+    /// Returns the intervals of a specific ID for in-place editing.
+    ///
+    /// The entry is marked dirty and its provenance and quality scores
+    /// dropped up front, whether or not it is actually changed;
+    /// [`IntervalSet`]'s own methods keep it canonical.
+    pub fn get_intervals_mut(&mut self, id: &str) -> Option<&mut IntervalSet<U>> {
+        let set = self.entries.get_mut(id)?;
+        self.provenance.remove(id);
+        self.quality.remove(id);
+        if !self.dirty.contains(id) {
+            self.dirty.insert(id.to_string());
+        }
+        Some(set)
+    }
+
+    /// Returns the intervals of a specific ID for in-place editing, inserting
+    /// an empty set if the ID has none.
+    ///
+    /// Like [`get_intervals_mut`](Self::get_intervals_mut), the entry is
+    /// marked dirty and its provenance and quality scores dropped.
+    pub fn entry(&mut self, id: impl Into<Id>) -> &mut IntervalSet<U> {
+        let id = id.into();
+        self.provenance.remove(&id);
+        self.quality.remove(&id);
+        self.dirty.insert(id.clone());
+        self.entries.entry(id).or_default()
+    }
+
+    /// Returns how the constraints of a specific ID produced its intervals.
+    ///
+    /// Only recorded by
+    /// [`populate_with_provenance`](Self::populate_with_provenance), for
+    /// tasks with constraints; dropped when the entry is modified.
+    pub fn provenance(&self, id: &str) -> Option<&Provenance<U>> {
+        self.provenance.get(id)
     }
 
     /// Returns IDs that have intervals defined.
     pub fn ids(&self) -> impl Iterator<Item = &str> + '_ {
-        self.0.keys().map(|k| k.as_str())
+        self.entries.keys().map(|k| k.as_str())
+    }
+
+    /// Returns every ID with its intervals, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &IntervalSet<U>)> + '_ {
+        self.entries.iter().map(|(id, set)| (id.as_str(), set))
     }
 
     /// Returns total number of entries in the solution space.
     pub fn count(&self) -> usize {
-        self.0.len()
+        self.entries.len()
     }
 
     /// Returns total number of intervals across all tasks.
     pub fn interval_count(&self) -> usize {
-        self.0.values().map(|v| v.len()).sum()
+        self.entries.values().map(|v| v.len()).sum()
     }
 
     /// Removes all intervals for a specific ID.
     pub fn remove(&mut self, id: &str) -> bool {
-        self.0.remove(id).is_some()
+        let removed = self.entries.remove(id).is_some();
+        self.provenance.remove(id);
+        self.quality.remove(id);
+        if removed {
+            self.dirty.insert(id.to_string());
+        }
+        removed
+    }
+
+    /// Keeps only the entries for which `keep` returns true, marking the
+    /// removed ones dirty.
+    pub fn retain<F: FnMut(&str, &IntervalSet<U>) -> bool>(&mut self, mut keep: F) {
+        let (dirty, provenance, quality) =
+            (&mut self.dirty, &mut self.provenance, &mut self.quality);
+        self.entries.retain(|id, set| {
+            let kept = keep(id, set);
+            if !kept {
+                provenance.remove(id);
+                quality.remove(id);
+                dirty.insert(id.clone());
+            }
+            kept
+        });
     }
 
+    /// Removes all entries, marking each of them dirty.
     pub fn clear(&mut self) {
-        self.0.clear();
+        self.dirty.extend(self.entries.drain().map(|(id, _)| id));
+        self.provenance.clear();
+        self.quality.clear();
     }
 
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.entries.is_empty()
     }
 
     /// Returns true if the specified ID has any interval containing `position` (O(log m) binary search).
     pub fn contains_position_for(&self, id: &str, position: Quantity<U>) -> bool {
-        self.0
+        self.entries
             .get(id)
-            .map(|set| find_interval_containing_sorted(set, position).is_some())
+            .map(|set| set.find_containing(position).is_some())
             .unwrap_or(false)
     }
 
     /// Returns true if any task has an interval containing `position`.
     pub fn contains_position(&self, position: Quantity<U>) -> bool {
-        self.0
+        self.entries
             .values()
             .any(|intervals| intervals.iter().any(|interval| interval.contains(position)))
     }
 
     /// Returns true if the specified ID can fit at `position` with given `size` (O(log m) binary search).
+    ///
+    /// A zero `size` (a milestone) may also sit on the closing point of a
+    /// window, see [`Interval::can_fit`].
     pub fn can_place(&self, id: &str, position: Quantity<U>, size: Quantity<U>) -> bool {
-        self.0
-            .get(id)
-            .map(|set| {
-                find_interval_containing_sorted(set, position)
-                    .map(|i| i.can_fit(position, size))
-                    .unwrap_or(false)
-            })
-            .unwrap_or(false)
+        self.entries.get(id).is_some_and(|set| {
+            let idx = set.partition_point(|i| i.end().value() < position.value());
+            set.get(idx).is_some_and(|i| i.can_fit(position, size))
+        })
     }
 
     /// Returns true if any entry can be placed at `position` with given `size`.
     pub fn can_place_at(&self, position: Quantity<U>, size: Quantity<U>) -> bool {
-        self.0.values().any(|intervals| {
+        self.entries.values().any(|intervals| {
             intervals
                 .iter()
                 .any(|interval| interval.can_fit(position, size))
         })
     }
 
+    /// Converts every window to another unit of the same dimension.
+    ///
+    /// Nothing is marked dirty in the converted space. Quality scores are
+    /// carried over, provenance is not.
+    pub fn to<T: Unit<Dim = U::Dim>>(&self) -> SolutionSpace<T> {
+        SolutionSpace {
+            entries: self
+                .entries
+                .iter()
+                .map(|(id, intervals)| (id.clone(), intervals.to()))
+                .collect(),
+            dirty: HashSet::new(),
+            provenance: HashMap::new(),
+            quality: self.quality.clone(),
+        }
+    }
+
     /// Returns sum of all interval durations for a specific ID.
     pub fn capacity(&self, id: &str) -> Quantity<U> {
-        self.0
+        self.entries
             .get(id)
             .map(|intervals| {
                 intervals
@@ -161,7 +275,7 @@ impl<U: Unit> SolutionSpace<U> {
 
     /// Returns sum of all interval durations across all entries.
     pub fn total_capacity(&self) -> Quantity<U> {
-        self.0
+        self.entries
             .values()
             .flat_map(|intervals| intervals.iter())
             .map(|interval| interval.duration())
@@ -170,7 +284,7 @@ impl<U: Unit> SolutionSpace<U> {
 
     /// Returns start of the first interval with capacity ≥ `size` for a specific ID.
     pub fn find_earliest_fit_for(&self, id: &str, size: Quantity<U>) -> Option<Quantity<U>> {
-        self.0.get(id).and_then(|intervals| {
+        self.entries.get(id).and_then(|intervals| {
             intervals
                 .iter()
                 .find(|interval| interval.duration().value() >= size.value())
@@ -180,7 +294,7 @@ impl<U: Unit> SolutionSpace<U> {
 
     /// Returns start of the first interval with capacity ≥ `size` across all entries.
     pub fn find_earliest_fit(&self, size: Quantity<U>) -> Option<Quantity<U>> {
-        self.0
+        self.entries
             .values()
             .flat_map(|intervals| intervals.iter())
             .filter(|interval| interval.duration().value() >= size.value())
@@ -194,16 +308,39 @@ impl<U: Unit> SolutionSpace<U> {
         id: &str,
         position: Quantity<U>,
     ) -> Option<&Interval<U>> {
-        self.0
+        self.entries
             .get(id)
-            .and_then(|set| find_interval_containing_sorted(set, position))
+            .and_then(|set| set.find_containing(position))
     }
 
     /// Returns the first interval containing `position` across all entries (O(log m) per entry).
     pub fn find_interval_containing(&self, position: Quantity<U>) -> Option<&Interval<U>> {
-        self.0
+        self.entries
             .values()
-            .find_map(|set| find_interval_containing_sorted(set, position))
+            .find_map(|set| set.find_containing(position))
+    }
+
+    /// Returns true if the entry for `id` changed since the last
+    /// [`take_dirty`](Self::take_dirty).
+    pub fn is_dirty(&self, id: &str) -> bool {
+        self.dirty.contains(id)
+    }
+
+    /// Returns true if any entry changed since the last
+    /// [`take_dirty`](Self::take_dirty).
+    pub fn has_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Returns the IDs of the entries changed since the last call, sorted,
+    /// and clears the dirty set.
+    ///
+    /// Entries created by [`from_hashmap`](Self::from_hashmap) or
+    /// [`populate`](Self::populate) start clean.
+    pub fn take_dirty(&mut self) -> Vec<Id> {
+        let mut ids: Vec<Id> = self.dirty.drain().collect();
+        ids.sort();
+        ids
     }
 }
 
@@ -220,10 +357,10 @@ impl<U: Unit> Display for SolutionSpace<U> {
         writeln!(f, "  Total intervals: {}", self.interval_count())?;
         writeln!(f, "  Total capacity: {:.3}", self.total_capacity().value())?;
 
-        if !self.0.is_empty() {
+        if !self.entries.is_empty() {
             writeln!(f, "  Per-entry breakdown:")?;
 
-            for (id, set) in &self.0 {
+            for (id, set) in &self.entries {
                 let capacity: Quantity<U> = set
                     .iter()
                     .map(|i| i.duration())
@@ -250,6 +387,7 @@ mod tests {
     use super::*;
     use crate::constraints::{ConstraintExpr, IntervalConstraint};
     use crate::scheduling_block::{SchedulingBlock, Task};
+    use crate::solution_space::Horizon;
     use qtty::{Quantity, Second};
 
     #[derive(Debug)]
@@ -287,7 +425,9 @@ mod tests {
     #[test]
     fn test_populate_empty_blocks() {
         let blocks: Vec<SchedulingBlock<TestTask, Second>> = vec![];
-        let range = Interval::new(Quantity::<Second>::new(0.0), Quantity::<Second>::new(100.0));
+        let range =
+            Horizon::from_bounds(Quantity::<Second>::new(0.0), Quantity::<Second>::new(100.0))
+                .unwrap();
 
         let solution_space = SolutionSpace::populate(&blocks, range);
         assert_eq!(solution_space.count(), 0);
@@ -304,7 +444,9 @@ mod tests {
         };
         let task_id = block.add_task(task);
 
-        let range = Interval::new(Quantity::<Second>::new(0.0), Quantity::<Second>::new(100.0));
+        let range =
+            Horizon::from_bounds(Quantity::<Second>::new(0.0), Quantity::<Second>::new(100.0))
+                .unwrap();
 
         let blocks = vec![block];
         let solution_space = SolutionSpace::populate(&blocks, range);
@@ -330,7 +472,9 @@ mod tests {
         };
         let task_id = block.add_task(task);
 
-        let range = Interval::new(Quantity::<Second>::new(0.0), Quantity::<Second>::new(100.0));
+        let range =
+            Horizon::from_bounds(Quantity::<Second>::new(0.0), Quantity::<Second>::new(100.0))
+                .unwrap();
 
         let blocks = vec![block];
         let solution_space = SolutionSpace::populate(&blocks, range);
@@ -363,7 +507,9 @@ mod tests {
         };
         let task2_id = block.add_task(task2);
 
-        let range = Interval::new(Quantity::<Second>::new(0.0), Quantity::<Second>::new(200.0));
+        let range =
+            Horizon::from_bounds(Quantity::<Second>::new(0.0), Quantity::<Second>::new(200.0))
+                .unwrap();
 
         let blocks = vec![block];
         let solution_space = SolutionSpace::populate(&blocks, range);
@@ -393,7 +539,9 @@ mod tests {
         };
         let task2_id = block.add_task(task2);
 
-        let range = Interval::new(Quantity::<Second>::new(0.0), Quantity::<Second>::new(100.0));
+        let range =
+            Horizon::from_bounds(Quantity::<Second>::new(0.0), Quantity::<Second>::new(100.0))
+                .unwrap();
 
         let blocks = vec![block];
         let solution_space = SolutionSpace::populate(&blocks, range);
@@ -432,7 +580,9 @@ mod tests {
         };
         block.add_task(task);
 
-        let range = Interval::new(Quantity::<Second>::new(0.0), Quantity::<Second>::new(100.0));
+        let range =
+            Horizon::from_bounds(Quantity::<Second>::new(0.0), Quantity::<Second>::new(100.0))
+                .unwrap();
 
         let blocks = vec![block];
         let solution_space = SolutionSpace::populate(&blocks, range);
@@ -461,10 +611,11 @@ mod tests {
         };
         block.add_task(task2);
 
-        let range = Interval::new(
+        let range = Horizon::from_bounds(
             Quantity::<Second>::new(0.0),
             Quantity::<Second>::new(15000.0),
-        );
+        )
+        .unwrap();
 
         let blocks = vec![block];
         let solution_space = SolutionSpace::populate(&blocks, range);
@@ -621,6 +772,34 @@ mod tests {
         assert_eq!(ids, vec!["alpha", "beta"]);
     }
 
+    #[test]
+    fn test_iter_entry_and_retain() {
+        let mut space: SolutionSpace<Second> = SolutionSpace::new();
+        space.add_interval("alpha", Interval::from_f64(0.0, 50.0));
+        space.add_interval("beta", Interval::from_f64(60.0, 100.0));
+        space.take_dirty();
+
+        let mut pairs: Vec<_> = space.iter().map(|(id, set)| (id, set.len())).collect();
+        pairs.sort();
+        assert_eq!(pairs, [("alpha", 1), ("beta", 1)]);
+
+        space
+            .get_intervals_mut("alpha")
+            .unwrap()
+            .push(Interval::from_f64(40.0, 70.0));
+        space.entry("gamma").push(Interval::from_f64(5.0, 10.0));
+        assert!(space.get_intervals_mut("missing").is_none());
+        assert_eq!(
+            space.get_intervals("alpha").unwrap(),
+            &vec![Interval::from_f64(0.0, 70.0)]
+        );
+        assert_eq!(space.take_dirty(), ["alpha", "gamma"]);
+
+        space.retain(|id, set| id != "beta" && !set.is_empty());
+        assert_eq!(space.count(), 2);
+        assert_eq!(space.take_dirty(), ["beta"]);
+    }
+
     #[test]
     fn test_capacity_multiple_intervals() {
         let mut space: SolutionSpace<Second> = SolutionSpace::new();
@@ -743,6 +922,26 @@ mod tests {
         assert!(!space.contains_position_for("task1", Quantity::new(60.0)));
     }
 
+    #[test]
+    fn test_mutations_mark_entries_dirty() {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), vec![Interval::from_f64(0.0, 10.0)]);
+        map.insert("b".to_string(), vec![Interval::from_f64(0.0, 10.0)]);
+        let mut space: SolutionSpace<Second> = SolutionSpace::from_hashmap(map);
+        assert!(!space.has_dirty());
+
+        space.add_interval("c", Interval::from_f64(0.0, 5.0));
+        space.remove("a");
+        space.remove("missing");
+        assert!(space.is_dirty("a") && space.is_dirty("c"));
+        assert!(!space.is_dirty("b"));
+        assert_eq!(space.take_dirty(), vec!["a".to_string(), "c".to_string()]);
+        assert!(space.take_dirty().is_empty());
+
+        space.clear();
+        assert_eq!(space.take_dirty(), vec!["b".to_string(), "c".to_string()]);
+    }
+
     /// from_hashmap must normalize every entry it receives.
     #[test]
     fn test_from_hashmap_normalizes() {
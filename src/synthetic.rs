@@ -0,0 +1,674 @@
+//! Randomized problem instances and schedules.
+//!
+//! Generates random but *valid* inputs for benchmarking the crate and for
+//! downstream consumers that want to exercise their executors against
+//! realistic plans:
+//!
+//! - [`generate`] builds a [`SyntheticInstance`]: a scheduling block with
+//!   random sizes, priorities, visibility windows and an acyclic dependency
+//!   graph, plus the matching solution space.
+//! - [`random_schedule`] places the instance's tasks at random positions that
+//!   respect windows, dependencies and non-overlap.
+//! - [`generate_constraint_tree`] builds a random interval-constraint tree
+//!   together with the interval set it must evaluate to, for property tests
+//!   of the constraint operations and for benchmarking tree evaluation.
+//!
+//! Generation is fully determined by [`SyntheticConfig::with_seed`], so the
+//! same configuration always yields the same instance.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use virolai::solution_space::{Horizon, Interval};
+//! use virolai::synthetic::{generate, random_schedule, SyntheticConfig};
+//! # use qtty::Second;
+//!
+//! let horizon = Horizon::<Second>::new(Interval::from_f64(0.0, 10_000.0))?;
+//! let config = SyntheticConfig::new(50, horizon)
+//!     .with_window_density(0.3)
+//!     .with_dependency_density(0.05)
+//!     .with_seed(7);
+//! let instance = generate(&config);
+//! let plan = random_schedule(&instance, 7);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::constraints::{ConstraintExpr, IntervalConstraint};
+use crate::schedule::Schedule;
+use crate::scheduling_block::{SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, IntervalSet, SolutionSpace};
+use qtty::{Quantity, Unit};
+
+/// Parameters for [`generate`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyntheticConfig<U: Unit> {
+    task_count: usize,
+    horizon: Horizon<U>,
+    min_size: f64,
+    max_size: f64,
+    max_priority: i32,
+    windows_per_task: usize,
+    window_density: f64,
+    dependency_density: f64,
+    seed: u64,
+}
+
+impl<U: Unit> SyntheticConfig<U> {
+    /// Configuration for `task_count` tasks over `horizon`.
+    ///
+    /// Defaults: sizes between 1% and 5% of the horizon, priorities in
+    /// `0..=10`, up to 3 windows per task covering 25% of the horizon, no
+    /// dependencies, seed 0.
+    pub fn new(task_count: usize, horizon: Horizon<U>) -> Self {
+        let length = horizon.duration().value();
+        Self {
+            task_count,
+            horizon,
+            min_size: length * 0.01,
+            max_size: length * 0.05,
+            max_priority: 10,
+            windows_per_task: 3,
+            window_density: 0.25,
+            dependency_density: 0.0,
+            seed: 0,
+        }
+    }
+
+    /// Sets the task size range (axis units). Bounds are reordered if needed.
+    pub fn with_size_range(mut self, min: Quantity<U>, max: Quantity<U>) -> Self {
+        let (a, b) = (min.value().max(0.0), max.value().max(0.0));
+        self.min_size = a.min(b);
+        self.max_size = a.max(b);
+        self
+    }
+
+    /// Sets the highest priority; priorities are drawn from `0..=max`.
+    pub fn with_max_priority(mut self, max: i32) -> Self {
+        self.max_priority = max.max(0);
+        self
+    }
+
+    /// Sets the maximum number of visibility windows per task (at least 1).
+    pub fn with_windows_per_task(mut self, windows: usize) -> Self {
+        self.windows_per_task = windows.max(1);
+        self
+    }
+
+    /// Sets the fraction of the horizon each task's windows cover, in `[0, 1]`.
+    ///
+    /// Windows are never shorter than the task, so every generated task is
+    /// feasible in isolation.
+    pub fn with_window_density(mut self, density: f64) -> Self {
+        self.window_density = density.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the probability of a dependency between any ordered task pair,
+    /// in `[0, 1]`.
+    pub fn with_dependency_density(mut self, density: f64) -> Self {
+        self.dependency_density = density.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the random seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Number of tasks generated.
+    pub fn task_count(&self) -> usize {
+        self.task_count
+    }
+
+    /// Horizon the instance is generated over.
+    pub fn horizon(&self) -> Horizon<U> {
+        self.horizon
+    }
+
+    /// Random seed.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+/// A generated task: its visibility windows are its constraints.
+#[derive(Debug, Clone)]
+pub struct SyntheticTask<U: Unit + Send + Sync> {
+    name: String,
+    size: Quantity<U>,
+    priority: i32,
+    windows: ConstraintExpr<IntervalConstraint<U>>,
+}
+
+impl<U: Unit + Send + Sync> Task<U> for SyntheticTask<U> {
+    type SizeUnit = U;
+    type ConstraintLeaf = IntervalConstraint<U>;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn size(&self) -> Quantity<U> {
+        self.size
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn constraints(&self) -> Option<&ConstraintExpr<IntervalConstraint<U>>> {
+        Some(&self.windows)
+    }
+}
+
+/// A generated problem: tasks with dependencies and their solution space.
+#[derive(Debug)]
+pub struct SyntheticInstance<U: Unit + Send + Sync> {
+    /// Tasks (IDs `task-0`, `task-1`, …) and their dependencies.
+    pub block: SchedulingBlock<SyntheticTask<U>, U>,
+    /// Visibility windows of every task, clipped to the horizon.
+    pub solution_space: SolutionSpace<U>,
+    /// Horizon the instance was generated over.
+    pub horizon: Horizon<U>,
+}
+
+/// Generates a random instance from `config`.
+///
+/// Dependencies only point from lower to higher task indices, so the graph
+/// is always acyclic.
+pub fn generate<U: Unit + Send + Sync>(config: &SyntheticConfig<U>) -> SyntheticInstance<U> {
+    let mut rng = SplitMix64::new(config.seed);
+    let h0 = config.horizon.start().value();
+    let h1 = config.horizon.end().value();
+    let length = (h1 - h0).max(0.0);
+
+    let mut block = SchedulingBlock::new();
+    let mut solution_space = SolutionSpace::with_capacity(config.task_count);
+    let mut nodes = Vec::with_capacity(config.task_count);
+
+    for i in 0..config.task_count {
+        let size = rng.range(config.min_size, config.max_size).min(length);
+        let count = 1 + rng.below(config.windows_per_task as u64) as usize;
+        let window_len = (length * config.window_density / count as f64)
+            .max(size)
+            .min(length);
+
+        let windows: IntervalSet<U> = (0..count)
+            .map(|_| {
+                let start = h0 + rng.range(0.0, length - window_len);
+                Interval::new(Quantity::new(start), Quantity::new(start + window_len))
+            })
+            .collect();
+
+        let task = SyntheticTask {
+            name: format!("task-{}", i),
+            size: Quantity::new(size),
+            priority: rng.below(config.max_priority as u64 + 1) as i32,
+            windows: ConstraintExpr::union(
+                windows
+                    .iter()
+                    .map(|w| ConstraintExpr::leaf(IntervalConstraint::new(*w)))
+                    .collect(),
+            ),
+        };
+        let id = block
+            .add_task_with_id(task, Some(format!("task-{}", i)))
+            .expect("generated IDs are unique");
+        solution_space.set_intervals(id.clone(), windows.into_inner());
+        nodes.push(block.node_of(&id).expect("task was just added"));
+    }
+
+    for (j, &to) in nodes.iter().enumerate() {
+        for &from in &nodes[..j] {
+            if rng.chance(config.dependency_density) {
+                block
+                    .add_dependency(from, to, ())
+                    .expect("forward edges cannot form a cycle");
+            }
+        }
+    }
+
+    SyntheticInstance {
+        block,
+        solution_space,
+        horizon: config.horizon,
+    }
+}
+
+/// Places the instance's tasks at random valid positions.
+///
+/// Tasks are visited in topological order. Each one starts after all its
+/// scheduled predecessors end, inside its windows, without overlapping
+/// earlier placements; a task with no such position (or with an
+/// unscheduled predecessor) is left out. The result is therefore always a
+/// valid, though usually partial, plan.
+pub fn random_schedule<U: Unit + Send + Sync>(
+    instance: &SyntheticInstance<U>,
+    seed: u64,
+) -> Schedule<U> {
+    let mut rng = SplitMix64::new(seed);
+    let mut schedule = Schedule::new();
+    let block = &instance.block;
+    let horizon_end = instance.horizon.end().value();
+
+    let order = block.topo_order().expect("synthetic blocks are acyclic");
+    'tasks: for node in order {
+        let id = block.id_of(node).expect("node belongs to the block");
+        let size = block.get_task(node).expect("node exists").size().value();
+
+        let mut ready = instance.horizon.start().value();
+        for pred in block.predecessors(node) {
+            let pred_id = block.id_of(pred).expect("node belongs to the block");
+            match schedule.get_interval(pred_id) {
+                Some(iv) => ready = ready.max(iv.end().value()),
+                None => continue 'tasks,
+            }
+        }
+
+        let windows: Vec<(f64, f64)> = instance
+            .solution_space
+            .get_intervals(id)
+            .into_iter()
+            .flatten()
+            .map(|w| (w.start().value(), w.end().value()))
+            .collect();
+        let free: Vec<Interval<U>> =
+            crate::algorithms::greedy::free_windows(&windows, ready, horizon_end, &schedule)
+                .into_iter()
+                .filter(|w| w.duration().value() >= size)
+                .collect();
+        if free.is_empty() {
+            continue;
+        }
+
+        let window = free[rng.below(free.len() as u64) as usize];
+        let slack = window.duration().value() - size;
+        let start = window.start().value() + rng.range(0.0, slack);
+        schedule
+            .add(
+                id,
+                Interval::new(Quantity::new(start), Quantity::new(start + size)),
+            )
+            .expect("free window has room for the task");
+    }
+
+    schedule
+}
+
+/// Parameters for [`generate_constraint_tree`].
+///
+/// Trees are bounded by depth and branching: a tree has at most
+/// `max_branching^(max_depth - 1)` leaves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConstraintTreeConfig<U: Unit> {
+    range: Interval<U>,
+    grid: usize,
+    max_depth: usize,
+    max_branching: usize,
+    leaf_probability: f64,
+    not_probability: f64,
+    seed: u64,
+}
+
+impl<U: Unit> ConstraintTreeConfig<U> {
+    /// Configuration for trees evaluated over `range`.
+    ///
+    /// Defaults: a 64-cell grid, depth up to 4, up to 3 children per
+    /// combinator, 30% early leaves, 20% negations, seed 0.
+    pub fn new(range: Interval<U>) -> Self {
+        Self {
+            range,
+            grid: 64,
+            max_depth: 4,
+            max_branching: 3,
+            leaf_probability: 0.3,
+            not_probability: 0.2,
+            seed: 0,
+        }
+    }
+
+    /// Sets the number of grid cells leaf endpoints snap to (at least 1).
+    pub fn with_grid(mut self, cells: usize) -> Self {
+        self.grid = cells.max(1);
+        self
+    }
+
+    /// Sets the maximum tree depth; a leaf has depth 1.
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth.max(1);
+        self
+    }
+
+    /// Sets the maximum number of children of a union or intersection.
+    pub fn with_max_branching(mut self, branching: usize) -> Self {
+        self.max_branching = branching.max(1);
+        self
+    }
+
+    /// Sets the probability that an inner node is a leaf before the depth
+    /// limit is reached, clamped to `[0, 1]`.
+    pub fn with_leaf_probability(mut self, probability: f64) -> Self {
+        self.leaf_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the probability that a combinator is a negation, clamped to
+    /// `[0, 1]`.
+    pub fn with_not_probability(mut self, probability: f64) -> Self {
+        self.not_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the random seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// A generated constraint tree and its ground truth.
+#[derive(Debug, Clone)]
+pub struct GeneratedConstraintTree<U: Unit + Send + Sync> {
+    /// The random tree.
+    pub expr: ConstraintExpr<IntervalConstraint<U>>,
+    /// Range the tree is meant to be evaluated over.
+    pub range: Interval<U>,
+    /// What `expr.compute_intervals(range)` must return.
+    pub expected: IntervalSet<U>,
+}
+
+/// Generates a random constraint tree from `config`.
+///
+/// Leaf endpoints lie on a grid over the range, occasionally extending
+/// past it to exercise clipping. The ground truth is computed cell by cell
+/// with boolean logic, independently of the interval operations it is
+/// meant to check.
+pub fn generate_constraint_tree<U: Unit + Send + Sync>(
+    config: &ConstraintTreeConfig<U>,
+) -> GeneratedConstraintTree<U> {
+    let mut rng = SplitMix64::new(config.seed);
+    let (expr, cells) = random_tree(config, &mut rng, config.max_depth);
+
+    let mut expected = Vec::new();
+    let mut run_start = None;
+    for (cell, &inside) in cells.iter().chain([&false]).enumerate() {
+        match (inside, run_start) {
+            (true, None) => run_start = Some(cell),
+            (false, Some(first)) => {
+                expected.push(Interval::new(
+                    grid_point(config, first as i64),
+                    grid_point(config, cell as i64),
+                ));
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+
+    GeneratedConstraintTree {
+        expr,
+        range: config.range,
+        expected: IntervalSet::from(expected),
+    }
+}
+
+/// Position of grid line `k` (which may lie outside the range).
+fn grid_point<U: Unit>(config: &ConstraintTreeConfig<U>, k: i64) -> Quantity<U> {
+    let start = config.range.start().value();
+    let cell = config.range.duration().value() / config.grid as f64;
+    if k == config.grid as i64 {
+        // Land exactly on the range end despite rounding.
+        return config.range.end();
+    }
+    Quantity::new(start + k as f64 * cell)
+}
+
+/// Builds a subtree and the grid cells it covers.
+fn random_tree<U: Unit + Send + Sync>(
+    config: &ConstraintTreeConfig<U>,
+    rng: &mut SplitMix64,
+    depth: usize,
+) -> (ConstraintExpr<IntervalConstraint<U>>, Vec<bool>) {
+    let n = config.grid as i64;
+    if depth <= 1 || rng.chance(config.leaf_probability) {
+        // Endpoints in [-n/4, n + n/4] so some leaves stick out of the range.
+        let margin = (n / 4).max(1);
+        let a = rng.below((n + 2 * margin) as u64) as i64 - margin;
+        let len = 1 + rng.below((n + margin - a) as u64) as i64;
+        let b = a + len;
+        let cells = (0..n).map(|c| a <= c && c < b).collect();
+        let leaf =
+            IntervalConstraint::new(Interval::new(grid_point(config, a), grid_point(config, b)));
+        return (ConstraintExpr::leaf(leaf), cells);
+    }
+
+    if rng.chance(config.not_probability) {
+        let (child, cells) = random_tree(config, rng, depth - 1);
+        return (
+            ConstraintExpr::negate(child),
+            cells.into_iter().map(|c| !c).collect(),
+        );
+    }
+
+    let count = 1 + rng.below(config.max_branching as u64) as usize;
+    let is_union = rng.chance(0.5);
+    let mut children = Vec::with_capacity(count);
+    let mut cells = vec![!is_union; config.grid];
+    for _ in 0..count {
+        let (child, child_cells) = random_tree(config, rng, depth - 1);
+        for (acc, c) in cells.iter_mut().zip(child_cells) {
+            *acc = if is_union { *acc || c } else { *acc && c };
+        }
+        children.push(child);
+    }
+    let expr = if is_union {
+        ConstraintExpr::union(children)
+    } else {
+        ConstraintExpr::intersection(children)
+    };
+    (expr, cells)
+}
+
+/// Small deterministic PRNG (SplitMix64), so generation needs no extra
+/// dependencies and is reproducible across platforms.
+///
+/// Also drives [`crate::robustness`]; not suitable for cryptography.
+#[derive(Debug, Clone)]
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    /// Creates a generator from `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Next raw 64-bit output.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `[lo, hi)`; returns `lo` when the range is empty.
+    pub fn range(&mut self, lo: f64, hi: f64) -> f64 {
+        if hi <= lo {
+            lo
+        } else {
+            lo + self.unit() * (hi - lo)
+        }
+    }
+
+    /// Uniform in `0..n`; returns 0 when `n == 0`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        if n == 0 {
+            0
+        } else {
+            self.next_u64() % n
+        }
+    }
+
+    /// Returns true with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        p > 0.0 && self.unit() < p
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{ESTScheduler, SchedulingAlgorithm};
+    use crate::constraints::Constraint;
+    use crate::test_utils::{hz, iv, q};
+    use qtty::Second;
+
+    fn config() -> SyntheticConfig<Second> {
+        SyntheticConfig::new(40, hz(0.0, 1000.0))
+            .with_dependency_density(0.1)
+            .with_seed(42)
+    }
+
+    #[test]
+    fn generates_requested_tasks_within_horizon() {
+        let inst = generate(&config());
+        assert_eq!(inst.block.task_count(), 40);
+        assert_eq!(inst.solution_space.count(), 40);
+        for (id, task) in inst.block.tasks() {
+            let windows = inst.solution_space.get_intervals(id).unwrap();
+            assert!(!windows.is_empty());
+            assert!(windows
+                .iter()
+                .any(|w| w.duration().value() >= task.size().value()));
+            assert!(windows
+                .iter()
+                .all(|w| w.start().value() >= 0.0 && w.end().value() <= 1000.0));
+            assert!((0..=10).contains(&task.priority()));
+        }
+        assert!(inst.block.dependency_count() > 0);
+        assert!(inst.block.topo_order().is_ok());
+    }
+
+    #[test]
+    fn same_seed_same_instance() {
+        let a = generate(&config());
+        let b = generate(&config());
+        let c = generate(&config().with_seed(43));
+        let windows = |i: &SyntheticInstance<Second>| {
+            (0..40)
+                .map(|k| {
+                    i.solution_space
+                        .get_intervals(&format!("task-{}", k))
+                        .unwrap()
+                        .clone()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(windows(&a), windows(&b));
+        assert_eq!(a.block.dependency_count(), b.block.dependency_count());
+        assert_ne!(windows(&a), windows(&c));
+    }
+
+    #[test]
+    fn constraints_match_solution_space() {
+        let inst = generate(&config());
+        for (id, task) in inst.block.tasks() {
+            let computed = task
+                .constraints()
+                .unwrap()
+                .compute_intervals(inst.horizon.interval());
+            assert_eq!(&computed, inst.solution_space.get_intervals(id).unwrap());
+        }
+    }
+
+    #[test]
+    fn random_schedule_is_valid() {
+        let inst = generate(&config());
+        let schedule = random_schedule(&inst, 1);
+        assert!(!schedule.is_empty());
+
+        let block = &inst.block;
+        for (id, placed, _) in schedule.iter_all() {
+            assert!(inst
+                .solution_space
+                .can_place(&id, placed.start(), placed.duration()));
+            let node = block.node_of(&id).unwrap();
+            for pred in block.predecessors(node) {
+                let pred_iv = schedule.get_interval(block.id_of(pred).unwrap()).unwrap();
+                assert!(pred_iv.end().value() <= placed.start().value());
+            }
+        }
+    }
+
+    #[test]
+    fn no_dependencies_by_default() {
+        let inst = generate(
+            &SyntheticConfig::new(10, hz(0.0, 100.0))
+                .with_size_range(q(5.0), q(2.0))
+                .with_windows_per_task(1)
+                .with_window_density(1.0),
+        );
+        assert_eq!(inst.block.dependency_count(), 0);
+        for (id, task) in inst.block.tasks() {
+            assert!((2.0..5.0).contains(&task.size().value()));
+            assert_eq!(
+                inst.solution_space.get_intervals(id).unwrap().as_slice(),
+                [iv(0.0, 100.0)]
+            );
+        }
+    }
+
+    #[test]
+    fn constraint_trees_match_ground_truth() {
+        for seed in 0..300 {
+            let config = ConstraintTreeConfig::new(iv(-50.0, 250.0))
+                .with_grid(24)
+                .with_max_depth(5)
+                .with_seed(seed);
+            let tree = generate_constraint_tree(&config);
+            assert!(tree.expr.depth() <= 5);
+            assert!(tree.expr.leaf_count() <= 3usize.pow(4));
+            assert_eq!(
+                tree.expr.compute_intervals(tree.range),
+                tree.expected,
+                "seed {seed}: {}",
+                tree.expr.stringify()
+            );
+        }
+    }
+
+    #[test]
+    fn constraint_tree_config_bounds_shape() {
+        let leaf =
+            generate_constraint_tree(&ConstraintTreeConfig::new(iv(0.0, 10.0)).with_max_depth(1));
+        assert!(leaf.expr.is_leaf());
+
+        let config = ConstraintTreeConfig::new(iv(0.0, 10.0))
+            .with_leaf_probability(0.0)
+            .with_not_probability(1.0)
+            .with_max_depth(3)
+            .with_seed(9);
+        let tree = generate_constraint_tree(&config);
+        // Not(Not(leaf)) evaluates like the leaf itself.
+        assert!(tree.expr.is_not());
+        assert_eq!(tree.expr.depth(), 3);
+        assert_eq!(tree.expr.compute_intervals(tree.range), tree.expected);
+    }
+
+    #[test]
+    fn instances_feed_schedulers() {
+        let inst = generate(&SyntheticConfig::new(20, hz(0.0, 1000.0)).with_seed(3));
+        let schedule = ESTScheduler::new(q(1.0)).schedule(
+            std::slice::from_ref(&inst.block),
+            &inst.solution_space,
+            inst.horizon,
+        );
+        assert!(!schedule.is_empty());
+    }
+}
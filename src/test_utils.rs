@@ -2,9 +2,10 @@
 //!
 //! Provides reusable mock types and helper functions used across multiple test modules.
 
-use crate::constraints::{ConstraintExpr, IntervalConstraint};
-use crate::scheduling_block::Task;
-use crate::solution_space::Interval;
+use crate::constraints::{CoalitionConstraint, ConstraintExpr, IntervalConstraint};
+use crate::schedule::Schedule;
+use crate::scheduling_block::{PlacementPreference, SchedulingBlock, Task};
+use crate::solution_space::{Horizon, Interval, SolutionSpace};
 use qtty::{Quantity, Second};
 
 /// Convenience helper: creates an `Interval<Second>` from two `f64` values.
@@ -12,14 +13,88 @@ pub fn iv(start: f64, end: f64) -> Interval<Second> {
     Interval::from_f64(start, end)
 }
 
+/// Convenience helper: creates a `Horizon<Second>` from two `f64` values.
+///
+/// Panics if they do not make a valid horizon.
+pub fn hz(start: f64, end: f64) -> Horizon<Second> {
+    Horizon::new(iv(start, end)).expect("valid test horizon")
+}
+
 /// Convenience helper: creates a `Quantity<Second>` from an `f64` value.
 pub fn q(value: f64) -> Quantity<Second> {
     Quantity::new(value)
 }
 
+/// Builds a block of `tasks`, each keyed by its name.
+pub fn block_of(tasks: impl IntoIterator<Item = TestTask>) -> SchedulingBlock<TestTask, Second> {
+    let mut block = SchedulingBlock::new();
+    for task in tasks {
+        let id = task.name.clone();
+        block.add_task_with_id(task, Some(id)).unwrap();
+    }
+    block
+}
+
+/// Builds a block of [`TestTask`]s and their solution space from
+/// `(id, size, priority, window)` entries. Each task is named and keyed by
+/// its ID and allowed in its one window.
+pub fn block_and_space(
+    tasks: &[(&str, f64, i32, Interval<Second>)],
+) -> (SchedulingBlock<TestTask, Second>, SolutionSpace<Second>) {
+    let block = block_of(
+        tasks
+            .iter()
+            .map(|&(id, size, priority, _)| TestTask::new(id, size).with_priority(priority)),
+    );
+    let mut space = SolutionSpace::new();
+    for &(id, _, _, window) in tasks {
+        space.add_interval(id, window);
+    }
+    (block, space)
+}
+
+/// A schedule of a block of [`TestTask`]s with its solution space, see
+/// [`planned_block`].
+pub type PlannedBlock = (
+    Schedule<Second>,
+    SolutionSpace<Second>,
+    Vec<SchedulingBlock<TestTask, Second>>,
+);
+
+/// Builds a block of 10 s [`TestTask`]s from `(id, window, start)` entries,
+/// with `edges` as its dependencies, and a schedule placing every task that
+/// has a start.
+pub fn planned_block(
+    tasks: &[(&str, Interval<Second>, Option<f64>)],
+    edges: &[(&str, &str)],
+) -> PlannedBlock {
+    let entries: Vec<_> = tasks
+        .iter()
+        .map(|&(id, window, _)| (id, 10.0, 0, window))
+        .collect();
+    let (mut block, space) = block_and_space(&entries);
+    block
+        .add_dependencies(
+            edges
+                .iter()
+                .map(|&(from, to)| (from.to_string(), to.to_string(), ())),
+        )
+        .unwrap();
+    let mut schedule = Schedule::new();
+    for &(id, _, start) in tasks {
+        if let Some(start) = start {
+            schedule.add(id, iv(start, start + 10.0)).unwrap();
+        }
+    }
+    (schedule, space, vec![block])
+}
+
 /// A configurable mock task for testing scheduling logic.
 ///
-/// Supports setting name, size, priority, gap_after, and optional constraints.
+/// Supports setting name, size, priority, gap_after, optional constraints,
+/// a placement preference, a constant success probability, an optional
+/// coalition requirement, the background flag and a duration overriding the
+/// size.
 #[derive(Debug, Clone)]
 pub struct TestTask {
     pub name: String,
@@ -27,6 +102,11 @@ pub struct TestTask {
     pub priority: i32,
     pub delay: Quantity<Second>,
     pub constraints: Option<ConstraintExpr<IntervalConstraint<Second>>>,
+    pub placement: PlacementPreference<Second>,
+    pub success_probability: f64,
+    pub coalition: Option<CoalitionConstraint>,
+    pub background: bool,
+    pub duration: Option<Quantity<Second>>,
 }
 
 impl TestTask {
@@ -39,6 +119,11 @@ impl TestTask {
             priority: 0,
             delay: Quantity::new(0.0),
             constraints: None,
+            placement: PlacementPreference::Earliest,
+            success_probability: 1.0,
+            coalition: None,
+            background: false,
+            duration: None,
         }
     }
 
@@ -62,6 +147,37 @@ impl TestTask {
         self.constraints = Some(constraints);
         self
     }
+
+    /// Sets a constant success probability and returns self (builder pattern).
+    pub fn with_success_probability(mut self, probability: f64) -> Self {
+        self.success_probability = probability;
+        self
+    }
+
+    /// Sets a coalition requirement and returns self (builder pattern).
+    pub fn with_coalition(mut self, coalition: CoalitionConstraint) -> Self {
+        self.coalition = Some(coalition);
+        self
+    }
+
+    /// Sets the placement preference and returns self (builder pattern).
+    pub fn with_placement(mut self, placement: PlacementPreference<Second>) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Marks the task as a background task and returns self (builder pattern).
+    pub fn in_background(mut self) -> Self {
+        self.background = true;
+        self
+    }
+
+    /// Sets a constant duration, used instead of the size once placed, and
+    /// returns self (builder pattern).
+    pub fn with_duration(mut self, duration: f64) -> Self {
+        self.duration = Some(Quantity::new(duration));
+        self
+    }
 }
 
 impl Task<Second> for TestTask {
@@ -88,6 +204,26 @@ impl Task<Second> for TestTask {
         self.constraints.as_ref()
     }
 
+    fn success_probability(&self, _start: Quantity<Second>) -> f64 {
+        self.success_probability
+    }
+
+    fn placement_preference(&self) -> PlacementPreference<Second> {
+        self.placement
+    }
+
+    fn coalition(&self) -> Option<&CoalitionConstraint> {
+        self.coalition.as_ref()
+    }
+
+    fn is_background(&self) -> bool {
+        self.background
+    }
+
+    fn duration_at(&self, _start: Quantity<Second>) -> Quantity<Second> {
+        self.duration.unwrap_or(self.size)
+    }
+
     fn compute_gap_after(&self, _previous_task: &Self) -> Quantity<Second> {
         self.delay
     }
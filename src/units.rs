@@ -4,6 +4,13 @@
 //! of the same physical dimension, allowing tasks to specify durations in one unit
 //! (e.g., seconds) while constraints and scheduling operate on another unit
 //! (e.g., days/MJD).
+//!
+//! Inputs arriving in mixed units are brought onto a common axis with the
+//! `to` conversions of [`Interval`](crate::solution_space::Interval),
+//! [`IntervalSet`](crate::solution_space::IntervalSet),
+//! [`SolutionSpace`](crate::solution_space::SolutionSpace) and
+//! [`Schedule`](crate::schedule::Schedule), rather than by scaling raw
+//! values by hand.
 
 use qtty::{Quantity, Unit};
 
@@ -107,6 +114,34 @@ mod tests {
         assert!((same.value() - 42.0).abs() < 1e-12);
     }
 
+    #[test]
+    fn test_mixed_unit_inputs_share_an_axis() {
+        use crate::schedule::Schedule;
+        use crate::solution_space::{Interval, SolutionSpace};
+
+        let mut windows = SolutionSpace::<Minute>::new();
+        windows.add_interval("obs", Interval::from_f64(0.0, 30.0));
+        windows.add_interval("obs", Interval::from_f64(60.0, 90.0));
+        let mut plan = Schedule::<Hour>::new();
+        plan.add("obs", Interval::from_f64(1.0, 1.25)).unwrap();
+
+        let mut windows: SolutionSpace<Second> = windows.to();
+        let plan: Schedule<Second> = plan.to();
+        assert_eq!(
+            windows.get_intervals("obs").unwrap().as_slice(),
+            [
+                Interval::from_f64(0.0, 1800.0),
+                Interval::from_f64(3600.0, 5400.0)
+            ]
+        );
+        assert_eq!(
+            plan.get_interval("obs"),
+            Some(Interval::from_f64(3600.0, 4500.0))
+        );
+        assert!(plan.infeasible_entries(&windows).is_empty());
+        assert!(windows.take_dirty().is_empty());
+    }
+
     #[test]
     fn test_zero_conversion() {
         let zero = Quantity::<Second>::new(0.0);
@@ -0,0 +1,73 @@
+//! End-to-end runs of the `observing_pipeline` example over the fixtures in
+//! `examples/data`.
+//!
+//! Each test pins the KPIs of one fixture, so a change anywhere along the
+//! pipeline that alters the plan shows up here. If the change is intended,
+//! run the example on the fixture and update the numbers.
+
+#[path = "../examples/observing_pipeline.rs"]
+#[allow(dead_code)]
+mod pipeline;
+
+use pipeline::{run_pipeline, PipelineReport};
+use virolai::solution_space::{Horizon, Interval};
+
+/// 2026-03-01T20:00:00Z, dusk of the first night.
+const DUSK: f64 = 1_772_395_200.0;
+
+fn run(fixture: &str, horizon: Interval<qtty::Second>) -> PipelineReport {
+    let path = format!("{}/examples/data/{fixture}", env!("CARGO_MANIFEST_DIR"));
+    let json = std::fs::read_to_string(path).expect("fixture exists");
+    let horizon = Horizon::new(horizon).expect("fixture horizons are non-empty");
+    run_pipeline(&json, horizon, DUSK)
+}
+
+fn count(text: &str, needle: &str) -> usize {
+    text.matches(needle).count()
+}
+
+#[test]
+fn one_night() {
+    let report = run("night_48.json", Interval::from_f64(0.0, 43_200.0));
+
+    assert_eq!(report.violations, Vec::<String>::new());
+    let k = &report.kpis;
+    assert_eq!(k.scheduled + k.unscheduled, 48);
+    // The night is oversubscribed: half the targets do not fit.
+    assert_eq!((k.scheduled, k.unscheduled), (24, 24));
+    assert!((k.utilization - 0.9375).abs() < 1e-9);
+    assert_eq!(k.priority_scheduled, 160.0);
+    // Calibrations run first, in dependency order.
+    let first: Vec<String> = report.schedule.iter().take(2).map(|(id, _)| id).collect();
+    assert_eq!(first, ["bias", "flat-dusk"]);
+    assert_eq!(report.lints.len(), 10);
+
+    assert_eq!(count(&report.ics, "BEGIN:VFREEBUSY"), 48);
+    assert!(report
+        .ics
+        .contains("UID:bias\r\nDTSTAMP:20260301T200000Z\r\n"));
+    assert!(report.ics.contains(
+        "UID:flat-dawn\r\nDTSTAMP:20260301T200000Z\r\nDTSTART:20260301T200000Z\r\n\
+             DTEND:20260302T080000Z\r\nCOMMENT:flat-dawn\r\n\
+             FREEBUSY;FBTYPE=BUSY:20260301T200000Z/20260302T072000Z\r\n\
+             FREEBUSY;FBTYPE=FREE:20260302T072000Z/20260302T080000Z\r\n"
+    ));
+    assert_eq!(count(&report.svg, r#"<g class="windows">"#), 48);
+}
+
+#[test]
+fn week_long_campaign() {
+    let report = run("campaign_120.json", Interval::from_f64(0.0, 7.0 * 86_400.0));
+
+    assert_eq!(report.violations, Vec::<String>::new());
+    let k = &report.kpis;
+    assert_eq!(k.scheduled + k.unscheduled, 120);
+    assert_eq!((k.scheduled, k.unscheduled), (66, 54));
+    assert!((k.utilization - 0.473_214_285_7).abs() < 1e-9);
+    assert_eq!(k.priority_scheduled, 236.0);
+    assert_eq!(report.lints.len(), 40);
+
+    assert_eq!(count(&report.ics, "BEGIN:VFREEBUSY"), 120);
+    assert!(report.ics.contains("DTEND:20260308T200000Z\r\n"));
+    assert_eq!(count(&report.svg, r#"<g class="windows">"#), 120);
+}